@@ -18,7 +18,7 @@ use std::sync::Arc;
 
 use common_catalog::consts::{
     DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME, MIN_USER_TABLE_ID,
-    SYSTEM_CATALOG_NAME, SYSTEM_CATALOG_TABLE_NAME,
+    SYSTEM_CATALOG_NAME,
 };
 use common_recordbatch::{RecordBatch, SendableRecordBatchStream};
 use common_telemetry::{error, info};
@@ -36,14 +36,14 @@ use table::TableRef;
 use crate::error::{
     CatalogNotFoundSnafu, IllegalManagerStateSnafu, OpenTableSnafu, ReadSystemCatalogSnafu, Result,
     SchemaExistsSnafu, SchemaNotFoundSnafu, SystemCatalogSnafu, SystemCatalogTypeMismatchSnafu,
-    TableExistsSnafu, TableNotFoundSnafu, UnimplementedSnafu,
+    TableExistsSnafu, TableNotFoundSnafu,
 };
 use crate::local::memory::{MemoryCatalogManager, MemoryCatalogProvider, MemorySchemaProvider};
 use crate::system::{
     decode_system_catalog, Entry, SystemCatalogTable, TableEntry, ENTRY_TYPE_INDEX, KEY_INDEX,
     VALUE_INDEX,
 };
-use crate::tables::SystemCatalog;
+use crate::tables::{ProcessListProviderRef, SystemCatalog};
 use crate::{
     format_full_table_name, handle_system_table_request, CatalogList, CatalogManager,
     CatalogProvider, CatalogProviderRef, DeregisterTableRequest, RegisterSchemaRequest,
@@ -64,12 +64,22 @@ pub struct LocalCatalogManager {
 impl LocalCatalogManager {
     /// Create a new [CatalogManager] with given user catalogs and table engine
     pub async fn try_new(engine: TableEngineRef) -> Result<Self> {
+        Self::new_with_process_list(engine, None).await
+    }
+
+    /// Create a new [CatalogManager] whose `information_schema.processlist` table lists the
+    /// queries provided by `process_list`.
+    pub async fn new_with_process_list(
+        engine: TableEngineRef,
+        process_list: Option<ProcessListProviderRef>,
+    ) -> Result<Self> {
         let table = SystemCatalogTable::new(engine.clone()).await?;
         let memory_catalog_list = crate::local::memory::new_memory_catalog_list()?;
         let system_catalog = Arc::new(SystemCatalog::new(
             table,
             memory_catalog_list.clone(),
             engine.clone(),
+            process_list,
         ));
         Ok(Self {
             system: system_catalog,
@@ -105,10 +115,12 @@ impl LocalCatalogManager {
 
     fn init_system_catalog(&self) -> Result<()> {
         let system_schema = Arc::new(MemorySchemaProvider::new());
-        system_schema.register_table(
-            SYSTEM_CATALOG_TABLE_NAME.to_string(),
-            self.system.information_schema.system.clone(),
-        )?;
+        let information_schema = &self.system.information_schema;
+        for table_name in information_schema.table_names()? {
+            if let Some(table) = information_schema.table(&table_name)? {
+                system_schema.register_table(table_name, table)?;
+            }
+        }
         let system_catalog = Arc::new(MemoryCatalogProvider::new());
         system_catalog.register_schema(INFORMATION_SCHEMA_NAME.to_string(), system_schema)?;
         self.catalogs
@@ -370,11 +382,41 @@ impl CatalogManager for LocalCatalogManager {
         }
     }
 
-    async fn deregister_table(&self, _request: DeregisterTableRequest) -> Result<bool> {
-        UnimplementedSnafu {
-            operation: "deregister table",
+    async fn deregister_table(&self, request: DeregisterTableRequest) -> Result<bool> {
+        let started = self.init_lock.lock().await;
+
+        ensure!(
+            *started,
+            IllegalManagerStateSnafu {
+                msg: "Catalog manager not started",
+            }
+        );
+
+        let catalog_name = &request.catalog;
+        let schema_name = &request.schema;
+
+        let catalog = self
+            .catalogs
+            .catalog(catalog_name)?
+            .context(CatalogNotFoundSnafu { catalog_name })?;
+        let schema = catalog
+            .schema(schema_name)?
+            .with_context(|| SchemaNotFoundSnafu {
+                schema_info: format!("{}.{}", catalog_name, schema_name),
+            })?;
+
+        {
+            let _lock = self.register_lock.lock().await;
+            if schema.table(&request.table_name)?.is_none() {
+                return Ok(false);
+            }
+
+            self.system
+                .deregister_table(catalog_name, schema_name, &request.table_name)
+                .await?;
+            schema.deregister_table(&request.table_name)?;
+            Ok(true)
         }
-        .fail()
     }
 
     async fn register_schema(&self, request: RegisterSchemaRequest) -> Result<bool> {
@@ -33,7 +33,7 @@ use serde::{Deserialize, Serialize};
 use snafu::{ensure, OptionExt, ResultExt};
 use table::engine::{EngineContext, TableEngineRef};
 use table::metadata::{TableId, TableInfoRef};
-use table::requests::{CreateTableRequest, InsertRequest, OpenTableRequest};
+use table::requests::{CreateTableRequest, DeleteRequest, InsertRequest, OpenTableRequest};
 use table::{Table, TableRef};
 
 use crate::error::{
@@ -75,6 +75,11 @@ impl Table for SystemCatalogTable {
         self.table.insert(request).await
     }
 
+    /// Delete rows from table.
+    async fn delete(&self, request: DeleteRequest) -> table::error::Result<usize> {
+        self.table.delete(request).await
+    }
+
     fn table_info(&self) -> TableInfoRef {
         self.table_info.clone()
     }
@@ -197,6 +202,28 @@ pub fn build_table_insert_request(full_table_name: String, table_id: TableId) ->
     )
 }
 
+/// Build a request to delete the record of given table from system catalog table.
+pub fn build_table_deletion_request(full_table_name: String) -> DeleteRequest {
+    let mut key_column_values = HashMap::with_capacity(3);
+    key_column_values.insert(
+        "entry_type".to_string(),
+        Arc::new(UInt8Vector::from_slice(&[EntryType::Table as u8])) as _,
+    );
+
+    key_column_values.insert(
+        "key".to_string(),
+        Arc::new(BinaryVector::from_slice(&[full_table_name.as_bytes()])) as _,
+    );
+
+    // Timestamp in key part is always 0, see `build_insert_request`.
+    key_column_values.insert(
+        "timestamp".to_string(),
+        Arc::new(TimestampVector::from_slice(&[Timestamp::from_millis(0)])) as _,
+    );
+
+    DeleteRequest { key_column_values }
+}
+
 pub fn build_schema_insert_request(catalog_name: String, schema_name: String) -> InsertRequest {
     let full_schema_name = format!("{}.{}", catalog_name, schema_name);
     build_insert_request(
@@ -250,6 +277,7 @@ pub fn build_insert_request(entry_type: EntryType, key: &[u8], value: &[u8]) ->
         schema_name: DEFAULT_SCHEMA_NAME.to_string(),
         table_name: SYSTEM_CATALOG_TABLE_NAME.to_string(),
         columns_values,
+        region_number: 0,
     }
 }
 
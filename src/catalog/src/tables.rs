@@ -14,6 +14,11 @@
 
 // The `tables` table in system catalog keeps a record of all tables created by user.
 
+mod columns;
+mod processlist;
+mod region_statistics;
+mod schemata;
+
 use std::any::Any;
 use std::pin::Pin;
 use std::sync::Arc;
@@ -25,7 +30,7 @@ use common_error::ext::BoxedError;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::PhysicalPlanRef;
 use common_recordbatch::error::Result as RecordBatchResult;
-use common_recordbatch::{RecordBatch, RecordBatchStream};
+use common_recordbatch::{RecordBatch, RecordBatchStream, RecordBatches};
 use datatypes::prelude::{ConcreteDataType, VectorBuilder};
 use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
 use datatypes::value::Value;
@@ -38,8 +43,17 @@ use table::metadata::{TableId, TableInfoRef};
 use table::table::scan::SimpleTableScan;
 use table::{Table, TableRef};
 
-use crate::error::{Error, InsertCatalogRecordSnafu};
-use crate::system::{build_schema_insert_request, build_table_insert_request, SystemCatalogTable};
+pub use self::columns::Columns;
+pub use self::processlist::{
+    ProcessInfo, ProcessListProvider, ProcessListProviderRef, Processlist,
+};
+pub use self::region_statistics::RegionStatistics;
+pub use self::schemata::Schemata;
+use crate::error::{DeleteCatalogRecordSnafu, Error, InsertCatalogRecordSnafu};
+use crate::system::{
+    build_schema_insert_request, build_table_deletion_request, build_table_insert_request,
+    SystemCatalogTable,
+};
 use crate::{
     format_full_table_name, CatalogListRef, CatalogProvider, SchemaProvider, SchemaProviderRef,
 };
@@ -77,12 +91,14 @@ impl Table for Tables {
 
     async fn scan(
         &self,
-        _projection: &Option<Vec<usize>>,
+        projection: &Option<Vec<usize>>,
         _filters: &[Expr],
         _limit: Option<usize>,
     ) -> table::error::Result<PhysicalPlanRef> {
         let catalogs = self.catalogs.clone();
-        let schema_ref = self.schema.clone();
+        let projected_schema = project_schema(&self.schema, projection);
+        let schema_ref = projected_schema.clone();
+        let projection = projection.clone();
         let engine_name = self.engine_name.clone();
 
         let stream = stream!({
@@ -127,6 +143,7 @@ impl Table for Tables {
                         tables_in_schema,
                         &engine_name,
                     );
+                    let vec = project_columns(vec, &projection);
                     let record_batch_res = RecordBatch::new(schema_ref.clone(), vec);
                     yield record_batch_res;
                 }
@@ -134,13 +151,76 @@ impl Table for Tables {
         });
 
         let stream = Box::pin(TablesRecordBatchStream {
-            schema: self.schema.clone(),
+            schema: projected_schema,
             stream: Box::pin(stream),
         });
         Ok(Arc::new(SimpleTableScan::new(stream)))
     }
 }
 
+/// Returns the schema of the columns in `projection`.
+fn project_schema(schema: &SchemaRef, projection: &Option<Vec<usize>>) -> SchemaRef {
+    match projection {
+        Some(projection) => {
+            let column_schemas = projection
+                .iter()
+                .map(|i| schema.column_schemas()[*i].clone())
+                .collect();
+            Arc::new(Schema::new(column_schemas))
+        }
+        None => schema.clone(),
+    }
+}
+
+/// Returns the columns in `projection`.
+fn project_columns(columns: Vec<VectorRef>, projection: &Option<Vec<usize>>) -> Vec<VectorRef> {
+    match projection {
+        Some(projection) => projection.iter().map(|i| columns[*i].clone()).collect(),
+        None => columns,
+    }
+}
+
+/// Builds the plan to scan a table in information schema whose content is `columns`, only the
+/// columns in `projection` are returned.
+fn scan_columns(
+    schema: &SchemaRef,
+    columns: Vec<VectorRef>,
+    projection: &Option<Vec<usize>>,
+) -> table::error::Result<PhysicalPlanRef> {
+    let schema = project_schema(schema, projection);
+    let columns = project_columns(columns, projection);
+    let records = RecordBatches::try_from_columns(schema, columns)
+        .map_err(BoxedError::new)
+        .context(TablesRecordBatchSnafu)?;
+    Ok(Arc::new(SimpleTableScan::new(records.as_stream())))
+}
+
+/// Calls `f` with the catalog name, schema name, table name and the table itself of every
+/// table in `catalogs`.
+fn visit_tables<F>(catalogs: &CatalogListRef, mut f: F) -> Result<(), Error>
+where
+    F: FnMut(&str, &str, &str, TableRef),
+{
+    for catalog_name in catalogs.catalog_names()? {
+        let catalog = match catalogs.catalog(&catalog_name)? {
+            Some(catalog) => catalog,
+            None => continue,
+        };
+        for schema_name in catalog.schema_names()? {
+            let schema = match catalog.schema(&schema_name)? {
+                Some(schema) => schema,
+                None => continue,
+            };
+            for table_name in schema.table_names()? {
+                if let Some(table) = schema.table(&table_name)? {
+                    f(&catalog_name, &schema_name, &table_name, table);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
 /// Convert tables info to `RecordBatch`.
 fn tables_to_record_batch(
     catalog_name: &str,
@@ -191,8 +271,18 @@ impl RecordBatchStream for TablesRecordBatchStream {
     }
 }
 
+const TABLES_TABLE_NAME: &str = "tables";
+const COLUMNS_TABLE_NAME: &str = "columns";
+const SCHEMATA_TABLE_NAME: &str = "schemata";
+const PROCESSLIST_TABLE_NAME: &str = "processlist";
+const REGION_STATISTICS_TABLE_NAME: &str = "region_statistics";
+
 pub struct InformationSchema {
     pub tables: Arc<Tables>,
+    pub columns: Arc<Columns>,
+    pub schemata: Arc<Schemata>,
+    pub processlist: Arc<Processlist>,
+    pub region_statistics: Arc<RegionStatistics>,
     pub system: Arc<SystemCatalogTable>,
 }
 
@@ -203,19 +293,32 @@ impl SchemaProvider for InformationSchema {
 
     fn table_names(&self) -> Result<Vec<String>, Error> {
         Ok(vec![
-            "tables".to_string(),
+            TABLES_TABLE_NAME.to_string(),
+            COLUMNS_TABLE_NAME.to_string(),
+            SCHEMATA_TABLE_NAME.to_string(),
+            PROCESSLIST_TABLE_NAME.to_string(),
+            REGION_STATISTICS_TABLE_NAME.to_string(),
             SYSTEM_CATALOG_TABLE_NAME.to_string(),
         ])
     }
 
     fn table(&self, name: &str) -> Result<Option<TableRef>, Error> {
-        if name.eq_ignore_ascii_case("tables") {
-            Ok(Some(self.tables.clone()))
+        let table: TableRef = if name.eq_ignore_ascii_case(TABLES_TABLE_NAME) {
+            self.tables.clone()
+        } else if name.eq_ignore_ascii_case(COLUMNS_TABLE_NAME) {
+            self.columns.clone()
+        } else if name.eq_ignore_ascii_case(SCHEMATA_TABLE_NAME) {
+            self.schemata.clone()
+        } else if name.eq_ignore_ascii_case(PROCESSLIST_TABLE_NAME) {
+            self.processlist.clone()
+        } else if name.eq_ignore_ascii_case(REGION_STATISTICS_TABLE_NAME) {
+            self.region_statistics.clone()
         } else if name.eq_ignore_ascii_case(SYSTEM_CATALOG_TABLE_NAME) {
-            Ok(Some(self.system.clone()))
+            self.system.clone()
         } else {
-            Ok(None)
-        }
+            return Ok(None);
+        };
+        Ok(Some(table))
     }
 
     fn register_table(
@@ -231,8 +334,7 @@ impl SchemaProvider for InformationSchema {
     }
 
     fn table_exist(&self, name: &str) -> Result<bool, Error> {
-        Ok(name.eq_ignore_ascii_case("tables")
-            || name.eq_ignore_ascii_case(SYSTEM_CATALOG_TABLE_NAME))
+        Ok(self.table(name)?.is_some())
     }
 }
 
@@ -241,13 +343,20 @@ pub struct SystemCatalog {
 }
 
 impl SystemCatalog {
+    /// Creates the system catalog, the `processlist` table lists the queries provided by
+    /// `process_list`.
     pub fn new(
         system: SystemCatalogTable,
         catalogs: CatalogListRef,
         engine: TableEngineRef,
+        process_list: Option<ProcessListProviderRef>,
     ) -> Self {
         let schema = InformationSchema {
-            tables: Arc::new(Tables::new(catalogs, engine.name().to_string())),
+            tables: Arc::new(Tables::new(catalogs.clone(), engine.name().to_string())),
+            columns: Arc::new(Columns::new(catalogs.clone())),
+            schemata: Arc::new(Schemata::new(catalogs.clone())),
+            processlist: Arc::new(Processlist::new(process_list)),
+            region_statistics: Arc::new(RegionStatistics::new(catalogs)),
             system: Arc::new(system),
         };
         Self {
@@ -271,6 +380,21 @@ impl SystemCatalog {
             .context(InsertCatalogRecordSnafu)
     }
 
+    pub async fn deregister_table(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table_name: &str,
+    ) -> crate::error::Result<usize> {
+        let full_table_name = format_full_table_name(catalog, schema, table_name);
+        let request = build_table_deletion_request(full_table_name);
+        self.information_schema
+            .system
+            .delete(request)
+            .await
+            .context(DeleteCatalogRecordSnafu)
+    }
+
     pub async fn register_schema(
         &self,
         catalog: String,
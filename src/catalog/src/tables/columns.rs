@@ -0,0 +1,152 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `columns` table lists the columns of all tables.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_error::ext::BoxedError;
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use datatypes::prelude::{ConcreteDataType, DataType};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{StringVector, VectorRef};
+use snafu::ResultExt;
+use table::error::TablesRecordBatchSnafu;
+use table::metadata::TableInfoRef;
+use table::Table;
+
+use crate::tables::{scan_columns, visit_tables};
+use crate::CatalogListRef;
+
+pub struct Columns {
+    schema: SchemaRef,
+    catalogs: CatalogListRef,
+}
+
+impl Columns {
+    pub fn new(catalogs: CatalogListRef) -> Self {
+        Self {
+            schema: Arc::new(build_schema_for_columns()),
+            catalogs,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for Columns {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("Columns does not support table_info method")
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let mut catalog_names = Vec::new();
+        let mut schema_names = Vec::new();
+        let mut table_names = Vec::new();
+        let mut column_names = Vec::new();
+        let mut data_types = Vec::new();
+        let mut nullables = Vec::new();
+        let mut defaults = Vec::new();
+        visit_tables(
+            &self.catalogs,
+            |catalog_name, schema_name, table_name, table| {
+                for column_schema in table.schema().column_schemas() {
+                    catalog_names.push(catalog_name.to_string());
+                    schema_names.push(schema_name.to_string());
+                    table_names.push(table_name.to_string());
+                    column_names.push(column_schema.name.clone());
+                    data_types.push(column_schema.data_type.name().to_string());
+                    nullables.push(if column_schema.is_nullable() {
+                        "YES"
+                    } else {
+                        "NO"
+                    });
+                    defaults.push(
+                        column_schema
+                            .default_constraint()
+                            .map(|constraint| constraint.to_string()),
+                    );
+                }
+            },
+        )
+        .map_err(BoxedError::new)
+        .context(TablesRecordBatchSnafu)?;
+
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(catalog_names)),
+            Arc::new(StringVector::from(schema_names)),
+            Arc::new(StringVector::from(table_names)),
+            Arc::new(StringVector::from(column_names)),
+            Arc::new(StringVector::from(data_types)),
+            Arc::new(StringVector::from(nullables)),
+            Arc::new(StringVector::from(defaults)),
+        ];
+        scan_columns(&self.schema, columns, projection)
+    }
+}
+
+fn build_schema_for_columns() -> Schema {
+    let cols = vec![
+        ColumnSchema::new(
+            "catalog".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "schema".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "table_name".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "column_name".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "data_type".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "is_nullable".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "column_default".to_string(),
+            ConcreteDataType::string_datatype(),
+            true,
+        ),
+    ];
+    Schema::new(cols)
+}
@@ -0,0 +1,173 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `processlist` table lists the queries running in the server.
+
+use std::any::Any;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{StringVector, UInt32Vector, UInt64Vector, VectorRef};
+use table::metadata::TableInfoRef;
+use table::Table;
+
+use crate::tables::scan_columns;
+
+/// A running query.
+#[derive(Debug, Clone)]
+pub struct ProcessInfo {
+    /// Id of the connection running the query, which is also the id to kill the query.
+    pub id: u32,
+    pub user: Option<String>,
+    pub client_addr: Option<String>,
+    pub catalog: String,
+    pub schema: String,
+    pub query: String,
+    pub start: Instant,
+}
+
+impl ProcessInfo {
+    /// Time elapsed since the query started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// Provides the running queries listed in the `processlist` table.
+pub trait ProcessListProvider: Send + Sync {
+    /// Returns the running queries, ordered by id.
+    fn processes(&self) -> Vec<ProcessInfo>;
+}
+
+pub type ProcessListProviderRef = Arc<dyn ProcessListProvider>;
+
+pub struct Processlist {
+    schema: SchemaRef,
+    provider: Option<ProcessListProviderRef>,
+}
+
+impl Processlist {
+    /// Creates the table listing the queries provided by `provider`, the table is empty
+    /// if there is no provider.
+    pub fn new(provider: Option<ProcessListProviderRef>) -> Self {
+        Self {
+            schema: Arc::new(build_schema_for_processlist()),
+            provider,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for Processlist {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("Processlist does not support table_info method")
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let processes = self
+            .provider
+            .as_ref()
+            .map(|provider| provider.processes())
+            .unwrap_or_default();
+
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(UInt32Vector::from_values(processes.iter().map(|p| p.id))),
+            Arc::new(StringVector::from(
+                processes.iter().map(|p| p.user.clone()).collect::<Vec<_>>(),
+            )),
+            Arc::new(StringVector::from(
+                processes
+                    .iter()
+                    .map(|p| p.client_addr.clone())
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(StringVector::from(
+                processes
+                    .iter()
+                    .map(|p| p.catalog.as_str())
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(StringVector::from(
+                processes
+                    .iter()
+                    .map(|p| p.schema.as_str())
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(StringVector::from(
+                processes
+                    .iter()
+                    .map(|p| p.query.as_str())
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(UInt64Vector::from_values(
+                processes.iter().map(|p| p.elapsed().as_millis() as u64),
+            )),
+        ];
+        scan_columns(&self.schema, columns, projection)
+    }
+}
+
+fn build_schema_for_processlist() -> Schema {
+    let cols = vec![
+        ColumnSchema::new("id".to_string(), ConcreteDataType::uint32_datatype(), false),
+        ColumnSchema::new(
+            "user".to_string(),
+            ConcreteDataType::string_datatype(),
+            true,
+        ),
+        ColumnSchema::new(
+            "client_addr".to_string(),
+            ConcreteDataType::string_datatype(),
+            true,
+        ),
+        ColumnSchema::new(
+            "catalog".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "schema".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "query".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "elapsed_ms".to_string(),
+            ConcreteDataType::uint64_datatype(),
+            false,
+        ),
+    ];
+    Schema::new(cols)
+}
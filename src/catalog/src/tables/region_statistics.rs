@@ -0,0 +1,129 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `region_statistics` table lists the statistics of the regions of all tables.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_error::ext::BoxedError;
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{StringVector, UInt64Vector, VectorRef};
+use snafu::ResultExt;
+use table::error::TablesRecordBatchSnafu;
+use table::metadata::TableInfoRef;
+use table::Table;
+
+use crate::tables::{scan_columns, visit_tables};
+use crate::CatalogListRef;
+
+pub struct RegionStatistics {
+    schema: SchemaRef,
+    catalogs: CatalogListRef,
+}
+
+impl RegionStatistics {
+    pub fn new(catalogs: CatalogListRef) -> Self {
+        Self {
+            schema: Arc::new(build_schema_for_region_statistics()),
+            catalogs,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for RegionStatistics {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("RegionStatistics does not support table_info method")
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let mut catalog_names = Vec::new();
+        let mut schema_names = Vec::new();
+        let mut table_names = Vec::new();
+        let mut stats = Vec::new();
+        visit_tables(
+            &self.catalogs,
+            |catalog_name, schema_name, table_name, table| {
+                for stat in table.region_stats() {
+                    catalog_names.push(catalog_name.to_string());
+                    schema_names.push(schema_name.to_string());
+                    table_names.push(table_name.to_string());
+                    stats.push(stat);
+                }
+            },
+        )
+        .map_err(BoxedError::new)
+        .context(TablesRecordBatchSnafu)?;
+
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(catalog_names)),
+            Arc::new(StringVector::from(schema_names)),
+            Arc::new(StringVector::from(table_names)),
+            Arc::new(UInt64Vector::from_values(stats.iter().map(|s| s.region_id))),
+            Arc::new(StringVector::from(
+                stats
+                    .iter()
+                    .map(|s| s.region_name.as_str())
+                    .collect::<Vec<_>>(),
+            )),
+            Arc::new(UInt64Vector::from_values(
+                stats.iter().map(|s| s.memtable_bytes),
+            )),
+            Arc::new(UInt64Vector::from_values(stats.iter().map(|s| s.sst_num))),
+            Arc::new(UInt64Vector::from_values(stats.iter().map(|s| s.sst_bytes))),
+            Arc::new(UInt64Vector::from_values(
+                stats.iter().map(|s| s.flushed_sequence),
+            )),
+        ];
+        scan_columns(&self.schema, columns, projection)
+    }
+}
+
+fn build_schema_for_region_statistics() -> Schema {
+    let string_column = |name: &str| {
+        ColumnSchema::new(name.to_string(), ConcreteDataType::string_datatype(), false)
+    };
+    let uint64_column = |name: &str| {
+        ColumnSchema::new(name.to_string(), ConcreteDataType::uint64_datatype(), false)
+    };
+    let cols = vec![
+        string_column("catalog"),
+        string_column("schema"),
+        string_column("table_name"),
+        uint64_column("region_id"),
+        string_column("region_name"),
+        uint64_column("memtable_bytes"),
+        uint64_column("sst_num"),
+        uint64_column("sst_bytes"),
+        uint64_column("flushed_sequence"),
+    ];
+    Schema::new(cols)
+}
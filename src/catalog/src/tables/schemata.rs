@@ -0,0 +1,113 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The `schemata` table lists all schemas in the catalogs.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use common_error::ext::BoxedError;
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{StringVector, VectorRef};
+use snafu::ResultExt;
+use table::error::TablesRecordBatchSnafu;
+use table::metadata::TableInfoRef;
+use table::Table;
+
+use crate::error::Result;
+use crate::tables::scan_columns;
+use crate::CatalogListRef;
+
+pub struct Schemata {
+    schema: SchemaRef,
+    catalogs: CatalogListRef,
+}
+
+impl Schemata {
+    pub fn new(catalogs: CatalogListRef) -> Self {
+        Self {
+            schema: Arc::new(build_schema_for_schemata()),
+            catalogs,
+        }
+    }
+
+    /// Returns the catalog name and schema name of all schemas.
+    fn schemas(&self) -> Result<Vec<(String, String)>> {
+        let mut schemas = Vec::new();
+        for catalog_name in self.catalogs.catalog_names()? {
+            if let Some(catalog) = self.catalogs.catalog(&catalog_name)? {
+                for schema_name in catalog.schema_names()? {
+                    schemas.push((catalog_name.clone(), schema_name));
+                }
+            }
+        }
+        Ok(schemas)
+    }
+}
+
+#[async_trait::async_trait]
+impl Table for Schemata {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        unreachable!("Schemata does not support table_info method")
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        _filters: &[Expr],
+        _limit: Option<usize>,
+    ) -> table::error::Result<PhysicalPlanRef> {
+        let schemas = self
+            .schemas()
+            .map_err(BoxedError::new)
+            .context(TablesRecordBatchSnafu)?;
+
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(
+                schemas.iter().map(|s| s.0.as_str()).collect::<Vec<_>>(),
+            )),
+            Arc::new(StringVector::from(
+                schemas.iter().map(|s| s.1.as_str()).collect::<Vec<_>>(),
+            )),
+        ];
+        scan_columns(&self.schema, columns, projection)
+    }
+}
+
+fn build_schema_for_schemata() -> Schema {
+    let cols = vec![
+        ColumnSchema::new(
+            "catalog".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            "schema".to_string(),
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+    ];
+    Schema::new(cols)
+}
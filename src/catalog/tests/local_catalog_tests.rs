@@ -15,10 +15,16 @@
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
+    use std::time::Instant;
 
     use catalog::local::LocalCatalogManager;
-    use catalog::{CatalogManager, RegisterTableRequest};
-    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+    use catalog::tables::{ProcessInfo, ProcessListProvider, ProcessListProviderRef};
+    use catalog::{CatalogManager, DeregisterTableRequest, RegisterTableRequest};
+    use common_catalog::consts::{
+        DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, INFORMATION_SCHEMA_NAME, SYSTEM_CATALOG_NAME,
+    };
+    use common_query::physical_plan::RuntimeEnv;
+    use common_recordbatch::RecordBatches;
     use common_telemetry::{error, info};
     use mito::config::EngineConfig;
     use table::table::numbers::NumbersTable;
@@ -26,6 +32,12 @@ mod tests {
     use tokio::sync::Mutex;
 
     async fn create_local_catalog_manager() -> Result<LocalCatalogManager, catalog::error::Error> {
+        create_local_catalog_manager_with_process_list(None).await
+    }
+
+    async fn create_local_catalog_manager_with_process_list(
+        process_list: Option<ProcessListProviderRef>,
+    ) -> Result<LocalCatalogManager, catalog::error::Error> {
         let (_dir, object_store) =
             mito::table::test_util::new_test_object_store("setup_mock_engine_and_table").await;
         let mock_engine = Arc::new(mito::table::test_util::MockMitoEngine::new(
@@ -33,11 +45,83 @@ mod tests {
             mito::table::test_util::MockEngine::default(),
             object_store,
         ));
-        let catalog_manager = LocalCatalogManager::try_new(mock_engine).await.unwrap();
+        let catalog_manager = LocalCatalogManager::new_with_process_list(mock_engine, process_list)
+            .await
+            .unwrap();
         catalog_manager.start().await?;
         Ok(catalog_manager)
     }
 
+    struct MockProcessList;
+
+    impl ProcessListProvider for MockProcessList {
+        fn processes(&self) -> Vec<ProcessInfo> {
+            vec![ProcessInfo {
+                id: 42,
+                user: Some("greptime".to_string()),
+                client_addr: Some("127.0.0.1:4002".to_string()),
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: DEFAULT_SCHEMA_NAME.to_string(),
+                query: "SELECT * FROM numbers".to_string(),
+                start: Instant::now(),
+            }]
+        }
+    }
+
+    async fn scan_information_schema(
+        catalog_manager: &LocalCatalogManager,
+        table_name: &str,
+        projection: Option<Vec<usize>>,
+    ) -> String {
+        let table = catalog_manager
+            .table(SYSTEM_CATALOG_NAME, INFORMATION_SCHEMA_NAME, table_name)
+            .unwrap()
+            .unwrap();
+        let plan = table.scan(&projection, &[], None).await.unwrap();
+        let stream = plan.execute(0, Arc::new(RuntimeEnv::default())).unwrap();
+        let schema = stream.schema();
+        let batches = common_recordbatch::util::collect(stream).await.unwrap();
+        RecordBatches::try_new(schema, batches)
+            .unwrap()
+            .pretty_print()
+    }
+
+    #[tokio::test]
+    async fn test_information_schema() {
+        let catalog_manager =
+            create_local_catalog_manager_with_process_list(Some(Arc::new(MockProcessList)))
+                .await
+                .unwrap();
+
+        let output = scan_information_schema(&catalog_manager, "tables", Some(vec![2])).await;
+        assert!(output.contains("numbers"), "{}", output);
+        assert!(!output.contains("engine"), "{}", output);
+
+        let output = scan_information_schema(&catalog_manager, "schemata", None).await;
+        assert!(output.contains(DEFAULT_SCHEMA_NAME), "{}", output);
+        assert!(output.contains(INFORMATION_SCHEMA_NAME), "{}", output);
+
+        let output = scan_information_schema(&catalog_manager, "columns", Some(vec![2, 3])).await;
+        assert!(output.contains("| numbers"), "{}", output);
+        assert!(output.contains("| number "), "{}", output);
+        assert!(!output.contains("data_type"), "{}", output);
+
+        let output = scan_information_schema(&catalog_manager, "processlist", None).await;
+        assert!(output.contains("| 42 "), "{}", output);
+        assert!(output.contains("127.0.0.1:4002"), "{}", output);
+        assert!(output.contains("SELECT * FROM numbers"), "{}", output);
+
+        // Only the system catalog table is stored in regions.
+        let output = scan_information_schema(&catalog_manager, "region_statistics", None).await;
+        assert!(output.contains("system_catalog"), "{}", output);
+        assert!(!output.contains("| numbers"), "{}", output);
+
+        // The process list is empty without provider.
+        let catalog_manager = create_local_catalog_manager().await.unwrap();
+        let output = scan_information_schema(&catalog_manager, "processlist", None).await;
+        assert!(!output.contains("| 42 "), "{}", output);
+    }
+
     #[tokio::test]
     async fn test_duplicate_register() {
         let catalog_manager = create_local_catalog_manager().await.unwrap();
@@ -74,6 +158,36 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_deregister_table() {
+        let catalog_manager = create_local_catalog_manager().await.unwrap();
+        let request = RegisterTableRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: "test_table".to_string(),
+            table_id: 42,
+            table: Arc::new(NumbersTable::new(42)),
+        };
+        assert!(catalog_manager.register_table(request).await.unwrap());
+
+        let request = DeregisterTableRequest {
+            catalog: DEFAULT_CATALOG_NAME.to_string(),
+            schema: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: "test_table".to_string(),
+        };
+        assert!(catalog_manager
+            .deregister_table(request.clone())
+            .await
+            .unwrap());
+        assert!(catalog_manager
+            .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "test_table")
+            .unwrap()
+            .is_none());
+
+        // deregister a table that doesn't exist returns false.
+        assert!(!catalog_manager.deregister_table(request).await.unwrap());
+    }
+
     #[test]
     fn test_concurrent_register() {
         common_telemetry::init_default_ut_logging();
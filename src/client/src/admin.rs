@@ -37,6 +37,7 @@ impl Admin {
     pub async fn create(&self, expr: CreateExpr) -> Result<AdminResult> {
         let header = ExprHeader {
             version: PROTOCOL_VERSION,
+            ..Default::default()
         };
         let expr = AdminExpr {
             header: Some(header),
@@ -53,6 +54,7 @@ impl Admin {
     pub async fn alter(&self, expr: AlterExpr) -> Result<AdminResult> {
         let header = ExprHeader {
             version: PROTOCOL_VERSION,
+            ..Default::default()
         };
         let expr = AdminExpr {
             header: Some(header),
@@ -64,6 +66,7 @@ impl Admin {
     pub async fn drop_table(&self, expr: DropTableExpr) -> Result<AdminResult> {
         let header = ExprHeader {
             version: PROTOCOL_VERSION,
+            ..Default::default()
         };
         let expr = AdminExpr {
             header: Some(header),
@@ -73,6 +76,32 @@ impl Admin {
         self.do_request(expr).await
     }
 
+    pub async fn flush_table(&self, expr: FlushTableExpr) -> Result<AdminResult> {
+        let header = ExprHeader {
+            version: PROTOCOL_VERSION,
+            ..Default::default()
+        };
+        let expr = AdminExpr {
+            header: Some(header),
+            expr: Some(admin_expr::Expr::FlushTable(expr)),
+        };
+
+        self.do_request(expr).await
+    }
+
+    pub async fn compact_table(&self, expr: CompactTableExpr) -> Result<AdminResult> {
+        let header = ExprHeader {
+            version: PROTOCOL_VERSION,
+            ..Default::default()
+        };
+        let expr = AdminExpr {
+            header: Some(header),
+            expr: Some(admin_expr::Expr::CompactTable(expr)),
+        };
+
+        self.do_request(expr).await
+    }
+
     /// Invariants: the lengths of input vec (`Vec<AdminExpr>`) and output vec (`Vec<AdminResult>`) are equal.
     async fn do_requests(&self, exprs: Vec<AdminExpr>) -> Result<Vec<AdminResult>> {
         let expr_count = exprs.len();
@@ -98,6 +127,7 @@ impl Admin {
     pub async fn create_database(&self, expr: CreateDatabaseExpr) -> Result<AdminResult> {
         let header = ExprHeader {
             version: PROTOCOL_VERSION,
+            ..Default::default()
         };
         let expr = AdminExpr {
             header: Some(header),
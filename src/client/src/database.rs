@@ -42,6 +42,9 @@ pub const PROTOCOL_VERSION: u32 = 1;
 pub struct Database {
     name: String,
     client: Client,
+    // Catalog and schema sent in the header of requests, empty means the default one.
+    catalog: String,
+    schema: String,
 }
 
 impl Database {
@@ -49,6 +52,8 @@ impl Database {
         Self {
             name: name.into(),
             client,
+            catalog: String::default(),
+            schema: String::default(),
         }
     }
 
@@ -56,21 +61,34 @@ impl Database {
         &self.name
     }
 
-    pub async fn insert(&self, insert: InsertExpr) -> Result<ObjectResult> {
-        let header = ExprHeader {
+    /// Sets the catalog and schema to resolve unqualified table names against.
+    pub fn set_catalog_and_schema(
+        &mut self,
+        catalog: impl Into<String>,
+        schema: impl Into<String>,
+    ) {
+        self.catalog = catalog.into();
+        self.schema = schema.into();
+    }
+
+    fn header(&self) -> ExprHeader {
+        ExprHeader {
             version: PROTOCOL_VERSION,
-        };
+            catalog: self.catalog.clone(),
+            schema: self.schema.clone(),
+        }
+    }
+
+    pub async fn insert(&self, insert: InsertExpr) -> Result<ObjectResult> {
         let expr = ObjectExpr {
-            header: Some(header),
+            header: Some(self.header()),
             expr: Some(object_expr::Expr::Insert(insert)),
         };
         self.object(expr).await?.try_into()
     }
 
     pub async fn batch_insert(&self, insert_exprs: Vec<InsertExpr>) -> Result<Vec<ObjectResult>> {
-        let header = ExprHeader {
-            version: PROTOCOL_VERSION,
-        };
+        let header = self.header();
         let obj_exprs = insert_exprs
             .into_iter()
             .map(|expr| ObjectExpr {
@@ -120,12 +138,8 @@ impl Database {
     }
 
     async fn do_select(&self, select_expr: SelectExpr) -> Result<ObjectResult> {
-        let header = ExprHeader {
-            version: PROTOCOL_VERSION,
-        };
-
         let expr = ObjectExpr {
-            header: Some(header),
+            header: Some(self.header()),
             expr: Some(object_expr::Expr::Select(select_expr)),
         };
 
@@ -170,9 +170,64 @@ mod tests {
             ObjectStoreConfig::File { data_dir } => {
                 assert_eq!("/tmp/greptimedb/data/".to_string(), data_dir)
             }
+            ObjectStoreConfig::S3(_) | ObjectStoreConfig::Azblob(_) => unreachable!(),
         };
     }
 
+    #[test]
+    fn test_read_object_store_config() {
+        let storage: ObjectStoreConfig = toml::from_str(
+            r#"
+            type = 'S3'
+            bucket = 'greptimedb'
+            root = 'data'
+            access_key_id = 'access_key_id'
+            secret_access_key = 'secret_access_key'
+            endpoint = 'http://127.0.0.1:9000'
+            "#,
+        )
+        .unwrap();
+        match &storage {
+            ObjectStoreConfig::S3(s3_config) => {
+                assert_eq!("greptimedb", s3_config.bucket);
+                assert_eq!("data", s3_config.root);
+                assert_eq!("access_key_id", s3_config.access_key_id);
+                assert_eq!("secret_access_key", s3_config.secret_access_key);
+                assert_eq!(Some("http://127.0.0.1:9000"), s3_config.endpoint.as_deref());
+                assert!(s3_config.region.is_none());
+            }
+            _ => unreachable!(),
+        }
+        // Credentials should not be printed.
+        assert!(!format!("{:?}", storage).contains("secret_access_key"));
+
+        let storage: ObjectStoreConfig = toml::from_str(
+            r#"
+            type = 'Azblob'
+            container = 'greptimedb'
+            root = 'data'
+            account_name = 'account_name'
+            account_key = 'account_key'
+            endpoint = 'http://127.0.0.1:10000/account_name'
+            "#,
+        )
+        .unwrap();
+        match &storage {
+            ObjectStoreConfig::Azblob(azblob_config) => {
+                assert_eq!("greptimedb", azblob_config.container);
+                assert_eq!("data", azblob_config.root);
+                assert_eq!("account_name", azblob_config.account_name);
+                assert_eq!("account_key", azblob_config.account_key);
+                assert_eq!(
+                    "http://127.0.0.1:10000/account_name",
+                    azblob_config.endpoint
+                );
+            }
+            _ => unreachable!(),
+        }
+        assert!(!format!("{:?}", storage).contains("account_key"));
+    }
+
     #[test]
     fn test_try_from_cmd() {
         assert_eq!(
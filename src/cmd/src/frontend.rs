@@ -71,6 +71,8 @@ pub struct StartCommand {
     influxdb_enable: Option<bool>,
     #[clap(long)]
     metasrv_addr: Option<String>,
+    #[clap(long)]
+    user_provider: Option<String>,
 }
 
 impl StartCommand {
@@ -139,6 +141,9 @@ impl TryFrom<StartCommand> for FrontendOptions {
                 .collect::<Vec<_>>();
             opts.mode = Mode::Distributed;
         }
+        if let Some(user_provider) = cmd.user_provider {
+            opts.user_provider = Some(user_provider);
+        }
         Ok(opts)
     }
 }
@@ -160,6 +165,7 @@ mod tests {
             influxdb_enable: Some(false),
             config_file: None,
             metasrv_addr: None,
+            user_provider: Some("static_user_provider:cmd:root=123456".to_string()),
         };
 
         let opts: FrontendOptions = command.try_into().unwrap();
@@ -193,6 +199,10 @@ mod tests {
         );
 
         assert!(!opts.influxdb_options.unwrap().enable);
+        assert_eq!(
+            Some("static_user_provider:cmd:root=123456"),
+            opts.user_provider.as_deref()
+        );
     }
 
     #[test]
@@ -209,6 +219,7 @@ mod tests {
                 std::env::current_dir().unwrap().as_path().to_str().unwrap()
             )),
             metasrv_addr: None,
+            user_provider: None,
         };
 
         let fe_opts = FrontendOptions::try_from(command).unwrap();
@@ -12,9 +12,11 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::time::Duration;
+
 use clap::Parser;
 use common_telemetry::info;
-use datanode::datanode::{Datanode, DatanodeOptions, ObjectStoreConfig};
+use datanode::datanode::{Datanode, DatanodeOptions, ObjectStoreConfig, ReadCacheConfig};
 use datanode::instance::InstanceRef;
 use frontend::frontend::{Frontend, FrontendOptions};
 use frontend::grpc::GrpcOptions;
@@ -72,7 +74,11 @@ pub struct StandaloneOptions {
     pub mode: Mode,
     pub wal_dir: String,
     pub storage: ObjectStoreConfig,
+    pub read_cache: Option<ReadCacheConfig>,
     pub enable_memory_catalog: bool,
+    pub user_provider: Option<String>,
+    #[serde(default, with = "humantime_serde")]
+    pub query_timeout: Option<Duration>,
 }
 
 impl Default for StandaloneOptions {
@@ -88,7 +94,10 @@ impl Default for StandaloneOptions {
             mode: Mode::Standalone,
             wal_dir: "/tmp/greptimedb/wal".to_string(),
             storage: ObjectStoreConfig::default(),
+            read_cache: None,
             enable_memory_catalog: false,
+            user_provider: None,
+            query_timeout: None,
         }
     }
 }
@@ -106,6 +115,8 @@ impl StandaloneOptions {
             mode: self.mode,
             datanode_rpc_addr: "127.0.0.1:3001".to_string(),
             meta_client_opts: None,
+            user_provider: self.user_provider,
+            query_timeout: self.query_timeout,
         }
     }
 
@@ -113,7 +124,9 @@ impl StandaloneOptions {
         DatanodeOptions {
             wal_dir: self.wal_dir,
             storage: self.storage,
+            read_cache: self.read_cache,
             enable_memory_catalog: self.enable_memory_catalog,
+            query_timeout: self.query_timeout,
             ..Default::default()
         }
     }
@@ -137,6 +150,8 @@ struct StartCommand {
     config_file: Option<String>,
     #[clap(short = 'm', long = "memory-catalog")]
     enable_memory_catalog: bool,
+    #[clap(long)]
+    user_provider: Option<String>,
 }
 
 impl StartCommand {
@@ -261,6 +276,10 @@ impl TryFrom<StartCommand> for FrontendOptions {
             opts.influxdb_options = Some(InfluxdbOptions { enable: true });
         }
 
+        if let Some(user_provider) = cmd.user_provider {
+            opts.user_provider = Some(user_provider);
+        }
+
         Ok(opts)
     }
 }
@@ -285,6 +304,8 @@ mod tests {
             )),
             influxdb_enable: false,
             enable_memory_catalog: false,
+            user_provider: None,
+            query_timeout: None,
         };
 
         let fe_opts = FrontendOptions::try_from(cmd).unwrap();
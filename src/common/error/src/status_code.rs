@@ -43,6 +43,10 @@ pub enum StatusCode {
     PlanQuery = 3000,
     /// The query engine fail to execute query.
     EngineExecuteQuery = 3001,
+    /// The query is cancelled, e.g. killed by the user.
+    Cancelled = 3002,
+    /// The query runs longer than the configured timeout.
+    DeadlineExceeded = 3003,
     // ====== End of query related status code =========
 
     // ====== Begin of catalog related status code =====
@@ -51,6 +55,7 @@ pub enum StatusCode {
     TableNotFound = 4001,
     TableColumnNotFound = 4002,
     TableColumnExists = 4003,
+    DatabaseNotFound = 4004,
     // ====== End of catalog related status code =======
 
     // ====== Begin of storage related status code =====
@@ -62,6 +67,21 @@ pub enum StatusCode {
     /// Runtime resources exhausted, like creating threads failed.
     RuntimeResourcesExhausted = 6000,
     // ====== End of server related status code =======
+
+    // ====== Begin of auth related status code =====
+    /// User not exist.
+    UserNotFound = 7000,
+    /// Unsupported password type.
+    UnsupportedPasswordType = 7001,
+    /// Username and password does not match.
+    UserPasswordMismatch = 7002,
+    /// Not found http authorization header.
+    AuthHeaderNotFound = 7003,
+    /// Invalid http authorization header.
+    InvalidAuthHeader = 7004,
+    /// The user is not allowed to perform the operation.
+    AccessDenied = 7005,
+    // ====== End of auth related status code =====
 }
 
 impl StatusCode {
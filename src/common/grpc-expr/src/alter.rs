@@ -15,7 +15,7 @@
 use std::sync::Arc;
 
 use api::v1::alter_expr::Kind;
-use api::v1::{AlterExpr, CreateExpr, DropColumns};
+use api::v1::{AlterExpr, CreateExpr, DropColumns, SetTableOptions};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use datatypes::schema::{ColumnSchema, SchemaBuilder, SchemaRef};
 use snafu::{ensure, OptionExt, ResultExt};
@@ -77,6 +77,19 @@ pub fn alter_expr_to_request(expr: AlterExpr) -> Result<Option<AlterTableRequest
             };
             Ok(Some(request))
         }
+        Some(Kind::SetTableOptions(SetTableOptions { table_options })) => {
+            let alter_kind = AlterKind::SetOptions {
+                options: table_options,
+            };
+
+            let request = AlterTableRequest {
+                catalog_name: expr.catalog_name,
+                schema_name: expr.schema_name,
+                table_name: expr.table_name,
+                alter_kind,
+            };
+            Ok(Some(request))
+        }
         None => Ok(None),
     }
 }
@@ -163,6 +176,8 @@ pub fn create_expr_to_request(table_id: TableId, expr: CreateExpr) -> Result<Cre
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
     use api::v1::{AddColumn, AddColumns, ColumnDataType, ColumnDef, DropColumn};
     use datatypes::prelude::ConcreteDataType;
 
@@ -231,4 +246,27 @@ mod tests {
         assert_eq!(1, drop_names.len());
         assert_eq!("mem_usage".to_string(), drop_names.pop().unwrap());
     }
+
+    #[test]
+    fn test_set_table_options_expr() {
+        let expr = AlterExpr {
+            catalog_name: None,
+            schema_name: None,
+            table_name: "monitor".to_string(),
+
+            kind: Some(Kind::SetTableOptions(SetTableOptions {
+                table_options: HashMap::from([("ttl".to_string(), "7d".to_string())]),
+            })),
+        };
+
+        let alter_request = alter_expr_to_request(expr).unwrap().unwrap();
+        assert_eq!("monitor".to_string(), alter_request.table_name);
+
+        let options = match alter_request.alter_kind {
+            AlterKind::SetOptions { options } => options,
+            _ => unreachable!(),
+        };
+        assert_eq!(1, options.len());
+        assert_eq!("7d", options["ttl"]);
+    }
 }
@@ -259,6 +259,7 @@ pub fn insertion_expr_to_request(
     catalog_name: &str,
     schema_name: &str,
     table_name: &str,
+    region_number: u32,
     insert_batches: Vec<(Vec<Column>, u32)>,
     table: Arc<dyn Table>,
 ) -> Result<InsertRequest> {
@@ -308,6 +309,7 @@ pub fn insertion_expr_to_request(
         schema_name: schema_name.to_string(),
         table_name: table_name.to_string(),
         columns_values,
+        region_number,
     })
 }
 
@@ -605,7 +607,8 @@ mod tests {
 
         let insert_batches = vec![mock_insert_batch()];
         let insert_req =
-            insertion_expr_to_request("greptime", "public", "demo", insert_batches, table).unwrap();
+            insertion_expr_to_request("greptime", "public", "demo", 0, insert_batches, table)
+                .unwrap();
 
         assert_eq!("greptime", insert_req.catalog_name);
         assert_eq!("public", insert_req.schema_name);
@@ -0,0 +1,166 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cooperative cancellation of running queries.
+//!
+//! A query is bound to a [CancellationToken] while it is planned, so table scans can capture
+//! the token via [CancellationToken::current] and check it between batches.
+
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Arc;
+
+use crate::error::{self, Error, Result};
+
+tokio::task_local! {
+    static CURRENT_TOKEN: CancellationToken;
+}
+
+const REASON_NONE: u8 = 0;
+const REASON_KILLED: u8 = 1;
+const REASON_TIMEOUT: u8 = 2;
+
+/// Why a query is cancelled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CancelReason {
+    /// Killed by the user, e.g. by a `KILL QUERY` statement.
+    Killed,
+    /// Runs longer than the query timeout.
+    Timeout,
+}
+
+impl fmt::Display for CancelReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CancelReason::Killed => write!(f, "killed"),
+            CancelReason::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+/// A token to cancel a running query. Clones share the same cancellation state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: tokio_util::sync::CancellationToken,
+    reason: Arc<AtomicU8>,
+}
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the token bound to the current task by [CancellationToken::scope], or a token
+    /// that is never cancelled if there is none.
+    pub fn current() -> Self {
+        CURRENT_TOKEN
+            .try_with(|token| token.clone())
+            .unwrap_or_default()
+    }
+
+    /// Runs `fut` with this token bound as the current token.
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        CURRENT_TOKEN.scope(self.clone(), fut).await
+    }
+
+    /// Cancels the query. Only the reason of the first cancellation is kept.
+    pub fn cancel(&self, reason: CancelReason) {
+        let reason = match reason {
+            CancelReason::Killed => REASON_KILLED,
+            CancelReason::Timeout => REASON_TIMEOUT,
+        };
+        let current = &self.reason;
+        let _ = current.compare_exchange(REASON_NONE, reason, Ordering::SeqCst, Ordering::SeqCst);
+        self.inner.cancel();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.is_cancelled()
+    }
+
+    pub fn reason(&self) -> Option<CancelReason> {
+        match self.reason.load(Ordering::SeqCst) {
+            REASON_KILLED => Some(CancelReason::Killed),
+            REASON_TIMEOUT => Some(CancelReason::Timeout),
+            _ => None,
+        }
+    }
+
+    /// Waits until the token is cancelled, returns the error describing the cancellation.
+    pub async fn cancelled(&self) -> Error {
+        self.inner.cancelled().await;
+        let reason = self.reason().unwrap_or(CancelReason::Killed);
+        error::QueryCancelledSnafu { reason }.build().into()
+    }
+
+    /// Returns an error if the token is cancelled.
+    pub fn check(&self) -> Result<()> {
+        match self.reason() {
+            Some(reason) => error::QueryCancelledSnafu { reason }
+                .fail()
+                .map_err(Into::into),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_cancel() {
+        let token = CancellationToken::new();
+        assert!(!token.is_cancelled());
+        assert!(token.reason().is_none());
+        assert!(token.check().is_ok());
+
+        let cloned = token.clone();
+        cloned.cancel(CancelReason::Timeout);
+        token.cancel(CancelReason::Killed);
+        assert!(token.is_cancelled());
+        assert_eq!(Some(CancelReason::Timeout), token.reason());
+        let err = token.check().unwrap_err();
+        assert_eq!(StatusCode::DeadlineExceeded, err.status_code());
+
+        let token = CancellationToken::new();
+        token.cancel(CancelReason::Killed);
+        assert_eq!(
+            StatusCode::Cancelled,
+            token.check().unwrap_err().status_code()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_current_token() {
+        assert!(!CancellationToken::current().is_cancelled());
+
+        let token = CancellationToken::new();
+        token
+            .scope(async {
+                let current = CancellationToken::current();
+                assert!(!current.is_cancelled());
+                token.cancel(CancelReason::Killed);
+                assert!(current.is_cancelled());
+                let err = current.cancelled().await;
+                assert_eq!(StatusCode::Cancelled, err.status_code());
+            })
+            .await;
+
+        assert!(!CancellationToken::current().is_cancelled());
+    }
+}
@@ -21,6 +21,8 @@ use datatypes::error::Error as DataTypeError;
 use datatypes::prelude::ConcreteDataType;
 use statrs::StatsError;
 
+use crate::cancellation::CancelReason;
+
 common_error::define_opaque_error!(Error);
 
 #[derive(Debug, Snafu)]
@@ -120,6 +122,12 @@ pub enum InnerError {
         #[snafu(backtrace)]
         source: BoxedError,
     },
+
+    #[snafu(display("Query is cancelled, reason: {}", reason))]
+    QueryCancelled {
+        reason: CancelReason,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -148,6 +156,11 @@ impl ErrorExt for InnerError {
 
             InnerError::ConvertDfRecordBatchStream { source, .. } => source.status_code(),
             InnerError::ExecutePhysicalPlan { source } => source.status_code(),
+
+            InnerError::QueryCancelled { reason, .. } => match reason {
+                CancelReason::Killed => StatusCode::Cancelled,
+                CancelReason::Timeout => StatusCode::DeadlineExceeded,
+            },
         }
     }
 
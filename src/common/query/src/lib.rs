@@ -14,6 +14,7 @@
 
 use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
 
+pub mod cancellation;
 pub mod columnar_value;
 pub mod error;
 mod function;
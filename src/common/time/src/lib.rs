@@ -22,6 +22,6 @@ pub mod util;
 
 pub use date::Date;
 pub use datetime::DateTime;
-pub use range::RangeMillis;
+pub use range::{RangeMillis, TimestampRange};
 pub use timestamp::Timestamp;
 pub use timestamp_millis::TimestampMillis;
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use crate::timestamp::Timestamp;
 use crate::timestamp_millis::TimestampMillis;
 
 /// A half-open time range.
@@ -74,9 +75,79 @@ impl<T: PartialOrd> TimeRange<T> {
 /// Time range in milliseconds.
 pub type RangeMillis = TimeRange<TimestampMillis>;
 
+/// A closed range of [Timestamp]s.
+///
+/// The range contains all timestamp `ts` that `ts >= start` and `ts <= end`. A `None` bound
+/// means the range is unbounded in that direction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TimestampRange {
+    start: Option<Timestamp>,
+    end: Option<Timestamp>,
+}
+
+impl TimestampRange {
+    /// Creates a new range that contains timestamp in `[start, end]`.
+    pub fn new(start: Option<Timestamp>, end: Option<Timestamp>) -> TimestampRange {
+        TimestampRange { start, end }
+    }
+
+    /// Returns a range that contains all timestamps.
+    pub fn min_to_max() -> TimestampRange {
+        TimestampRange::default()
+    }
+
+    /// Returns a range only contains `ts`.
+    pub fn single(ts: Timestamp) -> TimestampRange {
+        TimestampRange::new(Some(ts), Some(ts))
+    }
+
+    /// Returns the lower bound of the range (inclusive).
+    #[inline]
+    pub fn start(&self) -> Option<&Timestamp> {
+        self.start.as_ref()
+    }
+
+    /// Returns the upper bound of the range (inclusive).
+    #[inline]
+    pub fn end(&self) -> Option<&Timestamp> {
+        self.end.as_ref()
+    }
+
+    /// Returns true if the range contains no timestamps.
+    pub fn is_empty(&self) -> bool {
+        match (&self.start, &self.end) {
+            (Some(start), Some(end)) => start > end,
+            _ => false,
+        }
+    }
+
+    /// Returns the intersection of `self` and `other`.
+    pub fn and(&self, other: &TimestampRange) -> TimestampRange {
+        let start = match (self.start, other.start) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        };
+        let end = match (self.end, other.end) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+
+        TimestampRange { start, end }
+    }
+
+    /// Returns true if the range overlaps with the closed range `[start, end]`.
+    pub fn intersects(&self, start: &Timestamp, end: &Timestamp) -> bool {
+        let after_start = self.start.map(|s| *end >= s).unwrap_or(true);
+        let before_end = self.end.map(|e| *start <= e).unwrap_or(true);
+
+        after_start && before_end && start <= end
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::timestamp::TimeUnit;
 
     #[test]
     fn test_new_range() {
@@ -118,4 +189,47 @@ mod tests {
         assert!(range.is_empty());
         assert!(!range.contains(&0));
     }
+
+    #[test]
+    fn test_timestamp_range_and() {
+        let ts = Timestamp::from_millis;
+        let range = TimestampRange::min_to_max();
+        assert!(!range.is_empty());
+        assert_eq!(None, range.start());
+        assert_eq!(None, range.end());
+
+        let range = range.and(&TimestampRange::new(Some(ts(10)), None));
+        assert_eq!(TimestampRange::new(Some(ts(10)), None), range);
+        let range = range.and(&TimestampRange::new(Some(ts(5)), Some(ts(20))));
+        assert_eq!(TimestampRange::new(Some(ts(10)), Some(ts(20))), range);
+        let range = range.and(&TimestampRange::single(ts(15)));
+        assert_eq!(TimestampRange::single(ts(15)), range);
+        assert!(!range.is_empty());
+
+        let range = range.and(&TimestampRange::new(None, Some(ts(12))));
+        assert!(range.is_empty());
+    }
+
+    #[test]
+    fn test_timestamp_range_intersects() {
+        let ts = Timestamp::from_millis;
+        assert!(TimestampRange::min_to_max().intersects(&ts(0), &ts(10)));
+
+        let range = TimestampRange::new(Some(ts(10)), Some(ts(20)));
+        assert!(range.intersects(&ts(0), &ts(10)));
+        assert!(range.intersects(&ts(12), &ts(15)));
+        assert!(range.intersects(&ts(20), &ts(30)));
+        assert!(range.intersects(&ts(0), &ts(30)));
+        assert!(!range.intersects(&ts(0), &ts(9)));
+        assert!(!range.intersects(&ts(21), &ts(30)));
+
+        let range = TimestampRange::new(Some(ts(10)), None);
+        assert!(range.intersects(&ts(100), &ts(200)));
+        assert!(!range.intersects(&ts(0), &ts(5)));
+
+        // Compare timestamps in different units.
+        let range = TimestampRange::new(Some(Timestamp::new(1, TimeUnit::Second)), None);
+        assert!(range.intersects(&ts(500), &ts(1000)));
+        assert!(!range.intersects(&ts(500), &ts(999)));
+    }
 }
@@ -65,9 +65,17 @@ impl Timestamp {
             nsecs += nano_factor;
         }
 
-        let datetime = Utc.timestamp(secs, nsecs as u32);
+        let datetime = Utc.timestamp_opt(secs, nsecs as u32).unwrap();
         format!("{}", datetime.format("%Y-%m-%d %H:%M:%S%.f%z"))
     }
+
+    /// Converts to [NaiveDateTime] in UTC, returns `None` if the timestamp is out of range.
+    pub fn to_chrono_datetime(&self) -> Option<NaiveDateTime> {
+        let units_per_sec = TimeUnit::Second.factor() / self.unit.factor();
+        let secs = self.value.div_euclid(units_per_sec);
+        let nsecs = self.value.rem_euclid(units_per_sec) * self.unit.factor();
+        NaiveDateTime::from_timestamp_opt(secs, nsecs as u32)
+    }
 }
 
 impl FromStr for Timestamp {
@@ -225,6 +233,22 @@ mod tests {
         assert!(t > Timestamp::new(999, TimeUnit::Microsecond));
     }
 
+    #[test]
+    pub fn test_to_chrono_datetime() {
+        let t = Timestamp::new(1_000_001, TimeUnit::Millisecond);
+        assert_eq!(
+            NaiveDateTime::from_timestamp_opt(1000, 1_000_000).unwrap(),
+            t.to_chrono_datetime().unwrap()
+        );
+        let t = Timestamp::new(-1, TimeUnit::Microsecond);
+        assert_eq!(
+            NaiveDateTime::from_timestamp_opt(-1, 999_999_000).unwrap(),
+            t.to_chrono_datetime().unwrap()
+        );
+        let t = Timestamp::new(i64::MAX, TimeUnit::Second);
+        assert!(t.to_chrono_datetime().is_none());
+    }
+
     #[test]
     pub fn test_from_i64() {
         let t: Timestamp = 42.into();
@@ -236,10 +260,11 @@ mod tests {
     // but expected timestamp is in UTC timezone
     fn check_from_str(s: &str, expect: &str) {
         let ts = Timestamp::from_str(s).unwrap();
-        let time = NaiveDateTime::from_timestamp(
+        let time = NaiveDateTime::from_timestamp_opt(
             ts.value / 1_000_000_000,
             (ts.value % 1_000_000_000) as u32,
-        );
+        )
+        .unwrap();
         assert_eq!(expect, time.to_string());
     }
 
@@ -252,7 +277,13 @@ mod tests {
         check_from_str(
             "2020-09-08 13:42:29",
             &NaiveDateTime::from_timestamp_opt(
-                1599572549 - Local.timestamp(0, 0).offset().fix().local_minus_utc() as i64,
+                1599572549
+                    - Local
+                        .timestamp_opt(0, 0)
+                        .unwrap()
+                        .offset()
+                        .fix()
+                        .local_minus_utc() as i64,
                 0,
             )
             .unwrap()
@@ -262,7 +293,13 @@ mod tests {
         check_from_str(
             "2020-09-08T13:42:29",
             &NaiveDateTime::from_timestamp_opt(
-                1599572549 - Local.timestamp(0, 0).offset().fix().local_minus_utc() as i64,
+                1599572549
+                    - Local
+                        .timestamp_opt(0, 0)
+                        .unwrap()
+                        .offset()
+                        .fix()
+                        .local_minus_utc() as i64,
                 0,
             )
             .unwrap()
@@ -272,7 +309,13 @@ mod tests {
         check_from_str(
             "2020-09-08 13:42:29.042",
             &NaiveDateTime::from_timestamp_opt(
-                1599572549 - Local.timestamp(0, 0).offset().fix().local_minus_utc() as i64,
+                1599572549
+                    - Local
+                        .timestamp_opt(0, 0)
+                        .unwrap()
+                        .offset()
+                        .fix()
+                        .local_minus_utc() as i64,
                 42000000,
             )
             .unwrap()
@@ -283,7 +326,13 @@ mod tests {
         check_from_str(
             "2020-09-08T13:42:29.042",
             &NaiveDateTime::from_timestamp_opt(
-                1599572549 - Local.timestamp(0, 0).offset().fix().local_minus_utc() as i64,
+                1599572549
+                    - Local
+                        .timestamp_opt(0, 0)
+                        .unwrap()
+                        .offset()
+                        .fix()
+                        .local_minus_utc() as i64,
                 42000000,
             )
             .unwrap()
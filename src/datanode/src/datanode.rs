@@ -12,12 +12,15 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::fmt;
 use std::sync::Arc;
+use std::time::Duration;
 
 use common_telemetry::info;
 use meta_client::MetaClientOpts;
 use serde::{Deserialize, Serialize};
 use servers::Mode;
+pub use storage::config::ReadCacheConfig;
 
 use crate::error::Result;
 use crate::instance::{Instance, InstanceRef};
@@ -27,6 +30,58 @@ use crate::server::Services;
 #[serde(tag = "type")]
 pub enum ObjectStoreConfig {
     File { data_dir: String },
+    S3(S3Config),
+    Azblob(AzblobConfig),
+}
+
+/// Config of the S3 (or S3 compatible) object store backend.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct S3Config {
+    pub bucket: String,
+    /// Root path of data in the bucket.
+    pub root: String,
+    pub access_key_id: String,
+    pub secret_access_key: String,
+    /// Custom endpoint of the service, e.g. the address of a MinIO server.
+    pub endpoint: Option<String>,
+    pub region: Option<String>,
+}
+
+/// Config of the Azure Blob object store backend.
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AzblobConfig {
+    pub container: String,
+    /// Root path of data in the container.
+    pub root: String,
+    pub account_name: String,
+    pub account_key: String,
+    /// Endpoint of the service, e.g. `https://{account_name}.blob.core.windows.net`.
+    pub endpoint: String,
+}
+
+// Credentials are omitted from the debug output as options are printed in logs.
+impl fmt::Debug for S3Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("S3Config")
+            .field("bucket", &self.bucket)
+            .field("root", &self.root)
+            .field("endpoint", &self.endpoint)
+            .field("region", &self.region)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Debug for AzblobConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AzblobConfig")
+            .field("container", &self.container)
+            .field("root", &self.root)
+            .field("account_name", &self.account_name)
+            .field("endpoint", &self.endpoint)
+            .finish_non_exhaustive()
+    }
 }
 
 impl Default for ObjectStoreConfig {
@@ -47,8 +102,13 @@ pub struct DatanodeOptions {
     pub meta_client_opts: Option<MetaClientOpts>,
     pub wal_dir: String,
     pub storage: ObjectStoreConfig,
+    /// Config of the local read cache for SST files, disabled if not set.
+    pub read_cache: Option<ReadCacheConfig>,
     pub enable_memory_catalog: bool,
     pub mode: Mode,
+    /// Queries running longer than this are cancelled, e.g. `"5m"`, no limit if not set.
+    #[serde(default, with = "humantime_serde")]
+    pub query_timeout: Option<Duration>,
 }
 
 impl Default for DatanodeOptions {
@@ -62,8 +122,10 @@ impl Default for DatanodeOptions {
             meta_client_opts: None,
             wal_dir: "/tmp/greptimedb/wal".to_string(),
             storage: ObjectStoreConfig::default(),
+            read_cache: None,
             enable_memory_catalog: false,
             mode: Mode::Standalone,
+            query_timeout: None,
         }
     }
 }
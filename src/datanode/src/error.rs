@@ -52,6 +52,13 @@ pub enum Error {
     #[snafu(display("Schema not found: {}", name))]
     SchemaNotFound { name: String, backtrace: Backtrace },
 
+    #[snafu(display("Database not found: {} in catalog: {}", database, catalog))]
+    DatabaseNotFound {
+        catalog: String,
+        database: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to create table: {}, source: {}", table_name, source))]
     CreateTable {
         table_name: String,
@@ -80,6 +87,20 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to flush table {}, source: {}", table_name, source))]
+    FlushTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to compact table {}, source: {}", table_name, source))]
+    CompactTable {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
     #[snafu(display("Table not found: {}", table_name))]
     TableNotFound { table_name: String },
 
@@ -112,6 +133,23 @@ pub enum Error {
         source: TableError,
     },
 
+    #[snafu(display(
+        "Failed to delete value from table: {}, source: {}",
+        table_name,
+        source
+    ))]
+    Delete {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: TableError,
+    },
+
+    #[snafu(display("Failed to collect record batches, source: {}", source))]
+    CollectRecords {
+        #[snafu(backtrace)]
+        source: common_recordbatch::error::Error,
+    },
+
     #[snafu(display("Failed to start server, source: {}", source))]
     StartServer {
         #[snafu(backtrace)]
@@ -304,10 +342,14 @@ impl ErrorExt for Error {
             Error::FindTable { source, .. } => source.status_code(),
             Error::CreateTable { source, .. }
             | Error::GetTable { source, .. }
-            | Error::AlterTable { source, .. } => source.status_code(),
+            | Error::AlterTable { source, .. }
+            | Error::FlushTable { source, .. }
+            | Error::CompactTable { source, .. } => source.status_code(),
             Error::DropTable { source, .. } => source.status_code(),
 
             Error::Insert { source, .. } => source.status_code(),
+            Error::Delete { source, .. } => source.status_code(),
+            Error::CollectRecords { source } => source.status_code(),
 
             Error::TableNotFound { .. } => StatusCode::TableNotFound,
             Error::ColumnNotFound { .. } => StatusCode::TableColumnNotFound,
@@ -332,6 +374,8 @@ impl ErrorExt for Error {
             | Error::ConstraintNotSupported { .. }
             | Error::ParseTimestamp { .. } => StatusCode::InvalidArguments,
 
+            Error::DatabaseNotFound { .. } => StatusCode::DatabaseNotFound,
+
             // TODO(yingwen): Further categorize http error.
             Error::StartServer { .. }
             | Error::ParseAddr { .. }
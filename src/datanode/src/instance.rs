@@ -28,8 +28,11 @@ use meta_client::MetaClientOpts;
 use mito::config::EngineConfig as TableEngineConfig;
 use mito::engine::MitoEngine;
 use object_store::layers::{LoggingLayer, MetricsLayer, RetryLayer, TracingLayer};
-use object_store::services::fs::Builder;
+use object_store::services::azblob::Builder as AzblobBuilder;
+use object_store::services::fs::Builder as FsBuilder;
+use object_store::services::s3::Builder as S3Builder;
 use object_store::{util, ObjectStore};
+use query::process::{ProcessManager, ProcessManagerRef};
 use query::query_engine::{QueryEngineFactory, QueryEngineRef};
 use servers::Mode;
 use snafu::prelude::*;
@@ -37,7 +40,7 @@ use storage::config::EngineConfig as StorageEngineConfig;
 use storage::EngineImpl;
 use table::table::TableIdProviderRef;
 
-use crate::datanode::{DatanodeOptions, ObjectStoreConfig};
+use crate::datanode::{AzblobConfig, DatanodeOptions, ObjectStoreConfig, S3Config};
 use crate::error::{
     self, CatalogSnafu, MetaClientInitSnafu, MissingMetasrvOptsSnafu, MissingNodeIdSnafu,
     NewCatalogSnafu, Result,
@@ -64,6 +67,9 @@ pub struct Instance {
     #[allow(unused)]
     pub(crate) meta_client: Option<Arc<MetaClient>>,
     pub(crate) heartbeat_task: Option<HeartbeatTask>,
+    pub(crate) process_manager: ProcessManagerRef,
+    /// Queries running longer than this are cancelled, no limit if not set.
+    pub(crate) query_timeout: Option<Duration>,
 }
 
 pub type InstanceRef = Arc<Instance>;
@@ -90,13 +96,18 @@ impl Instance {
         let table_engine = Arc::new(DefaultEngine::new(
             TableEngineConfig::default(),
             EngineImpl::new(
-                StorageEngineConfig::default(),
+                StorageEngineConfig {
+                    read_cache: opts.read_cache.clone(),
+                    ..Default::default()
+                },
                 Arc::new(log_store),
                 object_store.clone(),
             ),
             object_store,
         ));
 
+        let process_manager = Arc::new(ProcessManager::new());
+
         // create remote catalog manager
         let (catalog_manager, factory, table_id_provider) = match opts.mode {
             Mode::Standalone => {
@@ -111,9 +122,12 @@ impl Instance {
                     )
                 } else {
                     let catalog = Arc::new(
-                        catalog::local::LocalCatalogManager::try_new(table_engine.clone())
-                            .await
-                            .context(CatalogSnafu)?,
+                        catalog::local::LocalCatalogManager::new_with_process_list(
+                            table_engine.clone(),
+                            Some(process_manager.clone()),
+                        )
+                        .await
+                        .context(CatalogSnafu)?,
                     );
                     let factory = QueryEngineFactory::new(catalog.clone());
 
@@ -163,6 +177,8 @@ impl Instance {
             meta_client,
             heartbeat_task,
             table_id_provider,
+            process_manager,
+            query_timeout: opts.query_timeout,
         })
     }
 
@@ -184,13 +200,34 @@ impl Instance {
     pub fn catalog_manager(&self) -> &CatalogManagerRef {
         &self.catalog_manager
     }
+
+    pub fn process_manager(&self) -> &ProcessManagerRef {
+        &self.process_manager
+    }
 }
 
 pub(crate) async fn new_object_store(store_config: &ObjectStoreConfig) -> Result<ObjectStore> {
-    // TODO(dennis): supports other backend
-    let data_dir = util::normalize_dir(match store_config {
-        ObjectStoreConfig::File { data_dir } => data_dir,
-    });
+    let object_store = match store_config {
+        ObjectStoreConfig::File { data_dir } => new_fs_object_store(data_dir)?,
+        ObjectStoreConfig::S3(s3_config) => new_s3_object_store(s3_config)?,
+        ObjectStoreConfig::Azblob(azblob_config) => new_azblob_object_store(azblob_config)?,
+    };
+
+    let object_store = object_store
+        // Add retry
+        .layer(RetryLayer::new(ExponentialBackoff::default().with_jitter()))
+        // Add metrics
+        .layer(MetricsLayer)
+        // Add logging
+        .layer(LoggingLayer)
+        // Add tracing
+        .layer(TracingLayer);
+
+    Ok(object_store)
+}
+
+fn new_fs_object_store(data_dir: &str) -> Result<ObjectStore> {
+    let data_dir = util::normalize_dir(data_dir);
 
     fs::create_dir_all(path::Path::new(&data_dir))
         .context(error::CreateDirSnafu { dir: &data_dir })?;
@@ -199,23 +236,61 @@ pub(crate) async fn new_object_store(store_config: &ObjectStoreConfig) -> Result
 
     let atomic_write_dir = format!("{}/.tmp/", data_dir);
 
-    let accessor = Builder::default()
+    let accessor = FsBuilder::default()
         .root(&data_dir)
         .atomic_write_dir(&atomic_write_dir)
         .build()
         .context(error::InitBackendSnafu { dir: &data_dir })?;
 
-    let object_store = ObjectStore::new(accessor)
-        // Add retry
-        .layer(RetryLayer::new(ExponentialBackoff::default().with_jitter()))
-        // Add metrics
-        .layer(MetricsLayer)
-        // Add logging
-        .layer(LoggingLayer)
-        // Add tracing
-        .layer(TracingLayer);
+    Ok(ObjectStore::new(accessor))
+}
 
-    Ok(object_store)
+fn new_s3_object_store(s3_config: &S3Config) -> Result<ObjectStore> {
+    let root = util::normalize_dir(&s3_config.root);
+
+    info!(
+        "The s3 storage bucket is: {}, root is: {}",
+        s3_config.bucket, &root
+    );
+
+    let mut builder = S3Builder::default();
+    builder
+        .root(&root)
+        .bucket(&s3_config.bucket)
+        .access_key_id(&s3_config.access_key_id)
+        .secret_access_key(&s3_config.secret_access_key);
+    if let Some(endpoint) = &s3_config.endpoint {
+        builder.endpoint(endpoint);
+    }
+    if let Some(region) = &s3_config.region {
+        builder.region(region);
+    }
+
+    let accessor = builder
+        .build()
+        .context(error::InitBackendSnafu { dir: &root })?;
+
+    Ok(ObjectStore::new(accessor))
+}
+
+fn new_azblob_object_store(azblob_config: &AzblobConfig) -> Result<ObjectStore> {
+    let root = util::normalize_dir(&azblob_config.root);
+
+    info!(
+        "The azblob storage container is: {}, root is: {}",
+        azblob_config.container, &root
+    );
+
+    let accessor = AzblobBuilder::default()
+        .root(&root)
+        .container(&azblob_config.container)
+        .endpoint(&azblob_config.endpoint)
+        .account_name(&azblob_config.account_name)
+        .account_key(&azblob_config.account_key)
+        .build()
+        .context(error::InitBackendSnafu { dir: &root })?;
+
+    Ok(ObjectStore::new(accessor))
 }
 
 /// Create metasrv client instance and spawn heartbeat loop.
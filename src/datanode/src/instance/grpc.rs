@@ -25,8 +25,11 @@ use common_grpc::select::to_object_result;
 use common_grpc_expr::insertion_expr_to_request;
 use common_query::Output;
 use query::plan::LogicalPlan;
+use servers::grpc::query_context_from_header;
 use servers::query_handler::{GrpcAdminHandler, GrpcQueryHandler};
+use session::context::QueryContextRef;
 use snafu::prelude::*;
+use store_api::storage::RegionNumber;
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
 use table::requests::CreateDatabaseRequest;
 
@@ -44,6 +47,7 @@ impl Instance {
         catalog_name: &str,
         schema_name: &str,
         table_name: &str,
+        region_number: RegionNumber,
         insert_batches: Vec<(Vec<Column>, u32)>,
     ) -> Result<Output> {
         let schema_provider = self
@@ -65,6 +69,7 @@ impl Instance {
             catalog_name,
             schema_name,
             table_name,
+            region_number,
             insert_batches,
             table.clone(),
         )
@@ -83,10 +88,17 @@ impl Instance {
         catalog_name: &str,
         schema_name: &str,
         table_name: &str,
+        region_number: RegionNumber,
         insert_batches: Vec<(Vec<Column>, u32)>,
     ) -> ObjectResult {
         match self
-            .execute_grpc_insert(catalog_name, schema_name, table_name, insert_batches)
+            .execute_grpc_insert(
+                catalog_name,
+                schema_name,
+                table_name,
+                region_number,
+                insert_batches,
+            )
             .await
         {
             Ok(Output::AffectedRows(rows)) => ObjectResultBuilder::new()
@@ -102,15 +114,23 @@ impl Instance {
         }
     }
 
-    async fn handle_select(&self, select_expr: SelectExpr) -> ObjectResult {
-        let result = self.do_handle_select(select_expr).await;
+    async fn handle_select(
+        &self,
+        select_expr: SelectExpr,
+        query_ctx: QueryContextRef,
+    ) -> ObjectResult {
+        let result = self.do_handle_select(select_expr, query_ctx).await;
         to_object_result(result).await
     }
 
-    async fn do_handle_select(&self, select_expr: SelectExpr) -> Result<Output> {
+    async fn do_handle_select(
+        &self,
+        select_expr: SelectExpr,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
         let expr = select_expr.expr;
         match expr {
-            Some(select_expr::Expr::Sql(sql)) => self.execute_sql(&sql).await,
+            Some(select_expr::Expr::Sql(sql)) => self.execute_tracked_sql(&sql, query_ctx).await,
             Some(select_expr::Expr::LogicalPlan(plan)) => self.execute_logical(plan).await,
             Some(select_expr::Expr::PhysicalPlan(api::v1::PhysicalPlan { original_ql, plan })) => {
                 self.physical_planner
@@ -166,15 +186,22 @@ impl GrpcQueryHandler for Instance {
                 let catalog_name = DEFAULT_CATALOG_NAME;
                 let schema_name = &insert_expr.schema_name;
                 let table_name = &insert_expr.table_name;
-
-                // TODO(fys): _region_number is for later use.
-                let _region_number: u32 = insert_expr.region_number;
+                let region_number = insert_expr.region_number;
 
                 let insert_batches = vec![(insert_expr.columns, insert_expr.row_count)];
-                self.handle_insert(catalog_name, schema_name, table_name, insert_batches)
-                    .await
+                self.handle_insert(
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                    region_number,
+                    insert_batches,
+                )
+                .await
+            }
+            Some(object_expr::Expr::Select(select_expr)) => {
+                let query_ctx = query_context_from_header(query.header.as_ref());
+                self.handle_select(select_expr, query_ctx).await
             }
-            Some(object_expr::Expr::Select(select_expr)) => self.handle_select(select_expr).await,
             other => {
                 return servers::error::NotSupportedSnafu {
                     feat: format!("{:?}", other),
@@ -198,6 +225,12 @@ impl GrpcAdminHandler for Instance {
             Some(admin_expr::Expr::DropTable(drop_table_expr)) => {
                 self.handle_drop_table(drop_table_expr).await
             }
+            Some(admin_expr::Expr::FlushTable(flush_table_expr)) => {
+                self.handle_flush_table(flush_table_expr).await
+            }
+            Some(admin_expr::Expr::CompactTable(compact_table_expr)) => {
+                self.handle_compact_table(compact_table_expr).await
+            }
             other => {
                 return servers::error::NotSupportedSnafu {
                     feat: format!("{:?}", other),
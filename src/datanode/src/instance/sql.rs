@@ -17,21 +17,24 @@ use common_error::prelude::BoxedError;
 use common_query::Output;
 use common_telemetry::logging::{error, info};
 use common_telemetry::timer;
+use datatypes::schema::SchemaRef;
+use query::sql::show_processlist;
 use servers::query_handler::SqlQueryHandler;
+use session::context::QueryContextRef;
 use snafu::prelude::*;
 use sql::statements::statement::Statement;
 use table::requests::CreateDatabaseRequest;
 
 use crate::error::{
-    BumpTableIdSnafu, CatalogNotFoundSnafu, CatalogSnafu, ExecuteSqlSnafu, ParseSqlSnafu, Result,
-    SchemaNotFoundSnafu, TableIdProviderNotFoundSnafu,
+    BumpTableIdSnafu, CatalogNotFoundSnafu, CatalogSnafu, DatabaseNotFoundSnafu, ExecuteSqlSnafu,
+    ParseSqlSnafu, Result, SchemaNotFoundSnafu, TableIdProviderNotFoundSnafu,
 };
 use crate::instance::Instance;
 use crate::metric;
 use crate::sql::SqlRequest;
 
 impl Instance {
-    pub async fn execute_sql(&self, sql: &str) -> Result<Output> {
+    pub fn describe_sql(&self, sql: &str, query_ctx: QueryContextRef) -> Result<Option<SchemaRef>> {
         let stmt = self
             .query_engine
             .sql_to_statement(sql)
@@ -41,7 +44,43 @@ impl Instance {
             Statement::Query(_) => {
                 let logical_plan = self
                     .query_engine
-                    .statement_to_plan(stmt)
+                    .statement_to_plan(stmt, query_ctx)
+                    .context(ExecuteSqlSnafu)?;
+                let schema = logical_plan.schema().context(ExecuteSqlSnafu)?;
+                Ok(Some(schema))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// Executes the `sql` as a registered process, so it could be listed, killed or cancelled
+    /// on timeout.
+    pub async fn execute_tracked_sql(
+        &self,
+        sql: &str,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let guard = self
+            .process_manager
+            .register(sql, &query_ctx, self.query_timeout);
+        let output = guard
+            .run(self.execute_sql(sql, query_ctx))
+            .await
+            .context(ExecuteSqlSnafu)?;
+        Ok(guard.track(output?))
+    }
+
+    pub async fn execute_sql(&self, sql: &str, query_ctx: QueryContextRef) -> Result<Output> {
+        let stmt = self
+            .query_engine
+            .sql_to_statement(sql)
+            .context(ExecuteSqlSnafu)?;
+
+        match stmt {
+            Statement::Query(_) => {
+                let logical_plan = self
+                    .query_engine
+                    .statement_to_plan(stmt, query_ctx)
                     .context(ExecuteSqlSnafu)?;
 
                 self.query_engine
@@ -51,7 +90,7 @@ impl Instance {
             }
             Statement::Insert(i) => {
                 let (catalog_name, schema_name, _table_name) =
-                    i.full_table_name().context(ParseSqlSnafu)?;
+                    i.full_table_name(&query_ctx).context(ParseSqlSnafu)?;
 
                 let schema_provider = self
                     .catalog_manager
@@ -62,8 +101,15 @@ impl Instance {
                     .context(CatalogSnafu)?
                     .context(SchemaNotFoundSnafu { name: schema_name })?;
 
-                let request = self.sql_handler.insert_to_request(schema_provider, *i)?;
-                self.sql_handler.execute(request).await
+                let request =
+                    self.sql_handler
+                        .insert_to_request(schema_provider, *i, &query_ctx)?;
+                self.sql_handler.execute(request, query_ctx).await
+            }
+            Statement::Delete(d) => {
+                self.sql_handler
+                    .execute(SqlRequest::Delete(d), query_ctx)
+                    .await
             }
 
             Statement::CreateDatabase(c) => {
@@ -74,7 +120,7 @@ impl Instance {
                 info!("Creating a new database: {}", request.db_name);
 
                 self.sql_handler
-                    .execute(SqlRequest::CreateDatabase(request))
+                    .execute(SqlRequest::CreateDatabase(request), query_ctx)
                     .await
             }
 
@@ -89,7 +135,9 @@ impl Instance {
                 let _engine_name = c.engine.clone();
                 // TODO(hl): Select table engine by engine_name
 
-                let request = self.sql_handler.create_to_request(table_id, c)?;
+                let request = self
+                    .sql_handler
+                    .create_to_request(table_id, c, &query_ctx)?;
                 let catalog_name = &request.catalog_name;
                 let schema_name = &request.schema_name;
                 let table_name = &request.table_name;
@@ -100,47 +148,99 @@ impl Instance {
                 );
 
                 self.sql_handler
-                    .execute(SqlRequest::CreateTable(request))
+                    .execute(SqlRequest::CreateTable(request), query_ctx)
                     .await
             }
             Statement::Alter(alter_table) => {
-                let req = self.sql_handler.alter_to_request(alter_table)?;
-                self.sql_handler.execute(SqlRequest::Alter(req)).await
+                let req = self.sql_handler.alter_to_request(alter_table, &query_ctx)?;
+                self.sql_handler
+                    .execute(SqlRequest::Alter(req), query_ctx)
+                    .await
             }
             Statement::DropTable(drop_table) => {
-                let req = self.sql_handler.drop_table_to_request(drop_table);
-                self.sql_handler.execute(SqlRequest::DropTable(req)).await
+                let req = self
+                    .sql_handler
+                    .drop_table_to_request(drop_table, &query_ctx)?;
+                self.sql_handler
+                    .execute(SqlRequest::DropTable(req), query_ctx)
+                    .await
             }
             Statement::ShowDatabases(stmt) => {
                 self.sql_handler
-                    .execute(SqlRequest::ShowDatabases(stmt))
+                    .execute(SqlRequest::ShowDatabases(stmt), query_ctx)
                     .await
             }
             Statement::ShowTables(stmt) => {
-                self.sql_handler.execute(SqlRequest::ShowTables(stmt)).await
+                self.sql_handler
+                    .execute(SqlRequest::ShowTables(stmt), query_ctx)
+                    .await
             }
             Statement::Explain(stmt) => {
                 self.sql_handler
-                    .execute(SqlRequest::Explain(Box::new(stmt)))
+                    .execute(SqlRequest::Explain(Box::new(stmt)), query_ctx)
                     .await
             }
             Statement::DescribeTable(stmt) => {
                 self.sql_handler
-                    .execute(SqlRequest::DescribeTable(stmt))
+                    .execute(SqlRequest::DescribeTable(stmt), query_ctx)
+                    .await
+            }
+            Statement::ShowCreateTable(stmt) => {
+                self.sql_handler
+                    .execute(SqlRequest::ShowCreateTable(stmt), query_ctx)
+                    .await
+            }
+            Statement::ShowColumns(stmt) => {
+                self.sql_handler
+                    .execute(SqlRequest::ShowColumns(stmt), query_ctx)
                     .await
             }
-            Statement::ShowCreateTable(_stmt) => {
-                unimplemented!("SHOW CREATE TABLE is unimplemented yet");
+            Statement::ShowProcesslist(stmt) => {
+                show_processlist(stmt, &self.process_manager).context(ExecuteSqlSnafu)
+            }
+            Statement::Use(db) => {
+                let catalog = query_ctx.current_catalog();
+                ensure!(
+                    self.is_valid_schema(&catalog, &db)?,
+                    DatabaseNotFoundSnafu {
+                        catalog,
+                        database: db
+                    }
+                );
+                query_ctx.set_current_schema(&db);
+                Ok(Output::AffectedRows(0))
+            }
+            Statement::Kill(id) => {
+                self.process_manager
+                    .kill(id, &query_ctx)
+                    .context(ExecuteSqlSnafu)?;
+                Ok(Output::AffectedRows(0))
+            }
+            Statement::Admin(admin) => {
+                let req = self.sql_handler.admin_to_request(admin, &query_ctx)?;
+                self.sql_handler.execute(req, query_ctx).await
             }
         }
     }
+
+    fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
+        let schema = self
+            .catalog_manager
+            .schema(catalog, schema)
+            .context(CatalogSnafu)?;
+        Ok(schema.is_some())
+    }
 }
 
 #[async_trait]
 impl SqlQueryHandler for Instance {
-    async fn do_query(&self, query: &str) -> servers::error::Result<Output> {
+    async fn do_query(
+        &self,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> servers::error::Result<Output> {
         let _timer = timer!(metric::METRIC_HANDLE_SQL_ELAPSED);
-        self.execute_sql(query)
+        self.execute_tracked_sql(query, query_ctx)
             .await
             .map_err(|e| {
                 error!(e; "Instance failed to execute sql");
@@ -148,4 +248,27 @@ impl SqlQueryHandler for Instance {
             })
             .context(servers::error::ExecuteQuerySnafu { query })
     }
+
+    async fn do_describe(
+        &self,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> servers::error::Result<Option<SchemaRef>> {
+        self.describe_sql(query, query_ctx)
+            .map_err(BoxedError::new)
+            .context(servers::error::DescribeQuerySnafu { query })
+    }
+
+    fn is_valid_schema(&self, catalog: &str, schema: &str) -> servers::error::Result<bool> {
+        Instance::is_valid_schema(self, catalog, schema)
+            .map_err(BoxedError::new)
+            .context(servers::error::CheckDatabaseValiditySnafu)
+    }
+
+    fn kill_query(&self, connection_id: u32) -> servers::error::Result<()> {
+        self.process_manager
+            .kill_unchecked(connection_id)
+            .map_err(BoxedError::new)
+            .context(servers::error::KillQuerySnafu { connection_id })
+    }
 }
@@ -20,6 +20,7 @@ use common_catalog::consts::MIN_USER_TABLE_ID;
 use meta_client::client::{MetaClient, MetaClientBuilder};
 use meta_srv::mocks::MockInfo;
 use mito::config::EngineConfig as TableEngineConfig;
+use query::process::ProcessManager;
 use query::QueryEngineFactory;
 use storage::config::EngineConfig as StorageEngineConfig;
 use storage::EngineImpl;
@@ -84,6 +85,8 @@ impl Instance {
             meta_client,
             heartbeat_task,
             table_id_provider,
+            process_manager: Arc::new(ProcessManager::new()),
+            query_timeout: None,
         })
     }
 
@@ -138,6 +141,8 @@ impl Instance {
             table_id_provider: Some(Arc::new(LocalTableIdProvider::default())),
             meta_client: Some(meta_client),
             heartbeat_task: Some(heartbeat_task),
+            process_manager: Arc::new(ProcessManager::new()),
+            query_timeout: opts.query_timeout,
         })
     }
 }
@@ -62,6 +62,8 @@ impl Services {
                 Some(MysqlServer::create_server(
                     instance.clone(),
                     mysql_io_runtime,
+                    Default::default(),
+                    None,
                 ))
             }
         };
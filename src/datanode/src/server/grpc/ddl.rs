@@ -13,14 +13,17 @@
 // limitations under the License.
 
 use api::result::AdminResultBuilder;
-use api::v1::{AdminResult, AlterExpr, CreateExpr, DropTableExpr};
+use api::v1::{
+    AdminResult, AlterExpr, CompactTableExpr, CreateExpr, DropTableExpr, FlushTableExpr,
+};
 use common_error::prelude::{ErrorExt, StatusCode};
 use common_grpc_expr::{alter_expr_to_request, create_expr_to_request};
 use common_query::Output;
 use common_telemetry::{error, info};
 use futures::TryFutureExt;
+use session::context::QueryContext;
 use snafu::prelude::*;
-use table::requests::DropTableRequest;
+use table::requests::{CompactTableRequest, DropTableRequest, FlushTableRequest};
 
 use crate::error::{AlterExprToRequestSnafu, BumpTableIdSnafu, CreateExprToRequestSnafu};
 use crate::instance::Instance;
@@ -72,7 +75,10 @@ impl Instance {
 
         let request = create_expr_to_request(table_id, expr).context(CreateExprToRequestSnafu);
         let result = futures::future::ready(request)
-            .and_then(|request| self.sql_handler().execute(SqlRequest::CreateTable(request)))
+            .and_then(|request| {
+                self.sql_handler()
+                    .execute(SqlRequest::CreateTable(request), QueryContext::arc())
+            })
             .await;
         match result {
             Ok(Output::AffectedRows(rows)) => AdminResultBuilder::default()
@@ -103,7 +109,10 @@ impl Instance {
         };
 
         let result = futures::future::ready(request)
-            .and_then(|request| self.sql_handler().execute(SqlRequest::Alter(request)))
+            .and_then(|request| {
+                self.sql_handler()
+                    .execute(SqlRequest::Alter(request), QueryContext::arc())
+            })
             .await;
         match result {
             Ok(Output::AffectedRows(rows)) => AdminResultBuilder::default()
@@ -124,7 +133,34 @@ impl Instance {
             schema_name: expr.schema_name,
             table_name: expr.table_name,
         };
-        let result = self.sql_handler().execute(SqlRequest::DropTable(req)).await;
+        self.execute_admin_request(SqlRequest::DropTable(req)).await
+    }
+
+    pub(crate) async fn handle_flush_table(&self, expr: FlushTableExpr) -> AdminResult {
+        let req = FlushTableRequest {
+            catalog_name: expr.catalog_name,
+            schema_name: expr.schema_name,
+            table_name: expr.table_name,
+        };
+        self.execute_admin_request(SqlRequest::FlushTable(req))
+            .await
+    }
+
+    pub(crate) async fn handle_compact_table(&self, expr: CompactTableExpr) -> AdminResult {
+        let req = CompactTableRequest {
+            catalog_name: expr.catalog_name,
+            schema_name: expr.schema_name,
+            table_name: expr.table_name,
+        };
+        self.execute_admin_request(SqlRequest::CompactTable(req))
+            .await
+    }
+
+    async fn execute_admin_request(&self, request: SqlRequest) -> AdminResult {
+        let result = self
+            .sql_handler()
+            .execute(request, QueryContext::arc())
+            .await;
         match result {
             Ok(Output::AffectedRows(rows)) => AdminResultBuilder::default()
                 .status_code(StatusCode::Success as u32)
@@ -18,32 +18,43 @@ use catalog::CatalogManagerRef;
 use common_query::Output;
 use common_telemetry::error;
 use query::query_engine::QueryEngineRef;
-use query::sql::{describe_table, explain, show_databases, show_tables};
+use query::sql::{
+    describe_table, explain, show_columns, show_create_table, show_databases, show_tables,
+};
+use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt};
+use sql::statements::delete::Delete;
 use sql::statements::describe::DescribeTable;
 use sql::statements::explain::Explain;
-use sql::statements::show::{ShowDatabases, ShowTables};
+use sql::statements::show::{ShowColumns, ShowCreateTable, ShowDatabases, ShowTables};
 use table::engine::{EngineContext, TableEngineRef, TableReference};
 use table::requests::*;
 use table::TableRef;
 
 use crate::error::{ExecuteSqlSnafu, GetTableSnafu, Result, TableNotFoundSnafu};
 
+mod admin;
 mod alter;
 mod create;
+mod delete;
 mod drop_table;
 mod insert;
 
 #[derive(Debug)]
 pub enum SqlRequest {
     Insert(InsertRequest),
+    Delete(Box<Delete>),
     CreateTable(CreateTableRequest),
     CreateDatabase(CreateDatabaseRequest),
     Alter(AlterTableRequest),
     DropTable(DropTableRequest),
+    FlushTable(FlushTableRequest),
+    CompactTable(CompactTableRequest),
     ShowDatabases(ShowDatabases),
     ShowTables(ShowTables),
     DescribeTable(DescribeTable),
+    ShowCreateTable(ShowCreateTable),
+    ShowColumns(ShowColumns),
     Explain(Box<Explain>),
 }
 
@@ -67,23 +78,35 @@ impl SqlHandler {
         }
     }
 
-    pub async fn execute(&self, request: SqlRequest) -> Result<Output> {
+    pub async fn execute(&self, request: SqlRequest, query_ctx: QueryContextRef) -> Result<Output> {
         let result = match request {
             SqlRequest::Insert(req) => self.insert(req).await,
+            SqlRequest::Delete(stmt) => self.delete(*stmt, query_ctx).await,
             SqlRequest::CreateTable(req) => self.create_table(req).await,
             SqlRequest::CreateDatabase(req) => self.create_database(req).await,
             SqlRequest::Alter(req) => self.alter(req).await,
             SqlRequest::DropTable(req) => self.drop_table(req).await,
+            SqlRequest::FlushTable(req) => self.flush_table(req).await,
+            SqlRequest::CompactTable(req) => self.compact_table(req).await,
             SqlRequest::ShowDatabases(stmt) => {
-                show_databases(stmt, self.catalog_manager.clone()).context(ExecuteSqlSnafu)
+                show_databases(stmt, self.catalog_manager.clone(), query_ctx)
+                    .context(ExecuteSqlSnafu)
             }
             SqlRequest::ShowTables(stmt) => {
-                show_tables(stmt, self.catalog_manager.clone()).context(ExecuteSqlSnafu)
+                show_tables(stmt, self.catalog_manager.clone(), query_ctx).context(ExecuteSqlSnafu)
             }
             SqlRequest::DescribeTable(stmt) => {
-                describe_table(stmt, self.catalog_manager.clone()).context(ExecuteSqlSnafu)
+                describe_table(stmt, self.catalog_manager.clone(), query_ctx)
+                    .context(ExecuteSqlSnafu)
             }
-            SqlRequest::Explain(stmt) => explain(stmt, self.query_engine.clone())
+            SqlRequest::ShowCreateTable(stmt) => {
+                show_create_table(stmt, self.catalog_manager.clone(), query_ctx)
+                    .context(ExecuteSqlSnafu)
+            }
+            SqlRequest::ShowColumns(stmt) => {
+                show_columns(stmt, self.catalog_manager.clone(), query_ctx).context(ExecuteSqlSnafu)
+            }
+            SqlRequest::Explain(stmt) => explain(stmt, self.query_engine.clone(), query_ctx)
                 .await
                 .context(ExecuteSqlSnafu),
         };
@@ -127,6 +150,7 @@ mod tests {
     use object_store::services::fs::Builder;
     use object_store::ObjectStore;
     use query::QueryEngineFactory;
+    use session::context::QueryContext;
     use sql::statements::statement::Statement;
     use storage::config::EngineConfig as StorageEngineConfig;
     use storage::EngineImpl;
@@ -246,7 +270,7 @@ mod tests {
         };
         let schema_provider = Arc::new(MockSchemaProvider {});
         let request = sql_handler
-            .insert_to_request(schema_provider, *stmt)
+            .insert_to_request(schema_provider, *stmt, &QueryContext::new())
             .unwrap();
 
         match request {
@@ -0,0 +1,82 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_query::Output;
+use common_telemetry::info;
+use session::context::QueryContext;
+use snafu::ResultExt;
+use sql::statements::admin::Admin;
+use sql::statements::table_idents_to_full_name;
+use table::engine::TableReference;
+use table::requests::{CompactTableRequest, FlushTableRequest};
+
+use crate::error::{self, Result};
+use crate::sql::{SqlHandler, SqlRequest};
+
+impl SqlHandler {
+    pub(crate) async fn flush_table(&self, req: FlushTableRequest) -> Result<Output> {
+        let table_ref = TableReference {
+            catalog: &req.catalog_name,
+            schema: &req.schema_name,
+            table: &req.table_name,
+        };
+        let table = self.get_table(&table_ref)?;
+        table.flush().await.context(error::FlushTableSnafu {
+            table_name: table_ref.to_string(),
+        })?;
+
+        info!("Successfully flushed table: {}", table_ref);
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub(crate) async fn compact_table(&self, req: CompactTableRequest) -> Result<Output> {
+        let table_ref = TableReference {
+            catalog: &req.catalog_name,
+            schema: &req.schema_name,
+            table: &req.table_name,
+        };
+        let table = self.get_table(&table_ref)?;
+        table.compact().await.context(error::CompactTableSnafu {
+            table_name: table_ref.to_string(),
+        })?;
+
+        info!("Successfully compacted table: {}", table_ref);
+
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub(crate) fn admin_to_request(
+        &self,
+        admin: Admin,
+        query_ctx: &QueryContext,
+    ) -> Result<SqlRequest> {
+        let (catalog_name, schema_name, table_name) =
+            table_idents_to_full_name(admin.table_name(), query_ctx)
+                .context(error::ParseSqlSnafu)?;
+        let request = match admin {
+            Admin::FlushTable(_) => SqlRequest::FlushTable(FlushTableRequest {
+                catalog_name,
+                schema_name,
+                table_name,
+            }),
+            Admin::CompactTable(_) => SqlRequest::CompactTable(CompactTableRequest {
+                catalog_name,
+                schema_name,
+                table_name,
+            }),
+        };
+        Ok(request)
+    }
+}
@@ -14,9 +14,12 @@
 
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
+use session::context::QueryContext;
 use snafu::prelude::*;
 use sql::statements::alter::{AlterTable, AlterTableOperation};
-use sql::statements::{column_def_to_schema, table_idents_to_full_name};
+use sql::statements::{
+    column_def_to_schema, sql_options_to_table_options, table_idents_to_full_name,
+};
 use table::engine::{EngineContext, TableReference};
 use table::requests::{AddColumnRequest, AlterKind, AlterTableRequest};
 
@@ -53,9 +56,14 @@ impl SqlHandler {
         Ok(Output::AffectedRows(0))
     }
 
-    pub(crate) fn alter_to_request(&self, alter_table: AlterTable) -> Result<AlterTableRequest> {
+    pub(crate) fn alter_to_request(
+        &self,
+        alter_table: AlterTable,
+        query_ctx: &QueryContext,
+    ) -> Result<AlterTableRequest> {
         let (catalog_name, schema_name, table_name) =
-            table_idents_to_full_name(alter_table.table_name()).context(error::ParseSqlSnafu)?;
+            table_idents_to_full_name(alter_table.table_name(), query_ctx)
+                .context(error::ParseSqlSnafu)?;
 
         let alter_kind = match alter_table.alter_operation() {
             AlterTableOperation::AddConstraint(table_constraint) => {
@@ -75,6 +83,9 @@ impl SqlHandler {
             AlterTableOperation::DropColumn { name } => AlterKind::DropColumns {
                 names: vec![name.value.clone()],
             },
+            AlterTableOperation::SetOptions { options } => AlterKind::SetOptions {
+                options: sql_options_to_table_options(options),
+            },
         };
         Ok(AlterTableRequest {
             catalog_name: Some(catalog_name),
@@ -112,7 +123,9 @@ mod tests {
     async fn test_alter_to_request_with_adding_column() {
         let handler = create_mock_sql_handler().await;
         let alter_table = parse_sql("ALTER TABLE my_metric_1 ADD tagk_i STRING Null;");
-        let req = handler.alter_to_request(alter_table).unwrap();
+        let req = handler
+            .alter_to_request(alter_table, &QueryContext::new())
+            .unwrap();
         assert_eq!(req.catalog_name, Some("greptime".to_string()));
         assert_eq!(req.schema_name, Some("public".to_string()));
         assert_eq!(req.table_name, "my_metric_1");
@@ -130,4 +143,24 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[tokio::test]
+    async fn test_alter_to_request_with_setting_options() {
+        let handler = create_mock_sql_handler().await;
+        let alter_table = parse_sql("ALTER TABLE my_metric_1 SET (TTL = '7d');");
+        let req = handler
+            .alter_to_request(alter_table, &QueryContext::new())
+            .unwrap();
+        assert_eq!(req.table_name, "my_metric_1");
+
+        let alter_kind = req.alter_kind;
+        assert_matches!(alter_kind, AlterKind::SetOptions { .. });
+        match alter_kind {
+            AlterKind::SetOptions { options } => {
+                assert_eq!(1, options.len());
+                assert_eq!("7d", options["ttl"]);
+            }
+            _ => unreachable!(),
+        }
+    }
 }
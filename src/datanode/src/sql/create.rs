@@ -21,10 +21,13 @@ use common_query::Output;
 use common_telemetry::tracing::info;
 use common_telemetry::tracing::log::error;
 use datatypes::schema::SchemaBuilder;
+use session::context::QueryContext;
 use snafu::{ensure, OptionExt, ResultExt};
 use sql::ast::TableConstraint;
 use sql::statements::create::CreateTable;
-use sql::statements::{column_def_to_schema, table_idents_to_full_name};
+use sql::statements::{
+    column_def_to_schema, sql_options_to_table_options, table_idents_to_full_name,
+};
 use store_api::storage::consts::TIME_INDEX_NAME;
 use table::engine::EngineContext;
 use table::metadata::TableId;
@@ -114,12 +117,13 @@ impl SqlHandler {
         &self,
         table_id: TableId,
         stmt: CreateTable,
+        query_ctx: &QueryContext,
     ) -> Result<CreateTableRequest> {
         let mut ts_index = usize::MAX;
         let mut primary_keys = vec![];
 
         let (catalog_name, schema_name, table_name) =
-            table_idents_to_full_name(&stmt.name).context(error::ParseSqlSnafu)?;
+            table_idents_to_full_name(&stmt.name, query_ctx).context(error::ParseSqlSnafu)?;
 
         let col_map = stmt
             .columns
@@ -219,7 +223,7 @@ impl SqlHandler {
             region_numbers: vec![0],
             primary_key_indices: primary_keys,
             create_if_not_exists: stmt.if_not_exists,
-            table_options: HashMap::new(),
+            table_options: sql_options_to_table_options(&stmt.options),
         };
         Ok(request)
     }
@@ -259,15 +263,18 @@ mod tests {
                        cpu double default 0,
                        memory double,
                        TIME INDEX (ts),
-                       PRIMARY KEY(host)) engine=mito with(regions=1);"#,
+                       PRIMARY KEY(host)) engine=mito with(regions=1, ttl='7d');"#,
         );
-        let c = handler.create_to_request(42, parsed_stmt).unwrap();
+        let c = handler
+            .create_to_request(42, parsed_stmt, &QueryContext::new())
+            .unwrap();
         assert_eq!("demo_table", c.table_name);
         assert_eq!(42, c.id);
         assert!(!c.create_if_not_exists);
         assert_eq!(vec![0], c.primary_key_indices);
         assert_eq!(1, c.schema.timestamp_index().unwrap());
         assert_eq!(4, c.schema.column_schemas().len());
+        assert_eq!("7d", c.table_options["ttl"]);
     }
 
     /// Time index not specified in sql
@@ -282,7 +289,9 @@ mod tests {
                       memory double,
                       PRIMARY KEY(host)) engine=mito with(regions=1);"#,
         );
-        let error = handler.create_to_request(42, parsed_stmt).unwrap_err();
+        let error = handler
+            .create_to_request(42, parsed_stmt, &QueryContext::new())
+            .unwrap_err();
         assert_matches!(error, Error::MissingTimestampColumn { .. });
     }
 
@@ -299,7 +308,9 @@ mod tests {
                       memory double,
                       TIME INDEX (ts)) engine=mito with(regions=1);"#,
         );
-        let c = handler.create_to_request(42, parsed_stmt).unwrap();
+        let c = handler
+            .create_to_request(42, parsed_stmt, &QueryContext::new())
+            .unwrap();
         assert_eq!(1, c.primary_key_indices.len());
         assert_eq!(
             c.schema.timestamp_index().unwrap(),
@@ -318,7 +329,9 @@ mod tests {
                 TIME INDEX (ts)) engine=mito with(regions=1);"#,
         );
 
-        let error = handler.create_to_request(42, parsed_stmt).unwrap_err();
+        let error = handler
+            .create_to_request(42, parsed_stmt, &QueryContext::new())
+            .unwrap_err();
         assert_matches!(error, Error::KeyColumnNotFound { .. });
     }
 
@@ -338,7 +351,9 @@ mod tests {
 
         let handler = create_mock_sql_handler().await;
 
-        let error = handler.create_to_request(42, create_table).unwrap_err();
+        let error = handler
+            .create_to_request(42, create_table, &QueryContext::new())
+            .unwrap_err();
         assert_matches!(error, Error::InvalidPrimaryKey { .. });
     }
 
@@ -358,7 +373,9 @@ mod tests {
 
         let handler = create_mock_sql_handler().await;
 
-        let request = handler.create_to_request(42, create_table).unwrap();
+        let request = handler
+            .create_to_request(42, create_table, &QueryContext::new())
+            .unwrap();
 
         assert_eq!(42, request.id);
         assert_eq!("c".to_string(), request.catalog_name);
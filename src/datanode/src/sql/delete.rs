@@ -0,0 +1,139 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::HashMap;
+
+use common_query::Output;
+use common_recordbatch::{util, RecordBatch};
+use datatypes::vectors::{Helper, VectorRef};
+use session::context::QueryContextRef;
+use snafu::ResultExt;
+use sql::ast::{Expr, Ident};
+use sql::statements::delete::Delete;
+use table::engine::TableReference;
+use table::requests::DeleteRequest;
+use table::TableRef;
+
+use crate::error::{
+    CollectRecordsSnafu, DeleteSnafu, ExecuteSqlSnafu, ParseSqlSnafu, Result,
+    VectorComputationSnafu,
+};
+use crate::sql::SqlHandler;
+
+impl SqlHandler {
+    /// Deletes rows matching the `WHERE` condition of the `DELETE` statement.
+    ///
+    /// We first query row keys of all rows to delete, then write a tombstone for
+    /// each row key to the table.
+    pub(crate) async fn delete(&self, stmt: Delete, query_ctx: QueryContextRef) -> Result<Output> {
+        let (catalog_name, schema_name, table_name) =
+            stmt.full_table_name(&query_ctx).context(ParseSqlSnafu)?;
+        let table_ref = TableReference {
+            catalog: &catalog_name,
+            schema: &schema_name,
+            table: &table_name,
+        };
+        let table = self.get_table(&table_ref)?;
+
+        let key_columns = row_key_column_names(&table);
+        let sql = select_row_keys_sql(&table_ref, &key_columns, stmt.selection());
+        let stmt = self
+            .query_engine
+            .sql_to_statement(&sql)
+            .context(ExecuteSqlSnafu)?;
+        let plan = self
+            .query_engine
+            .statement_to_plan(stmt, query_ctx)
+            .context(ExecuteSqlSnafu)?;
+        let batches = match self
+            .query_engine
+            .execute(&plan)
+            .await
+            .context(ExecuteSqlSnafu)?
+        {
+            Output::Stream(stream) => util::collect(stream).await.context(CollectRecordsSnafu)?,
+            Output::RecordBatches(batches) => batches.take(),
+            Output::AffectedRows(_) => unreachable!(),
+        };
+
+        let mut affected_rows = 0;
+        for batch in batches {
+            if batch.num_rows() == 0 {
+                continue;
+            }
+
+            let request = DeleteRequest {
+                key_column_values: batch_to_key_column_values(&key_columns, &batch)?,
+            };
+            affected_rows += table.delete(request).await.with_context(|_| DeleteSnafu {
+                table_name: table_ref.to_string(),
+            })?;
+        }
+
+        Ok(Output::AffectedRows(affected_rows))
+    }
+}
+
+/// Returns names of columns that identify a row, which are the primary key columns and
+/// the timestamp column.
+fn row_key_column_names(table: &TableRef) -> Vec<String> {
+    let table_info = table.table_info();
+    let mut names: Vec<_> = table_info.meta.row_key_column_names().cloned().collect();
+    if let Some(ts_column) = table.schema().timestamp_column() {
+        if !names.contains(&ts_column.name) {
+            names.push(ts_column.name.clone());
+        }
+    }
+    names
+}
+
+fn select_row_keys_sql(
+    table_ref: &TableReference,
+    key_columns: &[String],
+    selection: Option<&Expr>,
+) -> String {
+    let projection = key_columns
+        .iter()
+        .map(|name| quote_ident(name))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let mut sql = format!(
+        "SELECT {} FROM {}.{}.{}",
+        projection,
+        quote_ident(table_ref.catalog),
+        quote_ident(table_ref.schema),
+        quote_ident(table_ref.table),
+    );
+    if let Some(selection) = selection {
+        sql.push_str(&format!(" WHERE {}", selection));
+    }
+    sql
+}
+
+fn quote_ident(name: &str) -> String {
+    Ident::with_quote('"', name).to_string()
+}
+
+fn batch_to_key_column_values(
+    key_columns: &[String],
+    batch: &RecordBatch,
+) -> Result<HashMap<String, VectorRef>> {
+    key_columns
+        .iter()
+        .zip(batch.df_recordbatch.columns())
+        .map(|(name, array)| {
+            let vector = Helper::try_into_vector(array.clone()).context(VectorComputationSnafu)?;
+            Ok((name.clone(), vector))
+        })
+        .collect()
+}
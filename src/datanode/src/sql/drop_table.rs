@@ -16,8 +16,10 @@ use catalog::DeregisterTableRequest;
 use common_error::prelude::BoxedError;
 use common_query::Output;
 use common_telemetry::info;
+use session::context::QueryContext;
 use snafu::ResultExt;
 use sql::statements::drop::DropTable;
+use sql::statements::table_idents_to_full_name;
 use table::engine::{EngineContext, TableReference};
 use table::requests::DropTableRequest;
 
@@ -61,11 +63,18 @@ impl SqlHandler {
         Ok(Output::AffectedRows(1))
     }
 
-    pub fn drop_table_to_request(&self, drop_table: DropTable) -> DropTableRequest {
-        DropTableRequest {
-            catalog_name: drop_table.catalog_name,
-            schema_name: drop_table.schema_name,
-            table_name: drop_table.table_name,
-        }
+    pub fn drop_table_to_request(
+        &self,
+        drop_table: DropTable,
+        query_ctx: &QueryContext,
+    ) -> Result<DropTableRequest> {
+        let (catalog_name, schema_name, table_name) =
+            table_idents_to_full_name(drop_table.table_name(), query_ctx)
+                .context(error::ParseSqlSnafu)?;
+        Ok(DropTableRequest {
+            catalog_name,
+            schema_name,
+            table_name,
+        })
     }
 }
@@ -15,6 +15,7 @@
 use catalog::SchemaProviderRef;
 use common_query::Output;
 use datatypes::prelude::{ConcreteDataType, VectorBuilder};
+use session::context::QueryContext;
 use snafu::{ensure, OptionExt, ResultExt};
 use sql::ast::Value as SqlValue;
 use sql::statements::insert::Insert;
@@ -51,11 +52,12 @@ impl SqlHandler {
         &self,
         schema_provider: SchemaProviderRef,
         stmt: Insert,
+        query_ctx: &QueryContext,
     ) -> Result<SqlRequest> {
         let columns = stmt.columns();
         let values = stmt.values().context(ParseSqlValueSnafu)?;
         let (catalog_name, schema_name, table_name) =
-            stmt.full_table_name().context(ParseSqlSnafu)?;
+            stmt.full_table_name(query_ctx).context(ParseSqlSnafu)?;
 
         let table = schema_provider
             .table(&table_name)
@@ -126,6 +128,7 @@ impl SqlHandler {
                 .into_iter()
                 .map(|(c, _, mut b)| (c.to_owned(), b.finish()))
                 .collect(),
+            region_number: 0,
         }))
     }
 }
@@ -65,6 +65,7 @@ async fn setup_grpc_server(
         grpc_options: Some(GrpcOptions {
             addr: fe_grpc_addr.clone(),
             runtime_size: 8,
+            ..Default::default()
         }),
         ..Default::default()
     };
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
 use common_recordbatch::util;
 use datafusion::arrow_print;
@@ -19,6 +20,7 @@ use datafusion_common::record_batch::RecordBatch as DfRecordBatch;
 use datatypes::arrow::array::{Int64Array, UInt64Array, Utf8Array};
 use datatypes::arrow_array::StringArray;
 use datatypes::prelude::ConcreteDataType;
+use session::context::QueryContext;
 
 use crate::instance::Instance;
 use crate::tests::test_util;
@@ -32,7 +34,10 @@ async fn test_create_database_and_insert_query() {
     let instance = Instance::with_mock_meta_client(&opts).await.unwrap();
     instance.start().await.unwrap();
 
-    let output = instance.execute_sql("create database test").await.unwrap();
+    let output = instance
+        .execute_sql("create database test", QueryContext::arc())
+        .await
+        .unwrap();
     assert!(matches!(output, Output::AffectedRows(1)));
 
     let output = instance
@@ -44,6 +49,7 @@ async fn test_create_database_and_insert_query() {
              ts bigint,
              TIME INDEX(ts)
 )"#,
+            QueryContext::arc(),
         )
         .await
         .unwrap();
@@ -55,13 +61,14 @@ async fn test_create_database_and_insert_query() {
                            ('host1', 66.6, 1024, 1655276557000),
                            ('host2', 88.8,  333.3, 1655276558000)
                            "#,
+            QueryContext::arc(),
         )
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(2)));
 
     let query_output = instance
-        .execute_sql("select ts from test.demo order by ts")
+        .execute_sql("select ts from test.demo order by ts", QueryContext::arc())
         .await
         .unwrap();
 
@@ -88,9 +95,15 @@ async fn test_issue477_same_table_name_in_different_databases() {
     instance.start().await.unwrap();
 
     // Create database a and b
-    let output = instance.execute_sql("create database a").await.unwrap();
+    let output = instance
+        .execute_sql("create database a", QueryContext::arc())
+        .await
+        .unwrap();
     assert!(matches!(output, Output::AffectedRows(1)));
-    let output = instance.execute_sql("create database b").await.unwrap();
+    let output = instance
+        .execute_sql("create database b", QueryContext::arc())
+        .await
+        .unwrap();
     assert!(matches!(output, Output::AffectedRows(1)));
 
     // Create table a.demo and b.demo
@@ -101,6 +114,7 @@ async fn test_issue477_same_table_name_in_different_databases() {
              ts bigint,
              TIME INDEX(ts)
 )"#,
+            QueryContext::arc(),
         )
         .await
         .unwrap();
@@ -113,6 +127,7 @@ async fn test_issue477_same_table_name_in_different_databases() {
              ts bigint,
              TIME INDEX(ts)
 )"#,
+            QueryContext::arc(),
         )
         .await
         .unwrap();
@@ -124,6 +139,7 @@ async fn test_issue477_same_table_name_in_different_databases() {
             r#"insert into a.demo(host, ts) values
                            ('host1', 1655276557000)
                            "#,
+            QueryContext::arc(),
         )
         .await
         .unwrap();
@@ -133,6 +149,7 @@ async fn test_issue477_same_table_name_in_different_databases() {
             r#"insert into b.demo(host, ts) values
                            ('host2',1655276558000)
                            "#,
+            QueryContext::arc(),
         )
         .await
         .unwrap();
@@ -156,8 +173,174 @@ async fn test_issue477_same_table_name_in_different_databases() {
     .await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_use_database() {
+    common_telemetry::init_default_ut_logging();
+
+    let (opts, _guard) = test_util::create_tmp_dir_and_datanode_opts("use_database");
+    let instance = Instance::with_mock_meta_client(&opts).await.unwrap();
+    instance.start().await.unwrap();
+
+    let query_ctx = QueryContext::arc();
+    assert!(instance
+        .execute_sql("use db1", query_ctx.clone())
+        .await
+        .is_err());
+    assert_eq!(DEFAULT_SCHEMA_NAME, query_ctx.current_schema());
+
+    let output = instance
+        .execute_sql("create database db1", QueryContext::arc())
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let output = instance
+        .execute_sql("use db1", query_ctx.clone())
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(0)));
+    assert_eq!("db1", query_ctx.current_schema());
+
+    // Unqualified table names are resolved against the current database.
+    let output = instance
+        .execute_sql(
+            r#"create table demo(
+             host STRING,
+             ts bigint,
+             TIME INDEX(ts)
+)"#,
+            query_ctx.clone(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let output = instance
+        .execute_sql(
+            "insert into demo(host, ts) values ('host1', 1655276557000)",
+            query_ctx.clone(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    assert_query_result(
+        &instance,
+        "select host,ts from db1.demo order by ts",
+        1655276557000,
+        "host1",
+    )
+    .await;
+
+    let output = instance
+        .execute_sql("select host, ts from demo order by ts", query_ctx.clone())
+        .await
+        .unwrap();
+    match output {
+        Output::Stream(s) => {
+            let batches = util::collect(s).await.unwrap();
+            assert_eq!(1, batches[0].df_recordbatch.num_rows());
+        }
+        _ => unreachable!(),
+    }
+
+    let output = instance
+        .execute_sql("show tables", query_ctx.clone())
+        .await
+        .unwrap();
+    match output {
+        Output::RecordBatches(batches) => {
+            let pretty_print = batches.pretty_print();
+            assert!(pretty_print.contains("demo"), "{}", pretty_print);
+        }
+        _ => unreachable!(),
+    }
+
+    // The default database doesn't contain the table.
+    assert!(instance
+        .execute_sql("select host, ts from demo", QueryContext::arc())
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_kill_query() {
+    common_telemetry::init_default_ut_logging();
+
+    let (opts, _guard) = test_util::create_tmp_dir_and_datanode_opts("kill_query");
+    let instance = Instance::with_mock_meta_client(&opts).await.unwrap();
+    instance.start().await.unwrap();
+
+    // Pretends a query is running on another connection.
+    let query_ctx = QueryContext::arc();
+    let guard = instance
+        .process_manager()
+        .register("select * from numbers", &query_ctx, None);
+    let processes = instance.process_manager().list();
+    assert_eq!(1, processes.len());
+    assert_eq!(query_ctx.connection_id(), processes[0].id);
+
+    let output = instance
+        .execute_tracked_sql(
+            &format!("KILL QUERY {}", query_ctx.connection_id()),
+            QueryContext::arc(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(0)));
+    assert!(guard.token().is_cancelled());
+
+    drop(guard);
+    assert!(instance.process_manager().list().is_empty());
+    assert!(instance
+        .execute_sql(
+            &format!("KILL {}", query_ctx.connection_id()),
+            QueryContext::arc()
+        )
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_show_processlist() {
+    common_telemetry::init_default_ut_logging();
+
+    let (opts, _guard) = test_util::create_tmp_dir_and_datanode_opts("show_processlist");
+    let instance = Instance::with_mock_meta_client(&opts).await.unwrap();
+    instance.start().await.unwrap();
+
+    // Pretends a query is running on another connection.
+    let query_ctx = QueryContext::arc();
+    let _process_guard =
+        instance
+            .process_manager()
+            .register("select * from numbers", &query_ctx, None);
+
+    let output = instance
+        .execute_tracked_sql("show full processlist", QueryContext::arc())
+        .await
+        .unwrap();
+    match output {
+        Output::RecordBatches(recordbatches) => {
+            let recordbatches = recordbatches.take();
+            let columns = recordbatches[0].df_recordbatch.columns();
+            assert_eq!(7, columns.len());
+
+            // The running query and the `SHOW PROCESSLIST` itself.
+            let queries = columns[6].as_any().downcast_ref::<StringArray>().unwrap();
+            assert_eq!(2, queries.len());
+            assert_eq!("select * from numbers", queries.value(0));
+            assert_eq!("show full processlist", queries.value(1));
+        }
+        _ => unreachable!(),
+    }
+}
+
 async fn assert_query_result(instance: &Instance, sql: &str, ts: i64, host: &str) {
-    let query_output = instance.execute_sql(sql).await.unwrap();
+    let query_output = instance
+        .execute_sql(sql, QueryContext::arc())
+        .await
+        .unwrap();
     match query_output {
         Output::Stream(s) => {
             let batches = util::collect(s).await.unwrap();
@@ -206,10 +389,145 @@ async fn test_execute_insert() {
                            ('host1', 66.6, 1024, 1655276557000),
                            ('host2', 88.8,  333.3, 1655276558000)
                            "#,
+            QueryContext::arc(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(2)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_delete() {
+    let instance = setup_test_instance().await;
+    let output = instance
+        .execute_sql(
+            r#"insert into demo(host, cpu, memory, ts) values
+                           ('host1', 66.6, 1024, 1655276557000),
+                           ('host2', 88.8,  333.3, 1655276558000),
+                           ('host3', 99.9,  444.4, 1655276559000)
+                           "#,
+            QueryContext::arc(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(3)));
+
+    let output = instance
+        .execute_sql(
+            "delete from demo where host = 'host1' or cpu > 90",
+            QueryContext::arc(),
         )
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(2)));
+
+    // Delete rows not exist.
+    let output = instance
+        .execute_sql("delete from demo where host = 'host4'", QueryContext::arc())
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let query_output = instance
+        .execute_sql("select host from demo order by ts", QueryContext::arc())
+        .await
+        .unwrap();
+    match query_output {
+        Output::Stream(s) => {
+            let batches = util::collect(s).await.unwrap();
+            let columns = batches[0].df_recordbatch.columns();
+            assert_eq!(1, columns.len());
+            assert_eq!(
+                &Utf8Array::<i32>::from_slice(&["host2"]),
+                columns[0]
+                    .as_any()
+                    .downcast_ref::<Utf8Array<i32>>()
+                    .unwrap()
+            );
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_drop_table() {
+    let instance = setup_test_instance().await;
+    let output = instance
+        .execute_sql(
+            r#"insert into demo(host, cpu, memory, ts) values
+                           ('host1', 66.6, 1024, 1655276557000)
+                           "#,
+            QueryContext::arc(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let output = instance
+        .execute_sql("drop table demo", QueryContext::arc())
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    // The table is dropped, so we can't query it anymore.
+    assert!(instance
+        .execute_sql("select * from demo", QueryContext::arc())
+        .await
+        .is_err());
+    assert!(instance
+        .catalog_manager()
+        .table(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME, "demo")
+        .unwrap()
+        .is_none());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_admin_flush_and_compact() {
+    let instance = setup_test_instance().await;
+    for ts in [1655276557000i64, 1655276558000] {
+        let sql = format!(
+            "insert into demo(host, cpu, memory, ts) values ('host1', 66.6, 1024, {})",
+            ts
+        );
+        let output = instance
+            .execute_sql(&sql, QueryContext::arc())
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        let output = instance
+            .execute_sql("admin flush table demo", QueryContext::arc())
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+    }
+
+    let output = instance
+        .execute_sql("admin compact table demo", QueryContext::arc())
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(0)));
+
+    let output = instance
+        .execute_sql("select count(*) from demo", QueryContext::arc())
+        .await
+        .unwrap();
+    match output {
+        Output::Stream(s) => {
+            let batches = util::collect(s).await.unwrap();
+            let columns = batches[0].df_recordbatch.columns();
+            assert_eq!(
+                &UInt64Array::from_slice(&[2]),
+                columns[0].as_any().downcast_ref::<UInt64Array>().unwrap()
+            );
+        }
+        _ => unreachable!(),
+    }
+
+    assert!(instance
+        .execute_sql("admin flush table not_exist", QueryContext::arc())
+        .await
+        .is_err());
 }
 
 #[tokio::test(flavor = "multi_thread")]
@@ -234,13 +552,14 @@ async fn test_execute_insert_query_with_i64_timestamp() {
                            ('host1', 66.6, 1024, 1655276557000),
                            ('host2', 88.8,  333.3, 1655276558000)
                            "#,
+            QueryContext::arc(),
         )
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(2)));
 
     let query_output = instance
-        .execute_sql("select ts from demo order by ts")
+        .execute_sql("select ts from demo order by ts", QueryContext::arc())
         .await
         .unwrap();
 
@@ -258,7 +577,10 @@ async fn test_execute_insert_query_with_i64_timestamp() {
     }
 
     let query_output = instance
-        .execute_sql("select ts as time from demo order by ts")
+        .execute_sql(
+            "select ts as time from demo order by ts",
+            QueryContext::arc(),
+        )
         .await
         .unwrap();
 
@@ -283,7 +605,10 @@ async fn test_execute_query() {
     instance.start().await.unwrap();
 
     let output = instance
-        .execute_sql("select sum(number) from numbers limit 20")
+        .execute_sql(
+            "select sum(number) from numbers limit 20",
+            QueryContext::arc(),
+        )
         .await
         .unwrap();
     match output {
@@ -309,7 +634,10 @@ async fn test_execute_show_databases_tables() {
     let instance = Instance::with_mock_meta_client(&opts).await.unwrap();
     instance.start().await.unwrap();
 
-    let output = instance.execute_sql("show databases").await.unwrap();
+    let output = instance
+        .execute_sql("show databases", QueryContext::arc())
+        .await
+        .unwrap();
     match output {
         Output::RecordBatches(databases) => {
             let databases = databases.take();
@@ -326,7 +654,7 @@ async fn test_execute_show_databases_tables() {
     }
 
     let output = instance
-        .execute_sql("show databases like '%bl%'")
+        .execute_sql("show databases like '%bl%'", QueryContext::arc())
         .await
         .unwrap();
     match output {
@@ -344,7 +672,10 @@ async fn test_execute_show_databases_tables() {
         _ => unreachable!(),
     }
 
-    let output = instance.execute_sql("show tables").await.unwrap();
+    let output = instance
+        .execute_sql("show tables", QueryContext::arc())
+        .await
+        .unwrap();
     match output {
         Output::RecordBatches(databases) => {
             let databases = databases.take();
@@ -364,7 +695,10 @@ async fn test_execute_show_databases_tables() {
     .await
     .unwrap();
 
-    let output = instance.execute_sql("show tables").await.unwrap();
+    let output = instance
+        .execute_sql("show tables", QueryContext::arc())
+        .await
+        .unwrap();
     match output {
         Output::RecordBatches(databases) => {
             let databases = databases.take();
@@ -377,7 +711,7 @@ async fn test_execute_show_databases_tables() {
 
     // show tables like [string]
     let output = instance
-        .execute_sql("show tables like 'de%'")
+        .execute_sql("show tables like 'de%'", QueryContext::arc())
         .await
         .unwrap();
     match output {
@@ -396,6 +730,34 @@ async fn test_execute_show_databases_tables() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_show_columns() {
+    let instance = setup_test_instance().await;
+
+    for sql in ["show columns from demo", "show fields in demo from public"] {
+        let output = instance
+            .execute_sql(sql, QueryContext::arc())
+            .await
+            .unwrap();
+        match output {
+            Output::RecordBatches(recordbatches) => {
+                let recordbatches = recordbatches.take();
+                let columns = recordbatches[0].df_recordbatch.columns();
+                assert_eq!(
+                    *columns[0].as_any().downcast_ref::<StringArray>().unwrap(),
+                    StringArray::from(vec![Some("host"), Some("cpu"), Some("memory"), Some("ts")])
+                );
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    assert!(instance
+        .execute_sql("show columns from unknown", QueryContext::arc())
+        .await
+        .is_err());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 pub async fn test_execute_create() {
     common_telemetry::init_default_ut_logging();
@@ -414,12 +776,95 @@ pub async fn test_execute_create() {
                             TIME INDEX (ts),
                             PRIMARY KEY(host)
                         ) engine=mito with(regions=1);"#,
+            QueryContext::arc(),
         )
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(1)));
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_execute_show_create_table() {
+    let (opts, _guard) = test_util::create_tmp_dir_and_datanode_opts("execute_show_create_table");
+    let instance = Instance::with_mock_meta_client(&opts).await.unwrap();
+    instance.start().await.unwrap();
+
+    let output = instance
+        .execute_sql(
+            r#"create table test_table(
+                            host string,
+                            ts timestamp,
+                            cpu double default 0,
+                            memory double null,
+                            TIME INDEX (ts),
+                            PRIMARY KEY(host)
+                        ) engine=mito with(regions=1, ttl='7d');"#,
+            QueryContext::arc(),
+        )
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(1)));
+
+    let create_sql = show_create_table(&instance, "test_table").await;
+    assert_eq!(
+        create_sql,
+        r#"CREATE TABLE "test_table" (
+  "host" STRING NOT NULL,
+  "ts" TIMESTAMP NOT NULL,
+  "cpu" DOUBLE NOT NULL DEFAULT 0,
+  "memory" DOUBLE NULL,
+  TIME INDEX ("ts"),
+  PRIMARY KEY ("host")
+)
+ENGINE=mito
+WITH(
+  regions = '1',
+  ttl = '7d'
+)"#
+    );
+
+    // The rendered statement must be able to recreate the same table.
+    let output = instance
+        .execute_sql("drop table test_table", QueryContext::arc())
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(1)));
+    let output = instance
+        .execute_sql(&create_sql, QueryContext::arc())
+        .await
+        .unwrap();
+    assert!(matches!(output, Output::AffectedRows(1)));
+    assert_eq!(create_sql, show_create_table(&instance, "test_table").await);
+
+    assert!(instance
+        .execute_sql("show create table unknown", QueryContext::arc())
+        .await
+        .is_err());
+}
+
+async fn show_create_table(instance: &Instance, table_name: &str) -> String {
+    let output = instance
+        .execute_sql(
+            &format!("show create table {}", table_name),
+            QueryContext::arc(),
+        )
+        .await
+        .unwrap();
+    match output {
+        Output::RecordBatches(recordbatches) => {
+            let recordbatches = recordbatches.take();
+            let columns = recordbatches[0].df_recordbatch.columns();
+            assert_eq!(2, columns.len());
+
+            let table_names = columns[0].as_any().downcast_ref::<StringArray>().unwrap();
+            assert_eq!(table_name, table_names.value(0));
+            let create_sqls = columns[1].as_any().downcast_ref::<StringArray>().unwrap();
+            create_sqls.value(0).to_string()
+        }
+        _ => unreachable!(),
+    }
+}
+
 #[tokio::test(flavor = "multi_thread")]
 pub async fn test_create_table_illegal_timestamp_type() {
     common_telemetry::init_default_ut_logging();
@@ -439,6 +884,7 @@ pub async fn test_create_table_illegal_timestamp_type() {
                             TIME INDEX (ts),
                             PRIMARY KEY(host)
                         ) engine=mito with(regions=1);"#,
+            QueryContext::arc(),
         )
         .await
         .unwrap();
@@ -480,32 +926,40 @@ async fn test_alter_table() {
     .unwrap();
     // make sure table insertion is ok before altering table
     instance
-        .execute_sql("insert into demo(host, cpu, memory, ts) values ('host1', 1.1, 100, 1000)")
+        .execute_sql(
+            "insert into demo(host, cpu, memory, ts) values ('host1', 1.1, 100, 1000)",
+            QueryContext::arc(),
+        )
         .await
         .unwrap();
 
     // Add column
     let output = instance
-        .execute_sql("alter table demo add my_tag string null")
+        .execute_sql(
+            "alter table demo add my_tag string null",
+            QueryContext::arc(),
+        )
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(0)));
 
     let output = instance
         .execute_sql(
-            "insert into demo(host, cpu, memory, ts, my_tag) values ('host2', 2.2, 200, 2000, 'hello')",
-        )
+            "insert into demo(host, cpu, memory, ts, my_tag) values ('host2', 2.2, 200, 2000, 'hello')", QueryContext::arc())
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(1)));
     let output = instance
-        .execute_sql("insert into demo(host, cpu, memory, ts) values ('host3', 3.3, 300, 3000)")
+        .execute_sql(
+            "insert into demo(host, cpu, memory, ts) values ('host3', 3.3, 300, 3000)",
+            QueryContext::arc(),
+        )
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(1)));
 
     let output = instance
-        .execute_sql("select * from demo order by ts")
+        .execute_sql("select * from demo order by ts", QueryContext::arc())
         .await
         .unwrap();
     let expected = vec![
@@ -521,13 +975,13 @@ async fn test_alter_table() {
 
     // Drop a column
     let output = instance
-        .execute_sql("alter table demo drop column memory")
+        .execute_sql("alter table demo drop column memory", QueryContext::arc())
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(0)));
 
     let output = instance
-        .execute_sql("select * from demo order by ts")
+        .execute_sql("select * from demo order by ts", QueryContext::arc())
         .await
         .unwrap();
     let expected = vec![
@@ -543,13 +997,16 @@ async fn test_alter_table() {
 
     // insert a new row
     let output = instance
-        .execute_sql("insert into demo(host, cpu, ts, my_tag) values ('host4', 400, 4000, 'world')")
+        .execute_sql(
+            "insert into demo(host, cpu, ts, my_tag) values ('host4', 400, 4000, 'world')",
+            QueryContext::arc(),
+        )
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(1)));
 
     let output = instance
-        .execute_sql("select * from demo order by ts")
+        .execute_sql("select * from demo order by ts", QueryContext::arc())
         .await
         .unwrap();
     let expected = vec![
@@ -580,25 +1037,34 @@ async fn test_insert_with_default_value_for_type(type_name: &str) {
     ) engine=mito with(regions=1);"#,
         type_name
     );
-    let output = instance.execute_sql(&create_sql).await.unwrap();
+    let output = instance
+        .execute_sql(&create_sql, QueryContext::arc())
+        .await
+        .unwrap();
     assert!(matches!(output, Output::AffectedRows(1)));
 
     // Insert with ts.
     instance
-        .execute_sql("insert into test_table(host, cpu, ts) values ('host1', 1.1, 1000)")
+        .execute_sql(
+            "insert into test_table(host, cpu, ts) values ('host1', 1.1, 1000)",
+            QueryContext::arc(),
+        )
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(1)));
 
     // Insert without ts, so it should be filled by default value.
     let output = instance
-        .execute_sql("insert into test_table(host, cpu) values ('host2', 2.2)")
+        .execute_sql(
+            "insert into test_table(host, cpu) values ('host2', 2.2)",
+            QueryContext::arc(),
+        )
         .await
         .unwrap();
     assert!(matches!(output, Output::AffectedRows(1)));
 
     let output = instance
-        .execute_sql("select host, cpu from test_table")
+        .execute_sql("select host, cpu from test_table", QueryContext::arc())
         .await
         .unwrap();
     let expected = vec![
@@ -47,6 +47,18 @@ pub enum Error {
         source: servers::error::Error,
     },
 
+    #[snafu(display("Illegal auth config, source: {}", source))]
+    IllegalAuthConfig {
+        #[snafu(backtrace)]
+        source: servers::error::Error,
+    },
+
+    #[snafu(display("Illegal TLS config, source: {}", source))]
+    IllegalTlsConfig {
+        #[snafu(backtrace)]
+        source: servers::error::Error,
+    },
+
     #[snafu(display("Failed to parse address {}, source: {}", addr, source))]
     ParseAddr {
         addr: String,
@@ -59,6 +71,12 @@ pub enum Error {
         source: sql::error::Error,
     },
 
+    #[snafu(display("Failed to convert partition bound to sql value, source: {}", source))]
+    ConvertPartitionBound {
+        #[snafu(backtrace)]
+        source: sql::error::Error,
+    },
+
     #[snafu(display("Column datatype error, source: {}", source))]
     ColumnDataType {
         #[snafu(backtrace)]
@@ -256,6 +274,18 @@ pub enum Error {
         source: client::Error,
     },
 
+    #[snafu(display("Failed to flush table, source: {}", source))]
+    FlushTable {
+        #[snafu(backtrace)]
+        source: client::Error,
+    },
+
+    #[snafu(display("Failed to compact table, source: {}", source))]
+    CompactTable {
+        #[snafu(backtrace)]
+        source: client::Error,
+    },
+
     #[snafu(display("Failed to insert values to table, source: {}", source))]
     Insert {
         #[snafu(backtrace)]
@@ -445,6 +475,12 @@ pub enum Error {
 
     #[snafu(display("Table already exists: `{}`", table))]
     TableAlreadyExist { table: String, backtrace: Backtrace },
+
+    #[snafu(display("Query is cancelled, source: {}", source))]
+    QueryCancelled {
+        #[snafu(backtrace)]
+        source: common_query::error::Error,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -464,9 +500,13 @@ impl ErrorExt for Error {
 
             Error::RuntimeResource { source, .. } => source.status_code(),
 
-            Error::StartServer { source, .. } => source.status_code(),
+            Error::StartServer { source, .. }
+            | Error::IllegalAuthConfig { source }
+            | Error::IllegalTlsConfig { source } => source.status_code(),
 
-            Error::ParseSql { source } => source.status_code(),
+            Error::ParseSql { source } | Error::ConvertPartitionBound { source } => {
+                source.status_code()
+            }
 
             Error::FullTableName { source, .. } => source.status_code(),
 
@@ -518,6 +558,8 @@ impl ErrorExt for Error {
             Error::CreateTable { source, .. }
             | Error::AlterTable { source, .. }
             | Error::DropTable { source }
+            | Error::FlushTable { source }
+            | Error::CompactTable { source }
             | Error::Select { source, .. }
             | Error::CreateDatabase { source, .. }
             | Error::CreateTableOnInsertion { source, .. }
@@ -536,6 +578,7 @@ impl ErrorExt for Error {
             Error::AlterExprToRequest { source, .. } => source.status_code(),
             Error::LeaderNotFound { .. } => StatusCode::StorageUnavailable,
             Error::TableAlreadyExist { .. } => StatusCode::TableAlreadyExists,
+            Error::QueryCancelled { source } => source.status_code(),
         }
     }
 
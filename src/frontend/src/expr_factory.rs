@@ -12,15 +12,17 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
 use std::sync::Arc;
 
 use api::helper::ColumnDataTypeWrapper;
 use api::v1::{Column, ColumnDataType, CreateExpr};
 use datatypes::schema::ColumnSchema;
+use session::context::{QueryContext, QueryContextRef};
 use snafu::{ensure, ResultExt};
 use sql::statements::create::{CreateTable, TIME_INDEX};
-use sql::statements::{column_def_to_schema, table_idents_to_full_name};
+use sql::statements::{
+    column_def_to_schema, sql_options_to_table_options, table_idents_to_full_name,
+};
 use sqlparser::ast::{ColumnDef, TableConstraint};
 
 use crate::error::{
@@ -32,7 +34,11 @@ pub type CreateExprFactoryRef = Arc<dyn CreateExprFactory + Send + Sync>;
 
 #[async_trait::async_trait]
 pub trait CreateExprFactory {
-    async fn create_expr_by_stmt(&self, stmt: &CreateTable) -> Result<CreateExpr>;
+    async fn create_expr_by_stmt(
+        &self,
+        stmt: &CreateTable,
+        query_ctx: QueryContextRef,
+    ) -> Result<CreateExpr>;
 
     async fn create_expr_by_columns(
         &self,
@@ -48,8 +54,12 @@ pub struct DefaultCreateExprFactory;
 
 #[async_trait::async_trait]
 impl CreateExprFactory for DefaultCreateExprFactory {
-    async fn create_expr_by_stmt(&self, stmt: &CreateTable) -> Result<CreateExpr> {
-        create_to_expr(None, vec![0], stmt)
+    async fn create_expr_by_stmt(
+        &self,
+        stmt: &CreateTable,
+        query_ctx: QueryContextRef,
+    ) -> Result<CreateExpr> {
+        create_to_expr(None, vec![0], stmt, &query_ctx)
     }
 
     async fn create_expr_by_columns(
@@ -78,11 +88,14 @@ fn create_to_expr(
     table_id: Option<u32>,
     region_ids: Vec<u32>,
     create: &CreateTable,
+    query_ctx: &QueryContext,
 ) -> Result<CreateExpr> {
     let (catalog_name, schema_name, table_name) =
-        table_idents_to_full_name(&create.name).context(ParseSqlSnafu)?;
+        table_idents_to_full_name(&create.name, query_ctx).context(ParseSqlSnafu)?;
 
     let time_index = find_time_index(&create.constraints)?;
+    let mut table_options = sql_options_to_table_options(&create.options);
+    table_options.insert("engine".to_string(), create.engine.clone());
     let expr = CreateExpr {
         catalog_name: Some(catalog_name),
         schema_name: Some(schema_name),
@@ -92,8 +105,7 @@ fn create_to_expr(
         time_index,
         primary_keys: find_primary_keys(&create.constraints)?,
         create_if_not_exists: create.if_not_exists,
-        // TODO(LFC): Fill in other table options.
-        table_options: HashMap::from([("engine".to_string(), create.engine.clone())]),
+        table_options,
         table_id,
         region_ids,
     };
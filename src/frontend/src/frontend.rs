@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use meta_client::MetaClientOpts;
 use serde::{Deserialize, Serialize};
@@ -42,6 +43,11 @@ pub struct FrontendOptions {
     pub mode: Mode,
     pub datanode_rpc_addr: String,
     pub meta_client_opts: Option<MetaClientOpts>,
+    /// User provider of all servers, e.g. `static_user_provider:file:/path/to/users`.
+    pub user_provider: Option<String>,
+    /// Queries running longer than this are cancelled, e.g. `"5m"`, no limit if not set.
+    #[serde(default, with = "humantime_serde")]
+    pub query_timeout: Option<Duration>,
 }
 
 impl Default for FrontendOptions {
@@ -57,6 +63,8 @@ impl Default for FrontendOptions {
             mode: Mode::Standalone,
             datanode_rpc_addr: "127.0.0.1:3001".to_string(),
             meta_client_opts: None,
+            user_provider: None,
+            query_timeout: None,
         }
     }
 }
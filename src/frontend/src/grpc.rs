@@ -13,11 +13,14 @@
 // limitations under the License.
 
 use serde::{Deserialize, Serialize};
+use servers::tls::TlsOption;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GrpcOptions {
     pub addr: String,
     pub runtime_size: usize,
+    #[serde(default)]
+    pub tls: TlsOption,
 }
 
 impl Default for GrpcOptions {
@@ -25,6 +28,7 @@ impl Default for GrpcOptions {
         Self {
             addr: "127.0.0.1:4001".to_string(),
             runtime_size: 8,
+            tls: TlsOption::default(),
         }
     }
 }
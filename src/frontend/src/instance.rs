@@ -16,7 +16,9 @@ pub(crate) mod distributed;
 mod influxdb;
 mod opentsdb;
 mod prometheus;
+mod promql;
 
+use std::future::Future;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -25,8 +27,8 @@ use api::v1::alter_expr::Kind;
 use api::v1::object_expr::Expr;
 use api::v1::{
     admin_expr, select_expr, AddColumns, AdminExpr, AdminResult, AlterExpr, Column,
-    CreateDatabaseExpr, CreateExpr, DropTableExpr, InsertExpr, ObjectExpr,
-    ObjectResult as GrpcObjectResult,
+    CompactTableExpr, CreateDatabaseExpr, CreateExpr, DropTableExpr, FlushTableExpr, InsertExpr,
+    ObjectExpr, ObjectResult as GrpcObjectResult,
 };
 use async_trait::async_trait;
 use catalog::remote::MetaKvBackend;
@@ -39,32 +41,42 @@ use common_grpc::channel_manager::{ChannelConfig, ChannelManager};
 use common_grpc::select::to_object_result;
 use common_query::Output;
 use common_telemetry::{debug, error, info};
+use datatypes::schema::SchemaRef;
 use distributed::DistInstance;
 use meta_client::client::MetaClientBuilder;
 use meta_client::MetaClientOpts;
+use query::process::{ProcessManager, ProcessManagerRef};
+use query::sql::show_processlist;
+use query::{QueryEngineFactory, QueryEngineRef};
+use servers::grpc::query_context_from_header;
 use servers::query_handler::{
     GrpcAdminHandler, GrpcQueryHandler, InfluxdbLineProtocolHandler, OpentsdbProtocolHandler,
-    PrometheusProtocolHandler, ScriptHandler, ScriptHandlerRef, SqlQueryHandler,
+    PrometheusProtocolHandler, PromqlHandler, ScriptHandler, ScriptHandlerRef, SqlQueryHandler,
 };
 use servers::{error as server_error, Mode};
+use session::context::{QueryContext, QueryContextRef};
 use snafu::prelude::*;
 use sql::dialect::GenericDialect;
 use sql::parser::ParserContext;
+use sql::statements::admin::Admin as AdminStatement;
+use sql::statements::alter::alter_to_expr;
 use sql::statements::create::Partitions;
 use sql::statements::explain::Explain;
 use sql::statements::insert::Insert;
 use sql::statements::statement::Statement;
+use sql::statements::table_idents_to_full_name;
 
 use crate::catalog::FrontendCatalogManager;
 use crate::datanode::DatanodeClients;
 use crate::error::{
     self, AlterTableOnInsertionSnafu, AlterTableSnafu, CatalogNotFoundSnafu, CatalogSnafu,
-    CreateDatabaseSnafu, CreateTableSnafu, DropTableSnafu, FindNewColumnsOnInsertionSnafu,
-    InsertSnafu, MissingMetasrvOptsSnafu, Result, SchemaNotFoundSnafu, SelectSnafu,
-    UnsupportedExprSnafu,
+    CompactTableSnafu, CreateDatabaseSnafu, CreateTableSnafu, DropTableSnafu,
+    FindNewColumnsOnInsertionSnafu, FlushTableSnafu, InsertSnafu, MissingMetasrvOptsSnafu, Result,
+    SchemaNotFoundSnafu, SelectSnafu, UnsupportedExprSnafu,
 };
 use crate::expr_factory::{CreateExprFactoryRef, DefaultCreateExprFactory};
 use crate::frontend::FrontendOptions;
+use crate::prometheus::DEFAULT_READ_SAMPLE_LIMIT;
 use crate::sql::insert_to_request;
 use crate::table::insert::insert_request_to_insert_batch;
 use crate::table::route::TableRoutes;
@@ -77,6 +89,7 @@ pub trait FrontendInstance:
     + OpentsdbProtocolHandler
     + InfluxdbLineProtocolHandler
     + PrometheusProtocolHandler
+    + PromqlHandler
     + ScriptHandler
     + Send
     + Sync
@@ -103,6 +116,14 @@ pub struct Instance {
     mode: Mode,
     // TODO(LFC): Refactor consideration: Can we split Frontend to DistInstance and EmbedInstance?
     dist_instance: Option<DistInstance>,
+    /// Query engine to execute plans built by frontend (e.g. prometheus remote read), available
+    /// once the catalog manager is set.
+    query_engine: Option<QueryEngineRef>,
+    /// Max number of samples returned by a prometheus remote read query, 0 means no limit.
+    prom_read_sample_limit: usize,
+    process_manager: ProcessManagerRef,
+    /// Queries running longer than this are cancelled, no limit if not set.
+    query_timeout: Option<Duration>,
 }
 
 impl Default for Instance {
@@ -114,6 +135,10 @@ impl Default for Instance {
             create_expr_factory: Arc::new(DefaultCreateExprFactory {}),
             mode: Mode::Standalone,
             dist_instance: None,
+            query_engine: None,
+            prom_read_sample_limit: DEFAULT_READ_SAMPLE_LIMIT,
+            process_manager: Arc::new(ProcessManager::new()),
+            query_timeout: None,
         }
     }
 }
@@ -122,6 +147,12 @@ impl Instance {
     pub async fn try_new(opts: &FrontendOptions) -> Result<Self> {
         let mut instance = Instance {
             mode: opts.mode.clone(),
+            prom_read_sample_limit: opts
+                .prometheus_options
+                .as_ref()
+                .map(|opts| opts.read_sample_limit)
+                .unwrap_or(DEFAULT_READ_SAMPLE_LIMIT),
+            query_timeout: opts.query_timeout,
             ..Default::default()
         };
 
@@ -173,11 +204,10 @@ impl Instance {
 
                 instance.catalog_manager = Some(catalog_manager.clone());
 
-                Some(DistInstance::new(
-                    meta_client,
-                    catalog_manager,
-                    datanode_clients,
-                ))
+                let dist_instance =
+                    DistInstance::new(meta_client, catalog_manager, datanode_clients);
+                instance.query_engine = Some(dist_instance.query_engine());
+                Some(dist_instance)
             }
         };
         Ok(instance)
@@ -200,6 +230,7 @@ impl Instance {
             self.catalog_manager.is_none(),
             "Catalog manager can be set only once!"
         );
+        self.query_engine = Some(QueryEngineFactory::new(catalog_manager.clone()).query_engine());
         self.catalog_manager = Some(catalog_manager);
     }
 
@@ -211,15 +242,23 @@ impl Instance {
         self.script_handler = Some(handler);
     }
 
-    pub async fn handle_select(&self, expr: Select, stmt: Statement) -> Result<Output> {
+    pub async fn handle_select(
+        &self,
+        expr: Select,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
         if let Some(dist_instance) = &self.dist_instance {
             let Select::Sql(sql) = expr;
-            dist_instance.handle_sql(&sql, stmt).await
+            dist_instance.handle_sql(&sql, stmt, query_ctx).await
         } else {
             // TODO(LFC): Refactor consideration: Datanode should directly execute statement in standalone mode to avoid parse SQL again.
             // Find a better way to execute query between Frontend and Datanode in standalone mode.
             // Otherwise we have to parse SQL first to get schema name. Maybe not GRPC.
-            self.database(DEFAULT_SCHEMA_NAME)
+            let mut database = self.database(DEFAULT_SCHEMA_NAME);
+            database
+                .set_catalog_and_schema(query_ctx.current_catalog(), query_ctx.current_schema());
+            database
                 .select(expr)
                 .await
                 .and_then(Output::try_from)
@@ -297,11 +336,70 @@ impl Instance {
         }
     }
 
+    /// Handle flush table expr
+    pub async fn handle_flush_table(&self, expr: FlushTableExpr) -> Result<Output> {
+        match &self.dist_instance {
+            Some(dist_instance) => dist_instance.handle_flush_table(expr).await,
+            None => self
+                .admin(&expr.schema_name)
+                .flush_table(expr)
+                .await
+                .and_then(admin_result_to_output)
+                .context(FlushTableSnafu),
+        }
+    }
+
+    /// Handle compact table expr
+    pub async fn handle_compact_table(&self, expr: CompactTableExpr) -> Result<Output> {
+        match &self.dist_instance {
+            Some(dist_instance) => dist_instance.handle_compact_table(expr).await,
+            None => self
+                .admin(&expr.schema_name)
+                .compact_table(expr)
+                .await
+                .and_then(admin_result_to_output)
+                .context(CompactTableSnafu),
+        }
+    }
+
+    async fn handle_admin(
+        &self,
+        admin: AdminStatement,
+        query_ctx: &QueryContextRef,
+    ) -> Result<Output> {
+        let (catalog_name, schema_name, table_name) =
+            table_idents_to_full_name(admin.table_name(), query_ctx)
+                .context(error::ParseSqlSnafu)?;
+        match admin {
+            AdminStatement::FlushTable(_) => {
+                let expr = FlushTableExpr {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                };
+                self.handle_flush_table(expr).await
+            }
+            AdminStatement::CompactTable(_) => {
+                let expr = CompactTableExpr {
+                    catalog_name,
+                    schema_name,
+                    table_name,
+                };
+                self.handle_compact_table(expr).await
+            }
+        }
+    }
+
     /// Handle explain expr
-    pub async fn handle_explain(&self, sql: &str, explain_stmt: Explain) -> Result<Output> {
+    pub async fn handle_explain(
+        &self,
+        sql: &str,
+        explain_stmt: Explain,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
         if let Some(dist_instance) = &self.dist_instance {
             dist_instance
-                .handle_sql(sql, Statement::Explain(explain_stmt))
+                .handle_sql(sql, Statement::Explain(explain_stmt), query_ctx)
                 .await
         } else {
             Ok(Output::AffectedRows(0))
@@ -468,13 +566,19 @@ impl Instance {
             })
     }
 
-    async fn sql_dist_insert(&self, insert: Box<Insert>) -> Result<usize> {
-        let (catalog, schema, table) = insert.full_table_name().context(error::ParseSqlSnafu)?;
+    async fn sql_dist_insert(
+        &self,
+        insert: Box<Insert>,
+        query_ctx: QueryContextRef,
+    ) -> Result<usize> {
+        let (catalog, schema, table) = insert
+            .full_table_name(&query_ctx)
+            .context(error::ParseSqlSnafu)?;
 
         let catalog_provider = self.get_catalog(&catalog)?;
         let schema_provider = Self::get_schema(catalog_provider, &schema)?;
 
-        let insert_request = insert_to_request(&schema_provider, *insert)?;
+        let insert_request = insert_to_request(&schema_provider, *insert, &query_ctx)?;
 
         let (columns, _row_count) =
             crate::table::insert::insert_request_to_insert_batch(&insert_request)?;
@@ -498,11 +602,12 @@ impl Instance {
         catalog: &str,
         schema: &str,
         insert: Box<Insert>,
+        query_ctx: &QueryContext,
     ) -> Result<(Vec<Column>, u32)> {
         let catalog_provider = self.get_catalog(catalog)?;
         let schema_provider = Self::get_schema(catalog_provider, schema)?;
 
-        let insert_request = insert_to_request(&schema_provider, *insert)?;
+        let insert_request = insert_to_request(&schema_provider, *insert, query_ctx)?;
         insert_request_to_insert_batch(&insert_request)
     }
 }
@@ -520,11 +625,15 @@ impl Instance {
     pub fn with_client_and_catalog_manager(client: Client, catalog: CatalogManagerRef) -> Self {
         Self {
             client,
+            query_engine: Some(QueryEngineFactory::new(catalog.clone()).query_engine()),
             catalog_manager: Some(catalog),
             script_handler: None,
             create_expr_factory: Arc::new(DefaultCreateExprFactory),
             mode: Mode::Standalone,
             dist_instance: None,
+            prom_read_sample_limit: DEFAULT_READ_SAMPLE_LIMIT,
+            process_manager: Arc::new(ProcessManager::new()),
+            query_timeout: None,
         }
     }
 }
@@ -543,23 +652,52 @@ fn parse_stmt(sql: &str) -> Result<Statement> {
     Ok(stmt.remove(0))
 }
 
-#[async_trait]
-impl SqlQueryHandler for Instance {
-    async fn do_query(&self, query: &str) -> server_error::Result<Output> {
+impl Instance {
+    pub fn process_manager(&self) -> &ProcessManagerRef {
+        &self.process_manager
+    }
+
+    /// Runs the non-SQL `query` (e.g. PromQL) on `database` by `fut` as a registered
+    /// process, so it could be listed, killed and times out like SQL queries.
+    pub(crate) async fn run_as_process<F, T>(
+        &self,
+        database: &str,
+        query: &str,
+        fut: F,
+    ) -> server_error::Result<T>
+    where
+        F: Future<Output = server_error::Result<T>>,
+    {
+        let query_ctx = Arc::new(QueryContext::with(DEFAULT_CATALOG_NAME, database));
+        let guard = self
+            .process_manager
+            .register(query, &query_ctx, self.query_timeout);
+        guard
+            .run(fut)
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteQuerySnafu { query })?
+    }
+
+    async fn execute_query(
+        &self,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
         let stmt = parse_stmt(query)
             .map_err(BoxedError::new)
             .context(server_error::ExecuteQuerySnafu { query })?;
 
         match stmt {
             Statement::Query(_) => self
-                .handle_select(Select::Sql(query.to_string()), stmt)
+                .handle_select(Select::Sql(query.to_string()), stmt, query_ctx)
                 .await
                 .map_err(BoxedError::new)
                 .context(server_error::ExecuteQuerySnafu { query }),
             Statement::Insert(insert) => match self.mode {
                 Mode::Standalone => {
                     let (catalog_name, schema_name, table_name) = insert
-                        .full_table_name()
+                        .full_table_name(&query_ctx)
                         .context(error::ParseSqlSnafu)
                         .map_err(BoxedError::new)
                         .context(server_error::ExecuteInsertSnafu {
@@ -567,7 +705,7 @@ impl SqlQueryHandler for Instance {
                         })?;
 
                     let (columns, row_count) = self
-                        .stmt_to_insert_batch(&catalog_name, &schema_name, insert)
+                        .stmt_to_insert_batch(&catalog_name, &schema_name, insert, &query_ctx)
                         .map_err(BoxedError::new)
                         .context(server_error::ExecuteQuerySnafu { query })?;
 
@@ -585,7 +723,7 @@ impl SqlQueryHandler for Instance {
                 }
                 Mode::Distributed => {
                     let affected = self
-                        .sql_dist_insert(insert)
+                        .sql_dist_insert(insert, query_ctx)
                         .await
                         .map_err(BoxedError::new)
                         .context(server_error::ExecuteInsertSnafu {
@@ -594,10 +732,24 @@ impl SqlQueryHandler for Instance {
                     Ok(Output::AffectedRows(affected))
                 }
             },
+            Statement::Delete(_) => match self.mode {
+                // Datanode executes the DELETE statement directly in standalone mode.
+                Mode::Standalone => self
+                    .handle_select(Select::Sql(query.to_string()), stmt, query_ctx)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(server_error::ExecuteQuerySnafu { query }),
+                Mode::Distributed => {
+                    return server_error::NotSupportedSnafu {
+                        feat: "DELETE in distributed mode",
+                    }
+                    .fail();
+                }
+            },
             Statement::CreateTable(create) => {
                 let create_expr = self
                     .create_expr_factory
-                    .create_expr_by_stmt(&create)
+                    .create_expr_by_stmt(&create, query_ctx)
                     .await
                     .map_err(BoxedError::new)
                     .context(server_error::ExecuteQuerySnafu { query })?;
@@ -610,8 +762,10 @@ impl SqlQueryHandler for Instance {
 
             Statement::ShowDatabases(_)
             | Statement::ShowTables(_)
-            | Statement::DescribeTable(_) => self
-                .handle_select(Select::Sql(query.to_string()), stmt)
+            | Statement::DescribeTable(_)
+            | Statement::ShowCreateTable(_)
+            | Statement::ShowColumns(_) => self
+                .handle_select(Select::Sql(query.to_string()), stmt, query_ctx)
                 .await
                 .map_err(BoxedError::new)
                 .context(server_error::ExecuteQuerySnafu { query }),
@@ -627,7 +781,7 @@ impl SqlQueryHandler for Instance {
             }
             Statement::Alter(alter_stmt) => self
                 .handle_alter(
-                    AlterExpr::try_from(alter_stmt)
+                    alter_to_expr(alter_stmt, &query_ctx)
                         .map_err(BoxedError::new)
                         .context(server_error::ExecuteAlterSnafu { query })?,
                 )
@@ -635,23 +789,52 @@ impl SqlQueryHandler for Instance {
                 .map_err(BoxedError::new)
                 .context(server_error::ExecuteQuerySnafu { query }),
             Statement::DropTable(drop_stmt) => {
+                let (catalog_name, schema_name, table_name) =
+                    table_idents_to_full_name(drop_stmt.table_name(), &query_ctx)
+                        .map_err(BoxedError::new)
+                        .context(server_error::ExecuteQuerySnafu { query })?;
                 let expr = DropTableExpr {
-                    catalog_name: drop_stmt.catalog_name,
-                    schema_name: drop_stmt.schema_name,
-                    table_name: drop_stmt.table_name,
+                    catalog_name,
+                    schema_name,
+                    table_name,
                 };
                 self.handle_drop_table(expr)
                     .await
                     .map_err(BoxedError::new)
                     .context(server_error::ExecuteQuerySnafu { query })
             }
+            Statement::Admin(admin) => self
+                .handle_admin(admin, &query_ctx)
+                .await
+                .map_err(BoxedError::new)
+                .context(server_error::ExecuteQuerySnafu { query }),
             Statement::Explain(explain_stmt) => self
-                .handle_explain(query, explain_stmt)
+                .handle_explain(query, explain_stmt, query_ctx)
                 .await
                 .map_err(BoxedError::new)
                 .context(server_error::ExecuteQuerySnafu { query }),
-            Statement::ShowCreateTable(_) => {
-                return server_error::NotSupportedSnafu { feat: query }.fail();
+            Statement::Use(db) => {
+                let catalog = query_ctx.current_catalog();
+                if !self.is_valid_schema(&catalog, &db)? {
+                    return server_error::InvalidQuerySnafu {
+                        reason: format!("Unknown database: {}", db),
+                    }
+                    .fail();
+                }
+                query_ctx.set_current_schema(&db);
+                Ok(Output::AffectedRows(0))
+            }
+            Statement::ShowProcesslist(stmt) => show_processlist(stmt, &self.process_manager)
+                .context(error::ExecuteSqlSnafu { sql: query })
+                .map_err(BoxedError::new)
+                .context(server_error::ExecuteQuerySnafu { query }),
+            Statement::Kill(id) => {
+                self.process_manager
+                    .kill(id, &query_ctx)
+                    .context(error::ExecuteSqlSnafu { sql: query })
+                    .map_err(BoxedError::new)
+                    .context(server_error::ExecuteQuerySnafu { query })?;
+                Ok(Output::AffectedRows(0))
             }
         }
         .map_err(BoxedError::new)
@@ -659,6 +842,73 @@ impl SqlQueryHandler for Instance {
     }
 }
 
+#[async_trait]
+impl SqlQueryHandler for Instance {
+    async fn do_query(
+        &self,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        let guard = self
+            .process_manager
+            .register(query, &query_ctx, self.query_timeout);
+        let output = guard
+            .run(self.execute_query(query, query_ctx))
+            .await
+            .context(error::ExecuteSqlSnafu { sql: query })
+            .map_err(BoxedError::new)
+            .context(server_error::ExecuteQuerySnafu { query })?;
+        Ok(guard.track(output?))
+    }
+
+    async fn do_describe(
+        &self,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Option<SchemaRef>> {
+        let query_engine = match &self.query_engine {
+            Some(query_engine) => query_engine,
+            None => return Ok(None),
+        };
+
+        let stmt = parse_stmt(query)
+            .map_err(BoxedError::new)
+            .context(server_error::DescribeQuerySnafu { query })?;
+
+        match stmt {
+            Statement::Query(_) => query_engine
+                .statement_to_plan(stmt, query_ctx)
+                .and_then(|plan| plan.schema())
+                .context(error::ExecuteSqlSnafu { sql: query })
+                .map(Some),
+            _ => Ok(None),
+        }
+        .map_err(BoxedError::new)
+        .context(server_error::DescribeQuerySnafu { query })
+    }
+
+    fn is_valid_schema(&self, catalog: &str, schema: &str) -> server_error::Result<bool> {
+        self.catalog_manager
+            .as_ref()
+            .context(error::CatalogManagerSnafu)
+            .and_then(|catalog_manager| {
+                catalog_manager
+                    .schema(catalog, schema)
+                    .context(error::CatalogSnafu)
+            })
+            .map(|schema| schema.is_some())
+            .map_err(BoxedError::new)
+            .context(server_error::CheckDatabaseValiditySnafu)
+    }
+
+    fn kill_query(&self, connection_id: u32) -> server_error::Result<()> {
+        self.process_manager
+            .kill_unchecked(connection_id)
+            .map_err(BoxedError::new)
+            .context(server_error::KillQuerySnafu { connection_id })
+    }
+}
+
 #[async_trait]
 impl ScriptHandler for Instance {
     async fn insert_script(&self, name: &str, script: &str) -> server_error::Result<()> {
@@ -716,7 +966,8 @@ impl GrpcQueryHandler for Instance {
                         })?;
                     match select {
                         select_expr::Expr::Sql(sql) => {
-                            let output = SqlQueryHandler::do_query(self, sql).await;
+                            let query_ctx = query_context_from_header(query.header.as_ref());
+                            let output = SqlQueryHandler::do_query(self, sql, query_ctx).await;
                             Ok(to_object_result(output).await)
                         }
                         _ => {
@@ -757,6 +1008,8 @@ fn get_schema_name(expr: &AdminExpr) -> &str {
         Some(admin_expr::Expr::Alter(expr)) => expr.schema_name.as_deref(),
         Some(admin_expr::Expr::CreateDatabase(_)) | None => Some(DEFAULT_SCHEMA_NAME),
         Some(admin_expr::Expr::DropTable(expr)) => Some(expr.schema_name.as_ref()),
+        Some(admin_expr::Expr::FlushTable(expr)) => Some(expr.schema_name.as_ref()),
+        Some(admin_expr::Expr::CompactTable(expr)) => Some(expr.schema_name.as_ref()),
     };
     schema_name.unwrap_or(DEFAULT_SCHEMA_NAME)
 }
@@ -808,7 +1061,9 @@ mod tests {
                             TIME INDEX (ts),
                             PRIMARY KEY(ts, host)
                         ) engine=mito with(regions=1);"#;
-        let output = SqlQueryHandler::do_query(&*instance, sql).await.unwrap();
+        let output = SqlQueryHandler::do_query(&*instance, sql, QueryContext::arc())
+            .await
+            .unwrap();
         match output {
             Output::AffectedRows(rows) => assert_eq!(rows, 1),
             _ => unreachable!(),
@@ -819,14 +1074,18 @@ mod tests {
                                 ('frontend.host2', null, null, 2000),
                                 ('frontend.host3', 3.3, 300, 3000)
                                 "#;
-        let output = SqlQueryHandler::do_query(&*instance, sql).await.unwrap();
+        let output = SqlQueryHandler::do_query(&*instance, sql, QueryContext::arc())
+            .await
+            .unwrap();
         match output {
             Output::AffectedRows(rows) => assert_eq!(rows, 3),
             _ => unreachable!(),
         }
 
         let sql = "select * from demo";
-        let output = SqlQueryHandler::do_query(&*instance, sql).await.unwrap();
+        let output = SqlQueryHandler::do_query(&*instance, sql, QueryContext::arc())
+            .await
+            .unwrap();
         match output {
             Output::RecordBatches(recordbatches) => {
                 let pretty_print = recordbatches.pretty_print();
@@ -846,7 +1105,9 @@ mod tests {
         };
 
         let sql = "select * from demo where ts>cast(1000000000 as timestamp)"; // use nanoseconds as where condition
-        let output = SqlQueryHandler::do_query(&*instance, sql).await.unwrap();
+        let output = SqlQueryHandler::do_query(&*instance, sql, QueryContext::arc())
+            .await
+            .unwrap();
         match output {
             Output::RecordBatches(recordbatches) => {
                 let pretty_print = recordbatches.pretty_print();
@@ -865,6 +1126,76 @@ mod tests {
         };
     }
 
+    #[tokio::test]
+    async fn test_use_database() {
+        let instance = tests::create_frontend_instance().await;
+        let query_ctx = QueryContext::arc();
+
+        let output = SqlQueryHandler::do_query(&*instance, "USE my_db", query_ctx.clone()).await;
+        assert!(output.is_err());
+        assert_eq!(DEFAULT_SCHEMA_NAME, query_ctx.current_schema());
+
+        let output =
+            SqlQueryHandler::do_query(&*instance, "CREATE DATABASE my_db", query_ctx.clone())
+                .await
+                .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        let output = SqlQueryHandler::do_query(&*instance, "USE my_db", query_ctx.clone())
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(0)));
+        assert_eq!("my_db", query_ctx.current_schema());
+
+        let sql = r#"CREATE TABLE demo(
+                            host STRING,
+                            ts TIMESTAMP,
+                            cpu DOUBLE NULL,
+                            TIME INDEX (ts),
+                            PRIMARY KEY(host)
+                        ) engine=mito with(regions=1);"#;
+        let output = SqlQueryHandler::do_query(&*instance, sql, query_ctx.clone())
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        let sql = "insert into demo(host, cpu, ts) values ('host1', 1.1, 1000)";
+        let output = SqlQueryHandler::do_query(&*instance, sql, query_ctx.clone())
+            .await
+            .unwrap();
+        assert!(matches!(output, Output::AffectedRows(1)));
+
+        // The table is created in `my_db` rather than the default schema.
+        let sql = "select * from demo";
+        let output = SqlQueryHandler::do_query(&*instance, sql, QueryContext::arc()).await;
+        assert!(output.is_err());
+
+        let output = SqlQueryHandler::do_query(&*instance, sql, query_ctx.clone())
+            .await
+            .unwrap();
+        match output {
+            Output::RecordBatches(recordbatches) => {
+                let pretty_print = recordbatches.pretty_print();
+                let pretty_print = pretty_print.lines().collect::<Vec<&str>>();
+                let expected = vec![
+                    "+-------+---------------------+-----+",
+                    "| host  | ts                  | cpu |",
+                    "+-------+---------------------+-----+",
+                    "| host1 | 1970-01-01 00:00:01 | 1.1 |",
+                    "+-------+---------------------+-----+",
+                ];
+                assert_eq!(pretty_print, expected);
+            }
+            _ => unreachable!(),
+        };
+
+        let output =
+            SqlQueryHandler::do_query(&*instance, "select * from my_db.demo", QueryContext::arc())
+                .await
+                .unwrap();
+        assert!(matches!(output, Output::RecordBatches(_)));
+    }
+
     #[tokio::test]
     async fn test_execute_grpc() {
         let instance = tests::create_frontend_instance().await;
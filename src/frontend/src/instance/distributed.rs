@@ -16,8 +16,8 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use api::helper::ColumnDataTypeWrapper;
-use api::v1::{AlterExpr, CreateDatabaseExpr, CreateExpr};
-use catalog::CatalogList;
+use api::v1::{AlterExpr, CompactTableExpr, CreateDatabaseExpr, CreateExpr, FlushTableExpr};
+use catalog::{CatalogList, CatalogManagerRef};
 use chrono::DateTime;
 use client::admin::{admin_result_to_output, Admin};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
@@ -31,14 +31,20 @@ use meta_client::rpc::{
     CreateRequest as MetaCreateRequest, Partition as MetaPartition, PutRequest, RouteResponse,
     TableName, TableRoute,
 };
-use query::sql::{describe_table, explain, show_databases, show_tables};
+use query::sql::{
+    describe_table, explain, find_table, render_show_create_table, show_columns, show_databases,
+    show_tables,
+};
 use query::{QueryEngineFactory, QueryEngineRef};
+use session::context::QueryContextRef;
 use snafu::{ensure, OptionExt, ResultExt};
 use sql::statements::create::Partitions;
+use sql::statements::show::ShowCreateTable;
 use sql::statements::sql_value_to_value;
 use sql::statements::statement::Statement;
 use sqlparser::ast::Value as SqlValue;
 use table::metadata::{RawTableInfo, RawTableMeta, TableIdent, TableType};
+use table::TableRef;
 
 use crate::catalog::FrontendCatalogManager;
 use crate::datanode::DatanodeClients;
@@ -73,6 +79,10 @@ impl DistInstance {
         }
     }
 
+    pub(crate) fn query_engine(&self) -> QueryEngineRef {
+        self.query_engine.clone()
+    }
+
     pub(crate) async fn create_table(
         &self,
         create_table: &mut CreateExpr,
@@ -128,31 +138,67 @@ impl DistInstance {
         Ok(Output::AffectedRows(region_routes.len()))
     }
 
-    pub(crate) async fn handle_sql(&self, sql: &str, stmt: Statement) -> Result<Output> {
+    pub(crate) async fn handle_sql(
+        &self,
+        sql: &str,
+        stmt: Statement,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
         match stmt {
             Statement::Query(_) => {
                 let plan = self
                     .query_engine
-                    .statement_to_plan(stmt)
+                    .statement_to_plan(stmt, query_ctx)
                     .context(error::ExecuteSqlSnafu { sql })?;
                 self.query_engine
                     .execute(&plan)
                     .await
                     .context(error::ExecuteSqlSnafu { sql })
             }
-            Statement::ShowDatabases(stmt) => show_databases(stmt, self.catalog_manager.clone())
-                .context(error::ExecuteSqlSnafu { sql }),
-            Statement::ShowTables(stmt) => show_tables(stmt, self.catalog_manager.clone())
-                .context(error::ExecuteSqlSnafu { sql }),
-            Statement::DescribeTable(stmt) => describe_table(stmt, self.catalog_manager.clone())
-                .context(error::ExecuteSqlSnafu { sql }),
-            Statement::Explain(stmt) => explain(Box::new(stmt), self.query_engine.clone())
-                .await
-                .context(error::ExecuteSqlSnafu { sql }),
+            Statement::ShowDatabases(stmt) => {
+                show_databases(stmt, self.catalog_manager.clone(), query_ctx)
+                    .context(error::ExecuteSqlSnafu { sql })
+            }
+            Statement::ShowTables(stmt) => {
+                show_tables(stmt, self.catalog_manager.clone(), query_ctx)
+                    .context(error::ExecuteSqlSnafu { sql })
+            }
+            Statement::DescribeTable(stmt) => {
+                describe_table(stmt, self.catalog_manager.clone(), query_ctx)
+                    .context(error::ExecuteSqlSnafu { sql })
+            }
+            Statement::ShowCreateTable(stmt) => self.show_create_table(sql, stmt, query_ctx).await,
+            Statement::ShowColumns(stmt) => {
+                show_columns(stmt, self.catalog_manager.clone(), query_ctx)
+                    .context(error::ExecuteSqlSnafu { sql })
+            }
+            Statement::Explain(stmt) => {
+                explain(Box::new(stmt), self.query_engine.clone(), query_ctx)
+                    .await
+                    .context(error::ExecuteSqlSnafu { sql })
+            }
             _ => unreachable!(),
         }
     }
 
+    async fn show_create_table(
+        &self,
+        sql: &str,
+        stmt: ShowCreateTable,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let catalog_manager = self.catalog_manager.clone() as CatalogManagerRef;
+        let table = find_table(&stmt.table_name, &catalog_manager, &query_ctx)
+            .context(error::ExecuteSqlSnafu { sql })?;
+
+        let partitions = match table.as_any().downcast_ref::<DistTable>() {
+            Some(dist_table) => dist_table.partitions().await?,
+            None => None,
+        };
+        render_show_create_table(&table.table_info(), partitions.as_ref())
+            .context(error::ExecuteSqlSnafu { sql })
+    }
+
     /// Handles distributed database creation
     pub(crate) async fn handle_create_database(&self, expr: CreateDatabaseExpr) -> Result<Output> {
         let key = SchemaKey {
@@ -175,9 +221,30 @@ impl DistInstance {
     pub async fn handle_alter_table(&self, expr: AlterExpr) -> Result<Output> {
         let catalog_name = expr.catalog_name.as_deref().unwrap_or(DEFAULT_CATALOG_NAME);
         let schema_name = expr.schema_name.as_deref().unwrap_or(DEFAULT_SCHEMA_NAME);
-        let table_name = expr.table_name.as_str();
-        let table = self
-            .catalog_manager
+        let table = self.get_table(catalog_name, schema_name, &expr.table_name)?;
+        as_dist_table(&table).alter_by_expr(expr).await?;
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub async fn handle_flush_table(&self, expr: FlushTableExpr) -> Result<Output> {
+        let table = self.get_table(&expr.catalog_name, &expr.schema_name, &expr.table_name)?;
+        as_dist_table(&table).flush_by_expr(expr).await?;
+        Ok(Output::AffectedRows(0))
+    }
+
+    pub async fn handle_compact_table(&self, expr: CompactTableExpr) -> Result<Output> {
+        let table = self.get_table(&expr.catalog_name, &expr.schema_name, &expr.table_name)?;
+        as_dist_table(&table).compact_by_expr(expr).await?;
+        Ok(Output::AffectedRows(0))
+    }
+
+    fn get_table(
+        &self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+    ) -> Result<TableRef> {
+        self.catalog_manager
             .catalog(catalog_name)
             .context(CatalogSnafu)?
             .context(CatalogNotFoundSnafu { catalog_name })?
@@ -190,14 +257,7 @@ impl DistInstance {
             .context(CatalogSnafu)?
             .context(TableNotFoundSnafu {
                 table_name: format!("{}.{}.{}", catalog_name, schema_name, table_name),
-            })?;
-
-        let dist_table = table
-            .as_any()
-            .downcast_ref::<DistTable>()
-            .expect("Table impl must be DistTable in distributed mode");
-        dist_table.alter_by_expr(expr).await?;
-        Ok(Output::AffectedRows(0))
+            })
     }
 
     async fn create_table_in_meta(
@@ -268,6 +328,13 @@ impl DistInstance {
     }
 }
 
+fn as_dist_table(table: &TableRef) -> &DistTable {
+    table
+        .as_any()
+        .downcast_ref::<DistTable>()
+        .expect("Table impl must be DistTable in distributed mode")
+}
+
 fn create_table_global_value(
     create_table: &CreateExpr,
     table_route: &TableRoute,
@@ -344,7 +411,7 @@ fn create_table_global_value(
         next_column_id: column_schemas.len() as u32,
         region_numbers: vec![],
         engine_options: HashMap::new(),
-        options: HashMap::new(),
+        options: create_table.table_options.clone(),
         created_on: DateTime::default(),
     };
 
@@ -451,6 +518,7 @@ fn find_partition_columns(
 
 #[cfg(test)]
 mod test {
+    use session::context::QueryContext;
     use sql::parser::ParserContext;
     use sql::statements::statement::Statement;
     use sqlparser::dialect::GenericDialect;
@@ -491,7 +559,10 @@ ENGINE=mito",
                 Statement::CreateTable(c) => {
                     common_telemetry::info!("{}", sql);
                     let factory = DefaultCreateExprFactory {};
-                    let expr = factory.create_expr_by_stmt(c).await.unwrap();
+                    let expr = factory
+                        .create_expr_by_stmt(c, QueryContext::arc())
+                        .await
+                        .unwrap();
                     let partitions = parse_partitions(&expr, c.partitions.clone()).unwrap();
                     let json = serde_json::to_string(&partitions).unwrap();
                     assert_eq!(json, expected);
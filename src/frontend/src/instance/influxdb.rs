@@ -122,6 +122,7 @@ impl Instance {
             schema_name: schema_name.to_string(),
             table_name: table_name.to_string(),
             columns_values: vectors,
+            region_number: 0,
         })
     }
 }
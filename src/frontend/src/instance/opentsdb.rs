@@ -63,6 +63,7 @@ mod tests {
     use common_query::Output;
     use datafusion::arrow_print;
     use servers::query_handler::SqlQueryHandler;
+    use session::context::QueryContext;
 
     use super::*;
     use crate::tests;
@@ -121,7 +122,7 @@ mod tests {
         assert!(result.is_ok());
 
         let output = instance
-            .do_query("select * from my_metric_1")
+            .do_query("select * from my_metric_1", QueryContext::arc())
             .await
             .unwrap();
         match output {
@@ -15,19 +15,20 @@
 use api::prometheus::remote::read_request::ResponseType;
 use api::prometheus::remote::{Query, QueryResult, ReadRequest, ReadResponse, WriteRequest};
 use async_trait::async_trait;
-use client::{ObjectResult, Select};
+use client::ObjectResult;
+use common_catalog::consts::DEFAULT_CATALOG_NAME;
 use common_error::prelude::BoxedError;
 use common_grpc::select::to_object_result;
 use common_telemetry::logging;
-use futures_util::TryFutureExt;
 use prost::Message;
 use servers::error::{self, Result as ServerResult};
 use servers::prometheus::{self, Metrics};
 use servers::query_handler::{PrometheusProtocolHandler, PrometheusResponse};
 use servers::Mode;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
+use table::TableRef;
 
-use crate::instance::{parse_stmt, Instance};
+use crate::instance::Instance;
 
 const SAMPLES_RESPONSE_TYPE: i32 = ResponseType::Samples as i32;
 
@@ -80,31 +81,82 @@ impl Instance {
         &self,
         db: &str,
         queries: &[Query],
-    ) -> ServerResult<Vec<(String, ObjectResult)>> {
+    ) -> ServerResult<Vec<QueryResult>> {
         let mut results = Vec::with_capacity(queries.len());
 
         for query in queries {
-            let (table_name, sql) = prometheus::query_to_sql(db, query)?;
-            logging::debug!(
-                "prometheus remote read, table: {}, sql: {}",
-                table_name,
-                sql
-            );
+            let table_name = prometheus::query_table_name(query)?;
+            let query_result = match self.find_prom_table(db, &table_name)? {
+                Some(table) => self.handle_remote_query(table, &table_name, query).await?,
+                // Metrics that have never been written have no samples.
+                None => QueryResult::default(),
+            };
+            results.push(query_result);
+        }
+        Ok(results)
+    }
 
-            let object_result = if let Some(dist_instance) = &self.dist_instance {
-                let output = futures::future::ready(parse_stmt(&sql))
-                    .and_then(|stmt| dist_instance.handle_sql(&sql, stmt))
-                    .await;
-                to_object_result(output).await.try_into()
-            } else {
-                self.database(db).select(Select::Sql(sql.clone())).await
-            }
+    pub(crate) fn find_prom_table(
+        &self,
+        db: &str,
+        table_name: &str,
+    ) -> ServerResult<Option<TableRef>> {
+        let catalog_manager = self
+            .catalog_manager
+            .as_ref()
+            .context(error::NotSupportedSnafu {
+                feat: "prometheus remote read without catalog manager",
+            })?;
+        catalog_manager
+            .table(DEFAULT_CATALOG_NAME, db, table_name)
             .map_err(BoxedError::new)
-            .context(error::ExecuteQuerySnafu { query: sql })?;
+            .context(error::ExecuteQuerySnafu {
+                query: format!("find table {}.{}", db, table_name),
+            })
+    }
+
+    pub(crate) async fn handle_remote_query(
+        &self,
+        table: TableRef,
+        table_name: &str,
+        query: &Query,
+    ) -> ServerResult<QueryResult> {
+        let query_engine = self
+            .query_engine
+            .as_ref()
+            .context(error::NotSupportedSnafu {
+                feat: "prometheus remote read without query engine",
+            })?;
+
+        let sample_limit = self.prom_read_sample_limit;
+        // Fetches one more sample to tell whether the query exceeds the limit.
+        let fetch_limit = (sample_limit > 0).then(|| sample_limit + 1);
+        let plan = prometheus::query_to_plan(table, query, fetch_limit)?;
+        logging::debug!(
+            "prometheus remote read, table: {}, plan: {:?}",
+            table_name,
+            plan
+        );
 
-            results.push((table_name, object_result));
+        let output = query_engine.execute(&plan).await;
+        let object_result: ObjectResult = to_object_result(output)
+            .await
+            .try_into()
+            .map_err(BoxedError::new)
+            .context(error::ExecuteQuerySnafu {
+                query: format!("{:?}", plan),
+            })?;
+
+        if let ObjectResult::Select(select_result) = &object_result {
+            ensure!(
+                sample_limit == 0 || select_result.row_count as usize <= sample_limit,
+                error::PromReadSampleLimitExceededSnafu {
+                    table_name,
+                    limit: sample_limit,
+                }
+            );
         }
-        Ok(results)
+        object_result_to_query_result(table_name, object_result)
     }
 }
 
@@ -146,22 +198,22 @@ impl PrometheusProtocolHandler for Instance {
         let response_type = negotiate_response_type(&request.accepted_response_types)?;
 
         // TODO(dennis): use read_hints to speedup query if possible
+        let metrics = request
+            .queries
+            .iter()
+            .filter_map(|query| prometheus::query_table_name(query).ok())
+            .collect::<Vec<_>>();
         let results = self
-            .handle_remote_queries(database, &request.queries)
+            .run_as_process(
+                database,
+                &format!("prometheus remote read {:?}", metrics),
+                self.handle_remote_queries(database, &request.queries),
+            )
             .await?;
 
         match response_type {
             ResponseType::Samples => {
-                let query_results = results
-                    .into_iter()
-                    .map(|(table_name, object_result)| {
-                        object_result_to_query_result(&table_name, object_result)
-                    })
-                    .collect::<ServerResult<Vec<_>>>()?;
-
-                let response = ReadResponse {
-                    results: query_results,
-                };
+                let response = ReadResponse { results };
 
                 // TODO(dennis): may consume too much memory, adds flow control
                 Ok(PrometheusResponse {
@@ -241,17 +293,49 @@ mod tests {
                     ],
                     ..Default::default()
                 },
+                Query {
+                    start_timestamp_ms: 1000,
+                    end_timestamp_ms: 3000,
+                    matchers: vec![
+                        LabelMatcher {
+                            name: prometheus::METRIC_NAME_LABEL.to_string(),
+                            value: "metric3".to_string(),
+                            r#type: 0,
+                        },
+                        LabelMatcher {
+                            name: "app".to_string(),
+                            value: "b.*".to_string(),
+                            r#type: MatcherType::Re as i32,
+                        },
+                        LabelMatcher {
+                            name: "idc".to_string(),
+                            value: "z'001".to_string(),
+                            r#type: MatcherType::Neq as i32,
+                        },
+                    ],
+                    ..Default::default()
+                },
+                Query {
+                    start_timestamp_ms: 1000,
+                    end_timestamp_ms: 3000,
+                    matchers: vec![LabelMatcher {
+                        name: prometheus::METRIC_NAME_LABEL.to_string(),
+                        value: "not_exist".to_string(),
+                        r#type: 0,
+                    }],
+                    ..Default::default()
+                },
             ],
             ..Default::default()
         };
 
-        let resp = instance.read(db, read_request).await.unwrap();
+        let resp = instance.read(db, read_request.clone()).await.unwrap();
         assert_eq!(resp.content_type, "application/x-protobuf");
         assert_eq!(resp.content_encoding, "snappy");
         let body = prometheus::snappy_decompress(&resp.body).unwrap();
         let read_response = ReadResponse::decode(&body[..]).unwrap();
         let query_results = read_response.results;
-        assert_eq!(2, query_results.len());
+        assert_eq!(4, query_results.len());
 
         assert_eq!(1, query_results[0].timeseries.len());
         let timeseries = &query_results[0].timeseries[0];
@@ -322,5 +406,15 @@ mod tests {
                 }
             ]
         );
+        assert_eq!(timeseries, &query_results[2].timeseries[0]);
+        assert!(query_results[3].timeseries.is_empty());
+
+        let mut instance = (*instance).clone();
+        instance.prom_read_sample_limit = 2;
+        let err = instance.read(db, read_request).await.unwrap_err();
+        assert!(matches!(
+            err,
+            error::Error::PromReadSampleLimitExceeded { limit: 2, .. }
+        ));
     }
 }
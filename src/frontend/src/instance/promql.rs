@@ -0,0 +1,351 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use common_catalog::consts::DEFAULT_CATALOG_NAME;
+use common_error::prelude::BoxedError;
+use common_query::Output;
+use datafusion::logical_plan::LogicalPlan as DfLogicalPlan;
+use datafusion_common::record_batch::RecordBatch as DfRecordBatch;
+use promql::error::{Result as PromqlResult, StorageSnafu};
+use promql::value::{Labels, Value};
+use promql::{Engine, EvalRange, Storage};
+use query::plan::LogicalPlan;
+use servers::error::{self, Result as ServerResult};
+use servers::query_handler::PromqlHandler;
+use snafu::{OptionExt, ResultExt};
+use table::TableRef;
+
+use crate::instance::Instance;
+
+/// PromQL storage backed by the prometheus tables in a database, each metric is stored in
+/// a table of the same name. Plans of PromQL queries are executed by the query engine.
+struct PromStorage<'a> {
+    instance: &'a Instance,
+    db: &'a str,
+}
+
+impl<'a> PromStorage<'a> {
+    fn table_names(&self) -> ServerResult<Vec<String>> {
+        let catalog_manager =
+            self.instance
+                .catalog_manager
+                .as_ref()
+                .context(error::NotSupportedSnafu {
+                    feat: "PromQL query without catalog manager",
+                })?;
+        let schema = catalog_manager
+            .schema(DEFAULT_CATALOG_NAME, self.db)
+            .map_err(BoxedError::new)
+            .context(error::ExecuteQuerySnafu {
+                query: format!("find schema {}", self.db),
+            })?;
+        match schema {
+            Some(schema) => {
+                schema
+                    .table_names()
+                    .map_err(BoxedError::new)
+                    .context(error::ExecuteQuerySnafu {
+                        query: format!("list tables in {}", self.db),
+                    })
+            }
+            None => Ok(vec![]),
+        }
+    }
+
+    async fn execute_plan(&self, plan: DfLogicalPlan) -> ServerResult<Vec<DfRecordBatch>> {
+        let query_engine =
+            self.instance
+                .query_engine
+                .as_ref()
+                .context(error::NotSupportedSnafu {
+                    feat: "PromQL query without query engine",
+                })?;
+
+        let plan = LogicalPlan::DfPlan(plan);
+        let output = query_engine
+            .execute(&plan)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::ExecuteQuerySnafu {
+                query: format!("{:?}", plan),
+            })?;
+        let batches = match output {
+            Output::Stream(stream) => common_recordbatch::util::collect(stream)
+                .await
+                .map_err(BoxedError::new)
+                .context(error::ExecuteQuerySnafu {
+                    query: format!("{:?}", plan),
+                })?,
+            Output::RecordBatches(batches) => batches.take(),
+            Output::AffectedRows(_) => vec![],
+        };
+        Ok(batches
+            .into_iter()
+            .map(|batch| batch.df_recordbatch)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl<'a> Storage for PromStorage<'a> {
+    fn metric_names(&self) -> PromqlResult<Vec<String>> {
+        self.table_names()
+            .map_err(BoxedError::new)
+            .context(StorageSnafu)
+    }
+
+    fn table(&self, metric: &str) -> PromqlResult<Option<TableRef>> {
+        self.instance
+            .find_prom_table(self.db, metric)
+            .map_err(BoxedError::new)
+            .context(StorageSnafu)
+    }
+
+    async fn execute(&self, plan: DfLogicalPlan) -> PromqlResult<Vec<DfRecordBatch>> {
+        self.execute_plan(plan)
+            .await
+            .map_err(BoxedError::new)
+            .context(StorageSnafu)
+    }
+}
+
+impl Instance {
+    fn promql_engine<'a>(&'a self, db: &'a str) -> Engine<PromStorage<'a>> {
+        let engine = Engine::new(PromStorage { instance: self, db });
+        // Selectors read at most as many samples as a remote read query.
+        if self.prom_read_sample_limit > 0 {
+            engine.with_max_samples(self.prom_read_sample_limit)
+        } else {
+            engine
+        }
+    }
+}
+
+#[async_trait]
+impl PromqlHandler for Instance {
+    async fn query(&self, database: &str, query: &str, time: i64) -> ServerResult<Value> {
+        self.run_as_process(database, query, async {
+            self.promql_engine(database)
+                .instant_query(query, time)
+                .await
+                .context(error::ExecutePromqlSnafu)
+        })
+        .await
+    }
+
+    async fn query_range(
+        &self,
+        database: &str,
+        query: &str,
+        range: EvalRange,
+    ) -> ServerResult<Value> {
+        self.run_as_process(database, query, async {
+            self.promql_engine(database)
+                .range_query(query, range)
+                .await
+                .context(error::ExecutePromqlSnafu)
+        })
+        .await
+    }
+
+    async fn series(
+        &self,
+        database: &str,
+        matches: &[String],
+        start: i64,
+        end: i64,
+    ) -> ServerResult<Vec<Labels>> {
+        let query = format!("series {:?}", matches);
+        self.run_as_process(database, &query, async {
+            self.promql_engine(database)
+                .series(matches, start, end)
+                .await
+                .context(error::ExecutePromqlSnafu)
+        })
+        .await
+    }
+
+    async fn label_names(
+        &self,
+        database: &str,
+        matches: &[String],
+        start: i64,
+        end: i64,
+    ) -> ServerResult<Vec<String>> {
+        let query = format!("label names {:?}", matches);
+        self.run_as_process(database, &query, async {
+            self.promql_engine(database)
+                .label_names(matches, start, end)
+                .await
+                .context(error::ExecutePromqlSnafu)
+        })
+        .await
+    }
+
+    async fn label_values(
+        &self,
+        database: &str,
+        name: &str,
+        matches: &[String],
+        start: i64,
+        end: i64,
+    ) -> ServerResult<Vec<String>> {
+        let query = format!("label values {} {:?}", name, matches);
+        self.run_as_process(database, &query, async {
+            self.promql_engine(database)
+                .label_values(name, matches, start, end)
+                .await
+                .context(error::ExecutePromqlSnafu)
+        })
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use api::prometheus::remote::WriteRequest;
+    use api::v1::CreateDatabaseExpr;
+    use common_error::prelude::{ErrorExt, StatusCode};
+    use promql::value::{Sample, Series, VectorElement};
+    use servers::prometheus;
+    use servers::query_handler::PrometheusProtocolHandler;
+    use session::context::QueryContext;
+
+    use super::*;
+    use crate::tests;
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_promql_query() {
+        common_telemetry::init_default_ut_logging();
+        let instance = tests::create_frontend_instance().await;
+
+        let db = "prometheus";
+        instance
+            .handle_create_database(CreateDatabaseExpr {
+                database_name: db.to_string(),
+            })
+            .await
+            .unwrap();
+        let write_request = WriteRequest {
+            timeseries: prometheus::mock_timeseries(),
+            ..Default::default()
+        };
+        instance.write(db, write_request).await.unwrap();
+
+        let value = instance.query(db, "metric1", 2000).await.unwrap();
+        assert_eq!(
+            Value::Vector(vec![VectorElement {
+                labels: labels(&[("__name__", "metric1"), ("job", "spark")]),
+                sample: Sample::new(2000, 2.0),
+            }]),
+            value
+        );
+
+        let value = instance
+            .query(db, "sum(metric3{app=~\"b.*\"}) * 2", 3000)
+            .await
+            .unwrap();
+        assert_eq!(
+            Value::Vector(vec![VectorElement {
+                labels: Labels::new(),
+                sample: Sample::new(3000, 14.0),
+            }]),
+            value
+        );
+
+        let value = instance
+            .query_range(db, "metric1", EvalRange::new(1000, 3000, 1000).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(
+            Value::Matrix(vec![Series {
+                labels: labels(&[("__name__", "metric1"), ("job", "spark")]),
+                samples: vec![
+                    Sample::new(1000, 1.0),
+                    Sample::new(2000, 2.0),
+                    Sample::new(3000, 2.0),
+                ],
+            }]),
+            value
+        );
+
+        // Queries on metrics never written return nothing.
+        let value = instance.query(db, "not_exist", 2000).await.unwrap();
+        assert_eq!(Value::Vector(vec![]), value);
+
+        let names = instance
+            .label_names(db, &[], i64::MIN, i64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(vec!["__name__", "app", "idc", "instance", "job"], names);
+
+        let values = instance
+            .label_values(db, "__name__", &[], i64::MIN, i64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(vec!["metric1", "metric2", "metric3"], values);
+
+        let series = instance
+            .series(db, &["metric3".to_string()], i64::MIN, i64::MAX)
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![labels(&[
+                ("__name__", "metric3"),
+                ("app", "biz"),
+                ("idc", "z002")
+            ])],
+            series
+        );
+
+        assert!(instance.query(db, "sum(", 2000).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_kill_promql_query() {
+        let instance = tests::create_frontend_instance().await;
+        let process_manager = instance.process_manager();
+
+        let query = instance.run_as_process(
+            "public",
+            "up",
+            futures::future::pending::<ServerResult<Value>>(),
+        );
+        let kill = async {
+            loop {
+                if let Some(process) = process_manager.list().first() {
+                    assert_eq!("up", process.query);
+                    assert_eq!("public", process.schema);
+                    process_manager
+                        .kill(process.id, &QueryContext::arc())
+                        .unwrap();
+                    return;
+                }
+                tokio::task::yield_now().await;
+            }
+        };
+        let (result, ()) = tokio::join!(query, kill);
+
+        assert_eq!(StatusCode::Cancelled, result.unwrap_err().status_code());
+        assert!(process_manager.list().is_empty());
+    }
+}
@@ -13,11 +13,14 @@
 // limitations under the License.
 
 use serde::{Deserialize, Serialize};
+use servers::tls::TlsOption;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct MysqlOptions {
     pub addr: String,
     pub runtime_size: usize,
+    #[serde(default)]
+    pub tls: TlsOption,
 }
 
 impl Default for MysqlOptions {
@@ -25,6 +28,7 @@ impl Default for MysqlOptions {
         Self {
             addr: "127.0.0.1:4002".to_string(),
             runtime_size: 2,
+            tls: TlsOption::default(),
         }
     }
 }
@@ -13,12 +13,18 @@
 // limitations under the License.
 
 use serde::{Deserialize, Serialize};
+use servers::postgres::PgAuthMethod;
+use servers::tls::TlsOption;
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct PostgresOptions {
     pub addr: String,
     pub runtime_size: usize,
-    pub check_pwd: bool,
+    #[serde(default)]
+    pub tls: TlsOption,
+    /// How clients send passwords, `md5` or `cleartext`.
+    #[serde(default)]
+    pub auth_method: PgAuthMethod,
 }
 
 impl Default for PostgresOptions {
@@ -26,7 +32,8 @@ impl Default for PostgresOptions {
         Self {
             addr: "127.0.0.1:4003".to_string(),
             runtime_size: 2,
-            check_pwd: false,
+            tls: TlsOption::default(),
+            auth_method: PgAuthMethod::default(),
         }
     }
 }
@@ -14,24 +14,34 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Default max number of samples returned by a single remote read query, same as Prometheus.
+pub const DEFAULT_READ_SAMPLE_LIMIT: usize = 50_000_000;
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
 pub struct PrometheusOptions {
     pub enable: bool,
+    /// Max number of samples returned by a single remote read query, 0 means no limit.
+    pub read_sample_limit: usize,
 }
 
 impl Default for PrometheusOptions {
     fn default() -> Self {
-        Self { enable: true }
+        Self {
+            enable: true,
+            read_sample_limit: DEFAULT_READ_SAMPLE_LIMIT,
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::PrometheusOptions;
+    use super::{PrometheusOptions, DEFAULT_READ_SAMPLE_LIMIT};
 
     #[test]
     fn test_prometheus_options() {
         let default = PrometheusOptions::default();
         assert!(default.enable);
+        assert_eq!(DEFAULT_READ_SAMPLE_LIMIT, default.read_sample_limit);
     }
 }
@@ -17,12 +17,16 @@ use std::sync::Arc;
 
 use common_runtime::Builder as RuntimeBuilder;
 use common_telemetry::info;
+use servers::auth::user_provider_from_option;
 use servers::grpc::GrpcServer;
 use servers::http::HttpServer;
 use servers::mysql::server::MysqlServer;
 use servers::opentsdb::OpentsdbServer;
 use servers::postgres::PostgresServer;
 use servers::server::Server;
+use servers::tls::{
+    maybe_watch_tls_config, ReloadableTlsServerConfig, TlsOption, TlsServerConfigRef,
+};
 use snafu::ResultExt;
 use tokio::try_join;
 
@@ -40,6 +44,13 @@ impl Services {
         T: FrontendInstance,
     {
         info!("Starting frontend servers");
+        let user_provider = opts
+            .user_provider
+            .as_deref()
+            .map(user_provider_from_option)
+            .transpose()
+            .context(error::IllegalAuthConfigSnafu)?;
+
         let grpc_server_and_addr = if let Some(opts) = &opts.grpc_options {
             let grpc_addr = parse_addr(&opts.addr)?;
 
@@ -51,7 +62,8 @@ impl Services {
                     .context(error::RuntimeResourceSnafu)?,
             );
 
-            let grpc_server = GrpcServer::new(instance.clone(), instance.clone(), grpc_runtime);
+            let mut grpc_server = GrpcServer::new(instance.clone(), instance.clone(), grpc_runtime);
+            grpc_server.set_tls_server_config(tls_server_config(&opts.tls)?);
 
             Some((Box::new(grpc_server) as _, grpc_addr))
         } else {
@@ -69,7 +81,12 @@ impl Services {
                     .context(error::RuntimeResourceSnafu)?,
             );
 
-            let mysql_server = MysqlServer::create_server(instance.clone(), mysql_io_runtime);
+            let mysql_server = MysqlServer::create_server(
+                instance.clone(),
+                mysql_io_runtime,
+                tls_server_config(&opts.tls)?,
+                user_provider.clone(),
+            );
 
             Some((mysql_server, mysql_addr))
         } else {
@@ -89,8 +106,10 @@ impl Services {
 
             let pg_server = Box::new(PostgresServer::new(
                 instance.clone(),
-                opts.check_pwd,
                 pg_io_runtime,
+                tls_server_config(&opts.tls)?,
+                user_provider.clone(),
+                opts.auth_method,
             )) as Box<dyn Server>;
 
             Some((pg_server, pg_addr))
@@ -120,6 +139,7 @@ impl Services {
             let http_addr = parse_addr(&http_options.addr)?;
 
             let mut http_server = HttpServer::new(instance.clone(), http_options.clone());
+            http_server.set_tls_server_config(tls_server_config(&http_options.tls)?);
             if opentsdb_server_and_addr.is_some() {
                 http_server.set_opentsdb_handler(instance.clone());
             }
@@ -132,11 +152,15 @@ impl Services {
 
             if matches!(
                 opts.prometheus_options,
-                Some(PrometheusOptions { enable: true })
+                Some(PrometheusOptions { enable: true, .. })
             ) {
                 http_server.set_prom_handler(instance.clone());
+                http_server.set_promql_handler(instance.clone());
             }
             http_server.set_script_handler(instance.clone());
+            if let Some(user_provider) = user_provider {
+                http_server.set_user_provider(user_provider);
+            }
 
             Some((Box::new(http_server) as _, http_addr))
         } else {
@@ -155,6 +179,15 @@ impl Services {
     }
 }
 
+/// Builds the TLS server config, and reloads it on file changes if `watch` is set.
+fn tls_server_config(option: &TlsOption) -> Result<TlsServerConfigRef> {
+    let tls_server_config = Arc::new(
+        ReloadableTlsServerConfig::try_new(option.clone()).context(error::IllegalTlsConfigSnafu)?,
+    );
+    maybe_watch_tls_config(tls_server_config.clone()).context(error::IllegalTlsConfigSnafu)?;
+    Ok(tls_server_config)
+}
+
 fn parse_addr(addr: &str) -> Result<SocketAddr> {
     addr.parse().context(error::ParseAddrSnafu { addr })
 }
@@ -160,6 +160,7 @@ fn partition_insert_request(
                     schema_name: schema_name.to_string(),
                     table_name: table_name.to_string(),
                     columns_values,
+                    region_number: region_id,
                 },
             )
         })
@@ -391,6 +392,7 @@ mod tests {
             schema_name: DEFAULT_SCHEMA_NAME.to_string(),
             table_name: "demo".to_string(),
             columns_values,
+            region_number: 0,
         }
     }
 
@@ -418,6 +420,7 @@ mod tests {
             schema_name: DEFAULT_SCHEMA_NAME.to_string(),
             table_name: "demo".to_string(),
             columns_values,
+            region_number: 0,
         }
     }
 
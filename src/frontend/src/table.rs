@@ -18,11 +18,12 @@ use std::any::Any;
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
-use api::v1::AlterExpr;
+use api::v1::{AlterExpr, CompactTableExpr, FlushTableExpr};
 use async_trait::async_trait;
-use client::admin::Admin;
+use client::admin::{admin_result_to_output, Admin};
 use client::Database;
 use common_catalog::consts::DEFAULT_CATALOG_NAME;
+use common_query::cancellation::CancellationToken;
 use common_query::error::Result as QueryResult;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::{PhysicalPlan, PhysicalPlanRef};
@@ -39,6 +40,9 @@ use datatypes::prelude::Value;
 use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
 use meta_client::rpc::{Peer, TableName};
 use snafu::prelude::*;
+use sql::ast::{Ident, Value as SqlValue};
+use sql::statements::create::{PartitionEntry, Partitions};
+use sql::statements::value_to_sql_value;
 use store_api::storage::RegionNumber;
 use table::error::Error as TableError;
 use table::metadata::{FilterPushDownType, TableInfoRef};
@@ -60,6 +64,8 @@ use crate::table::scan::{DatanodeInstance, TableScanPlan};
 pub mod insert;
 pub(crate) mod scan;
 
+const MAXVALUE: &str = "MAXVALUE";
+
 #[derive(Clone)]
 pub struct DistTable {
     table_name: TableName,
@@ -110,6 +116,9 @@ impl Table for DistTable {
             .await
             .map_err(TableError::new)?;
 
+        // Requests to datanodes are sent while executing the plan, possibly in other tasks,
+        // so capture the token of the running query here.
+        let cancellation = CancellationToken::current();
         let mut partition_execs = Vec::with_capacity(datanodes.len());
         for (datanode, _regions) in datanodes.iter() {
             let client = self.datanode_clients.get_client(datanode).await;
@@ -124,6 +133,7 @@ impl Table for DistTable {
                 filters: filters.to_vec(),
                 limit,
                 batches: Arc::new(RwLock::new(None)),
+                cancellation: cancellation.clone(),
             }));
         }
 
@@ -274,7 +284,8 @@ impl DistTable {
         Ok(datanodes)
     }
 
-    async fn find_partition_rule(&self) -> Result<PartitionRuleRef<Error>> {
+    /// Finds the partitions of all regions, sorted by their bounds.
+    async fn find_partition_defs(&self) -> Result<Vec<(u64, PartitionDef)>> {
         let route = self.table_routes.get_route(&self.table_name).await?;
         ensure!(
             !route.region_routes.is_empty(),
@@ -307,14 +318,19 @@ impl DistTable {
                 err_msg: "partition columns of all regions are not the same"
             }
         );
-        let partition_columns = partitions[0].1.partition_columns();
         ensure!(
-            !partition_columns.is_empty(),
+            !partitions[0].1.partition_columns().is_empty(),
             error::IllegalTableRoutesDataSnafu {
                 table_name: self.table_name.to_string(),
                 err_msg: "no partition columns found"
             }
         );
+        Ok(partitions)
+    }
+
+    async fn find_partition_rule(&self) -> Result<PartitionRuleRef<Error>> {
+        let partitions = self.find_partition_defs().await?;
+        let partition_columns = partitions[0].1.partition_columns();
 
         let regions = partitions
             .iter()
@@ -353,6 +369,44 @@ impl DistTable {
         Ok(partition_rule)
     }
 
+    /// Returns the partition rule of the table as the `PARTITION BY` clause of `CREATE TABLE`,
+    /// or `None` if the table has only one partition, which is the default of creating table.
+    pub(crate) async fn partitions(&self) -> Result<Option<Partitions>> {
+        let partitions = self.find_partition_defs().await?;
+        if partitions.len() == 1 {
+            return Ok(None);
+        }
+
+        let column_list = partitions[0]
+            .1
+            .partition_columns()
+            .iter()
+            .map(|c| Ident::with_quote('"', c))
+            .collect::<Vec<_>>();
+        let mut entries = Vec::with_capacity(partitions.len());
+        for (i, (_, partition)) in partitions.iter().enumerate() {
+            let value_list = partition
+                .partition_bounds()
+                .iter()
+                .map(|bound| match bound {
+                    PartitionBound::Value(v) => {
+                        value_to_sql_value(v).context(error::ConvertPartitionBoundSnafu)
+                    }
+                    PartitionBound::MaxValue => Ok(SqlValue::Number(MAXVALUE.to_string(), false)),
+                })
+                .collect::<Result<Vec<_>>>()?;
+            entries.push(PartitionEntry {
+                // Names of partitions are not persisted, so we name them by their orders.
+                name: Ident::new(format!("r{}", i)),
+                value_list,
+            });
+        }
+        Ok(Some(Partitions {
+            column_list,
+            entries,
+        }))
+    }
+
     /// Define a `alter_by_expr` instead of impl [`Table::alter`] to avoid redundant conversion between  
     /// [`table::requests::AlterTableRequest`] and [`AlterExpr`].
     pub(crate) async fn alter_by_expr(&self, expr: AlterExpr) -> Result<()> {
@@ -382,6 +436,53 @@ impl DistTable {
         }
         Ok(())
     }
+
+    /// Flushes the regions of the table on all the datanodes it is distributed to.
+    pub(crate) async fn flush_by_expr(&self, expr: FlushTableExpr) -> Result<()> {
+        for admin in self.leader_admins().await? {
+            debug!("Sent flush table {:?} to {:?}", expr, admin);
+            let result = admin
+                .flush_table(expr.clone())
+                .await
+                .and_then(admin_result_to_output)
+                .context(RequestDatanodeSnafu)?;
+            debug!("Flush table result: {:?}", result);
+        }
+        Ok(())
+    }
+
+    /// Compacts the regions of the table on all the datanodes it is distributed to.
+    pub(crate) async fn compact_by_expr(&self, expr: CompactTableExpr) -> Result<()> {
+        for admin in self.leader_admins().await? {
+            debug!("Sent compact table {:?} to {:?}", expr, admin);
+            let result = admin
+                .compact_table(expr.clone())
+                .await
+                .and_then(admin_result_to_output)
+                .context(RequestDatanodeSnafu)?;
+            debug!("Compact table result: {:?}", result);
+        }
+        Ok(())
+    }
+
+    async fn leader_admins(&self) -> Result<Vec<Admin>> {
+        let table_routes = self.table_routes.get_route(&self.table_name).await?;
+        let leaders = table_routes.find_leaders();
+        ensure!(
+            !leaders.is_empty(),
+            LeaderNotFoundSnafu {
+                table: self.table_name.to_string()
+            }
+        );
+        let mut admins = Vec::with_capacity(leaders.len());
+        for datanode in leaders {
+            admins.push(Admin::new(
+                DEFAULT_CATALOG_NAME,
+                self.datanode_clients.get_client(&datanode).await,
+            ));
+        }
+        Ok(admins)
+    }
 }
 
 fn project_schema(table_schema: SchemaRef, projection: &Option<Vec<usize>>) -> SchemaRef {
@@ -471,6 +572,7 @@ struct PartitionExec {
     filters: Vec<Expr>,
     limit: Option<usize>,
     batches: Arc<RwLock<Option<RecordBatches>>>,
+    cancellation: CancellationToken,
 }
 
 impl PartitionExec {
@@ -490,7 +592,12 @@ impl PartitionExec {
             filters: self.filters.clone(),
             limit: self.limit,
         };
-        let result = self.datanode_instance.grpc_table_scan(plan).await?;
+        let result = tokio::select! {
+            result = self.datanode_instance.grpc_table_scan(plan) => result?,
+            err = self.cancellation.cancelled() => {
+                return Err(err).context(error::QueryCancelledSnafu);
+            }
+        };
         let _ = batches.insert(result);
         Ok(())
     }
@@ -530,6 +637,7 @@ mod test {
     use meta_srv::mocks::MockInfo;
     use meta_srv::service::store::kv::KvStoreRef;
     use meta_srv::service::store::memory::MemStore;
+    use session::context::QueryContext;
     use sql::parser::ParserContext;
     use sql::statements::statement::Statement;
     use sqlparser::dialect::GenericDialect;
@@ -737,6 +845,31 @@ mod test {
             ]
         );
         assert_eq!(range_columns_rule.regions(), &vec![1, 2, 3]);
+
+        let partitions = table.partitions().await.unwrap().unwrap();
+        assert_eq!(
+            partitions
+                .column_list
+                .iter()
+                .map(|c| c.to_string())
+                .collect::<Vec<_>>(),
+            vec![r#""a""#, r#""b""#]
+        );
+        assert_eq!(
+            partitions
+                .entries
+                .iter()
+                .map(|e| {
+                    let values = e
+                        .value_list
+                        .iter()
+                        .map(|v| v.to_string())
+                        .collect::<Vec<_>>();
+                    format!("{} ({})", e.name, values.join(", "))
+                })
+                .collect::<Vec<_>>(),
+            vec!["r0 (10, 'hz')", "r1 (50, 'sh')", "r2 (MAXVALUE, MAXVALUE)",]
+        );
     }
 
     #[tokio::test(flavor = "multi_thread")]
@@ -896,7 +1029,10 @@ mod test {
         wait_datanodes_alive(kv_store).await;
 
         let factory = DefaultCreateExprFactory {};
-        let mut expr = factory.create_expr_by_stmt(&create_table).await.unwrap();
+        let mut expr = factory
+            .create_expr_by_stmt(&create_table, QueryContext::arc())
+            .await
+            .unwrap();
         let _result = dist_instance
             .create_table(&mut expr, create_table.partitions)
             .await
@@ -926,7 +1062,7 @@ mod test {
             let start_ts = global_start_ts;
             global_start_ts += numbers.len() as i64;
 
-            insert_testing_data(&table_name, instance.clone(), numbers, start_ts).await;
+            insert_testing_data(&table_name, instance.clone(), region_id, numbers, start_ts).await;
         }
 
         let meta = TableMetaBuilder::default()
@@ -965,6 +1101,7 @@ mod test {
     async fn insert_testing_data(
         table_name: &TableName,
         dn_instance: Arc<Instance>,
+        region_number: RegionNumber,
         data: Vec<i32>,
         start_ts: i64,
     ) {
@@ -1007,6 +1144,7 @@ mod test {
                 &table_name.catalog_name,
                 &table_name.schema_name,
                 &table_name.table_name,
+                region_number,
                 values,
             )
             .await
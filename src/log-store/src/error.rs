@@ -62,6 +62,13 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to delete log file {}, source: {}", file_name, source))]
+    DeleteLog {
+        file_name: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to open log file {}, source: {}", file_name, source))]
     OpenLog {
         file_name: String,
@@ -69,12 +76,22 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Failed to write tombstone file {}, source: {}", path, source))]
+    WriteTombstone {
+        path: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("File name {} illegal", file_name))]
     FileNameIllegal {
         file_name: String,
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Illegal append batch, msg: {}", msg))]
+    IllegalBatch { msg: String, backtrace: Backtrace },
+
     #[snafu(display("Internal error, msg: {}", msg))]
     Internal { msg: String, backtrace: Backtrace },
 
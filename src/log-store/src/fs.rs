@@ -26,6 +26,7 @@ mod io;
 pub mod log;
 mod namespace;
 pub mod noop;
+mod tombstone;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct AppendResponseImpl {
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::fs::{File, OpenOptions};
 use std::pin::Pin;
@@ -23,13 +24,14 @@ use byteorder::{ByteOrder, LittleEndian};
 use bytes::{Bytes, BytesMut};
 use common_error::ext::BoxedError;
 use common_telemetry::logging::{error, info};
-use common_telemetry::{debug, trace};
+use common_telemetry::{debug, timer, trace};
 use futures::Stream;
 use futures_util::StreamExt;
-use snafu::ResultExt;
+use metrics::counter;
+use snafu::{OptionExt, ResultExt};
 use store_api::logstore::entry::{Encode, Entry, Id, Offset};
 use store_api::logstore::entry_stream::EntryStream;
-use store_api::logstore::namespace::Namespace;
+use store_api::logstore::namespace::{Id as NamespaceId, Namespace};
 use tokio::sync::mpsc::error::TryRecvError;
 use tokio::sync::mpsc::{Receiver, Sender as MpscSender};
 use tokio::sync::oneshot::Sender as OneshotSender;
@@ -39,15 +41,18 @@ use tokio::time;
 
 use crate::error::Error::Eof;
 use crate::error::{
-    AppendSnafu, Error, InternalSnafu, IoSnafu, OpenLogSnafu, Result, WaitWriteSnafu, WriteSnafu,
+    AppendSnafu, Error, IllegalBatchSnafu, InternalSnafu, IoSnafu, OpenLogSnafu, Result,
+    WaitWriteSnafu, WriteSnafu,
 };
 use crate::fs::chunk::{Chunk, ChunkList};
 use crate::fs::config::LogConfig;
 use crate::fs::crc::CRC_ALGO;
 use crate::fs::entry::{EntryImpl, StreamImpl};
 use crate::fs::file_name::FileName;
+use crate::fs::index::{EntryIndexRef, Location};
 use crate::fs::namespace::LocalNamespace;
 use crate::fs::AppendResponseImpl;
+use crate::metric::{METRIC_LOG_STORE_FSYNC_ELAPSED, METRIC_LOG_STORE_WRITE_BYTES};
 
 pub const CHUNK_SIZE: usize = 4096;
 const LOG_WRITER_BATCH_SIZE: usize = 16;
@@ -75,7 +80,9 @@ impl FileWriter {
         let mut futures = Vec::with_capacity(batch.len());
 
         let mut max_offset = 0;
+        let mut bytes = 0;
         for req in batch {
+            bytes += req.data.len();
             let offset = req.offset;
             let end = req.data.len() + offset;
             max_offset = max_offset.max(end);
@@ -90,11 +97,14 @@ impl FileWriter {
         futures::future::join_all(futures)
             .await
             .into_iter()
-            .collect::<Result<Vec<_>>>()
-            .map(|_| max_offset)
+            .collect::<Result<Vec<_>>>()?;
+        counter!(METRIC_LOG_STORE_WRITE_BYTES, bytes as u64);
+
+        Ok(max_offset)
     }
 
     pub async fn flush(&self) -> Result<()> {
+        let _timer = timer!(METRIC_LOG_STORE_FSYNC_ELAPSED);
         let file = self.inner.clone();
         common_runtime::spawn_blocking_write(move || file.sync_all().context(IoSnafu))
             .await
@@ -123,6 +133,10 @@ pub struct LogFile {
     max_file_size: usize,
     // buffer size for append request channel. read from config on start.
     append_buffer_size: usize,
+    // max entry id of each namespace inside current log file
+    max_entry_ids: Mutex<HashMap<NamespaceId, Id>>,
+    // index of entry locations, shared by all log files of the log store
+    index: EntryIndexRef,
 }
 
 impl Drop for LogFile {
@@ -133,8 +147,13 @@ impl Drop for LogFile {
 }
 
 impl LogFile {
-    /// Opens a file in path with given log config.
-    pub async fn open(path: impl Into<String>, config: &LogConfig) -> Result<Self> {
+    /// Opens a file in path with given log config, locations of entries inside the file
+    /// are added to `index` while replaying.
+    pub async fn open(
+        path: impl Into<String>,
+        config: &LogConfig,
+        index: EntryIndexRef,
+    ) -> Result<Self> {
         let path = path.into();
         let file = OpenOptions::new()
             .write(true)
@@ -156,6 +175,8 @@ impl LogFile {
             join_handle: Mutex::new(None),
             state: Arc::new(State::default()),
             append_buffer_size: config.append_buffer_size,
+            max_entry_ids: Mutex::new(HashMap::new()),
+            index,
         };
 
         let metadata = log.writer.inner.metadata().context(IoSnafu)?;
@@ -338,7 +359,7 @@ impl LogFile {
         let ns = LocalNamespace::default();
         let mut stream = self.create_stream(
             // TODO(hl): LocalNamespace should be filled
-            &ns, 0,
+            &ns, 0, 0,
         );
 
         let mut last_offset = 0usize;
@@ -347,8 +368,14 @@ impl LogFile {
             match res {
                 Ok(entries) => {
                     for e in entries {
+                        self.index.add_entry_id(
+                            e.namespace_id,
+                            e.id(),
+                            Location::new(self.name, last_offset),
+                        );
                         last_offset += e.len();
                         last_entry_id = Some(e.id());
+                        self.update_max_entry_id(e.namespace_id, e.id());
                     }
                 }
                 Err(e) => {
@@ -365,6 +392,8 @@ impl LogFile {
     }
 
     /// Creates a reader stream that asynchronously generates entries start from given entry id.
+    /// The stream starts decoding entries from `start_offset`, which must be the offset of an entry
+    /// inside the file.
     /// ### Notice
     /// If the entry with start entry id is not present, the first generated entry will start with
     /// the first entry with an id greater than `start_entry_id`.
@@ -372,10 +401,12 @@ impl LogFile {
         &self,
         _ns: &impl Namespace,
         start_entry_id: u64,
+        start_offset: usize,
     ) -> impl EntryStream<Entry = EntryImpl, Error = Error> + '_ {
         let length = self.state.flush_offset.load(Ordering::Relaxed);
 
-        let mut chunk_stream = file_chunk_stream(self.writer.inner.clone(), 0, length, 0);
+        let mut chunk_stream =
+            file_chunk_stream(self.writer.inner.clone(), start_offset, length, 0);
         let entry_stream = stream!({
             let mut chunks = ChunkList::new();
             while let Some(chunk) = chunk_stream.next().await {
@@ -418,6 +449,79 @@ impl LogFile {
         if self.state.is_stopped() {
             return Err(Error::Eof);
         }
+        let entry_id = e.id();
+        let namespace_id = e.namespace().id();
+        let serialized = Self::serialize_entry(e)?;
+
+        if serialized.len() + self.state.write_offset() > self.max_file_size {
+            return Err(Error::Eof);
+        }
+
+        // Record the entry id before it's actually written, so that the file won't be
+        // considered obsolete while the entry is still in flight.
+        self.update_max_entry_id(namespace_id, entry_id);
+
+        let resp = self.send_request(serialized.freeze(), entry_id).await?;
+        self.index.add_entry_id(
+            namespace_id,
+            entry_id,
+            Location::new(self.name, resp.offset),
+        );
+        Ok(resp)
+    }
+
+    /// Appends a batch of entries to `LogFile` in one write request, so they are flushed
+    /// to disk together. Returns the id and offset of the first entry in the batch.
+    pub async fn append_batch<T: Entry>(&self, entries: &[T]) -> Result<AppendResponseImpl>
+    where
+        T: Encode<Error = Error>,
+    {
+        if self.state.is_stopped() {
+            return Err(Error::Eof);
+        }
+        let first_entry_id = entries
+            .first()
+            .context(IllegalBatchSnafu {
+                msg: "batch is empty",
+            })?
+            .id();
+
+        let mut data = BytesMut::new();
+        // (namespace id, entry id, offset inside the batch) of each entry.
+        let mut entry_offsets = Vec::with_capacity(entries.len());
+        for e in entries {
+            entry_offsets.push((e.namespace().id(), e.id(), data.len()));
+            data.extend_from_slice(&Self::serialize_entry(e)?);
+        }
+
+        if data.len() + self.state.write_offset() > self.max_file_size {
+            return Err(Error::Eof);
+        }
+
+        for (namespace_id, entry_id, _) in &entry_offsets {
+            self.update_max_entry_id(*namespace_id, *entry_id);
+        }
+
+        let last_entry_id = entries.last().unwrap().id();
+        let resp = self.send_request(data.freeze(), last_entry_id).await?;
+        for (namespace_id, entry_id, offset) in entry_offsets {
+            self.index.add_entry_id(
+                namespace_id,
+                entry_id,
+                Location::new(self.name, resp.offset + offset),
+            );
+        }
+
+        Ok(AppendResponseImpl {
+            entry_id: first_entry_id,
+            offset: resp.offset,
+        })
+    }
+
+    fn serialize_entry<T: Entry>(e: &T) -> Result<BytesMut>
+    where
+        T: Encode<Error = Error>,
+    {
         let entry_id = e.id();
         let mut serialized = BytesMut::with_capacity(e.encoded_size());
         e.encode_to(&mut serialized)
@@ -425,21 +529,22 @@ impl LogFile {
             .context(AppendSnafu)?;
         let size = serialized.len();
 
-        if size + self.state.write_offset() > self.max_file_size {
-            return Err(Error::Eof);
-        }
-
         // rewrite encoded data
         LittleEndian::write_u64(&mut serialized[0..8], entry_id);
         let checksum = CRC_ALGO.checksum(&serialized[0..size - 4]);
         LittleEndian::write_u32(&mut serialized[size - 4..], checksum);
+        Ok(serialized)
+    }
 
+    /// Sends data to the write task and waits until it's flushed, `entry_id` is the id of
+    /// the last entry inside `data`.
+    async fn send_request(&self, data: Bytes, entry_id: Id) -> Result<AppendResponseImpl> {
         let (tx, rx) = oneshot::channel();
         self.pending_request_tx
             .as_ref()
             .expect("Call start before write to LogFile!")
             .send(AppendRequest {
-                data: serialized.freeze(),
+                data,
                 tx,
                 offset: 0,
                 id: entry_id,
@@ -491,6 +596,40 @@ impl LogFile {
     pub fn last_entry_id(&self) -> Id {
         self.state.last_entry_id.load(Ordering::Acquire)
     }
+
+    fn update_max_entry_id(&self, namespace_id: NamespaceId, entry_id: Id) {
+        let mut max_entry_ids = self.max_entry_ids.lock().unwrap();
+        let max_id = max_entry_ids.entry(namespace_id).or_insert(entry_id);
+        if *max_id < entry_id {
+            *max_id = entry_id;
+        }
+    }
+
+    /// Returns ids of namespaces that have entries inside current log file.
+    pub fn namespace_ids(&self) -> Vec<NamespaceId> {
+        self.max_entry_ids.lock().unwrap().keys().copied().collect()
+    }
+
+    /// Returns the max entry id of given namespace inside current log file.
+    pub fn max_entry_id(&self, namespace_id: NamespaceId) -> Option<Id> {
+        self.max_entry_ids
+            .lock()
+            .unwrap()
+            .get(&namespace_id)
+            .copied()
+    }
+
+    /// Returns true if all entries inside current log file are obsolete, i.e. for each namespace
+    /// in this file, the max entry id is not greater than the obsolete id of that namespace.
+    pub fn is_obsolete(&self, obsolete_ids: &HashMap<NamespaceId, Id>) -> bool {
+        let max_entry_ids = self.max_entry_ids.lock().unwrap();
+        max_entry_ids.iter().all(|(namespace_id, max_id)| {
+            obsolete_ids
+                .get(namespace_id)
+                .map(|obsolete_id| max_id <= obsolete_id)
+                .unwrap_or(false)
+        })
+    }
 }
 
 impl Debug for LogFile {
@@ -654,6 +793,7 @@ mod tests {
     use tokio::io::AsyncWriteExt;
 
     use super::*;
+    use crate::fs::index::MemoryIndex;
     use crate::fs::namespace::LocalNamespace;
 
     #[tokio::test]
@@ -666,7 +806,7 @@ mod tests {
         let path = path_buf.to_str().unwrap().to_string();
         File::create(path.as_str()).unwrap();
 
-        let mut file = LogFile::open(path.clone(), &config)
+        let mut file = LogFile::open(path.clone(), &config, Arc::new(MemoryIndex::new()))
             .await
             .unwrap_or_else(|_| panic!("Failed to open file: {}", path));
         file.start().await.expect("Failed to start log file");
@@ -713,7 +853,7 @@ mod tests {
         );
 
         let ns = LocalNamespace::new(42);
-        let mut stream = file.create_stream(&ns, 0);
+        let mut stream = file.create_stream(&ns, 0, 0);
         let mut data = vec![];
 
         while let Some(v) = stream.next().await {
@@ -855,7 +995,7 @@ mod tests {
         let path = path_buf.to_str().unwrap().to_string();
         File::create(path.as_str()).unwrap();
 
-        let mut file = LogFile::open(path.clone(), &config)
+        let mut file = LogFile::open(path.clone(), &config, Arc::new(MemoryIndex::new()))
             .await
             .unwrap_or_else(|_| panic!("Failed to open file: {}", path));
 
@@ -12,10 +12,12 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
-use std::sync::RwLock;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Debug;
+use std::sync::{Arc, RwLock};
 
 use store_api::logstore::entry::{Id, Offset};
+use store_api::logstore::namespace::Id as NamespaceId;
 
 use crate::error::Result;
 use crate::fs::file_name::FileName;
@@ -26,42 +28,84 @@ pub struct Location {
     pub offset: Offset,
 }
 
-#[allow(dead_code)]
 impl Location {
     pub fn new(file_name: FileName, offset: Offset) -> Self {
         Self { file_name, offset }
     }
 }
 
-/// In-memory entry id to offset index.
-pub trait EntryIndex {
+pub type EntryIndexRef = Arc<dyn EntryIndex>;
+
+/// Index from entry id of namespace to the location of entry in log files.
+pub trait EntryIndex: Send + Sync + Debug {
     /// Add entry id to offset mapping.
-    fn add_entry_id(&self, id: Id, loc: Location) -> Option<Location>;
+    fn add_entry_id(&self, ns: NamespaceId, id: Id, loc: Location) -> Option<Location>;
 
     /// Find offset by entry id.
-    fn find_offset_by_id(&self, id: Id) -> Result<Option<Location>>;
+    fn find_offset_by_id(&self, ns: NamespaceId, id: Id) -> Result<Option<Location>>;
+
+    /// Find the location of the first entry of `ns` whose id is not less than `id`.
+    fn seek(&self, ns: NamespaceId, id: Id) -> Result<Option<Location>>;
+
+    /// Remove entries of `ns` whose ids are not greater than `id` from the index.
+    fn remove_until(&self, ns: NamespaceId, id: Id);
+
+    /// Remove all entries of `ns` from the index.
+    fn remove_namespace(&self, ns: NamespaceId);
 }
 
+#[derive(Debug, Default)]
 pub struct MemoryIndex {
-    map: RwLock<BTreeMap<Id, Location>>,
+    map: RwLock<HashMap<NamespaceId, BTreeMap<Id, Location>>>,
 }
 
-#[allow(dead_code)]
 impl MemoryIndex {
     pub fn new() -> Self {
-        Self {
-            map: RwLock::new(BTreeMap::new()),
-        }
+        Self::default()
     }
 }
 
 impl EntryIndex for MemoryIndex {
-    fn add_entry_id(&self, id: Id, loc: Location) -> Option<Location> {
-        self.map.write().unwrap().insert(id, loc)
+    fn add_entry_id(&self, ns: NamespaceId, id: Id, loc: Location) -> Option<Location> {
+        self.map
+            .write()
+            .unwrap()
+            .entry(ns)
+            .or_default()
+            .insert(id, loc)
+    }
+
+    fn find_offset_by_id(&self, ns: NamespaceId, id: Id) -> Result<Option<Location>> {
+        Ok(self
+            .map
+            .read()
+            .unwrap()
+            .get(&ns)
+            .and_then(|entries| entries.get(&id).cloned()))
     }
 
-    fn find_offset_by_id(&self, id: Id) -> Result<Option<Location>> {
-        Ok(self.map.read().unwrap().get(&id).cloned())
+    fn seek(&self, ns: NamespaceId, id: Id) -> Result<Option<Location>> {
+        Ok(self
+            .map
+            .read()
+            .unwrap()
+            .get(&ns)
+            .and_then(|entries| entries.range(id..).next().map(|(_, loc)| *loc)))
+    }
+
+    fn remove_until(&self, ns: NamespaceId, id: Id) {
+        let mut map = self.map.write().unwrap();
+        if let Some(entries) = map.get_mut(&ns) {
+            if id == Id::MAX {
+                entries.clear();
+            } else {
+                *entries = entries.split_off(&(id + 1));
+            }
+        }
+    }
+
+    fn remove_namespace(&self, ns: NamespaceId) {
+        self.map.write().unwrap().remove(&ns);
     }
 }
 
@@ -72,11 +116,40 @@ mod tests {
     #[test]
     pub fn test_entry() {
         let index = MemoryIndex::new();
-        index.add_entry_id(1, Location::new(FileName::log(0), 1));
+        index.add_entry_id(42, 1, Location::new(FileName::log(0), 1));
         assert_eq!(
             Location::new(FileName::log(0), 1),
-            index.find_offset_by_id(1).unwrap().unwrap()
+            index.find_offset_by_id(42, 1).unwrap().unwrap()
+        );
+        assert_eq!(None, index.find_offset_by_id(42, 2).unwrap());
+        assert_eq!(None, index.find_offset_by_id(43, 1).unwrap());
+    }
+
+    #[test]
+    pub fn test_seek_and_remove() {
+        let index = MemoryIndex::new();
+        for id in 0..4 {
+            index.add_entry_id(42, id * 2, Location::new(FileName::log(id), 0));
+        }
+        index.add_entry_id(43, 1, Location::new(FileName::log(1), 128));
+
+        assert_eq!(
+            Some(Location::new(FileName::log(2), 0)),
+            index.seek(42, 3).unwrap()
         );
-        assert_eq!(None, index.find_offset_by_id(2).unwrap());
+        assert_eq!(
+            Some(Location::new(FileName::log(1), 128)),
+            index.seek(43, 0).unwrap()
+        );
+        assert_eq!(None, index.seek(42, 7).unwrap());
+
+        index.remove_until(42, 4);
+        assert_eq!(
+            Some(Location::new(FileName::log(3), 0)),
+            index.seek(42, 0).unwrap()
+        );
+
+        index.remove_namespace(43);
+        assert_eq!(None, index.seek(43, 0).unwrap());
     }
 }
@@ -12,30 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 use arc_swap::ArcSwap;
 use async_stream::stream;
 use common_telemetry::{error, info, warn};
 use futures::{pin_mut, StreamExt};
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::logstore::entry::{Encode, Entry, Id};
 use store_api::logstore::entry_stream::SendableEntryStream;
 use store_api::logstore::namespace::{Id as NamespaceId, Namespace};
 use store_api::logstore::LogStore;
-use tokio::sync::RwLock;
+use tokio::sync::{Mutex as AsyncMutex, RwLock};
 
 use crate::error::{
-    CreateDirSnafu, DuplicateFileSnafu, Error, FileNameIllegalSnafu, InternalSnafu, IoSnafu,
-    ReadPathSnafu, Result,
+    CreateDirSnafu, DeleteLogSnafu, DuplicateFileSnafu, Error, FileNameIllegalSnafu,
+    IllegalBatchSnafu, InternalSnafu, IoSnafu, ReadPathSnafu, Result,
 };
 use crate::fs::config::LogConfig;
 use crate::fs::entry::EntryImpl;
 use crate::fs::file::{LogFile, LogFileRef};
 use crate::fs::file_name::FileName;
+use crate::fs::index::{EntryIndexRef, MemoryIndex};
 use crate::fs::namespace::LocalNamespace;
+use crate::fs::tombstone::{self, Tombstones};
 use crate::fs::AppendResponseImpl;
 
 type FileMap = BTreeMap<u64, LogFileRef>;
@@ -45,6 +47,15 @@ pub struct LocalFileLogStore {
     files: RwLock<FileMap>,
     active: ArcSwap<LogFile>,
     config: LogConfig,
+    /// Entries with ids not greater than the obsolete id of their namespace are obsolete.
+    obsolete_ids: Mutex<HashMap<NamespaceId, Id>>,
+    /// Index from (namespace, entry id) to the location of entry.
+    index: EntryIndexRef,
+    /// Ids of existing namespaces.
+    namespaces: Mutex<BTreeSet<NamespaceId>>,
+    /// Deleted namespaces whose entries still exist in log files, they are also persisted
+    /// in the tombstone file.
+    tombstones: AsyncMutex<Tombstones>,
 }
 
 impl LocalFileLogStore {
@@ -57,10 +68,11 @@ impl LocalFileLogStore {
                 path: &config.log_file_dir,
             })?;
 
-        let mut files = Self::load_dir(&config.log_file_dir, config).await?;
+        let index: EntryIndexRef = Arc::new(MemoryIndex::new());
+        let mut files = Self::load_dir(&config.log_file_dir, config, &index).await?;
 
         if files.is_empty() {
-            Self::init_on_empty(&mut files, config).await?;
+            Self::init_on_empty(&mut files, config, &index).await?;
             info!("Initialized log store directory: {}", config.log_file_dir)
         }
 
@@ -100,24 +112,65 @@ impl LocalFileLogStore {
         );
 
         let active_file_cloned = active_file.clone();
-        Ok(Self {
+        let tombstones = tombstone::load(&config.log_file_dir).await?;
+        // A deleted namespace only exists if it has entries written after the deletion.
+        let namespaces = files
+            .values()
+            .flat_map(|file| {
+                file.namespace_ids()
+                    .into_iter()
+                    .filter(|id| match tombstones.get(id) {
+                        Some(deleted_id) => file
+                            .max_entry_id(*id)
+                            .map(|max_id| max_id > *deleted_id)
+                            .unwrap_or(false),
+                        None => true,
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        let mut obsolete_ids = HashMap::new();
+        for (namespace_id, deleted_id) in &tombstones {
+            // Entries of deleted namespaces are still obsolete after restart.
+            obsolete_ids.insert(*namespace_id, *deleted_id);
+            index.remove_until(*namespace_id, *deleted_id);
+        }
+        info!("Loaded tombstones of deleted namespaces: {:?}", tombstones);
+
+        let logstore = Self {
             files: RwLock::new(files),
             active: ArcSwap::new(active_file_cloned),
             config: config.clone(),
-        })
+            obsolete_ids: Mutex::new(obsolete_ids),
+            index,
+            namespaces: Mutex::new(namespaces),
+            tombstones: AsyncMutex::new(tombstones),
+        };
+        // Files containing entries of deleted namespaces might be purged before the
+        // tombstones are removed.
+        logstore.prune_tombstones().await?;
+        Ok(logstore)
     }
 
-    pub async fn init_on_empty(files: &mut FileMap, config: &LogConfig) -> Result<()> {
+    pub async fn init_on_empty(
+        files: &mut FileMap,
+        config: &LogConfig,
+        index: &EntryIndexRef,
+    ) -> Result<()> {
         let path = Path::new(&config.log_file_dir).join(FileName::log(0).to_string());
         let file_path = path.to_str().context(FileNameIllegalSnafu {
             file_name: config.log_file_dir.clone(),
         })?;
-        let file = LogFile::open(file_path, config).await?;
+        let file = LogFile::open(file_path, config, index.clone()).await?;
         files.insert(0, Arc::new(file));
         Ok(())
     }
 
-    pub async fn load_dir(path: impl AsRef<str>, config: &LogConfig) -> Result<FileMap> {
+    pub async fn load_dir(
+        path: impl AsRef<str>,
+        config: &LogConfig,
+        index: &EntryIndexRef,
+    ) -> Result<FileMap> {
         let mut map = FileMap::new();
         let mut dir = tokio::fs::read_dir(Path::new(path.as_ref()))
             .await
@@ -130,9 +183,12 @@ impl LocalFileLogStore {
             let path = path_buf.to_str().context(FileNameIllegalSnafu {
                 file_name: path.as_ref().to_string(),
             })?;
+            if tombstone::is_tombstone_file(&path_buf) {
+                continue;
+            }
             let file_name = FileName::try_from(path)?;
             let start_id = file_name.entry_id();
-            let file = LogFile::open(path, config).await?;
+            let file = LogFile::open(path, config, index.clone()).await?;
             info!("Load log store file {}: {:?}", start_id, file);
             if map.contains_key(&start_id) {
                 error!("Log file with start entry id: {} already exists", start_id);
@@ -165,7 +221,7 @@ impl LocalFileLogStore {
             file_name: self.config.log_file_dir.clone(),
         })?;
 
-        let mut new_file = LogFile::open(path, &self.config).await?;
+        let mut new_file = LogFile::open(path, &self.config, self.index.clone()).await?;
         new_file.start().await?;
 
         let new_file = Arc::new(new_file);
@@ -183,6 +239,72 @@ impl LocalFileLogStore {
     pub fn active_file(&self) -> Arc<LogFile> {
         self.active.load().clone()
     }
+
+    fn register_namespace(&self, namespace_id: NamespaceId) {
+        self.namespaces.lock().unwrap().insert(namespace_id);
+    }
+
+    fn contains_namespace(&self, namespace_id: NamespaceId) -> bool {
+        self.namespaces.lock().unwrap().contains(&namespace_id)
+    }
+
+    fn mark_obsolete(&self, namespace_id: NamespaceId, id: Id) {
+        let mut obsolete_ids = self.obsolete_ids.lock().unwrap();
+        let obsolete_id = obsolete_ids.entry(namespace_id).or_insert(id);
+        if *obsolete_id < id {
+            *obsolete_id = id;
+        }
+    }
+
+    /// Removes tombstones once no log file contains entries deleted with the namespace.
+    async fn prune_tombstones(&self) -> Result<()> {
+        let mut tombstones = self.tombstones.lock().await;
+        let files = self.files.read().await;
+        let num_tombstones = tombstones.len();
+        tombstones.retain(|namespace_id, deleted_id| {
+            files.values().any(|file| {
+                file.max_entry_id(*namespace_id)
+                    .map(|max_id| max_id <= *deleted_id)
+                    .unwrap_or(false)
+            })
+        });
+        if tombstones.len() == num_tombstones {
+            return Ok(());
+        }
+
+        tombstone::persist(&self.config.log_file_dir, &tombstones).await
+    }
+
+    /// Deletes sealed log files whose entries are all obsolete.
+    async fn purge_obsolete_files(&self) -> Result<()> {
+        // acquires lock, so no one is reading the files to purge.
+        let mut files = self.files.write().await;
+        let active = self.active_file();
+
+        let obsolete_files = {
+            let obsolete_ids = self.obsolete_ids.lock().unwrap();
+            files
+                .iter()
+                .filter(|(_, file)| {
+                    !Arc::ptr_eq(file, &active) && file.is_seal() && file.is_obsolete(&obsolete_ids)
+                })
+                .map(|(start_id, _)| *start_id)
+                .collect::<Vec<_>>()
+        };
+
+        for start_id in obsolete_files {
+            let file = files.remove(&start_id).unwrap();
+            let file_name = file.file_name();
+            let path = Path::new(&self.config.log_file_dir).join(&file_name);
+            tokio::fs::remove_file(&path)
+                .await
+                .context(DeleteLogSnafu {
+                    file_name: &file_name,
+                })?;
+            info!("Purged obsolete log file: {}", file_name);
+        }
+        Ok(()) // release lock
+    }
 }
 
 #[async_trait::async_trait]
@@ -193,6 +315,7 @@ impl LogStore for LocalFileLogStore {
     type AppendResponse = AppendResponseImpl;
 
     async fn append(&self, mut entry: Self::Entry) -> Result<Self::AppendResponse> {
+        self.register_namespace(entry.namespace_id);
         // TODO(hl): configurable retry times
         for _ in 0..3 {
             let current_active_file = self.active_file();
@@ -225,8 +348,44 @@ impl LogStore for LocalFileLogStore {
         .fail();
     }
 
-    async fn append_batch(&self, _ns: &Self::Namespace, _e: Vec<Self::Entry>) -> Result<Id> {
-        todo!()
+    async fn append_batch(&self, ns: &Self::Namespace, entries: Vec<Self::Entry>) -> Result<Id> {
+        ensure!(
+            entries.iter().all(|e| e.namespace_id == ns.id()),
+            IllegalBatchSnafu {
+                msg: format!("all entries should belong to namespace {}", ns.id()),
+            }
+        );
+        self.register_namespace(ns.id());
+
+        for _ in 0..3 {
+            let current_active_file = self.active_file();
+            match current_active_file.append_batch(&entries).await {
+                Ok(r) => return Ok(r.entry_id),
+                Err(e) => match e {
+                    Error::Eof => {
+                        self.roll_next(current_active_file.clone()).await?;
+                        info!(
+                            "Rolled to next file, retry append batch, batch size: {}",
+                            entries.len()
+                        );
+                        continue;
+                    }
+                    Error::Internal { .. } => {
+                        warn!("File closed, try new file");
+                        continue;
+                    }
+                    _ => {
+                        error!(e; "Failed to append batch to log file");
+                        return Err(e);
+                    }
+                },
+            }
+        }
+
+        return InternalSnafu {
+            msg: "Failed to append batch with max retry time exceeds",
+        }
+        .fail();
     }
 
     async fn read(
@@ -236,23 +395,37 @@ impl LogStore for LocalFileLogStore {
     ) -> Result<SendableEntryStream<'_, Self::Entry, Self::Error>> {
         let files = self.files.read().await;
         let ns = ns.clone();
+        // Entries of deleted namespaces are never read.
+        let start = if self.contains_namespace(ns.id()) {
+            self.index.seek(ns.id(), id)?
+        } else {
+            None
+        };
 
         let s = stream!({
-            for (start_id, file) in files.iter() {
-                // TODO(hl): Use index to lookup file
-                if *start_id <= id {
-                    let s = file.create_stream(&ns, id);
-                    pin_mut!(s);
-                    while let Some(entries) = s.next().await {
-                        match entries {
-                            Ok(entries) => {
-                                yield Ok(entries
-                                    .into_iter()
-                                    .filter(|e| e.namespace().id() == ns.id())
-                                    .collect::<Vec<_>>())
-                            }
-                            Err(e) => yield Err(e),
+            // Nothing to read if the index doesn't contain the start entry.
+            let files_to_read = start.map(|_| files.iter()).into_iter().flatten();
+            for (start_id, file) in files_to_read {
+                // Skips files without entries to read.
+                match file.max_entry_id(ns.id()) {
+                    Some(max_id) if max_id >= id => (),
+                    _ => continue,
+                }
+                let start_offset = match start {
+                    Some(loc) if loc.file_name.entry_id() == *start_id => loc.offset,
+                    _ => 0,
+                };
+                let s = file.create_stream(&ns, id, start_offset);
+                pin_mut!(s);
+                while let Some(entries) = s.next().await {
+                    match entries {
+                        Ok(entries) => {
+                            yield Ok(entries
+                                .into_iter()
+                                .filter(|e| e.namespace().id() == ns.id())
+                                .collect::<Vec<_>>())
                         }
+                        Err(e) => yield Err(e),
                     }
                 }
             }
@@ -261,16 +434,50 @@ impl LogStore for LocalFileLogStore {
         Ok(Box::pin(s))
     }
 
-    async fn create_namespace(&mut self, _ns: &Self::Namespace) -> Result<()> {
-        todo!()
+    async fn create_namespace(&self, ns: &Self::Namespace) -> Result<()> {
+        self.register_namespace(ns.id());
+        info!("Create namespace: {}", ns.id());
+        Ok(())
     }
 
-    async fn delete_namespace(&mut self, _ns: &Self::Namespace) -> Result<()> {
-        todo!()
+    async fn delete_namespace(&self, ns: &Self::Namespace) -> Result<()> {
+        let max_id = {
+            let files = self.files.read().await;
+            files
+                .values()
+                .filter_map(|file| file.max_entry_id(ns.id()))
+                .max()
+        };
+        if let Some(max_id) = max_id {
+            let mut tombstones = self.tombstones.lock().await;
+            tombstones.insert(ns.id(), max_id);
+            // Persist the tombstone first, so the namespace won't be recovered from its
+            // entries after restart.
+            tombstone::persist(&self.config.log_file_dir, &tombstones).await?;
+            // All existing entries of a deleted namespace are obsolete.
+            self.mark_obsolete(ns.id(), max_id);
+        }
+        self.namespaces.lock().unwrap().remove(&ns.id());
+        self.index.remove_namespace(ns.id());
+        info!("Delete namespace: {}", ns.id());
+        self.purge_obsolete_files().await?;
+        self.prune_tombstones().await
     }
 
     async fn list_namespaces(&self) -> Result<Vec<Self::Namespace>> {
-        todo!()
+        Ok(self
+            .namespaces
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|id| LocalNamespace::new(*id))
+            .collect())
+    }
+
+    async fn obsolete(&self, namespace: Self::Namespace, id: Id) -> Result<()> {
+        self.mark_obsolete(namespace.id(), id);
+        self.index.remove_until(namespace.id(), id);
+        self.purge_obsolete_files().await
     }
 
     fn entry<D: AsRef<[u8]>>(&self, data: D, id: Id, namespace: Self::Namespace) -> Self::Entry {
@@ -426,4 +633,203 @@ mod tests {
         assert_eq!(entries[0].id(), 1);
         assert_eq!(43, entries[0].namespace_id);
     }
+
+    #[tokio::test]
+    pub async fn test_purge_obsolete_files() {
+        common_telemetry::logging::init_default_ut_logging();
+        let dir = TempDir::new("greptimedb-purge").unwrap();
+        let config = LogConfig {
+            append_buffer_size: 128,
+            max_log_file_size: 128,
+            log_file_dir: dir.path().to_str().unwrap().to_string(),
+        };
+        let logstore = LocalFileLogStore::open(&config).await.unwrap();
+        // Each entry takes a whole log file.
+        for (id, ns) in [(0, 42), (1, 43), (2, 42)] {
+            logstore
+                .append(EntryImpl::new(
+                    generate_data(96),
+                    id,
+                    LocalNamespace::new(ns),
+                ))
+                .await
+                .unwrap();
+        }
+        assert_eq!(3, logstore.files.read().await.len());
+
+        logstore.obsolete(LocalNamespace::new(42), 1).await.unwrap();
+        // Only the first file is obsolete.
+        let start_ids = logstore
+            .files
+            .read()
+            .await
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(vec![1, 2], start_ids);
+
+        logstore
+            .delete_namespace(&LocalNamespace::new(43))
+            .await
+            .unwrap();
+        let start_ids = logstore
+            .files
+            .read()
+            .await
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(vec![2], start_ids);
+
+        let files_in_dir = std::fs::read_dir(dir.path()).unwrap().count();
+        assert_eq!(1, files_in_dir);
+
+        // Entries in the active file are still readable.
+        {
+            let stream = logstore.read(&LocalNamespace::new(42), 0).await.unwrap();
+            tokio::pin!(stream);
+            let entries = stream.next().await.unwrap().unwrap();
+            assert_eq!(1, entries.len());
+            assert_eq!(2, entries[0].id());
+        }
+
+        // The active file is never purged.
+        logstore.obsolete(LocalNamespace::new(42), 2).await.unwrap();
+        let start_ids = logstore
+            .files
+            .read()
+            .await
+            .keys()
+            .copied()
+            .collect::<Vec<_>>();
+        assert_eq!(vec![2], start_ids);
+    }
+
+    async fn read_entry_ids(logstore: &LocalFileLogStore, ns: u64, start: Id) -> Vec<Id> {
+        let stream = logstore
+            .read(&LocalNamespace::new(ns), start)
+            .await
+            .unwrap();
+        tokio::pin!(stream);
+        let mut ids = vec![];
+        while let Some(entries) = stream.next().await {
+            ids.extend(entries.unwrap().iter().map(|e| e.id()));
+        }
+        ids
+    }
+
+    #[tokio::test]
+    pub async fn test_append_batch() {
+        common_telemetry::logging::init_default_ut_logging();
+        let dir = TempDir::new("greptimedb-batch").unwrap();
+        let config = LogConfig {
+            append_buffer_size: 128,
+            max_log_file_size: 1024 * 1024,
+            log_file_dir: dir.path().to_str().unwrap().to_string(),
+        };
+        let logstore = LocalFileLogStore::open(&config).await.unwrap();
+        let ns = LocalNamespace::new(42);
+        let entries = (0..3)
+            .map(|id| EntryImpl::new(generate_data(32), id, ns.clone()))
+            .collect();
+        assert_eq!(0, logstore.append_batch(&ns, entries).await.unwrap());
+        logstore
+            .append(EntryImpl::new(
+                generate_data(32),
+                0,
+                LocalNamespace::new(43),
+            ))
+            .await
+            .unwrap();
+
+        // Entries of other namespaces are not allowed in the batch.
+        let entries = vec![EntryImpl::new(
+            generate_data(32),
+            1,
+            LocalNamespace::new(43),
+        )];
+        assert!(logstore.append_batch(&ns, entries).await.is_err());
+
+        assert_eq!(vec![1, 2], read_entry_ids(&logstore, 42, 1).await);
+        assert_eq!(vec![0], read_entry_ids(&logstore, 43, 0).await);
+        drop(logstore);
+
+        // The index is rebuilt after reopening.
+        let logstore = LocalFileLogStore::open(&config).await.unwrap();
+        assert_eq!(vec![1, 2], read_entry_ids(&logstore, 42, 1).await);
+        assert_eq!(vec![0], read_entry_ids(&logstore, 43, 0).await);
+    }
+
+    #[tokio::test]
+    pub async fn test_namespace_lifecycle() {
+        common_telemetry::logging::init_default_ut_logging();
+        let dir = TempDir::new("greptimedb-namespace").unwrap();
+        let config = LogConfig {
+            append_buffer_size: 128,
+            max_log_file_size: 1024 * 1024,
+            log_file_dir: dir.path().to_str().unwrap().to_string(),
+        };
+        let logstore = LocalFileLogStore::open(&config).await.unwrap();
+        logstore
+            .create_namespace(&LocalNamespace::new(42))
+            .await
+            .unwrap();
+        logstore
+            .append(EntryImpl::new(
+                generate_data(32),
+                0,
+                LocalNamespace::new(43),
+            ))
+            .await
+            .unwrap();
+        let namespaces = logstore.list_namespaces().await.unwrap();
+        assert_eq!(
+            vec![42, 43],
+            namespaces.iter().map(|ns| ns.id()).collect::<Vec<_>>()
+        );
+
+        logstore
+            .delete_namespace(&LocalNamespace::new(43))
+            .await
+            .unwrap();
+        let namespaces = logstore.list_namespaces().await.unwrap();
+        assert_eq!(
+            vec![42],
+            namespaces.iter().map(|ns| ns.id()).collect::<Vec<_>>()
+        );
+        // Entries of the deleted namespace are no longer readable.
+        assert!(read_entry_ids(&logstore, 43, 0).await.is_empty());
+        drop(logstore);
+
+        // The deleted namespace is not recovered from its entries after reopening, and
+        // namespace 42 without entries is not persisted.
+        let logstore = LocalFileLogStore::open(&config).await.unwrap();
+        let namespaces = logstore.list_namespaces().await.unwrap();
+        assert!(namespaces.is_empty());
+        assert!(read_entry_ids(&logstore, 43, 0).await.is_empty());
+
+        // Entries of the namespace created again are readable.
+        logstore
+            .create_namespace(&LocalNamespace::new(43))
+            .await
+            .unwrap();
+        logstore
+            .append(EntryImpl::new(
+                generate_data(32),
+                1,
+                LocalNamespace::new(43),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(vec![1], read_entry_ids(&logstore, 43, 0).await);
+        drop(logstore);
+
+        let logstore = LocalFileLogStore::open(&config).await.unwrap();
+        let namespaces = logstore.list_namespaces().await.unwrap();
+        assert_eq!(
+            vec![43],
+            namespaces.iter().map(|ns| ns.id()).collect::<Vec<_>>()
+        );
+        assert_eq!(vec![1], read_entry_ids(&logstore, 43, 0).await);
+    }
 }
@@ -41,7 +41,7 @@ impl LogStore for NoopLogStore {
     }
 
     async fn append_batch(&self, _ns: &Self::Namespace, _e: Vec<Self::Entry>) -> Result<Id> {
-        todo!()
+        Ok(0)
     }
 
     async fn read(
@@ -53,18 +53,22 @@ impl LogStore for NoopLogStore {
         todo!()
     }
 
-    async fn create_namespace(&mut self, _ns: &Self::Namespace) -> Result<()> {
-        todo!()
+    async fn create_namespace(&self, _ns: &Self::Namespace) -> Result<()> {
+        Ok(())
     }
 
-    async fn delete_namespace(&mut self, _ns: &Self::Namespace) -> Result<()> {
-        todo!()
+    async fn delete_namespace(&self, _ns: &Self::Namespace) -> Result<()> {
+        Ok(())
     }
 
     async fn list_namespaces(&self) -> Result<Vec<Self::Namespace>> {
         todo!()
     }
 
+    async fn obsolete(&self, _namespace: Self::Namespace, _id: Id) -> Result<()> {
+        Ok(())
+    }
+
     fn entry<D: AsRef<[u8]>>(&self, data: D, id: Id, ns: Self::Namespace) -> Self::Entry {
         EntryImpl::new(data, id, ns)
     }
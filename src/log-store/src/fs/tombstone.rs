@@ -0,0 +1,152 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tombstones of deleted namespaces.
+//!
+//! Entries of a deleted namespace stay in log files until the files are purged, so deleted
+//! namespaces are persisted in a tombstone file under the log directory. Otherwise the
+//! namespace would be recovered from its entries after restart.
+//!
+//! Each line of the tombstone file is the id of a deleted namespace and the max entry id of
+//! that namespace when it was deleted, entries not greater than that id are obsolete.
+
+use std::collections::BTreeMap;
+use std::io::ErrorKind;
+use std::path::Path;
+
+use snafu::{OptionExt, ResultExt};
+use store_api::logstore::entry::Id;
+use store_api::logstore::namespace::Id as NamespaceId;
+
+use crate::error::{CorruptedSnafu, ReadPathSnafu, Result, WriteTombstoneSnafu};
+
+/// Name of the tombstone file in the log directory.
+const TOMBSTONE_FILE_NAME: &str = "namespace.tombstone";
+/// Name of the temporary file to write before replacing the tombstone file.
+const TOMBSTONE_TMP_FILE_NAME: &str = "namespace.tombstone.tmp";
+
+/// Returns true if the file at `path` is a tombstone file instead of a log file.
+pub fn is_tombstone_file(path: &Path) -> bool {
+    match path.file_name().and_then(|name| name.to_str()) {
+        Some(name) => name == TOMBSTONE_FILE_NAME || name == TOMBSTONE_TMP_FILE_NAME,
+        None => false,
+    }
+}
+
+/// Tombstones of deleted namespaces, from namespace id to the max entry id of the namespace
+/// when it was deleted.
+pub type Tombstones = BTreeMap<NamespaceId, Id>;
+
+/// Loads tombstones from the tombstone file under `dir`, returns empty tombstones if there
+/// is no tombstone file.
+pub async fn load(dir: &str) -> Result<Tombstones> {
+    let path = Path::new(dir).join(TOMBSTONE_FILE_NAME);
+    let content = match tokio::fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Tombstones::new()),
+        Err(e) => {
+            return Err(e).context(ReadPathSnafu {
+                path: path.to_string_lossy(),
+            })
+        }
+    };
+
+    let mut tombstones = Tombstones::new();
+    for line in content.lines().filter(|line| !line.is_empty()) {
+        let (namespace_id, entry_id) = parse_line(line).with_context(|| CorruptedSnafu {
+            msg: format!("invalid line {} in tombstone file", line),
+        })?;
+        tombstones.insert(namespace_id, entry_id);
+    }
+    Ok(tombstones)
+}
+
+fn parse_line(line: &str) -> Option<(NamespaceId, Id)> {
+    let (namespace_id, entry_id) = line.split_once(' ')?;
+    Some((namespace_id.parse().ok()?, entry_id.parse().ok()?))
+}
+
+/// Replaces the tombstone file under `dir` with `tombstones`, the tombstone file is removed
+/// if `tombstones` is empty.
+pub async fn persist(dir: &str, tombstones: &Tombstones) -> Result<()> {
+    let path = Path::new(dir).join(TOMBSTONE_FILE_NAME);
+    if tombstones.is_empty() {
+        return match tokio::fs::remove_file(&path).await {
+            Err(e) if e.kind() != ErrorKind::NotFound => Err(e).context(WriteTombstoneSnafu {
+                path: path.to_string_lossy(),
+            }),
+            _ => Ok(()),
+        };
+    }
+
+    let content = tombstones
+        .iter()
+        .map(|(namespace_id, entry_id)| format!("{} {}\n", namespace_id, entry_id))
+        .collect::<String>();
+    // Write to a temporary file then rename it, so a crash won't leave a partial file.
+    let tmp_path = Path::new(dir).join(TOMBSTONE_TMP_FILE_NAME);
+    tokio::fs::write(&tmp_path, content)
+        .await
+        .context(WriteTombstoneSnafu {
+            path: tmp_path.to_string_lossy(),
+        })?;
+    tokio::fs::rename(&tmp_path, &path)
+        .await
+        .context(WriteTombstoneSnafu {
+            path: path.to_string_lossy(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::error::Error;
+
+    #[tokio::test]
+    async fn test_persist_and_load() {
+        let dir = TempDir::new("greptimedb-tombstone").unwrap();
+        let dir = dir.path().to_str().unwrap();
+        assert!(load(dir).await.unwrap().is_empty());
+
+        let tombstones = Tombstones::from([(1, 10), (42, Id::MAX)]);
+        persist(dir, &tombstones).await.unwrap();
+        assert_eq!(tombstones, load(dir).await.unwrap());
+
+        persist(dir, &Tombstones::new()).await.unwrap();
+        assert!(load(dir).await.unwrap().is_empty());
+        assert_eq!(0, std::fs::read_dir(dir).unwrap().count());
+    }
+
+    #[tokio::test]
+    async fn test_load_corrupted() {
+        let dir = TempDir::new("greptimedb-tombstone-corrupted").unwrap();
+        std::fs::write(dir.path().join(TOMBSTONE_FILE_NAME), "1 10\n42\n").unwrap();
+
+        let err = load(dir.path().to_str().unwrap()).await.unwrap_err();
+        assert!(matches!(err, Error::Corrupted { .. }));
+    }
+
+    #[test]
+    fn test_is_tombstone_file() {
+        assert!(is_tombstone_file(Path::new("/path/to/namespace.tombstone")));
+        assert!(is_tombstone_file(Path::new(
+            "/path/to/namespace.tombstone.tmp"
+        )));
+        assert!(!is_tombstone_file(Path::new(
+            "/path/to/00000000000000000000.log"
+        )));
+    }
+}
@@ -14,5 +14,6 @@
 
 pub mod error;
 pub mod fs;
+mod metric;
 
 pub mod test_util;
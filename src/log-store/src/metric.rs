@@ -0,0 +1,20 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! log store metrics
+
+/// Counter of bytes written to log files.
+pub const METRIC_LOG_STORE_WRITE_BYTES: &str = "log_store.write_bytes";
+/// Histogram of elapsed time to fsync log files.
+pub const METRIC_LOG_STORE_FSYNC_ELAPSED: &str = "log_store.fsync_elapsed";
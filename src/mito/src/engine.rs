@@ -21,15 +21,17 @@ use common_error::ext::BoxedError;
 use common_telemetry::logging;
 use datatypes::schema::SchemaRef;
 use object_store::ObjectStore;
-use snafu::{OptionExt, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use store_api::storage::{
     ColumnDescriptorBuilder, ColumnFamilyDescriptor, ColumnFamilyDescriptorBuilder, ColumnId,
-    CreateOptions, EngineContext as StorageEngineContext, OpenOptions, RegionDescriptorBuilder,
-    RegionId, RowKeyDescriptor, RowKeyDescriptorBuilder, StorageEngine,
+    CreateOptions, EngineContext as StorageEngineContext, OpenOptions, Region,
+    RegionDescriptorBuilder, RegionId, RowKeyDescriptor, RowKeyDescriptorBuilder, StorageEngine,
 };
 use table::engine::{EngineContext, TableEngine, TableReference};
 use table::metadata::{TableId, TableInfoBuilder, TableMetaBuilder, TableType, TableVersion};
-use table::requests::{AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest};
+use table::requests::{
+    self, AlterTableRequest, CreateTableRequest, DropTableRequest, OpenTableRequest,
+};
 use table::table::TableRef;
 use table::{Result as TableResult, Table};
 use tokio::sync::Mutex;
@@ -37,7 +39,8 @@ use tokio::sync::Mutex;
 use crate::config::EngineConfig;
 use crate::error::{
     self, BuildColumnDescriptorSnafu, BuildColumnFamilyDescriptorSnafu, BuildRegionDescriptorSnafu,
-    BuildRowKeyDescriptorSnafu, MissingTimestampIndexSnafu, Result, TableExistsSnafu,
+    BuildRowKeyDescriptorSnafu, EmptyRegionNumbersSnafu, MissingTimestampIndexSnafu, Result,
+    TableExistsSnafu,
 };
 use crate::table::MitoTable;
 
@@ -133,7 +136,7 @@ struct MitoEngineInner<S: StorageEngine> {
     /// All tables opened by the engine. Map key is formatted [TableReference].
     ///
     /// Writing to `tables` should also hold the `table_mutex`.
-    tables: RwLock<HashMap<String, TableRef>>,
+    tables: RwLock<HashMap<String, Arc<MitoTable<S::Region>>>>,
     object_store: ObjectStore,
     storage_engine: S,
     /// Table mutex is used to protect the operations such as creating/opening/closing
@@ -290,22 +293,27 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         )?;
 
         let table_id = request.id;
-        // TODO(dennis): supports multi regions;
-        assert_eq!(1, request.region_numbers.len());
-        let region_number = request.region_numbers[0];
-        let region_id = region_id(table_id, region_number);
-
-        let region_name = region_name(table_id, region_number);
-        let region_descriptor = RegionDescriptorBuilder::default()
-            .id(region_id)
-            .name(&region_name)
-            .row_key(row_key)
-            .default_cf(default_cf)
-            .build()
-            .context(BuildRegionDescriptorSnafu {
-                table_name,
-                region_name,
-            })?;
+        ensure!(
+            !request.region_numbers.is_empty(),
+            EmptyRegionNumbersSnafu { table_name }
+        );
+        let region_descriptors = request
+            .region_numbers
+            .iter()
+            .map(|region_number| {
+                let region_name = region_name(table_id, *region_number);
+                RegionDescriptorBuilder::default()
+                    .id(region_id(table_id, *region_number))
+                    .name(&region_name)
+                    .row_key(row_key.clone())
+                    .default_cf(default_cf.clone())
+                    .build()
+                    .context(BuildRegionDescriptorSnafu {
+                        table_name,
+                        region_name,
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
 
         let _lock = self.table_mutex.lock().await;
         // Checks again, read lock should be enough since we are guarded by the mutex.
@@ -317,24 +325,34 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             }
         }
 
+        let ttl = requests::parse_ttl(&request.table_options)
+            .context(error::InvalidTableOptionSnafu { table_name })?;
         let table_dir = table_dir(schema_name, table_name, table_id);
         let opts = CreateOptions {
             parent_dir: table_dir.clone(),
+            ttl,
         };
 
-        let region = self
-            .storage_engine
-            .create_region(&StorageEngineContext::default(), region_descriptor, &opts)
-            .await
-            .map_err(BoxedError::new)
-            .context(error::CreateRegionSnafu)?;
+        let mut regions = HashMap::with_capacity(region_descriptors.len());
+        for (region_number, region_descriptor) in
+            request.region_numbers.iter().zip(region_descriptors)
+        {
+            let region = self
+                .storage_engine
+                .create_region(&StorageEngineContext::default(), region_descriptor, &opts)
+                .await
+                .map_err(BoxedError::new)
+                .context(error::CreateRegionSnafu)?;
+            regions.insert(*region_number, region);
+        }
 
         let table_meta = TableMetaBuilder::default()
             .schema(request.schema)
             .engine(MITO_ENGINE)
             .next_column_id(next_column_id)
             .primary_key_indices(request.primary_key_indices.clone())
-            .region_numbers(vec![region_number])
+            .region_numbers(request.region_numbers)
+            .options(request.table_options)
             .build()
             .context(error::BuildTableMetaSnafu { table_name })?;
 
@@ -353,7 +371,7 @@ impl<S: StorageEngine> MitoEngineInner<S> {
                 table_name,
                 &table_dir,
                 table_info,
-                region,
+                regions,
                 self.object_store.clone(),
             )
             .await?,
@@ -399,29 +417,51 @@ impl<S: StorageEngine> MitoEngineInner<S> {
             let table_id = request.table_id;
             let engine_ctx = StorageEngineContext::default();
             let table_dir = table_dir(schema_name, table_name, table_id);
+            // The ttl of the region is set after recovering the table info from manifest.
             let opts = OpenOptions {
                 parent_dir: table_dir.to_string(),
+                ..Default::default()
             };
 
-            // TODO(dennis): supports multi regions;
-            assert_eq!(request.region_numbers.len(), 1);
-            let region_number = request.region_numbers[0];
-            let region_name = region_name(table_id, region_number);
-
-            let region = match self
-                .storage_engine
-                .open_region(&engine_ctx, &region_name, &opts)
-                .await
-                .map_err(BoxedError::new)
-                .context(error::OpenRegionSnafu { region_name })?
+            let (mut table_info, manifest) = match MitoTable::<S::Region>::recover(
+                table_name,
+                &table_dir,
+                self.object_store.clone(),
+            )
+            .await?
             {
                 None => return Ok(None),
-                Some(region) => region,
+                Some(recovered) => recovered,
             };
 
-            let table = Arc::new(
-                MitoTable::open(table_name, &table_dir, region, self.object_store.clone()).await?,
+            // Opens the regions recorded in the manifest instead of the requested ones, since
+            // callers like the catalog might not know all regions of the table. Altering the
+            // table used to drop the region numbers, so falls back to the requested ones.
+            if table_info.meta.region_numbers.is_empty() {
+                table_info.meta.region_numbers = request.region_numbers.clone();
+            }
+            let region_numbers = &table_info.meta.region_numbers;
+            ensure!(
+                !region_numbers.is_empty(),
+                EmptyRegionNumbersSnafu { table_name }
             );
+            let mut regions = HashMap::with_capacity(region_numbers.len());
+            for region_number in region_numbers {
+                let region_name = region_name(table_id, *region_number);
+                let region = match self
+                    .storage_engine
+                    .open_region(&engine_ctx, &region_name, &opts)
+                    .await
+                    .map_err(BoxedError::new)
+                    .context(error::OpenRegionSnafu { region_name })?
+                {
+                    None => return Ok(None),
+                    Some(region) => region,
+                };
+                regions.insert(*region_number, region);
+            }
+
+            let table = Arc::new(MitoTable::open(table_name, table_info, regions, manifest)?);
 
             self.tables
                 .write()
@@ -436,6 +476,13 @@ impl<S: StorageEngine> MitoEngineInner<S> {
     }
 
     fn get_table<'a>(&self, table_ref: &'a TableReference) -> Option<TableRef> {
+        self.get_mito_table(table_ref).map(|table| table as _)
+    }
+
+    fn get_mito_table<'a>(
+        &self,
+        table_ref: &'a TableReference,
+    ) -> Option<Arc<MitoTable<S::Region>>> {
         self.tables
             .read()
             .unwrap()
@@ -465,20 +512,45 @@ impl<S: StorageEngine> MitoEngineInner<S> {
         Ok(table)
     }
 
-    /// Drop table. Returns whether a table is dropped (true) or not exist (false).
+    /// Drop table and reclaim its persisted data. Returns whether a table is dropped (true)
+    /// or not exist (false).
     async fn drop_table(&self, req: DropTableRequest) -> Result<bool> {
         let table_reference = TableReference {
             catalog: &req.catalog_name,
             schema: &req.schema_name,
             table: &req.table_name,
         };
-        // todo(ruihang): reclaim persisted data
-        Ok(self
-            .tables
-            .write()
-            .unwrap()
-            .remove(&table_reference.to_string())
-            .is_some())
+        let table_name = table_reference.to_string();
+
+        let _lock = self.table_mutex.lock().await;
+        let table = match self.get_mito_table(&table_reference) {
+            Some(table) => table,
+            None => return Ok(false),
+        };
+
+        // Dropping the region also stops the writers and removes the data of the region.
+        for region in table.regions().values() {
+            let region_name = region.name().to_string();
+            self.storage_engine
+                .drop_region(&StorageEngineContext::default(), region.clone())
+                .await
+                .map_err(BoxedError::new)
+                .context(error::DropRegionSnafu { region_name })?;
+        }
+
+        table
+            .manifest()
+            .delete_all()
+            .await
+            .context(error::DeleteTableManifestSnafu {
+                table_name: &table_name,
+            })?;
+
+        self.tables.write().unwrap().remove(&table_name);
+
+        logging::info!("Mito engine dropped table {}", table_name);
+
+        Ok(true)
     }
 }
 
@@ -495,9 +567,16 @@ impl<S: StorageEngine> MitoEngineInner<S> {
 
 #[cfg(test)]
 mod tests {
-    use common_query::physical_plan::RuntimeEnv;
+    use std::time::Duration;
+
+    use common_error::ext::ErrorExt;
+    use common_error::status_code::StatusCode;
+    use common_query::logical_plan::Expr;
+    use common_query::physical_plan::{PhysicalPlanRef, RuntimeEnv};
     use common_recordbatch::util;
+    use datafusion::logical_plan::{col, lit};
     use datafusion_common::field_util::{FieldExt, SchemaExt};
+    use datafusion_common::ScalarValue;
     use datatypes::prelude::{ConcreteDataType, ScalarVector};
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, SchemaBuilder};
     use datatypes::value::Value;
@@ -507,7 +586,8 @@ mod tests {
     use storage::EngineImpl;
     use store_api::manifest::Manifest;
     use store_api::storage::ReadContext;
-    use table::requests::{AddColumnRequest, AlterKind};
+    use table::metadata::FilterPushDownType;
+    use table::requests::{AddColumnRequest, AlterKind, DeleteRequest};
     use tempdir::TempDir;
 
     use super::*;
@@ -732,6 +812,169 @@ mod tests {
         assert_eq!(tss.to_arrow_array(), columns[0]);
     }
 
+    #[tokio::test]
+    async fn test_create_table_insert_delete_scan() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts = StringVector::from(vec!["host1", "host2", "host3"]);
+        let cpus = Float64Vector::from_vec(vec![55.5, 66.6, 77.7]);
+        let memories = Float64Vector::from_vec(vec![1024f64, 4096f64, 8192f64]);
+        let tss = TimestampVector::from_vec(vec![1, 2, 3]);
+
+        columns_values.insert("host".to_string(), Arc::new(hosts));
+        columns_values.insert("cpu".to_string(), Arc::new(cpus));
+        columns_values.insert("memory".to_string(), Arc::new(memories));
+        columns_values.insert("ts".to_string(), Arc::new(tss));
+
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(3, table.insert(insert_req).await.unwrap());
+
+        // Missing the timestamp column.
+        let mut key_column_values: HashMap<String, VectorRef> = HashMap::with_capacity(1);
+        key_column_values.insert(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["host1"])),
+        );
+        let delete_req = DeleteRequest { key_column_values };
+        assert!(table.delete(delete_req).await.is_err());
+
+        // Delete host1 and a row not exists.
+        let mut key_column_values: HashMap<String, VectorRef> = HashMap::with_capacity(2);
+        key_column_values.insert(
+            "host".to_string(),
+            Arc::new(StringVector::from(vec!["host1", "host2"])),
+        );
+        key_column_values.insert(
+            "ts".to_string(),
+            Arc::new(TimestampVector::from_vec(vec![1, 3])),
+        );
+        let delete_req = DeleteRequest { key_column_values };
+        assert_eq!(2, table.delete(delete_req).await.unwrap());
+
+        let stream = table.scan(&Some(vec![0, 3]), &[], None).await.unwrap();
+        let stream = stream.execute(0, Arc::new(RuntimeEnv::default())).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        assert_eq!(1, batches.len());
+
+        let columns = batches[0].df_recordbatch.columns();
+        assert_eq!(
+            StringVector::from(vec!["host2", "host3"]).to_arrow_array(),
+            columns[0]
+        );
+        assert_eq!(
+            TimestampVector::from_vec(vec![2, 3]).to_arrow_array(),
+            columns[1]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_flush_and_compact_table() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        for ts in [1, 2] {
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            columns_values.insert(
+                "host".to_string(),
+                Arc::new(StringVector::from(vec!["host1"])),
+            );
+            columns_values.insert(
+                "cpu".to_string(),
+                Arc::new(Float64Vector::from_vec(vec![1.0])),
+            );
+            columns_values.insert(
+                "memory".to_string(),
+                Arc::new(Float64Vector::from_vec(vec![1024f64])),
+            );
+            columns_values.insert(
+                "ts".to_string(),
+                Arc::new(TimestampVector::from_vec(vec![ts])),
+            );
+            let insert_req = new_insert_request("demo".to_string(), columns_values);
+            assert_eq!(1, table.insert(insert_req).await.unwrap());
+
+            table.flush().await.unwrap();
+            assert_eq!(ts as u64, table.region_stats()[0].sst_num);
+        }
+
+        table.compact().await.unwrap();
+        assert_eq!(1, table.region_stats()[0].sst_num);
+
+        let stream = table.scan(&None, &[], None).await.unwrap();
+        let stream = stream.execute(0, Arc::new(RuntimeEnv::default())).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        let num_rows: usize = batches.iter().map(|b| b.df_recordbatch.num_rows()).sum();
+        assert_eq!(2, num_rows);
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_multi_regions() {
+        let (_dir, object_store) =
+            test_util::new_test_object_store("test_create_table_with_multi_regions").await;
+        let table_engine = MitoEngine::new(
+            EngineConfig::default(),
+            EngineImpl::new(
+                StorageEngineConfig::default(),
+                Arc::new(NoopLogStore::default()),
+                object_store.clone(),
+            ),
+            object_store,
+        );
+        let ctx = EngineContext::default();
+        let mut request = test_util::new_create_request(Arc::new(test_util::schema_for_test()));
+        request.region_numbers = vec![0, 1];
+        let table = table_engine.create_table(&ctx, request).await.unwrap();
+        assert_eq!(vec![0, 1], table.table_info().meta.region_numbers);
+        assert_eq!(2, table.region_stats().len());
+
+        for (region_number, host) in [(0, "host1"), (1, "host2"), (2, "host3")] {
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            columns_values.insert("host".to_string(), Arc::new(StringVector::from(vec![host])));
+            columns_values.insert(
+                "cpu".to_string(),
+                Arc::new(Float64Vector::from_vec(vec![55.5])),
+            );
+            columns_values.insert(
+                "memory".to_string(),
+                Arc::new(Float64Vector::from_vec(vec![1024f64])),
+            );
+            columns_values.insert(
+                "ts".to_string(),
+                Arc::new(TimestampVector::from_vec(vec![1])),
+            );
+            let mut insert_req = new_insert_request("demo".to_string(), columns_values);
+            insert_req.region_number = region_number;
+
+            let result = table.insert(insert_req).await;
+            if region_number == 2 {
+                // The table doesn't have region 2.
+                assert_eq!(
+                    StatusCode::InvalidArguments,
+                    result.unwrap_err().status_code()
+                );
+            } else {
+                assert_eq!(1, result.unwrap());
+            }
+        }
+
+        // Scan the rows of all regions.
+        let stream = table.scan(&Some(vec![0]), &[], None).await.unwrap();
+        let stream = stream.execute(0, Arc::new(RuntimeEnv::default())).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        let mut hosts = batches
+            .iter()
+            .flat_map(|batch| {
+                let column = batch.df_recordbatch.column(0);
+                let vector = StringVector::try_from_arrow_array(column).unwrap();
+                (0..vector.len())
+                    .map(|i| vector.get(i).to_string())
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        hosts.sort();
+        assert_eq!(vec!["host1", "host2"], hosts);
+    }
+
     #[tokio::test]
     async fn test_create_table_scan_batches() {
         common_telemetry::init_default_ut_logging();
@@ -770,6 +1013,130 @@ mod tests {
         assert_eq!(test_batch_size, total);
     }
 
+    /// Scans the cpu column of the test table.
+    async fn scan_cpus(
+        table: &TableRef,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Vec<Option<f64>> {
+        let plan = table.scan(&Some(vec![1]), filters, limit).await.unwrap();
+        collect_cpus(plan).await
+    }
+
+    /// Collects the cpu column scanned by `plan`.
+    async fn collect_cpus(plan: PhysicalPlanRef) -> Vec<Option<f64>> {
+        let stream = plan.execute(0, Arc::new(RuntimeEnv::default())).unwrap();
+        let batches = util::collect(stream).await.unwrap();
+        batches
+            .iter()
+            .flat_map(|batch| {
+                let column = batch.df_recordbatch.column(0);
+                let vector = Float64Vector::try_from_arrow_array(column).unwrap();
+                vector.iter_data().collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_scan_with_limit_and_exact_filters() {
+        let (_engine, table, _schema, _dir) = test_util::setup_test_engine_and_table().await;
+
+        let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+        let hosts = StringVector::from(vec!["host1", "host2", "host1", "host2"]);
+        let cpus = Float64Vector::from_vec(vec![1.0, 2.0, 3.0, 4.0]);
+        let memories = Float64Vector::from_vec(vec![1024f64; 4]);
+        let tss = TimestampVector::from_vec(vec![1, 2, 3, 4]);
+
+        columns_values.insert("host".to_string(), Arc::new(hosts));
+        columns_values.insert("cpu".to_string(), Arc::new(cpus));
+        columns_values.insert("memory".to_string(), Arc::new(memories));
+        columns_values.insert("ts".to_string(), Arc::new(tss));
+
+        let insert_req = new_insert_request("demo".to_string(), columns_values);
+        assert_eq!(4, table.insert(insert_req).await.unwrap());
+
+        let ts_lit = |v| lit(ScalarValue::TimestampMillisecond(Some(v), None));
+        let host_filter: Expr = col("host").eq(lit("host1")).into();
+        let ts_filter: Expr = col("ts").gt(ts_lit(1)).into();
+        let cpu_filter: Expr = col("cpu").gt(lit(1.0f64)).into();
+        assert_eq!(
+            FilterPushDownType::Exact,
+            table.supports_filter_pushdown(&host_filter).unwrap()
+        );
+        assert_eq!(
+            FilterPushDownType::Exact,
+            table.supports_filter_pushdown(&ts_filter).unwrap()
+        );
+        assert_eq!(
+            FilterPushDownType::Inexact,
+            table.supports_filter_pushdown(&cpu_filter).unwrap()
+        );
+
+        // Rows are sorted by (host, ts).
+        assert_eq!(
+            vec![Some(3.0), Some(2.0), Some(4.0)],
+            scan_cpus(&table, &[ts_filter.clone()], None).await
+        );
+        assert_eq!(
+            vec![Some(3.0)],
+            scan_cpus(&table, &[host_filter.clone(), ts_filter], None).await
+        );
+        assert_eq!(
+            vec![Some(1.0)],
+            scan_cpus(&table, &[host_filter], Some(1)).await
+        );
+        assert_eq!(
+            vec![Some(1.0), Some(3.0), Some(2.0)],
+            scan_cpus(&table, &[], Some(3)).await
+        );
+    }
+
+    #[tokio::test]
+    async fn test_scan_latest() {
+        let (table_engine, table, schema, _dir) = test_util::setup_test_engine_and_table().await;
+        let mut request = test_util::new_create_request(schema);
+        request.id = 2;
+        request.table_name = "no_row_keys".to_string();
+        request.primary_key_indices = vec![];
+        let no_row_keys = table_engine
+            .create_table(&EngineContext::default(), request)
+            .await
+            .unwrap();
+
+        for table in [&table, &no_row_keys] {
+            let mut columns_values: HashMap<String, VectorRef> = HashMap::with_capacity(4);
+            let hosts = StringVector::from(vec!["host1", "host2", "host1", "host2"]);
+            let cpus = Float64Vector::from_vec(vec![1.0, 2.0, 3.0, 4.0]);
+            let memories = Float64Vector::from_vec(vec![1024f64; 4]);
+            let tss = TimestampVector::from_vec(vec![1, 4, 3, 2]);
+
+            columns_values.insert("host".to_string(), Arc::new(hosts));
+            columns_values.insert("cpu".to_string(), Arc::new(cpus));
+            columns_values.insert("memory".to_string(), Arc::new(memories));
+            columns_values.insert("ts".to_string(), Arc::new(tss));
+
+            let insert_req = new_insert_request(table.table_info().name.clone(), columns_values);
+            assert_eq!(4, table.insert(insert_req).await.unwrap());
+        }
+
+        // Rows are sorted by ts if the table has no row keys, so only the latest rows are read.
+        let plan = no_row_keys
+            .scan_latest(&Some(vec![1]), &[], Some(2))
+            .await
+            .unwrap();
+        assert_eq!(vec![Some(2.0), Some(3.0)], collect_cpus(plan).await);
+
+        // Rows are sorted by (host, ts), all rows are read.
+        let plan = table
+            .scan_latest(&Some(vec![1]), &[], Some(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![Some(1.0), Some(3.0), Some(4.0), Some(2.0)],
+            collect_cpus(plan).await
+        );
+    }
+
     #[tokio::test]
     async fn test_create_if_not_exists() {
         common_telemetry::init_default_ut_logging();
@@ -864,6 +1231,68 @@ mod tests {
         assert_eq!(reopened.manifest().last_version(), 1);
     }
 
+    #[tokio::test]
+    async fn test_open_table_with_multi_regions() {
+        let (_dir, object_store) =
+            test_util::new_test_object_store("test_open_table_with_multi_regions").await;
+        let engine = test_util::MockEngine::default();
+        let table_engine = MitoEngine::new(
+            EngineConfig::default(),
+            engine.clone(),
+            object_store.clone(),
+        );
+        let ctx = EngineContext::default();
+        let mut request = test_util::new_create_request(Arc::new(test_util::schema_for_test()));
+        request.region_numbers = vec![0, 1, 2];
+        table_engine.create_table(&ctx, request).await.unwrap();
+
+        // Altering the table keeps its regions.
+        let req = AlterTableRequest {
+            catalog_name: None,
+            schema_name: None,
+            table_name: TABLE_NAME.to_string(),
+            alter_kind: AlterKind::SetOptions {
+                options: HashMap::from([(requests::TTL_KEY.to_string(), "7d".to_string())]),
+            },
+        };
+        let table = table_engine.alter_table(&ctx, req).await.unwrap();
+        assert_eq!(vec![0, 1, 2], table.table_info().meta.region_numbers);
+
+        // Regions are recovered from the manifest, like the catalog, the request only knows
+        // region 0.
+        let table_engine = MitoEngine::new(EngineConfig::default(), engine, object_store);
+        let open_req = OpenTableRequest {
+            catalog_name: DEFAULT_CATALOG_NAME.to_string(),
+            schema_name: DEFAULT_SCHEMA_NAME.to_string(),
+            table_name: TABLE_NAME.to_string(),
+            table_id: 1,
+            region_numbers: vec![0],
+        };
+        let reopened = table_engine
+            .open_table(&ctx, open_req)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(table.table_info(), reopened.table_info());
+        assert_eq!(vec![0, 1, 2], reopened.table_info().meta.region_numbers);
+        assert_eq!(3, reopened.region_stats().len());
+    }
+
+    #[tokio::test]
+    async fn test_create_table_without_regions() {
+        let (_engine, table_engine, _table, _object_store, _dir) =
+            test_util::setup_mock_engine_and_table().await;
+        let mut request = test_util::new_create_request(Arc::new(test_util::schema_for_test()));
+        request.table_name = "no_regions".to_string();
+        request.region_numbers = vec![];
+
+        let err = table_engine
+            .create_table(&EngineContext::default(), request)
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
     #[test]
     fn test_region_id() {
         assert_eq!(1, region_id(0, 1));
@@ -984,12 +1413,94 @@ mod tests {
         assert_eq!(new_schema.version(), old_schema.version() + 1);
     }
 
+    #[tokio::test]
+    async fn test_alter_table_set_ttl() {
+        let (_engine, table_engine, table, _object_store, _dir) =
+            test_util::setup_mock_engine_and_table().await;
+
+        let old_info = table.table_info();
+        assert_eq!(None, old_info.meta.ttl().unwrap());
+
+        let req = AlterTableRequest {
+            catalog_name: None,
+            schema_name: None,
+            table_name: TABLE_NAME.to_string(),
+            alter_kind: AlterKind::SetOptions {
+                options: HashMap::from([(requests::TTL_KEY.to_string(), "7d".to_string())]),
+            },
+        };
+        let table = table_engine
+            .alter_table(&EngineContext::default(), req)
+            .await
+            .unwrap();
+
+        let new_info = table.table_info();
+        assert_eq!(
+            Some(Duration::from_secs(7 * 24 * 3600)),
+            new_info.meta.ttl().unwrap()
+        );
+        assert_eq!(old_info.ident.version + 1, new_info.ident.version);
+        assert_eq!(old_info.meta.schema, new_info.meta.schema);
+
+        // Invalid ttl is rejected and the table is unchanged.
+        let req = AlterTableRequest {
+            catalog_name: None,
+            schema_name: None,
+            table_name: TABLE_NAME.to_string(),
+            alter_kind: AlterKind::SetOptions {
+                options: HashMap::from([(requests::TTL_KEY.to_string(), "forever".to_string())]),
+            },
+        };
+        let err = table_engine
+            .alter_table(&EngineContext::default(), req)
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        assert_eq!(new_info, table.table_info());
+    }
+
+    #[tokio::test]
+    async fn test_create_table_with_ttl() {
+        let (_engine, table_engine, table, _object_store, _dir) =
+            test_util::setup_mock_engine_and_table().await;
+        let table_info = table.table_info();
+        let new_request = |ttl: &str| CreateTableRequest {
+            id: 2,
+            catalog_name: "greptime".to_string(),
+            schema_name: "public".to_string(),
+            table_name: "ttl_table".to_string(),
+            schema: table_info.meta.schema.clone(),
+            create_if_not_exists: true,
+            desc: None,
+            primary_key_indices: Vec::default(),
+            table_options: HashMap::from([(requests::TTL_KEY.to_string(), ttl.to_string())]),
+            region_numbers: vec![0],
+        };
+
+        let err = table_engine
+            .create_table(&EngineContext::default(), new_request("1 year ago"))
+            .await
+            .err()
+            .unwrap();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        let table = table_engine
+            .create_table(&EngineContext::default(), new_request("12h"))
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(Duration::from_secs(12 * 3600)),
+            table.table_info().meta.ttl().unwrap()
+        );
+    }
+
     #[tokio::test]
     async fn test_drop_table() {
         common_telemetry::init_default_ut_logging();
         let ctx = EngineContext::default();
 
-        let (_engine, table_engine, table, _object_store, _dir) =
+        let (engine, table_engine, table, _object_store, _dir) =
             test_util::setup_mock_engine_and_table().await;
         let engine_ctx = EngineContext {};
 
@@ -1031,6 +1542,11 @@ mod tests {
             .unwrap();
         assert!(table_dropped);
         assert!(!table_engine.table_exists(&engine_ctx, &table_reference));
+        // The region of the table is also dropped.
+        assert!(engine
+            .get_region(&StorageEngineContext::default(), &region_name(1, 0))
+            .unwrap()
+            .is_none());
 
         // should be able to re-create
         let request = CreateTableRequest {
@@ -1048,4 +1564,35 @@ mod tests {
         table_engine.create_table(&ctx, request).await.unwrap();
         assert!(table_engine.table_exists(&engine_ctx, &table_reference));
     }
+    fn has_file(dir: &std::path::Path) -> bool {
+        std::fs::read_dir(dir).unwrap().any(|entry| {
+            let path = entry.unwrap().path();
+            !path.is_dir() || has_file(&path)
+        })
+    }
+
+    #[tokio::test]
+    async fn test_drop_table_reclaim_data() {
+        let (table_engine, table, _schema, dir) = test_util::setup_test_engine_and_table().await;
+        // Manifests of the table and its region are persisted.
+        assert!(has_file(dir.path()));
+
+        let table_info = table.table_info();
+        let ctx = EngineContext::default();
+        let request = DropTableRequest {
+            catalog_name: table_info.catalog_name.clone(),
+            schema_name: table_info.schema_name.clone(),
+            table_name: table_info.name.clone(),
+        };
+        assert!(table_engine.drop_table(&ctx, request).await.unwrap());
+        assert!(!has_file(dir.path()));
+
+        // Drop a table that doesn't exist.
+        let request = DropTableRequest {
+            catalog_name: table_info.catalog_name.clone(),
+            schema_name: table_info.schema_name.clone(),
+            table_name: table_info.name.clone(),
+        };
+        assert!(!table_engine.drop_table(&ctx, request).await.unwrap());
+    }
 }
@@ -16,6 +16,7 @@ use std::any::Any;
 
 use common_error::ext::BoxedError;
 use common_error::prelude::*;
+use store_api::storage::RegionNumber;
 use table::metadata::{TableInfoBuilderError, TableMetaBuilderError};
 
 #[derive(Debug, Snafu)]
@@ -34,6 +35,13 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to drop region, region: {}, source: {}", region_name, source))]
+    DropRegion {
+        region_name: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display(
         "Failed to build table meta for table: {}, source: {}",
         table_name,
@@ -132,8 +140,19 @@ pub enum Error {
         table_name: String,
     },
 
-    #[snafu(display("Table info not found in manifest, table: {}", table_name))]
-    TableInfoNotFound {
+    #[snafu(display(
+        "Failed to delete table manifest, table: {}, source: {}",
+        table_name,
+        source,
+    ))]
+    DeleteTableManifest {
+        #[snafu(backtrace)]
+        source: storage::error::Error,
+        table_name: String,
+    },
+
+    #[snafu(display("Table {} must have at least one region", table_name))]
+    EmptyRegionNumbers {
         backtrace: Backtrace,
         table_name: String,
     },
@@ -150,6 +169,13 @@ pub enum Error {
         table_name: String,
     },
 
+    #[snafu(display("Region {} not found in table {}", region_number, table_name))]
+    RegionNotFound {
+        backtrace: Backtrace,
+        region_number: RegionNumber,
+        table_name: String,
+    },
+
     #[snafu(display("Columns {} not exist in table {}", column_names.join(","), table_name))]
     ColumnsNotExist {
         backtrace: Backtrace,
@@ -187,6 +213,13 @@ pub enum Error {
         #[snafu(backtrace)]
         source: table::metadata::ConvertError,
     },
+
+    #[snafu(display("Invalid options of table {}, source: {}", table_name, source))]
+    InvalidTableOption {
+        table_name: String,
+        #[snafu(backtrace)]
+        source: table::error::Error,
+    },
 }
 
 impl From<Error> for table::error::Error {
@@ -202,9 +235,11 @@ impl ErrorExt for Error {
         use Error::*;
 
         match self {
-            CreateRegion { source, .. } | OpenRegion { source, .. } => source.status_code(),
+            CreateRegion { source, .. } | OpenRegion { source, .. } | DropRegion { source, .. } => {
+                source.status_code()
+            }
 
-            AlterTable { source, .. } => source.status_code(),
+            AlterTable { source, .. } | InvalidTableOption { source, .. } => source.status_code(),
 
             BuildRowKeyDescriptor { .. }
             | BuildColumnDescriptor { .. }
@@ -216,13 +251,17 @@ impl ErrorExt for Error {
             | ProjectedColumnNotFound { .. }
             | MissingTimestampIndex { .. }
             | UnsupportedDefaultConstraint { .. }
-            | TableNotFound { .. } => StatusCode::InvalidArguments,
+            | TableNotFound { .. }
+            | RegionNotFound { .. }
+            | EmptyRegionNumbers { .. } => StatusCode::InvalidArguments,
 
             ColumnsNotExist { .. } => StatusCode::TableColumnNotFound,
 
-            TableInfoNotFound { .. } | ConvertRaw { .. } => StatusCode::Unexpected,
+            ConvertRaw { .. } => StatusCode::Unexpected,
 
-            ScanTableManifest { .. } | UpdateTableManifest { .. } => StatusCode::StorageUnavailable,
+            ScanTableManifest { .. } | UpdateTableManifest { .. } | DeleteTableManifest { .. } => {
+                StatusCode::StorageUnavailable
+            }
         }
     }
 
@@ -16,11 +16,13 @@
 pub mod test_util;
 
 use std::any::Any;
+use std::collections::HashMap;
 use std::pin::Pin;
 use std::sync::Arc;
 
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
+use common_query::cancellation::CancellationToken;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::PhysicalPlanRef;
 use common_recordbatch::error::{Error as RecordBatchError, Result as RecordBatchResult};
@@ -35,20 +37,24 @@ use snafu::{ensure, OptionExt, ResultExt};
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
     AddColumn, AlterOperation, AlterRequest, ChunkReader, PutOperation, ReadContext, Region,
-    RegionMeta, ScanRequest, SchemaRef, Snapshot, WriteContext, WriteRequest,
+    RegionMeta, RegionNumber, RegionStat, ScanRequest, SchemaRef, Snapshot, WriteContext,
+    WriteRequest,
 };
 use table::error::{Error as TableError, MissingColumnSnafu, Result as TableResult};
 use table::metadata::{
     FilterPushDownType, RawTableInfo, TableInfo, TableInfoRef, TableMeta, TableType,
 };
-use table::requests::{AddColumnRequest, AlterKind, AlterTableRequest, InsertRequest};
+use table::predicate::ExactFilter;
+use table::requests::{
+    AddColumnRequest, AlterKind, AlterTableRequest, DeleteRequest, InsertRequest,
+};
 use table::table::scan::SimpleTableScan;
 use table::table::Table;
 use tokio::sync::Mutex;
 
 use crate::error::{
-    self, ColumnsNotExistSnafu, ProjectedColumnNotFoundSnafu, Result, ScanTableManifestSnafu,
-    TableInfoNotFoundSnafu, UnsupportedDefaultConstraintSnafu, UpdateTableManifestSnafu,
+    self, ColumnsNotExistSnafu, ProjectedColumnNotFoundSnafu, RegionNotFoundSnafu, Result,
+    ScanTableManifestSnafu, UnsupportedDefaultConstraintSnafu, UpdateTableManifestSnafu,
 };
 use crate::manifest::action::*;
 use crate::manifest::TableManifest;
@@ -63,8 +69,7 @@ pub struct MitoTable<R: Region> {
     manifest: TableManifest,
     // guarded by `self.alter_lock`
     table_info: ArcSwap<TableInfo>,
-    // TODO(dennis): a table contains multi regions
-    region: R,
+    regions: HashMap<RegionNumber, R>,
     alter_lock: Mutex<()>,
 }
 
@@ -83,7 +88,8 @@ impl<R: Region> Table for MitoTable<R> {
             return Ok(0);
         }
 
-        let mut write_request = self.region.write_request();
+        let region = self.find_region(request.region_number)?;
+        let mut write_request = region.write_request();
 
         let mut put_op = write_request.put_op();
         let mut columns_values = request.columns_values;
@@ -146,8 +152,7 @@ impl<R: Region> Table for MitoTable<R> {
 
         write_request.put(put_op).map_err(TableError::new)?;
 
-        let _resp = self
-            .region
+        let _resp = region
             .write(&WriteContext::default(), write_request)
             .await
             .map_err(TableError::new)?;
@@ -155,6 +160,63 @@ impl<R: Region> Table for MitoTable<R> {
         Ok(rows_num)
     }
 
+    async fn delete(&self, request: DeleteRequest) -> TableResult<usize> {
+        if request.key_column_values.is_empty() {
+            return Ok(0);
+        }
+
+        let mut key_column_values = request.key_column_values;
+        let table_info = self.table_info();
+        let schema = self.schema();
+        // Rows in region are identified by the primary key columns and the timestamp column.
+        let mut key_columns: Vec<_> = table_info.meta.row_key_column_names().collect();
+        if let Some(ts_column) = schema.timestamp_column() {
+            if !key_columns.contains(&&ts_column.name) {
+                key_columns.push(&ts_column.name);
+            }
+        }
+
+        let mut keys = HashMap::with_capacity(key_columns.len());
+        for name in key_columns {
+            let vector = key_column_values
+                .remove(name)
+                .context(MissingColumnSnafu { name })?;
+            keys.insert(name.clone(), vector);
+        }
+
+        ensure!(
+            key_column_values.is_empty(),
+            ColumnsNotExistSnafu {
+                table_name: &table_info.name,
+                column_names: key_column_values.into_keys().collect::<Vec<_>>(),
+            }
+        );
+
+        // keys is not empty, it's safe to unwrap
+        let rows_num = keys.values().next().unwrap().len();
+
+        logging::trace!(
+            "Delete from table {} with keys: {:?}",
+            table_info.name,
+            keys
+        );
+
+        // The rows to delete may be stored in any region of the table.
+        for region in self.regions.values() {
+            let mut write_request = region.write_request();
+            write_request
+                .delete(keys.clone())
+                .map_err(TableError::new)?;
+
+            let _resp = region
+                .write(&WriteContext::default(), write_request)
+                .await
+                .map_err(TableError::new)?;
+        }
+
+        Ok(rows_num)
+    }
+
     fn table_type(&self) -> TableType {
         self.table_info().table_type
     }
@@ -167,35 +229,32 @@ impl<R: Region> Table for MitoTable<R> {
         &self,
         projection: &Option<Vec<usize>>,
         filters: &[Expr],
-        _limit: Option<usize>,
+        limit: Option<usize>,
     ) -> TableResult<PhysicalPlanRef> {
-        let read_ctx = ReadContext::default();
-        let snapshot = self.region.snapshot(&read_ctx).map_err(TableError::new)?;
-
-        let projection = self.transform_projection(&self.region, projection.clone())?;
-        let filters = filters.into();
-        let scan_request = ScanRequest {
-            projection,
-            filters,
-            ..Default::default()
-        };
-        let mut reader = snapshot
-            .scan(&read_ctx, scan_request)
-            .await
-            .map_err(TableError::new)?
-            .reader;
-
-        let schema = reader.schema().clone();
-        let stream_schema = schema.clone();
+        self.scan_regions(projection, filters, limit, false).await
+    }
 
-        let stream = Box::pin(async_stream::try_stream! {
-            while let Some(chunk) = reader.next_chunk().await.map_err(RecordBatchError::new)? {
-                yield RecordBatch::new(stream_schema.clone(), chunk.columns)?
-            }
-        });
+    async fn scan_latest(
+        &self,
+        projection: &Option<Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> TableResult<PhysicalPlanRef> {
+        let table_info = self.table_info();
+        let ts_index = table_info.meta.schema.timestamp_index();
+        let has_row_keys = table_info
+            .meta
+            .primary_key_indices
+            .iter()
+            .any(|index| Some(*index) != ts_index);
+        if has_row_keys {
+            // Regions sort rows by row keys first, so the latest rows of all row keys are not
+            // returned first.
+            return self.scan(projection, filters, None).await;
+        }
 
-        let stream = Box::pin(ChunkStream { schema, stream });
-        Ok(Arc::new(SimpleTableScan::new(stream)))
+        // Each region returns its latest rows, then they are sorted by the query.
+        self.scan_regions(projection, filters, limit, true).await
     }
 
     /// Alter table changes the schemas of the table.
@@ -211,6 +270,11 @@ impl<R: Region> Table for MitoTable<R> {
             .context(error::BuildTableMetaSnafu { table_name })?;
 
         let alter_op = create_alter_operation(table_name, &req.alter_kind, &mut new_meta)?;
+        // Options are validated while building the new meta.
+        let new_ttl = match &req.alter_kind {
+            AlterKind::SetOptions { .. } => Some(new_meta.ttl()?),
+            _ => None,
+        };
 
         let mut new_info = TableInfo::clone(&*table_info);
         // Increase version of the table.
@@ -231,25 +295,31 @@ impl<R: Region> Table for MitoTable<R> {
             )))
             .await
             .context(UpdateTableManifestSnafu {
-                table_name: &self.table_info().name,
+                table_name: self.table_info().name.clone(),
             })?;
 
         // TODO(yingwen): Error handling. Maybe the region need to provide a method to
         // validate the request first.
-        let region = self.region();
-        let region_meta = region.in_memory_metadata();
-        let alter_req = AlterRequest {
-            operation: alter_op,
-            version: region_meta.version(),
-        };
-        // Alter the region.
-        logging::debug!(
-            "start altering region {} of table {}, with request {:?}",
-            region.name(),
-            table_name,
-            alter_req,
-        );
-        region.alter(alter_req).await.map_err(TableError::new)?;
+        for region in self.regions.values() {
+            if let Some(alter_op) = &alter_op {
+                let region_meta = region.in_memory_metadata();
+                let alter_req = AlterRequest {
+                    operation: alter_op.clone(),
+                    version: region_meta.version(),
+                };
+                // Alter the region.
+                logging::debug!(
+                    "start altering region {} of table {}, with request {:?}",
+                    region.name(),
+                    table_name,
+                    alter_req,
+                );
+                region.alter(alter_req).await.map_err(TableError::new)?;
+            }
+            if let Some(ttl) = new_ttl {
+                region.set_ttl(ttl);
+            }
+        }
 
         // Update in memory metadata of the table.
         self.set_table_info(new_info);
@@ -257,8 +327,55 @@ impl<R: Region> Table for MitoTable<R> {
         Ok(())
     }
 
-    fn supports_filter_pushdown(&self, _filter: &Expr) -> table::error::Result<FilterPushDownType> {
-        Ok(FilterPushDownType::Inexact)
+    fn supports_filter_pushdown(&self, filter: &Expr) -> table::error::Result<FilterPushDownType> {
+        let table_info = self.table_info();
+        let schema = &table_info.meta.schema;
+        // Storage always reads the row key columns, so filters on them could be evaluated
+        // exactly while scanning.
+        let mut key_columns: Vec<_> = table_info
+            .meta
+            .row_key_column_names()
+            .map(|name| name.as_str())
+            .collect();
+        if let Some(ts_col) = schema.timestamp_column() {
+            key_columns.push(&ts_col.name);
+        }
+
+        if ExactFilter::try_new(filter, schema, &key_columns).is_some() {
+            Ok(FilterPushDownType::Exact)
+        } else {
+            Ok(FilterPushDownType::Inexact)
+        }
+    }
+
+    fn region_stats(&self) -> Vec<RegionStat> {
+        self.regions.values().map(|region| region.stat()).collect()
+    }
+
+    async fn flush(&self) -> TableResult<()> {
+        for region in self.regions.values() {
+            logging::info!(
+                "Flush region {} of table {}",
+                region.name(),
+                self.table_info().name
+            );
+
+            region.flush().await.map_err(TableError::new)?;
+        }
+        Ok(())
+    }
+
+    async fn compact(&self) -> TableResult<()> {
+        for region in self.regions.values() {
+            logging::info!(
+                "Compact region {} of table {}",
+                region.name(),
+                self.table_info().name
+            );
+
+            region.compact().await.map_err(TableError::new)?;
+        }
+        Ok(())
     }
 }
 
@@ -287,15 +404,96 @@ fn column_qualified_name(table_name: &str, region_name: &str, column_name: &str)
 }
 
 impl<R: Region> MitoTable<R> {
-    fn new(table_info: TableInfo, region: R, manifest: TableManifest) -> Self {
+    fn new(
+        table_info: TableInfo,
+        regions: HashMap<RegionNumber, R>,
+        manifest: TableManifest,
+    ) -> Self {
         Self {
             table_info: ArcSwap::new(Arc::new(table_info)),
-            region,
+            regions,
             manifest,
             alter_lock: Mutex::new(()),
         }
     }
 
+    fn find_region(&self, region_number: RegionNumber) -> Result<&R> {
+        self.regions
+            .get(&region_number)
+            .with_context(|| RegionNotFoundSnafu {
+                region_number,
+                table_name: self.table_info().name.clone(),
+            })
+    }
+
+    /// Scans all regions, returns rows in descending order of the row key in each region if
+    /// `descending` is true.
+    async fn scan_regions(
+        &self,
+        projection: &Option<Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        descending: bool,
+    ) -> TableResult<PhysicalPlanRef> {
+        // Scans are created while planning the query, so the token of the running query
+        // is still bound to the current task.
+        let read_ctx = ReadContext {
+            cancellation: CancellationToken::current(),
+            ..Default::default()
+        };
+        let streams = futures::future::try_join_all(self.regions.values().map(|region| {
+            self.scan_region(region, &read_ctx, projection, filters, limit, descending)
+        }))
+        .await?;
+        // The engine always creates a table with at least one region.
+        let schema = streams[0].schema.clone();
+
+        // Polls the streams of all regions concurrently.
+        let stream = Box::pin(ChunkStream {
+            schema,
+            stream: Box::pin(futures::stream::select_all(streams)),
+        });
+        Ok(Arc::new(SimpleTableScan::new(stream)))
+    }
+
+    /// Scans the `region`, returns a stream of the chunks read.
+    async fn scan_region(
+        &self,
+        region: &R,
+        read_ctx: &ReadContext,
+        projection: &Option<Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+        descending: bool,
+    ) -> TableResult<ChunkStream> {
+        let snapshot = region.snapshot(read_ctx).map_err(TableError::new)?;
+
+        let projection = self.transform_projection(region, projection.clone())?;
+        let scan_request = ScanRequest {
+            projection,
+            filters: filters.to_vec(),
+            limit,
+            descending,
+            ..Default::default()
+        };
+        let mut reader = snapshot
+            .scan(read_ctx, scan_request)
+            .await
+            .map_err(TableError::new)?
+            .reader;
+
+        let schema = reader.schema().clone();
+        let stream_schema = schema.clone();
+
+        let stream = Box::pin(async_stream::try_stream! {
+            while let Some(chunk) = reader.next_chunk().await.map_err(RecordBatchError::new)? {
+                yield RecordBatch::new(stream_schema.clone(), chunk.columns)?
+            }
+        });
+
+        Ok(ChunkStream { schema, stream })
+    }
+
     /// Transform projection which is based on table schema
     /// into projection based on region schema.
     fn transform_projection(
@@ -356,7 +554,7 @@ impl<R: Region> MitoTable<R> {
         table_name: &str,
         table_dir: &str,
         table_info: TableInfo,
-        region: R,
+        regions: HashMap<RegionNumber, R>,
         object_store: ObjectStore,
     ) -> Result<MitoTable<R>> {
         let manifest = TableManifest::new(&table_manifest_dir(table_dir), object_store);
@@ -371,7 +569,7 @@ impl<R: Region> MitoTable<R> {
             .await
             .context(UpdateTableManifestSnafu { table_name })?;
 
-        Ok(MitoTable::new(table_info, region, manifest))
+        Ok(MitoTable::new(table_info, regions, manifest))
     }
 
     fn try_get_column_default_constraint_vector(
@@ -389,19 +587,37 @@ impl<R: Region> MitoTable<R> {
         Ok(vector)
     }
 
-    pub async fn open(
+    /// Recovers the table info from the manifest of the table under `table_dir`, returns
+    /// `None` if the table info is not found.
+    pub async fn recover(
         table_name: &str,
         table_dir: &str,
-        region: R,
         object_store: ObjectStore,
-    ) -> Result<MitoTable<R>> {
+    ) -> Result<Option<(TableInfo, TableManifest)>> {
         let manifest = TableManifest::new(&table_manifest_dir(table_dir), object_store);
+        let table_info = Self::recover_table_info(table_name, &manifest).await?;
+        Ok(table_info.map(|table_info| (table_info, manifest)))
+    }
+
+    /// Opens the table from the recovered `table_info` and `manifest`, `regions` are the
+    /// regions recorded in the table info.
+    pub fn open(
+        table_name: &str,
+        table_info: TableInfo,
+        regions: HashMap<RegionNumber, R>,
+        manifest: TableManifest,
+    ) -> Result<MitoTable<R>> {
+        // The ttl is stored in table options, so we set it to the regions after the
+        // table info is recovered.
+        let ttl = table_info
+            .meta
+            .ttl()
+            .context(error::InvalidTableOptionSnafu { table_name })?;
+        for region in regions.values() {
+            region.set_ttl(ttl);
+        }
 
-        let mut table_info = Self::recover_table_info(table_name, &manifest)
-            .await?
-            .context(TableInfoNotFoundSnafu { table_name })?;
-        table_info.meta.region_numbers = vec![(region.id() & 0xFFFFFFFF) as u32];
-        Ok(MitoTable::new(table_info, region, manifest))
+        Ok(MitoTable::new(table_info, regions, manifest))
     }
 
     async fn recover_table_info(
@@ -452,8 +668,8 @@ impl<R: Region> MitoTable<R> {
     }
 
     #[inline]
-    pub fn region(&self) -> &R {
-        &self.region
+    pub fn regions(&self) -> &HashMap<RegionNumber, R> {
+        &self.regions
     }
 
     pub fn set_table_info(&self, table_info: TableInfo) {
@@ -471,19 +687,21 @@ impl<R: Region> MitoTable<R> {
     }
 }
 
-/// Create [`AlterOperation`] according to given `alter_kind`.
+/// Create [`AlterOperation`] according to given `alter_kind`, returns `None` if the
+/// schema of the region is unchanged.
 fn create_alter_operation(
     table_name: &str,
     alter_kind: &AlterKind,
     table_meta: &mut TableMeta,
-) -> TableResult<AlterOperation> {
+) -> TableResult<Option<AlterOperation>> {
     match alter_kind {
         AlterKind::AddColumns { columns } => {
-            create_add_columns_operation(table_name, columns, table_meta)
+            create_add_columns_operation(table_name, columns, table_meta).map(Some)
         }
-        AlterKind::DropColumns { names } => Ok(AlterOperation::DropColumns {
+        AlterKind::DropColumns { names } => Ok(Some(AlterOperation::DropColumns {
             names: names.to_vec(),
-        }),
+        })),
+        AlterKind::SetOptions { .. } => Ok(None),
     }
 }
 
@@ -47,6 +47,7 @@ pub fn new_insert_request(
         schema_name: DEFAULT_SCHEMA_NAME.to_string(),
         table_name,
         columns_values,
+        region_number: 0,
     }
 }
 
@@ -98,7 +99,7 @@ pub async fn new_test_object_store(prefix: &str) -> (TempDir, ObjectStore) {
     (dir, ObjectStore::new(accessor))
 }
 
-fn new_create_request(schema: SchemaRef) -> CreateTableRequest {
+pub fn new_create_request(schema: SchemaRef) -> CreateTableRequest {
     CreateTableRequest {
         id: 1,
         catalog_name: "greptime".to_string(),
@@ -16,6 +16,7 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use arc_swap::ArcSwap;
 use async_trait::async_trait;
@@ -27,8 +28,8 @@ use storage::metadata::{RegionMetaImpl, RegionMetadata};
 use storage::write_batch::{Mutation, WriteBatch};
 use store_api::storage::{
     AlterRequest, Chunk, ChunkReader, CreateOptions, EngineContext, GetRequest, GetResponse,
-    OpenOptions, ReadContext, Region, RegionDescriptor, RegionId, RegionMeta, ScanRequest,
-    ScanResponse, SchemaRef, Snapshot, StorageEngine, WriteContext, WriteResponse,
+    OpenOptions, ReadContext, Region, RegionDescriptor, RegionId, RegionMeta, RegionStat,
+    ScanRequest, ScanResponse, SchemaRef, Snapshot, StorageEngine, WriteContext, WriteResponse,
 };
 
 pub type Result<T> = std::result::Result<T, MockError>;
@@ -185,6 +186,24 @@ impl Region for MockRegion {
 
         Ok(())
     }
+
+    fn set_ttl(&self, _ttl: Option<Duration>) {}
+
+    fn stat(&self) -> RegionStat {
+        RegionStat {
+            region_id: self.id(),
+            region_name: self.inner.name.clone(),
+            ..Default::default()
+        }
+    }
+
+    async fn flush(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn compact(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 impl MockRegionInner {
@@ -220,17 +239,50 @@ impl MockRegionInner {
 
         let mut memtable = self.memtable.write().unwrap();
 
-        for Mutation::Put(put) in request.iter() {
-            for ColumnSchema { name, .. } in metadata.user_schema().column_schemas() {
-                let column = memtable.get_mut(name).unwrap();
-                if let Some(data) = put.column_by_name(name) {
-                    (0..data.len()).for_each(|i| column.push(data.get(i)));
+        for mutation in request.iter() {
+            match mutation {
+                Mutation::Put(put) => {
+                    for ColumnSchema { name, .. } in metadata.user_schema().column_schemas() {
+                        let column = memtable.get_mut(name).unwrap();
+                        if let Some(data) = put.column_by_name(name) {
+                            (0..data.len()).for_each(|i| column.push(data.get(i)));
+                        }
+                    }
+                }
+                Mutation::Delete(delete) => {
+                    let key_names = metadata
+                        .schema()
+                        .row_key_columns()
+                        .map(|column| column.name().to_string())
+                        .collect::<Vec<_>>();
+                    for i in 0..delete.num_rows() {
+                        delete_row(&mut memtable, &key_names, |name| {
+                            delete.column_by_name(name).unwrap().get(i)
+                        });
+                    }
                 }
             }
         }
     }
 }
 
+/// Removes rows whose row keys are equal to the key returned by `key_of`.
+fn delete_row(memtable: &mut MockMemtable, key_names: &[String], key_of: impl Fn(&str) -> Value) {
+    let rows = memtable.values().next().map(|c| c.len()).unwrap_or(0);
+    let retained = (0..rows)
+        .map(|row| {
+            key_names
+                .iter()
+                .any(|name| memtable[name][row] != key_of(name))
+        })
+        .collect::<Vec<_>>();
+
+    for column in memtable.values_mut() {
+        let mut iter = retained.iter();
+        column.retain(|_| *iter.next().unwrap());
+    }
+}
+
 type RegionMap = HashMap<String, MockRegion>;
 
 #[derive(Debug, Default)]
@@ -272,8 +324,17 @@ impl StorageEngine for MockEngine {
         return Ok(None);
     }
 
-    async fn close_region(&self, _ctx: &EngineContext, _region: MockRegion) -> Result<()> {
-        unimplemented!()
+    async fn close_region(&self, _ctx: &EngineContext, region: MockRegion) -> Result<()> {
+        logging::info!("Mock engine close region, name: {}", region.name());
+
+        let mut regions = self.regions.lock().unwrap();
+        if let Some(region) = regions.opened_regions.remove(region.name()) {
+            regions
+                .closed_regions
+                .insert(region.name().to_string(), region);
+        }
+
+        Ok(())
     }
 
     async fn create_region(
@@ -299,8 +360,14 @@ impl StorageEngine for MockEngine {
         Ok(region)
     }
 
-    async fn drop_region(&self, _ctx: &EngineContext, _region: Self::Region) -> Result<()> {
-        unimplemented!()
+    async fn drop_region(&self, _ctx: &EngineContext, region: Self::Region) -> Result<()> {
+        logging::info!("Mock engine drop region, name: {}", region.name());
+
+        let mut regions = self.regions.lock().unwrap();
+        regions.opened_regions.remove(region.name());
+        regions.closed_regions.remove(region.name());
+
+        Ok(())
     }
 
     fn get_region(&self, _ctx: &EngineContext, name: &str) -> Result<Option<MockRegion>> {
@@ -0,0 +1,426 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Abstract syntax tree of PromQL.
+
+use regex::Regex;
+use snafu::ResultExt;
+
+use crate::error::{self, Result};
+
+/// Name of the label holding the metric name.
+pub const METRIC_NAME: &str = "__name__";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    NumberLiteral(f64),
+    StringLiteral(String),
+    VectorSelector(VectorSelector),
+    /// A range vector selector, e.g. `http_requests_total[5m]`.
+    MatrixSelector(MatrixSelector),
+    /// A subquery, e.g. `rate(http_requests_total[5m])[30m:1m]`.
+    Subquery(SubqueryExpr),
+    Call(Call),
+    Aggregate(AggregateExpr),
+    Binary(BinaryExpr),
+    /// Negation of the inner expression.
+    Neg(Box<Expr>),
+    Paren(Box<Expr>),
+}
+
+impl Expr {
+    pub fn value_type(&self) -> ValueType {
+        match self {
+            Expr::NumberLiteral(_) => ValueType::Scalar,
+            Expr::StringLiteral(_) => ValueType::String,
+            Expr::VectorSelector(_) | Expr::Aggregate(_) => ValueType::Vector,
+            Expr::MatrixSelector(_) | Expr::Subquery(_) => ValueType::Matrix,
+            Expr::Call(call) => call.func.return_type,
+            Expr::Binary(binary) => {
+                if binary.lhs.value_type() == ValueType::Scalar
+                    && binary.rhs.value_type() == ValueType::Scalar
+                {
+                    ValueType::Scalar
+                } else {
+                    ValueType::Vector
+                }
+            }
+            Expr::Neg(expr) | Expr::Paren(expr) => expr.value_type(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueType {
+    Scalar,
+    String,
+    Vector,
+    Matrix,
+}
+
+impl ValueType {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ValueType::Scalar => "scalar",
+            ValueType::String => "string",
+            ValueType::Vector => "instant vector",
+            ValueType::Matrix => "range vector",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOp {
+    Equal,
+    NotEqual,
+    Re,
+    NotRe,
+}
+
+/// A label matcher, regular expressions are fully anchored as in Prometheus.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    pub name: String,
+    pub op: MatchOp,
+    pub value: String,
+    regex: Option<Regex>,
+}
+
+impl PartialEq for Matcher {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name && self.op == other.op && self.value == other.value
+    }
+}
+
+impl Matcher {
+    pub fn new(op: MatchOp, name: impl Into<String>, value: impl Into<String>) -> Result<Self> {
+        let value = value.into();
+        let regex = match op {
+            MatchOp::Equal | MatchOp::NotEqual => None,
+            MatchOp::Re | MatchOp::NotRe => Some(
+                Regex::new(&format!("^(?:{})$", value))
+                    .context(error::InvalidRegexSnafu { regex: &value })?,
+            ),
+        };
+        Ok(Self {
+            name: name.into(),
+            op,
+            value,
+            regex,
+        })
+    }
+
+    /// Returns whether the label value `s` matches, a missing label is treated as an empty value.
+    pub fn matches(&self, s: &str) -> bool {
+        match (self.op, &self.regex) {
+            (MatchOp::Equal, _) => self.value == s,
+            (MatchOp::NotEqual, _) => self.value != s,
+            (MatchOp::Re, Some(regex)) => regex.is_match(s),
+            (MatchOp::NotRe, Some(regex)) => !regex.is_match(s),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorSelector {
+    /// Matchers of the selector, including the matcher on [METRIC_NAME].
+    pub matchers: Vec<Matcher>,
+    pub offset_ms: i64,
+}
+
+impl VectorSelector {
+    /// Returns the metric name if the selector selects a single metric.
+    pub fn metric_name(&self) -> Option<&str> {
+        self.matchers
+            .iter()
+            .find(|m| m.name == METRIC_NAME && m.op == MatchOp::Equal)
+            .map(|m| m.value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MatrixSelector {
+    pub selector: VectorSelector,
+    pub range_ms: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubqueryExpr {
+    pub expr: Box<Expr>,
+    pub range_ms: i64,
+    /// Resolution of the subquery, defaults to the step of the query.
+    pub step_ms: Option<i64>,
+    pub offset_ms: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Call {
+    pub func: &'static Function,
+    pub args: Vec<Expr>,
+}
+
+/// Signature of a PromQL function.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Function {
+    pub name: &'static str,
+    pub arg_types: &'static [ValueType],
+    /// Min number of arguments, trailing arguments after it are optional.
+    pub min_args: usize,
+    /// Whether the last argument can be repeated.
+    pub variadic: bool,
+    pub return_type: ValueType,
+}
+
+const fn function(
+    name: &'static str,
+    arg_types: &'static [ValueType],
+    return_type: ValueType,
+) -> Function {
+    Function {
+        name,
+        arg_types,
+        min_args: arg_types.len(),
+        variadic: false,
+        return_type,
+    }
+}
+
+const SCALAR: ValueType = ValueType::Scalar;
+const STRING: ValueType = ValueType::String;
+const VECTOR: ValueType = ValueType::Vector;
+const MATRIX: ValueType = ValueType::Matrix;
+
+const FUNCTIONS: &[Function] = &[
+    function("abs", &[VECTOR], VECTOR),
+    function("absent", &[VECTOR], VECTOR),
+    function("avg_over_time", &[MATRIX], VECTOR),
+    function("ceil", &[VECTOR], VECTOR),
+    function("changes", &[MATRIX], VECTOR),
+    function("clamp", &[VECTOR, SCALAR, SCALAR], VECTOR),
+    function("clamp_max", &[VECTOR, SCALAR], VECTOR),
+    function("clamp_min", &[VECTOR, SCALAR], VECTOR),
+    function("count_over_time", &[MATRIX], VECTOR),
+    function("delta", &[MATRIX], VECTOR),
+    function("deriv", &[MATRIX], VECTOR),
+    function("exp", &[VECTOR], VECTOR),
+    function("floor", &[VECTOR], VECTOR),
+    function("histogram_quantile", &[SCALAR, VECTOR], VECTOR),
+    function("idelta", &[MATRIX], VECTOR),
+    function("increase", &[MATRIX], VECTOR),
+    function("irate", &[MATRIX], VECTOR),
+    Function {
+        variadic: true,
+        ..function("label_join", &[VECTOR, STRING, STRING, STRING], VECTOR)
+    },
+    function(
+        "label_replace",
+        &[VECTOR, STRING, STRING, STRING, STRING],
+        VECTOR,
+    ),
+    function("last_over_time", &[MATRIX], VECTOR),
+    function("ln", &[VECTOR], VECTOR),
+    function("log10", &[VECTOR], VECTOR),
+    function("log2", &[VECTOR], VECTOR),
+    function("max_over_time", &[MATRIX], VECTOR),
+    function("min_over_time", &[MATRIX], VECTOR),
+    function("predict_linear", &[MATRIX, SCALAR], VECTOR),
+    function("present_over_time", &[MATRIX], VECTOR),
+    function("quantile_over_time", &[SCALAR, MATRIX], VECTOR),
+    function("rate", &[MATRIX], VECTOR),
+    function("resets", &[MATRIX], VECTOR),
+    Function {
+        min_args: 1,
+        ..function("round", &[VECTOR, SCALAR], VECTOR)
+    },
+    function("scalar", &[VECTOR], SCALAR),
+    function("sgn", &[VECTOR], VECTOR),
+    function("sort", &[VECTOR], VECTOR),
+    function("sort_desc", &[VECTOR], VECTOR),
+    function("sqrt", &[VECTOR], VECTOR),
+    function("stddev_over_time", &[MATRIX], VECTOR),
+    function("stdvar_over_time", &[MATRIX], VECTOR),
+    function("sum_over_time", &[MATRIX], VECTOR),
+    function("time", &[], SCALAR),
+    function("vector", &[SCALAR], VECTOR),
+];
+
+impl Function {
+    pub fn get(name: &str) -> Option<&'static Function> {
+        FUNCTIONS.iter().find(|f| f.name == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregateOp {
+    Sum,
+    Avg,
+    Count,
+    Min,
+    Max,
+    Group,
+    Stddev,
+    Stdvar,
+    Topk,
+    Bottomk,
+    Quantile,
+    CountValues,
+}
+
+impl AggregateOp {
+    pub fn from_name(name: &str) -> Option<Self> {
+        let op = match name {
+            "sum" => AggregateOp::Sum,
+            "avg" => AggregateOp::Avg,
+            "count" => AggregateOp::Count,
+            "min" => AggregateOp::Min,
+            "max" => AggregateOp::Max,
+            "group" => AggregateOp::Group,
+            "stddev" => AggregateOp::Stddev,
+            "stdvar" => AggregateOp::Stdvar,
+            "topk" => AggregateOp::Topk,
+            "bottomk" => AggregateOp::Bottomk,
+            "quantile" => AggregateOp::Quantile,
+            "count_values" => AggregateOp::CountValues,
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Returns the type of the parameter preceding the aggregated vector, if any.
+    pub fn param_type(&self) -> Option<ValueType> {
+        match self {
+            AggregateOp::Topk | AggregateOp::Bottomk | AggregateOp::Quantile => {
+                Some(ValueType::Scalar)
+            }
+            AggregateOp::CountValues => Some(ValueType::String),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Grouping {
+    By(Vec<String>),
+    Without(Vec<String>),
+}
+
+impl Default for Grouping {
+    fn default() -> Self {
+        Grouping::By(vec![])
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AggregateExpr {
+    pub op: AggregateOp,
+    pub expr: Box<Expr>,
+    pub param: Option<Box<Expr>>,
+    pub grouping: Grouping,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+    Atan2,
+    Eq,
+    Ne,
+    Gt,
+    Lt,
+    Ge,
+    Le,
+    And,
+    Or,
+    Unless,
+}
+
+impl BinaryOp {
+    /// Precedence of the operator, operators with higher precedence bind tighter.
+    pub fn precedence(&self) -> u8 {
+        match self {
+            BinaryOp::Or => 1,
+            BinaryOp::And | BinaryOp::Unless => 2,
+            BinaryOp::Eq
+            | BinaryOp::Ne
+            | BinaryOp::Gt
+            | BinaryOp::Lt
+            | BinaryOp::Ge
+            | BinaryOp::Le => 3,
+            BinaryOp::Add | BinaryOp::Sub => 4,
+            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod | BinaryOp::Atan2 => 5,
+            BinaryOp::Pow => 6,
+        }
+    }
+
+    pub fn is_right_associative(&self) -> bool {
+        *self == BinaryOp::Pow
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Eq | BinaryOp::Ne | BinaryOp::Gt | BinaryOp::Lt | BinaryOp::Ge | BinaryOp::Le
+        )
+    }
+
+    pub fn is_set_operator(&self) -> bool {
+        matches!(self, BinaryOp::And | BinaryOp::Or | BinaryOp::Unless)
+    }
+}
+
+/// How the series on both sides of a binary operator between vectors are matched.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VectorMatching {
+    pub card: Cardinality,
+    /// Labels to match on (`on`), or to ignore (`ignoring`) if `on` is false.
+    pub labels: Vec<String>,
+    pub on: bool,
+    /// Extra labels to copy from the "one" side of a many-to-one / one-to-many matching.
+    pub include: Vec<String>,
+}
+
+impl Default for VectorMatching {
+    fn default() -> Self {
+        Self {
+            card: Cardinality::OneToOne,
+            labels: vec![],
+            on: false,
+            include: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cardinality {
+    OneToOne,
+    ManyToOne,
+    OneToMany,
+    ManyToMany,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BinaryExpr {
+    pub op: BinaryOp,
+    pub lhs: Box<Expr>,
+    pub rhs: Box<Expr>,
+    /// Whether a comparison returns 0/1 instead of filtering.
+    pub return_bool: bool,
+    /// Matching of series, only present if both sides are vectors.
+    pub matching: Option<VectorMatching>,
+}
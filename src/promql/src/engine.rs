@@ -0,0 +1,975 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Evaluation of PromQL expressions.
+//!
+//! A query is lowered into a logical plan by the [PromPlanner], and executed by the [Storage].
+//! Selectors are planned as scans of the metric tables with the label matchers and the time
+//! range pushed down, so only the samples of the selected series are read. Functions,
+//! aggregations and binary operators are evaluated by the plan nodes over the series at all
+//! steps of the query range at once.
+//!
+//! A selector fails the query once it reads more than `max_samples` samples (see
+//! [Engine::with_max_samples]).
+
+pub(crate) mod aggregate;
+pub(crate) mod binary;
+pub(crate) mod functions;
+
+use std::collections::{BTreeSet, HashMap};
+
+use async_trait::async_trait;
+use datafusion::logical_plan::LogicalPlan as DfLogicalPlan;
+use datafusion_common::record_batch::RecordBatch as DfRecordBatch;
+use snafu::ensure;
+use table::TableRef;
+
+use crate::ast::{Expr, ValueType, VectorSelector, METRIC_NAME};
+use crate::error::{self, Result};
+use crate::extension_plan::{batch_labels, SeriesGrouper};
+use crate::parser::parse;
+use crate::planner::{
+    matrix_window, string_literal, PromPlanner, TIMESTAMP_COLUMN_NAME, VALUE_COLUMN_NAME,
+};
+use crate::value::{Labels, Sample, Series, Value, VectorElement};
+
+/// How far an instant vector selector looks back for the latest sample, same as Prometheus.
+pub const DEFAULT_LOOKBACK_DELTA_MS: i64 = 5 * 60 * 1000;
+/// Resolution of subqueries without explicit step in instant queries.
+pub const DEFAULT_SUBQUERY_STEP_MS: i64 = 60 * 1000;
+/// Max number of points of a series in range query results, same as Prometheus.
+pub const MAX_POINTS_PER_SERIES: i64 = 11000;
+/// Default max number of samples a selector could read, same as Prometheus.
+pub const DEFAULT_MAX_SAMPLES: usize = 50_000_000;
+
+/// Storage of the Prometheus metrics, each metric is stored in a table named after it.
+///
+/// A metric table has a string column for each label, the timestamp column
+/// [TIMESTAMP_COLUMN_NAME] and the value column [VALUE_COLUMN_NAME].
+#[async_trait]
+pub trait Storage: Send + Sync {
+    /// Returns the names of all metrics.
+    fn metric_names(&self) -> Result<Vec<String>>;
+
+    /// Returns the table of the `metric`, `None` if the metric doesn't exist.
+    fn table(&self, metric: &str) -> Result<Option<TableRef>>;
+
+    /// Executes the `plan` built by the [PromPlanner], the physical planner must plan the
+    /// PromQL nodes by the [PromExtensionPlanner](crate::extension_plan::PromExtensionPlanner).
+    async fn execute(&self, plan: DfLogicalPlan) -> Result<Vec<DfRecordBatch>>;
+}
+
+/// Timestamps (in milliseconds) to evaluate an expression at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalRange {
+    pub start: i64,
+    pub end: i64,
+    pub step: i64,
+}
+
+impl EvalRange {
+    pub fn new(start: i64, end: i64, step: i64) -> Result<Self> {
+        ensure!(
+            end >= start,
+            error::InvalidRangeSnafu {
+                msg: "end timestamp must not be before start time",
+            }
+        );
+        ensure!(
+            step > 0,
+            error::InvalidRangeSnafu {
+                msg: "zero or negative query resolution step widths are not accepted",
+            }
+        );
+        ensure!(
+            (end - start) / step <= MAX_POINTS_PER_SERIES,
+            error::InvalidRangeSnafu {
+                msg: format!(
+                    "exceeded maximum resolution of {} points per timeseries, try decreasing the query resolution",
+                    MAX_POINTS_PER_SERIES
+                ),
+            }
+        );
+        Ok(Self { start, end, step })
+    }
+
+    pub fn instant(time: i64) -> Self {
+        Self {
+            start: time,
+            end: time,
+            step: DEFAULT_SUBQUERY_STEP_MS,
+        }
+    }
+
+    pub(crate) fn num_steps(&self) -> usize {
+        if self.end < self.start {
+            0
+        } else {
+            ((self.end - self.start) / self.step + 1) as usize
+        }
+    }
+
+    pub(crate) fn timestamps(&self) -> Vec<i64> {
+        (0..self.num_steps() as i64)
+            .map(|i| self.start + i * self.step)
+            .collect()
+    }
+}
+
+/// Series of an instant vector, with one value per step of the evaluation range.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StepSeries {
+    pub(crate) labels: Labels,
+    pub(crate) values: Vec<Option<f64>>,
+}
+
+/// Raw samples of a range vector, the window at step `t` is `(t - offset - range, t - offset]`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct RangeVector {
+    pub(crate) series: Vec<Series>,
+    pub(crate) range: i64,
+    pub(crate) offset: i64,
+}
+
+impl RangeVector {
+    /// Calls `f` with the samples in the window of each step, for each series.
+    fn map_windows(
+        &self,
+        timestamps: &[i64],
+        mut f: impl FnMut(&[Sample], i64, usize) -> Option<f64>,
+    ) -> Vec<Vec<Option<f64>>> {
+        self.series
+            .iter()
+            .map(|series| map_windows(&series.samples, self.range, self.offset, timestamps, &mut f))
+            .collect()
+    }
+}
+
+/// Calls `f` with the `samples` in the window `(t - offset - range, t - offset]` of each step
+/// `t` in `timestamps`, the value is `None` at steps without samples.
+pub(crate) fn map_windows(
+    samples: &[Sample],
+    range: i64,
+    offset: i64,
+    timestamps: &[i64],
+    mut f: impl FnMut(&[Sample], i64, usize) -> Option<f64>,
+) -> Vec<Option<f64>> {
+    let (mut lo, mut hi) = (0, 0);
+    timestamps
+        .iter()
+        .enumerate()
+        .map(|(step, ts)| {
+            let window_end = ts - offset;
+            let window_start = window_end - range;
+            while lo < samples.len() && samples[lo].timestamp <= window_start {
+                lo += 1;
+            }
+            hi = hi.max(lo);
+            while hi < samples.len() && samples[hi].timestamp <= window_end {
+                hi += 1;
+            }
+            if lo == hi {
+                None
+            } else {
+                f(&samples[lo..hi], window_end, step)
+            }
+        })
+        .collect()
+}
+
+/// Intermediate value of an expression evaluated at all steps of the evaluation range.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum EvalValue {
+    Scalar(Vec<f64>),
+    String(String),
+    Vector(Vec<StepSeries>),
+    Matrix(RangeVector),
+}
+
+impl EvalValue {
+    fn type_name(&self) -> &'static str {
+        match self {
+            EvalValue::Scalar(_) => "scalar",
+            EvalValue::String(_) => "string",
+            EvalValue::Vector(_) => "instant vector",
+            EvalValue::Matrix(_) => "range vector",
+        }
+    }
+
+    pub(crate) fn into_scalar(self) -> Result<Vec<f64>> {
+        match self {
+            EvalValue::Scalar(values) => Ok(values),
+            other => unexpected_type("scalar", &other),
+        }
+    }
+
+    pub(crate) fn into_string(self) -> Result<String> {
+        match self {
+            EvalValue::String(s) => Ok(s),
+            other => unexpected_type("string", &other),
+        }
+    }
+
+    pub(crate) fn into_vector(self) -> Result<Vec<StepSeries>> {
+        match self {
+            EvalValue::Vector(series) => Ok(series),
+            other => unexpected_type("instant vector", &other),
+        }
+    }
+
+    pub(crate) fn into_matrix(self) -> Result<RangeVector> {
+        match self {
+            EvalValue::Matrix(range_vector) => Ok(range_vector),
+            other => unexpected_type("range vector", &other),
+        }
+    }
+}
+
+pub(crate) fn unexpected_type<T>(expected: &str, actual: &EvalValue) -> Result<T> {
+    error::EvalSnafu {
+        msg: format!("expected {}, got {}", expected, actual.type_name()),
+    }
+    .fail()
+}
+
+/// Removes the metric name from labels of the result of an operation that changes the meaning
+/// of the samples.
+pub(crate) fn drop_metric_name(mut labels: Labels) -> Labels {
+    labels.remove(METRIC_NAME);
+    labels
+}
+
+/// Evaluates PromQL queries over the metrics in the [Storage].
+pub struct Engine<S> {
+    storage: S,
+    lookback_delta: i64,
+    /// Max number of samples each selector could read.
+    max_samples: usize,
+}
+
+impl<S: Storage> Engine<S> {
+    pub fn new(storage: S) -> Self {
+        Self {
+            storage,
+            lookback_delta: DEFAULT_LOOKBACK_DELTA_MS,
+            max_samples: DEFAULT_MAX_SAMPLES,
+        }
+    }
+
+    /// Sets the max number of samples each selector of a query could read from the storage.
+    pub fn with_max_samples(mut self, max_samples: usize) -> Self {
+        self.max_samples = max_samples;
+        self
+    }
+
+    /// Evaluates the `query` at timestamp `time`.
+    pub async fn instant_query(&self, query: &str, time: i64) -> Result<Value> {
+        let expr = parse(query)?;
+        let value_type = expr.value_type();
+        if value_type == ValueType::String {
+            return Ok(Value::String(time, string_literal(&expr)?));
+        }
+
+        let mut series = self.eval(&expr, EvalRange::instant(time)).await?;
+        let value = match value_type {
+            ValueType::Scalar => {
+                let value = series
+                    .first()
+                    .and_then(|s| s.samples.first())
+                    .map(|s| s.value)
+                    .unwrap_or(f64::NAN);
+                Value::Scalar(Sample::new(time, value))
+            }
+            ValueType::Matrix => {
+                // Safe to unwrap since the expression is a range vector.
+                let (range, offset) = matrix_window(&expr).unwrap();
+                let window_end = time - offset;
+                let window_start = window_end - range;
+                for s in &mut series {
+                    s.samples
+                        .retain(|s| s.timestamp > window_start && s.timestamp <= window_end);
+                }
+                series.retain(|s| !s.samples.is_empty());
+                series.sort_by(|a, b| a.labels.cmp(&b.labels));
+                Value::Matrix(series)
+            }
+            _ => {
+                let mut elements: Vec<_> = series
+                    .into_iter()
+                    .filter_map(|s| {
+                        s.samples.last().map(|sample| VectorElement {
+                            labels: s.labels,
+                            sample: *sample,
+                        })
+                    })
+                    .collect();
+                // Keeps the order of series sorted by `sort()` and `sort_desc()`.
+                if !is_sort(&expr) {
+                    elements.sort_by(|a, b| a.labels.cmp(&b.labels));
+                }
+                Value::Vector(elements)
+            }
+        };
+        Ok(value)
+    }
+
+    /// Evaluates the `query` at each step of the `range`, the result is always a matrix.
+    pub async fn range_query(&self, query: &str, range: EvalRange) -> Result<Value> {
+        let expr = parse(query)?;
+        let value_type = expr.value_type();
+        ensure!(
+            matches!(value_type, ValueType::Scalar | ValueType::Vector),
+            error::EvalSnafu {
+                msg: format!(
+                    "invalid expression type \"{}\" for range query, must be scalar or instant vector",
+                    value_type.name()
+                ),
+            }
+        );
+
+        let mut matrix = self.eval(&expr, range).await?;
+        matrix.sort_by(|a, b| a.labels.cmp(&b.labels));
+        Ok(Value::Matrix(matrix))
+    }
+
+    /// Returns labels of the series matching any of the selectors in `matches`.
+    pub async fn series(&self, matches: &[String], start: i64, end: i64) -> Result<Vec<Labels>> {
+        let mut labels = BTreeSet::new();
+        for selector in matches {
+            let selector = parse_selector(selector)?;
+            for (metric, plan) in self.planner().plan_series(&selector.matchers, start, end)? {
+                for batch in self.storage.execute(plan).await? {
+                    labels.extend(batch_labels(&batch)?.into_iter().map(|mut labels| {
+                        labels.insert(METRIC_NAME.to_string(), metric.clone());
+                        labels
+                    }));
+                }
+            }
+        }
+        Ok(labels.into_iter().collect())
+    }
+
+    /// Returns the label names of the series matching any of the selectors in `matches`, or of
+    /// all metrics if `matches` is empty.
+    pub async fn label_names(
+        &self,
+        matches: &[String],
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<String>> {
+        let mut names = BTreeSet::new();
+        if matches.is_empty() {
+            let metrics = self.storage.metric_names()?;
+            if !metrics.is_empty() {
+                names.insert(METRIC_NAME.to_string());
+            }
+            for metric in metrics {
+                if let Some(table) = self.storage.table(&metric)? {
+                    names.extend(
+                        table
+                            .schema()
+                            .column_schemas()
+                            .iter()
+                            .map(|column| &column.name)
+                            .filter(|name| {
+                                *name != TIMESTAMP_COLUMN_NAME && *name != VALUE_COLUMN_NAME
+                            })
+                            .cloned(),
+                    );
+                }
+            }
+        } else {
+            for labels in self.series(matches, start, end).await? {
+                names.extend(labels.into_keys());
+            }
+        }
+        Ok(names.into_iter().collect())
+    }
+
+    /// Returns the values of label `name` in the series matching any of the selectors in
+    /// `matches`, or in all series if `matches` is empty.
+    pub async fn label_values(
+        &self,
+        name: &str,
+        matches: &[String],
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<String>> {
+        let mut values = BTreeSet::new();
+        if !matches.is_empty() {
+            for mut labels in self.series(matches, start, end).await? {
+                values.extend(labels.remove(name));
+            }
+        } else if name == METRIC_NAME {
+            values.extend(self.storage.metric_names()?);
+        } else {
+            for plan in self.planner().plan_label_values(name, start, end)? {
+                for batch in self.storage.execute(plan).await? {
+                    for mut labels in batch_labels(&batch)? {
+                        values.extend(labels.remove(name));
+                    }
+                }
+            }
+        }
+        Ok(values.into_iter().collect())
+    }
+
+    fn planner(&self) -> PromPlanner<'_, S> {
+        PromPlanner::new(&self.storage, self.lookback_delta, self.max_samples)
+    }
+
+    /// Evaluates the `expr` at each step of the `range`, returns the series of the result.
+    async fn eval(&self, expr: &Expr, range: EvalRange) -> Result<Vec<Series>> {
+        let plan = self.planner().plan(expr, range)?;
+        let mut grouper = SeriesGrouper::default();
+        let mut series = Vec::new();
+        for batch in self.storage.execute(plan).await? {
+            grouper.push(&batch, |s| {
+                series.push(s);
+                Ok(())
+            })?;
+        }
+        series.extend(grouper.finish());
+        Ok(series)
+    }
+}
+
+/// Returns whether the `expr` is a call of `sort()` or `sort_desc()`.
+fn is_sort(expr: &Expr) -> bool {
+    match expr {
+        Expr::Call(call) => matches!(call.func.name, "sort" | "sort_desc"),
+        Expr::Paren(expr) => is_sort(expr),
+        _ => false,
+    }
+}
+
+fn parse_selector(selector: &str) -> Result<VectorSelector> {
+    match parse(selector)? {
+        Expr::VectorSelector(selector) => Ok(selector),
+        _ => error::EvalSnafu {
+            msg: format!("invalid series selector {}", selector),
+        }
+        .fail(),
+    }
+}
+
+/// Builds series of an instant vector whose labels are only known while evaluating each step.
+#[derive(Default)]
+pub(crate) struct StepSeriesBuilder {
+    index: HashMap<Labels, usize>,
+    series: Vec<StepSeries>,
+}
+
+impl StepSeriesBuilder {
+    /// Sets the value of series `labels` at `step`, returns false if the value has been set.
+    pub(crate) fn set(
+        &mut self,
+        labels: Labels,
+        step: usize,
+        num_steps: usize,
+        value: f64,
+    ) -> bool {
+        let series = &mut self.series;
+        let index = *self.index.entry(labels).or_insert_with_key(|labels| {
+            series.push(StepSeries {
+                labels: labels.clone(),
+                values: vec![None; num_steps],
+            });
+            series.len() - 1
+        });
+        let slot = &mut self.series[index].values[step];
+        let is_new = slot.is_none();
+        *slot = Some(value);
+        is_new
+    }
+
+    pub(crate) fn build(self) -> Vec<StepSeries> {
+        self.series
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Arc;
+
+    use common_recordbatch::RecordBatch;
+    use datafusion::error::{DataFusionError, Result as DfResult};
+    use datafusion::execution::context::{ExecutionContextState, QueryPlanner};
+    use datafusion::physical_plan::planner::DefaultPhysicalPlanner;
+    use datafusion::physical_plan::{collect, ExecutionPlan, PhysicalPlanner};
+    use datafusion::prelude::{ExecutionConfig, ExecutionContext};
+    use datatypes::prelude::{ConcreteDataType, VectorRef};
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampVector};
+    use table::test_util::MemTable;
+
+    use super::*;
+    use crate::extension_plan::PromExtensionPlanner;
+
+    const STEP_MS: i64 = 15 * 1000;
+
+    struct PromQueryPlanner;
+
+    #[async_trait]
+    impl QueryPlanner for PromQueryPlanner {
+        async fn create_physical_plan(
+            &self,
+            logical_plan: &DfLogicalPlan,
+            ctx_state: &ExecutionContextState,
+        ) -> DfResult<Arc<dyn ExecutionPlan>> {
+            DefaultPhysicalPlanner::with_extension_planners(vec![Arc::new(PromExtensionPlanner)])
+                .create_physical_plan(logical_plan, ctx_state)
+                .await
+        }
+    }
+
+    /// Storage of metrics in memory tables.
+    struct MockStorage {
+        tables: BTreeMap<String, TableRef>,
+        ctx: ExecutionContext,
+    }
+
+    impl MockStorage {
+        fn new(series: Vec<Series>) -> Self {
+            let mut metrics: BTreeMap<_, Vec<_>> = BTreeMap::new();
+            for mut s in series {
+                let metric = s.labels.remove(METRIC_NAME).unwrap();
+                metrics.entry(metric).or_default().push(s);
+            }
+            let tables = metrics
+                .into_iter()
+                .map(|(metric, series)| {
+                    let table = metric_table(&metric, &series);
+                    (metric, table)
+                })
+                .collect();
+            let config = ExecutionConfig::new().with_query_planner(Arc::new(PromQueryPlanner));
+            Self {
+                tables,
+                ctx: ExecutionContext::with_config(config),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Storage for MockStorage {
+        fn metric_names(&self) -> Result<Vec<String>> {
+            Ok(self.tables.keys().cloned().collect())
+        }
+
+        fn table(&self, metric: &str) -> Result<Option<TableRef>> {
+            Ok(self.tables.get(metric).cloned())
+        }
+
+        async fn execute(&self, plan: DfLogicalPlan) -> Result<Vec<DfRecordBatch>> {
+            let result = async {
+                let plan = self.ctx.optimize(&plan)?;
+                let plan = self.ctx.create_physical_plan(&plan).await?;
+                collect(plan, self.ctx.runtime_env()).await
+            }
+            .await;
+            // Returns the errors of the PromQL plan nodes as is.
+            result.map_err(|e| match e {
+                DataFusionError::External(e) => *e.downcast::<error::Error>().unwrap(),
+                e => panic!("failed to execute plan: {}", e),
+            })
+        }
+    }
+
+    /// Stores the `series` of the `metric` in a table.
+    fn metric_table(metric: &str, series: &[Series]) -> TableRef {
+        let names: BTreeSet<_> = series.iter().flat_map(|s| s.labels.keys()).collect();
+        let mut column_schemas = Vec::new();
+        let mut columns: Vec<VectorRef> = Vec::new();
+        for name in names {
+            column_schemas.push(ColumnSchema::new(
+                name,
+                ConcreteDataType::string_datatype(),
+                true,
+            ));
+            let values: Vec<_> = series
+                .iter()
+                .flat_map(|s| std::iter::repeat(s.labels.get(name).cloned()).take(s.samples.len()))
+                .collect();
+            columns.push(Arc::new(StringVector::from(values)));
+        }
+        let samples = || series.iter().flat_map(|s| &s.samples);
+        column_schemas.push(ColumnSchema::new(
+            TIMESTAMP_COLUMN_NAME,
+            ConcreteDataType::timestamp_millis_datatype(),
+            false,
+        ));
+        columns.push(Arc::new(TimestampVector::from_values(
+            samples().map(|s| s.timestamp),
+        )));
+        column_schemas.push(ColumnSchema::new(
+            VALUE_COLUMN_NAME,
+            ConcreteDataType::float64_datatype(),
+            true,
+        ));
+        columns.push(Arc::new(Float64Vector::from_values(
+            samples().map(|s| s.value),
+        )));
+
+        let schema = Arc::new(Schema::new(column_schemas));
+        let batch = RecordBatch::new(schema, columns).unwrap();
+        Arc::new(MemTable::new(metric, batch))
+    }
+
+    fn labels(pairs: &[(&str, &str)]) -> Labels {
+        pairs
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    /// Series with a sample every 15 seconds in the first 10 minutes.
+    fn series(pairs: &[(&str, &str)], f: impl Fn(i64) -> f64) -> Series {
+        Series {
+            labels: labels(pairs),
+            samples: (0..=40).map(|i| Sample::new(i * STEP_MS, f(i))).collect(),
+        }
+    }
+
+    fn new_engine() -> Engine<MockStorage> {
+        let metric = "http_requests_total";
+        Engine::new(MockStorage::new(vec![
+            series(
+                &[(METRIC_NAME, metric), ("job", "api"), ("instance", "a")],
+                |i| (i * 10) as f64,
+            ),
+            series(
+                &[(METRIC_NAME, metric), ("job", "api"), ("instance", "b")],
+                |i| (i * 20) as f64,
+            ),
+            series(
+                &[(METRIC_NAME, metric), ("job", "db"), ("instance", "a")],
+                |i| (i * 5) as f64,
+            ),
+            series(
+                &[(METRIC_NAME, "up"), ("job", "api"), ("instance", "a")],
+                |_| 1.0,
+            ),
+            series(
+                &[(METRIC_NAME, "up"), ("job", "api"), ("instance", "b")],
+                |_| 0.0,
+            ),
+        ]))
+    }
+
+    async fn instant_vector(
+        engine: &Engine<MockStorage>,
+        query: &str,
+        time: i64,
+    ) -> Vec<(Labels, f64)> {
+        match engine.instant_query(query, time).await.unwrap() {
+            Value::Vector(elements) => elements
+                .into_iter()
+                .map(|e| {
+                    assert_eq!(time, e.sample.timestamp);
+                    (e.labels, e.sample.value)
+                })
+                .collect(),
+            other => panic!("unexpected value: {:?}", other),
+        }
+    }
+
+    fn assert_float_eq(expected: f64, actual: f64) {
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[tokio::test]
+    async fn test_instant_query() {
+        let engine = new_engine();
+        let metric = "http_requests_total";
+
+        assert_eq!(
+            vec![
+                (
+                    labels(&[(METRIC_NAME, metric), ("job", "api"), ("instance", "a")]),
+                    200.0
+                ),
+                (
+                    labels(&[(METRIC_NAME, metric), ("job", "api"), ("instance", "b")]),
+                    400.0
+                ),
+            ],
+            instant_vector(&engine, r#"http_requests_total{job="api"}"#, 300_000).await
+        );
+        // Takes the latest sample in the lookback window.
+        assert_eq!(
+            vec![(
+                labels(&[(METRIC_NAME, "up"), ("job", "api"), ("instance", "a")]),
+                1.0
+            )],
+            instant_vector(&engine, r#"up{instance="a"}"#, 700_000).await
+        );
+        assert!(instant_vector(&engine, "up", 1_000_000).await.is_empty());
+        assert_eq!(
+            vec![(
+                labels(&[(METRIC_NAME, metric), ("job", "db"), ("instance", "a")]),
+                20.0
+            )],
+            instant_vector(
+                &engine,
+                r#"http_requests_total{job="db"} offset 4m"#,
+                300_000
+            )
+            .await
+        );
+
+        let rate = instant_vector(
+            &engine,
+            r#"rate(http_requests_total{job="api", instance="a"}[1m])"#,
+            300_000,
+        )
+        .await;
+        assert_eq!(1, rate.len());
+        assert_eq!(labels(&[("job", "api"), ("instance", "a")]), rate[0].0);
+        assert_float_eq(10.0 / 15.0, rate[0].1);
+
+        let increase = instant_vector(
+            &engine,
+            r#"increase(http_requests_total{job="db"}[2m])"#,
+            300_000,
+        )
+        .await;
+        assert_float_eq(40.0, increase[0].1);
+
+        assert_eq!(
+            vec![
+                (labels(&[("job", "api")]), 600.0),
+                (labels(&[("job", "db")]), 100.0)
+            ],
+            instant_vector(&engine, "sum by (job) (http_requests_total)", 300_000).await
+        );
+        assert_eq!(
+            vec![(
+                labels(&[(METRIC_NAME, metric), ("job", "api"), ("instance", "b")]),
+                400.0
+            )],
+            instant_vector(&engine, "topk(1, http_requests_total)", 300_000).await
+        );
+        assert_eq!(
+            vec![(labels(&[("job", "db")]), 100.0)],
+            instant_vector(
+                &engine,
+                r#"max_over_time(sum by (job) (http_requests_total{job="db"})[2m:30s])"#,
+                300_000
+            )
+            .await
+        );
+        assert_eq!(
+            vec![(labels(&[("job", "x")]), 1.0)],
+            instant_vector(&engine, r#"absent(nonexistent{job="x"})"#, 300_000).await
+        );
+        assert_eq!(
+            vec![(
+                labels(&[
+                    (METRIC_NAME, "up"),
+                    ("job", "api"),
+                    ("instance", "a"),
+                    ("dst", "a-api")
+                ]),
+                1.0
+            )],
+            instant_vector(
+                &engine,
+                r#"label_replace(up{instance="a"}, "dst", "$1-api", "instance", "(.*)")"#,
+                300_000
+            )
+            .await
+        );
+
+        match engine.instant_query("time() - 1", 300_000).await.unwrap() {
+            Value::Scalar(sample) => assert_eq!(Sample::new(300_000, 299.0), sample),
+            other => panic!("unexpected value: {:?}", other),
+        }
+        match engine.instant_query("up[30s]", 300_000).await.unwrap() {
+            Value::Matrix(series) => {
+                assert_eq!(2, series.len());
+                assert_eq!(
+                    vec![Sample::new(285_000, 1.0), Sample::new(300_000, 1.0)],
+                    series[0].samples
+                );
+            }
+            other => panic!("unexpected value: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_binary_operators() {
+        let engine = new_engine();
+
+        assert_eq!(
+            vec![(
+                labels(&[
+                    (METRIC_NAME, "http_requests_total"),
+                    ("job", "api"),
+                    ("instance", "b")
+                ]),
+                400.0
+            )],
+            instant_vector(&engine, r#"http_requests_total{job="api"} > 300"#, 300_000).await
+        );
+        assert_eq!(
+            vec![
+                (labels(&[("job", "api"), ("instance", "a")]), 1.0),
+                (labels(&[("job", "api"), ("instance", "b")]), 0.0),
+            ],
+            instant_vector(&engine, "up == bool 1", 300_000).await
+        );
+        assert_eq!(
+            vec![
+                (labels(&[("job", "api"), ("instance", "a")]), 200.0),
+                (labels(&[("job", "api"), ("instance", "b")]), 0.0),
+            ],
+            instant_vector(&engine, "http_requests_total * up", 300_000).await
+        );
+        assert_eq!(
+            vec![
+                (labels(&[("job", "api"), ("instance", "a")]), 1.0 / 3.0),
+                (labels(&[("job", "db"), ("instance", "a")]), 1.0),
+                (labels(&[("job", "api"), ("instance", "b")]), 2.0 / 3.0),
+            ],
+            instant_vector(
+                &engine,
+                "http_requests_total / ignoring(instance) group_left sum by (job) (http_requests_total)",
+                300_000
+            )
+            .await
+        );
+        assert_eq!(
+            vec![(
+                labels(&[
+                    (METRIC_NAME, "http_requests_total"),
+                    ("job", "db"),
+                    ("instance", "a")
+                ]),
+                100.0
+            )],
+            instant_vector(&engine, "http_requests_total unless on(job) up", 300_000).await
+        );
+        // Series of `up` are dropped since they have matching series on the left side.
+        assert_eq!(
+            3,
+            instant_vector(&engine, "http_requests_total or up", 300_000)
+                .await
+                .len()
+        );
+
+        let err = engine
+            .instant_query("http_requests_total + on(job) up", 300_000)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, error::Error::Eval { .. }), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_range_query() {
+        let engine = new_engine();
+
+        let range = EvalRange::new(120_000, 180_000, 30_000).unwrap();
+        match engine
+            .range_query("sum(rate(http_requests_total[1m]))", range)
+            .await
+            .unwrap()
+        {
+            Value::Matrix(series) => {
+                assert_eq!(1, series.len());
+                assert!(series[0].labels.is_empty());
+                let timestamps: Vec<_> = series[0].samples.iter().map(|s| s.timestamp).collect();
+                assert_eq!(vec![120_000, 150_000, 180_000], timestamps);
+                for sample in &series[0].samples {
+                    assert_float_eq(35.0 / 15.0, sample.value);
+                }
+            }
+            other => panic!("unexpected value: {:?}", other),
+        }
+
+        match engine.range_query("1 + 1", range).await.unwrap() {
+            Value::Matrix(series) => assert_eq!(3, series[0].samples.len()),
+            other => panic!("unexpected value: {:?}", other),
+        }
+        assert!(engine.range_query("up[1m]", range).await.is_err());
+        assert!(EvalRange::new(0, 1_000_000_000, 1).is_err());
+        assert!(EvalRange::new(100, 0, 1).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_metadata() {
+        let engine = new_engine();
+
+        let series = engine
+            .series(&[r#"up{instance="b"}"#.to_string()], 0, 600_000)
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![labels(&[
+                (METRIC_NAME, "up"),
+                ("job", "api"),
+                ("instance", "b")
+            ])],
+            series
+        );
+        assert!(engine
+            .series(&["sum(up)".to_string()], 0, 600_000)
+            .await
+            .is_err());
+
+        assert_eq!(
+            vec![METRIC_NAME, "instance", "job"],
+            engine.label_names(&[], 0, 600_000).await.unwrap()
+        );
+        assert_eq!(
+            vec!["http_requests_total", "up"],
+            engine
+                .label_values(METRIC_NAME, &[], 0, 600_000)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            vec!["api", "db"],
+            engine.label_values("job", &[], 0, 600_000).await.unwrap()
+        );
+        assert_eq!(
+            vec!["a", "b"],
+            engine
+                .label_values("instance", &["up".to_string()], 0, 600_000)
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_samples() {
+        // Each series has 41 samples, the selector selects 2 series.
+        let engine = new_engine().with_max_samples(82);
+        let query = "up[10m]";
+        assert!(engine.instant_query(query, 10 * 60 * 1000).await.is_ok());
+
+        let engine = new_engine().with_max_samples(81);
+        let err = engine
+            .instant_query(query, 10 * 60 * 1000)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, error::Error::TooManySamples { limit: 81 }));
+    }
+}
@@ -0,0 +1,185 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use crate::ast::{AggregateExpr, AggregateOp, Grouping, METRIC_NAME};
+use crate::engine::functions::quantile;
+use crate::engine::{EvalValue, StepSeries, StepSeriesBuilder};
+use crate::error::Result;
+use crate::value::{format_value, Labels};
+
+pub(crate) fn eval_aggregate(
+    aggregate: &AggregateExpr,
+    param: Option<EvalValue>,
+    series: Vec<StepSeries>,
+    num_steps: usize,
+) -> Result<EvalValue> {
+    let mut groups: BTreeMap<Labels, Vec<&StepSeries>> = BTreeMap::new();
+    for s in &series {
+        groups
+            .entry(group_labels(&s.labels, &aggregate.grouping))
+            .or_default()
+            .push(s);
+    }
+
+    let result = match aggregate.op {
+        AggregateOp::Topk | AggregateOp::Bottomk => {
+            // Safe to unwrap since the parser ensures the parameter exists.
+            let k = param.unwrap().into_scalar()?;
+            top_k(&groups, &k, aggregate.op == AggregateOp::Topk, num_steps)
+        }
+        AggregateOp::CountValues => {
+            let label = param.unwrap().into_string()?;
+            count_values(&groups, &label, num_steps)
+        }
+        op => {
+            let phi = match param {
+                Some(param) => param.into_scalar()?,
+                None => vec![],
+            };
+            groups
+                .into_iter()
+                .map(|(labels, group)| {
+                    let values = (0..num_steps)
+                        .map(|step| {
+                            let mut values: Vec<_> =
+                                group.iter().filter_map(|s| s.values[step]).collect();
+                            (!values.is_empty()).then(|| match op {
+                                AggregateOp::Quantile => quantile(phi[step], &mut values),
+                                op => aggregate_values(op, &values),
+                            })
+                        })
+                        .collect();
+                    StepSeries { labels, values }
+                })
+                .collect()
+        }
+    };
+    Ok(EvalValue::Vector(result))
+}
+
+fn group_labels(labels: &Labels, grouping: &Grouping) -> Labels {
+    labels
+        .iter()
+        .filter(|(name, _)| match grouping {
+            Grouping::By(names) => names.contains(*name),
+            Grouping::Without(names) => name.as_str() != METRIC_NAME && !names.contains(*name),
+        })
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+fn aggregate_values(op: AggregateOp, values: &[f64]) -> f64 {
+    let count = values.len() as f64;
+    match op {
+        AggregateOp::Sum => values.iter().sum(),
+        AggregateOp::Avg => values.iter().sum::<f64>() / count,
+        AggregateOp::Count => count,
+        AggregateOp::Group => 1.0,
+        AggregateOp::Min => {
+            values
+                .iter()
+                .copied()
+                .fold(f64::NAN, |a, b| if a.is_nan() || b < a { b } else { a })
+        }
+        AggregateOp::Max => {
+            values
+                .iter()
+                .copied()
+                .fold(f64::NAN, |a, b| if a.is_nan() || b > a { b } else { a })
+        }
+        AggregateOp::Stddev | AggregateOp::Stdvar => {
+            let mean = values.iter().sum::<f64>() / count;
+            let variance = values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / count;
+            if op == AggregateOp::Stddev {
+                variance.sqrt()
+            } else {
+                variance
+            }
+        }
+        AggregateOp::Topk
+        | AggregateOp::Bottomk
+        | AggregateOp::Quantile
+        | AggregateOp::CountValues => unreachable!(),
+    }
+}
+
+/// Selects the `k` largest (or smallest) series of each group at each step, the selected series
+/// keep their labels.
+fn top_k(
+    groups: &BTreeMap<Labels, Vec<&StepSeries>>,
+    k: &[f64],
+    largest: bool,
+    num_steps: usize,
+) -> Vec<StepSeries> {
+    let mut result = Vec::new();
+    for group in groups.values() {
+        let mut values = vec![vec![None; num_steps]; group.len()];
+        for (step, k) in k.iter().enumerate() {
+            if k.is_nan() || *k < 1.0 {
+                continue;
+            }
+            let mut candidates: Vec<_> = group
+                .iter()
+                .enumerate()
+                .filter_map(|(i, s)| s.values[step].map(|v| (i, v)))
+                .collect();
+            // NaN values are always selected last.
+            candidates.sort_by(|(_, a), (_, b)| match (a.is_nan(), b.is_nan()) {
+                (false, false) if largest => b.total_cmp(a),
+                (false, false) => a.total_cmp(b),
+                (a_nan, b_nan) => a_nan.cmp(&b_nan),
+            });
+            for (i, v) in candidates.into_iter().take(*k as usize) {
+                values[i][step] = Some(v);
+            }
+        }
+
+        result.extend(
+            group
+                .iter()
+                .zip(values)
+                .filter(|(_, values)| values.iter().any(Option::is_some))
+                .map(|(s, values)| StepSeries {
+                    labels: s.labels.clone(),
+                    values,
+                }),
+        );
+    }
+    result
+}
+
+/// Counts the series with the same value in each group, the value is stored in label `label`.
+fn count_values(
+    groups: &BTreeMap<Labels, Vec<&StepSeries>>,
+    label: &str,
+    num_steps: usize,
+) -> Vec<StepSeries> {
+    let mut builder = StepSeriesBuilder::default();
+    for (labels, group) in groups {
+        for step in 0..num_steps {
+            let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+            for value in group.iter().filter_map(|s| s.values[step]) {
+                *counts.entry(format_value(value)).or_default() += 1;
+            }
+            for (value, count) in counts {
+                let mut labels = labels.clone();
+                labels.insert(label.to_string(), value);
+                builder.set(labels, step, num_steps, count as f64);
+            }
+        }
+    }
+    builder.build()
+}
@@ -0,0 +1,354 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+
+use snafu::ensure;
+
+use crate::ast::{BinaryExpr, BinaryOp, Cardinality, VectorMatching, METRIC_NAME};
+use crate::engine::{drop_metric_name, EvalValue, StepSeries, StepSeriesBuilder};
+use crate::error::{self, Result};
+use crate::value::Labels;
+
+pub(crate) fn eval_binary(
+    binary: &BinaryExpr,
+    lhs: EvalValue,
+    rhs: EvalValue,
+    num_steps: usize,
+) -> Result<EvalValue> {
+    let (op, return_bool) = (binary.op, binary.return_bool);
+    let value = match (lhs, rhs) {
+        (EvalValue::Scalar(lhs), EvalValue::Scalar(rhs)) => EvalValue::Scalar(
+            lhs.into_iter()
+                .zip(rhs)
+                .map(|(l, r)| match apply(op, l, r) {
+                    (_, keep) if op.is_comparison() => bool_value(keep),
+                    (value, _) => value,
+                })
+                .collect(),
+        ),
+        (EvalValue::Vector(lhs), EvalValue::Scalar(rhs)) => {
+            vector_scalar(op, lhs, &rhs, false, return_bool)
+        }
+        (EvalValue::Scalar(lhs), EvalValue::Vector(rhs)) => {
+            vector_scalar(op, rhs, &lhs, true, return_bool)
+        }
+        (EvalValue::Vector(lhs), EvalValue::Vector(rhs)) => {
+            let default_matching = VectorMatching::default();
+            let matching = binary.matching.as_ref().unwrap_or(&default_matching);
+            match op {
+                BinaryOp::And | BinaryOp::Unless => {
+                    and_unless(lhs, rhs, matching, op == BinaryOp::And, num_steps)
+                }
+                BinaryOp::Or => or(lhs, rhs, matching, num_steps),
+                _ => vector_vector(op, lhs, rhs, matching, return_bool, num_steps)?,
+            }
+        }
+        (lhs, rhs) => {
+            return error::EvalSnafu {
+                msg: format!(
+                    "invalid operands of binary expression: {} and {}",
+                    lhs.type_name(),
+                    rhs.type_name()
+                ),
+            }
+            .fail()
+        }
+    };
+    Ok(value)
+}
+
+/// Returns the result of `lhs op rhs` and whether the comparison holds. The result of a
+/// comparison is `lhs`.
+fn apply(op: BinaryOp, lhs: f64, rhs: f64) -> (f64, bool) {
+    match op {
+        BinaryOp::Add => (lhs + rhs, true),
+        BinaryOp::Sub => (lhs - rhs, true),
+        BinaryOp::Mul => (lhs * rhs, true),
+        BinaryOp::Div => (lhs / rhs, true),
+        BinaryOp::Mod => (lhs % rhs, true),
+        BinaryOp::Pow => (lhs.powf(rhs), true),
+        BinaryOp::Atan2 => (lhs.atan2(rhs), true),
+        BinaryOp::Eq => (lhs, lhs == rhs),
+        BinaryOp::Ne => (lhs, lhs != rhs),
+        BinaryOp::Gt => (lhs, lhs > rhs),
+        BinaryOp::Lt => (lhs, lhs < rhs),
+        BinaryOp::Ge => (lhs, lhs >= rhs),
+        BinaryOp::Le => (lhs, lhs <= rhs),
+        BinaryOp::And | BinaryOp::Or | BinaryOp::Unless => unreachable!(),
+    }
+}
+
+fn bool_value(b: bool) -> f64 {
+    if b {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Whether the metric name is removed from the result, comparisons that filter series keep
+/// the metric name.
+pub(crate) fn drops_metric_name(op: BinaryOp, return_bool: bool) -> bool {
+    !op.is_comparison() || return_bool
+}
+
+fn vector_scalar(
+    op: BinaryOp,
+    vector: Vec<StepSeries>,
+    scalar: &[f64],
+    scalar_on_left: bool,
+    return_bool: bool,
+) -> EvalValue {
+    EvalValue::Vector(
+        vector
+            .into_iter()
+            .map(|s| {
+                let values = s
+                    .values
+                    .into_iter()
+                    .zip(scalar)
+                    .map(|(v, scalar)| {
+                        let v = v?;
+                        let (value, keep) = if scalar_on_left {
+                            apply(op, *scalar, v)
+                        } else {
+                            apply(op, v, *scalar)
+                        };
+                        if return_bool {
+                            Some(bool_value(keep))
+                        } else if op.is_comparison() {
+                            // Keeps the value of the vector even if it's the right operand.
+                            keep.then(|| v)
+                        } else {
+                            Some(value)
+                        }
+                    })
+                    .collect();
+                let labels = if drops_metric_name(op, return_bool) {
+                    drop_metric_name(s.labels)
+                } else {
+                    s.labels
+                };
+                StepSeries { labels, values }
+            })
+            .filter(|s| s.values.iter().any(Option::is_some))
+            .collect(),
+    )
+}
+
+/// Returns the labels used to match series on both sides.
+fn signature(labels: &Labels, matching: &VectorMatching) -> Labels {
+    labels
+        .iter()
+        .filter(|(name, _)| {
+            if matching.on {
+                matching.labels.contains(*name)
+            } else {
+                name.as_str() != METRIC_NAME && !matching.labels.contains(*name)
+            }
+        })
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect()
+}
+
+/// Returns the signatures of series having value at each step.
+fn signatures_by_step(
+    series: &[StepSeries],
+    matching: &VectorMatching,
+    num_steps: usize,
+) -> Vec<HashSet<Labels>> {
+    let signatures: Vec<_> = series
+        .iter()
+        .map(|s| signature(&s.labels, matching))
+        .collect();
+    (0..num_steps)
+        .map(|step| {
+            series
+                .iter()
+                .zip(&signatures)
+                .filter(|(s, _)| s.values[step].is_some())
+                .map(|(_, signature)| signature.clone())
+                .collect()
+        })
+        .collect()
+}
+
+fn and_unless(
+    lhs: Vec<StepSeries>,
+    rhs: Vec<StepSeries>,
+    matching: &VectorMatching,
+    is_and: bool,
+    num_steps: usize,
+) -> EvalValue {
+    let rhs_signatures = signatures_by_step(&rhs, matching, num_steps);
+    EvalValue::Vector(
+        lhs.into_iter()
+            .map(|mut s| {
+                let signature = signature(&s.labels, matching);
+                for (step, value) in s.values.iter_mut().enumerate() {
+                    if rhs_signatures[step].contains(&signature) != is_and {
+                        *value = None;
+                    }
+                }
+                s
+            })
+            .filter(|s| s.values.iter().any(Option::is_some))
+            .collect(),
+    )
+}
+
+fn or(
+    lhs: Vec<StepSeries>,
+    rhs: Vec<StepSeries>,
+    matching: &VectorMatching,
+    num_steps: usize,
+) -> EvalValue {
+    let lhs_signatures = signatures_by_step(&lhs, matching, num_steps);
+    let mut builder = StepSeriesBuilder::default();
+    for s in lhs {
+        for (step, value) in s.values.into_iter().enumerate() {
+            if let Some(value) = value {
+                builder.set(s.labels.clone(), step, num_steps, value);
+            }
+        }
+    }
+    for s in rhs {
+        let signature = signature(&s.labels, matching);
+        for (step, value) in s.values.into_iter().enumerate() {
+            match value {
+                Some(value) if !lhs_signatures[step].contains(&signature) => {
+                    builder.set(s.labels.clone(), step, num_steps, value);
+                }
+                _ => {}
+            }
+        }
+    }
+    EvalValue::Vector(builder.build())
+}
+
+fn vector_vector(
+    op: BinaryOp,
+    lhs: Vec<StepSeries>,
+    rhs: Vec<StepSeries>,
+    matching: &VectorMatching,
+    return_bool: bool,
+    num_steps: usize,
+) -> Result<EvalValue> {
+    // Series on the "one" side are matched by series on the "many" side.
+    let swapped = matching.card == Cardinality::OneToMany;
+    let (many, one) = if swapped { (rhs, lhs) } else { (lhs, rhs) };
+    let many_signatures: Vec<_> = many
+        .iter()
+        .map(|s| signature(&s.labels, matching))
+        .collect();
+    let one_signatures: Vec<_> = one.iter().map(|s| signature(&s.labels, matching)).collect();
+
+    let mut builder = StepSeriesBuilder::default();
+    for step in 0..num_steps {
+        let mut one_index = HashMap::with_capacity(one.len());
+        for (i, signature) in one_signatures.iter().enumerate() {
+            if one[i].values[step].is_none() {
+                continue;
+            }
+            ensure!(
+                one_index.insert(signature, i).is_none(),
+                error::EvalSnafu {
+                    msg: format!(
+                        "found duplicate series for the match group {:?} on the {} hand-side of the operation, many-to-many matching not allowed: matching labels must be unique on one side",
+                        signature,
+                        if swapped { "left" } else { "right" }
+                    ),
+                }
+            );
+        }
+
+        let mut matched = HashSet::new();
+        for (i, signature) in many_signatures.iter().enumerate() {
+            let many_value = match many[i].values[step] {
+                Some(value) => value,
+                None => continue,
+            };
+            let j = match one_index.get(signature) {
+                Some(j) => *j,
+                None => continue,
+            };
+            if matching.card == Cardinality::OneToOne {
+                ensure!(
+                    matched.insert(signature),
+                    error::EvalSnafu {
+                        msg: format!(
+                            "multiple matches for labels {:?}, many-to-one matching must be explicit (group_left/group_right)",
+                            signature
+                        ),
+                    }
+                );
+            }
+
+            // Safe to unwrap since series in `one_index` have value at this step.
+            let one_value = one[j].values[step].unwrap();
+            let (l, r) = if swapped {
+                (one_value, many_value)
+            } else {
+                (many_value, one_value)
+            };
+            let value = match apply(op, l, r) {
+                (_, keep) if return_bool => bool_value(keep),
+                (value, true) => value,
+                (_, false) => continue,
+            };
+
+            let labels = result_labels(&many[i].labels, &one[j].labels, op, matching, return_bool);
+            ensure!(
+                builder.set(labels, step, num_steps, value),
+                error::EvalSnafu {
+                    msg: "multiple matches for labels: grouping labels must ensure unique matches",
+                }
+            );
+        }
+    }
+    Ok(EvalValue::Vector(builder.build()))
+}
+
+fn result_labels(
+    many: &Labels,
+    one: &Labels,
+    op: BinaryOp,
+    matching: &VectorMatching,
+    return_bool: bool,
+) -> Labels {
+    let mut labels = if drops_metric_name(op, return_bool) {
+        drop_metric_name(many.clone())
+    } else {
+        many.clone()
+    };
+    if matching.card == Cardinality::OneToOne {
+        if matching.on {
+            labels.retain(|name, _| matching.labels.contains(name));
+        } else {
+            labels.retain(|name, _| !matching.labels.contains(name));
+        }
+    }
+    for name in &matching.include {
+        match one.get(name) {
+            Some(value) => {
+                labels.insert(name.clone(), value.clone());
+            }
+            None => {
+                labels.remove(name);
+            }
+        }
+    }
+    labels
+}
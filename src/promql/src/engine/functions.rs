@@ -0,0 +1,618 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use regex::Regex;
+use snafu::{ensure, ResultExt};
+
+use crate::ast::{Call, Expr, MatchOp, METRIC_NAME};
+use crate::engine::{drop_metric_name, EvalValue, RangeVector, StepSeries};
+use crate::error::{self, Result};
+use crate::value::{Labels, Sample};
+
+pub(crate) fn eval_call(
+    call: &Call,
+    mut args: Vec<EvalValue>,
+    timestamps: &[i64],
+) -> Result<EvalValue> {
+    let name = call.func.name;
+    let value = match name {
+        "time" => EvalValue::Scalar(timestamps.iter().map(|ts| *ts as f64 / 1000.0).collect()),
+        "vector" => EvalValue::Vector(vec![StepSeries {
+            labels: Labels::new(),
+            values: args
+                .remove(0)
+                .into_scalar()?
+                .into_iter()
+                .map(Some)
+                .collect(),
+        }]),
+        "scalar" => {
+            let series = args.remove(0).into_vector()?;
+            EvalValue::Scalar(
+                (0..timestamps.len())
+                    .map(|step| {
+                        let mut values = series.iter().filter_map(|s| s.values[step]);
+                        match (values.next(), values.next()) {
+                            (Some(value), None) => value,
+                            _ => f64::NAN,
+                        }
+                    })
+                    .collect(),
+            )
+        }
+        "absent" => {
+            let series = args.remove(0).into_vector()?;
+            let values: Vec<_> = (0..timestamps.len())
+                .map(|step| (!series.iter().any(|s| s.values[step].is_some())).then(|| 1.0))
+                .collect();
+            if values.iter().any(Option::is_some) {
+                EvalValue::Vector(vec![StepSeries {
+                    labels: absent_labels(&call.args[0]),
+                    values,
+                }])
+            } else {
+                EvalValue::Vector(vec![])
+            }
+        }
+        "sort" | "sort_desc" => {
+            let mut series = args.remove(0).into_vector()?;
+            // Sorting only makes sense in instant queries, so sorts by the last value.
+            let last = |s: &StepSeries| s.values.last().copied().flatten();
+            series.sort_by(|a, b| match (last(a), last(b)) {
+                (Some(a), Some(b)) if name == "sort" => a.total_cmp(&b),
+                (Some(a), Some(b)) => b.total_cmp(&a),
+                (a, b) => b.is_some().cmp(&a.is_some()),
+            });
+            EvalValue::Vector(series)
+        }
+        "label_replace" => {
+            let mut args = args.into_iter();
+            let series = args.next().unwrap().into_vector()?;
+            let dst = args.next().unwrap().into_string()?;
+            let replacement = args.next().unwrap().into_string()?;
+            let src = args.next().unwrap().into_string()?;
+            let regex = args.next().unwrap().into_string()?;
+            let regex = Regex::new(&format!("^(?:{})$", regex))
+                .context(error::InvalidRegexSnafu { regex })?;
+
+            relabel(series, |labels| {
+                let src_value = labels.get(&src).map(String::as_str).unwrap_or_default();
+                if let Some(captures) = regex.captures(src_value) {
+                    let mut value = String::new();
+                    captures.expand(&replacement, &mut value);
+                    set_label(labels, &dst, value);
+                }
+            })?
+        }
+        "label_join" => {
+            let mut args = args.into_iter();
+            let series = args.next().unwrap().into_vector()?;
+            let dst = args.next().unwrap().into_string()?;
+            let separator = args.next().unwrap().into_string()?;
+            let srcs = args
+                .map(EvalValue::into_string)
+                .collect::<Result<Vec<_>>>()?;
+
+            relabel(series, |labels| {
+                let value = srcs
+                    .iter()
+                    .map(|src| labels.get(src).map(String::as_str).unwrap_or_default())
+                    .collect::<Vec<_>>()
+                    .join(&separator);
+                set_label(labels, &dst, value);
+            })?
+        }
+        "histogram_quantile" => {
+            let mut args = args.into_iter();
+            let phi = args.next().unwrap().into_scalar()?;
+            let series = args.next().unwrap().into_vector()?;
+            EvalValue::Vector(histogram_quantile(&phi, series))
+        }
+        "round" | "clamp" | "clamp_max" | "clamp_min" => {
+            let mut args = args.into_iter();
+            let series = args.next().unwrap().into_vector()?;
+            let params = args
+                .map(EvalValue::into_scalar)
+                .collect::<Result<Vec<_>>>()?;
+            map_values(series, |v, step| {
+                let param = |i: usize| params[i][step];
+                match name {
+                    "round" => {
+                        let to_nearest = if params.is_empty() { 1.0 } else { param(0) };
+                        // Rounds half up, same as Prometheus.
+                        let inverse = 1.0 / to_nearest;
+                        Some((v * inverse + 0.5).floor() / inverse)
+                    }
+                    "clamp" => {
+                        let (min, max) = (param(0), param(1));
+                        (min <= max).then(|| v.max(min).min(max))
+                    }
+                    "clamp_max" => Some(v.min(param(0))),
+                    _ => Some(v.max(param(0))),
+                }
+            })
+        }
+        "abs" | "ceil" | "exp" | "floor" | "ln" | "log10" | "log2" | "sgn" | "sqrt" => {
+            let f: fn(f64) -> f64 = match name {
+                "abs" => f64::abs,
+                "ceil" => f64::ceil,
+                "exp" => f64::exp,
+                "floor" => f64::floor,
+                "ln" => f64::ln,
+                "log10" => f64::log10,
+                "log2" => f64::log2,
+                "sgn" => sgn,
+                _ => f64::sqrt,
+            };
+            map_values(args.remove(0).into_vector()?, |v, _| Some(f(v)))
+        }
+        "quantile_over_time" => {
+            let mut args = args.into_iter();
+            let phi = args.next().unwrap().into_scalar()?;
+            let range_vector = args.next().unwrap().into_matrix()?;
+            eval_range_function(name, &range_vector, timestamps, |samples, _, step| {
+                let mut values: Vec<_> = samples.iter().map(|s| s.value).collect();
+                Some(quantile(phi[step], &mut values))
+            })
+        }
+        "predict_linear" => {
+            let mut args = args.into_iter();
+            let range_vector = args.next().unwrap().into_matrix()?;
+            let duration = args.next().unwrap().into_scalar()?;
+            eval_range_function(name, &range_vector, timestamps, |samples, _, step| {
+                let ts = timestamps[step];
+                let (slope, intercept) = linear_regression(samples, ts)?;
+                Some(slope * duration[step] + intercept)
+            })
+        }
+        _ => {
+            let range_vector = args.remove(0).into_matrix()?;
+            let range = range_vector.range;
+            eval_range_function(
+                name,
+                &range_vector,
+                timestamps,
+                |samples, window_end, step| {
+                    let values = samples.iter().map(|s| s.value);
+                    match name {
+                        "rate" | "increase" | "delta" => {
+                            extrapolated_rate(samples, window_end - range, window_end, name)
+                        }
+                        "irate" | "idelta" => instant_rate(samples, name == "irate"),
+                        "deriv" => linear_regression(samples, samples[0].timestamp).map(|r| r.0),
+                        "changes" => Some(
+                            samples
+                                .windows(2)
+                                .filter(|w| w[0].value != w[1].value)
+                                .count() as f64,
+                        ),
+                        "resets" => Some(
+                            samples
+                                .windows(2)
+                                .filter(|w| w[1].value < w[0].value)
+                                .count() as f64,
+                        ),
+                        "avg_over_time" => Some(values.sum::<f64>() / samples.len() as f64),
+                        "sum_over_time" => Some(values.sum()),
+                        "min_over_time" => {
+                            Some(values.fold(
+                                f64::NAN,
+                                |a, b| {
+                                    if a.is_nan() || b < a {
+                                        b
+                                    } else {
+                                        a
+                                    }
+                                },
+                            ))
+                        }
+                        "max_over_time" => {
+                            Some(values.fold(
+                                f64::NAN,
+                                |a, b| {
+                                    if a.is_nan() || b > a {
+                                        b
+                                    } else {
+                                        a
+                                    }
+                                },
+                            ))
+                        }
+                        "count_over_time" => Some(samples.len() as f64),
+                        "last_over_time" => samples.last().map(|s| s.value),
+                        "present_over_time" => Some(1.0),
+                        "stddev_over_time" => Some(variance(values).sqrt()),
+                        "stdvar_over_time" => Some(variance(values)),
+                        _ => unreachable!("unknown range function {}", name),
+                    }
+                },
+            )
+        }
+    };
+    Ok(value)
+}
+
+fn eval_range_function(
+    name: &str,
+    range_vector: &RangeVector,
+    timestamps: &[i64],
+    f: impl FnMut(&[Sample], i64, usize) -> Option<f64>,
+) -> EvalValue {
+    let values = range_vector.map_windows(timestamps, f);
+    EvalValue::Vector(
+        range_vector
+            .series
+            .iter()
+            .zip(values)
+            .filter(|(_, values)| values.iter().any(Option::is_some))
+            .map(|(series, values)| StepSeries {
+                labels: if name == "last_over_time" {
+                    series.labels.clone()
+                } else {
+                    drop_metric_name(series.labels.clone())
+                },
+                values,
+            })
+            .collect(),
+    )
+}
+
+fn map_values(series: Vec<StepSeries>, mut f: impl FnMut(f64, usize) -> Option<f64>) -> EvalValue {
+    EvalValue::Vector(
+        series
+            .into_iter()
+            .map(|s| StepSeries {
+                labels: drop_metric_name(s.labels),
+                values: s
+                    .values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(step, v)| v.and_then(|v| f(v, step)))
+                    .collect(),
+            })
+            .collect(),
+    )
+}
+
+/// Applies `f` to the labels of each series, the result must not contain duplicated series.
+fn relabel(mut series: Vec<StepSeries>, f: impl Fn(&mut Labels)) -> Result<EvalValue> {
+    for s in &mut series {
+        f(&mut s.labels);
+    }
+    let mut labels: Vec<_> = series.iter().map(|s| &s.labels).collect();
+    labels.sort();
+    ensure!(
+        labels.windows(2).all(|w| w[0] != w[1]),
+        error::EvalSnafu {
+            msg: "vector cannot contain metrics with the same labelset",
+        }
+    );
+    Ok(EvalValue::Vector(series))
+}
+
+fn set_label(labels: &mut Labels, name: &str, value: String) {
+    if value.is_empty() {
+        labels.remove(name);
+    } else {
+        labels.insert(name.to_string(), value);
+    }
+}
+
+/// Labels of the result of `absent()`, taken from the equality matchers of the selector.
+pub(crate) fn absent_labels(expr: &Expr) -> Labels {
+    let mut labels = Labels::new();
+    if let Expr::VectorSelector(selector) = expr {
+        let mut duplicated = Vec::new();
+        for matcher in &selector.matchers {
+            if matcher.op != MatchOp::Equal || matcher.name == METRIC_NAME {
+                continue;
+            }
+            if labels
+                .insert(matcher.name.clone(), matcher.value.clone())
+                .is_some()
+            {
+                duplicated.push(matcher.name.clone());
+            }
+        }
+        for name in duplicated {
+            labels.remove(&name);
+        }
+    }
+    labels
+}
+
+fn sgn(v: f64) -> f64 {
+    if v > 0.0 {
+        1.0
+    } else if v < 0.0 {
+        -1.0
+    } else {
+        v
+    }
+}
+
+fn variance(values: impl Iterator<Item = f64>) -> f64 {
+    let values: Vec<_> = values.collect();
+    let count = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / count;
+    values.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / count
+}
+
+/// Calculates the `phi` quantile of `values`, same as the `quantile` aggregation of Prometheus.
+pub(crate) fn quantile(phi: f64, values: &mut [f64]) -> f64 {
+    if values.is_empty() || phi.is_nan() {
+        return f64::NAN;
+    }
+    if phi < 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if phi > 1.0 {
+        return f64::INFINITY;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+
+    let n = values.len() as f64;
+    let rank = phi * (n - 1.0);
+    let lower = rank.floor().max(0.0);
+    let upper = (lower + 1.0).min(n - 1.0);
+    let weight = rank - rank.floor();
+    values[lower as usize] * (1.0 - weight) + values[upper as usize] * weight
+}
+
+/// Calculates the rate, increase or delta of samples in the window `(start, end]`, extrapolated
+/// to the boundaries of the window like Prometheus does.
+fn extrapolated_rate(samples: &[Sample], start: i64, end: i64, name: &str) -> Option<f64> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let is_counter = name != "delta";
+    let (first, last) = (samples[0], samples[samples.len() - 1]);
+
+    let mut result = last.value - first.value;
+    if is_counter {
+        // Adds back the value before each counter reset.
+        result += samples
+            .windows(2)
+            .filter(|w| w[1].value < w[0].value)
+            .map(|w| w[0].value)
+            .sum::<f64>();
+    }
+
+    let sampled_interval = (last.timestamp - first.timestamp) as f64 / 1000.0;
+    let average_interval = sampled_interval / (samples.len() - 1) as f64;
+    let mut duration_to_start = (first.timestamp - start) as f64 / 1000.0;
+    let duration_to_end = (end - last.timestamp) as f64 / 1000.0;
+    if is_counter && result > 0.0 && first.value >= 0.0 {
+        // Counters can't be extrapolated below zero.
+        let duration_to_zero = sampled_interval * (first.value / result);
+        duration_to_start = duration_to_start.min(duration_to_zero);
+    }
+
+    let threshold = average_interval * 1.1;
+    let mut extrapolate_to = sampled_interval;
+    extrapolate_to += if duration_to_start < threshold {
+        duration_to_start
+    } else {
+        average_interval / 2.0
+    };
+    extrapolate_to += if duration_to_end < threshold {
+        duration_to_end
+    } else {
+        average_interval / 2.0
+    };
+    result *= extrapolate_to / sampled_interval;
+
+    if name == "rate" {
+        result /= (end - start) as f64 / 1000.0;
+    }
+    Some(result)
+}
+
+/// Calculates the per-second rate (`irate`) or difference (`idelta`) of the last two samples.
+fn instant_rate(samples: &[Sample], is_rate: bool) -> Option<f64> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let (prev, last) = (samples[samples.len() - 2], samples[samples.len() - 1]);
+    if !is_rate {
+        return Some(last.value - prev.value);
+    }
+
+    let delta = if last.value < prev.value {
+        // Counter reset.
+        last.value
+    } else {
+        last.value - prev.value
+    };
+    let interval = (last.timestamp - prev.timestamp) as f64 / 1000.0;
+    (interval > 0.0).then(|| delta / interval)
+}
+
+/// Returns the slope (per second) and the intercept at `intercept_time` of the simple linear
+/// regression of samples.
+fn linear_regression(samples: &[Sample], intercept_time: i64) -> Option<(f64, f64)> {
+    if samples.len() < 2 {
+        return None;
+    }
+    let n = samples.len() as f64;
+    let (mut sum_x, mut sum_y, mut sum_xy, mut sum_x2) = (0.0, 0.0, 0.0, 0.0);
+    for sample in samples {
+        let x = (sample.timestamp - intercept_time) as f64 / 1000.0;
+        sum_x += x;
+        sum_y += sample.value;
+        sum_xy += x * sample.value;
+        sum_x2 += x * x;
+    }
+    let cov_xy = sum_xy - sum_x * sum_y / n;
+    let var_x = sum_x2 - sum_x * sum_x / n;
+    if var_x == 0.0 {
+        return None;
+    }
+    let slope = cov_xy / var_x;
+    let intercept = sum_y / n - slope * sum_x / n;
+    Some((slope, intercept))
+}
+
+/// Calculates `histogram_quantile()` on the buckets (series with label `le`) of each histogram.
+fn histogram_quantile(phi: &[f64], series: Vec<StepSeries>) -> Vec<StepSeries> {
+    let num_steps = phi.len();
+    // Groups buckets by histograms, i.e. labels other than `le`.
+    let mut histograms: Vec<(Labels, Vec<(f64, &[Option<f64>])>)> = Vec::new();
+    for s in &series {
+        let upper_bound = match s.labels.get("le").and_then(|le| parse_bound(le)) {
+            Some(upper_bound) => upper_bound,
+            None => continue,
+        };
+        let mut labels = drop_metric_name(s.labels.clone());
+        labels.remove("le");
+        match histograms.iter_mut().find(|(l, _)| *l == labels) {
+            Some((_, buckets)) => buckets.push((upper_bound, s.values.as_slice())),
+            None => histograms.push((labels, vec![(upper_bound, s.values.as_slice())])),
+        }
+    }
+
+    histograms
+        .into_iter()
+        .map(|(labels, buckets)| {
+            let values = (0..num_steps)
+                .map(|step| {
+                    let buckets: Vec<_> = buckets
+                        .iter()
+                        .filter_map(|(upper_bound, values)| values[step].map(|v| (*upper_bound, v)))
+                        .collect();
+                    (!buckets.is_empty()).then(|| bucket_quantile(phi[step], buckets))
+                })
+                .collect();
+            StepSeries { labels, values }
+        })
+        .collect()
+}
+
+fn parse_bound(s: &str) -> Option<f64> {
+    match s {
+        "+Inf" | "Inf" | "inf" => Some(f64::INFINITY),
+        s => s.parse().ok(),
+    }
+}
+
+/// Calculates the quantile of a histogram from its cumulative buckets `(upper bound, count)`.
+fn bucket_quantile(phi: f64, mut buckets: Vec<(f64, f64)>) -> f64 {
+    if phi.is_nan() {
+        return f64::NAN;
+    }
+    if phi < 0.0 {
+        return f64::NEG_INFINITY;
+    }
+    if phi > 1.0 {
+        return f64::INFINITY;
+    }
+    buckets.sort_by(|a, b| a.0.total_cmp(&b.0));
+    if buckets.len() < 2 || buckets[buckets.len() - 1].0 != f64::INFINITY {
+        return f64::NAN;
+    }
+    // Counts of buckets must be monotonic, which may be violated by scraping at different times.
+    for i in 1..buckets.len() {
+        if buckets[i].1 < buckets[i - 1].1 {
+            buckets[i].1 = buckets[i - 1].1;
+        }
+    }
+
+    let observations = buckets[buckets.len() - 1].1;
+    if observations == 0.0 {
+        return f64::NAN;
+    }
+    let mut rank = phi * observations;
+    let b = buckets
+        .iter()
+        .position(|(_, count)| *count >= rank)
+        .unwrap_or(buckets.len() - 1);
+
+    if b == buckets.len() - 1 {
+        return buckets[buckets.len() - 2].0;
+    }
+    if b == 0 && buckets[0].0 <= 0.0 {
+        return buckets[0].0;
+    }
+    let (mut bucket_start, bucket_end, mut count) = (0.0, buckets[b].0, buckets[b].1);
+    if b > 0 {
+        bucket_start = buckets[b - 1].0;
+        count -= buckets[b - 1].1;
+        rank -= buckets[b - 1].1;
+    }
+    bucket_start + (bucket_end - bucket_start) * (rank / count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quantile() {
+        assert_eq!(2.5, quantile(0.5, &mut [4.0, 1.0, 3.0, 2.0]));
+        assert_eq!(4.0, quantile(1.0, &mut [4.0, 1.0, 3.0, 2.0]));
+        assert_eq!(f64::NEG_INFINITY, quantile(-1.0, &mut [1.0]));
+        assert!(quantile(0.5, &mut []).is_nan());
+    }
+
+    fn assert_float_eq(expected: f64, actual: Option<f64>) {
+        let actual = actual.unwrap();
+        assert!(
+            (expected - actual).abs() < 1e-9,
+            "expected {}, got {}",
+            expected,
+            actual
+        );
+    }
+
+    #[test]
+    fn test_bucket_quantile() {
+        let buckets = vec![
+            (0.1, 10.0),
+            (0.5, 50.0),
+            (1.0, 90.0),
+            (f64::INFINITY, 100.0),
+        ];
+        assert_float_eq(0.5, Some(bucket_quantile(0.5, buckets.clone())));
+        assert_float_eq(0.75, Some(bucket_quantile(0.7, buckets.clone())));
+        // Quantiles in the +Inf bucket return the upper bound of the second last bucket.
+        assert_eq!(1.0, bucket_quantile(0.99, buckets));
+        assert!(bucket_quantile(0.5, vec![(0.1, 1.0), (1.0, 2.0)]).is_nan());
+    }
+
+    #[test]
+    fn test_extrapolated_rate() {
+        let samples: Vec<_> = (1..=4)
+            .map(|i| Sample::new(i * 15_000, 10.0 * i as f64))
+            .collect();
+        assert_float_eq(40.0, extrapolated_rate(&samples, 0, 60_000, "increase"));
+        assert_float_eq(40.0 / 60.0, extrapolated_rate(&samples, 0, 60_000, "rate"));
+
+        // The counter resets at the third sample.
+        let samples = vec![
+            Sample::new(15_000, 10.0),
+            Sample::new(30_000, 20.0),
+            Sample::new(45_000, 5.0),
+            Sample::new(60_000, 15.0),
+        ];
+        assert_float_eq(
+            25.0 * 60.0 / 45.0,
+            extrapolated_rate(&samples, 0, 60_000, "increase"),
+        );
+        assert_float_eq(
+            5.0 * 60.0 / 45.0,
+            extrapolated_rate(&samples, 0, 60_000, "delta"),
+        );
+        assert_eq!(None, extrapolated_rate(&samples[..1], 0, 60_000, "rate"));
+    }
+}
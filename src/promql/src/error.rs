@@ -0,0 +1,91 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+
+use common_error::prelude::*;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// PromQL errors.
+// Parsing and evaluation errors don't carry backtraces since they are caused by user input.
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub))]
+pub enum Error {
+    #[snafu(display("Failed to parse PromQL at position {}: {}", pos, msg))]
+    Parse { pos: usize, msg: String },
+
+    #[snafu(display("Invalid regex {} in PromQL, source: {}", regex, source))]
+    InvalidRegex { regex: String, source: regex::Error },
+
+    #[snafu(display("Unsupported PromQL feature: {}", feat))]
+    Unsupported { feat: String },
+
+    #[snafu(display("Invalid PromQL query range: {}", msg))]
+    InvalidRange { msg: String },
+
+    #[snafu(display("Failed to evaluate PromQL: {}", msg))]
+    Eval { msg: String },
+
+    #[snafu(display(
+        "Query loads more than {} samples into memory, try narrowing the query",
+        limit
+    ))]
+    TooManySamples { limit: usize },
+
+    #[snafu(display("Failed to read series from storage, source: {}", source))]
+    Storage {
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display("Failed to build plan of PromQL, source: {}", source))]
+    BuildPlan {
+        source: datafusion::error::DataFusionError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Table {} is not a valid metric table: {}", table, msg))]
+    InvalidMetricTable { table: String, msg: String },
+
+    #[snafu(display("Invalid series in PromQL plan: {}", msg))]
+    InvalidSeries { msg: String, backtrace: Backtrace },
+}
+
+impl ErrorExt for Error {
+    fn status_code(&self) -> StatusCode {
+        use Error::*;
+
+        match self {
+            Parse { .. } => StatusCode::InvalidSyntax,
+            InvalidRegex { .. }
+            | InvalidRange { .. }
+            | Eval { .. }
+            | TooManySamples { .. }
+            | InvalidMetricTable { .. } => StatusCode::InvalidArguments,
+            Unsupported { .. } => StatusCode::Unsupported,
+            Storage { source } => source.status_code(),
+            BuildPlan { .. } => StatusCode::PlanQuery,
+            InvalidSeries { .. } => StatusCode::Unexpected,
+        }
+    }
+
+    fn backtrace_opt(&self) -> Option<&Backtrace> {
+        ErrorCompat::backtrace(self)
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+}
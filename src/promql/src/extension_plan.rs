@@ -0,0 +1,416 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Plan nodes evaluating PromQL over the series read from the metric tables.
+//!
+//! All nodes output series in the same layout: a nullable string column for each label sorted
+//! by name, null if the series doesn't have the label, followed by the timestamp column
+//! [TIMESTAMP_COLUMN_NAME] and the value column [VALUE_COLUMN_NAME]. Rows of a series are
+//! contiguous and sorted by timestamp. A scalar has no label columns and a row at each step.
+
+mod range_function;
+mod series_select;
+mod step_eval;
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef as DfSchemaRef;
+use datafusion::error::{DataFusionError, Result as DfResult};
+use datafusion::execution::context::ExecutionContextState;
+use datafusion::execution::runtime_env::RuntimeEnv;
+use datafusion::logical_plan::{
+    Column, Expr as DfExpr, LogicalPlan, ToDFSchema, UserDefinedLogicalNode,
+};
+use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use datafusion::physical_plan::memory::MemoryStream;
+use datafusion::physical_plan::planner::ExtensionPlanner;
+use datafusion::physical_plan::{ExecutionPlan, PhysicalPlanner, SendableRecordBatchStream};
+use datafusion_common::record_batch::RecordBatch as DfRecordBatch;
+use datafusion_common::{DFSchema, DFSchemaRef};
+use datatypes::arrow::array::{Array, PrimitiveArray, Utf8Array};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
+use datatypes::vectors::{Float64Vector, StringVector, TimestampVector, Vector};
+use futures::StreamExt;
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::engine::{EvalRange, StepSeries};
+use crate::error::{self, Error, Result};
+pub use crate::extension_plan::range_function::{RangeFunction, RangeFunctionExec};
+pub use crate::extension_plan::series_select::{SeriesSelect, SeriesSelectExec};
+pub use crate::extension_plan::step_eval::{StepEval, StepEvalExec, StepOp};
+use crate::planner::{TIMESTAMP_COLUMN_NAME, VALUE_COLUMN_NAME};
+use crate::value::{Labels, Sample, Series};
+
+/// Plans the PromQL nodes into their execution plans. It must be registered to the physical
+/// planner executing the plans built by the [PromPlanner](crate::planner::PromPlanner).
+pub struct PromExtensionPlanner;
+
+#[async_trait]
+impl ExtensionPlanner for PromExtensionPlanner {
+    async fn plan_extension(
+        &self,
+        _planner: &dyn PhysicalPlanner,
+        node: &dyn UserDefinedLogicalNode,
+        _logical_inputs: &[&LogicalPlan],
+        physical_inputs: &[Arc<dyn ExecutionPlan>],
+        _ctx_state: &ExecutionContextState,
+    ) -> DfResult<Option<Arc<dyn ExecutionPlan>>> {
+        let node = node.as_any();
+        let inputs = physical_inputs.to_vec();
+        let plan: Arc<dyn ExecutionPlan> = if let Some(node) = node.downcast_ref::<SeriesSelect>() {
+            Arc::new(node.to_execution_plan(inputs))
+        } else if let Some(node) = node.downcast_ref::<RangeFunction>() {
+            Arc::new(node.to_execution_plan(inputs))
+        } else if let Some(node) = node.downcast_ref::<StepEval>() {
+            Arc::new(node.to_execution_plan(inputs))
+        } else {
+            return Ok(None);
+        };
+        Ok(Some(plan))
+    }
+}
+
+/// Returns the schema of series with `labels`, and the same schema for the logical plan.
+fn series_schema(labels: impl IntoIterator<Item = String>) -> Result<(SchemaRef, DFSchemaRef)> {
+    let mut columns: Vec<_> = labels
+        .into_iter()
+        .map(|label| ColumnSchema::new(label, ConcreteDataType::string_datatype(), true))
+        .collect();
+    columns.push(ColumnSchema::new(
+        TIMESTAMP_COLUMN_NAME,
+        ConcreteDataType::timestamp_millis_datatype(),
+        false,
+    ));
+    columns.push(ColumnSchema::new(
+        VALUE_COLUMN_NAME,
+        ConcreteDataType::float64_datatype(),
+        true,
+    ));
+    let schema = Arc::new(Schema::new(columns));
+    let df_schema = schema
+        .arrow_schema()
+        .clone()
+        .to_dfschema_ref()
+        .context(error::BuildPlanSnafu)?;
+    Ok((schema, df_schema))
+}
+
+/// Returns the label names of series in the layout of the `schema`.
+pub(crate) fn label_names(schema: &DFSchema) -> BTreeSet<String> {
+    schema
+        .fields()
+        .iter()
+        .map(|field| field.name())
+        .filter(|name| *name != TIMESTAMP_COLUMN_NAME && *name != VALUE_COLUMN_NAME)
+        .cloned()
+        .collect()
+}
+
+/// Returns all columns of the `inputs`, so optimizers won't prune columns a node reads.
+fn input_columns<'a>(inputs: impl IntoIterator<Item = &'a LogicalPlan>) -> Vec<DfExpr> {
+    let names: BTreeSet<_> = inputs
+        .into_iter()
+        .flat_map(|input| input.schema().fields().iter().map(|field| field.name()))
+        .collect();
+    names
+        .into_iter()
+        .map(|name| DfExpr::Column(Column::from_name(name)))
+        .collect()
+}
+
+fn to_df_error(e: Error) -> DataFusionError {
+    DataFusionError::External(Box::new(e))
+}
+
+/// Executes the `input` in a single partition.
+async fn execute_input(
+    input: &Arc<dyn ExecutionPlan>,
+    runtime: Arc<RuntimeEnv>,
+) -> DfResult<SendableRecordBatchStream> {
+    let input: Arc<dyn ExecutionPlan> = if input.output_partitioning().partition_count() > 1 {
+        Arc::new(CoalescePartitionsExec::new(input.clone()))
+    } else {
+        input.clone()
+    };
+    input.execute(0, runtime).await
+}
+
+/// Reads all series of the `input`.
+async fn collect_series(
+    input: &Arc<dyn ExecutionPlan>,
+    runtime: Arc<RuntimeEnv>,
+) -> DfResult<Vec<Series>> {
+    let mut stream = execute_input(input, runtime).await?;
+    let mut grouper = SeriesGrouper::default();
+    let mut series = Vec::new();
+    while let Some(batch) = stream.next().await {
+        grouper
+            .push(&batch?, |s| {
+                series.push(s);
+                Ok(())
+            })
+            .map_err(to_df_error)?;
+    }
+    series.extend(grouper.finish());
+    Ok(series)
+}
+
+fn memory_stream(batch: DfRecordBatch, schema: DfSchemaRef) -> DfResult<SendableRecordBatchStream> {
+    Ok(Box::pin(MemoryStream::try_new(vec![batch], schema, None)?))
+}
+
+/// Returns the values of the `series` at each step of the `range`.
+fn step_values(series: &Series, range: &EvalRange) -> Vec<Option<f64>> {
+    let mut values = vec![None; range.num_steps()];
+    for sample in &series.samples {
+        let offset = sample.timestamp - range.start;
+        if offset < 0 || offset % range.step != 0 {
+            continue;
+        }
+        if let Some(value) = values.get_mut((offset / range.step) as usize) {
+            *value = Some(sample.value);
+        }
+    }
+    values
+}
+
+/// Returns the values of a scalar read as `series` at each step of the `range`.
+fn scalar_values(series: &[Series], range: &EvalRange) -> Vec<f64> {
+    match series.first() {
+        Some(series) => step_values(series, range)
+            .into_iter()
+            .map(|value| value.unwrap_or(f64::NAN))
+            .collect(),
+        None => vec![f64::NAN; range.num_steps()],
+    }
+}
+
+/// Groups rows of batches in the series layout into series.
+#[derive(Default)]
+pub(crate) struct SeriesGrouper {
+    current: Option<Series>,
+}
+
+impl SeriesGrouper {
+    /// Pushes the rows of `batch`, calls `f` with each series whose samples are all read.
+    pub(crate) fn push(
+        &mut self,
+        batch: &DfRecordBatch,
+        mut f: impl FnMut(Series) -> Result<()>,
+    ) -> Result<()> {
+        let columns = SeriesColumns::try_new(batch)?;
+        for row in 0..batch.num_rows() {
+            let sample = match columns.sample(row) {
+                Some(sample) => sample,
+                None => continue,
+            };
+            if let Some(series) = &mut self.current {
+                if columns.has_labels(row, &series.labels) {
+                    series.samples.push(sample);
+                    continue;
+                }
+            }
+            let series = Series {
+                labels: columns.labels(row),
+                samples: vec![sample],
+            };
+            if let Some(series) = self.current.replace(series) {
+                f(series)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the last series.
+    pub(crate) fn finish(self) -> Option<Series> {
+        self.current
+    }
+}
+
+/// Returns the labels of each row of the `batch`, whose columns are label columns except the
+/// timestamp and value columns.
+pub(crate) fn batch_labels(batch: &DfRecordBatch) -> Result<Vec<Labels>> {
+    let schema = batch.schema();
+    let mut columns = Vec::new();
+    for (field, column) in schema.fields.iter().zip(batch.columns()) {
+        if field.name != TIMESTAMP_COLUMN_NAME && field.name != VALUE_COLUMN_NAME {
+            let column: Utf8Array<i32> = downcast(column, &field.name)?;
+            columns.push((&field.name, column));
+        }
+    }
+    Ok((0..batch.num_rows())
+        .map(|row| {
+            columns
+                .iter()
+                .filter(|(_, column)| column.is_valid(row))
+                .map(|(name, column)| (name.to_string(), column.value(row).to_string()))
+                .collect()
+        })
+        .collect())
+}
+
+/// Columns of a batch in the series layout.
+struct SeriesColumns {
+    labels: Vec<(String, Utf8Array<i32>)>,
+    timestamps: PrimitiveArray<i64>,
+    values: PrimitiveArray<f64>,
+}
+
+impl SeriesColumns {
+    fn try_new(batch: &DfRecordBatch) -> Result<Self> {
+        let mut labels = Vec::new();
+        let (mut timestamps, mut values) = (None, None);
+        for (field, column) in batch.schema().fields.iter().zip(batch.columns()) {
+            match field.name.as_str() {
+                TIMESTAMP_COLUMN_NAME => timestamps = Some(downcast(column, &field.name)?),
+                VALUE_COLUMN_NAME => values = Some(downcast(column, &field.name)?),
+                name => labels.push((name.to_string(), downcast(column, name)?)),
+            }
+        }
+        Ok(Self {
+            labels,
+            timestamps: timestamps.context(error::InvalidSeriesSnafu {
+                msg: "missing timestamp column",
+            })?,
+            values: values.context(error::InvalidSeriesSnafu {
+                msg: "missing value column",
+            })?,
+        })
+    }
+
+    fn label(&self, index: usize, row: usize) -> Option<&str> {
+        let column = &self.labels[index].1;
+        column.is_valid(row).then(|| column.value(row))
+    }
+
+    fn labels(&self, row: usize) -> Labels {
+        (0..self.labels.len())
+            .filter_map(|i| {
+                self.label(i, row)
+                    .map(|value| (self.labels[i].0.clone(), value.to_string()))
+            })
+            .collect()
+    }
+
+    /// Returns whether the series at `row` has exactly the `labels`.
+    fn has_labels(&self, row: usize, labels: &Labels) -> bool {
+        let mut num_labels = 0;
+        for (i, (name, _)) in self.labels.iter().enumerate() {
+            let value = self.label(i, row);
+            if value != labels.get(name).map(String::as_str) {
+                return false;
+            }
+            num_labels += value.is_some() as usize;
+        }
+        num_labels == labels.len()
+    }
+
+    fn sample(&self, row: usize) -> Option<Sample> {
+        (self.timestamps.is_valid(row) && self.values.is_valid(row))
+            .then(|| Sample::new(self.timestamps.value(row), self.values.value(row)))
+    }
+}
+
+fn downcast<T: Clone + 'static>(column: &Arc<dyn Array>, name: &str) -> Result<T> {
+    column
+        .as_any()
+        .downcast_ref::<T>()
+        .cloned()
+        .with_context(|| error::InvalidSeriesSnafu {
+            msg: format!(
+                "unexpected type {:?} of column {}",
+                column.data_type(),
+                name
+            ),
+        })
+}
+
+/// Builds a batch of series in the layout of `schema`.
+struct SeriesBatchBuilder {
+    schema: SchemaRef,
+    /// Names of the label columns.
+    names: Vec<String>,
+    labels: Vec<Vec<Option<String>>>,
+    timestamps: Vec<i64>,
+    values: Vec<f64>,
+}
+
+impl SeriesBatchBuilder {
+    fn new(schema: SchemaRef) -> Self {
+        let names: Vec<_> = schema
+            .column_schemas()
+            .iter()
+            .map(|column| &column.name)
+            .filter(|name| *name != TIMESTAMP_COLUMN_NAME && *name != VALUE_COLUMN_NAME)
+            .cloned()
+            .collect();
+        Self {
+            schema,
+            labels: vec![Vec::new(); names.len()],
+            names,
+            timestamps: Vec::new(),
+            values: Vec::new(),
+        }
+    }
+
+    /// Appends the `samples` of the series with `labels`.
+    fn push(&mut self, labels: &Labels, samples: impl IntoIterator<Item = Sample>) -> Result<()> {
+        for name in labels.keys() {
+            ensure!(
+                self.names.contains(name),
+                error::InvalidSeriesSnafu {
+                    msg: format!("label {} is not in the schema of the plan", name),
+                }
+            );
+        }
+
+        let num_rows = self.timestamps.len();
+        for sample in samples {
+            self.timestamps.push(sample.timestamp);
+            self.values.push(sample.value);
+        }
+        let num_samples = self.timestamps.len() - num_rows;
+        for (name, column) in self.names.iter().zip(&mut self.labels) {
+            let value = labels.get(name);
+            column.extend(std::iter::repeat(value.cloned()).take(num_samples));
+        }
+        Ok(())
+    }
+
+    /// Appends the values of the `series` at each step.
+    fn push_steps(&mut self, series: &StepSeries, timestamps: &[i64]) -> Result<()> {
+        let samples = timestamps
+            .iter()
+            .zip(&series.values)
+            .filter_map(|(ts, value)| value.map(|value| Sample::new(*ts, value)));
+        self.push(&series.labels, samples)
+    }
+
+    fn finish(self) -> DfResult<DfRecordBatch> {
+        let mut columns: Vec<_> = self
+            .labels
+            .into_iter()
+            .map(|values| StringVector::from(values).to_arrow_array())
+            .collect();
+        columns.push(TimestampVector::from_values(self.timestamps).to_arrow_array());
+        columns.push(Float64Vector::from_vec(self.values).to_arrow_array());
+        Ok(DfRecordBatch::try_new(
+            self.schema.arrow_schema().clone(),
+            columns,
+        )?)
+    }
+}
@@ -0,0 +1,259 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef as DfSchemaRef;
+use datafusion::error::Result as DfResult;
+use datafusion::execution::runtime_env::RuntimeEnv;
+use datafusion::logical_plan::plan::Extension;
+use datafusion::logical_plan::{Expr as DfExpr, LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_plan::expressions::PhysicalSortExpr;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion_common::DFSchemaRef;
+use datatypes::schema::SchemaRef;
+use futures::StreamExt;
+
+use crate::ast::{Call, ValueType};
+use crate::engine::functions::eval_call;
+use crate::engine::{EvalRange, EvalValue, RangeVector};
+use crate::error::Result;
+use crate::extension_plan::{
+    collect_series, execute_input, input_columns, memory_stream, scalar_values, series_schema,
+    to_df_error, SeriesBatchBuilder, SeriesGrouper,
+};
+use crate::value::Series;
+
+/// Evaluates a function over a range vector, e.g. `rate()`, one series at a time.
+///
+/// The first input is the range vector, followed by the scalar arguments of the function.
+#[derive(Debug, Clone)]
+pub struct RangeFunction {
+    inputs: Vec<LogicalPlan>,
+    call: Call,
+    /// Range of the window at each step.
+    window: i64,
+    offset: i64,
+    range: EvalRange,
+    schema: SchemaRef,
+    df_schema: DFSchemaRef,
+}
+
+impl RangeFunction {
+    pub fn try_new(
+        matrix: LogicalPlan,
+        params: Vec<LogicalPlan>,
+        call: Call,
+        window: i64,
+        offset: i64,
+        range: EvalRange,
+        labels: BTreeSet<String>,
+    ) -> Result<Self> {
+        let (schema, df_schema) = series_schema(labels)?;
+        let mut inputs = vec![matrix];
+        inputs.extend(params);
+        Ok(Self {
+            inputs,
+            call,
+            window,
+            offset,
+            range,
+            schema,
+            df_schema,
+        })
+    }
+
+    pub fn into_plan(self) -> LogicalPlan {
+        LogicalPlan::Extension(Extension {
+            node: Arc::new(self),
+        })
+    }
+
+    pub(crate) fn to_execution_plan(
+        &self,
+        inputs: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> RangeFunctionExec {
+        RangeFunctionExec {
+            inputs,
+            call: self.call.clone(),
+            window: self.window,
+            offset: self.offset,
+            range: self.range,
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl UserDefinedLogicalNode for RangeFunction {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        self.inputs.iter().collect()
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.df_schema
+    }
+
+    fn expressions(&self) -> Vec<DfExpr> {
+        input_columns(&self.inputs)
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PromRangeFunction: func={}, window={}, offset={}, range={:?}",
+            self.call.func.name, self.window, self.offset, self.range
+        )
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[DfExpr],
+        inputs: &[LogicalPlan],
+    ) -> Arc<dyn UserDefinedLogicalNode + Send + Sync> {
+        Arc::new(Self {
+            inputs: inputs.to_vec(),
+            ..self.clone()
+        })
+    }
+}
+
+/// Execution plan of [RangeFunction].
+#[derive(Debug, Clone)]
+pub struct RangeFunctionExec {
+    inputs: Vec<Arc<dyn ExecutionPlan>>,
+    call: Call,
+    window: i64,
+    offset: i64,
+    range: EvalRange,
+    schema: SchemaRef,
+}
+
+impl RangeFunctionExec {
+    fn eval(
+        &self,
+        series: Series,
+        params: &[Vec<f64>],
+        timestamps: &[i64],
+        builder: &mut SeriesBatchBuilder,
+    ) -> Result<()> {
+        let mut series = Some(series);
+        let mut params = params.iter();
+        let args = self
+            .call
+            .args
+            .iter()
+            .map(|arg| match arg.value_type() {
+                ValueType::Matrix => EvalValue::Matrix(RangeVector {
+                    series: series.take().into_iter().collect(),
+                    range: self.window,
+                    offset: self.offset,
+                }),
+                _ => EvalValue::Scalar(params.next().cloned().unwrap_or_default()),
+            })
+            .collect();
+        for series in eval_call(&self.call, args, timestamps)?.into_vector()? {
+            builder.push_steps(&series, timestamps)?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for RangeFunctionExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> DfSchemaRef {
+        self.schema.arrow_schema().clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::SinglePartition
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.inputs.clone()
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            inputs: children,
+            ..self.clone()
+        }))
+    }
+
+    async fn execute(
+        &self,
+        _partition: usize,
+        runtime: Arc<RuntimeEnv>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let timestamps = self.range.timestamps();
+        let mut params = Vec::with_capacity(self.inputs.len() - 1);
+        for input in &self.inputs[1..] {
+            let series = collect_series(input, runtime.clone()).await?;
+            params.push(scalar_values(&series, &self.range));
+        }
+
+        let mut builder = SeriesBatchBuilder::new(self.schema.clone());
+        let mut stream = execute_input(&self.inputs[0], runtime).await?;
+        let mut grouper = SeriesGrouper::default();
+        while let Some(batch) = stream.next().await {
+            grouper
+                .push(&batch?, |series| {
+                    self.eval(series, &params, &timestamps, &mut builder)
+                })
+                .map_err(to_df_error)?;
+        }
+        if let Some(series) = grouper.finish() {
+            self.eval(series, &params, &timestamps, &mut builder)
+                .map_err(to_df_error)?;
+        }
+        memory_stream(builder.finish()?, self.schema())
+    }
+
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PromRangeFunctionExec: func={}, window={}, offset={}, range={:?}",
+            self.call.func.name, self.window, self.offset, self.range
+        )
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
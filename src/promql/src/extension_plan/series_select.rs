@@ -0,0 +1,264 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef as DfSchemaRef;
+use datafusion::error::Result as DfResult;
+use datafusion::execution::runtime_env::RuntimeEnv;
+use datafusion::logical_plan::plan::Extension;
+use datafusion::logical_plan::{Expr as DfExpr, LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_plan::expressions::PhysicalSortExpr;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion_common::DFSchemaRef;
+use datatypes::schema::SchemaRef;
+use futures::StreamExt;
+
+use crate::engine::{map_windows, EvalRange, StepSeries};
+use crate::error::{self, Result};
+use crate::extension_plan::{
+    execute_input, input_columns, label_names, memory_stream, series_schema, to_df_error,
+    SeriesBatchBuilder, SeriesGrouper,
+};
+use crate::value::Series;
+
+/// Selects the series of a vector selector from its inputs, which are scans of the matched
+/// metric tables.
+///
+/// An instant vector selector outputs the latest sample in the lookback window at each step,
+/// while a range vector selector outputs the raw samples.
+#[derive(Debug, Clone)]
+pub struct SeriesSelect {
+    inputs: Vec<LogicalPlan>,
+    /// Lookback window of an instant vector selector, `None` for a range vector selector.
+    lookback_delta: Option<i64>,
+    offset: i64,
+    range: EvalRange,
+    /// Max number of samples the selector could read.
+    max_samples: usize,
+    schema: SchemaRef,
+    df_schema: DFSchemaRef,
+}
+
+impl SeriesSelect {
+    pub fn try_new(
+        inputs: Vec<LogicalPlan>,
+        lookback_delta: Option<i64>,
+        offset: i64,
+        range: EvalRange,
+        max_samples: usize,
+    ) -> Result<Self> {
+        let labels: BTreeSet<_> = inputs
+            .iter()
+            .flat_map(|input| label_names(input.schema()))
+            .collect();
+        let (schema, df_schema) = series_schema(labels)?;
+        Ok(Self {
+            inputs,
+            lookback_delta,
+            offset,
+            range,
+            max_samples,
+            schema,
+            df_schema,
+        })
+    }
+
+    pub fn into_plan(self) -> LogicalPlan {
+        LogicalPlan::Extension(Extension {
+            node: Arc::new(self),
+        })
+    }
+
+    pub(crate) fn to_execution_plan(
+        &self,
+        inputs: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> SeriesSelectExec {
+        SeriesSelectExec {
+            inputs,
+            lookback_delta: self.lookback_delta,
+            offset: self.offset,
+            range: self.range,
+            max_samples: self.max_samples,
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl UserDefinedLogicalNode for SeriesSelect {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        self.inputs.iter().collect()
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.df_schema
+    }
+
+    fn expressions(&self) -> Vec<DfExpr> {
+        input_columns(&self.inputs)
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PromSeriesSelect: lookback_delta={:?}, offset={}, range={:?}",
+            self.lookback_delta, self.offset, self.range
+        )
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[DfExpr],
+        inputs: &[LogicalPlan],
+    ) -> Arc<dyn UserDefinedLogicalNode + Send + Sync> {
+        Arc::new(Self {
+            inputs: inputs.to_vec(),
+            ..self.clone()
+        })
+    }
+}
+
+/// Execution plan of [SeriesSelect].
+#[derive(Debug, Clone)]
+pub struct SeriesSelectExec {
+    inputs: Vec<Arc<dyn ExecutionPlan>>,
+    lookback_delta: Option<i64>,
+    offset: i64,
+    range: EvalRange,
+    max_samples: usize,
+    schema: SchemaRef,
+}
+
+impl SeriesSelectExec {
+    fn select(
+        &self,
+        series: Series,
+        timestamps: &[i64],
+        builder: &mut SeriesBatchBuilder,
+    ) -> Result<()> {
+        match self.lookback_delta {
+            None => builder.push(&series.labels, series.samples),
+            Some(lookback_delta) => {
+                // The value at each step is the latest sample in the lookback window.
+                let values = map_windows(
+                    &series.samples,
+                    lookback_delta,
+                    self.offset,
+                    timestamps,
+                    |samples, _, _| samples.last().map(|s| s.value),
+                );
+                let series = StepSeries {
+                    labels: series.labels,
+                    values,
+                };
+                builder.push_steps(&series, timestamps)
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ExecutionPlan for SeriesSelectExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> DfSchemaRef {
+        self.schema.arrow_schema().clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::SinglePartition
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.inputs.clone()
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            inputs: children,
+            ..self.clone()
+        }))
+    }
+
+    async fn execute(
+        &self,
+        _partition: usize,
+        runtime: Arc<RuntimeEnv>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let timestamps = self.range.timestamps();
+        let mut builder = SeriesBatchBuilder::new(self.schema.clone());
+        let mut num_samples = 0;
+        for input in &self.inputs {
+            let mut stream = execute_input(input, runtime.clone()).await?;
+            let mut grouper = SeriesGrouper::default();
+            while let Some(batch) = stream.next().await {
+                let batch = batch?;
+                num_samples += batch.num_rows();
+                if num_samples > self.max_samples {
+                    return error::TooManySamplesSnafu {
+                        limit: self.max_samples,
+                    }
+                    .fail()
+                    .map_err(to_df_error);
+                }
+                grouper
+                    .push(&batch, |series| {
+                        self.select(series, &timestamps, &mut builder)
+                    })
+                    .map_err(to_df_error)?;
+            }
+            if let Some(series) = grouper.finish() {
+                self.select(series, &timestamps, &mut builder)
+                    .map_err(to_df_error)?;
+            }
+        }
+        memory_stream(builder.finish()?, self.schema())
+    }
+
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PromSeriesSelectExec: lookback_delta={:?}, offset={}, range={:?}",
+            self.lookback_delta, self.offset, self.range
+        )
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
@@ -0,0 +1,318 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::any::Any;
+use std::collections::BTreeSet;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use datafusion::arrow::datatypes::SchemaRef as DfSchemaRef;
+use datafusion::error::Result as DfResult;
+use datafusion::execution::runtime_env::RuntimeEnv;
+use datafusion::logical_plan::plan::Extension;
+use datafusion::logical_plan::{Expr as DfExpr, LogicalPlan, UserDefinedLogicalNode};
+use datafusion::physical_plan::expressions::PhysicalSortExpr;
+use datafusion::physical_plan::{
+    DisplayFormatType, Distribution, ExecutionPlan, Partitioning, SendableRecordBatchStream,
+    Statistics,
+};
+use datafusion_common::DFSchemaRef;
+use datatypes::schema::SchemaRef;
+use snafu::OptionExt;
+
+use crate::ast::{AggregateExpr, BinaryExpr, Call, ValueType};
+use crate::engine::aggregate::eval_aggregate;
+use crate::engine::binary::eval_binary;
+use crate::engine::functions::eval_call;
+use crate::engine::{drop_metric_name, unexpected_type, EvalRange, EvalValue, StepSeries};
+use crate::error::{self, Result};
+use crate::extension_plan::{
+    collect_series, input_columns, memory_stream, scalar_values, series_schema, step_values,
+    to_df_error, SeriesBatchBuilder,
+};
+use crate::planner::string_literal;
+use crate::value::{Labels, Sample};
+
+/// Operation of a [StepEval] node.
+#[derive(Debug, Clone)]
+pub enum StepOp {
+    /// A number literal, which has no input.
+    Literal(f64),
+    /// Negation of the input.
+    Neg,
+    /// A function call, whose inputs are the arguments except string literals.
+    Call(Call),
+    /// An aggregation, whose inputs are the aggregated vector and the scalar parameter if any.
+    Aggregate(AggregateExpr),
+    /// A binary operation, whose inputs are the operands.
+    Binary(BinaryExpr),
+}
+
+impl StepOp {
+    fn eval(&self, args: Vec<EvalValue>, timestamps: &[i64]) -> Result<EvalValue> {
+        let num_steps = timestamps.len();
+        let mut args = args.into_iter();
+        let mut next_arg = || {
+            args.next().context(error::InvalidSeriesSnafu {
+                msg: format!("missing input of {}", self),
+            })
+        };
+        let value = match self {
+            StepOp::Literal(n) => EvalValue::Scalar(vec![*n; num_steps]),
+            StepOp::Neg => match next_arg()? {
+                EvalValue::Scalar(values) => {
+                    EvalValue::Scalar(values.into_iter().map(|v| -v).collect())
+                }
+                EvalValue::Vector(series) => EvalValue::Vector(
+                    series
+                        .into_iter()
+                        .map(|s| StepSeries {
+                            labels: drop_metric_name(s.labels),
+                            values: s.values.into_iter().map(|v| v.map(|v| -v)).collect(),
+                        })
+                        .collect(),
+                ),
+                other => return unexpected_type("scalar or instant vector", &other),
+            },
+            StepOp::Call(call) => {
+                let mut call_args = Vec::with_capacity(call.args.len());
+                for arg in &call.args {
+                    if arg.value_type() == ValueType::String {
+                        call_args.push(EvalValue::String(string_literal(arg)?));
+                    } else {
+                        call_args.push(next_arg()?);
+                    }
+                }
+                eval_call(call, call_args, timestamps)?
+            }
+            StepOp::Aggregate(aggregate) => {
+                let series = next_arg()?.into_vector()?;
+                let param = match &aggregate.param {
+                    Some(param) if param.value_type() == ValueType::String => {
+                        Some(EvalValue::String(string_literal(param)?))
+                    }
+                    Some(_) => Some(next_arg()?),
+                    None => None,
+                };
+                eval_aggregate(aggregate, param, series, num_steps)?
+            }
+            StepOp::Binary(binary) => {
+                let lhs = next_arg()?;
+                let rhs = next_arg()?;
+                eval_binary(binary, lhs, rhs, num_steps)?
+            }
+        };
+        Ok(value)
+    }
+}
+
+impl fmt::Display for StepOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StepOp::Literal(n) => write!(f, "literal {}", n),
+            StepOp::Neg => write!(f, "neg"),
+            StepOp::Call(call) => write!(f, "call {}", call.func.name),
+            StepOp::Aggregate(aggregate) => {
+                write!(f, "aggregate {:?} {:?}", aggregate.op, aggregate.grouping)
+            }
+            StepOp::Binary(binary) => write!(f, "binary {:?}", binary.op),
+        }
+    }
+}
+
+/// Evaluates an operation over its inputs at all steps of the evaluation range at once, each
+/// input is either a scalar or an instant vector.
+#[derive(Debug, Clone)]
+pub struct StepEval {
+    op: StepOp,
+    inputs: Vec<LogicalPlan>,
+    input_types: Vec<ValueType>,
+    range: EvalRange,
+    schema: SchemaRef,
+    df_schema: DFSchemaRef,
+}
+
+impl StepEval {
+    pub fn try_new(
+        op: StepOp,
+        inputs: Vec<(LogicalPlan, ValueType)>,
+        range: EvalRange,
+        labels: BTreeSet<String>,
+    ) -> Result<Self> {
+        let (schema, df_schema) = series_schema(labels)?;
+        let (inputs, input_types) = inputs.into_iter().unzip();
+        Ok(Self {
+            op,
+            inputs,
+            input_types,
+            range,
+            schema,
+            df_schema,
+        })
+    }
+
+    pub fn into_plan(self) -> LogicalPlan {
+        LogicalPlan::Extension(Extension {
+            node: Arc::new(self),
+        })
+    }
+
+    pub(crate) fn to_execution_plan(&self, inputs: Vec<Arc<dyn ExecutionPlan>>) -> StepEvalExec {
+        StepEvalExec {
+            op: self.op.clone(),
+            inputs,
+            input_types: self.input_types.clone(),
+            range: self.range,
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl UserDefinedLogicalNode for StepEval {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn inputs(&self) -> Vec<&LogicalPlan> {
+        self.inputs.iter().collect()
+    }
+
+    fn schema(&self) -> &DFSchemaRef {
+        &self.df_schema
+    }
+
+    fn expressions(&self) -> Vec<DfExpr> {
+        input_columns(&self.inputs)
+    }
+
+    fn fmt_for_explain(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PromStepEval: op={}, range={:?}", self.op, self.range)
+    }
+
+    fn from_template(
+        &self,
+        _exprs: &[DfExpr],
+        inputs: &[LogicalPlan],
+    ) -> Arc<dyn UserDefinedLogicalNode + Send + Sync> {
+        Arc::new(Self {
+            inputs: inputs.to_vec(),
+            ..self.clone()
+        })
+    }
+}
+
+/// Execution plan of [StepEval].
+#[derive(Debug, Clone)]
+pub struct StepEvalExec {
+    op: StepOp,
+    inputs: Vec<Arc<dyn ExecutionPlan>>,
+    input_types: Vec<ValueType>,
+    range: EvalRange,
+    schema: SchemaRef,
+}
+
+#[async_trait]
+impl ExecutionPlan for StepEvalExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> DfSchemaRef {
+        self.schema.arrow_schema().clone()
+    }
+
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        None
+    }
+
+    fn required_child_distribution(&self) -> Distribution {
+        Distribution::SinglePartition
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        self.inputs.clone()
+    }
+
+    fn with_new_children(
+        &self,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(Self {
+            inputs: children,
+            ..self.clone()
+        }))
+    }
+
+    async fn execute(
+        &self,
+        _partition: usize,
+        runtime: Arc<RuntimeEnv>,
+    ) -> DfResult<SendableRecordBatchStream> {
+        let timestamps = self.range.timestamps();
+        let mut args = Vec::with_capacity(self.inputs.len());
+        for (input, value_type) in self.inputs.iter().zip(&self.input_types) {
+            let series = collect_series(input, runtime.clone()).await?;
+            let arg = match value_type {
+                ValueType::Scalar => EvalValue::Scalar(scalar_values(&series, &self.range)),
+                _ => EvalValue::Vector(
+                    series
+                        .into_iter()
+                        .map(|s| StepSeries {
+                            values: step_values(&s, &self.range),
+                            labels: s.labels,
+                        })
+                        .collect(),
+                ),
+            };
+            args.push(arg);
+        }
+
+        let mut builder = SeriesBatchBuilder::new(self.schema.clone());
+        match self.op.eval(args, &timestamps).map_err(to_df_error)? {
+            EvalValue::Scalar(values) => {
+                let samples = timestamps
+                    .iter()
+                    .zip(values)
+                    .map(|(ts, value)| Sample::new(*ts, value));
+                builder.push(&Labels::new(), samples).map_err(to_df_error)?;
+            }
+            EvalValue::Vector(series) => {
+                for s in &series {
+                    builder.push_steps(s, &timestamps).map_err(to_df_error)?;
+                }
+            }
+            other => {
+                return unexpected_type("scalar or instant vector", &other).map_err(to_df_error)
+            }
+        }
+        memory_stream(builder.finish()?, self.schema())
+    }
+
+    fn fmt_as(&self, _t: DisplayFormatType, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "PromStepEvalExec: op={}, range={:?}",
+            self.op, self.range
+        )
+    }
+
+    fn statistics(&self) -> Statistics {
+        Statistics::default()
+    }
+}
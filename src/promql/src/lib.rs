@@ -0,0 +1,26 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A PromQL parser and evaluator over the Prometheus metrics stored in GreptimeDB.
+
+pub mod ast;
+pub mod engine;
+pub mod error;
+pub mod extension_plan;
+pub mod parser;
+pub mod planner;
+pub mod value;
+
+pub use engine::{Engine, EvalRange, Storage};
+pub use parser::{parse, parse_duration};
@@ -0,0 +1,853 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod lexer;
+
+use snafu::ensure;
+
+use crate::ast::{
+    AggregateExpr, AggregateOp, BinaryExpr, BinaryOp, Call, Cardinality, Expr, Function, Grouping,
+    MatchOp, Matcher, MatrixSelector, SubqueryExpr, ValueType, VectorMatching, VectorSelector,
+    METRIC_NAME,
+};
+use crate::error::{self, Result};
+use crate::parser::lexer::Token;
+
+/// Parses a PromQL query into an expression.
+pub fn parse(query: &str) -> Result<Expr> {
+    let tokens = lexer::tokenize(query)?;
+    let mut parser = Parser { tokens, index: 0 };
+    let expr = parser.parse_expr(0)?;
+    parser.expect(Token::Eof)?;
+    Ok(expr)
+}
+
+/// Parses a duration like `1h30m` into milliseconds.
+pub fn parse_duration(duration: &str) -> Result<i64> {
+    let tokens = lexer::tokenize(duration)?;
+    match tokens.as_slice() {
+        [(Token::Duration(ms), _), (Token::Eof, _)] => Ok(*ms),
+        _ => error::ParseSnafu {
+            pos: 0usize,
+            msg: format!("invalid duration \"{}\"", duration),
+        }
+        .fail(),
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> &Token {
+        // The last token is always `Eof`.
+        let index = (self.index + n).min(self.tokens.len() - 1);
+        &self.tokens[index].0
+    }
+
+    fn pos(&self) -> usize {
+        self.tokens[self.index].1
+    }
+
+    fn next_token(&mut self) -> Token {
+        let token = self.peek().clone();
+        if self.index < self.tokens.len() - 1 {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<()> {
+        if *self.peek() == expected {
+            self.next_token();
+            Ok(())
+        } else {
+            self.unexpected(&expected.describe())
+        }
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T> {
+        self.fail(format!(
+            "unexpected {}, expected {}",
+            self.peek().describe(),
+            expected
+        ))
+    }
+
+    fn fail<T>(&self, msg: impl Into<String>) -> Result<T> {
+        error::ParseSnafu {
+            pos: self.pos(),
+            msg: msg.into(),
+        }
+        .fail()
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Ident(ident) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    /// Consumes the next token if it's the `keyword`.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let is_keyword = self.is_keyword(keyword);
+        if is_keyword {
+            self.next_token();
+        }
+        is_keyword
+    }
+
+    fn expect_ident(&mut self) -> Result<String> {
+        match self.peek().clone() {
+            Token::Ident(ident) => {
+                self.next_token();
+                Ok(ident)
+            }
+            _ => self.unexpected("identifier"),
+        }
+    }
+
+    fn expect_duration(&mut self) -> Result<i64> {
+        match self.peek() {
+            Token::Duration(ms) => {
+                let ms = *ms;
+                self.next_token();
+                Ok(ms)
+            }
+            _ => self.unexpected("duration"),
+        }
+    }
+
+    fn binary_op(&self) -> Option<BinaryOp> {
+        let op = match self.peek() {
+            Token::Add => BinaryOp::Add,
+            Token::Sub => BinaryOp::Sub,
+            Token::Mul => BinaryOp::Mul,
+            Token::Div => BinaryOp::Div,
+            Token::Mod => BinaryOp::Mod,
+            Token::Pow => BinaryOp::Pow,
+            Token::EqualEqual => BinaryOp::Eq,
+            Token::NotEqual => BinaryOp::Ne,
+            Token::Greater => BinaryOp::Gt,
+            Token::Less => BinaryOp::Lt,
+            Token::GreaterEqual => BinaryOp::Ge,
+            Token::LessEqual => BinaryOp::Le,
+            Token::Ident(ident) => match ident.to_lowercase().as_str() {
+                "and" => BinaryOp::And,
+                "or" => BinaryOp::Or,
+                "unless" => BinaryOp::Unless,
+                "atan2" => BinaryOp::Atan2,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(op)
+    }
+
+    /// Parses an expression whose binary operators have at least `min_precedence`.
+    fn parse_expr(&mut self, min_precedence: u8) -> Result<Expr> {
+        let mut lhs = self.parse_unary()?;
+        while let Some(op) = self.binary_op() {
+            if op.precedence() < min_precedence {
+                break;
+            }
+            let pos = self.pos();
+            self.next_token();
+
+            let return_bool = self.eat_keyword("bool");
+            if return_bool && !op.is_comparison() {
+                return self.fail("bool modifier can only be used on comparison operators");
+            }
+            let matching = self.parse_vector_matching()?;
+
+            let next_precedence = if op.is_right_associative() {
+                op.precedence()
+            } else {
+                op.precedence() + 1
+            };
+            let rhs = self.parse_expr(next_precedence)?;
+            lhs = new_binary_expr(op, lhs, rhs, return_bool, matching, pos)?;
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        let negative = match self.peek() {
+            Token::Sub => true,
+            Token::Add => false,
+            _ => {
+                let expr = self.parse_primary()?;
+                return self.parse_postfix(expr);
+            }
+        };
+        self.next_token();
+
+        let pos = self.pos();
+        // Unary operators bind tighter than binary operators except `^`.
+        let expr = self.parse_expr(BinaryOp::Pow.precedence())?;
+        ensure!(
+            matches!(expr.value_type(), ValueType::Scalar | ValueType::Vector),
+            error::ParseSnafu {
+                pos,
+                msg: format!(
+                    "unary expression only allowed on expressions of type scalar or instant vector, got {}",
+                    expr.value_type().name()
+                ),
+            }
+        );
+        if negative {
+            Ok(Expr::Neg(Box::new(expr)))
+        } else {
+            Ok(expr)
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        match self.peek().clone() {
+            Token::Number(n) => {
+                self.next_token();
+                Ok(Expr::NumberLiteral(n))
+            }
+            Token::String(s) => {
+                self.next_token();
+                Ok(Expr::StringLiteral(s))
+            }
+            Token::LeftParen => {
+                self.next_token();
+                let expr = self.parse_expr(0)?;
+                self.expect(Token::RightParen)?;
+                Ok(Expr::Paren(Box::new(expr)))
+            }
+            Token::LeftBrace => self.parse_vector_selector(None),
+            Token::Ident(ident) => {
+                let is_call = *self.peek_nth(1) == Token::LeftParen;
+                let has_grouping = matches!(
+                    self.peek_nth(1),
+                    Token::Ident(next) if next.eq_ignore_ascii_case("by")
+                        || next.eq_ignore_ascii_case("without")
+                );
+                match AggregateOp::from_name(&ident.to_lowercase()) {
+                    Some(op) if is_call || has_grouping => {
+                        self.next_token();
+                        self.parse_aggregate(op)
+                    }
+                    _ if is_call => {
+                        self.next_token();
+                        self.parse_call(&ident)
+                    }
+                    _ => {
+                        self.next_token();
+                        self.parse_vector_selector(Some(ident))
+                    }
+                }
+            }
+            _ => self.unexpected("expression"),
+        }
+    }
+
+    /// Parses the range, subquery and offset modifiers following `expr`.
+    fn parse_postfix(&mut self, mut expr: Expr) -> Result<Expr> {
+        loop {
+            if *self.peek() == Token::LeftBracket {
+                let pos = self.pos();
+                self.next_token();
+                let range_ms = self.expect_duration()?;
+                if *self.peek() == Token::Colon {
+                    self.next_token();
+                    let step_ms = match self.peek() {
+                        Token::Duration(_) => Some(self.expect_duration()?),
+                        _ => None,
+                    };
+                    self.expect(Token::RightBracket)?;
+                    ensure!(
+                        expr.value_type() == ValueType::Vector,
+                        error::ParseSnafu {
+                            pos,
+                            msg: format!(
+                                "subquery is only allowed on instant vector, got {}",
+                                expr.value_type().name()
+                            ),
+                        }
+                    );
+                    expr = Expr::Subquery(SubqueryExpr {
+                        expr: Box::new(expr),
+                        range_ms,
+                        step_ms,
+                        offset_ms: 0,
+                    });
+                } else {
+                    self.expect(Token::RightBracket)?;
+                    expr = match expr {
+                        Expr::VectorSelector(selector) if selector.offset_ms == 0 => {
+                            Expr::MatrixSelector(MatrixSelector { selector, range_ms })
+                        }
+                        _ => {
+                            return error::ParseSnafu {
+                                pos,
+                                msg: "ranges only allowed for vector selectors",
+                            }
+                            .fail()
+                        }
+                    };
+                }
+            } else if self.is_keyword("offset") {
+                let pos = self.pos();
+                self.next_token();
+                let negative = *self.peek() == Token::Sub;
+                if negative {
+                    self.next_token();
+                }
+                let offset = self.expect_duration()?;
+                let offset = if negative { -offset } else { offset };
+                let offset_ms = match &mut expr {
+                    Expr::VectorSelector(selector)
+                    | Expr::MatrixSelector(MatrixSelector { selector, .. }) => {
+                        &mut selector.offset_ms
+                    }
+                    Expr::Subquery(subquery) => &mut subquery.offset_ms,
+                    _ => {
+                        return error::ParseSnafu {
+                            pos,
+                            msg: "offset modifier must be preceded by an instant vector selector or range vector selector or a subquery",
+                        }
+                        .fail()
+                    }
+                };
+                ensure!(
+                    *offset_ms == 0,
+                    error::ParseSnafu {
+                        pos,
+                        msg: "offset may not be set multiple times",
+                    }
+                );
+                *offset_ms = offset;
+            } else if *self.peek() == Token::At {
+                return error::UnsupportedSnafu { feat: "@ modifier" }.fail();
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn parse_vector_selector(&mut self, metric_name: Option<String>) -> Result<Expr> {
+        let pos = self.pos();
+        let mut matchers = Vec::new();
+        if let Some(name) = &metric_name {
+            matchers.push(Matcher::new(MatchOp::Equal, METRIC_NAME, name)?);
+        }
+        if *self.peek() == Token::LeftBrace {
+            self.next_token();
+            self.parse_label_matchers(&mut matchers)?;
+        }
+
+        if metric_name.is_some() {
+            ensure!(
+                matchers.iter().filter(|m| m.name == METRIC_NAME).count() == 1,
+                error::ParseSnafu {
+                    pos,
+                    msg: "metric name must not be set twice",
+                }
+            );
+        }
+        // Selectors that match all series of all metrics are not allowed.
+        ensure!(
+            matchers.iter().any(|m| !m.matches("")),
+            error::ParseSnafu {
+                pos,
+                msg: "vector selector must contain at least one non-empty matcher",
+            }
+        );
+
+        Ok(Expr::VectorSelector(VectorSelector {
+            matchers,
+            offset_ms: 0,
+        }))
+    }
+
+    /// Parses label matchers after the left brace.
+    fn parse_label_matchers(&mut self, matchers: &mut Vec<Matcher>) -> Result<()> {
+        loop {
+            if *self.peek() == Token::RightBrace {
+                self.next_token();
+                return Ok(());
+            }
+
+            let name = self.expect_ident()?;
+            let op = match self.peek() {
+                Token::Assign => MatchOp::Equal,
+                Token::NotEqual => MatchOp::NotEqual,
+                Token::RegexMatch => MatchOp::Re,
+                Token::RegexNotMatch => MatchOp::NotRe,
+                _ => return self.unexpected("label matching operator"),
+            };
+            self.next_token();
+            let value = match self.peek().clone() {
+                Token::String(value) => value,
+                _ => return self.unexpected("string"),
+            };
+            self.next_token();
+            matchers.push(Matcher::new(op, name, value)?);
+
+            match self.peek() {
+                Token::Comma => {
+                    self.next_token();
+                }
+                Token::RightBrace => {}
+                _ => return self.unexpected("\",\" or \"}\""),
+            }
+        }
+    }
+
+    fn parse_aggregate(&mut self, op: AggregateOp) -> Result<Expr> {
+        let mut grouping = self.parse_grouping()?;
+        self.expect(Token::LeftParen)?;
+
+        let param = match op.param_type() {
+            Some(param_type) => {
+                let param = self.parse_typed_expr(param_type)?;
+                self.expect(Token::Comma)?;
+                Some(Box::new(param))
+            }
+            None => None,
+        };
+        let expr = self.parse_typed_expr(ValueType::Vector)?;
+        self.expect(Token::RightParen)?;
+
+        if grouping.is_none() {
+            grouping = self.parse_grouping()?;
+        }
+        Ok(Expr::Aggregate(AggregateExpr {
+            op,
+            expr: Box::new(expr),
+            param,
+            grouping: grouping.unwrap_or_default(),
+        }))
+    }
+
+    fn parse_grouping(&mut self) -> Result<Option<Grouping>> {
+        if self.eat_keyword("by") {
+            Ok(Some(Grouping::By(self.parse_label_list()?)))
+        } else if self.eat_keyword("without") {
+            Ok(Some(Grouping::Without(self.parse_label_list()?)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Parses a parenthesized list of label names.
+    fn parse_label_list(&mut self) -> Result<Vec<String>> {
+        self.expect(Token::LeftParen)?;
+        let mut labels = Vec::new();
+        loop {
+            if *self.peek() == Token::RightParen {
+                self.next_token();
+                return Ok(labels);
+            }
+            labels.push(self.expect_ident()?);
+            match self.peek() {
+                Token::Comma => {
+                    self.next_token();
+                }
+                Token::RightParen => {}
+                _ => return self.unexpected("\",\" or \")\""),
+            }
+        }
+    }
+
+    fn parse_call(&mut self, name: &str) -> Result<Expr> {
+        let func = match Function::get(name) {
+            Some(func) => func,
+            None => return self.fail(format!("unknown function with name \"{}\"", name)),
+        };
+        self.expect(Token::LeftParen)?;
+
+        let mut args = Vec::new();
+        while *self.peek() != Token::RightParen {
+            if !args.is_empty() {
+                self.expect(Token::Comma)?;
+            }
+            let arg_type = match func.arg_types.get(args.len()) {
+                Some(arg_type) => *arg_type,
+                None if func.variadic => *func.arg_types.last().unwrap(),
+                None => {
+                    return self.fail(format!(
+                        "expected at most {} argument(s) in call to \"{}\"",
+                        func.arg_types.len(),
+                        name
+                    ))
+                }
+            };
+            args.push(self.parse_typed_expr(arg_type)?);
+        }
+        if args.len() < func.min_args {
+            return self.fail(format!(
+                "expected at least {} argument(s) in call to \"{}\", got {}",
+                func.min_args,
+                name,
+                args.len()
+            ));
+        }
+        self.expect(Token::RightParen)?;
+
+        Ok(Expr::Call(Call { func, args }))
+    }
+
+    /// Parses an expression of the `expected` type.
+    fn parse_typed_expr(&mut self, expected: ValueType) -> Result<Expr> {
+        let pos = self.pos();
+        let expr = self.parse_expr(0)?;
+        ensure!(
+            expr.value_type() == expected,
+            error::ParseSnafu {
+                pos,
+                msg: format!(
+                    "expected type {} in expression, got {}",
+                    expected.name(),
+                    expr.value_type().name()
+                ),
+            }
+        );
+        Ok(expr)
+    }
+
+    fn parse_vector_matching(&mut self) -> Result<Option<VectorMatching>> {
+        let on = if self.eat_keyword("on") {
+            true
+        } else if self.eat_keyword("ignoring") {
+            false
+        } else {
+            return Ok(None);
+        };
+        let labels = self.parse_label_list()?;
+
+        let card = if self.eat_keyword("group_left") {
+            Cardinality::ManyToOne
+        } else if self.eat_keyword("group_right") {
+            Cardinality::OneToMany
+        } else {
+            return Ok(Some(VectorMatching {
+                labels,
+                on,
+                ..Default::default()
+            }));
+        };
+        let include = if *self.peek() == Token::LeftParen {
+            self.parse_label_list()?
+        } else {
+            vec![]
+        };
+        Ok(Some(VectorMatching {
+            card,
+            labels,
+            on,
+            include,
+        }))
+    }
+}
+
+fn new_binary_expr(
+    op: BinaryOp,
+    lhs: Expr,
+    rhs: Expr,
+    return_bool: bool,
+    matching: Option<VectorMatching>,
+    pos: usize,
+) -> Result<Expr> {
+    let fail = |msg: &str| {
+        error::ParseSnafu {
+            pos,
+            msg: msg.to_string(),
+        }
+        .fail()
+    };
+
+    let (lhs_type, rhs_type) = (lhs.value_type(), rhs.value_type());
+    for operand_type in [lhs_type, rhs_type] {
+        if !matches!(operand_type, ValueType::Scalar | ValueType::Vector) {
+            return fail(&format!(
+                "binary expression must contain only scalar and instant vector types, got {}",
+                operand_type.name()
+            ));
+        }
+    }
+
+    let both_vectors = lhs_type == ValueType::Vector && rhs_type == ValueType::Vector;
+    if op.is_comparison()
+        && !return_bool
+        && lhs_type == ValueType::Scalar
+        && rhs_type == ValueType::Scalar
+    {
+        return fail("comparisons between scalars must use bool modifier");
+    }
+    if op.is_set_operator() && !both_vectors {
+        return fail("set operator not allowed in binary scalar expression");
+    }
+
+    let matching = match matching {
+        Some(_) if !both_vectors => {
+            return fail("vector matching only allowed between instant vectors");
+        }
+        Some(matching) if op.is_set_operator() => {
+            if matching.card != Cardinality::OneToOne {
+                return fail("no grouping allowed for set operations");
+            }
+            Some(VectorMatching {
+                card: Cardinality::ManyToMany,
+                ..matching
+            })
+        }
+        Some(matching) => Some(matching),
+        None if both_vectors => Some(VectorMatching {
+            card: if op.is_set_operator() {
+                Cardinality::ManyToMany
+            } else {
+                Cardinality::OneToOne
+            },
+            ..Default::default()
+        }),
+        None => None,
+    };
+
+    Ok(Expr::Binary(BinaryExpr {
+        op,
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+        return_bool,
+        matching,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selector(name: &str, matchers: Vec<(MatchOp, &str, &str)>) -> VectorSelector {
+        let mut all = vec![Matcher::new(MatchOp::Equal, METRIC_NAME, name).unwrap()];
+        all.extend(
+            matchers
+                .into_iter()
+                .map(|(op, name, value)| Matcher::new(op, name, value).unwrap()),
+        );
+        VectorSelector {
+            matchers: all,
+            offset_ms: 0,
+        }
+    }
+
+    fn binary(op: BinaryOp, lhs: Expr, rhs: Expr, matching: Option<VectorMatching>) -> Expr {
+        Expr::Binary(BinaryExpr {
+            op,
+            lhs: Box::new(lhs),
+            rhs: Box::new(rhs),
+            return_bool: false,
+            matching,
+        })
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(90_000, parse_duration("1m30s").unwrap());
+        assert_eq!(5 * 60 * 1000, parse_duration("5m").unwrap());
+        assert!(parse_duration("5").is_err());
+        assert!(parse_duration("5m foo").is_err());
+    }
+
+    #[test]
+    fn test_parse_selector() {
+        assert_eq!(
+            Expr::VectorSelector(selector(
+                "http_requests_total",
+                vec![
+                    (MatchOp::Equal, "job", "api"),
+                    (MatchOp::NotEqual, "code", "500"),
+                    (MatchOp::Re, "path", "/api/.*"),
+                    (MatchOp::NotRe, "method", "GET|HEAD"),
+                ]
+            )),
+            parse(r#"http_requests_total{job="api", code!="500", path=~"/api/.*", method!~"GET|HEAD",}"#)
+                .unwrap()
+        );
+
+        let mut expected = selector("foo", vec![]);
+        expected.offset_ms = 5 * 60 * 1000;
+        assert_eq!(
+            Expr::MatrixSelector(MatrixSelector {
+                selector: expected,
+                range_ms: 30 * 1000,
+            }),
+            parse("foo[30s] offset 5m").unwrap()
+        );
+
+        let expr = parse(r#"{__name__=~"foo|bar", job="a"}"#).unwrap();
+        match expr {
+            Expr::VectorSelector(selector) => {
+                assert_eq!(None, selector.metric_name());
+                assert_eq!(2, selector.matchers.len());
+            }
+            _ => panic!("unexpected expr: {:?}", expr),
+        }
+
+        assert!(parse(r#"{job=~".*"}"#).is_err());
+        assert!(parse(r#"foo{__name__="bar"}"#).is_err());
+        assert!(parse(r#"foo{job=~"("}"#).is_err());
+        assert!(parse("foo offset 1m[5m]").is_err());
+        assert!(parse("foo @ 100").is_err());
+    }
+
+    #[test]
+    fn test_parse_call_and_aggregate() {
+        let rate = Expr::Call(Call {
+            func: Function::get("rate").unwrap(),
+            args: vec![Expr::MatrixSelector(MatrixSelector {
+                selector: selector("http_requests_total", vec![]),
+                range_ms: 5 * 60 * 1000,
+            })],
+        });
+        let expected = Expr::Aggregate(AggregateExpr {
+            op: AggregateOp::Sum,
+            expr: Box::new(rate.clone()),
+            param: None,
+            grouping: Grouping::By(vec!["job".to_string(), "instance".to_string()]),
+        });
+        assert_eq!(
+            expected,
+            parse("sum by (job, instance) (rate(http_requests_total[5m]))").unwrap()
+        );
+        assert_eq!(
+            expected,
+            parse("SUM(rate(http_requests_total[5m])) BY (job, instance)").unwrap()
+        );
+
+        assert_eq!(
+            Expr::Aggregate(AggregateExpr {
+                op: AggregateOp::Topk,
+                expr: Box::new(rate),
+                param: Some(Box::new(Expr::NumberLiteral(3.0))),
+                grouping: Grouping::Without(vec!["instance".to_string()]),
+            }),
+            parse("topk without (instance) (3, rate(http_requests_total[5m]))").unwrap()
+        );
+
+        match parse("max_over_time(rate(foo[1m])[10m:30s] offset 1m)").unwrap() {
+            Expr::Call(call) => match &call.args[0] {
+                Expr::Subquery(subquery) => {
+                    assert_eq!(10 * 60 * 1000, subquery.range_ms);
+                    assert_eq!(Some(30 * 1000), subquery.step_ms);
+                    assert_eq!(60 * 1000, subquery.offset_ms);
+                }
+                other => panic!("unexpected expr: {:?}", other),
+            },
+            other => panic!("unexpected expr: {:?}", other),
+        }
+
+        // A metric can be named after an aggregation.
+        assert_eq!(
+            Expr::VectorSelector(selector("count", vec![])),
+            parse("count").unwrap()
+        );
+        assert!(parse("round(foo)").is_ok());
+        assert!(parse("label_join(foo, \"dst\", \",\", \"a\", \"b\", \"c\")").is_ok());
+        assert!(parse("rate(foo)").is_err());
+        assert!(parse("abs(foo, 1)").is_err());
+        assert!(parse("unknown_func(foo)").is_err());
+        assert!(parse("sum(foo[5m])").is_err());
+    }
+
+    #[test]
+    fn test_parse_binary() {
+        // `*` binds tighter than `+`, `^` is right associative and binds tighter than `-`.
+        assert_eq!(
+            binary(
+                BinaryOp::Add,
+                Expr::NumberLiteral(1.0),
+                binary(
+                    BinaryOp::Mul,
+                    Expr::NumberLiteral(2.0),
+                    Expr::Neg(Box::new(binary(
+                        BinaryOp::Pow,
+                        Expr::NumberLiteral(3.0),
+                        binary(
+                            BinaryOp::Pow,
+                            Expr::NumberLiteral(4.0),
+                            Expr::NumberLiteral(5.0),
+                            None
+                        ),
+                        None
+                    ))),
+                    None
+                ),
+                None
+            ),
+            parse("1 + 2 * -3 ^ 4 ^ 5").unwrap()
+        );
+
+        assert_eq!(
+            binary(
+                BinaryOp::Div,
+                Expr::VectorSelector(selector("foo", vec![])),
+                Expr::VectorSelector(selector("bar", vec![])),
+                Some(VectorMatching {
+                    card: Cardinality::ManyToOne,
+                    labels: vec!["job".to_string()],
+                    on: true,
+                    include: vec!["team".to_string()],
+                })
+            ),
+            parse("foo / on(job) group_left(team) bar").unwrap()
+        );
+
+        assert_eq!(
+            binary(
+                BinaryOp::Or,
+                binary(
+                    BinaryOp::And,
+                    Expr::VectorSelector(selector("a", vec![])),
+                    Expr::VectorSelector(selector("b", vec![])),
+                    Some(VectorMatching {
+                        card: Cardinality::ManyToMany,
+                        ..Default::default()
+                    })
+                ),
+                Expr::VectorSelector(selector("c", vec![])),
+                Some(VectorMatching {
+                    card: Cardinality::ManyToMany,
+                    ..Default::default()
+                })
+            ),
+            parse("a and b or c").unwrap()
+        );
+
+        match parse("foo > bool 1").unwrap() {
+            Expr::Binary(binary) => {
+                assert!(binary.return_bool);
+                assert_eq!(None, binary.matching);
+            }
+            other => panic!("unexpected expr: {:?}", other),
+        }
+
+        assert!(parse("1 > 2").is_err());
+        assert!(parse("1 > bool 2").is_ok());
+        assert!(parse("foo + bool bar").is_err());
+        assert!(parse("foo and 1").is_err());
+        assert!(parse("foo[5m] + 1").is_err());
+        assert!(parse("1 + on(job) foo").is_err());
+        assert!(parse("foo and on(job) group_left bar").is_err());
+        assert!(parse("(foo").is_err());
+        assert!(parse("foo bar").is_err());
+    }
+}
@@ -0,0 +1,525 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::error::{self, Result};
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Token {
+    Ident(String),
+    Number(f64),
+    /// Duration in milliseconds.
+    Duration(i64),
+    String(String),
+    LeftParen,
+    RightParen,
+    LeftBrace,
+    RightBrace,
+    LeftBracket,
+    RightBracket,
+    Comma,
+    Colon,
+    At,
+    /// `=`, only valid in label matchers.
+    Assign,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Pow,
+    EqualEqual,
+    NotEqual,
+    Greater,
+    Less,
+    GreaterEqual,
+    LessEqual,
+    RegexMatch,
+    RegexNotMatch,
+    Eof,
+}
+
+impl Token {
+    pub(crate) fn describe(&self) -> String {
+        match self {
+            Token::Ident(ident) => format!("identifier \"{}\"", ident),
+            Token::Number(n) => format!("number \"{}\"", n),
+            Token::Duration(_) => "duration".to_string(),
+            Token::String(s) => format!("string \"{}\"", s),
+            Token::Eof => "end of input".to_string(),
+            other => format!("\"{}\"", other.symbol()),
+        }
+    }
+
+    fn symbol(&self) -> &'static str {
+        match self {
+            Token::LeftParen => "(",
+            Token::RightParen => ")",
+            Token::LeftBrace => "{",
+            Token::RightBrace => "}",
+            Token::LeftBracket => "[",
+            Token::RightBracket => "]",
+            Token::Comma => ",",
+            Token::Colon => ":",
+            Token::At => "@",
+            Token::Assign => "=",
+            Token::Add => "+",
+            Token::Sub => "-",
+            Token::Mul => "*",
+            Token::Div => "/",
+            Token::Mod => "%",
+            Token::Pow => "^",
+            Token::EqualEqual => "==",
+            Token::NotEqual => "!=",
+            Token::Greater => ">",
+            Token::Less => "<",
+            Token::GreaterEqual => ">=",
+            Token::LessEqual => "<=",
+            Token::RegexMatch => "=~",
+            Token::RegexNotMatch => "!~",
+            _ => "",
+        }
+    }
+}
+
+/// Splits a PromQL query into tokens, each token is paired with its position in the query.
+pub(crate) fn tokenize(input: &str) -> Result<Vec<(Token, usize)>> {
+    let mut lexer = Lexer {
+        chars: input.char_indices().collect(),
+        pos: 0,
+        input_len: input.len(),
+    };
+    let mut tokens = Vec::new();
+    loop {
+        let (token, pos) = lexer.next_token()?;
+        let eof = token == Token::Eof;
+        tokens.push((token, pos));
+        if eof {
+            return Ok(tokens);
+        }
+    }
+}
+
+struct Lexer {
+    chars: Vec<(usize, char)>,
+    /// Index of the next char in `chars`.
+    pos: usize,
+    input_len: usize,
+}
+
+impl Lexer {
+    fn peek(&self) -> Option<char> {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<char> {
+        self.chars.get(self.pos + n).map(|(_, c)| *c)
+    }
+
+    fn offset(&self) -> usize {
+        self.chars
+            .get(self.pos)
+            .map(|(offset, _)| *offset)
+            .unwrap_or(self.input_len)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek();
+        self.pos += 1;
+        c
+    }
+
+    fn bump_if(&mut self, expected: char) -> bool {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn skip_whitespaces_and_comments(&mut self) {
+        while let Some(c) = self.peek() {
+            if c.is_whitespace() {
+                self.pos += 1;
+            } else if c == '#' {
+                while !matches!(self.bump(), Some('\n') | None) {}
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn next_token(&mut self) -> Result<(Token, usize)> {
+        self.skip_whitespaces_and_comments();
+
+        let start = self.offset();
+        let c = match self.bump() {
+            Some(c) => c,
+            None => return Ok((Token::Eof, start)),
+        };
+        let token = match c {
+            '(' => Token::LeftParen,
+            ')' => Token::RightParen,
+            '{' => Token::LeftBrace,
+            '}' => Token::RightBrace,
+            '[' => Token::LeftBracket,
+            ']' => Token::RightBracket,
+            ',' => Token::Comma,
+            ':' => Token::Colon,
+            '@' => Token::At,
+            '+' => Token::Add,
+            '-' => Token::Sub,
+            '*' => Token::Mul,
+            '/' => Token::Div,
+            '%' => Token::Mod,
+            '^' => Token::Pow,
+            '=' => {
+                if self.bump_if('=') {
+                    Token::EqualEqual
+                } else if self.bump_if('~') {
+                    Token::RegexMatch
+                } else {
+                    Token::Assign
+                }
+            }
+            '!' => {
+                if self.bump_if('=') {
+                    Token::NotEqual
+                } else if self.bump_if('~') {
+                    Token::RegexNotMatch
+                } else {
+                    return error::ParseSnafu {
+                        pos: start,
+                        msg: "unexpected character after '!'",
+                    }
+                    .fail();
+                }
+            }
+            '>' => {
+                if self.bump_if('=') {
+                    Token::GreaterEqual
+                } else {
+                    Token::Greater
+                }
+            }
+            '<' => {
+                if self.bump_if('=') {
+                    Token::LessEqual
+                } else {
+                    Token::Less
+                }
+            }
+            '"' | '\'' => Token::String(self.quoted_string(c, start)?),
+            '`' => Token::String(self.raw_string(start)?),
+            c if c.is_ascii_digit()
+                || (c == '.' && self.peek().map_or(false, |c| c.is_ascii_digit())) =>
+            {
+                self.pos -= 1;
+                self.number_or_duration(start)?
+            }
+            c if is_ident_start(c) => {
+                self.pos -= 1;
+                let ident = self.ident();
+                if ident.eq_ignore_ascii_case("inf") {
+                    Token::Number(f64::INFINITY)
+                } else if ident.eq_ignore_ascii_case("nan") {
+                    Token::Number(f64::NAN)
+                } else {
+                    Token::Ident(ident)
+                }
+            }
+            c => {
+                return error::ParseSnafu {
+                    pos: start,
+                    msg: format!("unexpected character '{}'", c),
+                }
+                .fail();
+            }
+        };
+        Ok((token, start))
+    }
+
+    fn ident(&mut self) -> String {
+        let mut ident = String::new();
+        while let Some(c) = self.peek() {
+            if is_ident_start(c) || c.is_ascii_digit() || c == ':' {
+                ident.push(c);
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        ident
+    }
+
+    fn quoted_string(&mut self, quote: char, start: usize) -> Result<String> {
+        let mut s = String::new();
+        loop {
+            let c = match self.bump() {
+                Some(c) => c,
+                None => {
+                    return error::ParseSnafu {
+                        pos: start,
+                        msg: "unterminated quoted string",
+                    }
+                    .fail()
+                }
+            };
+            if c == quote {
+                return Ok(s);
+            }
+            if c != '\\' {
+                s.push(c);
+                continue;
+            }
+
+            let escaped = match self.bump() {
+                Some('n') => '\n',
+                Some('t') => '\t',
+                Some('r') => '\r',
+                Some('a') => '\x07',
+                Some('b') => '\x08',
+                Some('f') => '\x0c',
+                Some('v') => '\x0b',
+                Some('\\') => '\\',
+                Some(c) if c == quote => c,
+                Some(c @ ('x' | 'u' | 'U')) => {
+                    let len = match c {
+                        'x' => 2,
+                        'u' => 4,
+                        _ => 8,
+                    };
+                    let hex: String = (0..len).filter_map(|_| self.bump()).collect();
+                    u32::from_str_radix(&hex, 16)
+                        .ok()
+                        .and_then(char::from_u32)
+                        .ok_or_else(|| {
+                            error::ParseSnafu {
+                                pos: start,
+                                msg: format!("invalid escape sequence \\{}{}", c, hex),
+                            }
+                            .build()
+                        })?
+                }
+                other => {
+                    return error::ParseSnafu {
+                        pos: start,
+                        msg: format!(
+                            "unknown escape sequence \\{}",
+                            other.map(String::from).unwrap_or_default()
+                        ),
+                    }
+                    .fail()
+                }
+            };
+            s.push(escaped);
+        }
+    }
+
+    fn raw_string(&mut self, start: usize) -> Result<String> {
+        let mut s = String::new();
+        loop {
+            match self.bump() {
+                Some('`') => return Ok(s),
+                Some(c) => s.push(c),
+                None => {
+                    return error::ParseSnafu {
+                        pos: start,
+                        msg: "unterminated raw string",
+                    }
+                    .fail()
+                }
+            }
+        }
+    }
+
+    fn number_or_duration(&mut self, start: usize) -> Result<Token> {
+        if self.peek() == Some('0') && matches!(self.peek_nth(1), Some('x' | 'X')) {
+            self.pos += 2;
+            let digits = self.take_while(|c| c.is_ascii_hexdigit());
+            return i64::from_str_radix(&digits, 16)
+                .map(|n| Token::Number(n as f64))
+                .map_err(|_| {
+                    error::ParseSnafu {
+                        pos: start,
+                        msg: format!("invalid hexadecimal number 0x{}", digits),
+                    }
+                    .build()
+                });
+        }
+
+        let integer = self.take_while(|c| c.is_ascii_digit());
+        if !integer.is_empty() && self.peek().map_or(false, is_duration_unit_start) {
+            return self.duration(integer, start);
+        }
+
+        let mut number = integer;
+        if self.bump_if('.') {
+            number.push('.');
+            number.push_str(&self.take_while(|c| c.is_ascii_digit()));
+        }
+        if matches!(self.peek(), Some('e' | 'E')) {
+            let has_exponent = match self.peek_nth(1) {
+                Some('+' | '-') => self.peek_nth(2).map_or(false, |c| c.is_ascii_digit()),
+                Some(c) => c.is_ascii_digit(),
+                None => false,
+            };
+            if has_exponent {
+                number.push('e');
+                self.pos += 1;
+                if let Some(sign @ ('+' | '-')) = self.peek() {
+                    number.push(sign);
+                    self.pos += 1;
+                }
+                number.push_str(&self.take_while(|c| c.is_ascii_digit()));
+            }
+        }
+        number.parse::<f64>().map(Token::Number).map_err(|_| {
+            error::ParseSnafu {
+                pos: start,
+                msg: format!("invalid number {}", number),
+            }
+            .build()
+        })
+    }
+
+    /// Parses a duration like `1h30m`, `integer` is the leading number already consumed.
+    fn duration(&mut self, integer: String, start: usize) -> Result<Token> {
+        let invalid_duration = || {
+            error::ParseSnafu {
+                pos: start,
+                msg: "invalid duration",
+            }
+            .build()
+        };
+
+        let mut total_ms: i64 = 0;
+        let mut number = integer;
+        loop {
+            let unit = self.take_while(|c| c.is_ascii_alphabetic());
+            let unit_ms = match unit.as_str() {
+                "ms" => 1,
+                "s" => 1000,
+                "m" => 60 * 1000,
+                "h" => 60 * 60 * 1000,
+                "d" => 24 * 60 * 60 * 1000,
+                "w" => 7 * 24 * 60 * 60 * 1000,
+                "y" => 365 * 24 * 60 * 60 * 1000,
+                _ => return Err(invalid_duration()),
+            };
+            let n: i64 = number.parse().map_err(|_| invalid_duration())?;
+            total_ms = n
+                .checked_mul(unit_ms)
+                .and_then(|ms| total_ms.checked_add(ms))
+                .ok_or_else(invalid_duration)?;
+
+            number = self.take_while(|c| c.is_ascii_digit());
+            if number.is_empty() {
+                return Ok(Token::Duration(total_ms));
+            }
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let mut s = String::new();
+        while let Some(c) = self.peek() {
+            if !predicate(c) {
+                break;
+            }
+            s.push(c);
+            self.pos += 1;
+        }
+        s
+    }
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_duration_unit_start(c: char) -> bool {
+    matches!(c, 's' | 'm' | 'h' | 'd' | 'w' | 'y')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tokens(input: &str) -> Vec<Token> {
+        tokenize(input)
+            .unwrap()
+            .into_iter()
+            .map(|(token, _)| token)
+            .collect()
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(
+            vec![
+                Token::Ident("rate".to_string()),
+                Token::LeftParen,
+                Token::Ident("http_requests_total".to_string()),
+                Token::LeftBrace,
+                Token::Ident("job".to_string()),
+                Token::Assign,
+                Token::String("api".to_string()),
+                Token::Comma,
+                Token::Ident("path".to_string()),
+                Token::RegexNotMatch,
+                Token::String("/a\\.b'".to_string()),
+                Token::RightBrace,
+                Token::LeftBracket,
+                Token::Duration(90 * 1000),
+                Token::RightBracket,
+                Token::RightParen,
+                Token::GreaterEqual,
+                Token::Number(1.5e3),
+                Token::Eof,
+            ],
+            tokens(
+                r#"rate(http_requests_total{job="api", path!~'/a\\.b\''}[1m30s]) >= 1.5e3 # comment"#
+            )
+        );
+
+        assert_eq!(
+            vec![
+                Token::Number(255.0),
+                Token::Mul,
+                Token::Number(0.5),
+                Token::Sub,
+                Token::Number(f64::INFINITY),
+                Token::Pow,
+                Token::String("a\\b".to_string()),
+                Token::Eof,
+            ],
+            tokens("0xff * .5 - Inf ^ `a\\b`")
+        );
+
+        assert!(matches!(tokens("NaN")[0], Token::Number(n) if n.is_nan()));
+        assert_eq!(Token::Duration(100), tokens("100ms")[0]);
+        assert_eq!(
+            vec![Token::Ident("job:rate5m".to_string()), Token::Eof],
+            tokens("job:rate5m")
+        );
+    }
+
+    #[test]
+    fn test_tokenize_error() {
+        assert!(tokenize("\"abc").is_err());
+        assert!(tokenize("a ! b").is_err());
+        assert!(tokenize("5mx").is_err());
+        assert!(tokenize("a $ b").is_err());
+    }
+}
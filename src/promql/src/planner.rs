@@ -0,0 +1,520 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Lowers PromQL expressions into logical plans.
+//!
+//! A selector becomes scans of the matched metric tables, with the label matchers and the time
+//! range pushed down as filters, under a [SeriesSelect] node. Functions over range vectors
+//! become [RangeFunction] nodes, and other functions, aggregations and operators become
+//! [StepEval] nodes. See [extension_plan](crate::extension_plan) for the layout of the series.
+
+use std::collections::BTreeSet;
+use std::sync::Arc;
+
+use datafusion::logical_plan::{
+    binary_expr, lit, Column, Expr as DfExpr, LogicalPlan, LogicalPlanBuilder, Operator,
+};
+use datafusion::scalar::ScalarValue;
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::SchemaRef;
+use snafu::ResultExt;
+use table::table::adapter::DfTableProviderAdapter;
+use table::TableRef;
+
+use crate::ast::{
+    AggregateExpr, AggregateOp, BinaryExpr, BinaryOp, Call, Cardinality, Expr, Grouping, MatchOp,
+    Matcher, SubqueryExpr, ValueType, VectorMatching, VectorSelector, METRIC_NAME,
+};
+use crate::engine::binary::drops_metric_name;
+use crate::engine::functions::absent_labels;
+use crate::engine::{EvalRange, Storage};
+use crate::error::{self, Result};
+use crate::extension_plan::{label_names, RangeFunction, SeriesSelect, StepEval, StepOp};
+
+/// Name of the timestamp column of the metric tables and the plans.
+pub const TIMESTAMP_COLUMN_NAME: &str = "greptime_timestamp";
+/// Name of the value column of the metric tables and the plans.
+pub const VALUE_COLUMN_NAME: &str = "greptime_value";
+
+/// Plans PromQL expressions over the metric tables in the [Storage].
+pub struct PromPlanner<'a, S> {
+    storage: &'a S,
+    lookback_delta: i64,
+    /// Max number of samples each selector could read.
+    max_samples: usize,
+}
+
+impl<'a, S: Storage> PromPlanner<'a, S> {
+    pub fn new(storage: &'a S, lookback_delta: i64, max_samples: usize) -> Self {
+        Self {
+            storage,
+            lookback_delta,
+            max_samples,
+        }
+    }
+
+    /// Plans the `expr` evaluated at each step of the `range`. A range vector is planned as its
+    /// raw samples covering the windows of all steps.
+    pub fn plan(&self, expr: &Expr, range: EvalRange) -> Result<LogicalPlan> {
+        match expr {
+            Expr::NumberLiteral(n) => {
+                StepEval::try_new(StepOp::Literal(*n), vec![], range, BTreeSet::new())
+                    .map(StepEval::into_plan)
+            }
+            Expr::StringLiteral(_) => error::EvalSnafu {
+                msg: "string literal is only allowed as a function argument",
+            }
+            .fail(),
+            Expr::Paren(expr) => self.plan(expr, range),
+            Expr::Neg(expr) => {
+                let input = self.plan(expr, range)?;
+                let mut labels = plan_labels(&input);
+                labels.remove(METRIC_NAME);
+                StepEval::try_new(StepOp::Neg, vec![(input, expr.value_type())], range, labels)
+                    .map(StepEval::into_plan)
+            }
+            Expr::VectorSelector(selector) => self.plan_selector(
+                selector,
+                Some(self.lookback_delta),
+                self.lookback_delta,
+                range,
+            ),
+            Expr::MatrixSelector(matrix) => {
+                self.plan_selector(&matrix.selector, None, matrix.range_ms, range)
+            }
+            Expr::Subquery(subquery) => self.plan(&subquery.expr, subquery_range(subquery, range)),
+            Expr::Call(call) => self.plan_call(call, range),
+            Expr::Aggregate(aggregate) => self.plan_aggregate(aggregate, range),
+            Expr::Binary(binary) => self.plan_binary(binary, range),
+        }
+    }
+
+    fn plan_call(&self, call: &Call, range: EvalRange) -> Result<LogicalPlan> {
+        let name = call.func.name;
+        if let Some(matrix) = call
+            .args
+            .iter()
+            .find(|arg| arg.value_type() == ValueType::Matrix)
+        {
+            // Safe to unwrap since the argument is a range vector.
+            let (window, offset) = matrix_window(matrix).unwrap();
+            let matrix = self.plan(matrix, range)?;
+            let mut labels = plan_labels(&matrix);
+            if name != "last_over_time" {
+                labels.remove(METRIC_NAME);
+            }
+            let params = call
+                .args
+                .iter()
+                .filter(|arg| arg.value_type() == ValueType::Scalar)
+                .map(|arg| self.plan(arg, range))
+                .collect::<Result<_>>()?;
+            return RangeFunction::try_new(
+                matrix,
+                params,
+                call.clone(),
+                window,
+                offset,
+                range,
+                labels,
+            )
+            .map(RangeFunction::into_plan);
+        }
+
+        let mut inputs = Vec::with_capacity(call.args.len());
+        for arg in &call.args {
+            let value_type = arg.value_type();
+            if value_type != ValueType::String {
+                inputs.push((self.plan(arg, range)?, value_type));
+            }
+        }
+        // Labels of the instant vector argument.
+        let vector_labels = inputs
+            .iter()
+            .find(|(_, value_type)| *value_type == ValueType::Vector)
+            .map(|(input, _)| plan_labels(input))
+            .unwrap_or_default();
+        let labels = match name {
+            "absent" => absent_labels(&call.args[0]).into_keys().collect(),
+            "label_replace" | "label_join" => {
+                let mut labels = vector_labels;
+                labels.insert(string_literal(&call.args[1])?);
+                labels
+            }
+            "sort" | "sort_desc" => vector_labels,
+            "scalar" | "time" | "vector" => BTreeSet::new(),
+            _ => {
+                let mut labels = vector_labels;
+                labels.remove(METRIC_NAME);
+                if name == "histogram_quantile" {
+                    labels.remove("le");
+                }
+                labels
+            }
+        };
+        StepEval::try_new(StepOp::Call(call.clone()), inputs, range, labels)
+            .map(StepEval::into_plan)
+    }
+
+    fn plan_aggregate(&self, aggregate: &AggregateExpr, range: EvalRange) -> Result<LogicalPlan> {
+        let input = self.plan(&aggregate.expr, range)?;
+        let input_labels = plan_labels(&input);
+        let mut labels = match aggregate.op {
+            AggregateOp::Topk | AggregateOp::Bottomk => input_labels,
+            _ => match &aggregate.grouping {
+                Grouping::By(names) => input_labels
+                    .into_iter()
+                    .filter(|label| names.contains(label))
+                    .collect(),
+                Grouping::Without(names) => input_labels
+                    .into_iter()
+                    .filter(|label| label != METRIC_NAME && !names.contains(label))
+                    .collect(),
+            },
+        };
+
+        let mut inputs = vec![(input, ValueType::Vector)];
+        if let Some(param) = &aggregate.param {
+            match param.value_type() {
+                ValueType::String => {
+                    labels.insert(string_literal(param)?);
+                }
+                value_type => inputs.push((self.plan(param, range)?, value_type)),
+            }
+        }
+        StepEval::try_new(StepOp::Aggregate(aggregate.clone()), inputs, range, labels)
+            .map(StepEval::into_plan)
+    }
+
+    fn plan_binary(&self, binary: &BinaryExpr, range: EvalRange) -> Result<LogicalPlan> {
+        let lhs_type = binary.lhs.value_type();
+        let rhs_type = binary.rhs.value_type();
+        let lhs = self.plan(&binary.lhs, range)?;
+        let rhs = self.plan(&binary.rhs, range)?;
+        let drops_name = drops_metric_name(binary.op, binary.return_bool);
+
+        let labels = match (lhs_type, rhs_type) {
+            (ValueType::Scalar, ValueType::Scalar) => BTreeSet::new(),
+            (ValueType::Vector, ValueType::Scalar) | (ValueType::Scalar, ValueType::Vector) => {
+                let vector = if lhs_type == ValueType::Vector {
+                    &lhs
+                } else {
+                    &rhs
+                };
+                let mut labels = plan_labels(vector);
+                if drops_name {
+                    labels.remove(METRIC_NAME);
+                }
+                labels
+            }
+            _ => {
+                let default_matching = VectorMatching::default();
+                let matching = binary.matching.as_ref().unwrap_or(&default_matching);
+                match binary.op {
+                    BinaryOp::And | BinaryOp::Unless => plan_labels(&lhs),
+                    BinaryOp::Or => {
+                        let mut labels = plan_labels(&lhs);
+                        labels.extend(plan_labels(&rhs));
+                        labels
+                    }
+                    _ => vector_matching_labels(&lhs, &rhs, matching, drops_name),
+                }
+            }
+        };
+        let inputs = vec![(lhs, lhs_type), (rhs, rhs_type)];
+        StepEval::try_new(StepOp::Binary(binary.clone()), inputs, range, labels)
+            .map(StepEval::into_plan)
+    }
+
+    /// Plans the selector reading samples in the window of `window` ms before each step. The
+    /// selector outputs the latest sample in `lookback_delta` at each step if present, or the
+    /// raw samples otherwise.
+    fn plan_selector(
+        &self,
+        selector: &VectorSelector,
+        lookback_delta: Option<i64>,
+        window: i64,
+        range: EvalRange,
+    ) -> Result<LogicalPlan> {
+        let offset = selector.offset_ms;
+        let start = range.start - offset - window;
+        let end = range.end - offset;
+
+        let mut inputs = Vec::new();
+        for (metric, table) in self.matched_tables(&selector.matchers)? {
+            let (labels, builder) =
+                self.filtered_scan(&metric, table, &selector.matchers, start, end)?;
+            let mut columns: Vec<_> = labels
+                .iter()
+                .map(|label| column(label).alias(label))
+                .collect();
+            columns.push(lit(metric.as_str()).alias(METRIC_NAME));
+            columns.push(column(TIMESTAMP_COLUMN_NAME).alias(TIMESTAMP_COLUMN_NAME));
+            columns.push(column(VALUE_COLUMN_NAME).alias(VALUE_COLUMN_NAME));
+            let mut sort_exprs: Vec<_> = labels
+                .iter()
+                .map(|label| column(label).sort(true, true))
+                .collect();
+            sort_exprs.push(column(TIMESTAMP_COLUMN_NAME).sort(true, true));
+
+            // Reads one more sample to tell whether the selector exceeds the limit.
+            let input = builder
+                .limit(self.max_samples.saturating_add(1))
+                .and_then(|builder| builder.project(columns))
+                .and_then(|builder| builder.sort(sort_exprs))
+                .and_then(|builder| builder.build())
+                .context(error::BuildPlanSnafu)?;
+            inputs.push(input);
+        }
+        SeriesSelect::try_new(inputs, lookback_delta, offset, range, self.max_samples)
+            .map(SeriesSelect::into_plan)
+    }
+
+    /// Plans the series matching `matchers` in `[start, end]`, each plan outputs the distinct
+    /// label values of the series in a metric.
+    pub fn plan_series(
+        &self,
+        matchers: &[Matcher],
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<(String, LogicalPlan)>> {
+        let mut plans = Vec::new();
+        for (metric, table) in self.matched_tables(matchers)? {
+            let (labels, builder) = self.filtered_scan(&metric, table, matchers, start, end)?;
+            plans.push((metric, distinct_labels(builder, &labels)?));
+        }
+        Ok(plans)
+    }
+
+    /// Plans the values of label `name` in `[start, end]`, each plan outputs the distinct values
+    /// of the label in a metric having the label.
+    pub fn plan_label_values(&self, name: &str, start: i64, end: i64) -> Result<Vec<LogicalPlan>> {
+        let matchers = [Matcher::new(MatchOp::NotEqual, name, "")?];
+        let labels = [name.to_string()];
+        let mut plans = Vec::new();
+        for metric in self.storage.metric_names()? {
+            let table = match self.storage.table(&metric)? {
+                Some(table) if table.schema().contains_column(name) => table,
+                _ => continue,
+            };
+            let (_, builder) = self.filtered_scan(&metric, table, &matchers, start, end)?;
+            plans.push(distinct_labels(builder, &labels)?);
+        }
+        Ok(plans)
+    }
+
+    /// Returns the tables of metrics matching the name matchers in `matchers`.
+    fn matched_tables(&self, matchers: &[Matcher]) -> Result<Vec<(String, TableRef)>> {
+        let name_matchers: Vec<_> = matchers.iter().filter(|m| m.name == METRIC_NAME).collect();
+        let metrics = match name_matchers.iter().find(|m| m.op == MatchOp::Equal) {
+            Some(matcher) => vec![matcher.value.clone()],
+            None => self.storage.metric_names()?,
+        };
+
+        let mut tables = Vec::new();
+        for metric in metrics {
+            if !name_matchers.iter().all(|m| m.matches(&metric)) {
+                continue;
+            }
+            if let Some(table) = self.storage.table(&metric)? {
+                tables.push((metric, table));
+            }
+        }
+        Ok(tables)
+    }
+
+    /// Scans the rows of the metric `table` in `[start, end]` matching the label matchers in
+    /// `matchers`, returns the label columns of the table and the scan.
+    fn filtered_scan(
+        &self,
+        metric: &str,
+        table: TableRef,
+        matchers: &[Matcher],
+        start: i64,
+        end: i64,
+    ) -> Result<(Vec<String>, LogicalPlanBuilder)> {
+        let schema = table.schema();
+        let labels = label_columns(metric, &schema)?;
+
+        let timestamp = |ms| lit(ScalarValue::TimestampMillisecond(Some(ms), None));
+        let mut conditions = vec![
+            column(TIMESTAMP_COLUMN_NAME).gt_eq(timestamp(start)),
+            column(TIMESTAMP_COLUMN_NAME).lt_eq(timestamp(end)),
+        ];
+        conditions.extend(
+            matchers
+                .iter()
+                .filter(|m| m.name != METRIC_NAME)
+                .map(|m| matcher_to_expr(&schema, m)),
+        );
+        // Safe to unwrap since there are always conditions on the timestamp column.
+        let condition = conditions.into_iter().reduce(DfExpr::and).unwrap();
+
+        let provider = Arc::new(DfTableProviderAdapter::new(table));
+        let builder = LogicalPlanBuilder::scan(metric, provider, None)
+            .and_then(|builder| builder.filter(condition))
+            .context(error::BuildPlanSnafu)?;
+        Ok((labels, builder))
+    }
+}
+
+fn column(name: &str) -> DfExpr {
+    DfExpr::Column(Column::from_name(name))
+}
+
+fn plan_labels(plan: &LogicalPlan) -> BTreeSet<String> {
+    label_names(plan.schema())
+}
+
+/// Returns the label columns of the metric table, and checks the types of all columns.
+fn label_columns(metric: &str, schema: &SchemaRef) -> Result<Vec<String>> {
+    let mut labels = Vec::new();
+    for column in schema.column_schemas() {
+        let expected_type = match column.name.as_str() {
+            TIMESTAMP_COLUMN_NAME => ConcreteDataType::timestamp_millis_datatype(),
+            VALUE_COLUMN_NAME => ConcreteDataType::float64_datatype(),
+            _ => {
+                labels.push(column.name.clone());
+                ConcreteDataType::string_datatype()
+            }
+        };
+        if column.data_type != expected_type {
+            return error::InvalidMetricTableSnafu {
+                table: metric,
+                msg: format!(
+                    "column {} is of type {:?}, expect {:?}",
+                    column.name, column.data_type, expected_type
+                ),
+            }
+            .fail();
+        }
+    }
+    for name in [TIMESTAMP_COLUMN_NAME, VALUE_COLUMN_NAME] {
+        if !schema.contains_column(name) {
+            return error::InvalidMetricTableSnafu {
+                table: metric,
+                msg: format!("missing column {}", name),
+            }
+            .fail();
+        }
+    }
+    Ok(labels)
+}
+
+/// Converts the label matcher to a filter, a missing label is stored as null and matches as an
+/// empty value.
+fn matcher_to_expr(schema: &SchemaRef, matcher: &Matcher) -> DfExpr {
+    let matches_empty = matcher.matches("");
+    if !schema.contains_column(&matcher.name) {
+        return lit(matches_empty);
+    }
+
+    let label = column(&matcher.name);
+    let value = matcher.value.as_str();
+    let regex = || lit(format!("^(?:{})$", value));
+    let expr = match matcher.op {
+        MatchOp::Equal => label.clone().eq(lit(value)),
+        MatchOp::NotEqual => label.clone().not_eq(lit(value)),
+        MatchOp::Re => binary_expr(label.clone(), Operator::RegexMatch, regex()),
+        MatchOp::NotRe => binary_expr(label.clone(), Operator::RegexNotMatch, regex()),
+    };
+    if matches_empty {
+        label.is_null().or(expr)
+    } else {
+        expr
+    }
+}
+
+/// Outputs the distinct values of `labels`, or a row if there is any row when `labels` is empty.
+fn distinct_labels(builder: LogicalPlanBuilder, labels: &[String]) -> Result<LogicalPlan> {
+    let builder = if labels.is_empty() {
+        builder
+            .limit(1)
+            .and_then(|builder| builder.project(vec![column(TIMESTAMP_COLUMN_NAME)]))
+    } else {
+        let group_exprs: Vec<_> = labels.iter().map(|label| column(label)).collect();
+        // Aliases the output columns, which are named after the qualified columns otherwise.
+        let columns: Vec<_> = labels
+            .iter()
+            .map(|label| column(label).alias(label))
+            .collect();
+        builder
+            .aggregate(group_exprs, Vec::<DfExpr>::new())
+            .and_then(|builder| builder.project(columns))
+    };
+    builder
+        .and_then(|builder| builder.build())
+        .context(error::BuildPlanSnafu)
+}
+
+/// Labels of the result of a binary operation between vectors with `matching`.
+fn vector_matching_labels(
+    lhs: &LogicalPlan,
+    rhs: &LogicalPlan,
+    matching: &VectorMatching,
+    drops_name: bool,
+) -> BTreeSet<String> {
+    let (many, one) = if matching.card == Cardinality::OneToMany {
+        (rhs, lhs)
+    } else {
+        (lhs, rhs)
+    };
+    let mut labels = plan_labels(many);
+    if drops_name {
+        labels.remove(METRIC_NAME);
+    }
+    if matching.card == Cardinality::OneToOne {
+        labels.retain(|label| matching.labels.contains(label) == matching.on);
+    }
+    let one_labels = plan_labels(one);
+    labels.extend(
+        matching
+            .include
+            .iter()
+            .filter(|label| one_labels.contains(*label))
+            .cloned(),
+    );
+    labels
+}
+
+/// Returns the range and offset of the windows of a range vector expression.
+pub(crate) fn matrix_window(expr: &Expr) -> Option<(i64, i64)> {
+    match expr {
+        Expr::MatrixSelector(matrix) => Some((matrix.range_ms, matrix.selector.offset_ms)),
+        Expr::Subquery(subquery) => Some((subquery.range_ms, subquery.offset_ms)),
+        Expr::Paren(expr) => matrix_window(expr),
+        _ => None,
+    }
+}
+
+/// Returns the steps a subquery is evaluated at, which are aligned to multiples of its step.
+fn subquery_range(subquery: &SubqueryExpr, range: EvalRange) -> EvalRange {
+    let step = subquery.step_ms.unwrap_or(range.step);
+    let end = range.end - subquery.offset_ms;
+    let start = range.start - subquery.offset_ms - subquery.range_ms;
+    let start = start.div_euclid(step) * step + if start.rem_euclid(step) == 0 { 0 } else { step };
+    EvalRange { start, end, step }
+}
+
+/// Returns the value of a string literal.
+pub(crate) fn string_literal(expr: &Expr) -> Result<String> {
+    match expr {
+        Expr::StringLiteral(s) => Ok(s.clone()),
+        Expr::Paren(expr) => string_literal(expr),
+        _ => error::EvalSnafu {
+            msg: format!("expected string literal, got {}", expr.value_type().name()),
+        }
+        .fail(),
+    }
+}
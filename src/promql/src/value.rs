@@ -0,0 +1,96 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Values of PromQL queries.
+
+use std::collections::BTreeMap;
+
+/// Labels of a series, including the metric name label.
+pub type Labels = BTreeMap<String, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// Timestamp in milliseconds.
+    pub timestamp: i64,
+    pub value: f64,
+}
+
+impl Sample {
+    pub fn new(timestamp: i64, value: f64) -> Self {
+        Self { timestamp, value }
+    }
+}
+
+/// A series with its samples sorted by timestamp.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Series {
+    pub labels: Labels,
+    pub samples: Vec<Sample>,
+}
+
+/// A sample of an instant vector.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorElement {
+    pub labels: Labels,
+    pub sample: Sample,
+}
+
+/// Result of a PromQL query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Scalar(Sample),
+    /// A string and the evaluation timestamp.
+    String(i64, String),
+    Vector(Vec<VectorElement>),
+    Matrix(Vec<Series>),
+}
+
+impl Value {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Scalar(_) => "scalar",
+            Value::String(..) => "string",
+            Value::Vector(_) => "vector",
+            Value::Matrix(_) => "matrix",
+        }
+    }
+}
+
+/// Formats a sample value the way Prometheus does.
+pub fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_value() {
+        assert_eq!("1", format_value(1.0));
+        assert_eq!("-0.25", format_value(-0.25));
+        assert_eq!("100000000000000000000", format_value(1e20));
+        assert_eq!("NaN", format_value(f64::NAN));
+        assert_eq!("+Inf", format_value(f64::INFINITY));
+        assert_eq!("-Inf", format_value(f64::NEG_INFINITY));
+    }
+}
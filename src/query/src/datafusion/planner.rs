@@ -21,6 +21,7 @@ use datafusion::physical_plan::udaf::AggregateUDF;
 use datafusion::physical_plan::udf::ScalarUDF;
 use datafusion::sql::planner::{ContextProvider, SqlToRel};
 use datatypes::arrow::datatypes::DataType;
+use session::context::QueryContextRef;
 use snafu::ResultExt;
 use sql::statements::explain::Explain;
 use sql::statements::query::Query;
@@ -80,23 +81,30 @@ where
             Statement::ShowTables(_)
             | Statement::ShowDatabases(_)
             | Statement::ShowCreateTable(_)
+            | Statement::ShowColumns(_)
+            | Statement::ShowProcesslist(_)
             | Statement::DescribeTable(_)
             | Statement::CreateTable(_)
             | Statement::CreateDatabase(_)
             | Statement::Alter(_)
             | Statement::Insert(_)
-            | Statement::DropTable(_) => unreachable!(),
+            | Statement::Delete(_)
+            | Statement::DropTable(_)
+            | Statement::Use(_)
+            | Statement::Kill(_)
+            | Statement::Admin(_) => unreachable!(),
         }
     }
 }
 
 pub(crate) struct DfContextProviderAdapter {
     state: QueryEngineState,
+    query_ctx: QueryContextRef,
 }
 
 impl DfContextProviderAdapter {
-    pub(crate) fn new(state: QueryEngineState) -> Self {
-        Self { state }
+    pub(crate) fn new(state: QueryEngineState, query_ctx: QueryContextRef) -> Self {
+        Self { state, query_ctx }
     }
 }
 
@@ -104,6 +112,22 @@ impl DfContextProviderAdapter {
 ///                           manage UDFs, UDAFs, variables by ourself in future.
 impl ContextProvider for DfContextProviderAdapter {
     fn get_table_provider(&self, name: TableReference) -> Option<Arc<dyn TableProvider>> {
+        // Resolves the table reference against the catalog and schema of current session.
+        let catalog = self.query_ctx.current_catalog();
+        let schema = self.query_ctx.current_schema();
+        let name = match name {
+            TableReference::Bare { table } => TableReference::Full {
+                catalog: &catalog,
+                schema: &schema,
+                table,
+            },
+            TableReference::Partial { schema, table } => TableReference::Full {
+                catalog: &catalog,
+                schema,
+                table,
+            },
+            full @ TableReference::Full { .. } => full,
+        };
         self.state
             .df_context()
             .state
@@ -58,6 +58,36 @@ pub enum InnerError {
         #[snafu(backtrace)]
         source: common_recordbatch::error::Error,
     },
+
+    #[snafu(display("Failed to parse SQL, source: {}", source))]
+    ParseSql {
+        #[snafu(backtrace)]
+        source: sql::error::Error,
+    },
+
+    #[snafu(display("Failed to convert value to sql value, source: {}", source))]
+    ConvertSqlValue {
+        #[snafu(backtrace)]
+        source: sql::error::Error,
+    },
+
+    #[snafu(display("Failed to convert plan schema, source: {}", source))]
+    ConvertSchema {
+        #[snafu(backtrace)]
+        source: datatypes::error::Error,
+    },
+
+    #[snafu(display("Query is cancelled, source: {}", source))]
+    QueryCancelled {
+        #[snafu(backtrace)]
+        source: common_query::error::Error,
+    },
+
+    #[snafu(display("Unknown process id: {}", id))]
+    ProcessNotFound { id: u32, backtrace: Backtrace },
+
+    #[snafu(display("Not allowed to kill process {} of another user", id))]
+    KillProcessDenied { id: u32, backtrace: Backtrace },
 }
 
 impl ErrorExt for InnerError {
@@ -68,10 +98,14 @@ impl ErrorExt for InnerError {
             UnsupportedExpr { .. }
             | CatalogNotFound { .. }
             | SchemaNotFound { .. }
-            | TableNotFound { .. } => StatusCode::InvalidArguments,
+            | TableNotFound { .. }
+            | ProcessNotFound { .. } => StatusCode::InvalidArguments,
+            KillProcessDenied { .. } => StatusCode::AccessDenied,
             Catalog { source } => source.status_code(),
-            VectorComputation { source } => source.status_code(),
+            VectorComputation { source } | ConvertSchema { source } => source.status_code(),
             CreateRecordBatch { source } => source.status_code(),
+            ParseSql { source } | ConvertSqlValue { source } => source.status_code(),
+            QueryCancelled { source } => source.status_code(),
         }
     }
 
@@ -18,14 +18,14 @@ use common_query::physical_plan::PhysicalPlan;
 use common_recordbatch::SendableRecordBatchStream;
 
 use crate::error::Result;
-use crate::query_engine::QueryContext;
+use crate::query_engine::QueryEngineContext;
 
 /// Executor to run [ExecutionPlan].
 #[async_trait::async_trait]
 pub trait QueryExecutor {
     async fn execute_stream(
         &self,
-        ctx: &QueryContext,
+        ctx: &QueryEngineContext,
         plan: &Arc<dyn PhysicalPlan>,
     ) -> Result<SendableRecordBatchStream>;
 }
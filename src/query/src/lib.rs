@@ -23,7 +23,10 @@ pub mod physical_optimizer;
 pub mod physical_planner;
 pub mod plan;
 pub mod planner;
+pub mod process;
 pub mod query_engine;
 pub mod sql;
 
-pub use crate::query_engine::{QueryContext, QueryEngine, QueryEngineFactory, QueryEngineRef};
+pub use crate::query_engine::{
+    QueryEngine, QueryEngineContext, QueryEngineFactory, QueryEngineRef,
+};
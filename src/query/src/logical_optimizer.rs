@@ -14,12 +14,12 @@
 
 use crate::error::Result;
 use crate::plan::LogicalPlan;
-use crate::query_engine::QueryContext;
+use crate::query_engine::QueryEngineContext;
 
 pub trait LogicalOptimizer {
     fn optimize_logical_plan(
         &self,
-        ctx: &mut QueryContext,
+        ctx: &mut QueryEngineContext,
         plan: &LogicalPlan,
     ) -> Result<LogicalPlan>;
 }
@@ -17,7 +17,7 @@ use std::sync::Arc;
 
 use common_time::timestamp::{TimeUnit, Timestamp};
 use datafusion::execution::context::ExecutionProps;
-use datafusion::logical_plan::plan::Filter;
+use datafusion::logical_plan::plan::{Filter, Limit, Sort};
 use datafusion::logical_plan::{
     Expr, ExprRewritable, ExprRewriter, ExprSchemable, LogicalPlan, Operator, TableScan,
 };
@@ -27,6 +27,7 @@ use datafusion_common::{DFSchemaRef, DataFusionError, Result, ScalarValue};
 use datatypes::arrow::compute;
 use datatypes::arrow::compute::cast::CastOptions;
 use datatypes::arrow::datatypes::DataType;
+use table::table::adapter::DfTableProviderAdapter;
 
 /// TypeConversionRule converts some literal values in logical plan to other types according
 /// to data type of corresponding columns.
@@ -279,6 +280,103 @@ fn string_to_timestamp_ms(string: &str) -> Result<ScalarValue> {
     ))
 }
 
+/// ScanLatestRule scans the table for the latest rows in plans like
+/// `ORDER BY <time index> DESC LIMIT n`, so the table could stop reading once the latest `n`
+/// rows are read, see [Table::scan_latest()](table::table::Table::scan_latest).
+///
+/// The sort and the limit are kept, since the table may not return rows in order.
+pub struct ScanLatestRule;
+
+impl OptimizerRule for ScanLatestRule {
+    fn optimize(
+        &self,
+        plan: &LogicalPlan,
+        execution_props: &ExecutionProps,
+    ) -> Result<LogicalPlan> {
+        if let LogicalPlan::Limit(Limit { n, input }) = plan {
+            if let LogicalPlan::Sort(Sort { expr, input }) = input.as_ref() {
+                if let Some(input) = scan_latest_for_sort(expr, input, *n) {
+                    return Ok(LogicalPlan::Limit(Limit {
+                        n: *n,
+                        input: Arc::new(LogicalPlan::Sort(Sort {
+                            expr: expr.clone(),
+                            input: Arc::new(input),
+                        })),
+                    }));
+                }
+            }
+        }
+
+        let new_inputs = plan
+            .inputs()
+            .iter()
+            .map(|plan| self.optimize(plan, execution_props))
+            .collect::<Result<Vec<_>>>()?;
+        utils::from_plan(plan, &plan.expressions(), &new_inputs)
+    }
+
+    fn name(&self) -> &str {
+        "ScanLatestRule"
+    }
+}
+
+/// Rewrites the input `plan` of the sort by `sort_exprs` to scan the latest `limit` rows of
+/// the table, returns `None` if the sort is not by the time index in descending order.
+fn scan_latest_for_sort(
+    sort_exprs: &[Expr],
+    plan: &LogicalPlan,
+    limit: usize,
+) -> Option<LogicalPlan> {
+    match sort_exprs {
+        [Expr::Sort {
+            expr, asc: false, ..
+        }] => match expr.as_ref() {
+            Expr::Column(column) => scan_latest_by_column(&column.name, plan, limit),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn scan_latest_by_column(
+    column_name: &str,
+    plan: &LogicalPlan,
+    limit: usize,
+) -> Option<LogicalPlan> {
+    match plan {
+        // Projections of columns keep the rows and the names of the columns.
+        LogicalPlan::Projection(projection)
+            if projection
+                .expr
+                .iter()
+                .all(|expr| matches!(expr, Expr::Column(_))) =>
+        {
+            let input = scan_latest_by_column(column_name, &projection.input, limit)?;
+            utils::from_plan(plan, &plan.expressions(), &[input]).ok()
+        }
+        LogicalPlan::TableScan(scan) => {
+            let table = scan
+                .source
+                .as_any()
+                .downcast_ref::<DfTableProviderAdapter>()?
+                .table();
+            let schema = table.schema();
+            let ts_column = schema.timestamp_column()?;
+            if ts_column.name != column_name {
+                return None;
+            }
+
+            Some(LogicalPlan::TableScan(TableScan {
+                source: Arc::new(DfTableProviderAdapter::with_latest_first(table)),
+                limit: Some(limit),
+                ..scan.clone()
+            }))
+        }
+        // Other plans (e.g. filters not pushed down to the table) may change the rows to sort.
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
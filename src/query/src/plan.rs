@@ -13,8 +13,14 @@
 // limitations under the License.
 
 use std::fmt::Debug;
+use std::sync::Arc;
 
 use datafusion::logical_plan::LogicalPlan as DfLogicalPlan;
+use datatypes::arrow::datatypes::Schema as ArrowSchema;
+use datatypes::schema::{Schema, SchemaRef};
+use snafu::ResultExt;
+
+use crate::error::{ConvertSchemaSnafu, Result};
 
 /// A LogicalPlan represents the different types of relational
 /// operators (such as Projection, Filter, etc) and can be created by
@@ -28,3 +34,16 @@ use datafusion::logical_plan::LogicalPlan as DfLogicalPlan;
 pub enum LogicalPlan {
     DfPlan(DfLogicalPlan),
 }
+
+impl LogicalPlan {
+    /// Returns the schema of the plan's output.
+    pub fn schema(&self) -> Result<SchemaRef> {
+        match self {
+            LogicalPlan::DfPlan(plan) => {
+                let arrow_schema = ArrowSchema::from(plan.schema().as_ref().clone());
+                let schema = Schema::try_from(arrow_schema).context(ConvertSchemaSnafu)?;
+                Ok(Arc::new(schema))
+            }
+        }
+    }
+}
@@ -0,0 +1,348 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Process list of the running queries.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+pub use catalog::tables::ProcessInfo;
+use catalog::tables::ProcessListProvider;
+use common_error::ext::BoxedError;
+use common_query::cancellation::{CancelReason, CancellationToken};
+use common_query::Output;
+use common_recordbatch::error::ExternalSnafu;
+use common_recordbatch::{RecordBatch, RecordBatchStream, SendableRecordBatchStream};
+use datatypes::schema::SchemaRef;
+use futures::Stream;
+use session::context::QueryContextRef;
+use snafu::{ensure, ResultExt};
+use tokio::task::JoinHandle;
+
+use crate::error::{KillProcessDeniedSnafu, ProcessNotFoundSnafu, QueryCancelledSnafu, Result};
+
+pub type ProcessManagerRef = Arc<ProcessManager>;
+
+#[derive(Debug)]
+struct Process {
+    info: ProcessInfo,
+    token: CancellationToken,
+}
+
+/// Tracks the running queries, so they could be listed and killed.
+#[derive(Debug, Default)]
+pub struct ProcessManager {
+    next_key: AtomicU64,
+    processes: RwLock<HashMap<u64, Process>>,
+}
+
+impl ProcessManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the `query` issued in `query_ctx`, the query is cancelled once it runs
+    /// longer than `timeout`. The query is deregistered when the returned guard is dropped.
+    pub fn register(
+        self: &Arc<Self>,
+        query: &str,
+        query_ctx: &QueryContextRef,
+        timeout: Option<Duration>,
+    ) -> ProcessGuard {
+        let key = self.next_key.fetch_add(1, Ordering::Relaxed);
+        let token = CancellationToken::new();
+        let process = Process {
+            info: ProcessInfo {
+                id: query_ctx.connection_id(),
+                user: query_ctx.user(),
+                client_addr: query_ctx.client_addr().map(ToString::to_string),
+                catalog: query_ctx.current_catalog(),
+                schema: query_ctx.current_schema(),
+                query: query.to_string(),
+                start: Instant::now(),
+            },
+            token: token.clone(),
+        };
+        self.processes.write().unwrap().insert(key, process);
+
+        let timer = timeout.map(|timeout| {
+            let token = token.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(timeout).await;
+                token.cancel(CancelReason::Timeout);
+            })
+        });
+
+        ProcessGuard {
+            manager: self.clone(),
+            key,
+            token,
+            timer,
+        }
+    }
+
+    /// Returns the running queries, ordered by id.
+    pub fn list(&self) -> Vec<ProcessInfo> {
+        let mut processes = self
+            .processes
+            .read()
+            .unwrap()
+            .values()
+            .map(|process| process.info.clone())
+            .collect::<Vec<_>>();
+        processes.sort_by_key(|info| (info.id, info.start));
+        processes
+    }
+
+    /// Kills the queries running on connection `id` for the caller issuing queries in
+    /// `query_ctx`, who is only allowed to kill the queries of its own user.
+    pub fn kill(&self, id: u32, query_ctx: &QueryContextRef) -> Result<()> {
+        let user = query_ctx.user();
+        self.cancel(id, |info| info.user == user)
+    }
+
+    /// Kills the queries running on connection `id` without checking the user, the caller
+    /// must have authorized the request by other means, e.g. the secret key of a PostgreSQL
+    /// cancel request.
+    pub fn kill_unchecked(&self, id: u32) -> Result<()> {
+        self.cancel(id, |_| true)
+    }
+
+    fn cancel(&self, id: u32, permitted: impl Fn(&ProcessInfo) -> bool) -> Result<()> {
+        let processes = self.processes.read().unwrap();
+        let targets = processes
+            .values()
+            .filter(|process| process.info.id == id)
+            .collect::<Vec<_>>();
+        ensure!(!targets.is_empty(), ProcessNotFoundSnafu { id });
+        ensure!(
+            targets.iter().all(|process| permitted(&process.info)),
+            KillProcessDeniedSnafu { id }
+        );
+        for process in targets {
+            process.token.cancel(CancelReason::Killed);
+        }
+        Ok(())
+    }
+
+    fn deregister(&self, key: u64) {
+        self.processes.write().unwrap().remove(&key);
+    }
+}
+
+impl ProcessListProvider for ProcessManager {
+    fn processes(&self) -> Vec<ProcessInfo> {
+        self.list()
+    }
+}
+
+/// Guard of a registered query, cancels the remaining work of the query (e.g. after the
+/// client disconnects) and deregisters it on drop.
+pub struct ProcessGuard {
+    manager: ProcessManagerRef,
+    key: u64,
+    token: CancellationToken,
+    timer: Option<JoinHandle<()>>,
+}
+
+impl ProcessGuard {
+    #[inline]
+    pub fn token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Runs `fut` with the token of the query bound to the task, returns early if the query
+    /// is cancelled.
+    pub async fn run<F: Future>(&self, fut: F) -> Result<F::Output> {
+        tokio::select! {
+            output = self.token.scope(fut) => Ok(output),
+            err = self.token.cancelled() => {
+                Err(err).context(QueryCancelledSnafu).map_err(Into::into)
+            }
+        }
+    }
+
+    /// Keeps the query registered until the `output` is consumed.
+    pub fn track(self, output: Output) -> Output {
+        match output {
+            Output::Stream(stream) => Output::Stream(Box::pin(ProcessStream {
+                stream,
+                guard: self,
+            })),
+            output => output,
+        }
+    }
+}
+
+impl Drop for ProcessGuard {
+    fn drop(&mut self) {
+        if let Some(timer) = &self.timer {
+            timer.abort();
+        }
+        self.token.cancel(CancelReason::Killed);
+        self.manager.deregister(self.key);
+    }
+}
+
+/// Stream of a registered query, stops with an error once the query is cancelled.
+struct ProcessStream {
+    stream: SendableRecordBatchStream,
+    guard: ProcessGuard,
+}
+
+impl RecordBatchStream for ProcessStream {
+    fn schema(&self) -> SchemaRef {
+        self.stream.schema()
+    }
+}
+
+impl Stream for ProcessStream {
+    type Item = common_recordbatch::error::Result<RecordBatch>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let checked = self
+            .guard
+            .token
+            .check()
+            .map_err(BoxedError::new)
+            .context(ExternalSnafu);
+        if let Err(e) = checked {
+            return Poll::Ready(Some(Err(e.into())));
+        }
+        Pin::new(&mut self.stream).poll_next(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_error::prelude::*;
+    use common_recordbatch::RecordBatches;
+    use futures::StreamExt;
+    use session::context::QueryContext;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_register_and_kill() {
+        let manager = Arc::new(ProcessManager::new());
+        let query_ctx = Arc::new(QueryContext::new().with_client_addr("127.0.0.1:4002"));
+        query_ctx.set_user("greptime");
+        let id = query_ctx.connection_id();
+
+        let guard = manager.register("SELECT 1", &query_ctx, None);
+        let processes = manager.list();
+        assert_eq!(1, processes.len());
+        assert_eq!(id, processes[0].id);
+        assert_eq!("SELECT 1", processes[0].query);
+        assert_eq!(query_ctx.current_schema(), processes[0].schema);
+        assert_eq!(Some("greptime".to_string()), processes[0].user);
+        assert_eq!(Some("127.0.0.1:4002".to_string()), processes[0].client_addr);
+        assert_eq!(1, manager.processes().len());
+
+        assert_eq!(
+            StatusCode::InvalidArguments,
+            manager.kill(id + 1, &query_ctx).unwrap_err().status_code()
+        );
+        // Another connection of the same user.
+        let caller_ctx = QueryContext::arc();
+        caller_ctx.set_user("greptime");
+        manager.kill(id, &caller_ctx).unwrap();
+        assert!(guard.token().is_cancelled());
+        let err = guard
+            .run(futures::future::pending::<()>())
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::Cancelled, err.status_code());
+
+        let token = guard.token().clone();
+        drop(guard);
+        assert!(manager.list().is_empty());
+        assert!(token.is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_kill_denied() {
+        let manager = Arc::new(ProcessManager::new());
+        let query_ctx = QueryContext::arc();
+        query_ctx.set_user("greptime");
+        let id = query_ctx.connection_id();
+        let guard = manager.register("SELECT 1", &query_ctx, None);
+
+        let caller_ctx = QueryContext::arc();
+        caller_ctx.set_user("other");
+        assert_eq!(
+            StatusCode::AccessDenied,
+            manager.kill(id, &caller_ctx).unwrap_err().status_code()
+        );
+        // Anonymous callers can't kill queries of authenticated users either.
+        assert_eq!(
+            StatusCode::AccessDenied,
+            manager
+                .kill(id, &QueryContext::arc())
+                .unwrap_err()
+                .status_code()
+        );
+        assert!(!guard.token().is_cancelled());
+
+        manager.kill_unchecked(id).unwrap();
+        assert!(guard.token().is_cancelled());
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        let manager = Arc::new(ProcessManager::new());
+        let query_ctx = QueryContext::arc();
+
+        let guard = manager.register("SELECT 1", &query_ctx, Some(Duration::from_millis(10)));
+        let err = guard
+            .run(futures::future::pending::<()>())
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::DeadlineExceeded, err.status_code());
+
+        let guard = manager.register("SELECT 1", &query_ctx, Some(Duration::from_secs(10)));
+        let output = guard
+            .run(async { CancellationToken::current().is_cancelled() })
+            .await
+            .unwrap();
+        assert!(!output);
+    }
+
+    #[tokio::test]
+    async fn test_track_stream() {
+        let manager = Arc::new(ProcessManager::new());
+        let query_ctx = QueryContext::arc();
+
+        let guard = manager.register("SELECT 1", &query_ctx, None);
+        let token = guard.token().clone();
+        let output = guard.track(Output::Stream(RecordBatches::empty().as_stream()));
+        assert_eq!(1, manager.list().len());
+
+        let mut stream = match output {
+            Output::Stream(stream) => stream,
+            _ => unreachable!(),
+        };
+        token.cancel(CancelReason::Killed);
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert_eq!(StatusCode::Cancelled, err.status_code());
+
+        drop(stream);
+        assert!(manager.list().is_empty());
+    }
+}
@@ -16,11 +16,15 @@ use std::collections::HashMap;
 use std::fmt;
 use std::sync::{Arc, RwLock};
 
+use async_trait::async_trait;
 use catalog::CatalogListRef;
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_function::scalars::aggregate::AggregateFunctionMetaRef;
 use common_query::physical_plan::RuntimeEnv;
 use common_query::prelude::ScalarUdf;
+use datafusion::error::Result as DfResult;
+use datafusion::execution::context::{ExecutionContextState, QueryPlanner};
+use datafusion::logical_plan::LogicalPlan as DfLogicalPlan;
 use datafusion::optimizer::common_subexpr_eliminate::CommonSubexprEliminate;
 use datafusion::optimizer::eliminate_limit::EliminateLimit;
 use datafusion::optimizer::filter_push_down::FilterPushDown;
@@ -28,10 +32,13 @@ use datafusion::optimizer::limit_push_down::LimitPushDown;
 use datafusion::optimizer::projection_push_down::ProjectionPushDown;
 use datafusion::optimizer::single_distinct_to_groupby::SingleDistinctToGroupBy;
 use datafusion::optimizer::to_approx_perc::ToApproxPerc;
+use datafusion::physical_plan::planner::DefaultPhysicalPlanner;
+use datafusion::physical_plan::{ExecutionPlan, PhysicalPlanner};
 use datafusion::prelude::{ExecutionConfig, ExecutionContext};
+use promql::extension_plan::PromExtensionPlanner;
 
 use crate::datafusion::DfCatalogListAdapter;
-use crate::optimizer::TypeConversionRule;
+use crate::optimizer::{ScanLatestRule, TypeConversionRule};
 
 /// Query engine global state
 // TODO(yingwen): This QueryEngineState still relies on datafusion, maybe we can define a trait for it,
@@ -64,9 +71,12 @@ impl QueryEngineState {
                 Arc::new(ProjectionPushDown::new()),
                 Arc::new(FilterPushDown::new()),
                 Arc::new(LimitPushDown::new()),
+                // Runs after filters are pushed down to tables.
+                Arc::new(ScanLatestRule {}),
                 Arc::new(SingleDistinctToGroupBy::new()),
                 Arc::new(ToApproxPerc::new()),
-            ]);
+            ])
+            .with_query_planner(Arc::new(DfQueryPlanner::new()));
 
         let df_context = ExecutionContext::with_config(config);
 
@@ -122,3 +132,31 @@ impl QueryEngineState {
         self.df_context.runtime_env()
     }
 }
+
+/// Physical planner of the query engine, which also plans the PromQL plan nodes.
+struct DfQueryPlanner {
+    physical_planner: DefaultPhysicalPlanner,
+}
+
+impl DfQueryPlanner {
+    fn new() -> Self {
+        Self {
+            physical_planner: DefaultPhysicalPlanner::with_extension_planners(vec![Arc::new(
+                PromExtensionPlanner,
+            )]),
+        }
+    }
+}
+
+#[async_trait]
+impl QueryPlanner for DfQueryPlanner {
+    async fn create_physical_plan(
+        &self,
+        logical_plan: &DfLogicalPlan,
+        ctx_state: &ExecutionContextState,
+    ) -> DfResult<Arc<dyn ExecutionPlan>> {
+        self.physical_planner
+            .create_physical_plan(logical_plan, ctx_state)
+            .await
+    }
+}
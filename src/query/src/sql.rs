@@ -15,20 +15,30 @@
 use std::sync::Arc;
 
 use catalog::CatalogManagerRef;
-use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
 use common_recordbatch::RecordBatches;
 use datatypes::prelude::*;
-use datatypes::schema::{ColumnSchema, Schema};
-use datatypes::vectors::{Helper, StringVector};
+use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, Schema};
+use datatypes::vectors::{Helper, StringVector, UInt32Vector, UInt64Vector};
 use once_cell::sync::Lazy;
+use session::context::{QueryContext, QueryContextRef};
 use snafu::{ensure, OptionExt, ResultExt};
+use sql::ast::{Ident, ObjectName, Value as SqlValue};
+use sql::statements::create::Partitions;
 use sql::statements::describe::DescribeTable;
 use sql::statements::explain::Explain;
-use sql::statements::show::{ShowDatabases, ShowKind, ShowTables};
+use sql::statements::show::{
+    ShowColumns, ShowCreateTable, ShowDatabases, ShowKind, ShowProcesslist, ShowTables,
+};
 use sql::statements::statement::Statement;
+use sql::statements::{
+    concrete_data_type_to_sql_data_type, table_idents_to_full_name, value_to_sql_value,
+};
+use table::metadata::TableInfo;
+use table::TableRef;
 
 use crate::error::{self, Result};
+use crate::process::ProcessManagerRef;
 use crate::QueryEngineRef;
 
 const SCHEMAS_COLUMN: &str = "Schemas";
@@ -46,6 +56,74 @@ const SEMANTIC_TYPE_TIME_INDEX: &str = "TIME INDEX";
 const NULLABLE_YES: &str = "YES";
 const NULLABLE_NO: &str = "NO";
 
+const PROCESS_ID_COLUMN: &str = "Id";
+const PROCESS_USER_COLUMN: &str = "User";
+const PROCESS_HOST_COLUMN: &str = "Host";
+const PROCESS_CATALOG_COLUMN: &str = "Catalog";
+const PROCESS_SCHEMA_COLUMN: &str = "Db";
+const PROCESS_TIME_COLUMN: &str = "Time";
+const PROCESS_INFO_COLUMN: &str = "Info";
+
+/// Max characters of the query shown by `SHOW PROCESSLIST` without `FULL`.
+const PROCESS_INFO_MAX_CHARS: usize = 100;
+
+const TABLE_COLUMN: &str = "Table";
+const CREATE_TABLE_COLUMN: &str = "Create Table";
+
+/// The key of table option that frontend uses to carry the table engine.
+const ENGINE_OPTION_KEY: &str = "engine";
+
+static SHOW_CREATE_TABLE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
+    Arc::new(Schema::new(vec![
+        ColumnSchema::new(TABLE_COLUMN, ConcreteDataType::string_datatype(), false),
+        ColumnSchema::new(
+            CREATE_TABLE_COLUMN,
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+    ]))
+});
+
+static SHOW_PROCESSLIST_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
+    Arc::new(Schema::new(vec![
+        ColumnSchema::new(
+            PROCESS_ID_COLUMN,
+            ConcreteDataType::uint32_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            PROCESS_USER_COLUMN,
+            ConcreteDataType::string_datatype(),
+            true,
+        ),
+        ColumnSchema::new(
+            PROCESS_HOST_COLUMN,
+            ConcreteDataType::string_datatype(),
+            true,
+        ),
+        ColumnSchema::new(
+            PROCESS_CATALOG_COLUMN,
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            PROCESS_SCHEMA_COLUMN,
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            PROCESS_TIME_COLUMN,
+            ConcreteDataType::uint64_datatype(),
+            false,
+        ),
+        ColumnSchema::new(
+            PROCESS_INFO_COLUMN,
+            ConcreteDataType::string_datatype(),
+            false,
+        ),
+    ]))
+});
+
 static DESCRIBE_TABLE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
     Arc::new(Schema::new(vec![
         ColumnSchema::new(
@@ -76,7 +154,11 @@ static DESCRIBE_TABLE_OUTPUT_SCHEMA: Lazy<Arc<Schema>> = Lazy::new(|| {
     ]))
 });
 
-pub fn show_databases(stmt: ShowDatabases, catalog_manager: CatalogManagerRef) -> Result<Output> {
+pub fn show_databases(
+    stmt: ShowDatabases,
+    catalog_manager: CatalogManagerRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
     // TODO(LFC): supports WHERE
     ensure!(
         matches!(stmt.kind, ShowKind::All | ShowKind::Like(_)),
@@ -85,11 +167,12 @@ pub fn show_databases(stmt: ShowDatabases, catalog_manager: CatalogManagerRef) -
         }
     );
 
+    let catalog_name = query_ctx.current_catalog();
     let catalog = catalog_manager
-        .catalog(DEFAULT_CATALOG_NAME)
+        .catalog(&catalog_name)
         .context(error::CatalogSnafu)?
         .context(error::CatalogNotFoundSnafu {
-            catalog: DEFAULT_CATALOG_NAME,
+            catalog: &catalog_name,
         })?;
     let databases = catalog.schema_names().context(error::CatalogSnafu)?;
 
@@ -109,7 +192,11 @@ pub fn show_databases(stmt: ShowDatabases, catalog_manager: CatalogManagerRef) -
     Ok(Output::RecordBatches(records))
 }
 
-pub fn show_tables(stmt: ShowTables, catalog_manager: CatalogManagerRef) -> Result<Output> {
+pub fn show_tables(
+    stmt: ShowTables,
+    catalog_manager: CatalogManagerRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
     // TODO(LFC): supports WHERE
     ensure!(
         matches!(stmt.kind, ShowKind::All | ShowKind::Like(_)),
@@ -118,9 +205,9 @@ pub fn show_tables(stmt: ShowTables, catalog_manager: CatalogManagerRef) -> Resu
         }
     );
 
-    let schema = stmt.database.as_deref().unwrap_or(DEFAULT_SCHEMA_NAME);
+    let schema = stmt.database.unwrap_or_else(|| query_ctx.current_schema());
     let schema = catalog_manager
-        .schema(DEFAULT_CATALOG_NAME, schema)
+        .schema(&query_ctx.current_catalog(), &schema)
         .context(error::CatalogSnafu)?
         .context(error::SchemaNotFoundSnafu { schema })?;
     let tables = schema.table_names().context(error::CatalogSnafu)?;
@@ -141,14 +228,26 @@ pub fn show_tables(stmt: ShowTables, catalog_manager: CatalogManagerRef) -> Resu
     Ok(Output::RecordBatches(records))
 }
 
-pub async fn explain(stmt: Box<Explain>, query_engine: QueryEngineRef) -> Result<Output> {
-    let plan = query_engine.statement_to_plan(Statement::Explain(*stmt))?;
+pub async fn explain(
+    stmt: Box<Explain>,
+    query_engine: QueryEngineRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
+    let plan = query_engine.statement_to_plan(Statement::Explain(*stmt), query_ctx)?;
     query_engine.execute(&plan).await
 }
 
-pub fn describe_table(stmt: DescribeTable, catalog_manager: CatalogManagerRef) -> Result<Output> {
-    let catalog = stmt.catalog_name.as_str();
-    let schema = stmt.schema_name.as_str();
+/// Finds the table by the maybe not fully-qualified `table_name`, missing catalog and schema
+/// are taken from the `query_ctx`.
+pub fn find_table(
+    table_name: &ObjectName,
+    catalog_manager: &CatalogManagerRef,
+    query_ctx: &QueryContext,
+) -> Result<TableRef> {
+    let (catalog, schema, table) =
+        table_idents_to_full_name(table_name, query_ctx).context(error::ParseSqlSnafu)?;
+    let catalog = catalog.as_str();
+    let schema = schema.as_str();
     catalog_manager
         .catalog(catalog)
         .context(error::CatalogSnafu)?
@@ -158,12 +257,82 @@ pub fn describe_table(stmt: DescribeTable, catalog_manager: CatalogManagerRef) -
         .context(error::CatalogSnafu)?
         .context(error::SchemaNotFoundSnafu { schema })?;
     let table = schema
-        .table(&stmt.table_name)
+        .table(&table)
         .context(error::CatalogSnafu)?
-        .context(error::TableNotFoundSnafu {
-            table: &stmt.table_name,
-        })?;
+        .context(error::TableNotFoundSnafu { table: &table })?;
+    Ok(table)
+}
 
+pub fn describe_table(
+    stmt: DescribeTable,
+    catalog_manager: CatalogManagerRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
+    let table = find_table(stmt.name(), &catalog_manager, &query_ctx)?;
+    describe_columns(&table)
+}
+
+/// Executes `SHOW COLUMNS`, which has the same output as `DESCRIBE TABLE`.
+pub fn show_columns(
+    stmt: ShowColumns,
+    catalog_manager: CatalogManagerRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
+    let table = find_table(&stmt.table_name, &catalog_manager, &query_ctx)?;
+    describe_columns(&table)
+}
+
+/// Executes `SHOW [FULL] PROCESSLIST`, lists the queries tracked by the `process_manager`.
+pub fn show_processlist(
+    stmt: ShowProcesslist,
+    process_manager: &ProcessManagerRef,
+) -> Result<Output> {
+    let processes = process_manager.list();
+    let queries = processes
+        .iter()
+        .map(|p| {
+            if stmt.full {
+                p.query.clone()
+            } else {
+                p.query.chars().take(PROCESS_INFO_MAX_CHARS).collect()
+            }
+        })
+        .collect::<Vec<String>>();
+
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(UInt32Vector::from_values(processes.iter().map(|p| p.id))),
+        Arc::new(StringVector::from(
+            processes.iter().map(|p| p.user.clone()).collect::<Vec<_>>(),
+        )),
+        Arc::new(StringVector::from(
+            processes
+                .iter()
+                .map(|p| p.client_addr.clone())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(StringVector::from(
+            processes
+                .iter()
+                .map(|p| p.catalog.as_str())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(StringVector::from(
+            processes
+                .iter()
+                .map(|p| p.schema.as_str())
+                .collect::<Vec<_>>(),
+        )),
+        Arc::new(UInt64Vector::from_values(
+            processes.iter().map(|p| p.elapsed().as_secs()),
+        )),
+        Arc::new(StringVector::from(queries)),
+    ];
+    let records = RecordBatches::try_from_columns(SHOW_PROCESSLIST_OUTPUT_SCHEMA.clone(), columns)
+        .context(error::CreateRecordBatchSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+fn describe_columns(table: &TableRef) -> Result<Output> {
     let table_info = table.table_info();
     let columns_schemas = table_info.meta.schema.column_schemas();
     let columns = vec![
@@ -235,8 +404,150 @@ fn describe_column_semantic_types(
     ))
 }
 
+pub fn show_create_table(
+    stmt: ShowCreateTable,
+    catalog_manager: CatalogManagerRef,
+    query_ctx: QueryContextRef,
+) -> Result<Output> {
+    let table = find_table(&stmt.table_name, &catalog_manager, &query_ctx)?;
+    render_show_create_table(&table.table_info(), None)
+}
+
+/// Renders the output of `SHOW CREATE TABLE` from `table_info`. The `partitions` is the
+/// partition rule of the table, which only exists in distributed mode.
+pub fn render_show_create_table(
+    table_info: &TableInfo,
+    partitions: Option<&Partitions>,
+) -> Result<Output> {
+    let sql = create_table_sql(table_info, partitions)?;
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(StringVector::from(vec![table_info.name.as_str()])),
+        Arc::new(StringVector::from(vec![sql.as_str()])),
+    ];
+    let records = RecordBatches::try_from_columns(SHOW_CREATE_TABLE_OUTPUT_SCHEMA.clone(), columns)
+        .context(error::CreateRecordBatchSnafu)?;
+    Ok(Output::RecordBatches(records))
+}
+
+fn create_table_sql(table_info: &TableInfo, partitions: Option<&Partitions>) -> Result<String> {
+    let meta = &table_info.meta;
+    let column_schemas = meta.schema.column_schemas();
+
+    let mut definitions = Vec::with_capacity(column_schemas.len() + 2);
+    for column_schema in column_schemas {
+        definitions.push(column_def_sql(column_schema)?);
+    }
+    if let Some(timestamp_column) = meta.schema.timestamp_column() {
+        definitions.push(format!(
+            "TIME INDEX ({})",
+            quote_ident(&timestamp_column.name)
+        ));
+    }
+    // The time index column is used as primary key when no primary key is specified, it
+    // can't be declared in `PRIMARY KEY` explicitly.
+    let primary_keys = meta
+        .primary_key_indices
+        .iter()
+        .map(|i| &column_schemas[*i])
+        .filter(|column_schema| !column_schema.is_time_index())
+        .map(|column_schema| quote_ident(&column_schema.name))
+        .collect::<Vec<_>>();
+    if !primary_keys.is_empty() {
+        definitions.push(format!("PRIMARY KEY ({})", primary_keys.join(", ")));
+    }
+
+    let definitions = definitions
+        .iter()
+        .map(|d| format!("  {}", d))
+        .collect::<Vec<_>>();
+    let mut clauses = vec![format!(
+        "CREATE TABLE {} (\n{}\n)",
+        quote_ident(&table_info.name),
+        definitions.join(",\n")
+    )];
+
+    if let Some(partitions) = partitions {
+        clauses.push(partitions_sql(partitions));
+    }
+
+    if !meta.engine.is_empty() {
+        clauses.push(format!("ENGINE={}", meta.engine));
+    }
+
+    // Frontend passes the engine to datanodes by table options, which is already rendered above.
+    let mut options = meta
+        .options
+        .iter()
+        .filter(|(k, _)| k.as_str() != ENGINE_OPTION_KEY)
+        .map(|(k, v)| format!("  {} = {}", k, SqlValue::SingleQuotedString(v.to_string())))
+        .collect::<Vec<_>>();
+    if !options.is_empty() {
+        options.sort();
+        clauses.push(format!("WITH(\n{}\n)", options.join(",\n")));
+    }
+    Ok(clauses.join("\n"))
+}
+
+fn column_def_sql(column_schema: &ColumnSchema) -> Result<String> {
+    let nullable = if column_schema.is_nullable() {
+        "NULL"
+    } else {
+        "NOT NULL"
+    };
+    let mut sql = vec![
+        quote_ident(&column_schema.name),
+        concrete_data_type_to_sql_data_type(&column_schema.data_type).to_string(),
+        nullable.to_string(),
+    ];
+    match column_schema.default_constraint() {
+        Some(ColumnDefaultConstraint::Function(expr)) => {
+            sql.push(format!("DEFAULT {}", expr));
+        }
+        Some(ColumnDefaultConstraint::Value(v)) => {
+            let value = value_to_sql_value(v).context(error::ConvertSqlValueSnafu)?;
+            sql.push(format!("DEFAULT {}", value));
+        }
+        None => {}
+    }
+    Ok(sql.join(" "))
+}
+
+fn partitions_sql(partitions: &Partitions) -> String {
+    let columns = partitions
+        .column_list
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>();
+    let entries = partitions
+        .entries
+        .iter()
+        .map(|e| {
+            let values = e
+                .value_list
+                .iter()
+                .map(|v| v.to_string())
+                .collect::<Vec<_>>();
+            format!(
+                "  PARTITION {} VALUES LESS THAN ({})",
+                e.name,
+                values.join(", ")
+            )
+        })
+        .collect::<Vec<_>>();
+    format!(
+        "PARTITION BY RANGE COLUMNS ({}) (\n{}\n)",
+        columns.join(", "),
+        entries.join(",\n")
+    )
+}
+
+fn quote_ident(name: &str) -> String {
+    Ident::with_quote('"', name).to_string()
+}
+
 #[cfg(test)]
 mod test {
+    use std::collections::HashMap;
     use std::sync::Arc;
 
     use catalog::local::{MemoryCatalogManager, MemoryCatalogProvider, MemorySchemaProvider};
@@ -249,16 +560,24 @@ mod test {
     use datatypes::arrow::array::PrimitiveArray;
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema, Schema, SchemaRef};
+    use datatypes::value::Value;
     use datatypes::vectors::{StringVector, TimestampVector, UInt32Vector, VectorRef};
+    use session::context::QueryContext;
     use snafu::ResultExt;
+    use sql::ast::{Ident, ObjectName, Value as SqlValue};
+    use sql::statements::create::{PartitionEntry, Partitions};
     use sql::statements::describe::DescribeTable;
+    use sql::statements::show::ShowProcesslist;
+    use table::metadata::{TableInfoBuilder, TableMetaBuilder};
     use table::test_util::MemTable;
 
     use crate::error;
     use crate::error::Result;
+    use crate::process::ProcessManager;
     use crate::sql::{
-        describe_table, DESCRIBE_TABLE_OUTPUT_SCHEMA, NULLABLE_NO, NULLABLE_YES,
-        SEMANTIC_TYPE_TIME_INDEX, SEMANTIC_TYPE_VALUE,
+        describe_table, render_show_create_table, show_processlist, DESCRIBE_TABLE_OUTPUT_SCHEMA,
+        NULLABLE_NO, NULLABLE_YES, PROCESS_INFO_MAX_CHARS, SEMANTIC_TYPE_TIME_INDEX,
+        SEMANTIC_TYPE_VALUE, SHOW_CREATE_TABLE_OUTPUT_SCHEMA,
     };
 
     #[test]
@@ -275,9 +594,15 @@ mod test {
         let catalog_manager =
             prepare_describe_table(&catalog_name, &schema_name, table_name, table_schema, data);
 
-        let stmt = DescribeTable::new("unknown".to_string(), schema_name, table_name.to_string());
+        let stmt = DescribeTable::new(ObjectName(vec![
+            Ident::new("unknown"),
+            Ident::new(schema_name),
+            Ident::new(table_name),
+        ]));
 
-        let err = describe_table(stmt, catalog_manager).err().unwrap();
+        let err = describe_table(stmt, catalog_manager, QueryContext::arc())
+            .err()
+            .unwrap();
         let err = err.as_any().downcast_ref::<error::InnerError>().unwrap();
 
         if let error::InnerError::CatalogNotFound { catalog, .. } = err {
@@ -303,9 +628,15 @@ mod test {
         let catalog_manager =
             prepare_describe_table(&catalog_name, &schema_name, table_name, table_schema, data);
 
-        let stmt = DescribeTable::new(catalog_name, "unknown".to_string(), table_name.to_string());
+        let stmt = DescribeTable::new(ObjectName(vec![
+            Ident::new(catalog_name),
+            Ident::new("unknown"),
+            Ident::new(table_name),
+        ]));
 
-        let err = describe_table(stmt, catalog_manager).err().unwrap();
+        let err = describe_table(stmt, catalog_manager, QueryContext::arc())
+            .err()
+            .unwrap();
         let err = err.as_any().downcast_ref::<error::InnerError>().unwrap();
 
         if let error::InnerError::SchemaNotFound { schema, .. } = err {
@@ -331,9 +662,11 @@ mod test {
         let catalog_manager =
             prepare_describe_table(&catalog_name, &schema_name, table_name, table_schema, data);
 
-        let stmt = DescribeTable::new(catalog_name, schema_name, "unknown".to_string());
+        let stmt = DescribeTable::new(ObjectName(vec![Ident::new("unknown")]));
 
-        let err = describe_table(stmt, catalog_manager).err().unwrap();
+        let err = describe_table(stmt, catalog_manager, QueryContext::arc())
+            .err()
+            .unwrap();
         let err = err.as_any().downcast_ref::<error::InnerError>().unwrap();
 
         if let error::InnerError::TableNotFound { table, .. } = err {
@@ -388,6 +721,117 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_render_show_create_table() -> Result<()> {
+        let schema = Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true)
+                .with_default_constraint(Some(ColumnDefaultConstraint::Value(Value::from(0.5))))
+                .unwrap(),
+            ColumnSchema::new(
+                "ts",
+                ConcreteDataType::timestamp_datatype(TimeUnit::Millisecond),
+                false,
+            )
+            .with_default_constraint(Some(ColumnDefaultConstraint::Function(String::from(
+                "current_timestamp()",
+            ))))
+            .unwrap()
+            .with_time_index(true),
+        ]);
+        let meta = TableMetaBuilder::default()
+            .schema(Arc::new(schema))
+            .primary_key_indices(vec![0])
+            .engine("mito")
+            .next_column_id(3)
+            .options(HashMap::from([
+                ("ttl".to_string(), "7d".to_string()),
+                ("engine".to_string(), "mito".to_string()),
+                ("regions".to_string(), "1".to_string()),
+            ]))
+            .build()
+            .unwrap();
+        let table_info = TableInfoBuilder::new("monitor", meta).build().unwrap();
+        let partitions = Partitions {
+            column_list: vec![Ident::new("host")],
+            entries: vec![
+                PartitionEntry {
+                    name: Ident::new("r0"),
+                    value_list: vec![SqlValue::SingleQuotedString("h".to_string())],
+                },
+                PartitionEntry {
+                    name: Ident::new("r1"),
+                    value_list: vec![SqlValue::Number("MAXVALUE".to_string(), false)],
+                },
+            ],
+        };
+
+        let expected_sql = r#"CREATE TABLE "monitor" (
+  "host" STRING NULL,
+  "cpu" DOUBLE NULL DEFAULT 0.5,
+  "ts" TIMESTAMP NOT NULL DEFAULT current_timestamp(),
+  TIME INDEX ("ts"),
+  PRIMARY KEY ("host")
+)
+PARTITION BY RANGE COLUMNS (host) (
+  PARTITION r0 VALUES LESS THAN ('h'),
+  PARTITION r1 VALUES LESS THAN (MAXVALUE)
+)
+ENGINE=mito
+WITH(
+  regions = '1',
+  ttl = '7d'
+)"#;
+        let expected = RecordBatches::try_from_columns(
+            SHOW_CREATE_TABLE_OUTPUT_SCHEMA.clone(),
+            vec![
+                Arc::new(StringVector::from(vec!["monitor"])) as _,
+                Arc::new(StringVector::from(vec![expected_sql])) as _,
+            ],
+        )
+        .context(error::CreateRecordBatchSnafu)?;
+
+        if let Output::RecordBatches(res) =
+            render_show_create_table(&table_info, Some(&partitions))?
+        {
+            assert_eq!(res.take(), expected.take());
+        } else {
+            panic!("show create table must return record batch");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_show_processlist() {
+        let manager = Arc::new(ProcessManager::new());
+        let query_ctx = QueryContext::arc();
+        query_ctx.set_user("greptime");
+        let query = format!("SELECT '{}'", "a".repeat(PROCESS_INFO_MAX_CHARS));
+        let _guard = manager.register(&query, &query_ctx, None);
+
+        for (full, expected_query) in [
+            (false, &query[..PROCESS_INFO_MAX_CHARS]),
+            (true, query.as_str()),
+        ] {
+            let output = show_processlist(ShowProcesslist { full }, &manager).unwrap();
+            let batches = match output {
+                Output::RecordBatches(batches) => batches.take(),
+                _ => unreachable!(),
+            };
+            assert_eq!(1, batches.len());
+            let rows = batches[0].rows().collect::<Vec<_>>();
+            assert_eq!(1, rows.len());
+            let row = rows[0].as_ref().unwrap();
+            assert_eq!(Value::from(query_ctx.connection_id()), row[0]);
+            assert_eq!(Value::from("greptime"), row[1]);
+            assert_eq!(Value::Null, row[2]);
+            assert_eq!(Value::from(DEFAULT_CATALOG_NAME), row[3]);
+            assert_eq!(Value::from(DEFAULT_SCHEMA_NAME), row[4]);
+            assert_eq!(Value::from(expected_query), row[6]);
+        }
+    }
+
     fn describe_table_test_by_schema(
         catalog_name: &str,
         schema_name: &str,
@@ -404,12 +848,14 @@ mod test {
             RecordBatches::try_from_columns(DESCRIBE_TABLE_OUTPUT_SCHEMA.clone(), expected_columns)
                 .context(error::CreateRecordBatchSnafu)?;
 
-        let stmt = DescribeTable::new(
-            catalog_name.to_string(),
-            schema_name.to_string(),
-            table_name.to_string(),
-        );
-        if let Output::RecordBatches(res) = describe_table(stmt, catalog_manager)? {
+        let stmt = DescribeTable::new(ObjectName(vec![
+            Ident::new(catalog_name),
+            Ident::new(schema_name),
+            Ident::new(table_name),
+        ]));
+        if let Output::RecordBatches(res) =
+            describe_table(stmt, catalog_manager, QueryContext::arc())?
+        {
             assert_eq!(res.take(), expected.take());
         } else {
             panic!("describe table must return record batch");
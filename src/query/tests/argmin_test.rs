@@ -25,6 +25,7 @@ use datatypes::types::PrimitiveElement;
 use function::{create_query_engine, get_numbers_from_table};
 use query::error::Result;
 use query::QueryEngine;
+use session::context::QueryContext;
 
 #[tokio::test]
 async fn test_argmin_aggregator() -> Result<()> {
@@ -96,7 +97,7 @@ async fn execute_argmin<'a>(
         "select argmin({}) as argmin from {}",
         column_name, table_name
     );
-    let plan = engine.sql_to_plan(&sql).unwrap();
+    let plan = engine.sql_to_plan(&sql, QueryContext::arc()).unwrap();
 
     let output = engine.execute(&plan).await.unwrap();
     let recordbatch_stream = match output {
@@ -30,6 +30,7 @@ use function::{create_query_engine, get_numbers_from_table};
 use num_traits::AsPrimitive;
 use query::error::Result;
 use query::{QueryEngine, QueryEngineFactory};
+use session::context::QueryContext;
 use table::test_util::MemTable;
 
 #[tokio::test]
@@ -53,7 +54,7 @@ async fn test_percentile_aggregator() -> Result<()> {
 async fn test_percentile_correctness() -> Result<()> {
     let engine = create_correctness_engine();
     let sql = String::from("select PERCENTILE(corr_number,88.0) as percentile from corr_numbers");
-    let plan = engine.sql_to_plan(&sql).unwrap();
+    let plan = engine.sql_to_plan(&sql, QueryContext::arc()).unwrap();
 
     let output = engine.execute(&plan).await.unwrap();
     let recordbatch_stream = match output {
@@ -113,7 +114,7 @@ async fn execute_percentile<'a>(
         "select PERCENTILE({},50.0) as percentile from {}",
         column_name, table_name
     );
-    let plan = engine.sql_to_plan(&sql).unwrap();
+    let plan = engine.sql_to_plan(&sql, QueryContext::arc()).unwrap();
 
     let output = engine.execute(&plan).await.unwrap();
     let recordbatch_stream = match output {
@@ -26,6 +26,7 @@ use function::{create_query_engine, get_numbers_from_table};
 use num_traits::AsPrimitive;
 use query::error::Result;
 use query::QueryEngine;
+use session::context::QueryContext;
 
 #[tokio::test]
 async fn test_polyval_aggregator() -> Result<()> {
@@ -92,7 +93,7 @@ async fn execute_polyval<'a>(
         "select POLYVAL({}, 0) as polyval from {}",
         column_name, table_name
     );
-    let plan = engine.sql_to_plan(&sql).unwrap();
+    let plan = engine.sql_to_plan(&sql, QueryContext::arc()).unwrap();
 
     let output = engine.execute(&plan).await.unwrap();
     let recordbatch_stream = match output {
@@ -14,11 +14,14 @@
 
 mod pow;
 
-use std::sync::Arc;
+use std::any::Any;
+use std::sync::{Arc, Mutex};
 
 use catalog::local::{MemoryCatalogManager, MemoryCatalogProvider, MemorySchemaProvider};
 use catalog::{CatalogList, CatalogProvider, SchemaProvider};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+use common_query::logical_plan::Expr;
+use common_query::physical_plan::PhysicalPlanRef;
 use common_query::prelude::{create_udf, make_scalar_function, Volatility};
 use common_query::Output;
 use common_recordbatch::error::Result as RecordResult;
@@ -28,18 +31,21 @@ use datafusion::logical_plan::LogicalPlanBuilder;
 use datatypes::arrow::array::UInt32Array;
 use datatypes::for_all_primitive_types;
 use datatypes::prelude::*;
-use datatypes::schema::{ColumnSchema, Schema};
+use datatypes::schema::{ColumnSchema, Schema, SchemaRef};
 use datatypes::types::{OrdPrimitive, PrimitiveElement};
-use datatypes::vectors::{PrimitiveVector, UInt32Vector};
+use datatypes::vectors::{PrimitiveVector, TimestampVector, UInt32Vector};
 use num::NumCast;
 use query::error::Result;
 use query::plan::LogicalPlan;
 use query::query_engine::QueryEngineFactory;
 use query::QueryEngine;
 use rand::Rng;
+use session::context::QueryContext;
+use table::metadata::TableInfoRef;
 use table::table::adapter::DfTableProviderAdapter;
 use table::table::numbers::NumbersTable;
 use table::test_util::MemTable;
+use table::Table;
 
 use crate::pow::pow;
 
@@ -134,7 +140,10 @@ async fn test_udf() -> Result<()> {
 
     engine.register_udf(udf);
 
-    let plan = engine.sql_to_plan("select pow(number, number) as p from numbers limit 10")?;
+    let plan = engine.sql_to_plan(
+        "select pow(number, number) as p from numbers limit 10",
+        QueryContext::arc(),
+    )?;
 
     let output = engine.execute(&plan).await?;
     let recordbatch = match output {
@@ -160,6 +169,111 @@ async fn test_udf() -> Result<()> {
     Ok(())
 }
 
+/// A table that records the limits of the scans for the latest rows.
+struct LatestRowsTable {
+    inner: MemTable,
+    latest_limits: Mutex<Vec<Option<usize>>>,
+}
+
+#[async_trait::async_trait]
+impl Table for LatestRowsTable {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.inner.schema()
+    }
+
+    fn table_info(&self) -> TableInfoRef {
+        self.inner.table_info()
+    }
+
+    async fn scan(
+        &self,
+        projection: &Option<Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> table::Result<PhysicalPlanRef> {
+        self.inner.scan(projection, filters, limit).await
+    }
+
+    async fn scan_latest(
+        &self,
+        projection: &Option<Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> table::Result<PhysicalPlanRef> {
+        self.latest_limits.lock().unwrap().push(limit);
+        self.inner.scan(projection, filters, None).await
+    }
+}
+
+async fn query_values(engine: &Arc<dyn QueryEngine>, sql: &str) -> Vec<u32> {
+    let plan = engine.sql_to_plan(sql, QueryContext::arc()).unwrap();
+    let recordbatch = match engine.execute(&plan).await.unwrap() {
+        Output::Stream(recordbatch) => recordbatch,
+        _ => unreachable!(),
+    };
+
+    util::collect(recordbatch)
+        .await
+        .unwrap()
+        .iter()
+        .flat_map(|batch| {
+            let column = batch.df_recordbatch.column(1);
+            let values = column.as_any().downcast_ref::<UInt32Array>().unwrap();
+            values.values().to_vec()
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_scan_latest_rows() {
+    common_telemetry::init_default_ut_logging();
+    let catalog_list = catalog::local::new_memory_catalog_list().unwrap();
+
+    let column_schemas = vec![
+        ColumnSchema::new("ts", ConcreteDataType::timestamp_millis_datatype(), false)
+            .with_time_index(true),
+        ColumnSchema::new("v", ConcreteDataType::uint32_datatype(), false),
+    ];
+    let schema = Arc::new(Schema::new(column_schemas));
+    let columns: Vec<VectorRef> = vec![
+        Arc::new(TimestampVector::from_vec(vec![1, 5, 3, 2, 4])),
+        Arc::new(UInt32Vector::from_slice(&[10, 50, 30, 20, 40])),
+    ];
+    let recordbatch = RecordBatch::new(schema, columns).unwrap();
+    let table = Arc::new(LatestRowsTable {
+        inner: MemTable::new("latest", recordbatch),
+        latest_limits: Mutex::new(Vec::new()),
+    });
+
+    let default_schema = Arc::new(MemorySchemaProvider::new());
+    default_schema
+        .register_table("latest".to_string(), table.clone())
+        .unwrap();
+    let default_catalog = Arc::new(MemoryCatalogProvider::new());
+    default_catalog
+        .register_schema(DEFAULT_SCHEMA_NAME.to_string(), default_schema)
+        .unwrap();
+    catalog_list
+        .register_catalog(DEFAULT_CATALOG_NAME.to_string(), default_catalog)
+        .unwrap();
+    let engine = QueryEngineFactory::new(catalog_list).query_engine();
+
+    let values = query_values(&engine, "SELECT ts, v FROM latest ORDER BY ts DESC LIMIT 2").await;
+    assert_eq!(vec![50, 40], values);
+    assert_eq!(vec![Some(2)], *table.latest_limits.lock().unwrap());
+
+    // Other sorts scan the table as usual.
+    let values = query_values(&engine, "SELECT ts, v FROM latest ORDER BY ts LIMIT 2").await;
+    assert_eq!(vec![10, 20], values);
+    let values = query_values(&engine, "SELECT ts, v FROM latest ORDER BY v DESC LIMIT 2").await;
+    assert_eq!(vec![50, 40], values);
+    assert_eq!(vec![Some(2)], *table.latest_limits.lock().unwrap());
+}
+
 fn create_query_engine() -> Arc<dyn QueryEngine> {
     let schema_provider = Arc::new(MemorySchemaProvider::new());
     let catalog_provider = Arc::new(MemoryCatalogProvider::new());
@@ -242,7 +356,7 @@ where
     for<'a> T: Scalar<RefType<'a> = T>,
 {
     let sql = format!("SELECT {} FROM {}", column_name, table_name);
-    let plan = engine.sql_to_plan(&sql).unwrap();
+    let plan = engine.sql_to_plan(&sql, QueryContext::arc()).unwrap();
 
     let output = engine.execute(&plan).await.unwrap();
     let recordbatch_stream = match output {
@@ -330,7 +444,7 @@ async fn execute_median<'a>(
         "select MEDIAN({}) as median from {}",
         column_name, table_name
     );
-    let plan = engine.sql_to_plan(&sql).unwrap();
+    let plan = engine.sql_to_plan(&sql, QueryContext::arc()).unwrap();
 
     let output = engine.execute(&plan).await.unwrap();
     let recordbatch_stream = match output {
@@ -28,6 +28,7 @@ use datatypes::prelude::{ConcreteDataType, ScalarVector};
 use datatypes::schema::{ColumnSchema, Schema, SchemaBuilder};
 use datatypes::vectors::{StringVector, TimestampVector, VectorRef};
 use query::QueryEngineRef;
+use session::context::QueryContext;
 use snafu::{ensure, OptionExt, ResultExt};
 use table::requests::{CreateTableRequest, InsertRequest};
 
@@ -134,6 +135,7 @@ impl ScriptsTable {
                 schema_name: DEFAULT_SCHEMA_NAME.to_string(),
                 table_name: SCRIPTS_TABLE_NAME.to_string(),
                 columns_values,
+                region_number: 0,
             })
             .await
             .context(InsertScriptSnafu { name })?;
@@ -151,7 +153,7 @@ impl ScriptsTable {
 
         let plan = self
             .query_engine
-            .sql_to_plan(&sql)
+            .sql_to_plan(&sql, QueryContext::arc())
             .context(FindScriptSnafu { name })?;
 
         let stream = match self
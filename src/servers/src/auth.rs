@@ -0,0 +1,113 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod user_provider;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use snafu::OptionExt;
+
+use crate::auth::user_provider::StaticUserProvider;
+use crate::context::{AuthHashMethod, AuthMethod, Channel, UserInfo};
+use crate::error::{self, Result};
+
+pub type UserProviderRef = Arc<dyn UserProvider>;
+
+/// Authenticates users of all protocols. An instance is shared by all servers.
+#[async_trait]
+pub trait UserProvider: Send + Sync {
+    fn name(&self) -> &str;
+
+    /// Checks whether the `password` matches the user identified by `id`, returns the info of
+    /// the user connected from `channel`.
+    async fn auth(
+        &self,
+        id: Identity<'_>,
+        password: Password<'_>,
+        channel: Channel,
+    ) -> Result<UserInfo>;
+}
+
+pub type Username<'a> = &'a str;
+pub type HostOrIp<'a> = &'a str;
+
+#[derive(Debug, Clone, Copy)]
+pub enum Identity<'a> {
+    UserId(Username<'a>, Option<HostOrIp<'a>>),
+}
+
+pub type HashedPassword<'a> = &'a [u8];
+pub type Salt<'a> = &'a [u8];
+
+/// Password presented by the client, in the form required by its protocol.
+#[derive(Debug, Clone, Copy)]
+pub enum Password<'a> {
+    PlainText(&'a str),
+    /// Scrambled password of MySQL `mysql_native_password` plugin.
+    MysqlNativePassword(HashedPassword<'a>, Salt<'a>),
+    /// Password of PostgreSQL MD5 authentication, `"md5" + md5(md5(password + username) + salt)`.
+    PgMD5(HashedPassword<'a>, Salt<'a>),
+}
+
+impl From<Password<'_>> for AuthMethod {
+    fn from(password: Password<'_>) -> Self {
+        match password {
+            Password::PlainText(_) => AuthMethod::PlainText,
+            Password::MysqlNativePassword(hashed, salt) => AuthMethod::Password {
+                hash_method: AuthHashMethod::DoubleSha1,
+                hashed_value: hashed.to_vec(),
+                salt: salt.to_vec(),
+            },
+            Password::PgMD5(hashed, salt) => AuthMethod::Password {
+                hash_method: AuthHashMethod::Md5,
+                hashed_value: hashed.to_vec(),
+                salt: salt.to_vec(),
+            },
+        }
+    }
+}
+
+/// Creates a user provider from the option string in the format `<provider>:<args>`, e.g.
+/// `static_user_provider:file:/path/to/users` or `static_user_provider:cmd:alice=pwd1,bob=pwd2`.
+pub fn user_provider_from_option(option: &str) -> Result<UserProviderRef> {
+    let (name, args) = option
+        .split_once(':')
+        .context(error::InvalidUserProviderOptionSnafu {
+            msg: format!("invalid user provider option: {}", option),
+        })?;
+    match name {
+        user_provider::STATIC_USER_PROVIDER => {
+            Ok(Arc::new(StaticUserProvider::try_from(args)?) as UserProviderRef)
+        }
+        _ => error::InvalidUserProviderOptionSnafu {
+            msg: format!("unknown user provider: {}", name),
+        }
+        .fail(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_provider_from_option() {
+        let provider = user_provider_from_option("static_user_provider:cmd:root=123456").unwrap();
+        assert_eq!(user_provider::STATIC_USER_PROVIDER, provider.name());
+
+        assert!(user_provider_from_option("static_user_provider").is_err());
+        assert!(user_provider_from_option("unknown_provider:cmd:root=123456").is_err());
+    }
+}
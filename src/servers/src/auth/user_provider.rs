@@ -0,0 +1,312 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use async_trait::async_trait;
+use md5::Md5;
+use sha1::{Digest, Sha1};
+use snafu::{ensure, OptionExt, ResultExt};
+
+use crate::auth::{Identity, Password, UserProvider};
+use crate::context::{Channel, UserInfo};
+use crate::error::{self, Error, Result};
+
+pub const STATIC_USER_PROVIDER: &str = "static_user_provider";
+
+/// A user provider with a fixed set of users, loaded from a file (`file:<path>`) or from the
+/// command line (`cmd:<username>=<password>,...`, with `,` and `\` in passwords escaped as
+/// `\,` and `\\`). Each line of the file is a `<username>=<password>` pair, empty lines and
+/// lines starting with `#` are ignored.
+pub struct StaticUserProvider {
+    users: HashMap<String, Vec<u8>>,
+}
+
+impl TryFrom<&str> for StaticUserProvider {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self> {
+        let (mode, content) =
+            value
+                .split_once(':')
+                .context(error::InvalidUserProviderOptionSnafu {
+                    msg: format!("invalid static user provider option: {}", value),
+                })?;
+        let users = match mode {
+            "file" => {
+                let content = std::fs::read_to_string(content)
+                    .context(error::ReadUserProviderFileSnafu { path: content })?;
+                parse_users(content.lines())?
+            }
+            "cmd" => parse_users(split_cmd_users(content).iter().map(String::as_str))?,
+            _ => {
+                return error::InvalidUserProviderOptionSnafu {
+                    msg: format!("unknown static user provider mode: {}", mode),
+                }
+                .fail()
+            }
+        };
+        Ok(Self { users })
+    }
+}
+
+/// Splits the users of the `cmd` mode by unescaped `,`. A `\` escapes the next character.
+fn split_cmd_users(content: &str) -> Vec<String> {
+    let mut entries = vec![];
+    let mut entry = String::new();
+    let mut chars = content.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => entry.push(chars.next().unwrap_or(c)),
+            ',' => entries.push(std::mem::take(&mut entry)),
+            _ => entry.push(c),
+        }
+    }
+    entries.push(entry);
+    entries
+}
+
+fn parse_users<'a>(entries: impl Iterator<Item = &'a str>) -> Result<HashMap<String, Vec<u8>>> {
+    entries
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty() && !entry.starts_with('#'))
+        .map(|entry| {
+            let (username, password) = entry
+                .split_once('=')
+                .filter(|(username, _)| !username.trim().is_empty())
+                .context(error::InvalidUserProviderOptionSnafu {
+                    msg: format!("expect <username>=<password>, found: {}", entry),
+                })?;
+            Ok((
+                username.trim().to_string(),
+                password.trim().as_bytes().to_vec(),
+            ))
+        })
+        .collect()
+}
+
+#[async_trait]
+impl UserProvider for StaticUserProvider {
+    fn name(&self) -> &str {
+        STATIC_USER_PROVIDER
+    }
+
+    async fn auth(
+        &self,
+        id: Identity<'_>,
+        password: Password<'_>,
+        channel: Channel,
+    ) -> Result<UserInfo> {
+        let Identity::UserId(username, _) = id;
+        let saved_password = self
+            .users
+            .get(username)
+            .context(error::UserNotFoundSnafu { username })?;
+
+        let matched = match password {
+            Password::PlainText(password) => constant_time_eq(password.as_bytes(), saved_password),
+            Password::MysqlNativePassword(auth_data, salt) => {
+                auth_mysql(auth_data, salt, saved_password)
+            }
+            Password::PgMD5(hashed, salt) => auth_pg_md5(hashed, salt, username, saved_password),
+        };
+        ensure!(matched, error::UserPasswordMismatchSnafu { username });
+        Ok(UserInfo {
+            username: Some(username.to_string()),
+            from_channel: channel,
+            auth_method: password.into(),
+        })
+    }
+}
+
+/// Checks the scrambled password of `mysql_native_password`, which the client computes as
+/// `SHA1(password) XOR SHA1(salt + SHA1(SHA1(password)))`.
+fn auth_mysql(auth_data: &[u8], salt: &[u8], password: &[u8]) -> bool {
+    // Clients send nothing for empty passwords.
+    if password.is_empty() {
+        return auth_data.is_empty();
+    }
+    let stage1 = Sha1::digest(password);
+    let stage2 = Sha1::digest(stage1);
+    let mut hasher = Sha1::new();
+    hasher.update(salt);
+    hasher.update(stage2);
+    let mask = hasher.finalize();
+
+    let expected = stage1
+        .iter()
+        .zip(mask.iter())
+        .map(|(a, b)| a ^ b)
+        .collect::<Vec<_>>();
+    constant_time_eq(&expected, auth_data)
+}
+
+/// Checks the password of PostgreSQL MD5 authentication, which the client computes as
+/// `"md5" + md5_hex(md5_hex(password + username) + salt)`.
+fn auth_pg_md5(hashed: &[u8], salt: &[u8], username: &str, password: &[u8]) -> bool {
+    let mut hasher = Md5::new();
+    hasher.update(password);
+    hasher.update(username.as_bytes());
+    let inner = hex::encode(hasher.finalize());
+
+    let mut hasher = Md5::new();
+    hasher.update(inner.as_bytes());
+    hasher.update(salt);
+    let expected = format!("md5{}", hex::encode(hasher.finalize()));
+    constant_time_eq(expected.as_bytes(), hashed)
+}
+
+/// Compares two byte slices in time only depending on their lengths, so the time taken
+/// doesn't tell how many leading bytes of a guessed password are right.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::File;
+    use std::io::Write;
+
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::context::AuthMethod;
+
+    fn mysql_scramble(password: &[u8], salt: &[u8]) -> Vec<u8> {
+        let stage1 = Sha1::digest(password);
+        let mut hasher = Sha1::new();
+        hasher.update(salt);
+        hasher.update(Sha1::digest(stage1));
+        stage1
+            .iter()
+            .zip(hasher.finalize().iter())
+            .map(|(a, b)| a ^ b)
+            .collect()
+    }
+
+    async fn test_auth(provider: &dyn UserProvider, username: &str, password: &str) {
+        let id = Identity::UserId(username, None);
+        let user_info = provider
+            .auth(id, Password::PlainText(password), Channel::HTTP)
+            .await
+            .unwrap();
+        assert_eq!(Some(username), user_info.username.as_deref());
+        assert_eq!(Channel::HTTP, user_info.from_channel);
+        assert_eq!(AuthMethod::PlainText, user_info.auth_method);
+
+        assert!(matches!(
+            provider
+                .auth(id, Password::PlainText("wrong"), Channel::HTTP)
+                .await,
+            Err(Error::UserPasswordMismatch { .. })
+        ));
+        assert!(matches!(
+            provider
+                .auth(
+                    Identity::UserId("unknown", None),
+                    Password::PlainText(password),
+                    Channel::HTTP
+                )
+                .await,
+            Err(Error::UserNotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_static_user_provider_from_cmd() {
+        let provider = StaticUserProvider::try_from("cmd:root=123456, admin = 654321").unwrap();
+        test_auth(&provider, "root", "123456").await;
+        test_auth(&provider, "admin", "654321").await;
+
+        let provider = StaticUserProvider::try_from(r"cmd:root=12\,34,admin=a\\b\,").unwrap();
+        test_auth(&provider, "root", "12,34").await;
+        test_auth(&provider, "admin", r"a\b,").await;
+
+        assert!(StaticUserProvider::try_from("cmd").is_err());
+        assert!(StaticUserProvider::try_from("cmd:root").is_err());
+        assert!(StaticUserProvider::try_from("unknown:root=123456").is_err());
+    }
+
+    #[tokio::test]
+    async fn test_static_user_provider_from_file() {
+        let dir = TempDir::new("test_static_user_provider_from_file").unwrap();
+        let path = dir.path().join("users");
+        let mut file = File::create(&path).unwrap();
+        file.write_all(b"# users of greptime\nroot=123456\n\nadmin=654321\n")
+            .unwrap();
+        file.flush().unwrap();
+
+        let provider =
+            StaticUserProvider::try_from(format!("file:{}", path.display()).as_str()).unwrap();
+        test_auth(&provider, "root", "123456").await;
+        test_auth(&provider, "admin", "654321").await;
+
+        assert!(matches!(
+            StaticUserProvider::try_from("file:/not/exist"),
+            Err(Error::ReadUserProviderFile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_auth_mysql() {
+        // Stored by MySQL as `*6BB4837EB74329105EE4568DDA7DC67ED2CA2AD9`.
+        assert_eq!(
+            "6bb4837eb74329105ee4568dda7dc67ed2ca2ad9",
+            hex::encode(Sha1::digest(Sha1::digest(b"123456")))
+        );
+
+        let salt = b"01234567890123456789";
+        let auth_data = mysql_scramble(b"123456", salt);
+        assert!(auth_mysql(&auth_data, salt, b"123456"));
+        assert!(!auth_mysql(&auth_data, salt, b"1234567"));
+        assert!(!auth_mysql(&auth_data, b"98765432109876543210", b"123456"));
+        assert!(!auth_mysql(&[], salt, b"123456"));
+
+        assert!(auth_mysql(&[], salt, b""));
+        assert!(!auth_mysql(&auth_data, salt, b""));
+    }
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"", b""));
+        assert!(constant_time_eq(b"123456", b"123456"));
+        assert!(!constant_time_eq(b"123456", b"123457"));
+        assert!(!constant_time_eq(b"123456", b"023456"));
+        assert!(!constant_time_eq(b"123456", b"1234567"));
+    }
+
+    #[test]
+    fn test_auth_pg_md5() {
+        // Computed the same way as libpq does.
+        let salt = [1u8, 2, 3, 4];
+        let inner = hex::encode(Md5::digest(b"123456root"));
+        let mut hasher = Md5::new();
+        hasher.update(inner.as_bytes());
+        hasher.update(salt);
+        let hashed = format!("md5{}", hex::encode(hasher.finalize()));
+
+        assert!(auth_pg_md5(hashed.as_bytes(), &salt, "root", b"123456"));
+        assert!(!auth_pg_md5(hashed.as_bytes(), &salt, "admin", b"123456"));
+        assert!(!auth_pg_md5(
+            hashed.as_bytes(),
+            &[4, 3, 2, 1],
+            "root",
+            b"123456"
+        ));
+        assert!(!auth_pg_md5(b"123456", &salt, "root", b"123456"));
+    }
+}
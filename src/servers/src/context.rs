@@ -121,23 +121,26 @@ pub struct ClientInfo {
     pub client_host: String,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct UserInfo {
     pub username: Option<String>,
     pub from_channel: Channel,
     pub auth_method: AuthMethod,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Channel {
     GRPC,
     HTTP,
     MYSQL,
+    POSTGRES,
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AuthMethod {
     None,
+    /// The password is sent in plain text, it isn't kept in the context.
+    PlainText,
     Password {
         hash_method: AuthHashMethod,
         hashed_value: Vec<u8>,
@@ -152,10 +155,11 @@ impl Default for AuthMethod {
     }
 }
 
-#[derive(Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum AuthHashMethod {
     DoubleSha1,
     Sha256,
+    Md5,
 }
 
 #[derive(Default, Serialize, Deserialize)]
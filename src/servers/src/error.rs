@@ -101,6 +101,12 @@ pub enum Error {
         source: BoxedError,
     },
 
+    #[snafu(display("Failed to check database validity, source: {}", source))]
+    CheckDatabaseValidity {
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display("Not supported: {}", feat))]
     NotSupported { feat: String },
 
@@ -110,6 +116,12 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Invalid prepared statement: {}", err_msg))]
+    InvalidPrepareStatement {
+        err_msg: String,
+        backtrace: Backtrace,
+    },
+
     #[snafu(display("Failed to parse InfluxDB line protocol, source: {}", source))]
     InfluxdbLineProtocol {
         #[snafu(backtrace)]
@@ -169,9 +181,128 @@ pub enum Error {
     #[snafu(display("Invalid prometheus remote request, msg: {}", msg))]
     InvalidPromRemoteRequest { msg: String, backtrace: Backtrace },
 
+    #[snafu(display(
+        "Invalid regex {} in prometheus label matcher, source: {}",
+        regex,
+        source
+    ))]
+    InvalidPromRegex { regex: String, source: regex::Error },
+
+    #[snafu(display(
+        "Failed to build prometheus remote read plan on table {}, source: {}",
+        table_name,
+        source
+    ))]
+    BuildPromRemoteReadPlan {
+        table_name: String,
+        source: datafusion::error::DataFusionError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Prometheus remote read on table {} exceeds the sample limit {}",
+        table_name,
+        limit
+    ))]
+    PromReadSampleLimitExceeded { table_name: String, limit: usize },
+
     #[snafu(display("Invalid prometheus remote read query result, msg: {}", msg))]
     InvalidPromRemoteReadQueryResult { msg: String, backtrace: Backtrace },
 
+    #[snafu(display("Invalid parameter {} in PromQL request: {}", name, msg))]
+    InvalidPromqlParam {
+        name: String,
+        msg: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to execute PromQL query, source: {}", source))]
+    ExecutePromql {
+        #[snafu(backtrace)]
+        source: promql::error::Error,
+    },
+
+    #[snafu(display("Invalid user provider option: {}", msg))]
+    InvalidUserProviderOption { msg: String, backtrace: Backtrace },
+
+    #[snafu(display("Failed to read user provider file {}, source: {}", path, source))]
+    ReadUserProviderFile {
+        path: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("User {} not found", username))]
+    UserNotFound {
+        username: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Unsupported password type: {}", password_type))]
+    UnsupportedPasswordType {
+        password_type: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Username and password does not match, username: {}", username))]
+    UserPasswordMismatch {
+        username: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Not found http authorization header"))]
+    AuthHeaderNotFound { backtrace: Backtrace },
+
+    #[snafu(display("Invalid http authorization header, msg: {}", msg))]
+    InvalidAuthHeader { msg: String, backtrace: Backtrace },
+
+    #[snafu(display("Invalid TLS config: {}", msg))]
+    InvalidTlsConfig { msg: String, backtrace: Backtrace },
+
+    #[snafu(display("Failed to read TLS file {}, source: {}", path, source))]
+    ReadTlsFile {
+        path: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to build TLS server config, source: {}", source))]
+    BuildTlsConfig {
+        source: rustls::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to watch file {}, source: {}", path, source))]
+    FileWatch {
+        path: String,
+        source: notify::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("TLS is required by {} server", server))]
+    TlsRequired {
+        server: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to describe query: {}, source: {}", query, source))]
+    DescribeQuery {
+        query: String,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display(
+        "Failed to kill queries of connection {}, source: {}",
+        connection_id,
+        source
+    ))]
+    KillQuery {
+        connection_id: u32,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to decode region id, source: {}", source))]
     DecodeRegionNumber { source: api::DecodeError },
 
@@ -209,19 +340,27 @@ impl ErrorExt for Error {
             | StartGrpc { .. }
             | AlreadyStarted { .. }
             | InvalidPromRemoteReadQueryResult { .. }
+            | BuildPromRemoteReadPlan { .. }
             | TcpBind { .. }
+            | FileWatch { .. }
             | GrpcReflectionService { .. }
             | BuildingContext { .. } => StatusCode::Internal,
 
             InsertScript { source, .. }
             | ExecuteScript { source, .. }
             | ExecuteQuery { source, .. }
+            | DescribeQuery { source, .. }
+            | KillQuery { source, .. }
             | ExecuteInsert { source, .. }
             | ExecuteAlter { source, .. }
+            | CheckDatabaseValidity { source, .. }
             | PutOpentsdbDataPoint { source, .. } => source.status_code(),
 
+            ExecutePromql { source, .. } => source.status_code(),
+
             NotSupported { .. }
             | InvalidQuery { .. }
+            | InvalidPrepareStatement { .. }
             | InfluxdbLineProtocol { .. }
             | ConnResetByPeer { .. }
             | InvalidOpentsdbLine { .. }
@@ -229,9 +368,24 @@ impl ErrorExt for Error {
             | DecodePromRemoteRequest { .. }
             | DecompressPromRemoteRequest { .. }
             | InvalidPromRemoteRequest { .. }
+            | InvalidPromRegex { .. }
+            | PromReadSampleLimitExceeded { .. }
+            | InvalidPromqlParam { .. }
+            | InvalidUserProviderOption { .. }
+            | ReadUserProviderFile { .. }
+            | InvalidTlsConfig { .. }
+            | ReadTlsFile { .. }
+            | BuildTlsConfig { .. }
+            | TlsRequired { .. }
             | DecodeRegionNumber { .. }
             | TimePrecision { .. } => StatusCode::InvalidArguments,
 
+            UserNotFound { .. } => StatusCode::UserNotFound,
+            UnsupportedPasswordType { .. } => StatusCode::UnsupportedPasswordType,
+            UserPasswordMismatch { .. } => StatusCode::UserPasswordMismatch,
+            AuthHeaderNotFound { .. } => StatusCode::AuthHeaderNotFound,
+            InvalidAuthHeader { .. } => StatusCode::InvalidAuthHeader,
+
             InfluxdbLinesWrite { source, .. } => source.status_code(),
             Hyper { .. } => StatusCode::Unknown,
             StartFrontend { source, .. } => source.status_code(),
@@ -269,8 +423,16 @@ impl IntoResponse for Error {
             | Error::DecodePromRemoteRequest { .. }
             | Error::DecompressPromRemoteRequest { .. }
             | Error::InvalidPromRemoteRequest { .. }
+            | Error::InvalidPromRegex { .. }
+            | Error::PromReadSampleLimitExceeded { .. }
+            | Error::InvalidPromqlParam { .. }
             | Error::InvalidQuery { .. }
             | Error::TimePrecision { .. } => (HttpStatusCode::BAD_REQUEST, self.to_string()),
+            Error::UserNotFound { .. }
+            | Error::UnsupportedPasswordType { .. }
+            | Error::UserPasswordMismatch { .. }
+            | Error::AuthHeaderNotFound { .. }
+            | Error::InvalidAuthHeader { .. } => (HttpStatusCode::UNAUTHORIZED, self.to_string()),
             _ => (HttpStatusCode::INTERNAL_SERVER_ERROR, self.to_string()),
         };
         let body = Json(json!({
@@ -17,11 +17,12 @@ pub mod handler;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
-use api::v1::{greptime_server, BatchRequest, BatchResponse};
+use api::v1::{greptime_server, BatchRequest, BatchResponse, ExprHeader};
 use async_trait::async_trait;
 use common_runtime::Runtime;
 use common_telemetry::logging::info;
 use futures::FutureExt;
+use session::context::{QueryContext, QueryContextRef};
 use snafu::{ensure, ResultExt};
 use tokio::net::TcpListener;
 use tokio::sync::oneshot::{self, Sender};
@@ -33,12 +34,29 @@ use crate::error::{self, AlreadyStartedSnafu, Result, StartGrpcSnafu, TcpBindSna
 use crate::grpc::handler::BatchHandler;
 use crate::query_handler::{GrpcAdminHandlerRef, GrpcQueryHandlerRef};
 use crate::server::Server;
+use crate::tls::{self, TlsServerConfigRef};
+
+/// Creates the query context from the header of a gRPC request, empty catalog or schema in the
+/// header means the default one.
+pub fn query_context_from_header(header: Option<&ExprHeader>) -> QueryContextRef {
+    let query_ctx = QueryContext::new();
+    if let Some(header) = header {
+        if !header.catalog.is_empty() {
+            query_ctx.set_current_catalog(&header.catalog);
+        }
+        if !header.schema.is_empty() {
+            query_ctx.set_current_schema(&header.schema);
+        }
+    }
+    Arc::new(query_ctx)
+}
 
 pub struct GrpcServer {
     query_handler: GrpcQueryHandlerRef,
     admin_handler: GrpcAdminHandlerRef,
     shutdown_tx: Mutex<Option<Sender<()>>>,
     runtime: Arc<Runtime>,
+    tls_server_config: Option<TlsServerConfigRef>,
 }
 
 impl GrpcServer {
@@ -52,9 +70,19 @@ impl GrpcServer {
             admin_handler,
             shutdown_tx: Mutex::new(None),
             runtime,
+            tls_server_config: None,
         }
     }
 
+    /// Serves gRPC over TLS only if TLS is enabled.
+    pub fn set_tls_server_config(&mut self, tls_server_config: TlsServerConfigRef) {
+        debug_assert!(
+            self.tls_server_config.is_none(),
+            "TLS server config can be set only once!"
+        );
+        self.tls_server_config.get_or_insert(tls_server_config);
+    }
+
     pub fn create_service(&self) -> greptime_server::GreptimeServer<GrpcService> {
         let service = GrpcService {
             handler: BatchHandler::new(
@@ -123,13 +151,29 @@ impl Server for GrpcServer {
             .build()
             .context(error::GrpcReflectionServiceSnafu)?;
 
-        // Would block to serve requests.
-        tonic::transport::Server::builder()
+        let builder = tonic::transport::Server::builder()
             .add_service(self.create_service())
-            .add_service(reflection_service)
-            .serve_with_incoming_shutdown(TcpListenerStream::new(listener), rx.map(drop))
-            .await
-            .context(StartGrpcSnafu)?;
+            .add_service(reflection_service);
+        // Would block to serve requests.
+        let result = match self
+            .tls_server_config
+            .clone()
+            .filter(|tls_server_config| tls_server_config.enabled())
+        {
+            Some(tls_server_config) => {
+                let incoming =
+                    tls::tls_incoming(listener, tls_server_config, tls::GRPC_ALPN_PROTOCOLS);
+                builder
+                    .serve_with_incoming_shutdown(incoming, rx.map(drop))
+                    .await
+            }
+            None => {
+                builder
+                    .serve_with_incoming_shutdown(TcpListenerStream::new(listener), rx.map(drop))
+                    .await
+            }
+        };
+        result.context(StartGrpcSnafu)?;
 
         Ok(addr)
     }
@@ -12,11 +12,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod authorize;
 mod context;
 pub mod handler;
 pub mod influxdb;
 pub mod opentsdb;
 pub mod prometheus;
+pub mod promql;
 pub mod script;
 
 use std::net::SocketAddr;
@@ -26,6 +28,7 @@ use std::time::Duration;
 use aide::axum::{routing as apirouting, ApiRouter, IntoApiResponse};
 use aide::openapi::{Info, OpenApi, Server as OpenAPIServer};
 use async_trait::async_trait;
+use axum::body::BoxBody;
 use axum::error_handling::HandleErrorLayer;
 use axum::middleware::{self};
 use axum::response::{Html, Json};
@@ -37,23 +40,29 @@ use common_recordbatch::{util, RecordBatch};
 use common_telemetry::logging::info;
 use datatypes::data_type::DataType;
 use futures::FutureExt;
+use hyper::server::accept;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use snafu::{ensure, ResultExt};
+use tokio::net::TcpListener;
 use tokio::sync::oneshot::{self, Sender};
 use tokio::sync::Mutex;
 use tower::timeout::TimeoutLayer;
 use tower::ServiceBuilder;
+use tower_http::auth::AsyncRequireAuthorizationLayer;
 use tower_http::trace::TraceLayer;
 
+use self::authorize::HttpAuth;
 use self::influxdb::influxdb_write;
-use crate::error::{AlreadyStartedSnafu, Result, StartHttpSnafu};
+use crate::auth::UserProviderRef;
+use crate::error::{AlreadyStartedSnafu, Result, StartHttpSnafu, TcpBindSnafu};
 use crate::query_handler::{
     InfluxdbLineProtocolHandlerRef, OpentsdbProtocolHandlerRef, PrometheusProtocolHandlerRef,
-    ScriptHandlerRef, SqlQueryHandlerRef,
+    PromqlHandlerRef, ScriptHandlerRef, SqlQueryHandlerRef,
 };
 use crate::server::Server;
+use crate::tls::{self, TlsOption, TlsServerConfigRef};
 
 const HTTP_API_VERSION: &str = "v1";
 
@@ -63,7 +72,10 @@ pub struct HttpServer {
     influxdb_handler: Option<InfluxdbLineProtocolHandlerRef>,
     opentsdb_handler: Option<OpentsdbProtocolHandlerRef>,
     prom_handler: Option<PrometheusProtocolHandlerRef>,
+    promql_handler: Option<PromqlHandlerRef>,
     script_handler: Option<ScriptHandlerRef>,
+    user_provider: Option<UserProviderRef>,
+    tls_server_config: Option<TlsServerConfigRef>,
     shutdown_tx: Mutex<Option<Sender<()>>>,
 }
 
@@ -72,6 +84,8 @@ pub struct HttpOptions {
     pub addr: String,
     #[serde(with = "humantime_serde")]
     pub timeout: Duration,
+    #[serde(default)]
+    pub tls: TlsOption,
 }
 
 impl Default for HttpOptions {
@@ -79,6 +93,7 @@ impl Default for HttpOptions {
         Self {
             addr: "127.0.0.1:4000".to_string(),
             timeout: Duration::from_secs(30),
+            tls: TlsOption::default(),
         }
     }
 }
@@ -295,7 +310,10 @@ impl HttpServer {
             opentsdb_handler: None,
             influxdb_handler: None,
             prom_handler: None,
+            promql_handler: None,
             script_handler: None,
+            user_provider: None,
+            tls_server_config: None,
             shutdown_tx: Mutex::new(None),
         }
     }
@@ -332,6 +350,31 @@ impl HttpServer {
         self.prom_handler.get_or_insert(handler);
     }
 
+    pub fn set_promql_handler(&mut self, handler: PromqlHandlerRef) {
+        debug_assert!(
+            self.promql_handler.is_none(),
+            "PromQL handler can be set only once!"
+        );
+        self.promql_handler.get_or_insert(handler);
+    }
+
+    pub fn set_user_provider(&mut self, user_provider: UserProviderRef) {
+        debug_assert!(
+            self.user_provider.is_none(),
+            "User provider can be set only once!"
+        );
+        self.user_provider.get_or_insert(user_provider);
+    }
+
+    /// Serves HTTPS only if TLS is enabled.
+    pub fn set_tls_server_config(&mut self, tls_server_config: TlsServerConfigRef) {
+        debug_assert!(
+            self.tls_server_config.is_none(),
+            "TLS server config can be set only once!"
+        );
+        self.tls_server_config.get_or_insert(tls_server_config);
+    }
+
     pub fn make_app(&self) -> Router {
         let mut api = OpenApi {
             info: Info {
@@ -395,7 +438,35 @@ impl HttpServer {
             router = router.nest(&format!("/{}/prometheus", HTTP_API_VERSION), prom_router);
         }
 
-        router = router.route("/metrics", routing::get(handler::metrics));
+        if let Some(promql_handler) = self.promql_handler.clone() {
+            let promql_router = Router::with_state(promql_handler)
+                .route(
+                    "/query",
+                    routing::get(promql::instant_query).post(promql::instant_query),
+                )
+                .route(
+                    "/query_range",
+                    routing::get(promql::range_query).post(promql::range_query),
+                )
+                .route(
+                    "/labels",
+                    routing::get(promql::label_names).post(promql::label_names),
+                )
+                .route("/label/:name/values", routing::get(promql::label_values))
+                .route("/series", routing::get(promql::series).post(promql::series));
+
+            router = router.nest(
+                &format!("/{}/prometheus/api/v1", HTTP_API_VERSION),
+                promql_router,
+            );
+        }
+
+        // Only the metrics endpoint is exempted from authorization, to be scraped by Prometheus.
+        router = router
+            .layer(AsyncRequireAuthorizationLayer::new(
+                HttpAuth::<BoxBody>::new(self.user_provider.clone()),
+            ))
+            .route("/metrics", routing::get(handler::metrics));
 
         router
             // middlewares
@@ -426,6 +497,42 @@ impl Server for HttpServer {
 
     async fn start(&self, listening: SocketAddr) -> Result<SocketAddr> {
         let (tx, rx) = oneshot::channel();
+        let tls_server_config = self
+            .tls_server_config
+            .clone()
+            .filter(|tls_server_config| tls_server_config.enabled());
+        if let Some(tls_server_config) = tls_server_config {
+            let listener = {
+                let mut shutdown_tx = self.shutdown_tx.lock().await;
+                ensure!(
+                    shutdown_tx.is_none(),
+                    AlreadyStartedSnafu { server: "HTTP" }
+                );
+
+                let listener = TcpListener::bind(listening)
+                    .await
+                    .context(TcpBindSnafu { addr: listening })?;
+
+                *shutdown_tx = Some(tx);
+
+                listener
+            };
+            let listening = listener
+                .local_addr()
+                .context(TcpBindSnafu { addr: listening })?;
+            info!("HTTPS server is bound to {}", listening);
+
+            let incoming = tls::tls_incoming(listener, tls_server_config, tls::HTTP_ALPN_PROTOCOLS);
+            let app = self.make_app();
+            axum::Server::builder(accept::from_stream(incoming))
+                .serve(app.into_make_service())
+                .with_graceful_shutdown(rx.map(drop))
+                .await
+                .context(StartHttpSnafu)?;
+
+            return Ok(listening);
+        }
+
         let server = {
             let mut shutdown_tx = self.shutdown_tx.lock().await;
             ensure!(
@@ -471,6 +578,7 @@ mod test {
     use datatypes::prelude::*;
     use datatypes::schema::{ColumnSchema, Schema};
     use datatypes::vectors::{StringVector, UInt32Vector};
+    use session::context::QueryContextRef;
     use tokio::sync::mpsc;
 
     use super::*;
@@ -482,9 +590,13 @@ mod test {
 
     #[async_trait]
     impl SqlQueryHandler for DummyInstance {
-        async fn do_query(&self, _query: &str) -> Result<Output> {
+        async fn do_query(&self, _: &str, _: QueryContextRef) -> Result<Output> {
             unimplemented!()
         }
+
+        fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
+            Ok(true)
+        }
     }
 
     fn timeout() -> TimeoutLayer {
@@ -0,0 +1,241 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::marker::PhantomData;
+
+use axum::body::HttpBody;
+use axum::http::{self, Request, Response, StatusCode};
+use common_telemetry::error;
+use futures::future::BoxFuture;
+use snafu::OptionExt;
+use tower_http::auth::AsyncAuthorizeRequest;
+
+use crate::auth::{Identity, Password, UserProviderRef};
+use crate::context::{Channel, UserInfo};
+use crate::error::{self, Result};
+
+/// Authorizes HTTP requests with `Basic` credentials in the `Authorization` header, and puts
+/// the [UserInfo] of the request into its extensions. All requests are allowed if there is
+/// no user provider.
+pub struct HttpAuth<RespBody> {
+    user_provider: Option<UserProviderRef>,
+    _ty: PhantomData<RespBody>,
+}
+
+impl<RespBody> HttpAuth<RespBody> {
+    pub fn new(user_provider: Option<UserProviderRef>) -> Self {
+        Self {
+            user_provider,
+            _ty: PhantomData,
+        }
+    }
+}
+
+impl<RespBody> Clone for HttpAuth<RespBody> {
+    fn clone(&self) -> Self {
+        Self {
+            user_provider: self.user_provider.clone(),
+            _ty: PhantomData,
+        }
+    }
+}
+
+impl<B, RespBody> AsyncAuthorizeRequest<B> for HttpAuth<RespBody>
+where
+    B: Send + 'static,
+    RespBody: HttpBody + Default,
+{
+    type RequestBody = B;
+    type ResponseBody = RespBody;
+    type Future = BoxFuture<'static, std::result::Result<Request<B>, Response<RespBody>>>;
+
+    fn authorize(&mut self, mut request: Request<B>) -> Self::Future {
+        let user_provider = self.user_provider.clone();
+        // Parses the header before entering the future, so the request needn't be `Sync`.
+        let credential = auth_header(&request);
+        Box::pin(async move {
+            let user_provider = match user_provider {
+                Some(user_provider) => user_provider,
+                None => return Ok(request),
+            };
+            match authenticate(&user_provider, credential).await {
+                Ok(user_info) => {
+                    request.extensions_mut().insert(user_info);
+                    Ok(request)
+                }
+                Err(e) => {
+                    error!(e; "Failed to authorize http request");
+                    Err(unauthorized_resp())
+                }
+            }
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Credential {
+    Basic { username: String, password: String },
+}
+
+async fn authenticate(
+    user_provider: &UserProviderRef,
+    credential: Result<Credential>,
+) -> Result<UserInfo> {
+    match credential? {
+        Credential::Basic { username, password } => {
+            user_provider
+                .auth(
+                    Identity::UserId(&username, None),
+                    Password::PlainText(&password),
+                    Channel::HTTP,
+                )
+                .await
+        }
+    }
+}
+
+fn auth_header<B>(request: &Request<B>) -> Result<Credential> {
+    let header = request
+        .headers()
+        .get(http::header::AUTHORIZATION)
+        .context(error::AuthHeaderNotFoundSnafu)?
+        .to_str()
+        .ok()
+        .context(error::InvalidAuthHeaderSnafu {
+            msg: "header is not valid utf-8",
+        })?;
+    let (scheme, content) = header
+        .split_once(' ')
+        .context(error::InvalidAuthHeaderSnafu {
+            msg: "expect <scheme> <credential>",
+        })?;
+
+    match scheme.to_lowercase().as_str() {
+        "basic" => {
+            let decoded = base64::decode(content.trim())
+                .ok()
+                .and_then(|decoded| String::from_utf8(decoded).ok())
+                .context(error::InvalidAuthHeaderSnafu {
+                    msg: "invalid base64 credential",
+                })?;
+            let (username, password) =
+                decoded
+                    .split_once(':')
+                    .context(error::InvalidAuthHeaderSnafu {
+                        msg: "expect <username>:<password>",
+                    })?;
+            Ok(Credential::Basic {
+                username: username.to_string(),
+                password: password.to_string(),
+            })
+        }
+        _ => error::InvalidAuthHeaderSnafu {
+            msg: format!("unsupported scheme {}", scheme),
+        }
+        .fail(),
+    }
+}
+
+fn unauthorized_resp<RespBody: Default>() -> Response<RespBody> {
+    let mut response = Response::new(RespBody::default());
+    *response.status_mut() = StatusCode::UNAUTHORIZED;
+    response.headers_mut().insert(
+        http::header::WWW_AUTHENTICATE,
+        http::HeaderValue::from_static("Basic realm=\"greptime\""),
+    );
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::BoxBody;
+    use hyper::Body;
+
+    use super::*;
+    use crate::auth::user_provider_from_option;
+    use crate::context::AuthMethod;
+
+    fn request(authorization: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri("/v1/sql");
+        if let Some(authorization) = authorization {
+            builder = builder.header(http::header::AUTHORIZATION, authorization);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    #[test]
+    fn test_auth_header() {
+        // "root:123456"
+        assert_eq!(
+            Credential::Basic {
+                username: "root".to_string(),
+                password: "123456".to_string(),
+            },
+            auth_header(&request(Some("Basic cm9vdDoxMjM0NTY="))).unwrap()
+        );
+
+        assert!(matches!(
+            auth_header(&request(None)),
+            Err(error::Error::AuthHeaderNotFound { .. })
+        ));
+        for invalid in [
+            "Basic",
+            "Basic !!!",
+            "Basic cm9vdA==",
+            "Bearer token",
+            "Digest foo",
+        ] {
+            assert!(matches!(
+                auth_header(&request(Some(invalid))),
+                Err(error::Error::InvalidAuthHeader { .. })
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_http_auth() {
+        let mut http_auth = HttpAuth::<BoxBody>::new(None);
+        assert!(http_auth.authorize(request(None)).await.is_ok());
+
+        let user_provider =
+            user_provider_from_option("static_user_provider:cmd:root=123456").unwrap();
+        let mut http_auth = HttpAuth::<BoxBody>::new(Some(user_provider));
+
+        let authorized = http_auth
+            .authorize(request(Some("Basic cm9vdDoxMjM0NTY=")))
+            .await
+            .unwrap();
+        let expected = UserInfo {
+            username: Some("root".to_string()),
+            from_channel: Channel::HTTP,
+            auth_method: AuthMethod::PlainText,
+        };
+        assert_eq!(Some(&expected), authorized.extensions().get::<UserInfo>());
+
+        // "root:654321"
+        let response = http_auth
+            .authorize(request(Some("Basic cm9vdDo2NTQzMjE=")))
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        let response = http_auth.authorize(request(None)).await.unwrap_err();
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+        // Bearer tokens are not supported.
+        let response = http_auth
+            .authorize(request(Some("Bearer token")))
+            .await
+            .unwrap_err();
+        assert_eq!(StatusCode::UNAUTHORIZED, response.status());
+    }
+}
@@ -13,15 +13,20 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Instant;
 
 use aide::transform::TransformOperation;
 use axum::extract::{Json, Query, State};
+use axum::Extension;
+use common_error::prelude::ErrorExt;
 use common_error::status_code::StatusCode;
 use common_telemetry::metric;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use session::context::QueryContext;
 
+use crate::context::UserInfo;
 use crate::http::{ApiState, JsonResponse};
 
 #[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
@@ -35,11 +40,33 @@ pub struct SqlQuery {
 pub async fn sql(
     State(state): State<ApiState>,
     Query(params): Query<SqlQuery>,
+    user_info: Option<Extension<UserInfo>>,
 ) -> Json<JsonResponse> {
     let sql_handler = &state.sql_handler;
     let start = Instant::now();
     let resp = if let Some(sql) = &params.sql {
-        JsonResponse::from_output(sql_handler.do_query(sql).await).await
+        // Every request has its own context, `database` selects the schema to query in.
+        let query_ctx = Arc::new(QueryContext::new());
+        // The user authorized by `HttpAuth`, absent if there is no user provider.
+        if let Some(username) = user_info.and_then(|Extension(user_info)| user_info.username) {
+            query_ctx.set_user(username);
+        }
+        match &params.database {
+            Some(database) => {
+                match sql_handler.is_valid_schema(&query_ctx.current_catalog(), database) {
+                    Ok(true) => {
+                        query_ctx.set_current_schema(database);
+                        JsonResponse::from_output(sql_handler.do_query(sql, query_ctx).await).await
+                    }
+                    Ok(false) => JsonResponse::with_error(
+                        format!("Database not found: {}", database),
+                        StatusCode::DatabaseNotFound,
+                    ),
+                    Err(e) => JsonResponse::with_error(e.to_string(), e.status_code()),
+                }
+            }
+            None => JsonResponse::from_output(sql_handler.do_query(sql, query_ctx).await).await,
+        }
     } else {
         JsonResponse::with_error(
             "sql parameter is required.".to_string(),
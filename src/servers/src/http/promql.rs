@@ -0,0 +1,377 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Handlers of the [Prometheus HTTP API](https://prometheus.io/docs/prometheus/latest/querying/api/).
+
+use std::str::FromStr;
+
+use axum::extract::{Path, RawBody, RawQuery, State};
+use axum::http::StatusCode as HttpStatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use common_catalog::consts::DEFAULT_SCHEMA_NAME;
+use common_error::prelude::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_time::timestamp::TimeUnit;
+use common_time::util::current_time_millis;
+use common_time::Timestamp;
+use promql::value::{format_value, Sample, Value};
+use promql::EvalRange;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value as JsonValue};
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{self, Error, Result};
+use crate::query_handler::PromqlHandlerRef;
+
+/// Response in the format of the Prometheus HTTP API.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PromqlJsonResponse {
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data: Option<JsonValue>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl PromqlJsonResponse {
+    fn success(data: JsonValue) -> Self {
+        Self {
+            status: "success".to_string(),
+            data: Some(data),
+            error_type: None,
+            error: None,
+        }
+    }
+
+    fn from_result<T: Serialize>(result: Result<T>) -> Response {
+        match result {
+            Ok(data) => Json(Self::success(json!(data))).into_response(),
+            Err(e) => Self::from_error(e),
+        }
+    }
+
+    fn from_error(e: Error) -> Response {
+        let (status, error_type) = match e.status_code() {
+            StatusCode::InvalidArguments | StatusCode::InvalidSyntax | StatusCode::Unsupported => {
+                (HttpStatusCode::BAD_REQUEST, "bad_data")
+            }
+            StatusCode::Internal | StatusCode::Unexpected | StatusCode::Unknown => {
+                (HttpStatusCode::INTERNAL_SERVER_ERROR, "internal")
+            }
+            _ => (HttpStatusCode::UNPROCESSABLE_ENTITY, "execution"),
+        };
+        let body = Self {
+            status: "error".to_string(),
+            data: None,
+            error_type: Some(error_type.to_string()),
+            error: Some(e.to_string()),
+        };
+        (status, Json(body)).into_response()
+    }
+}
+
+/// Parameters of a request, collected from both the query string and the form body.
+#[derive(Debug, Default)]
+struct Params(Vec<(String, String)>);
+
+impl Params {
+    async fn new(query: Option<String>, body: hyper::Body) -> Result<Self> {
+        let mut params: Vec<(String, String)> = query
+            .map(|query| {
+                form_urlencoded::parse(query.as_bytes())
+                    .into_owned()
+                    .collect()
+            })
+            .unwrap_or_default();
+        let body = hyper::body::to_bytes(body)
+            .await
+            .context(error::HyperSnafu)?;
+        params.extend(form_urlencoded::parse(&body).into_owned());
+        Ok(Self(params))
+    }
+
+    fn get(&self, name: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn get_required(&self, name: &str) -> Result<&str> {
+        self.get(name).context(error::InvalidPromqlParamSnafu {
+            name,
+            msg: "parameter is required",
+        })
+    }
+
+    fn get_all(&self, name: &str) -> Vec<String> {
+        self.0
+            .iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+
+    fn database(&self) -> &str {
+        self.get("db").unwrap_or(DEFAULT_SCHEMA_NAME)
+    }
+
+    fn time_or(&self, name: &str, default: i64) -> Result<i64> {
+        self.get(name)
+            .map(|value| parse_time(name, value))
+            .unwrap_or(Ok(default))
+    }
+}
+
+/// Parses a timestamp in RFC3339 format or in (fractional) seconds into milliseconds.
+fn parse_time(name: &str, value: &str) -> Result<i64> {
+    if let Ok(secs) = value.parse::<f64>() {
+        if secs.is_finite() {
+            return Ok((secs * 1000.0).round() as i64);
+        }
+    }
+    Timestamp::from_str(value)
+        .map(|ts| ts.convert_to(TimeUnit::Millisecond))
+        .ok()
+        .context(error::InvalidPromqlParamSnafu {
+            name,
+            msg: format!("cannot parse \"{}\" to a valid timestamp", value),
+        })
+}
+
+/// Parses a duration like `5m` or in (fractional) seconds into milliseconds.
+fn parse_duration(name: &str, value: &str) -> Result<i64> {
+    if let Ok(secs) = value.parse::<f64>() {
+        if secs.is_finite() {
+            return Ok((secs * 1000.0).round() as i64);
+        }
+    }
+    promql::parse_duration(value)
+        .ok()
+        .context(error::InvalidPromqlParamSnafu {
+            name,
+            msg: format!("cannot parse \"{}\" to a valid duration", value),
+        })
+}
+
+/// Converts the sample to `[<unix seconds>, "<value>"]` as Prometheus does.
+fn sample_to_json(sample: &Sample) -> JsonValue {
+    json!([sample.timestamp as f64 / 1000.0, format_value(sample.value)])
+}
+
+fn value_to_json(value: Value) -> JsonValue {
+    match value {
+        Value::Scalar(sample) => json!({
+            "resultType": "scalar",
+            "result": sample_to_json(&sample),
+        }),
+        Value::String(timestamp, s) => json!({
+            "resultType": "string",
+            "result": [timestamp as f64 / 1000.0, s],
+        }),
+        Value::Vector(elements) => json!({
+            "resultType": "vector",
+            "result": elements
+                .iter()
+                .map(|e| json!({
+                    "metric": e.labels,
+                    "value": sample_to_json(&e.sample),
+                }))
+                .collect::<Vec<_>>(),
+        }),
+        Value::Matrix(series) => json!({
+            "resultType": "matrix",
+            "result": series
+                .iter()
+                .map(|s| json!({
+                    "metric": s.labels,
+                    "values": s.samples.iter().map(sample_to_json).collect::<Vec<_>>(),
+                }))
+                .collect::<Vec<_>>(),
+        }),
+    }
+}
+
+#[axum_macros::debug_handler]
+pub async fn instant_query(
+    State(handler): State<PromqlHandlerRef>,
+    RawQuery(query): RawQuery,
+    RawBody(body): RawBody,
+) -> Response {
+    let result = async {
+        let params = Params::new(query, body).await?;
+        let query = params.get_required("query")?;
+        let time = params.time_or("time", current_time_millis())?;
+        let value = handler.query(params.database(), query, time).await?;
+        Ok::<_, Error>(value_to_json(value))
+    }
+    .await;
+    PromqlJsonResponse::from_result(result)
+}
+
+#[axum_macros::debug_handler]
+pub async fn range_query(
+    State(handler): State<PromqlHandlerRef>,
+    RawQuery(query): RawQuery,
+    RawBody(body): RawBody,
+) -> Response {
+    let result = async {
+        let params = Params::new(query, body).await?;
+        let query = params.get_required("query")?;
+        let start = parse_time("start", params.get_required("start")?)?;
+        let end = parse_time("end", params.get_required("end")?)?;
+        let step = parse_duration("step", params.get_required("step")?)?;
+        let range = EvalRange::new(start, end, step).context(error::ExecutePromqlSnafu)?;
+        let value = handler.query_range(params.database(), query, range).await?;
+        Ok::<_, Error>(value_to_json(value))
+    }
+    .await;
+    PromqlJsonResponse::from_result(result)
+}
+
+#[axum_macros::debug_handler]
+pub async fn label_names(
+    State(handler): State<PromqlHandlerRef>,
+    RawQuery(query): RawQuery,
+    RawBody(body): RawBody,
+) -> Response {
+    let result = async {
+        let params = Params::new(query, body).await?;
+        let start = params.time_or("start", i64::MIN)?;
+        let end = params.time_or("end", i64::MAX)?;
+        handler
+            .label_names(params.database(), &params.get_all("match[]"), start, end)
+            .await
+    }
+    .await;
+    PromqlJsonResponse::from_result(result)
+}
+
+#[axum_macros::debug_handler]
+pub async fn label_values(
+    State(handler): State<PromqlHandlerRef>,
+    Path(name): Path<String>,
+    RawQuery(query): RawQuery,
+    RawBody(body): RawBody,
+) -> Response {
+    let result = async {
+        let params = Params::new(query, body).await?;
+        let start = params.time_or("start", i64::MIN)?;
+        let end = params.time_or("end", i64::MAX)?;
+        handler
+            .label_values(
+                params.database(),
+                &name,
+                &params.get_all("match[]"),
+                start,
+                end,
+            )
+            .await
+    }
+    .await;
+    PromqlJsonResponse::from_result(result)
+}
+
+#[axum_macros::debug_handler]
+pub async fn series(
+    State(handler): State<PromqlHandlerRef>,
+    RawQuery(query): RawQuery,
+    RawBody(body): RawBody,
+) -> Response {
+    let result = async {
+        let params = Params::new(query, body).await?;
+        let matches = params.get_all("match[]");
+        if matches.is_empty() {
+            return error::InvalidPromqlParamSnafu {
+                name: "match[]",
+                msg: "no match[] parameter provided",
+            }
+            .fail();
+        }
+        let start = params.time_or("start", i64::MIN)?;
+        let end = params.time_or("end", i64::MAX)?;
+        handler
+            .series(params.database(), &matches, start, end)
+            .await
+    }
+    .await;
+    PromqlJsonResponse::from_result(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use promql::value::{Labels, Series, VectorElement};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_time() {
+        assert_eq!(1_500_000, parse_time("time", "1500").unwrap());
+        assert_eq!(1_500_123, parse_time("time", "1500.123").unwrap());
+        assert_eq!(
+            1_666_000_000_000,
+            parse_time("time", "2022-10-17T09:46:40Z").unwrap()
+        );
+        assert!(parse_time("time", "NaN").is_err());
+        assert!(parse_time("time", "foo").is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(15_000, parse_duration("step", "15").unwrap());
+        assert_eq!(500, parse_duration("step", "0.5").unwrap());
+        assert_eq!(90_000, parse_duration("step", "1m30s").unwrap());
+        assert!(parse_duration("step", "1x").is_err());
+    }
+
+    #[test]
+    fn test_value_to_json() {
+        let labels: Labels = [("__name__", "up"), ("job", "node")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+
+        assert_eq!(
+            json!({"resultType": "scalar", "result": [1.5, "2"]}),
+            value_to_json(Value::Scalar(Sample::new(1500, 2.0)))
+        );
+        assert_eq!(
+            json!({
+                "resultType": "vector",
+                "result": [{"metric": {"__name__": "up", "job": "node"}, "value": [1.0, "+Inf"]}],
+            }),
+            value_to_json(Value::Vector(vec![VectorElement {
+                labels: labels.clone(),
+                sample: Sample::new(1000, f64::INFINITY),
+            }]))
+        );
+        assert_eq!(
+            json!({
+                "resultType": "matrix",
+                "result": [{
+                    "metric": {"__name__": "up", "job": "node"},
+                    "values": [[1.0, "1"], [2.0, "0.5"]],
+                }],
+            }),
+            value_to_json(Value::Matrix(vec![Series {
+                labels,
+                samples: vec![Sample::new(1000, 1.0), Sample::new(2000, 0.5)],
+            }]))
+        );
+    }
+}
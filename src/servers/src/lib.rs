@@ -16,6 +16,7 @@
 
 use serde::{Deserialize, Serialize};
 
+pub mod auth;
 pub mod context;
 pub mod error;
 pub mod grpc;
@@ -25,10 +26,12 @@ pub mod line_writer;
 pub mod mysql;
 pub mod opentsdb;
 pub mod postgres;
+pub mod prepared;
 pub mod prometheus;
 pub mod query_handler;
 pub mod server;
 mod shutdown;
+pub mod tls;
 
 #[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
 #[serde(rename_all = "lowercase")]
@@ -140,6 +140,7 @@ impl LineWriter {
             schema_name: self.db,
             table_name: self.table_name,
             columns_values,
+            region_number: 0,
         }
     }
 }
@@ -12,23 +12,30 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 
 use async_trait::async_trait;
+use common_query::Output;
 use common_telemetry::{debug, error};
 use opensrv_mysql::{
-    AsyncMysqlShim, ErrorKind, ParamParser, QueryResultWriter, StatementMetaWriter,
+    AsyncMysqlShim, Column, ColumnFlags, ColumnType, ErrorKind, InitWriter, ParamParser,
+    QueryResultWriter, StatementMetaWriter,
 };
 use rand::RngCore;
+use session::{Session, SessionRef};
 use tokio::io::AsyncWrite;
 use tokio::sync::RwLock;
 
+use crate::auth::{Identity, Password, UserProviderRef};
 use crate::context::AuthHashMethod::DoubleSha1;
 use crate::context::Channel::MYSQL;
 use crate::context::{AuthMethod, Context, CtxBuilder};
 use crate::error::{self, Result};
-use crate::mysql::writer::MysqlResultWriter;
+use crate::mysql::helper;
+use crate::mysql::writer::{self, MysqlResultWriter};
+use crate::prepared::PreparedStatement;
 use crate::query_handler::SqlQueryHandlerRef;
 
 // An intermediate shim for executing MySQL queries.
@@ -37,10 +44,19 @@ pub struct MysqlInstanceShim {
     salt: [u8; 20],
     client_addr: String,
     ctx: Arc<RwLock<Option<Context>>>,
+    session: SessionRef,
+    user_provider: Option<UserProviderRef>,
+    // Prepared statements of this connection, keyed by statement id.
+    prepared_stmts: HashMap<u32, PreparedStatement>,
+    prepared_stmts_counter: u32,
 }
 
 impl MysqlInstanceShim {
-    pub fn create(query_handler: SqlQueryHandlerRef, client_addr: String) -> MysqlInstanceShim {
+    pub fn create(
+        query_handler: SqlQueryHandlerRef,
+        client_addr: String,
+        user_provider: Option<UserProviderRef>,
+    ) -> MysqlInstanceShim {
         // init a random salt
         let mut bs = vec![0u8; 20];
         let mut rng = rand::thread_rng();
@@ -57,23 +73,96 @@ impl MysqlInstanceShim {
         MysqlInstanceShim {
             query_handler,
             salt: scramble,
+            session: Arc::new(Session::new(client_addr.clone())),
             client_addr,
             ctx: Arc::new(RwLock::new(None)),
+            user_provider,
+            prepared_stmts: HashMap::new(),
+            prepared_stmts_counter: 0,
         }
     }
+
+    async fn do_query(&self, query: &str) -> Result<Output> {
+        debug!("Start executing query: '{}'", query);
+        let start = Instant::now();
+
+        // TODO(LFC): Find a better way:
+        // `check` uses regex to filter out unsupported statements emitted by MySQL's federated
+        // components, this is quick and dirty, there must be a better way to do it.
+        let output = if let Some(output) = crate::mysql::federated::check(query) {
+            Ok(output)
+        } else {
+            self.query_handler
+                .do_query(query, self.session.context())
+                .await
+        };
+
+        debug!(
+            "Finished executing query: '{}', total time costs in microseconds: {}",
+            query,
+            start.elapsed().as_micros()
+        );
+        output
+    }
+
+    /// Parses the statement and describes its parameters and result columns. Placeholders
+    /// are filled with `NULL`s to describe the result columns, which is enough for planning
+    /// the query.
+    async fn prepare_stmt(
+        &self,
+        query: &str,
+    ) -> Result<(PreparedStatement, Vec<Column>, Vec<Column>)> {
+        let stmt = PreparedStatement::parse(query)?;
+        let param_types = stmt
+            .infer_param_types(&self.query_handler, self.session.context())
+            .await?;
+        // Clients send parameters in whatever types they have, and the values are converted
+        // to SQL literals when executing, so parameters whose types can't be inferred are
+        // simply described as strings.
+        let params = param_types
+            .iter()
+            .map(|data_type| {
+                let coltype = match data_type {
+                    Some(data_type) => writer::mysql_column_type(data_type)?,
+                    None => ColumnType::MYSQL_TYPE_VAR_STRING,
+                };
+                Ok(Column {
+                    table: "".to_string(),
+                    column: "?".to_string(),
+                    coltype,
+                    colflags: ColumnFlags::empty(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let schema = self
+            .query_handler
+            .do_describe(&stmt.bind_nulls(), self.session.context())
+            .await?;
+        let columns = match schema {
+            Some(schema) => writer::create_mysql_column_def(&schema)?,
+            None => vec![],
+        };
+        Ok((stmt, params, columns))
+    }
 }
 
 #[async_trait]
 impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShim {
     type Error = error::Error;
 
+    // The MySQL client kills the running query by `KILL QUERY <connect_id>` on Ctrl-C.
+    fn connect_id(&self) -> u32 {
+        self.session.connection_id()
+    }
+
     fn salt(&self) -> [u8; 20] {
         self.salt
     }
 
     async fn authenticate(
         &self,
-        _auth_plugin: &str,
+        auth_plugin: &str,
         username: &[u8],
         salt: &[u8],
         auth_data: &[u8],
@@ -81,6 +170,24 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         // if not specified then **root** will be used
         let username = String::from_utf8_lossy(username);
         let client_addr = self.client_addr.clone();
+
+        if let Some(user_provider) = &self.user_provider {
+            if auth_plugin != "mysql_native_password" {
+                let e = error::UnsupportedPasswordTypeSnafu {
+                    password_type: auth_plugin,
+                }
+                .build();
+                error!(e; "Failed to authenticate MySQL user {}", username);
+                return false;
+            }
+            let id = Identity::UserId(&username, Some(&client_addr));
+            let password = Password::MysqlNativePassword(auth_data, salt);
+            if let Err(e) = user_provider.auth(id, password, MYSQL).await {
+                error!(e; "Failed to authenticate MySQL user {}", username);
+                return false;
+            }
+        }
+
         let auth_method = match auth_data.len() {
             0 => AuthMethod::None,
             _ => AuthMethod::Password {
@@ -98,6 +205,7 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
             .build()
         {
             Ok(ctx) => {
+                self.session.set_user(username);
                 let mut a = self.ctx.write().await;
                 *a = Some(ctx);
                 true
@@ -109,34 +217,72 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         };
     }
 
-    async fn on_prepare<'a>(&'a mut self, _: &'a str, w: StatementMetaWriter<'a, W>) -> Result<()> {
-        w.error(
-            ErrorKind::ER_UNKNOWN_ERROR,
-            b"prepare statement is not supported yet",
-        )
-        .await?;
+    async fn on_prepare<'a>(
+        &'a mut self,
+        query: &'a str,
+        w: StatementMetaWriter<'a, W>,
+    ) -> Result<()> {
+        let (stmt, params, columns) = match self.prepare_stmt(query).await {
+            Ok(prepared) => prepared,
+            Err(e) => {
+                error!(e; "Failed to prepare statement '{}'", query);
+                w.error(ErrorKind::ER_UNKNOWN_ERROR, e.to_string().as_bytes())
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        self.prepared_stmts_counter = self.prepared_stmts_counter.wrapping_add(1);
+        let stmt_id = self.prepared_stmts_counter;
+        w.reply(stmt_id, &params, &columns).await?;
+
+        debug!("Prepared statement {}: '{}'", stmt_id, query);
+        let _ = self.prepared_stmts.insert(stmt_id, stmt);
         Ok(())
     }
 
     async fn on_execute<'a>(
         &'a mut self,
-        _: u32,
-        _: ParamParser<'a>,
+        stmt_id: u32,
+        p: ParamParser<'a>,
         w: QueryResultWriter<'a, W>,
     ) -> Result<()> {
-        w.error(
-            ErrorKind::ER_UNKNOWN_ERROR,
-            b"prepare statement is not supported yet",
-        )
-        .await?;
-        Ok(())
+        let stmt = match self.prepared_stmts.get(&stmt_id) {
+            Some(stmt) => stmt,
+            None => {
+                w.error(
+                    ErrorKind::ER_UNKNOWN_STMT_HANDLER,
+                    format!("Unknown prepared statement handler ({})", stmt_id).as_bytes(),
+                )
+                .await?;
+                return Ok(());
+            }
+        };
+
+        let query = p
+            .into_iter()
+            .map(helper::format_param)
+            .collect::<Result<Vec<_>>>()
+            .and_then(|params| stmt.bind(&params));
+        let query = match query {
+            Ok(query) => query,
+            Err(e) => {
+                w.error(ErrorKind::ER_WRONG_ARGUMENTS, e.to_string().as_bytes())
+                    .await?;
+                return Ok(());
+            }
+        };
+
+        let output = self.do_query(&query).await;
+        let mut writer = MysqlResultWriter::new(w);
+        writer.write(&query, output).await
     }
 
-    async fn on_close<'a>(&'a mut self, _stmt_id: u32)
+    async fn on_close<'a>(&'a mut self, stmt_id: u32)
     where
         W: 'async_trait,
     {
-        // do nothing because we haven't implemented prepare statement
+        let _ = self.prepared_stmts.remove(&stmt_id);
     }
 
     async fn on_query<'a>(
@@ -144,25 +290,31 @@ impl<W: AsyncWrite + Send + Sync + Unpin> AsyncMysqlShim<W> for MysqlInstanceShi
         query: &'a str,
         writer: QueryResultWriter<'a, W>,
     ) -> Result<()> {
-        debug!("Start executing query: '{}'", query);
-        let start = Instant::now();
-
-        // TODO(LFC): Find a better way:
-        // `check` uses regex to filter out unsupported statements emitted by MySQL's federated
-        // components, this is quick and dirty, there must be a better way to do it.
-        let output = if let Some(output) = crate::mysql::federated::check(query) {
-            Ok(output)
-        } else {
-            self.query_handler.do_query(query).await
-        };
-
-        debug!(
-            "Finished executing query: '{}', total time costs in microseconds: {}",
-            query,
-            start.elapsed().as_micros()
-        );
-
+        let output = self.do_query(query).await;
         let mut writer = MysqlResultWriter::new(writer);
         writer.write(query, output).await
     }
+
+    async fn on_init<'a>(&'a mut self, database: &'a str, w: InitWriter<'a, W>) -> Result<()> {
+        let query_ctx = self.session.context();
+        let catalog = query_ctx.current_catalog();
+        match self.query_handler.is_valid_schema(&catalog, database) {
+            Ok(true) => {
+                query_ctx.set_current_schema(database);
+                w.ok().await?;
+            }
+            Ok(false) => {
+                w.error(
+                    ErrorKind::ER_BAD_DB_ERROR,
+                    format!("Unknown database '{}'", database).as_bytes(),
+                )
+                .await?;
+            }
+            Err(e) => {
+                w.error(ErrorKind::ER_UNKNOWN_ERROR, e.to_string().as_bytes())
+                    .await?;
+            }
+        }
+        Ok(())
+    }
 }
@@ -0,0 +1,138 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use opensrv_mysql::{ParamValue, ValueInner};
+use snafu::OptionExt;
+
+use crate::error::{self, Result};
+use crate::prepared::{hex_literal, quote_string};
+
+/// Converts a parameter sent by `COM_STMT_EXECUTE` to a SQL literal.
+pub fn format_param(param: ParamValue) -> Result<String> {
+    let literal = match param.value.into_inner() {
+        ValueInner::NULL => "NULL".to_string(),
+        // Binary data is bound as a hex literal instead of being mangled into a string.
+        ValueInner::Bytes(b) => match std::str::from_utf8(b) {
+            Ok(s) => quote_string(s),
+            Err(_) => hex_literal(b),
+        },
+        ValueInner::Int(v) => v.to_string(),
+        ValueInner::UInt(v) => v.to_string(),
+        ValueInner::Double(v) => {
+            // `NaN` and `inf` are not numeric literals of SQL.
+            if !v.is_finite() {
+                return error::InvalidPrepareStatementSnafu {
+                    err_msg: format!("invalid double parameter: {}", v),
+                }
+                .fail();
+            }
+            v.to_string()
+        }
+        ValueInner::Date(b) | ValueInner::Datetime(b) => quote_string(&format_datetime(b)?),
+        ValueInner::Time(b) => quote_string(&format_time(b)?),
+    };
+    Ok(literal)
+}
+
+/// Formats a binary encoded MySQL `DATE`/`DATETIME` value, see
+/// <https://dev.mysql.com/doc/internals/en/binary-protocol-value.html>.
+fn format_datetime(b: &[u8]) -> Result<String> {
+    let (year, month, day) = match b.len() {
+        0 => (0, 0, 0),
+        4 | 7 | 11 => (u16::from_le_bytes([b[0], b[1]]), b[2], b[3]),
+        len => {
+            return error::InvalidPrepareStatementSnafu {
+                err_msg: format!("invalid datetime parameter length: {}", len),
+            }
+            .fail()
+        }
+    };
+    let (hour, minute, second) = if b.len() >= 7 {
+        (b[4], b[5], b[6])
+    } else {
+        (0, 0, 0)
+    };
+
+    let mut s = format!(
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        year, month, day, hour, minute, second
+    );
+    if b.len() == 11 {
+        let micros = u32::from_le_bytes([b[7], b[8], b[9], b[10]]);
+        s.push_str(&format!(".{:06}", micros));
+    }
+    Ok(s)
+}
+
+/// Formats a binary encoded MySQL `TIME` value.
+fn format_time(b: &[u8]) -> Result<String> {
+    if b.is_empty() {
+        return Ok("00:00:00".to_string());
+    }
+    if b.len() != 8 && b.len() != 12 {
+        return error::InvalidPrepareStatementSnafu {
+            err_msg: format!("invalid time parameter length: {}", b.len()),
+        }
+        .fail();
+    }
+
+    let negative = b[0] == 1;
+    let days = u32::from_le_bytes([b[1], b[2], b[3], b[4]]);
+    let hours = days
+        .checked_mul(24)
+        .and_then(|hours| hours.checked_add(b[5] as u32))
+        .with_context(|| error::InvalidPrepareStatementSnafu {
+            err_msg: format!("time parameter out of range: {} days", days),
+        })?;
+    let mut s = format!(
+        "{}{:02}:{:02}:{:02}",
+        if negative { "-" } else { "" },
+        hours,
+        b[6],
+        b[7]
+    );
+    if b.len() == 12 {
+        let micros = u32::from_le_bytes([b[8], b[9], b[10], b[11]]);
+        s.push_str(&format!(".{:06}", micros));
+    }
+    Ok(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_datetime() {
+        assert_eq!("0000-00-00 00:00:00", format_datetime(&[]).unwrap());
+        assert_eq!(
+            "2022-11-05 00:00:00",
+            format_datetime(&[0xe6, 0x07, 11, 5]).unwrap()
+        );
+        assert_eq!(
+            "2022-11-05 12:30:01",
+            format_datetime(&[0xe6, 0x07, 11, 5, 12, 30, 1]).unwrap()
+        );
+        assert_eq!(
+            "2022-11-05 12:30:01.000123",
+            format_datetime(&[0xe6, 0x07, 11, 5, 12, 30, 1, 123, 0, 0, 0]).unwrap()
+        );
+        assert!(format_datetime(&[1, 2]).is_err());
+
+        assert_eq!("00:00:00", format_time(&[]).unwrap());
+        assert_eq!("-25:02:03", format_time(&[1, 1, 0, 0, 0, 1, 2, 3]).unwrap());
+        assert!(format_time(&[1, 2]).is_err());
+        assert!(format_time(&[0, 0xff, 0xff, 0xff, 0xff, 0, 0, 0]).is_err());
+    }
+}
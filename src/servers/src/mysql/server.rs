@@ -20,15 +20,20 @@ use async_trait::async_trait;
 use common_runtime::Runtime;
 use common_telemetry::logging::{error, info};
 use futures::StreamExt;
-use opensrv_mysql::AsyncMysqlIntermediary;
+use opensrv_mysql::{
+    plain_run_with_options, secure_run_with_options, AsyncMysqlIntermediary, IntermediaryOptions,
+};
+use snafu::ensure;
 use tokio;
 use tokio::io::BufWriter;
 use tokio::net::TcpStream;
 
-use crate::error::Result;
+use crate::auth::UserProviderRef;
+use crate::error::{Result, TlsRequiredSnafu};
 use crate::mysql::handler::MysqlInstanceShim;
 use crate::query_handler::SqlQueryHandlerRef;
 use crate::server::{AbortableStream, BaseTcpServer, Server};
+use crate::tls::TlsServerConfigRef;
 
 // Default size of ResultSet write buffer: 100KB
 const DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE: usize = 100 * 1024;
@@ -36,16 +41,26 @@ const DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE: usize = 100 * 1024;
 pub struct MysqlServer {
     base_server: BaseTcpServer,
     query_handler: SqlQueryHandlerRef,
+    spawn_config: Arc<MysqlSpawnConfig>,
+}
+
+/// Settings shared by all connections of a server.
+struct MysqlSpawnConfig {
+    tls: TlsServerConfigRef,
+    user_provider: Option<UserProviderRef>,
 }
 
 impl MysqlServer {
     pub fn create_server(
         query_handler: SqlQueryHandlerRef,
         io_runtime: Arc<Runtime>,
+        tls: TlsServerConfigRef,
+        user_provider: Option<UserProviderRef>,
     ) -> Box<dyn Server> {
         Box::new(MysqlServer {
             base_server: BaseTcpServer::create_server("MySQL", io_runtime),
             query_handler,
+            spawn_config: Arc::new(MysqlSpawnConfig { tls, user_provider }),
         })
     }
 
@@ -55,14 +70,17 @@ impl MysqlServer {
         stream: AbortableStream,
     ) -> impl Future<Output = ()> {
         let query_handler = self.query_handler.clone();
+        let spawn_config = self.spawn_config.clone();
         stream.for_each(move |tcp_stream| {
             let io_runtime = io_runtime.clone();
             let query_handler = query_handler.clone();
+            let spawn_config = spawn_config.clone();
             async move {
                 match tcp_stream {
                     Err(error) => error!("Broken pipe: {}", error), // IoError doesn't impl ErrorExt.
                     Ok(io_stream) => {
-                        if let Err(error) = Self::handle(io_stream, io_runtime, query_handler).await
+                        if let Err(error) =
+                            Self::handle(io_stream, io_runtime, query_handler, spawn_config).await
                         {
                             error!(error; "Unexpected error when handling TcpStream");
                         };
@@ -76,15 +94,12 @@ impl MysqlServer {
         stream: TcpStream,
         io_runtime: Arc<Runtime>,
         query_handler: SqlQueryHandlerRef,
+        spawn_config: Arc<MysqlSpawnConfig>,
     ) -> Result<()> {
         info!("MySQL connection coming from: {}", stream.peer_addr()?);
-        let shim = MysqlInstanceShim::create(query_handler, stream.peer_addr()?.to_string());
-
-        let (r, w) = stream.into_split();
-        let w = BufWriter::with_capacity(DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE, w);
         // TODO(LFC): Use `output_stream` to write large MySQL ResultSet to client.
         let spawn_result = io_runtime
-            .spawn(AsyncMysqlIntermediary::run_on(shim, r, w))
+            .spawn(Self::do_handle(stream, query_handler, spawn_config))
             .await;
         match spawn_result {
             Ok(run_result) => {
@@ -98,6 +113,40 @@ impl MysqlServer {
         }
         Ok(())
     }
+
+    async fn do_handle(
+        stream: TcpStream,
+        query_handler: SqlQueryHandlerRef,
+        spawn_config: Arc<MysqlSpawnConfig>,
+    ) -> Result<()> {
+        let mut shim = MysqlInstanceShim::create(
+            query_handler,
+            stream.peer_addr()?.to_string(),
+            spawn_config.user_provider.clone(),
+        );
+        let (mut r, w) = stream.into_split();
+        let mut w = BufWriter::with_capacity(DEFAULT_RESULT_SET_WRITE_BUFFER_SIZE, w);
+        let ops = IntermediaryOptions::default();
+
+        // Takes the latest config, which may be reloaded.
+        let tls_conf = spawn_config.tls.get_server_config();
+        // Handshakes in plain text, the connection is upgraded to TLS if the client requests
+        // `CLIENT_SSL` and TLS is enabled.
+        let (client_tls, init_params) =
+            AsyncMysqlIntermediary::init_before_ssl(&mut shim, &mut r, &mut w, &tls_conf).await?;
+
+        ensure!(
+            client_tls || !spawn_config.tls.should_force_tls(),
+            TlsRequiredSnafu { server: "MySQL" }
+        );
+
+        match tls_conf {
+            Some(tls_conf) if client_tls => {
+                secure_run_with_options(shim, w, ops, tls_conf, init_params).await
+            }
+            _ => plain_run_with_options(shim, w, ops, init_params).await,
+        }
+    }
 }
 
 #[async_trait]
@@ -14,10 +14,10 @@
 
 use std::ops::Deref;
 
+use chrono::NaiveDateTime;
 use common_query::Output;
 use common_recordbatch::{util, RecordBatch};
 use common_telemetry::error;
-use common_time::datetime::DateTime;
 use common_time::timestamp::TimeUnit;
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::{ColumnSchema, SchemaRef};
@@ -127,8 +127,21 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
                     Value::Binary(v) => row_writer.write_col(v.deref())?,
                     Value::Date(v) => row_writer.write_col(v.val())?,
                     Value::DateTime(v) => row_writer.write_col(v.val())?,
-                    Value::Timestamp(v) => row_writer
-                        .write_col(DateTime::new(v.convert_to(TimeUnit::Second)).to_string())?,
+                    // Writes timestamps as `NaiveDateTime` so that they are encoded properly in
+                    // both text and binary (prepared statement) protocols.
+                    Value::Timestamp(v) => {
+                        match NaiveDateTime::from_timestamp_opt(v.convert_to(TimeUnit::Second), 0) {
+                            Some(datetime) => row_writer.write_col(datetime)?,
+                            None => {
+                                return Err(Error::Internal {
+                                    err_msg: format!(
+                                        "cannot write value {:?} in mysql protocol: out of range",
+                                        &value
+                                    ),
+                                })
+                            }
+                        }
+                    }
                     Value::List(_) => {
                         return Err(Error::Internal {
                             err_msg: format!(
@@ -157,8 +170,9 @@ impl<'a, W: AsyncWrite + Unpin> MysqlResultWriter<'a, W> {
     }
 }
 
-fn create_mysql_column(column_schema: &ColumnSchema) -> Result<Column> {
-    let column_type = match column_schema.data_type {
+/// Returns the MySQL column type of the data type.
+pub fn mysql_column_type(data_type: &ConcreteDataType) -> Result<ColumnType> {
+    match data_type {
         ConcreteDataType::Null(_) => Ok(ColumnType::MYSQL_TYPE_NULL),
         ConcreteDataType::Boolean(_) | ConcreteDataType::Int8(_) | ConcreteDataType::UInt8(_) => {
             Ok(ColumnType::MYSQL_TYPE_TINY)
@@ -170,22 +184,21 @@ fn create_mysql_column(column_schema: &ColumnSchema) -> Result<Column> {
         ConcreteDataType::Int64(_) | ConcreteDataType::UInt64(_) => {
             Ok(ColumnType::MYSQL_TYPE_LONGLONG)
         }
-        ConcreteDataType::Float32(_) | ConcreteDataType::Float64(_) => {
-            Ok(ColumnType::MYSQL_TYPE_FLOAT)
-        }
+        ConcreteDataType::Float32(_) => Ok(ColumnType::MYSQL_TYPE_FLOAT),
+        ConcreteDataType::Float64(_) => Ok(ColumnType::MYSQL_TYPE_DOUBLE),
         ConcreteDataType::Binary(_) | ConcreteDataType::String(_) => {
             Ok(ColumnType::MYSQL_TYPE_VARCHAR)
         }
         ConcreteDataType::Timestamp(_) => Ok(ColumnType::MYSQL_TYPE_DATETIME),
         _ => error::InternalSnafu {
-            err_msg: format!(
-                "not implemented for column datatype {:?}",
-                column_schema.data_type
-            ),
+            err_msg: format!("not implemented for column datatype {:?}", data_type),
         }
         .fail(),
-    };
-    column_type.map(|column_type| Column {
+    }
+}
+
+fn create_mysql_column(column_schema: &ColumnSchema) -> Result<Column> {
+    mysql_column_type(&column_schema.data_type).map(|column_type| Column {
         column: column_schema.name.clone(),
         coltype: column_type,
 
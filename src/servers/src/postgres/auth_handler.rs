@@ -16,18 +16,63 @@ use std::collections::HashMap;
 use std::fmt::Debug;
 
 use async_trait::async_trait;
-use futures::{Sink, SinkExt};
+use common_telemetry::error;
+use futures::{stream, Sink, SinkExt};
 use pgwire::api::auth::{ServerParameterProvider, StartupHandler};
 use pgwire::api::{auth, ClientInfo, PgWireConnectionState};
 use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
-use pgwire::messages::response::ErrorResponse;
-use pgwire::messages::startup::Authentication;
+use pgwire::messages::response::{ErrorResponse, ReadyForQuery, READY_STATUS_IDLE};
+use pgwire::messages::startup::{Authentication, BackendKeyData, ParameterStatus};
 use pgwire::messages::{PgWireBackendMessage, PgWireFrontendMessage};
+use serde::{Deserialize, Serialize};
 
-struct PgPwdVerifier;
+use crate::auth::{Identity, Password, UserProviderRef};
+use crate::context::Channel;
+use crate::error::Result;
+use crate::postgres::cancel::BackendKey;
+
+/// Key of the user name in the startup parameters.
+const METADATA_USER: &str = "user";
+
+/// Method to authenticate passwords of PostgreSQL clients.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PgAuthMethod {
+    /// Clients send `"md5" + md5(md5(password + username) + salt)` instead of the password.
+    #[default]
+    Md5,
+    /// Clients send the password in clear text, which should only be used with TLS.
+    Cleartext,
+}
+
+struct PgPwdVerifier {
+    user_provider: Option<UserProviderRef>,
+    auth_method: PgAuthMethod,
+}
 
 impl PgPwdVerifier {
-    async fn verify_pwd(&self, _pwd: &str, _meta: HashMap<String, String>) -> PgWireResult<bool> {
+    async fn verify_pwd(
+        &self,
+        pwd: &str,
+        salt: &[u8],
+        meta: &HashMap<String, String>,
+    ) -> Result<bool> {
+        let user_provider = match &self.user_provider {
+            Some(user_provider) => user_provider,
+            None => return Ok(true),
+        };
+        let username = meta.get(METADATA_USER).map(String::as_str).unwrap_or("");
+        let password = match self.auth_method {
+            PgAuthMethod::Md5 => Password::PgMD5(pwd.as_bytes(), salt),
+            PgAuthMethod::Cleartext => Password::PlainText(pwd),
+        };
+        user_provider
+            .auth(
+                Identity::UserId(username, None),
+                password,
+                Channel::POSTGRES,
+            )
+            .await?;
         Ok(true)
     }
 }
@@ -62,17 +107,73 @@ impl ServerParameterProvider for GreptimeDBStartupParameters {
 pub struct PgAuthStartupHandler {
     verifier: PgPwdVerifier,
     param_provider: GreptimeDBStartupParameters,
-    with_pwd: bool,
+    /// Salt of the MD5 password authentication, generated for each connection.
+    salt: [u8; 4],
+    /// Rejects connections not upgraded to TLS by `SSLRequest`.
+    force_tls: bool,
+    /// Key of the connection for the client to cancel its queries.
+    backend_key: BackendKey,
 }
 
 impl PgAuthStartupHandler {
-    pub fn new(with_pwd: bool) -> Self {
+    pub fn new(
+        user_provider: Option<UserProviderRef>,
+        auth_method: PgAuthMethod,
+        force_tls: bool,
+        backend_key: BackendKey,
+    ) -> Self {
         PgAuthStartupHandler {
-            verifier: PgPwdVerifier,
+            verifier: PgPwdVerifier {
+                user_provider,
+                auth_method,
+            },
             param_provider: GreptimeDBStartupParameters::new(),
-            with_pwd,
+            salt: rand::random(),
+            force_tls,
+            backend_key,
         }
     }
+
+    fn with_pwd(&self) -> bool {
+        self.verifier.user_provider.is_some()
+    }
+
+    /// Returns the message asking the client for its password.
+    fn password_request(&self) -> Authentication {
+        match self.verifier.auth_method {
+            PgAuthMethod::Md5 => Authentication::MD5Password(self.salt.to_vec()),
+            PgAuthMethod::Cleartext => Authentication::CleartextPassword,
+        }
+    }
+
+    /// Like [auth::finish_authentication], but sends the key of the connection, so the
+    /// client could cancel its queries.
+    async fn finish_authentication<C>(&self, client: &mut C) -> PgWireResult<()>
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        let mut messages = vec![PgWireBackendMessage::Authentication(Authentication::Ok)];
+        if let Some(parameters) = self.param_provider.server_parameters(client) {
+            messages.extend(
+                parameters.into_iter().map(|(k, v)| {
+                    PgWireBackendMessage::ParameterStatus(ParameterStatus::new(k, v))
+                }),
+            );
+        }
+        messages.push(PgWireBackendMessage::BackendKeyData(BackendKeyData::new(
+            self.backend_key.process_id,
+            self.backend_key.secret_key,
+        )));
+        messages.push(PgWireBackendMessage::ReadyForQuery(ReadyForQuery::new(
+            READY_STATUS_IDLE,
+        )));
+        client
+            .send_all(&mut stream::iter(messages.into_iter().map(Ok)))
+            .await?;
+        client.set_state(PgWireConnectionState::ReadyForQuery);
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -80,7 +181,7 @@ impl StartupHandler for PgAuthStartupHandler {
     async fn on_startup<C>(
         &self,
         client: &mut C,
-        message: &PgWireFrontendMessage,
+        message: PgWireFrontendMessage,
     ) -> PgWireResult<()>
     where
         C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send,
@@ -89,34 +190,55 @@ impl StartupHandler for PgAuthStartupHandler {
     {
         match message {
             PgWireFrontendMessage::Startup(ref startup) => {
+                if self.force_tls && !client.is_secure() {
+                    let error_info = ErrorInfo::new(
+                        "FATAL".to_owned(),
+                        "28000".to_owned(),
+                        "TLS is required by server".to_owned(),
+                    );
+                    client
+                        .feed(PgWireBackendMessage::ErrorResponse(error_info.into()))
+                        .await?;
+                    client.close().await?;
+                    return Ok(());
+                }
                 auth::save_startup_parameters_to_metadata(client, startup);
-                if self.with_pwd {
+                if self.with_pwd() {
                     client.set_state(PgWireConnectionState::AuthenticationInProgress);
                     client
                         .send(PgWireBackendMessage::Authentication(
-                            Authentication::CleartextPassword,
+                            self.password_request(),
                         ))
                         .await?;
                 } else {
-                    auth::finish_authentication(client, &self.param_provider).await;
+                    self.finish_authentication(client).await?;
                 }
             }
-            PgWireFrontendMessage::Password(ref pwd) => {
+            PgWireFrontendMessage::PasswordMessageFamily(pwd) => {
+                let pwd = pwd.into_password()?;
                 let meta = client.metadata().clone();
-                if let Ok(true) = self.verifier.verify_pwd(pwd.password(), meta).await {
-                    auth::finish_authentication(client, &self.param_provider).await
-                } else {
-                    let error_info = ErrorInfo::new(
-                        "FATAL".to_owned(),
-                        "28P01".to_owned(),
-                        "Password authentication failed".to_owned(),
-                    );
-                    let error = ErrorResponse::from(error_info);
+                match self
+                    .verifier
+                    .verify_pwd(pwd.password(), &self.salt, &meta)
+                    .await
+                {
+                    Ok(true) => self.finish_authentication(client).await?,
+                    result => {
+                        if let Err(e) = result {
+                            error!(e; "Failed to authenticate postgres user");
+                        }
+                        let error_info = ErrorInfo::new(
+                            "FATAL".to_owned(),
+                            "28P01".to_owned(),
+                            "Password authentication failed".to_owned(),
+                        );
+                        let error = ErrorResponse::from(error_info);
 
-                    client
-                        .feed(PgWireBackendMessage::ErrorResponse(error))
-                        .await?;
-                    client.close().await?;
+                        client
+                            .feed(PgWireBackendMessage::ErrorResponse(error))
+                            .await?;
+                        client.close().await?;
+                    }
                 }
             }
             _ => {}
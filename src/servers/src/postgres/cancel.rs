@@ -0,0 +1,125 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Cancellation of running queries by `CancelRequest`.
+//!
+//! Each connection is given a key in the `BackendKeyData` message after authentication. To
+//! cancel the running query, the client opens a new connection and sends the key back in a
+//! `CancelRequest`, the query is only cancelled if the key matches an open connection.
+
+use std::collections::HashMap;
+use std::io;
+use std::sync::Mutex;
+
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+
+/// Length of the `CancelRequest` message.
+const CANCEL_REQUEST_LEN: usize = 16;
+/// Code of the `CancelRequest` message, in place of the protocol version of a startup message.
+const CANCEL_REQUEST_CODE: i32 = 80877102;
+
+/// Key of a connection sent in `BackendKeyData`, the process id is the connection id.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BackendKey {
+    pub process_id: i32,
+    pub secret_key: i32,
+}
+
+/// Secret keys of the open connections, by connection id.
+#[derive(Debug, Default)]
+pub struct BackendKeys {
+    keys: Mutex<HashMap<u32, i32>>,
+}
+
+impl BackendKeys {
+    /// Generates the key of connection `connection_id`.
+    pub fn register(&self, connection_id: u32) -> BackendKey {
+        let secret_key = rand::random();
+        self.keys.lock().unwrap().insert(connection_id, secret_key);
+        BackendKey {
+            process_id: connection_id as i32,
+            secret_key,
+        }
+    }
+
+    /// Removes the key of connection `connection_id` once the connection is closed.
+    pub fn deregister(&self, connection_id: u32) {
+        self.keys.lock().unwrap().remove(&connection_id);
+    }
+
+    /// Returns the id of the connection if `key` is the key of an open connection.
+    pub fn verify(&self, key: &BackendKey) -> Option<u32> {
+        let connection_id = key.process_id as u32;
+        match self.keys.lock().unwrap().get(&connection_id) {
+            Some(secret_key) if *secret_key == key.secret_key => Some(connection_id),
+            _ => None,
+        }
+    }
+}
+
+/// Reads the `CancelRequest` if it is the first message of `stream`, otherwise leaves the
+/// message in `stream` and returns `None`.
+///
+/// Only cancel requests sent without TLS are recognized, which is what clients usually do.
+pub async fn read_cancel_request(stream: &mut TcpStream) -> io::Result<Option<BackendKey>> {
+    // Both the length and the code of the message are needed to tell a `CancelRequest`.
+    let mut header = [0u8; 8];
+    loop {
+        let size = stream.peek(&mut header).await?;
+        if size == 0 {
+            // The stream has ended.
+            return Ok(None);
+        }
+        if size == header.len() {
+            break;
+        }
+        tokio::task::yield_now().await;
+    }
+
+    let len = i32::from_be_bytes(header[0..4].try_into().unwrap());
+    let code = i32::from_be_bytes(header[4..8].try_into().unwrap());
+    if len as usize != CANCEL_REQUEST_LEN || code != CANCEL_REQUEST_CODE {
+        return Ok(None);
+    }
+
+    let mut message = [0u8; CANCEL_REQUEST_LEN];
+    stream.read_exact(&mut message).await?;
+    Ok(Some(BackendKey {
+        process_id: i32::from_be_bytes(message[8..12].try_into().unwrap()),
+        secret_key: i32::from_be_bytes(message[12..16].try_into().unwrap()),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backend_keys() {
+        let keys = BackendKeys::default();
+        let key = keys.register(42);
+        assert_eq!(42, key.process_id);
+        assert_eq!(Some(42), keys.verify(&key));
+
+        let wrong_secret = BackendKey {
+            process_id: 42,
+            secret_key: key.secret_key.wrapping_add(1),
+        };
+        assert_eq!(None, keys.verify(&wrong_secret));
+
+        keys.deregister(42);
+        assert_eq!(None, keys.verify(&key));
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::ops::Deref;
+use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_query::Output;
@@ -21,22 +22,125 @@ use common_recordbatch::RecordBatch;
 use datatypes::prelude::{ConcreteDataType, Value};
 use datatypes::schema::SchemaRef;
 use futures::{future, stream, Stream, StreamExt};
-use pgwire::api::portal::Portal;
-use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler};
-use pgwire::api::results::{text_query_response, FieldInfo, Response, Tag, TextDataRowEncoder};
+use pgwire::api::portal::{Format, Portal};
+use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler, StatementOrPortal};
+use pgwire::api::results::{
+    DataRowEncoder, DescribeResponse, FieldInfo, QueryResponse, Response, Tag,
+};
+use pgwire::api::stmt::QueryParser;
+use pgwire::api::store::MemPortalStore;
 use pgwire::api::{ClientInfo, Type};
 use pgwire::error::{PgWireError, PgWireResult};
+use session::SessionRef;
 
 use crate::error::{self, Error, Result};
+use crate::postgres::prepared;
+use crate::prepared::PreparedStatement;
 use crate::query_handler::SqlQueryHandlerRef;
 
 pub struct PostgresServerHandler {
     query_handler: SqlQueryHandlerRef,
+    session: SessionRef,
+    portal_store: Arc<MemPortalStore<PreparedStatement>>,
+    query_parser: Arc<PgQueryParser>,
 }
 
 impl PostgresServerHandler {
-    pub fn new(query_handler: SqlQueryHandlerRef) -> Self {
-        PostgresServerHandler { query_handler }
+    /// Creates a handler for a single connection, `session` holds the state of the connection.
+    pub fn new(query_handler: SqlQueryHandlerRef, session: SessionRef) -> Self {
+        PostgresServerHandler {
+            query_handler,
+            session,
+            portal_store: Arc::new(MemPortalStore::new()),
+            query_parser: Arc::new(PgQueryParser),
+        }
+    }
+
+    /// Infers the parameter types of the statement. Types specified by the client in the
+    /// `Parse` message are used as is, others are inferred from the columns they are compared
+    /// with or inserted into, and fall back to `TEXT` like Postgres does for unknown types.
+    async fn param_types(
+        &self,
+        stmt: &PreparedStatement,
+        specified_types: &[Type],
+    ) -> Result<Vec<Type>> {
+        let hints = stmt.param_hints();
+        let needs_inference = (0..stmt.params_num()).any(|i| {
+            hints[i].is_some()
+                && specified_types
+                    .get(i)
+                    .map_or(true, |ty| ty == &Type::UNKNOWN)
+        });
+        let inferred_types = if needs_inference {
+            stmt.infer_param_types(&self.query_handler, self.session.context())
+                .await?
+        } else {
+            vec![None; stmt.params_num()]
+        };
+
+        inferred_types
+            .into_iter()
+            .enumerate()
+            .map(|(i, data_type)| {
+                if let Some(ty) = specified_types.get(i).filter(|ty| *ty != &Type::UNKNOWN) {
+                    return Ok(ty.clone());
+                }
+                match data_type {
+                    Some(data_type) => type_translate(&data_type),
+                    None => Ok(Type::TEXT),
+                }
+            })
+            .collect()
+    }
+
+    /// Describes the result columns of the statement, placeholders are filled with `NULL`s,
+    /// which is enough for planning the query.
+    async fn describe_fields(
+        &self,
+        stmt: &PreparedStatement,
+        format: &Format,
+    ) -> Result<Vec<FieldInfo>> {
+        let schema = self
+            .query_handler
+            .do_describe(&stmt.bind_nulls(), self.session.context())
+            .await?;
+        match schema {
+            Some(schema) => schema_to_pg(schema, format),
+            None => Ok(vec![]),
+        }
+    }
+
+    async fn bind_portal(&self, portal: &Portal<PreparedStatement>) -> Result<String> {
+        let stmt = portal.statement();
+        let param_types = self
+            .param_types(stmt.statement(), stmt.parameter_types())
+            .await?;
+        let params = portal
+            .parameters()
+            .iter()
+            .zip(param_types.iter())
+            .enumerate()
+            .map(|(i, (param, pg_type))| {
+                prepared::format_param(
+                    param.as_deref(),
+                    pg_type,
+                    portal.parameter_format().is_binary(i),
+                )
+            })
+            .collect::<Result<Vec<_>>>()?;
+        stmt.statement().bind(&params)
+    }
+}
+
+/// Parses statements of the extended query protocol, the SQL is kept with its placeholders
+/// until the parameters are bound.
+pub struct PgQueryParser;
+
+impl QueryParser for PgQueryParser {
+    type Statement = PreparedStatement;
+
+    fn parse_sql(&self, sql: &str, _types: &[Type]) -> PgWireResult<Self::Statement> {
+        PreparedStatement::parse(sql).map_err(|e| PgWireError::ApiError(Box::new(e)))
     }
 }
 
@@ -48,26 +152,34 @@ impl SimpleQueryHandler for PostgresServerHandler {
     {
         let output = self
             .query_handler
-            .do_query(query)
+            .do_query(query, self.session.context())
             .await
             .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
 
-        match output {
-            Output::AffectedRows(rows) => Ok(vec![Response::Execution(Tag::new_for_execution(
-                "OK",
-                Some(rows),
-            ))]),
-            Output::Stream(record_stream) => {
-                let schema = record_stream.schema();
-                recordbatches_to_query_response(record_stream, schema)
-            }
-            Output::RecordBatches(recordbatches) => {
-                let schema = recordbatches.schema();
-                recordbatches_to_query_response(
-                    stream::iter(recordbatches.take().into_iter().map(Ok)),
-                    schema,
-                )
-            }
+        Ok(vec![output_to_query_response(
+            output,
+            &Format::UnifiedText,
+        )?])
+    }
+}
+
+fn output_to_query_response(output: Output, format: &Format) -> PgWireResult<Response> {
+    match output {
+        Output::AffectedRows(rows) => Ok(Response::Execution(Tag::new_for_execution(
+            "OK",
+            Some(rows),
+        ))),
+        Output::Stream(record_stream) => {
+            let schema = record_stream.schema();
+            recordbatches_to_query_response(record_stream, schema, format)
+        }
+        Output::RecordBatches(recordbatches) => {
+            let schema = recordbatches.schema();
+            recordbatches_to_query_response(
+                stream::iter(recordbatches.take().into_iter().map(Ok)),
+                schema,
+                format,
+            )
         }
     }
 }
@@ -75,12 +187,14 @@ impl SimpleQueryHandler for PostgresServerHandler {
 fn recordbatches_to_query_response<S>(
     recordbatches_stream: S,
     schema: SchemaRef,
-) -> PgWireResult<Vec<Response>>
+    format: &Format,
+) -> PgWireResult<Response>
 where
     S: Stream<Item = RecordBatchResult<RecordBatch>> + Send + Unpin + 'static,
 {
-    let pg_schema = schema_to_pg(schema).map_err(|e| PgWireError::ApiError(Box::new(e)))?;
-    let ncols = pg_schema.len();
+    let pg_schema =
+        Arc::new(schema_to_pg(schema, format).map_err(|e| PgWireError::ApiError(Box::new(e)))?);
+    let pg_schema_ref = pg_schema.clone();
 
     let data_row_stream = recordbatches_stream
         .map(|record_batch_result| match record_batch_result {
@@ -98,7 +212,7 @@ where
         .flatten() // flatten into stream<result<row>>
         .map(move |row| {
             row.and_then(|row| {
-                let mut encoder = TextDataRowEncoder::new(ncols);
+                let mut encoder = DataRowEncoder::new(pg_schema_ref.clone());
                 for value in row.into_iter() {
                     encode_value(&value, &mut encoder)?;
                 }
@@ -106,63 +220,86 @@ where
             })
         });
 
-    Ok(vec![Response::Query(text_query_response(
+    Ok(Response::Query(QueryResponse::new(
         pg_schema,
         data_row_stream,
-    ))])
+    )))
 }
 
-fn schema_to_pg(origin: SchemaRef) -> Result<Vec<FieldInfo>> {
+/// Converts the schema to Postgres fields, `format` tells whether each field is encoded in
+/// text or binary format.
+fn schema_to_pg(origin: SchemaRef, format: &Format) -> Result<Vec<FieldInfo>> {
     origin
         .column_schemas()
         .iter()
-        .map(|col| {
+        .enumerate()
+        .map(|(idx, col)| {
             Ok(FieldInfo::new(
                 col.name.clone(),
                 None,
                 None,
                 type_translate(&col.data_type)?,
+                format.format_for(idx),
             ))
         })
         .collect::<Result<Vec<FieldInfo>>>()
 }
 
-fn encode_value(value: &Value, builder: &mut TextDataRowEncoder) -> PgWireResult<()> {
+fn encode_value(value: &Value, builder: &mut DataRowEncoder) -> PgWireResult<()> {
     match value {
-        Value::Null => builder.append_field(None::<&i8>),
-        Value::Boolean(v) => builder.append_field(Some(v)),
-        Value::UInt8(v) => builder.append_field(Some(v)),
-        Value::UInt16(v) => builder.append_field(Some(v)),
-        Value::UInt32(v) => builder.append_field(Some(v)),
-        Value::UInt64(v) => builder.append_field(Some(v)),
-        Value::Int8(v) => builder.append_field(Some(v)),
-        Value::Int16(v) => builder.append_field(Some(v)),
-        Value::Int32(v) => builder.append_field(Some(v)),
-        Value::Int64(v) => builder.append_field(Some(v)),
-        Value::Float32(v) => builder.append_field(Some(&v.0)),
-        Value::Float64(v) => builder.append_field(Some(&v.0)),
-        Value::String(v) => builder.append_field(Some(&v.as_utf8())),
-        Value::Binary(v) => builder.append_field(Some(&hex::encode(v.deref()))),
-        Value::Date(v) => builder.append_field(Some(&v.to_string())),
-        Value::DateTime(v) => builder.append_field(Some(&v.to_string())),
-        Value::Timestamp(v) => builder.append_field(Some(&v.to_iso8601_string())),
-        Value::List(_) => Err(PgWireError::ApiError(Box::new(Error::Internal {
-            err_msg: format!(
-                "cannot write value {:?} in postgres protocol: unimplemented",
-                &value
-            ),
-        }))),
+        Value::Null => builder.encode_field(&None::<i8>),
+        Value::Boolean(v) => builder.encode_field(v),
+        // Unsigned integers are encoded as the wider signed integers.
+        Value::UInt8(v) => builder.encode_field(&(*v as i16)),
+        Value::UInt16(v) => builder.encode_field(&(*v as i32)),
+        Value::UInt32(v) => builder.encode_field(&(*v as i64)),
+        Value::UInt64(v) => match i64::try_from(*v) {
+            Ok(v) => builder.encode_field(&v),
+            Err(_) => Err(unsupported_value_error(value, "out of range of bigint")),
+        },
+        Value::Int8(v) => builder.encode_field(v),
+        Value::Int16(v) => builder.encode_field(v),
+        Value::Int32(v) => builder.encode_field(v),
+        Value::Int64(v) => builder.encode_field(v),
+        Value::Float32(v) => builder.encode_field(&v.0),
+        Value::Float64(v) => builder.encode_field(&v.0),
+        Value::String(v) => builder.encode_field(&v.as_utf8()),
+        Value::Binary(v) => builder.encode_field(&v.deref()),
+        Value::Date(v) => match v.to_chrono_date() {
+            Some(date) => builder.encode_field(&date),
+            None => Err(unsupported_value_error(value, "out of range")),
+        },
+        Value::DateTime(v) => match v.to_chrono_datetime() {
+            Some(datetime) => builder.encode_field(&datetime),
+            None => Err(unsupported_value_error(value, "out of range")),
+        },
+        Value::Timestamp(v) => match v.to_chrono_datetime() {
+            Some(datetime) => builder.encode_field(&datetime),
+            None => Err(unsupported_value_error(value, "out of range")),
+        },
+        Value::List(_) => Err(unsupported_value_error(value, "unimplemented")),
     }
 }
 
+fn unsupported_value_error(value: &Value, reason: &str) -> PgWireError {
+    PgWireError::ApiError(Box::new(Error::Internal {
+        err_msg: format!(
+            "cannot write value {:?} in postgres protocol: {}",
+            value, reason
+        ),
+    }))
+}
+
 fn type_translate(origin: &ConcreteDataType) -> Result<Type> {
     match origin {
         &ConcreteDataType::Null(_) => Ok(Type::UNKNOWN),
         &ConcreteDataType::Boolean(_) => Ok(Type::BOOL),
-        &ConcreteDataType::Int8(_) | &ConcreteDataType::UInt8(_) => Ok(Type::CHAR),
-        &ConcreteDataType::Int16(_) | &ConcreteDataType::UInt16(_) => Ok(Type::INT2),
-        &ConcreteDataType::Int32(_) | &ConcreteDataType::UInt32(_) => Ok(Type::INT4),
-        &ConcreteDataType::Int64(_) | &ConcreteDataType::UInt64(_) => Ok(Type::INT8),
+        &ConcreteDataType::Int8(_) => Ok(Type::CHAR),
+        &ConcreteDataType::Int16(_) | &ConcreteDataType::UInt8(_) => Ok(Type::INT2),
+        &ConcreteDataType::Int32(_) | &ConcreteDataType::UInt16(_) => Ok(Type::INT4),
+        &ConcreteDataType::Int64(_)
+        | &ConcreteDataType::UInt32(_)
+        | &ConcreteDataType::UInt64(_) => Ok(Type::INT8),
         &ConcreteDataType::Float32(_) => Ok(Type::FLOAT4),
         &ConcreteDataType::Float64(_) => Ok(Type::FLOAT8),
         &ConcreteDataType::Binary(_) => Ok(Type::BYTEA),
@@ -179,16 +316,70 @@ fn type_translate(origin: &ConcreteDataType) -> Result<Type> {
 
 #[async_trait]
 impl ExtendedQueryHandler for PostgresServerHandler {
+    type Statement = PreparedStatement;
+    type PortalStore = MemPortalStore<Self::Statement>;
+    type QueryParser = PgQueryParser;
+
+    fn portal_store(&self) -> Arc<Self::PortalStore> {
+        self.portal_store.clone()
+    }
+
+    fn query_parser(&self) -> Arc<Self::QueryParser> {
+        self.query_parser.clone()
+    }
+
     async fn do_query<C>(
         &self,
         _client: &mut C,
-        _portal: &Portal,
+        portal: &Portal<Self::Statement>,
         _max_rows: usize,
     ) -> PgWireResult<Response>
     where
         C: ClientInfo + Unpin + Send + Sync,
     {
-        unimplemented!()
+        let query = self
+            .bind_portal(portal)
+            .await
+            .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
+        let output = self
+            .query_handler
+            .do_query(&query, self.session.context())
+            .await
+            .map_err(|e| PgWireError::ApiError(Box::new(e)))?;
+
+        output_to_query_response(output, portal.result_column_format())
+    }
+
+    async fn do_describe<C>(
+        &self,
+        _client: &mut C,
+        target: StatementOrPortal<'_, Self::Statement>,
+    ) -> PgWireResult<DescribeResponse>
+    where
+        C: ClientInfo + Unpin + Send + Sync,
+    {
+        let result = match target {
+            StatementOrPortal::Statement(stmt) => {
+                // Result formats are unknown until the statement is bound, describes them as text.
+                let param_types = self
+                    .param_types(stmt.statement(), stmt.parameter_types())
+                    .await;
+                let fields = self
+                    .describe_fields(stmt.statement(), &Format::UnifiedText)
+                    .await;
+                param_types.and_then(|types| {
+                    fields.map(|fields| DescribeResponse::new(Some(types), fields))
+                })
+            }
+            StatementOrPortal::Portal(portal) => self
+                .describe_fields(
+                    portal.statement().statement(),
+                    portal.result_column_format(),
+                )
+                .await
+                .map(|fields| DescribeResponse::new(None, fields)),
+        };
+        result.map_err(|e| PgWireError::ApiError(Box::new(e)))
     }
 }
 
@@ -198,7 +389,7 @@ mod test {
 
     use datatypes::schema::{ColumnSchema, Schema};
     use datatypes::value::ListValue;
-    use pgwire::api::results::FieldInfo;
+    use pgwire::api::results::{FieldFormat, FieldInfo};
     use pgwire::api::Type;
 
     use super::*;
@@ -228,56 +419,146 @@ mod test {
             ColumnSchema::new("dates", ConcreteDataType::date_datatype(), true),
         ];
         let pg_field_info = vec![
-            FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN),
-            FieldInfo::new("bools".into(), None, None, Type::BOOL),
-            FieldInfo::new("int8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("int16s".into(), None, None, Type::INT2),
-            FieldInfo::new("int32s".into(), None, None, Type::INT4),
-            FieldInfo::new("int64s".into(), None, None, Type::INT8),
-            FieldInfo::new("uint8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("uint16s".into(), None, None, Type::INT2),
-            FieldInfo::new("uint32s".into(), None, None, Type::INT4),
-            FieldInfo::new("uint64s".into(), None, None, Type::INT8),
-            FieldInfo::new("float32s".into(), None, None, Type::FLOAT4),
-            FieldInfo::new("float64s".into(), None, None, Type::FLOAT8),
-            FieldInfo::new("binaries".into(), None, None, Type::BYTEA),
-            FieldInfo::new("strings".into(), None, None, Type::VARCHAR),
-            FieldInfo::new("timestamps".into(), None, None, Type::TIMESTAMP),
-            FieldInfo::new("dates".into(), None, None, Type::DATE),
+            FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN, FieldFormat::Text),
+            FieldInfo::new("bools".into(), None, None, Type::BOOL, FieldFormat::Text),
+            FieldInfo::new("int8s".into(), None, None, Type::CHAR, FieldFormat::Text),
+            FieldInfo::new("int16s".into(), None, None, Type::INT2, FieldFormat::Text),
+            FieldInfo::new("int32s".into(), None, None, Type::INT4, FieldFormat::Text),
+            FieldInfo::new("int64s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new("uint8s".into(), None, None, Type::INT2, FieldFormat::Text),
+            FieldInfo::new("uint16s".into(), None, None, Type::INT4, FieldFormat::Text),
+            FieldInfo::new("uint32s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new("uint64s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new(
+                "float32s".into(),
+                None,
+                None,
+                Type::FLOAT4,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float64s".into(),
+                None,
+                None,
+                Type::FLOAT8,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "binaries".into(),
+                None,
+                None,
+                Type::BYTEA,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "strings".into(),
+                None,
+                None,
+                Type::VARCHAR,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "timestamps".into(),
+                None,
+                None,
+                Type::TIMESTAMP,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new("dates".into(), None, None, Type::DATE, FieldFormat::Text),
         ];
         let schema = Arc::new(Schema::new(column_schemas));
-        let fs = schema_to_pg(schema).unwrap();
+        let fs = schema_to_pg(schema, &Format::UnifiedText).unwrap();
         assert_eq!(fs, pg_field_info);
     }
 
     #[test]
     fn test_encode_text_format_data() {
         let schema = vec![
-            FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN),
-            FieldInfo::new("bools".into(), None, None, Type::BOOL),
-            FieldInfo::new("uint8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("uint16s".into(), None, None, Type::INT2),
-            FieldInfo::new("uint32s".into(), None, None, Type::INT4),
-            FieldInfo::new("uint64s".into(), None, None, Type::INT8),
-            FieldInfo::new("int8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("int8s".into(), None, None, Type::CHAR),
-            FieldInfo::new("int16s".into(), None, None, Type::INT2),
-            FieldInfo::new("int16s".into(), None, None, Type::INT2),
-            FieldInfo::new("int32s".into(), None, None, Type::INT4),
-            FieldInfo::new("int32s".into(), None, None, Type::INT4),
-            FieldInfo::new("int64s".into(), None, None, Type::INT8),
-            FieldInfo::new("int64s".into(), None, None, Type::INT8),
-            FieldInfo::new("float32s".into(), None, None, Type::FLOAT4),
-            FieldInfo::new("float32s".into(), None, None, Type::FLOAT4),
-            FieldInfo::new("float32s".into(), None, None, Type::FLOAT4),
-            FieldInfo::new("float64s".into(), None, None, Type::FLOAT8),
-            FieldInfo::new("float64s".into(), None, None, Type::FLOAT8),
-            FieldInfo::new("float64s".into(), None, None, Type::FLOAT8),
-            FieldInfo::new("strings".into(), None, None, Type::VARCHAR),
-            FieldInfo::new("binaries".into(), None, None, Type::BYTEA),
-            FieldInfo::new("dates".into(), None, None, Type::DATE),
-            FieldInfo::new("datetimes".into(), None, None, Type::TIMESTAMP),
-            FieldInfo::new("timestamps".into(), None, None, Type::TIMESTAMP),
+            FieldInfo::new("nulls".into(), None, None, Type::UNKNOWN, FieldFormat::Text),
+            FieldInfo::new("bools".into(), None, None, Type::BOOL, FieldFormat::Text),
+            FieldInfo::new("uint8s".into(), None, None, Type::INT2, FieldFormat::Text),
+            FieldInfo::new("uint16s".into(), None, None, Type::INT4, FieldFormat::Text),
+            FieldInfo::new("uint32s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new("uint64s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new("int8s".into(), None, None, Type::CHAR, FieldFormat::Text),
+            FieldInfo::new("int8s".into(), None, None, Type::CHAR, FieldFormat::Text),
+            FieldInfo::new("int16s".into(), None, None, Type::INT2, FieldFormat::Text),
+            FieldInfo::new("int16s".into(), None, None, Type::INT2, FieldFormat::Text),
+            FieldInfo::new("int32s".into(), None, None, Type::INT4, FieldFormat::Text),
+            FieldInfo::new("int32s".into(), None, None, Type::INT4, FieldFormat::Text),
+            FieldInfo::new("int64s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new("int64s".into(), None, None, Type::INT8, FieldFormat::Text),
+            FieldInfo::new(
+                "float32s".into(),
+                None,
+                None,
+                Type::FLOAT4,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float32s".into(),
+                None,
+                None,
+                Type::FLOAT4,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float32s".into(),
+                None,
+                None,
+                Type::FLOAT4,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float64s".into(),
+                None,
+                None,
+                Type::FLOAT8,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float64s".into(),
+                None,
+                None,
+                Type::FLOAT8,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "float64s".into(),
+                None,
+                None,
+                Type::FLOAT8,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "strings".into(),
+                None,
+                None,
+                Type::VARCHAR,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "binaries".into(),
+                None,
+                None,
+                Type::BYTEA,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new("dates".into(), None, None, Type::DATE, FieldFormat::Text),
+            FieldInfo::new(
+                "datetimes".into(),
+                None,
+                None,
+                Type::TIMESTAMP,
+                FieldFormat::Text,
+            ),
+            FieldInfo::new(
+                "timestamps".into(),
+                None,
+                None,
+                Type::TIMESTAMP,
+                FieldFormat::Text,
+            ),
         ];
 
         let values = vec![
@@ -286,7 +567,7 @@ mod test {
             Value::UInt8(u8::MAX),
             Value::UInt16(u16::MAX),
             Value::UInt32(u32::MAX),
-            Value::UInt64(u64::MAX),
+            Value::UInt64(i64::MAX as u64),
             Value::Int8(i8::MAX),
             Value::Int8(i8::MIN),
             Value::Int16(i16::MAX),
@@ -307,11 +588,14 @@ mod test {
             Value::DateTime(1000001i64.into()),
             Value::Timestamp(1000001i64.into()),
         ];
-        let mut builder = TextDataRowEncoder::new(schema.len());
+        let mut builder = DataRowEncoder::new(Arc::new(schema));
         for i in values {
             assert!(encode_value(&i, &mut builder).is_ok());
         }
 
+        // bigint can't hold all values of uint64.
+        assert!(encode_value(&Value::UInt64(u64::MAX), &mut builder).is_err());
+
         let err = encode_value(
             &Value::List(ListValue::new(
                 Some(Box::new(vec![])),
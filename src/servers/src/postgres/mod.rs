@@ -13,7 +13,10 @@
 // limitations under the License.
 
 mod auth_handler;
+mod cancel;
 mod handler;
+mod prepared;
 mod server;
 
+pub use auth_handler::PgAuthMethod;
 pub use server::PostgresServer;
@@ -0,0 +1,172 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use chrono::{NaiveDate, NaiveDateTime};
+use pgwire::api::Type;
+use postgres_types::FromSql;
+
+use crate::error::{self, Result};
+use crate::prepared::{hex_literal, quote_string};
+
+/// Converts a parameter of the `Bind` message to a SQL literal, `binary` indicates whether
+/// the parameter is in binary or text format.
+pub fn format_param(param: Option<&[u8]>, pg_type: &Type, binary: bool) -> Result<String> {
+    let param = match param {
+        Some(param) => param,
+        None => return Ok("NULL".to_string()),
+    };
+    if binary {
+        format_binary_param(param, pg_type)
+    } else {
+        format_text_param(param, pg_type)
+    }
+}
+
+fn format_text_param(param: &[u8], pg_type: &Type) -> Result<String> {
+    let text = String::from_utf8_lossy(param);
+    let invalid = || {
+        error::InvalidPrepareStatementSnafu {
+            err_msg: format!("invalid {} parameter: {}", pg_type, text),
+        }
+        .fail()
+    };
+
+    match pg_type {
+        &Type::BOOL => match text.to_ascii_lowercase().as_str() {
+            "t" | "true" | "1" | "y" | "yes" | "on" => Ok("TRUE".to_string()),
+            "f" | "false" | "0" | "n" | "no" | "off" => Ok("FALSE".to_string()),
+            _ => invalid(),
+        },
+        &Type::CHAR | &Type::INT2 | &Type::INT4 | &Type::INT8 => match text.trim().parse::<i64>() {
+            Ok(v) => Ok(v.to_string()),
+            Err(_) => invalid(),
+        },
+        &Type::FLOAT4 | &Type::FLOAT8 => match text.trim().parse::<f64>() {
+            Ok(v) => Ok(v.to_string()),
+            Err(_) => invalid(),
+        },
+        // Text format of `BYTEA` is `\x` followed by hex digits, or the bytes themselves with
+        // backslashes escaped, which is only accepted if there is no backslash.
+        &Type::BYTEA => match text.strip_prefix("\\x") {
+            Some(hex) => match hex::decode(hex) {
+                Ok(bytes) => Ok(hex_literal(&bytes)),
+                Err(_) => invalid(),
+            },
+            None if !param.contains(&b'\\') => Ok(hex_literal(param)),
+            None => invalid(),
+        },
+        _ => Ok(quote_string(&text)),
+    }
+}
+
+fn format_binary_param(param: &[u8], pg_type: &Type) -> Result<String> {
+    fn decode<'a, T: FromSql<'a>>(pg_type: &Type, param: &'a [u8]) -> Result<T> {
+        T::from_sql(pg_type, param).map_err(|e| {
+            error::InvalidPrepareStatementSnafu {
+                err_msg: format!("failed to decode {} parameter: {}", pg_type, e),
+            }
+            .build()
+        })
+    }
+
+    let literal = match pg_type {
+        &Type::BOOL => {
+            if decode::<bool>(pg_type, param)? {
+                "TRUE".to_string()
+            } else {
+                "FALSE".to_string()
+            }
+        }
+        &Type::CHAR => decode::<i8>(pg_type, param)?.to_string(),
+        &Type::INT2 => decode::<i16>(pg_type, param)?.to_string(),
+        &Type::INT4 => decode::<i32>(pg_type, param)?.to_string(),
+        &Type::INT8 => decode::<i64>(pg_type, param)?.to_string(),
+        &Type::FLOAT4 => decode::<f32>(pg_type, param)?.to_string(),
+        &Type::FLOAT8 => decode::<f64>(pg_type, param)?.to_string(),
+        &Type::TEXT | &Type::VARCHAR | &Type::BPCHAR | &Type::NAME | &Type::UNKNOWN => {
+            quote_string(&decode::<String>(pg_type, param)?)
+        }
+        &Type::BYTEA => hex_literal(param),
+        &Type::DATE => quote_string(&decode::<NaiveDate>(pg_type, param)?.to_string()),
+        &Type::TIMESTAMP => quote_string(
+            &decode::<NaiveDateTime>(pg_type, param)?
+                .format("%Y-%m-%d %H:%M:%S%.f")
+                .to_string(),
+        ),
+        _ => {
+            return error::InvalidPrepareStatementSnafu {
+                err_msg: format!("unsupported parameter type: {}", pg_type),
+            }
+            .fail()
+        }
+    };
+    Ok(literal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_param() {
+        assert_eq!("NULL", format_param(None, &Type::INT4, true).unwrap());
+
+        assert_eq!(
+            "TRUE",
+            format_param(Some(b"t"), &Type::BOOL, false).unwrap()
+        );
+        assert_eq!("42", format_param(Some(b"42"), &Type::INT8, false).unwrap());
+        assert!(format_param(Some(b"1; DROP TABLE t"), &Type::INT8, false).is_err());
+        assert_eq!(
+            "'it''s'",
+            format_param(Some(b"it's"), &Type::TEXT, false).unwrap()
+        );
+
+        assert_eq!(
+            "42",
+            format_param(Some(&42i32.to_be_bytes()), &Type::INT4, true).unwrap()
+        );
+        assert_eq!(
+            "1.5",
+            format_param(Some(&1.5f64.to_be_bytes()), &Type::FLOAT8, true).unwrap()
+        );
+        assert_eq!(
+            "'greptime'",
+            format_param(Some(b"greptime"), &Type::VARCHAR, true).unwrap()
+        );
+        // 2000-01-02 00:00:01, microseconds since 2000-01-01.
+        let micros: i64 = 86_401_000_000;
+        assert_eq!(
+            "'2000-01-02 00:00:01'",
+            format_param(Some(&micros.to_be_bytes()), &Type::TIMESTAMP, true).unwrap()
+        );
+        assert!(format_param(Some(&[1, 2]), &Type::INT4, true).is_err());
+
+        // Binary parameters are not required to be valid UTF-8.
+        assert_eq!(
+            "X'00ff27'",
+            format_param(Some(&[0, 0xff, b'\'']), &Type::BYTEA, true).unwrap()
+        );
+        assert_eq!(
+            "X'00ff27'",
+            format_param(Some(b"\\x00ff27"), &Type::BYTEA, false).unwrap()
+        );
+        assert_eq!(
+            "X'6162'",
+            format_param(Some(b"ab"), &Type::BYTEA, false).unwrap()
+        );
+        assert!(format_param(Some(b"\\xzz"), &Type::BYTEA, false).is_err());
+        assert!(format_param(Some(b"a\\000"), &Type::BYTEA, false).is_err());
+    }
+}
@@ -18,36 +18,48 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use common_runtime::Runtime;
-use common_telemetry::logging::error;
-use futures::StreamExt;
+use common_telemetry::logging::{debug, error};
+use futures::{future, StreamExt};
 use pgwire::tokio::process_socket;
+use session::Session;
 use tokio;
+use tokio::net::TcpStream;
+use tokio_rustls::TlsAcceptor;
 
+use crate::auth::UserProviderRef;
 use crate::error::Result;
-use crate::postgres::auth_handler::PgAuthStartupHandler;
+use crate::postgres::auth_handler::{PgAuthMethod, PgAuthStartupHandler};
+use crate::postgres::cancel::{self, BackendKey, BackendKeys};
 use crate::postgres::handler::PostgresServerHandler;
 use crate::query_handler::SqlQueryHandlerRef;
 use crate::server::{AbortableStream, BaseTcpServer, Server};
+use crate::tls::TlsServerConfigRef;
 
 pub struct PostgresServer {
     base_server: BaseTcpServer,
-    auth_handler: Arc<PgAuthStartupHandler>,
-    query_handler: Arc<PostgresServerHandler>,
+    query_handler: SqlQueryHandlerRef,
+    tls: TlsServerConfigRef,
+    user_provider: Option<UserProviderRef>,
+    auth_method: PgAuthMethod,
+    backend_keys: Arc<BackendKeys>,
 }
 
 impl PostgresServer {
     /// Creates a new Postgres server with provided query_handler and async runtime
     pub fn new(
         query_handler: SqlQueryHandlerRef,
-        check_pwd: bool,
         io_runtime: Arc<Runtime>,
+        tls: TlsServerConfigRef,
+        user_provider: Option<UserProviderRef>,
+        auth_method: PgAuthMethod,
     ) -> PostgresServer {
-        let postgres_handler = Arc::new(PostgresServerHandler::new(query_handler));
-        let startup_handler = Arc::new(PgAuthStartupHandler::new(check_pwd));
         PostgresServer {
             base_server: BaseTcpServer::create_server("Postgres", io_runtime),
-            auth_handler: startup_handler,
-            query_handler: postgres_handler,
+            query_handler,
+            tls,
+            user_provider,
+            auth_method,
+            backend_keys: Arc::new(BackendKeys::default()),
         }
     }
 
@@ -56,29 +68,105 @@ impl PostgresServer {
         io_runtime: Arc<Runtime>,
         accepting_stream: AbortableStream,
     ) -> impl Future<Output = ()> {
-        let auth_handler = self.auth_handler.clone();
         let query_handler = self.query_handler.clone();
+        let tls = self.tls.clone();
+        let user_provider = self.user_provider.clone();
+        let auth_method = self.auth_method;
+        let backend_keys = self.backend_keys.clone();
 
         accepting_stream.for_each(move |tcp_stream| {
-            let io_runtime = io_runtime.clone();
-            let auth_handler = auth_handler.clone();
-            let query_handler = query_handler.clone();
+            match tcp_stream {
+                Err(error) => error!("Broken pipe: {}", error), // IoError doesn't impl ErrorExt.
+                Ok(io_stream) => {
+                    let connection = PostgresConnection {
+                        query_handler: query_handler.clone(),
+                        tls: tls.clone(),
+                        user_provider: user_provider.clone(),
+                        auth_method,
+                        backend_keys: backend_keys.clone(),
+                    };
+                    // Handles the connection in its own task, so a slow client doesn't block
+                    // accepting other connections.
+                    io_runtime.spawn(connection.handle(io_stream));
+                }
+            };
+            future::ready(())
+        })
+    }
+}
 
-            async move {
-                match tcp_stream {
-                    Err(error) => error!("Broken pipe: {}", error), // IoError doesn't impl ErrorExt.
-                    Ok(io_stream) => {
-                        io_runtime.spawn(process_socket(
-                            io_stream,
-                            None,
-                            auth_handler.clone(),
-                            query_handler.clone(),
-                            query_handler.clone(),
-                        ));
-                    }
-                };
+/// States shared by connections to handle a new connection.
+struct PostgresConnection {
+    query_handler: SqlQueryHandlerRef,
+    tls: TlsServerConfigRef,
+    user_provider: Option<UserProviderRef>,
+    auth_method: PgAuthMethod,
+    backend_keys: Arc<BackendKeys>,
+}
+
+impl PostgresConnection {
+    async fn handle(self, mut io_stream: TcpStream) {
+        // A `CancelRequest` comes from a new connection, which is closed after the request.
+        match cancel::read_cancel_request(&mut io_stream).await {
+            Ok(Some(key)) => return self.cancel(&key),
+            Ok(None) => {}
+            Err(e) => {
+                error!("Failed to read from postgres connection: {}", e);
+                return;
             }
-        })
+        }
+
+        let client_addr = match io_stream.peer_addr() {
+            Ok(addr) => addr.to_string(),
+            Err(e) => {
+                error!("Failed to get peer address: {}", e);
+                return;
+            }
+        };
+        // Each connection has its own session, e.g. the current schema.
+        let session = Arc::new(Session::new(client_addr));
+        let connection_id = session.connection_id();
+        let backend_key = self.backend_keys.register(connection_id);
+        let postgres_handler = Arc::new(PostgresServerHandler::new(self.query_handler, session));
+        // Each connection has its own salt for password authentication.
+        let auth_handler = Arc::new(PgAuthStartupHandler::new(
+            self.user_provider,
+            self.auth_method,
+            self.tls.should_force_tls(),
+            backend_key,
+        ));
+        // Takes the latest config, which may be reloaded.
+        let tls_acceptor = self
+            .tls
+            .get_server_config()
+            .map(|config| Arc::new(TlsAcceptor::from(config)));
+        let _ = process_socket(
+            io_stream,
+            tls_acceptor,
+            auth_handler,
+            postgres_handler.clone(),
+            postgres_handler,
+        )
+        .await;
+        self.backend_keys.deregister(connection_id);
+    }
+
+    /// Kills the queries running on the connection of `key`, requests with unknown keys are
+    /// ignored like PostgreSQL does, and no response is sent in any case.
+    fn cancel(&self, key: &BackendKey) {
+        let connection_id = match self.backend_keys.verify(key) {
+            Some(connection_id) => connection_id,
+            None => {
+                debug!("Ignored cancel request with unknown key {:?}", key);
+                return;
+            }
+        };
+        if let Err(e) = self.query_handler.kill_query(connection_id) {
+            debug!(
+                "Failed to cancel queries of connection {}: {}",
+                connection_id, e
+            );
+        }
     }
 }
 
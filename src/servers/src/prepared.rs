@@ -0,0 +1,644 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Prepared statements of the MySQL and PostgreSQL protocols.
+//!
+//! Statements are tokenized and parsed by the sql crate. Placeholders, `?` of MySQL or `$n`
+//! of PostgreSQL, are kept until the parameters are bound as SQL literals. The types of
+//! parameters are inferred from the columns they are compared with or inserted into.
+
+use datatypes::prelude::ConcreteDataType;
+use session::context::QueryContextRef;
+use snafu::ensure;
+use sql::dialect::GenericDialect;
+use sql::parser::ParserContext;
+use sql::statements::statement::Statement;
+use sqlparser::ast::{
+    BinaryOperator, Expr, FunctionArg, FunctionArgExpr, Ident, JoinConstraint, JoinOperator, Query,
+    Select, SelectItem, SetExpr, Statement as SpStatement, TableFactor, TableWithJoins, Value,
+};
+use sqlparser::keywords::Keyword;
+use sqlparser::tokenizer::{Token, Tokenizer};
+
+use crate::error::{self, Result};
+use crate::query_handler::SqlQueryHandlerRef;
+
+/// How the type of a parameter can be inferred from the statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamHint {
+    /// The parameter is compared with, or inserted into the named column.
+    Column(String),
+    /// The parameter is inserted into the column at the index of the table.
+    ColumnIndex(usize),
+    /// The parameter is a row count, e.g. in `LIMIT` and `OFFSET`.
+    Integer,
+}
+
+#[derive(Debug, Clone)]
+enum Fragment {
+    Sql(String),
+    /// Placeholder of the parameter at the zero based index.
+    Param(usize),
+}
+
+/// A statement split into SQL fragments and placeholders of its parameters.
+#[derive(Debug, Clone)]
+pub struct PreparedStatement {
+    fragments: Vec<Fragment>,
+    param_hints: Vec<Option<ParamHint>>,
+    table_name: Option<String>,
+}
+
+impl PreparedStatement {
+    /// Parses a statement with `?` or `$n` placeholders. The n-th `?` is the n-th parameter,
+    /// while `$n` refers to the n-th parameter explicitly.
+    pub fn parse(sql: &str) -> Result<PreparedStatement> {
+        let tokens = Tokenizer::new(&GenericDialect {}, sql)
+            .tokenize()
+            .map_err(|e| invalid_statement(format!("failed to tokenize {}: {}", sql, e)))?;
+
+        let mut fragments = Vec::new();
+        let mut current = String::new();
+        let mut positional = 0;
+        let mut row_counts = Vec::new();
+        // Statement to infer the parameter types from, `$n` placeholders are accepted by the
+        // parser except for row counts, which must be numbers.
+        let mut analyzed = String::new();
+        let mut prev_keyword = Keyword::NoKeyword;
+        for token in &tokens {
+            if let Token::Placeholder(placeholder) = token {
+                let index = if placeholder == "?" {
+                    positional += 1;
+                    positional - 1
+                } else {
+                    placeholder_index(placeholder).ok_or_else(|| {
+                        invalid_statement(format!("invalid placeholder {}", placeholder))
+                    })?
+                };
+                fragments.push(Fragment::Sql(std::mem::take(&mut current)));
+                fragments.push(Fragment::Param(index));
+
+                if prev_keyword == Keyword::LIMIT || prev_keyword == Keyword::OFFSET {
+                    row_counts.push(index);
+                    analyzed.push('0');
+                } else {
+                    analyzed.push_str(&format!("${}", index + 1));
+                }
+            } else {
+                let raw = render_token(token);
+                current.push_str(&raw);
+                analyzed.push_str(&raw);
+            }
+
+            match token {
+                Token::Whitespace(_) => {}
+                Token::Word(word) if word.quote_style.is_none() => prev_keyword = word.keyword,
+                _ => prev_keyword = Keyword::NoKeyword,
+            }
+        }
+        fragments.push(Fragment::Sql(current));
+
+        let params_num = fragments
+            .iter()
+            .filter_map(|f| match f {
+                Fragment::Param(index) => Some(index + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+        let mut hints = HintCollector {
+            hints: vec![None; params_num],
+        };
+        for index in row_counts {
+            hints.hints[index] = Some(ParamHint::Integer);
+        }
+
+        let mut statements = ParserContext::create_with_dialect(&analyzed, &GenericDialect {})
+            .map_err(|e| invalid_statement(format!("failed to parse {}: {}", sql, e)))?;
+        ensure!(
+            statements.len() <= 1,
+            error::InvalidPrepareStatementSnafu {
+                err_msg: format!("expect a single statement, found {}", statements.len()),
+            }
+        );
+        let table_name = match statements.pop() {
+            Some(statement) => hints.visit_statement(&statement),
+            None => None,
+        };
+
+        Ok(PreparedStatement {
+            fragments,
+            param_hints: hints.hints,
+            table_name,
+        })
+    }
+
+    /// Number of parameters, i.e. the max index of all placeholders.
+    pub fn params_num(&self) -> usize {
+        self.param_hints.len()
+    }
+
+    /// How to get the type of each parameter, `None` if the type can't be inferred.
+    pub fn param_hints(&self) -> &[Option<ParamHint>] {
+        &self.param_hints
+    }
+
+    /// Name of the table whose columns are used to infer the parameter types, as written in
+    /// the statement.
+    pub fn table_name(&self) -> Option<&str> {
+        self.table_name.as_deref()
+    }
+
+    /// Replaces the placeholders with the given SQL literals.
+    pub fn bind(&self, params: &[String]) -> Result<String> {
+        ensure!(
+            params.len() == self.params_num(),
+            error::InvalidPrepareStatementSnafu {
+                err_msg: format!(
+                    "expect {} parameters, actual {}",
+                    self.params_num(),
+                    params.len()
+                ),
+            }
+        );
+
+        Ok(self
+            .fragments
+            .iter()
+            .map(|f| match f {
+                Fragment::Sql(sql) => sql.as_str(),
+                Fragment::Param(index) => params[*index].as_str(),
+            })
+            .collect())
+    }
+
+    /// Fills placeholders with `NULL`, or `0` for row counts which can't be `NULL`, used to
+    /// describe the statement before it is executed.
+    pub fn bind_nulls(&self) -> String {
+        self.fragments
+            .iter()
+            .map(|f| match f {
+                Fragment::Sql(sql) => sql.as_str(),
+                Fragment::Param(index) if self.param_hints[*index] == Some(ParamHint::Integer) => {
+                    "0"
+                }
+                Fragment::Param(_) => "NULL",
+            })
+            .collect()
+    }
+
+    /// Infers the data types of the parameters, `None` if the type of a parameter can't be
+    /// inferred. The table is only described if any type is inferred from its columns.
+    pub async fn infer_param_types(
+        &self,
+        query_handler: &SqlQueryHandlerRef,
+        query_ctx: QueryContextRef,
+    ) -> Result<Vec<Option<ConcreteDataType>>> {
+        let needs_schema = self
+            .param_hints
+            .iter()
+            .any(|hint| matches!(hint, Some(ParamHint::Column(_) | ParamHint::ColumnIndex(_))));
+        let table_schema = match &self.table_name {
+            Some(table) if needs_schema => {
+                query_handler
+                    .do_describe(&format!("SELECT * FROM {}", table), query_ctx)
+                    .await?
+            }
+            _ => None,
+        };
+
+        Ok(self
+            .param_hints
+            .iter()
+            .map(|hint| match (hint, &table_schema) {
+                (Some(ParamHint::Integer), _) => Some(ConcreteDataType::int64_datatype()),
+                (Some(ParamHint::Column(column)), Some(schema)) => schema
+                    .column_schema_by_name(column)
+                    .map(|c| c.data_type.clone()),
+                (Some(ParamHint::ColumnIndex(index)), Some(schema)) => schema
+                    .column_schemas()
+                    .get(*index)
+                    .map(|c| c.data_type.clone()),
+                _ => None,
+            })
+            .collect())
+    }
+}
+
+fn invalid_statement(err_msg: String) -> error::Error {
+    error::InvalidPrepareStatementSnafu { err_msg }.build()
+}
+
+/// Returns the zero based index of the `$n` placeholder.
+fn placeholder_index(placeholder: &str) -> Option<usize> {
+    match placeholder.strip_prefix('$')?.parse::<usize>() {
+        Ok(n) if n > 0 => Some(n - 1),
+        _ => None,
+    }
+}
+
+/// Renders the token as SQL. Unlike `Display` of the token, quotes inside quoted strings and
+/// identifiers are escaped, as the tokenizer has unescaped them.
+fn render_token(token: &Token) -> String {
+    match token {
+        Token::SingleQuotedString(s) => quote_string(s),
+        Token::NationalStringLiteral(s) => format!("N{}", quote_string(s)),
+        Token::Word(word) => match word.quote_style {
+            Some(quote) => {
+                let end = if quote == '[' { ']' } else { quote };
+                let escaped = word.value.replace(end, &format!("{}{}", end, end));
+                format!("{}{}{}", quote, escaped, end)
+            }
+            None => word.value.clone(),
+        },
+        token => token.to_string(),
+    }
+}
+
+/// Quotes `s` as a SQL string literal.
+pub fn quote_string(s: &str) -> String {
+    format!("'{}'", s.replace('\'', "''"))
+}
+
+/// Formats `bytes` as a SQL hex string literal `X'...'`, which keeps bytes that are not
+/// valid UTF-8.
+pub fn hex_literal(bytes: &[u8]) -> String {
+    format!("X'{}'", hex::encode(bytes))
+}
+
+/// Walks the statement to find how to infer the types of the parameters.
+struct HintCollector {
+    hints: Vec<Option<ParamHint>>,
+}
+
+impl HintCollector {
+    /// Collects hints from the statement, returns the name of the table to look up the
+    /// columns.
+    fn visit_statement(&mut self, statement: &Statement) -> Option<String> {
+        match statement {
+            Statement::Query(query) => {
+                self.visit_query(&query.inner);
+                query_table_name(&query.inner)
+            }
+            Statement::Insert(insert) => match &insert.inner {
+                SpStatement::Insert {
+                    table_name,
+                    columns,
+                    source,
+                    ..
+                } => {
+                    self.visit_insert_source(columns, source);
+                    Some(table_name.to_string())
+                }
+                _ => None,
+            },
+            Statement::Delete(delete) => match &delete.inner {
+                SpStatement::Delete {
+                    table_name,
+                    selection,
+                } => {
+                    self.visit_opt_expr(selection);
+                    Some(table_name.to_string())
+                }
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    fn visit_insert_source(&mut self, columns: &[Ident], source: &Query) {
+        let rows = match &source.body {
+            SetExpr::Values(values) => &values.0,
+            _ => return self.visit_query(source),
+        };
+        for row in rows {
+            for (i, expr) in row.iter().enumerate() {
+                match placeholder(expr) {
+                    Some(index) if columns.is_empty() => {
+                        self.hints[index] = Some(ParamHint::ColumnIndex(i));
+                    }
+                    Some(index) => {
+                        self.hints[index] =
+                            columns.get(i).map(|c| ParamHint::Column(c.value.clone()));
+                    }
+                    None => self.visit_expr(expr),
+                }
+            }
+        }
+    }
+
+    fn visit_query(&mut self, query: &Query) {
+        if let Some(with) = &query.with {
+            for cte in &with.cte_tables {
+                self.visit_query(&cte.query);
+            }
+        }
+        self.visit_set_expr(&query.body);
+        for order_by in &query.order_by {
+            self.visit_expr(&order_by.expr);
+        }
+    }
+
+    fn visit_set_expr(&mut self, set_expr: &SetExpr) {
+        match set_expr {
+            SetExpr::Select(select) => self.visit_select(select),
+            SetExpr::Query(query) => self.visit_query(query),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.visit_set_expr(left);
+                self.visit_set_expr(right);
+            }
+            SetExpr::Values(_) | SetExpr::Insert(_) => {}
+        }
+    }
+
+    fn visit_select(&mut self, select: &Select) {
+        for item in &select.projection {
+            match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    self.visit_expr(expr)
+                }
+                SelectItem::QualifiedWildcard(_) | SelectItem::Wildcard => {}
+            }
+        }
+        for table in &select.from {
+            self.visit_table_with_joins(table);
+        }
+        self.visit_opt_expr(&select.selection);
+        for expr in &select.group_by {
+            self.visit_expr(expr);
+        }
+        self.visit_opt_expr(&select.having);
+    }
+
+    fn visit_table_with_joins(&mut self, table: &TableWithJoins) {
+        self.visit_table_factor(&table.relation);
+        for join in &table.joins {
+            self.visit_table_factor(&join.relation);
+            match &join.join_operator {
+                JoinOperator::Inner(JoinConstraint::On(expr))
+                | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+                | JoinOperator::RightOuter(JoinConstraint::On(expr))
+                | JoinOperator::FullOuter(JoinConstraint::On(expr)) => self.visit_expr(expr),
+                _ => {}
+            }
+        }
+    }
+
+    fn visit_table_factor(&mut self, table: &TableFactor) {
+        match table {
+            TableFactor::Derived { subquery, .. } => self.visit_query(subquery),
+            TableFactor::NestedJoin(table) => self.visit_table_with_joins(table),
+            _ => {}
+        }
+    }
+
+    fn visit_opt_expr(&mut self, expr: &Option<Expr>) {
+        if let Some(expr) = expr {
+            self.visit_expr(expr);
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::BinaryOp { left, op, right } => {
+                if is_comparison(op) {
+                    self.hint_column(left, right);
+                    self.hint_column(right, left);
+                }
+                self.visit_expr(left);
+                self.visit_expr(right);
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                self.hint_column(expr, low);
+                self.hint_column(expr, high);
+                self.visit_expr(expr);
+                self.visit_expr(low);
+                self.visit_expr(high);
+            }
+            Expr::InList { expr, list, .. } => {
+                for item in list {
+                    self.hint_column(expr, item);
+                    self.visit_expr(item);
+                }
+                self.visit_expr(expr);
+            }
+            Expr::InSubquery { expr, subquery, .. } => {
+                self.visit_expr(expr);
+                self.visit_query(subquery);
+            }
+            Expr::IsNull(expr)
+            | Expr::IsNotNull(expr)
+            | Expr::UnaryOp { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::TryCast { expr, .. }
+            | Expr::Nested(expr) => self.visit_expr(expr),
+            Expr::Exists(query) | Expr::Subquery(query) => self.visit_query(query),
+            Expr::Function(function) => {
+                for arg in &function.args {
+                    match arg {
+                        FunctionArg::Named {
+                            arg: FunctionArgExpr::Expr(expr),
+                            ..
+                        }
+                        | FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => {
+                            self.visit_expr(expr)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => {
+                if let Some(operand) = operand {
+                    self.visit_expr(operand);
+                }
+                for expr in conditions.iter().chain(results) {
+                    self.visit_expr(expr);
+                }
+                if let Some(else_result) = else_result {
+                    self.visit_expr(else_result);
+                }
+            }
+            Expr::Tuple(exprs) => {
+                for expr in exprs {
+                    self.visit_expr(expr);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Hints the type of `param` by the `column` if `param` is a placeholder and `column` is
+    /// a column.
+    fn hint_column(&mut self, column: &Expr, param: &Expr) {
+        let column = match column {
+            Expr::Identifier(ident) => &ident.value,
+            Expr::CompoundIdentifier(idents) => match idents.last() {
+                Some(ident) => &ident.value,
+                None => return,
+            },
+            _ => return,
+        };
+        if let Some(index) = placeholder(param) {
+            self.hints[index] = Some(ParamHint::Column(column.clone()));
+        }
+    }
+}
+
+/// Returns the index of the parameter if the expression is a placeholder.
+fn placeholder(expr: &Expr) -> Option<usize> {
+    match expr {
+        Expr::Value(Value::Placeholder(placeholder)) => placeholder_index(placeholder),
+        _ => None,
+    }
+}
+
+fn is_comparison(op: &BinaryOperator) -> bool {
+    matches!(
+        op,
+        BinaryOperator::Eq
+            | BinaryOperator::NotEq
+            | BinaryOperator::Lt
+            | BinaryOperator::LtEq
+            | BinaryOperator::Gt
+            | BinaryOperator::GtEq
+            | BinaryOperator::Like
+            | BinaryOperator::NotLike
+    )
+}
+
+/// Name of the first table the query selects from.
+fn query_table_name(query: &Query) -> Option<String> {
+    let mut body = &query.body;
+    loop {
+        match body {
+            SetExpr::Select(select) => {
+                return match &select.from.first()?.relation {
+                    TableFactor::Table { name, .. } => Some(name.to_string()),
+                    _ => None,
+                }
+            }
+            SetExpr::Query(query) => body = &query.body,
+            SetExpr::SetOperation { left, .. } => body = left,
+            SetExpr::Values(_) | SetExpr::Insert(_) => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bind_prepared_statement() {
+        let stmt = PreparedStatement::parse("SELECT * FROM t WHERE a = ? AND b > ?").unwrap();
+        assert_eq!(2, stmt.params_num());
+        assert_eq!(
+            "SELECT * FROM t WHERE a = 1 AND b > 'x'",
+            stmt.bind(&["1".to_string(), "'x'".to_string()]).unwrap()
+        );
+        assert_eq!(
+            "SELECT * FROM t WHERE a = NULL AND b > NULL",
+            stmt.bind_nulls()
+        );
+        assert!(stmt.bind(&["1".to_string()]).is_err());
+
+        let stmt = PreparedStatement::parse(
+            "SELECT '?', 'it''s $3', \"?\" FROM t -- ?\n WHERE a = $2 AND b > $1 /* $4 */",
+        )
+        .unwrap();
+        assert_eq!(2, stmt.params_num());
+        assert_eq!(
+            "SELECT '?', 'it''s $3', \"?\" FROM t -- ?\n WHERE a = 'x' AND b > 1 /* $4 */",
+            stmt.bind(&["1".to_string(), "'x'".to_string()]).unwrap()
+        );
+
+        let stmt = PreparedStatement::parse("SELECT 1").unwrap();
+        assert_eq!(0, stmt.params_num());
+        assert_eq!("SELECT 1", stmt.bind(&[]).unwrap());
+
+        assert_eq!(0, PreparedStatement::parse("").unwrap().params_num());
+        assert!(PreparedStatement::parse("SELECT 'a").is_err());
+        assert!(PreparedStatement::parse("SELECT 1; SELECT ?").is_err());
+        assert!(PreparedStatement::parse("SELECT $0").is_err());
+    }
+
+    #[test]
+    fn test_param_hints() {
+        let stmt = PreparedStatement::parse(
+            "SELECT * FROM public.\"Numbers\" WHERE a = ? AND ? < t.b AND c BETWEEN ? AND ? \
+             AND d IN (?, ?) AND e IS NULL LIMIT ? OFFSET ?",
+        )
+        .unwrap();
+        assert_eq!(Some("public.\"Numbers\""), stmt.table_name());
+        assert_eq!(
+            &[
+                Some(ParamHint::Column("a".to_string())),
+                Some(ParamHint::Column("b".to_string())),
+                Some(ParamHint::Column("c".to_string())),
+                Some(ParamHint::Column("c".to_string())),
+                Some(ParamHint::Column("d".to_string())),
+                Some(ParamHint::Column("d".to_string())),
+                Some(ParamHint::Integer),
+                Some(ParamHint::Integer),
+            ],
+            stmt.param_hints()
+        );
+        assert_eq!(
+            "SELECT * FROM public.\"Numbers\" WHERE a = NULL AND NULL < t.b \
+             AND c BETWEEN NULL AND NULL AND d IN (NULL, NULL) AND e IS NULL LIMIT 0 OFFSET 0",
+            stmt.bind_nulls()
+        );
+
+        let stmt =
+            PreparedStatement::parse("INSERT INTO t (ts, v) VALUES ($1, $2), ($3, 1)").unwrap();
+        assert_eq!(Some("t"), stmt.table_name());
+        assert_eq!(
+            &[
+                Some(ParamHint::Column("ts".to_string())),
+                Some(ParamHint::Column("v".to_string())),
+                Some(ParamHint::Column("ts".to_string())),
+            ],
+            stmt.param_hints()
+        );
+
+        let stmt = PreparedStatement::parse("INSERT INTO t VALUES (1, ?)").unwrap();
+        assert_eq!(&[Some(ParamHint::ColumnIndex(1))], stmt.param_hints());
+
+        let stmt = PreparedStatement::parse("DELETE FROM t WHERE host = ?").unwrap();
+        assert_eq!(Some("t"), stmt.table_name());
+        assert_eq!(
+            &[Some(ParamHint::Column("host".to_string()))],
+            stmt.param_hints()
+        );
+
+        let stmt = PreparedStatement::parse("SELECT ? + 1").unwrap();
+        assert_eq!(None, stmt.table_name());
+        assert_eq!(&[None], stmt.param_hints());
+    }
+
+    #[test]
+    fn test_quote_string() {
+        assert_eq!("'abc'", quote_string("abc"));
+        assert_eq!("'it''s'", quote_string("it's"));
+        assert_eq!("X''", hex_literal(&[]));
+        assert_eq!("X'00ff27'", hex_literal(&[0, 0xff, b'\'']));
+    }
+}
@@ -16,23 +16,31 @@
 use std::cmp::Ordering;
 use std::collections::BTreeMap;
 use std::hash::{Hash, Hasher};
+use std::sync::Arc;
 
 use api::prometheus::remote::label_matcher::Type as MatcherType;
-use api::prometheus::remote::{Label, Query, Sample, TimeSeries, WriteRequest};
+use api::prometheus::remote::{Label, LabelMatcher, Query, Sample, TimeSeries, WriteRequest};
 use api::v1::codec::SelectResult;
 use api::v1::column::SemanticType;
 use api::v1::{column, Column, ColumnDataType, InsertExpr};
 use common_grpc::writer::Precision::MILLISECOND;
+use datafusion::logical_plan::{binary_expr, lit, Column, Expr, LogicalPlanBuilder, Operator};
+use datafusion::scalar::ScalarValue;
+use datatypes::schema::SchemaRef;
 use openmetrics_parser::{MetricsExposition, PrometheusType, PrometheusValue};
+use query::plan::LogicalPlan;
+use regex::Regex;
 use snafu::{OptionExt, ResultExt};
 use snap::raw::{Decoder, Encoder};
 use table::requests::InsertRequest;
+use table::table::adapter::DfTableProviderAdapter;
+use table::TableRef;
 
 use crate::error::{self, Result};
 use crate::line_writer::LineWriter;
 
-const TIMESTAMP_COLUMN_NAME: &str = "greptime_timestamp";
-const VALUE_COLUMN_NAME: &str = "greptime_value";
+pub const TIMESTAMP_COLUMN_NAME: &str = "greptime_timestamp";
+pub const VALUE_COLUMN_NAME: &str = "greptime_value";
 pub const METRIC_NAME_LABEL: &str = "__name__";
 
 /// Metrics for push gateway protocol
@@ -40,14 +48,9 @@ pub struct Metrics {
     pub exposition: MetricsExposition<PrometheusType, PrometheusValue>,
 }
 
-/// Generate a sql from a remote request query
-/// TODO(dennis): maybe use logical plan in future to prevent sql injection
-pub fn query_to_sql(db: &str, q: &Query) -> Result<(String, String)> {
-    let start_timestamp_ms = q.start_timestamp_ms;
-    let end_timestamp_ms = q.end_timestamp_ms;
-
-    let label_matches = &q.matchers;
-    let table_name = label_matches
+/// Returns the metric name of a remote read query, which is also the name of the table to read.
+pub fn query_table_name(q: &Query) -> Result<String> {
+    q.matchers
         .iter()
         .find_map(|m| {
             if m.name == METRIC_NAME_LABEL {
@@ -58,55 +61,102 @@ pub fn query_to_sql(db: &str, q: &Query) -> Result<(String, String)> {
         })
         .context(error::InvalidPromRemoteRequestSnafu {
             msg: "missing '__name__' label in timeseries",
-        })?;
-
-    let mut conditions: Vec<String> = Vec::with_capacity(label_matches.len());
-
-    conditions.push(format!(
-        "{}>={} AND {}<={}",
-        TIMESTAMP_COLUMN_NAME, start_timestamp_ms, TIMESTAMP_COLUMN_NAME, end_timestamp_ms,
-    ));
-
-    for m in label_matches {
-        let name = &m.name;
+        })
+}
 
-        if name == METRIC_NAME_LABEL {
+/// Builds the logical plan of a remote read query on `table`, the plan fetches at most `limit`
+/// samples if `limit` is present.
+pub fn query_to_plan(table: TableRef, q: &Query, limit: Option<usize>) -> Result<LogicalPlan> {
+    let table_name = query_table_name(q)?;
+    let table_schema = table.schema();
+
+    let mut conditions = Vec::with_capacity(q.matchers.len() + 2);
+    conditions.push(
+        timestamp_column().gt_eq(lit(ScalarValue::TimestampMillisecond(
+            Some(q.start_timestamp_ms),
+            None,
+        ))),
+    );
+    conditions.push(
+        timestamp_column().lt_eq(lit(ScalarValue::TimestampMillisecond(
+            Some(q.end_timestamp_ms),
+            None,
+        ))),
+    );
+    for m in &q.matchers {
+        if m.name == METRIC_NAME_LABEL {
             continue;
         }
-
-        let value = &m.value;
-        let m_type =
-            MatcherType::from_i32(m.r#type).context(error::InvalidPromRemoteRequestSnafu {
-                msg: format!("invalid LabelMatcher type: {}", m.r#type),
+        conditions.push(label_matcher_to_expr(&table_schema, m)?);
+    }
+    // Safe to unwrap since there are always conditions on the timestamp column.
+    let condition = conditions.into_iter().reduce(Expr::and).unwrap();
+
+    let table_provider = Arc::new(DfTableProviderAdapter::new(table));
+    let mut builder = LogicalPlanBuilder::scan(&table_name, table_provider, None)
+        .and_then(|builder| builder.filter(condition))
+        .and_then(|builder| builder.sort(vec![timestamp_column().sort(true, true)]))
+        .context(error::BuildPromRemoteReadPlanSnafu {
+            table_name: &table_name,
+        })?;
+    if let Some(limit) = limit {
+        builder = builder
+            .limit(limit)
+            .context(error::BuildPromRemoteReadPlanSnafu {
+                table_name: &table_name,
             })?;
+    }
+    let plan = builder
+        .build()
+        .context(error::BuildPromRemoteReadPlanSnafu { table_name })?;
+    Ok(LogicalPlan::DfPlan(plan))
+}
 
-        match m_type {
-            MatcherType::Eq => {
-                conditions.push(format!("{}='{}'", name, value));
-            }
-            MatcherType::Neq => {
-                conditions.push(format!("{}!='{}'", name, value));
-            }
-            // Case sensitive regexp match
-            MatcherType::Re => {
-                conditions.push(format!("{}~'{}'", name, value));
-            }
-            // Case sensitive regexp not match
-            MatcherType::Nre => {
-                conditions.push(format!("{}!~'{}'", name, value));
-            }
+#[inline]
+fn timestamp_column() -> Expr {
+    Expr::Column(Column::from_name(TIMESTAMP_COLUMN_NAME))
+}
+
+/// Converts the label matcher to a filter expression, following the semantics of Prometheus:
+/// - a label that does not exist is equivalent to a label with an empty value, which is stored
+///   as null;
+/// - regular expressions are fully anchored.
+fn label_matcher_to_expr(table_schema: &SchemaRef, matcher: &LabelMatcher) -> Result<Expr> {
+    let m_type =
+        MatcherType::from_i32(matcher.r#type).context(error::InvalidPromRemoteRequestSnafu {
+            msg: format!("invalid LabelMatcher type: {}", matcher.r#type),
+        })?;
+    let value = &matcher.value;
+
+    let regex = format!("^(?:{})$", value);
+    // Whether the matcher selects the series without this label.
+    let matches_empty = match m_type {
+        MatcherType::Eq => value.is_empty(),
+        MatcherType::Neq => !value.is_empty(),
+        MatcherType::Re | MatcherType::Nre => {
+            let matches_empty = Regex::new(&regex)
+                .context(error::InvalidPromRegexSnafu { regex: value })?
+                .is_match("");
+            matches_empty == (m_type == MatcherType::Re)
         }
-    }
+    };
 
-    let conditions = conditions.join(" AND ");
+    if !table_schema.contains_column(&matcher.name) {
+        return Ok(lit(matches_empty));
+    }
 
-    Ok((
-        table_name.to_string(),
-        format!(
-            "select * from {}.{} where {} order by {}",
-            db, table_name, conditions, TIMESTAMP_COLUMN_NAME,
-        ),
-    ))
+    let column = Expr::Column(Column::from_name(&matcher.name));
+    let expr = match m_type {
+        MatcherType::Eq => column.clone().eq(lit(value.as_str())),
+        MatcherType::Neq => column.clone().not_eq(lit(value.as_str())),
+        MatcherType::Re => binary_expr(column.clone(), Operator::RegexMatch, lit(regex)),
+        MatcherType::Nre => binary_expr(column.clone(), Operator::RegexNotMatch, lit(regex)),
+    };
+    if matches_empty {
+        Ok(column.is_null().or(expr))
+    } else {
+        Ok(expr)
+    }
 }
 
 #[inline]
@@ -506,29 +556,57 @@ pub fn mock_timeseries() -> Vec<TimeSeries> {
 
 #[cfg(test)]
 mod tests {
-    use std::sync::Arc;
-
-    use api::prometheus::remote::LabelMatcher;
+    use common_recordbatch::RecordBatch;
     use common_time::timestamp::TimeUnit;
     use common_time::Timestamp;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
     use datatypes::value::Value;
-    use datatypes::vectors::Vector;
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampVector, Vector, VectorRef};
+    use table::test_util::MemTable;
 
     use super::*;
 
     const EQ_TYPE: i32 = MatcherType::Eq as i32;
     const NEQ_TYPE: i32 = MatcherType::Neq as i32;
     const RE_TYPE: i32 = MatcherType::Re as i32;
+    const NRE_TYPE: i32 = MatcherType::Nre as i32;
+
+    fn prom_testing_table() -> TableRef {
+        let column_schemas = vec![
+            ColumnSchema::new(
+                TIMESTAMP_COLUMN_NAME,
+                ConcreteDataType::timestamp_millis_datatype(),
+                false,
+            ),
+            ColumnSchema::new(
+                VALUE_COLUMN_NAME,
+                ConcreteDataType::float64_datatype(),
+                true,
+            ),
+            ColumnSchema::new("job", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("instance", ConcreteDataType::string_datatype(), true),
+        ];
+        let schema = Arc::new(Schema::new(column_schemas));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(TimestampVector::from_values(vec![1000])),
+            Arc::new(Float64Vector::from_vec(vec![1.0])),
+            Arc::new(StringVector::from(vec!["prometheus"])),
+            Arc::new(StringVector::from(vec!["localhost"])),
+        ];
+        let recordbatch = RecordBatch::new(schema, columns).unwrap();
+        Arc::new(MemTable::new("test", recordbatch))
+    }
 
     #[test]
-    fn test_query_to_sql() {
+    fn test_query_table_name() {
         let q = Query {
             start_timestamp_ms: 1000,
             end_timestamp_ms: 2000,
             matchers: vec![],
             ..Default::default()
         };
-        let err = query_to_sql("public", &q).unwrap_err();
+        let err = query_table_name(&q).unwrap_err();
         assert!(matches!(err, error::Error::InvalidPromRemoteRequest { .. }));
 
         let q = Query {
@@ -541,10 +619,11 @@ mod tests {
             }],
             ..Default::default()
         };
-        let (table, sql) = query_to_sql("public", &q).unwrap();
-        assert_eq!("test", table);
-        assert_eq!("select * from public.test where greptime_timestamp>=1000 AND greptime_timestamp<=2000 order by greptime_timestamp", sql);
+        assert_eq!("test", query_table_name(&q).unwrap());
+    }
 
+    #[test]
+    fn test_query_to_plan() {
         let q = Query {
             start_timestamp_ms: 1000,
             end_timestamp_ms: 2000,
@@ -556,20 +635,96 @@ mod tests {
                 },
                 LabelMatcher {
                     name: "job".to_string(),
-                    value: "*prom*".to_string(),
+                    value: ".*prom.*".to_string(),
                     r#type: RE_TYPE,
                 },
                 LabelMatcher {
                     name: "instance".to_string(),
-                    value: "localhost".to_string(),
+                    value: "localhost'; drop table test; --".to_string(),
                     r#type: NEQ_TYPE,
                 },
             ],
             ..Default::default()
         };
-        let (table, sql) = query_to_sql("public", &q).unwrap();
-        assert_eq!("test", table);
-        assert_eq!("select * from public.test where greptime_timestamp>=1000 AND greptime_timestamp<=2000 AND job~'*prom*' AND instance!='localhost' order by greptime_timestamp", sql);
+        let LogicalPlan::DfPlan(plan) = query_to_plan(prom_testing_table(), &q, Some(10)).unwrap();
+        let plan = format!("{:?}", plan);
+        assert!(plan.starts_with("Limit: 10"), "{}", plan);
+        assert!(plan.contains("Sort: #greptime_timestamp"), "{}", plan);
+        // The value is kept as a literal instead of being spliced into a statement.
+        assert!(
+            plan.contains(r#"#instance != Utf8("localhost'; drop table test; --")"#),
+            "{}",
+            plan
+        );
+        assert!(
+            plan.contains(r#"#job ~ Utf8("^(?:.*prom.*)$")"#),
+            "{}",
+            plan
+        );
+        assert!(plan.contains("TableScan: test"), "{}", plan);
+
+        let q = Query {
+            matchers: vec![
+                LabelMatcher {
+                    name: METRIC_NAME_LABEL.to_string(),
+                    value: "test".to_string(),
+                    r#type: EQ_TYPE,
+                },
+                LabelMatcher {
+                    name: "job".to_string(),
+                    value: "*prom*".to_string(),
+                    r#type: RE_TYPE,
+                },
+            ],
+            ..Default::default()
+        };
+        let err = query_to_plan(prom_testing_table(), &q, None).unwrap_err();
+        assert!(matches!(err, error::Error::InvalidPromRegex { .. }));
+    }
+
+    #[test]
+    fn test_label_matcher_to_expr() {
+        let schema = prom_testing_table().schema();
+        let matcher = |name: &str, value: &str, r#type: i32| LabelMatcher {
+            name: name.to_string(),
+            value: value.to_string(),
+            r#type,
+        };
+
+        // Labels that don't exist are treated as empty.
+        let expr = label_matcher_to_expr(&schema, &matcher("host", "", EQ_TYPE)).unwrap();
+        assert_eq!(lit(true), expr);
+        let expr = label_matcher_to_expr(&schema, &matcher("host", "a", EQ_TYPE)).unwrap();
+        assert_eq!(lit(false), expr);
+        let expr = label_matcher_to_expr(&schema, &matcher("host", "a", NEQ_TYPE)).unwrap();
+        assert_eq!(lit(true), expr);
+        let expr = label_matcher_to_expr(&schema, &matcher("host", "a.*", RE_TYPE)).unwrap();
+        assert_eq!(lit(false), expr);
+        let expr = label_matcher_to_expr(&schema, &matcher("host", "a.*", NRE_TYPE)).unwrap();
+        assert_eq!(lit(true), expr);
+
+        let job = Expr::Column(Column::from_name("job"));
+        let expr = label_matcher_to_expr(&schema, &matcher("job", "prom", EQ_TYPE)).unwrap();
+        assert_eq!(job.clone().eq(lit("prom")), expr);
+        let expr = label_matcher_to_expr(&schema, &matcher("job", "prom", NEQ_TYPE)).unwrap();
+        assert_eq!(
+            job.clone().is_null().or(job.clone().not_eq(lit("prom"))),
+            expr
+        );
+        let expr = label_matcher_to_expr(&schema, &matcher("job", "p.*", RE_TYPE)).unwrap();
+        assert_eq!(
+            binary_expr(job.clone(), Operator::RegexMatch, lit("^(?:p.*)$")),
+            expr
+        );
+        let expr = label_matcher_to_expr(&schema, &matcher("job", ".*", RE_TYPE)).unwrap();
+        assert_eq!(
+            job.clone().is_null().or(binary_expr(
+                job.clone(),
+                Operator::RegexMatch,
+                lit("^(?:.*)$")
+            )),
+            expr
+        );
     }
 
     #[test]
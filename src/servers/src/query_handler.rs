@@ -18,8 +18,12 @@ use api::prometheus::remote::{ReadRequest, WriteRequest};
 use api::v1::{AdminExpr, AdminResult, ObjectExpr, ObjectResult};
 use async_trait::async_trait;
 use common_query::Output;
+use datatypes::schema::SchemaRef;
+use promql::value::{Labels, Value};
+use promql::EvalRange;
+use session::context::QueryContextRef;
 
-use crate::error::Result;
+use crate::error::{self, Result};
 use crate::influxdb::InfluxdbRequest;
 use crate::opentsdb::codec::DataPoint;
 use crate::prometheus::Metrics;
@@ -41,10 +45,34 @@ pub type OpentsdbProtocolHandlerRef = Arc<dyn OpentsdbProtocolHandler + Send + S
 pub type InfluxdbLineProtocolHandlerRef = Arc<dyn InfluxdbLineProtocolHandler + Send + Sync>;
 pub type PrometheusProtocolHandlerRef = Arc<dyn PrometheusProtocolHandler + Send + Sync>;
 pub type ScriptHandlerRef = Arc<dyn ScriptHandler + Send + Sync>;
+pub type PromqlHandlerRef = Arc<dyn PromqlHandler + Send + Sync>;
 
 #[async_trait]
 pub trait SqlQueryHandler {
-    async fn do_query(&self, query: &str) -> Result<Output>;
+    /// Executes the `query`, unqualified table names are resolved against the current catalog
+    /// and schema in `query_ctx`.
+    async fn do_query(&self, query: &str, query_ctx: QueryContextRef) -> Result<Output>;
+
+    /// Describes the result set of the `query` without executing it, returns `None` if the
+    /// query has no result set or describing is not supported.
+    async fn do_describe(
+        &self,
+        _query: &str,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Option<SchemaRef>> {
+        Ok(None)
+    }
+
+    /// Checks whether the `schema` exists in the `catalog`.
+    fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool>;
+
+    /// Kills the queries running on the connection `connection_id`.
+    fn kill_query(&self, _connection_id: u32) -> Result<()> {
+        error::NotSupportedSnafu {
+            feat: "killing queries",
+        }
+        .fail()
+    }
 }
 
 #[async_trait]
@@ -92,3 +120,36 @@ pub trait PrometheusProtocolHandler {
     /// Handling push gateway requests
     async fn ingest_metrics(&self, metrics: Metrics) -> Result<()>;
 }
+
+#[async_trait]
+pub trait PromqlHandler {
+    /// Evaluates the PromQL `query` at the instant `time` (in milliseconds).
+    async fn query(&self, database: &str, query: &str, time: i64) -> Result<Value>;
+    /// Evaluates the PromQL `query` at each step of `range`.
+    async fn query_range(&self, database: &str, query: &str, range: EvalRange) -> Result<Value>;
+    /// Returns the label sets of series matching any of the selectors in `matches`.
+    async fn series(
+        &self,
+        database: &str,
+        matches: &[String],
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Labels>>;
+    /// Returns the label names, of all series if `matches` is empty.
+    async fn label_names(
+        &self,
+        database: &str,
+        matches: &[String],
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<String>>;
+    /// Returns the values of label `name`, of all series if `matches` is empty.
+    async fn label_values(
+        &self,
+        database: &str,
+        name: &str,
+        matches: &[String],
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<String>>;
+}
@@ -0,0 +1,470 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use common_telemetry::{error, info, warn};
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use rustls::server::AllowAnyAuthenticatedClient;
+use rustls::{Certificate, PrivateKey, RootCertStore, ServerConfig};
+use rustls_pemfile::Item;
+use serde::{Deserialize, Serialize};
+use snafu::{ensure, OptionExt, ResultExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_stream::wrappers::ReceiverStream;
+
+use crate::error::{self, Result};
+
+/// ALPN protocols of the HTTP server.
+pub(crate) const HTTP_ALPN_PROTOCOLS: &[&[u8]] = &[b"h2", b"http/1.1"];
+/// ALPN protocols of the gRPC server, gRPC is always over HTTP/2.
+pub(crate) const GRPC_ALPN_PROTOCOLS: &[&[u8]] = &[b"h2"];
+
+/// Size of the buffer holding the connections whose TLS handshakes are done.
+const TLS_INCOMING_BUFFER_SIZE: usize = 128;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TlsMode {
+    /// Only accepts plain connections.
+    #[default]
+    Disable,
+    /// Accepts both plain and TLS connections, for protocols able to upgrade a connection to
+    /// TLS, i.e. MySQL and PostgreSQL. Other protocols only accept TLS connections.
+    Prefer,
+    /// Only accepts TLS connections.
+    Require,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TlsOption {
+    pub mode: TlsMode,
+    /// Path of the PEM encoded certificate chain.
+    pub cert_path: String,
+    /// Path of the PEM encoded private key, in PKCS#8, RSA or EC format.
+    pub key_path: String,
+    /// Path of the PEM encoded CA certificates to verify client certificates. Clients must
+    /// present a valid certificate (mutual TLS) if it's set.
+    pub ca_cert_path: Option<String>,
+    /// Reloads the certificates and keys when the files change.
+    pub watch: bool,
+}
+
+impl TlsOption {
+    pub fn new(mode: TlsMode, cert_path: impl Into<String>, key_path: impl Into<String>) -> Self {
+        Self {
+            mode,
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.mode != TlsMode::Disable
+    }
+
+    pub fn should_force_tls(&self) -> bool {
+        self.mode == TlsMode::Require
+    }
+
+    /// Builds the rustls server config, returns `None` if TLS is disabled.
+    pub fn setup(&self) -> Result<Option<ServerConfig>> {
+        if !self.enabled() {
+            return Ok(None);
+        }
+        ensure!(
+            !self.cert_path.is_empty() && !self.key_path.is_empty(),
+            error::InvalidTlsConfigSnafu {
+                msg: "cert_path and key_path are required when TLS is enabled",
+            }
+        );
+
+        let certs = load_certs(&self.cert_path)?;
+        let key = load_private_key(&self.key_path)?;
+
+        let builder = ServerConfig::builder().with_safe_defaults();
+        let builder = match &self.ca_cert_path {
+            Some(ca_cert_path) => {
+                let mut roots = RootCertStore::empty();
+                for cert in load_certs(ca_cert_path)? {
+                    roots.add(&cert).map_err(|e| {
+                        error::InvalidTlsConfigSnafu {
+                            msg: format!("invalid CA certificate in {}: {}", ca_cert_path, e),
+                        }
+                        .build()
+                    })?;
+                }
+                builder.with_client_cert_verifier(AllowAnyAuthenticatedClient::new(roots))
+            }
+            None => builder.with_no_client_auth(),
+        };
+        let config = builder
+            .with_single_cert(certs, key)
+            .context(error::BuildTlsConfigSnafu)?;
+        Ok(Some(config))
+    }
+
+    /// Files to watch for reloading.
+    fn files(&self) -> Vec<&str> {
+        let mut files = vec![self.cert_path.as_str(), self.key_path.as_str()];
+        if let Some(ca_cert_path) = &self.ca_cert_path {
+            files.push(ca_cert_path);
+        }
+        files
+    }
+}
+
+fn load_certs(path: &str) -> Result<Vec<Certificate>> {
+    let file = File::open(path).context(error::ReadTlsFileSnafu { path })?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .context(error::ReadTlsFileSnafu { path })?;
+    ensure!(
+        !certs.is_empty(),
+        error::InvalidTlsConfigSnafu {
+            msg: format!("no certificate found in {}", path),
+        }
+    );
+    Ok(certs.into_iter().map(Certificate).collect())
+}
+
+fn load_private_key(path: &str) -> Result<PrivateKey> {
+    let file = File::open(path).context(error::ReadTlsFileSnafu { path })?;
+    let items = rustls_pemfile::read_all(&mut BufReader::new(file))
+        .context(error::ReadTlsFileSnafu { path })?;
+    items
+        .into_iter()
+        .find_map(|item| match item {
+            Item::PKCS8Key(key) | Item::RSAKey(key) | Item::ECKey(key) => Some(PrivateKey(key)),
+            _ => None,
+        })
+        .context(error::InvalidTlsConfigSnafu {
+            msg: format!("no private key found in {}", path),
+        })
+}
+
+pub type TlsServerConfigRef = Arc<ReloadableTlsServerConfig>;
+
+/// TLS server config which can be reloaded at runtime, servers should get the latest config
+/// for each new connection.
+#[derive(Default)]
+pub struct ReloadableTlsServerConfig {
+    tls_option: TlsOption,
+    config: RwLock<Option<Arc<ServerConfig>>>,
+}
+
+impl ReloadableTlsServerConfig {
+    pub fn try_new(tls_option: TlsOption) -> Result<Self> {
+        let config = tls_option.setup()?.map(Arc::new);
+        Ok(Self {
+            tls_option,
+            config: RwLock::new(config),
+        })
+    }
+
+    pub fn tls_option(&self) -> &TlsOption {
+        &self.tls_option
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.tls_option.enabled()
+    }
+
+    pub fn should_force_tls(&self) -> bool {
+        self.tls_option.should_force_tls()
+    }
+
+    pub fn get_server_config(&self) -> Option<Arc<ServerConfig>> {
+        self.config.read().unwrap().clone()
+    }
+
+    /// Rebuilds the config from the files, the current config is kept if it fails.
+    pub fn reload(&self) -> Result<()> {
+        let config = self.tls_option.setup()?.map(Arc::new);
+        *self.config.write().unwrap() = config;
+        Ok(())
+    }
+}
+
+/// Reloads the TLS server config on changes of the certificate and key files, if TLS is enabled
+/// and `watch` is set.
+pub fn maybe_watch_tls_config(tls_server_config: TlsServerConfigRef) -> Result<()> {
+    let tls_option = tls_server_config.tls_option();
+    if !tls_option.enabled() || !tls_option.watch {
+        return Ok(());
+    }
+
+    // Watches the parent directories of the configured paths instead of the files, since a
+    // file replaced by renaming (e.g. by editors) is no longer watched, and the files may be
+    // symlinks swapped to rotate them (e.g. the `..data` symlink of Kubernetes secrets), which
+    // only changes the directory holding the symlinks.
+    let dirs = tls_option
+        .files()
+        .into_iter()
+        .map(|path| match Path::new(path).parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        })
+        .collect::<BTreeSet<_>>();
+
+    let (tx, rx) = std::sync::mpsc::channel::<notify::Result<Event>>();
+    let mut watcher = notify::recommended_watcher(tx).context(error::FileWatchSnafu {
+        path: &tls_option.cert_path,
+    })?;
+    for dir in &dirs {
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .context(error::FileWatchSnafu {
+                path: dir.display().to_string(),
+            })?;
+    }
+
+    std::thread::Builder::new()
+        .name("tls-config-watcher".to_string())
+        .spawn(move || {
+            // The watcher stops watching once dropped.
+            let _watcher = watcher;
+            while let Ok(result) = rx.recv() {
+                match result {
+                    Ok(event) if is_changed(&event) => {
+                        info!("TLS files changed, reloading TLS config: {:?}", event.paths);
+                        if let Err(e) = tls_server_config.reload() {
+                            error!(e; "Failed to reload TLS config");
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => error!("Failed to watch TLS files, error: {}", e),
+                }
+            }
+        })?;
+    Ok(())
+}
+
+/// Any change in the watched directories reloads the config, since which entry a configured
+/// path resolves to can't be told from the event, e.g. a renamed symlink of its ancestors.
+fn is_changed(event: &Event) -> bool {
+    matches!(
+        event.kind,
+        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+    )
+}
+
+/// Accepts TLS connections from the `listener`. Handshakes are done in separate tasks so a
+/// slow client doesn't block others, failed handshakes are logged and dropped.
+pub(crate) fn tls_incoming(
+    listener: TcpListener,
+    tls_server_config: TlsServerConfigRef,
+    alpn_protocols: &'static [&'static [u8]],
+) -> ReceiverStream<io::Result<TlsStream<TcpStream>>> {
+    let (tx, rx) = mpsc::channel(TLS_INCOMING_BUFFER_SIZE);
+    tokio::spawn(async move {
+        loop {
+            let accepted = tokio::select! {
+                // The server is shutdown.
+                _ = tx.closed() => break,
+                accepted = listener.accept() => accepted,
+            };
+            let (stream, addr) = match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    error!("Failed to accept TCP connection, error: {}", e);
+                    continue;
+                }
+            };
+            let mut config = match tls_server_config.get_server_config() {
+                Some(config) => ServerConfig::clone(&config),
+                None => {
+                    warn!("TLS is disabled, drop the connection from {}", addr);
+                    continue;
+                }
+            };
+            config.alpn_protocols = alpn_protocols.iter().map(|p| p.to_vec()).collect();
+            let acceptor = TlsAcceptor::from(Arc::new(config));
+
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                match acceptor.accept(stream).await {
+                    Ok(stream) => {
+                        let _ = tx.send(Ok(stream)).await;
+                    }
+                    Err(e) => warn!("TLS handshake with {} failed, error: {}", addr, e),
+                }
+            });
+        }
+    });
+    ReceiverStream::new(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tempdir::TempDir;
+
+    use super::*;
+
+    fn test_file(name: &str) -> String {
+        format!("{}/tests/tls/{}", env!("CARGO_MANIFEST_DIR"), name)
+    }
+
+    /// Waits for the config to be replaced by the watcher.
+    fn wait_reloaded(tls_server_config: &TlsServerConfigRef, config: &Arc<ServerConfig>) -> bool {
+        for _ in 0..50 {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            let current = tls_server_config.get_server_config().unwrap();
+            if !Arc::ptr_eq(config, &current) {
+                return true;
+            }
+        }
+        false
+    }
+
+    #[test]
+    fn test_tls_option_serde() {
+        let option: TlsOption = serde_json::from_str(
+            r#"{"mode": "require", "cert_path": "/path/to/cert", "key_path": "/path/to/key"}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            TlsOption::new(TlsMode::Require, "/path/to/cert", "/path/to/key"),
+            option
+        );
+        assert!(option.should_force_tls());
+
+        let option: TlsOption = serde_json::from_str("{}").unwrap();
+        assert_eq!(TlsOption::default(), option);
+        assert!(!option.enabled());
+    }
+
+    #[test]
+    fn test_setup_tls_option() {
+        assert!(TlsOption::default().setup().unwrap().is_none());
+
+        for key in ["server.key", "server-rsa.key"] {
+            let option = TlsOption::new(TlsMode::Prefer, test_file("server.crt"), test_file(key));
+            assert!(option.setup().unwrap().is_some());
+            assert!(!option.should_force_tls());
+        }
+
+        // Mutual TLS.
+        let option = TlsOption {
+            ca_cert_path: Some(test_file("ca.crt")),
+            ..TlsOption::new(
+                TlsMode::Require,
+                test_file("server.crt"),
+                test_file("server.key"),
+            )
+        };
+        assert!(option.setup().unwrap().is_some());
+
+        let option = TlsOption::new(TlsMode::Require, "", "");
+        assert!(matches!(
+            option.setup(),
+            Err(error::Error::InvalidTlsConfig { .. })
+        ));
+        let option = TlsOption::new(TlsMode::Require, "/not/exist", test_file("server.key"));
+        assert!(matches!(
+            option.setup(),
+            Err(error::Error::ReadTlsFile { .. })
+        ));
+        // Certificates are not keys.
+        let option = TlsOption::new(
+            TlsMode::Require,
+            test_file("server.crt"),
+            test_file("server.crt"),
+        );
+        assert!(matches!(
+            option.setup(),
+            Err(error::Error::InvalidTlsConfig { .. })
+        ));
+    }
+
+    #[test]
+    fn test_reload_tls_config() {
+        let dir = TempDir::new("test_reload_tls_config").unwrap();
+        let cert_path = dir.path().join("server.crt");
+        let key_path = dir.path().join("server.key");
+        std::fs::copy(test_file("server.crt"), &cert_path).unwrap();
+        std::fs::copy(test_file("server.key"), &key_path).unwrap();
+
+        let option = TlsOption {
+            watch: true,
+            ..TlsOption::new(
+                TlsMode::Require,
+                cert_path.to_str().unwrap(),
+                key_path.to_str().unwrap(),
+            )
+        };
+        let tls_server_config = Arc::new(ReloadableTlsServerConfig::try_new(option).unwrap());
+        let config = tls_server_config.get_server_config().unwrap();
+        maybe_watch_tls_config(tls_server_config.clone()).unwrap();
+
+        // Switches to the RSA key.
+        let content = std::fs::read(test_file("server-rsa.key")).unwrap();
+        let mut file = File::create(&key_path).unwrap();
+        file.write_all(&content).unwrap();
+        file.sync_all().unwrap();
+        assert!(wait_reloaded(&tls_server_config, &config));
+
+        // Invalid files don't affect the current config.
+        std::fs::write(&key_path, "invalid key").unwrap();
+        assert!(tls_server_config.reload().is_err());
+        assert!(tls_server_config.get_server_config().is_some());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_reload_tls_config_by_symlink_swap() {
+        use std::os::unix::fs::symlink;
+
+        // Lays out the files like a Kubernetes secret volume, the configured paths are
+        // symlinks to `..data/<file>`, and `..data` is swapped to rotate the files.
+        let dir = TempDir::new("test_reload_tls_config_by_symlink_swap").unwrap();
+        for (version, key) in [("v1", "server.key"), ("v2", "server-rsa.key")] {
+            let version_dir = dir.path().join(version);
+            std::fs::create_dir(&version_dir).unwrap();
+            std::fs::copy(test_file("server.crt"), version_dir.join("server.crt")).unwrap();
+            std::fs::copy(test_file(key), version_dir.join("server.key")).unwrap();
+        }
+        symlink("v1", dir.path().join("..data")).unwrap();
+        let cert_path = dir.path().join("server.crt");
+        let key_path = dir.path().join("server.key");
+        symlink("..data/server.crt", &cert_path).unwrap();
+        symlink("..data/server.key", &key_path).unwrap();
+
+        let option = TlsOption {
+            watch: true,
+            ..TlsOption::new(
+                TlsMode::Require,
+                cert_path.to_str().unwrap(),
+                key_path.to_str().unwrap(),
+            )
+        };
+        let tls_server_config = Arc::new(ReloadableTlsServerConfig::try_new(option).unwrap());
+        let config = tls_server_config.get_server_config().unwrap();
+        maybe_watch_tls_config(tls_server_config.clone()).unwrap();
+
+        symlink("v2", dir.path().join("..data_tmp")).unwrap();
+        std::fs::rename(dir.path().join("..data_tmp"), dir.path().join("..data")).unwrap();
+        assert!(wait_reloaded(&tls_server_config, &config));
+    }
+}
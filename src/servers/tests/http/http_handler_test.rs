@@ -32,6 +32,7 @@ async fn test_sql_not_provided() {
             script_handler: None,
         }),
         Query(http_handler::SqlQuery::default()),
+        None,
     )
     .await;
     assert!(!json.success());
@@ -55,6 +56,7 @@ async fn test_sql_output_rows() {
             script_handler: None,
         }),
         query,
+        None,
     )
     .await;
     assert!(json.success(), "{:?}", json);
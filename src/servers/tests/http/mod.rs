@@ -16,3 +16,4 @@ mod http_handler_test;
 mod influxdb_test;
 mod opentsdb_test;
 mod prometheus_test;
+mod promql_test;
@@ -0,0 +1,216 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use axum::Router;
+use axum_test_helper::TestClient;
+use common_query::Output;
+use promql::value::{Labels, Sample, Series, Value, VectorElement};
+use promql::EvalRange;
+use serde_json::json;
+use servers::error::Result;
+use servers::http::promql::PromqlJsonResponse;
+use servers::http::{HttpOptions, HttpServer};
+use servers::query_handler::{PromqlHandler, SqlQueryHandler};
+use session::context::QueryContextRef;
+
+#[derive(Default)]
+struct DummyInstance {
+    requests: Mutex<Vec<String>>,
+}
+
+impl DummyInstance {
+    fn record(&self, request: String) {
+        self.requests.lock().unwrap().push(request);
+    }
+}
+
+fn labels() -> Labels {
+    [("__name__", "up"), ("job", "node")]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+#[async_trait]
+impl PromqlHandler for DummyInstance {
+    async fn query(&self, database: &str, query: &str, time: i64) -> Result<Value> {
+        self.record(format!("query {} {} {}", database, query, time));
+        Ok(Value::Vector(vec![VectorElement {
+            labels: labels(),
+            sample: Sample::new(time, 1.0),
+        }]))
+    }
+
+    async fn query_range(&self, database: &str, query: &str, range: EvalRange) -> Result<Value> {
+        self.record(format!(
+            "query_range {} {} {} {} {}",
+            database, query, range.start, range.end, range.step
+        ));
+        Ok(Value::Matrix(vec![Series {
+            labels: labels(),
+            samples: vec![Sample::new(range.start, 1.0), Sample::new(range.end, 0.0)],
+        }]))
+    }
+
+    async fn series(
+        &self,
+        database: &str,
+        matches: &[String],
+        start: i64,
+        end: i64,
+    ) -> Result<Vec<Labels>> {
+        self.record(format!(
+            "series {} {:?} {} {}",
+            database, matches, start, end
+        ));
+        Ok(vec![labels()])
+    }
+
+    async fn label_names(
+        &self,
+        database: &str,
+        matches: &[String],
+        _start: i64,
+        _end: i64,
+    ) -> Result<Vec<String>> {
+        self.record(format!("label_names {} {:?}", database, matches));
+        Ok(labels().into_keys().collect())
+    }
+
+    async fn label_values(
+        &self,
+        database: &str,
+        name: &str,
+        matches: &[String],
+        _start: i64,
+        _end: i64,
+    ) -> Result<Vec<String>> {
+        self.record(format!("label_values {} {} {:?}", database, name, matches));
+        Ok(labels().remove(name).into_iter().collect())
+    }
+}
+
+#[async_trait]
+impl SqlQueryHandler for DummyInstance {
+    async fn do_query(&self, _: &str, _: QueryContextRef) -> Result<Output> {
+        unimplemented!()
+    }
+
+    fn is_valid_schema(&self, _catalog: &str, _schema: &str) -> Result<bool> {
+        Ok(true)
+    }
+}
+
+fn make_test_app(instance: Arc<DummyInstance>) -> Router {
+    let mut server = HttpServer::new(instance.clone(), HttpOptions::default());
+    server.set_promql_handler(instance);
+    server.make_app()
+}
+
+async fn get_json(client: &TestClient, url: &str) -> (u16, PromqlJsonResponse) {
+    let result = client.get(url).send().await;
+    let status = result.status().as_u16();
+    (status, serde_json::from_str(&result.text().await).unwrap())
+}
+
+#[tokio::test]
+async fn test_promql_api() {
+    let instance = Arc::new(DummyInstance::default());
+    let client = TestClient::new(make_test_app(instance.clone()));
+
+    let (status, response) = get_json(
+        &client,
+        "/v1/prometheus/api/v1/query?query=up%7Bjob%3D%22node%22%7D&time=1.5",
+    )
+    .await;
+    assert_eq!(200, status);
+    assert_eq!("success", response.status);
+    assert_eq!(
+        Some(json!({
+            "resultType": "vector",
+            "result": [{"metric": {"__name__": "up", "job": "node"}, "value": [1.5, "1"]}],
+        })),
+        response.data
+    );
+
+    let result = client
+        .post("/v1/prometheus/api/v1/query_range?db=prometheus")
+        .body("query=up&start=2022-10-17T09:46:40Z&end=1666000060&step=30s")
+        .send()
+        .await;
+    assert_eq!(200, result.status());
+    let response: PromqlJsonResponse = serde_json::from_str(&result.text().await).unwrap();
+    assert_eq!(
+        Some(json!({
+            "resultType": "matrix",
+            "result": [{
+                "metric": {"__name__": "up", "job": "node"},
+                "values": [[1666000000.0, "1"], [1666000060.0, "0"]],
+            }],
+        })),
+        response.data
+    );
+
+    let (status, response) = get_json(
+        &client,
+        "/v1/prometheus/api/v1/series?match[]=up&match[]=node_load1",
+    )
+    .await;
+    assert_eq!(200, status);
+    assert_eq!(
+        Some(json!([{"__name__": "up", "job": "node"}])),
+        response.data
+    );
+
+    let (status, response) = get_json(&client, "/v1/prometheus/api/v1/labels").await;
+    assert_eq!(200, status);
+    assert_eq!(Some(json!(["__name__", "job"])), response.data);
+
+    let (status, response) =
+        get_json(&client, "/v1/prometheus/api/v1/label/job/values?match[]=up").await;
+    assert_eq!(200, status);
+    assert_eq!(Some(json!(["node"])), response.data);
+
+    assert_eq!(
+        vec![
+            "query public up{job=\"node\"} 1500",
+            "query_range prometheus up 1666000000000 1666000060000 30000",
+            "series public [\"up\", \"node_load1\"] -9223372036854775808 9223372036854775807",
+            "label_names public []",
+            "label_values public job [\"up\"]",
+        ],
+        *instance.requests.lock().unwrap()
+    );
+
+    // Invalid requests are rejected before reaching the handler.
+    let (status, response) = get_json(&client, "/v1/prometheus/api/v1/query").await;
+    assert_eq!(400, status);
+    assert_eq!("error", response.status);
+    assert_eq!(Some("bad_data".to_string()), response.error_type);
+
+    let (status, response) = get_json(
+        &client,
+        "/v1/prometheus/api/v1/query_range?query=up&start=10&end=0&step=1",
+    )
+    .await;
+    assert_eq!(400, status);
+    assert_eq!(Some("bad_data".to_string()), response.error_type);
+
+    let (status, _) = get_json(&client, "/v1/prometheus/api/v1/series").await;
+    assert_eq!(400, status);
+    assert_eq!(5, instance.requests.lock().unwrap().len());
+}
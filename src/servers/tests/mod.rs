@@ -13,18 +13,20 @@
 // limitations under the License.
 
 use std::collections::HashMap;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 
 use async_trait::async_trait;
 use catalog::local::{MemoryCatalogManager, MemoryCatalogProvider, MemorySchemaProvider};
 use catalog::{CatalogList, CatalogProvider, SchemaProvider};
 use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_query::Output;
+use datatypes::schema::SchemaRef;
 use query::{QueryEngineFactory, QueryEngineRef};
 use servers::error::Result;
 use servers::query_handler::{
     ScriptHandler, ScriptHandlerRef, SqlQueryHandler, SqlQueryHandlerRef,
 };
+use session::context::QueryContextRef;
 use table::test_util::MemTable;
 
 mod http;
@@ -38,6 +40,8 @@ struct DummyInstance {
     query_engine: QueryEngineRef,
     py_engine: Arc<PyEngine>,
     scripts: RwLock<HashMap<String, Arc<PyScript>>>,
+    /// Ids of the connections whose queries are killed.
+    killed_connections: Mutex<Vec<u32>>,
 }
 
 impl DummyInstance {
@@ -45,17 +49,40 @@ impl DummyInstance {
         Self {
             py_engine: Arc::new(PyEngine::new(query_engine.clone())),
             scripts: RwLock::new(HashMap::new()),
+            killed_connections: Mutex::new(vec![]),
             query_engine,
         }
     }
+
+    fn killed_connections(&self) -> Vec<u32> {
+        self.killed_connections.lock().unwrap().clone()
+    }
 }
 
 #[async_trait]
 impl SqlQueryHandler for DummyInstance {
-    async fn do_query(&self, query: &str) -> Result<Output> {
-        let plan = self.query_engine.sql_to_plan(query).unwrap();
+    async fn do_query(&self, query: &str, query_ctx: QueryContextRef) -> Result<Output> {
+        let plan = self.query_engine.sql_to_plan(query, query_ctx).unwrap();
         Ok(self.query_engine.execute(&plan).await.unwrap())
     }
+
+    async fn do_describe(
+        &self,
+        query: &str,
+        query_ctx: QueryContextRef,
+    ) -> Result<Option<SchemaRef>> {
+        let plan = self.query_engine.sql_to_plan(query, query_ctx).unwrap();
+        Ok(Some(plan.schema().unwrap()))
+    }
+
+    fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool> {
+        Ok(catalog == DEFAULT_CATALOG_NAME && schema == DEFAULT_SCHEMA_NAME)
+    }
+
+    fn kill_query(&self, connection_id: u32) -> Result<()> {
+        self.killed_connections.lock().unwrap().push(connection_id);
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -19,18 +19,25 @@ use std::time::Duration;
 use common_recordbatch::RecordBatch;
 use common_runtime::Builder as RuntimeBuilder;
 use datatypes::schema::Schema;
+use mysql_async::consts::ColumnType;
 use mysql_async::prelude::*;
 use rand::rngs::StdRng;
 use rand::Rng;
+use servers::auth::{user_provider_from_option, UserProviderRef};
 use servers::error::Result;
 use servers::mysql::server::MysqlServer;
 use servers::server::Server;
+use servers::tls::{ReloadableTlsServerConfig, TlsMode, TlsOption};
 use table::test_util::MemTable;
 
 use crate::create_testing_sql_query_handler;
 use crate::mysql::{all_datatype_testing_data, MysqlTextRow, TestingData};
 
-fn create_mysql_server(table: MemTable) -> Result<Box<dyn Server>> {
+fn create_mysql_server(
+    table: MemTable,
+    tls: TlsOption,
+    user_provider: Option<UserProviderRef>,
+) -> Result<Box<dyn Server>> {
     let query_handler = create_testing_sql_query_handler(table);
     let io_runtime = Arc::new(
         RuntimeBuilder::default()
@@ -39,14 +46,19 @@ fn create_mysql_server(table: MemTable) -> Result<Box<dyn Server>> {
             .build()
             .unwrap(),
     );
-    Ok(MysqlServer::create_server(query_handler, io_runtime))
+    Ok(MysqlServer::create_server(
+        query_handler,
+        io_runtime,
+        Arc::new(ReloadableTlsServerConfig::try_new(tls)?),
+        user_provider,
+    ))
 }
 
 #[tokio::test]
 async fn test_start_mysql_server() -> Result<()> {
     let table = MemTable::default_numbers_table();
 
-    let mysql_server = create_mysql_server(table)?;
+    let mysql_server = create_mysql_server(table, TlsOption::default(), None)?;
     let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
     let result = mysql_server.start(listening).await;
     assert!(result.is_ok());
@@ -65,7 +77,7 @@ async fn test_shutdown_mysql_server() -> Result<()> {
 
     let table = MemTable::default_numbers_table();
 
-    let mysql_server = create_mysql_server(table)?;
+    let mysql_server = create_mysql_server(table, TlsOption::default(), None)?;
     let result = mysql_server.shutdown().await;
     assert!(result
         .unwrap_err()
@@ -110,6 +122,48 @@ async fn test_shutdown_mysql_server() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_mysql_auth() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    let table = MemTable::default_numbers_table();
+    let user_provider = user_provider_from_option("static_user_provider:cmd:greptime=default_pwd")?;
+    let mysql_server = create_mysql_server(table, TlsOption::default(), Some(user_provider))?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let server_addr = mysql_server.start(listening).await.unwrap();
+    let server_port = server_addr.port();
+
+    let mut connection = create_connection(server_port, true).await.unwrap();
+    let result: u32 = connection
+        .query_first("SELECT uint32s FROM numbers LIMIT 1")
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(result, 0);
+
+    assert!(create_connection(server_port, false).await.is_err());
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_mysql_require_tls() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    let table = MemTable::default_numbers_table();
+    let tls = TlsOption::new(
+        TlsMode::Require,
+        format!("{}/tests/tls/server.crt", env!("CARGO_MANIFEST_DIR")),
+        format!("{}/tests/tls/server.key", env!("CARGO_MANIFEST_DIR")),
+    );
+    let mysql_server = create_mysql_server(table, tls, None)?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let server_addr = mysql_server.start(listening).await.unwrap();
+
+    // Plain connections are rejected.
+    assert!(create_connection(server_addr.port(), false).await.is_err());
+    Ok(())
+}
+
 #[tokio::test]
 async fn test_query_all_datatypes() -> Result<()> {
     common_telemetry::init_default_ut_logging();
@@ -124,7 +178,7 @@ async fn test_query_all_datatypes() -> Result<()> {
     let recordbatch = RecordBatch::new(schema, columns).unwrap();
     let table = MemTable::new("all_datatypes", recordbatch);
 
-    let mysql_server = create_mysql_server(table)?;
+    let mysql_server = create_mysql_server(table, TlsOption::default(), None)?;
     let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
     let server_addr = mysql_server.start(listening).await.unwrap();
 
@@ -149,13 +203,69 @@ async fn test_query_all_datatypes() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_prepared_statement() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    let table = MemTable::default_numbers_table();
+    let mysql_server = create_mysql_server(table, TlsOption::default(), None)?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let server_addr = mysql_server.start(listening).await.unwrap();
+
+    let mut connection = create_connection(server_addr.port(), false).await.unwrap();
+    let stmt = connection
+        .prep("SELECT uint32s FROM numbers WHERE uint32s >= ? AND uint32s < ?")
+        .await
+        .unwrap();
+    assert_eq!(2, stmt.num_params());
+    assert_eq!(1, stmt.num_columns());
+    // Parameter types are inferred from the column they are compared with.
+    let param_types = stmt
+        .params()
+        .iter()
+        .map(|column| column.column_type())
+        .collect::<Vec<_>>();
+    assert_eq!(vec![ColumnType::MYSQL_TYPE_LONG; 2], param_types);
+
+    for start in [0u32, 10, 95] {
+        let result: Vec<u32> = connection.exec(&stmt, (start, start + 3)).await.unwrap();
+        let expected = (start..start + 3).collect::<Vec<_>>();
+        assert_eq!(expected, result);
+    }
+
+    let result: Vec<u32> = connection
+        .exec(
+            "SELECT uint32s FROM numbers WHERE uint32s >= ? LIMIT ?",
+            (10u32, 2u32),
+        )
+        .await
+        .unwrap();
+    assert_eq!(vec![10, 11], result);
+
+    // Placeholders in quoted strings are not parameters.
+    let result: Option<String> = connection
+        .exec_first("SELECT '?' FROM numbers WHERE uint32s = ?", (1,))
+        .await
+        .unwrap();
+    assert_eq!(Some("?".to_string()), result);
+
+    // Mismatched number of parameters is rejected by the client or the server.
+    assert!(connection
+        .exec_first::<u32, _, _>(&stmt, (1,))
+        .await
+        .is_err());
+
+    connection.close(stmt).await.unwrap();
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn test_query_concurrently() -> Result<()> {
     common_telemetry::init_default_ut_logging();
 
     let table = MemTable::default_numbers_table();
 
-    let mysql_server = create_mysql_server(table)?;
+    let mysql_server = create_mysql_server(table, TlsOption::default(), None)?;
     let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
     let server_addr = mysql_server.start(listening).await.unwrap();
     let server_port = server_addr.port();
@@ -209,7 +319,9 @@ async fn create_connection(port: u16, with_pwd: bool) -> mysql_async::Result<mys
         .wait_timeout(Some(1000));
 
     if with_pwd {
-        opts = opts.pass(Some("default_pwd".to_string()));
+        opts = opts
+            .user(Some("greptime".to_string()))
+            .pass(Some("default_pwd".to_string()));
     }
 
     mysql_async::Conn::new(opts).await
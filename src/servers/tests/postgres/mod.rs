@@ -19,16 +19,44 @@ use std::time::Duration;
 use common_runtime::Builder as RuntimeBuilder;
 use rand::rngs::StdRng;
 use rand::Rng;
+use servers::auth::user_provider_from_option;
 use servers::error::Result;
-use servers::postgres::PostgresServer;
+use servers::postgres::{PgAuthMethod, PostgresServer};
+use servers::query_handler::SqlQueryHandlerRef;
 use servers::server::Server;
+use servers::tls::{ReloadableTlsServerConfig, TlsMode, TlsOption};
 use table::test_util::MemTable;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_postgres::types::Type;
 use tokio_postgres::{Client, Error as PgError, NoTls, SimpleQueryMessage};
 
-use crate::create_testing_sql_query_handler;
+use crate::{create_testing_instance, create_testing_sql_query_handler, DummyInstance};
 
-fn create_postgres_server(table: MemTable, check_pwd: bool) -> Result<Box<dyn Server>> {
+fn create_postgres_server(
+    table: MemTable,
+    check_pwd: bool,
+    tls: TlsOption,
+) -> Result<Box<dyn Server>> {
+    create_postgres_server_with_auth_method(table, check_pwd, tls, PgAuthMethod::default())
+}
+
+fn create_postgres_server_with_auth_method(
+    table: MemTable,
+    check_pwd: bool,
+    tls: TlsOption,
+    auth_method: PgAuthMethod,
+) -> Result<Box<dyn Server>> {
     let query_handler = create_testing_sql_query_handler(table);
+    create_postgres_server_with_handler(query_handler, check_pwd, tls, auth_method)
+}
+
+fn create_postgres_server_with_handler(
+    query_handler: SqlQueryHandlerRef,
+    check_pwd: bool,
+    tls: TlsOption,
+    auth_method: PgAuthMethod,
+) -> Result<Box<dyn Server>> {
     let io_runtime = Arc::new(
         RuntimeBuilder::default()
             .worker_threads(4)
@@ -36,10 +64,19 @@ fn create_postgres_server(table: MemTable, check_pwd: bool) -> Result<Box<dyn Se
             .build()
             .unwrap(),
     );
+    let user_provider = if check_pwd {
+        Some(user_provider_from_option(
+            "static_user_provider:cmd:test_user=test_pwd",
+        )?)
+    } else {
+        None
+    };
     Ok(Box::new(PostgresServer::new(
         query_handler,
-        check_pwd,
         io_runtime,
+        Arc::new(ReloadableTlsServerConfig::try_new(tls)?),
+        user_provider,
+        auth_method,
     )))
 }
 
@@ -47,7 +84,7 @@ fn create_postgres_server(table: MemTable, check_pwd: bool) -> Result<Box<dyn Se
 pub async fn test_start_postgres_server() -> Result<()> {
     let table = MemTable::default_numbers_table();
 
-    let pg_server = create_postgres_server(table, false)?;
+    let pg_server = create_postgres_server(table, false, TlsOption::default())?;
     let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
     let result = pg_server.start(listening).await;
     assert!(result.is_ok());
@@ -73,7 +110,7 @@ async fn test_shutdown_pg_server(with_pwd: bool) -> Result<()> {
 
     let table = MemTable::default_numbers_table();
 
-    let postgres_server = create_postgres_server(table, with_pwd)?;
+    let postgres_server = create_postgres_server(table, with_pwd, TlsOption::default())?;
     let result = postgres_server.shutdown().await;
     assert!(result
         .unwrap_err()
@@ -126,13 +163,168 @@ async fn test_shutdown_pg_server(with_pwd: bool) -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn test_pg_tls_mode() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    for (mode, plain_accepted) in [(TlsMode::Prefer, true), (TlsMode::Require, false)] {
+        let table = MemTable::default_numbers_table();
+        let tls = TlsOption::new(
+            mode,
+            format!("{}/tests/tls/server.crt", env!("CARGO_MANIFEST_DIR")),
+            format!("{}/tests/tls/server.key", env!("CARGO_MANIFEST_DIR")),
+        );
+        let pg_server = create_postgres_server(table, false, tls)?;
+        let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+        let server_addr = pg_server.start(listening).await.unwrap();
+
+        let result = create_connection(server_addr.port(), false).await;
+        assert_eq!(plain_accepted, result.is_ok());
+        if let Ok(client) = result {
+            let rows = client
+                .simple_query("SELECT uint32s FROM numbers LIMIT 1")
+                .await
+                .unwrap();
+            assert_eq!("0", unwrap_results(&rows)[0]);
+        }
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_pg_auth_method() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    for auth_method in [PgAuthMethod::Md5, PgAuthMethod::Cleartext] {
+        let table = MemTable::default_numbers_table();
+        let pg_server = create_postgres_server_with_auth_method(
+            table,
+            true,
+            TlsOption::default(),
+            auth_method,
+        )?;
+        let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+        let server_addr = pg_server.start(listening).await.unwrap();
+
+        let client = create_connection(server_addr.port(), true).await.unwrap();
+        let rows = client
+            .simple_query("SELECT uint32s FROM numbers LIMIT 1")
+            .await
+            .unwrap();
+        assert_eq!("0", unwrap_results(&rows)[0]);
+
+        let url = format!(
+            "host=127.0.0.1 port={} user=test_user password=wrong_pwd connect_timeout=2",
+            server_addr.port()
+        );
+        assert!(tokio_postgres::connect(&url, NoTls).await.is_err());
+    }
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_extended_query() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    let table = MemTable::default_numbers_table();
+    let pg_server = create_postgres_server(table, false, TlsOption::default())?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let server_addr = pg_server.start(listening).await.unwrap();
+
+    let client = create_connection(server_addr.port(), false).await.unwrap();
+    let stmt = client
+        .prepare("SELECT uint32s FROM numbers WHERE uint32s >= $1 LIMIT $2")
+        .await
+        .unwrap();
+    // Parameter types are inferred from the column and the `LIMIT` clause.
+    assert_eq!(&[Type::INT8, Type::INT8], stmt.params());
+    assert_eq!(1, stmt.columns().len());
+    assert_eq!("uint32s", stmt.columns()[0].name());
+    assert_eq!(&Type::INT8, stmt.columns()[0].type_());
+
+    let rows = client.query(&stmt, &[&10i64, &3i64]).await.unwrap();
+    let result = rows.iter().map(|row| row.get(0)).collect::<Vec<i64>>();
+    assert_eq!(vec![10, 11, 12], result);
+
+    // Explicitly typed parameters are used as is.
+    let stmt = client
+        .prepare_typed(
+            "SELECT uint32s FROM numbers WHERE uint32s = $1",
+            &[Type::INT4],
+        )
+        .await
+        .unwrap();
+    let row = client.query_one(&stmt, &[&42i32]).await.unwrap();
+    assert_eq!(42i64, row.get::<_, i64>(0));
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_cancel_request() -> Result<()> {
+    common_telemetry::init_default_ut_logging();
+
+    let instance = Arc::new(create_testing_instance(MemTable::default_numbers_table()));
+    let pg_server = create_postgres_server_with_handler(
+        instance.clone(),
+        false,
+        TlsOption::default(),
+        PgAuthMethod::default(),
+    )?;
+    let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
+    let server_addr = pg_server.start(listening).await.unwrap();
+
+    let client = create_connection(server_addr.port(), false).await.unwrap();
+    let another = create_connection(server_addr.port(), false).await.unwrap();
+    client.cancel_token().cancel_query(NoTls).await.unwrap();
+    let killed = wait_killed_connections(&instance, 1).await;
+    another.cancel_token().cancel_query(NoTls).await.unwrap();
+    let killed_another = wait_killed_connections(&instance, 2).await;
+    assert_eq!(killed[0], killed_another[0]);
+    assert_ne!(killed[0], killed_another[1]);
+
+    // Requests with unknown keys are ignored, the server closes the connection after
+    // handling the request.
+    for (process_id, secret_key) in [(killed[0] as i32, 0), (i32::MAX, 0)] {
+        let mut stream = TcpStream::connect(server_addr).await.unwrap();
+        let mut request = Vec::with_capacity(16);
+        request.extend_from_slice(&16i32.to_be_bytes());
+        request.extend_from_slice(&80877102i32.to_be_bytes());
+        request.extend_from_slice(&process_id.to_be_bytes());
+        request.extend_from_slice(&secret_key.to_be_bytes());
+        stream.write_all(&request).await.unwrap();
+        stream.read_to_end(&mut vec![]).await.unwrap();
+    }
+    assert_eq!(2, instance.killed_connections().len());
+
+    // The connections still work.
+    let result = client
+        .simple_query("SELECT uint32s FROM numbers LIMIT 1")
+        .await
+        .unwrap();
+    assert_eq!(vec!["0"], unwrap_results(&result));
+    Ok(())
+}
+
+/// Waits until the queries of `n` connections are killed, the client sends the cancel request
+/// without waiting for the server to handle it.
+async fn wait_killed_connections(instance: &DummyInstance, n: usize) -> Vec<u32> {
+    for _ in 0..100 {
+        let killed = instance.killed_connections();
+        if killed.len() >= n {
+            return killed;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("Queries of {} connections are not killed", n);
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
 async fn test_query_pg_concurrently() -> Result<()> {
     common_telemetry::init_default_ut_logging();
 
     let table = MemTable::default_numbers_table();
 
-    let pg_server = create_postgres_server(table, false)?;
+    let pg_server = create_postgres_server(table, false, TlsOption::default())?;
     let listening = "127.0.0.1:0".parse::<SocketAddr>().unwrap();
     let server_addr = pg_server.start(listening).await.unwrap();
     let server_port = server_addr.port();
@@ -0,0 +1,145 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+
+use arc_swap::{ArcSwap, ArcSwapOption};
+use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
+
+pub type QueryContextRef = Arc<QueryContext>;
+
+/// Id of the next connection, starts from 1.
+static NEXT_CONNECTION_ID: AtomicU32 = AtomicU32::new(1);
+
+/// Context of queries, including the current catalog and schema to resolve unqualified
+/// table names against.
+#[derive(Debug)]
+pub struct QueryContext {
+    /// Id of the connection issuing the queries, unique in the server process.
+    connection_id: u32,
+    /// Address of the client, `None` if the queries are not issued by a client connection.
+    client_addr: Option<String>,
+    user: ArcSwapOption<String>,
+    current_catalog: ArcSwap<String>,
+    current_schema: ArcSwap<String>,
+    time_zone: ArcSwapOption<String>,
+}
+
+impl Default for QueryContext {
+    fn default() -> Self {
+        QueryContext::with(DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME)
+    }
+}
+
+impl QueryContext {
+    /// Creates a context with default catalog and schema.
+    pub fn new() -> Self {
+        QueryContext::default()
+    }
+
+    /// Creates a shared context with default catalog and schema.
+    pub fn arc() -> QueryContextRef {
+        Arc::new(QueryContext::new())
+    }
+
+    pub fn with(catalog: &str, schema: &str) -> Self {
+        QueryContext {
+            connection_id: NEXT_CONNECTION_ID.fetch_add(1, Ordering::Relaxed),
+            client_addr: None,
+            user: ArcSwapOption::empty(),
+            current_catalog: ArcSwap::new(Arc::new(catalog.to_string())),
+            current_schema: ArcSwap::new(Arc::new(schema.to_string())),
+            time_zone: ArcSwapOption::empty(),
+        }
+    }
+
+    /// Sets the address of the client issuing the queries.
+    pub fn with_client_addr(mut self, client_addr: impl Into<String>) -> Self {
+        self.client_addr = Some(client_addr.into());
+        self
+    }
+
+    #[inline]
+    pub fn connection_id(&self) -> u32 {
+        self.connection_id
+    }
+
+    #[inline]
+    pub fn client_addr(&self) -> Option<&str> {
+        self.client_addr.as_deref()
+    }
+
+    /// Returns the name of the user, `None` if the user is not authenticated yet.
+    pub fn user(&self) -> Option<String> {
+        self.user.load().as_deref().cloned()
+    }
+
+    pub fn set_user(&self, user: impl Into<String>) {
+        self.user.store(Some(Arc::new(user.into())));
+    }
+
+    pub fn current_catalog(&self) -> String {
+        self.current_catalog.load().as_ref().clone()
+    }
+
+    pub fn current_schema(&self) -> String {
+        self.current_schema.load().as_ref().clone()
+    }
+
+    pub fn set_current_catalog(&self, catalog: &str) {
+        self.current_catalog.store(Arc::new(catalog.to_string()));
+    }
+
+    pub fn set_current_schema(&self, schema: &str) {
+        self.current_schema.store(Arc::new(schema.to_string()));
+    }
+
+    /// Returns the time zone of the session, `None` means using the time zone of the server.
+    pub fn time_zone(&self) -> Option<String> {
+        self.time_zone.load().as_deref().cloned()
+    }
+
+    pub fn set_time_zone(&self, time_zone: Option<String>) {
+        self.time_zone.store(time_zone.map(Arc::new));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_context() {
+        let ctx = QueryContext::new();
+        assert_eq!(DEFAULT_CATALOG_NAME, ctx.current_catalog());
+        assert_eq!(DEFAULT_SCHEMA_NAME, ctx.current_schema());
+        assert_eq!(None, ctx.time_zone());
+        assert_eq!(None, ctx.client_addr());
+        assert_eq!(None, ctx.user());
+        assert_ne!(ctx.connection_id(), QueryContext::new().connection_id());
+
+        ctx.set_current_catalog("my_catalog");
+        ctx.set_current_schema("my_schema");
+        ctx.set_time_zone(Some("+08:00".to_string()));
+        ctx.set_user("greptime");
+        assert_eq!("my_catalog", ctx.current_catalog());
+        assert_eq!("my_schema", ctx.current_schema());
+        assert_eq!(Some("+08:00".to_string()), ctx.time_zone());
+        assert_eq!(Some("greptime".to_string()), ctx.user());
+
+        let ctx = QueryContext::new().with_client_addr("127.0.0.1:4002");
+        assert_eq!(Some("127.0.0.1:4002"), ctx.client_addr());
+    }
+}
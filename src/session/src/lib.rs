@@ -0,0 +1,80 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+pub mod context;
+
+use std::sync::Arc;
+
+use crate::context::{QueryContext, QueryContextRef};
+
+pub type SessionRef = Arc<Session>;
+
+/// Session of a client connection, holds the states shared by all queries of the connection.
+#[derive(Debug)]
+pub struct Session {
+    query_ctx: QueryContextRef,
+}
+
+impl Session {
+    pub fn new(client_addr: impl Into<String>) -> Self {
+        Session {
+            query_ctx: Arc::new(QueryContext::new().with_client_addr(client_addr)),
+        }
+    }
+
+    #[inline]
+    pub fn connection_id(&self) -> u32 {
+        self.query_ctx.connection_id()
+    }
+
+    #[inline]
+    pub fn client_addr(&self) -> &str {
+        self.query_ctx.client_addr().unwrap_or_default()
+    }
+
+    /// Returns the name of the user, `None` if the connection is not authenticated yet.
+    pub fn user(&self) -> Option<String> {
+        self.query_ctx.user()
+    }
+
+    pub fn set_user(&self, user: impl Into<String>) {
+        self.query_ctx.set_user(user);
+    }
+
+    /// Returns the context for queries issued by this session.
+    #[inline]
+    pub fn context(&self) -> QueryContextRef {
+        self.query_ctx.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session() {
+        let session = Session::new("127.0.0.1:4002");
+        let another = Session::new("127.0.0.1:4002");
+        assert_ne!(session.connection_id(), another.connection_id());
+        assert_eq!("127.0.0.1:4002", session.client_addr());
+
+        assert_eq!(None, session.user());
+        session.set_user("greptime");
+        assert_eq!(Some("greptime".to_string()), session.user());
+
+        session.context().set_current_schema("test");
+        assert_eq!("test", session.context().current_schema());
+    }
+}
@@ -15,7 +15,7 @@
 use std::any::Any;
 
 use common_error::prelude::*;
-use datatypes::prelude::ConcreteDataType;
+use datatypes::prelude::{ConcreteDataType, Value};
 use sqlparser::parser::ParserError;
 use sqlparser::tokenizer::TokenizerError;
 
@@ -124,6 +124,9 @@ pub enum Error {
         #[snafu(backtrace)]
         source: api::error::Error,
     },
+
+    #[snafu(display("Unable to convert value {} to sql value", value))]
+    ConvertValue { value: Value, backtrace: Backtrace },
 }
 
 impl ErrorExt for Error {
@@ -131,7 +134,9 @@ impl ErrorExt for Error {
         use Error::*;
 
         match self {
-            UnsupportedDefaultValue { .. } | Unsupported { .. } => StatusCode::Unsupported,
+            UnsupportedDefaultValue { .. } | Unsupported { .. } | ConvertValue { .. } => {
+                StatusCode::Unsupported
+            }
             Unexpected { .. }
             | Syntax { .. }
             | InvalidTimeIndex { .. }
@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use snafu::{ensure, ResultExt};
+use snafu::{ensure, OptionExt, ResultExt};
 use sqlparser::dialect::Dialect;
 use sqlparser::keywords::Keyword;
 use sqlparser::parser::{Parser, ParserError};
@@ -21,12 +21,14 @@ use sqlparser::tokenizer::{Token, Tokenizer};
 use crate::error::{
     self, InvalidDatabaseNameSnafu, InvalidTableNameSnafu, Result, SyntaxSnafu, TokenizerSnafu,
 };
+use crate::statements::admin::Admin;
 use crate::statements::describe::DescribeTable;
 use crate::statements::drop::DropTable;
 use crate::statements::explain::Explain;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowKind, ShowTables};
+use crate::statements::show::{
+    ShowColumns, ShowCreateTable, ShowDatabases, ShowKind, ShowProcesslist, ShowTables,
+};
 use crate::statements::statement::Statement;
-use crate::statements::table_idents_to_full_name;
 
 /// GrepTime SQL parser context, a simple wrapper for Datafusion SQL parser.
 pub struct ParserContext<'a> {
@@ -96,12 +98,29 @@ impl<'a> ParserContext<'a> {
 
                     Keyword::INSERT => self.parse_insert(),
 
+                    Keyword::DELETE => self.parse_delete(),
+
                     Keyword::SELECT | Keyword::WITH | Keyword::VALUES => self.parse_query(),
 
                     Keyword::ALTER => self.parse_alter(),
 
                     Keyword::DROP => self.parse_drop(),
 
+                    _ if w.value.eq_ignore_ascii_case("USE") => {
+                        self.parser.next_token();
+                        self.parse_use()
+                    }
+
+                    _ if w.value.eq_ignore_ascii_case("KILL") => {
+                        self.parser.next_token();
+                        self.parse_kill()
+                    }
+
+                    _ if w.value.eq_ignore_ascii_case("ADMIN") => {
+                        self.parser.next_token();
+                        self.parse_admin()
+                    }
+
                     // todo(hl) support more statements.
                     _ => self.unsupported(self.peek_token_as_string()),
                 }
@@ -134,11 +153,78 @@ impl<'a> ParserContext<'a> {
             } else {
                 self.unsupported(self.peek_token_as_string())
             }
+        } else if self.consume_token("COLUMNS") || self.consume_token("FIELDS") {
+            self.parse_show_columns()
+        } else if self.consume_token("PROCESSLIST") {
+            Ok(Statement::ShowProcesslist(ShowProcesslist { full: false }))
+        } else if self.consume_token("FULL") {
+            if self.consume_token("PROCESSLIST") {
+                Ok(Statement::ShowProcesslist(ShowProcesslist { full: true }))
+            } else {
+                self.unsupported(self.peek_token_as_string())
+            }
         } else {
             self.unsupported(self.peek_token_as_string())
         }
     }
 
+    /// Parses `SHOW COLUMNS {FROM | IN} table [{FROM | IN} database]` statement.
+    fn parse_show_columns(&mut self) -> Result<Statement> {
+        if !self.consume_from_or_in() {
+            return self.unsupported(self.peek_token_as_string());
+        }
+        let mut table_name =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !table_name.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: table_name.to_string(),
+            }
+        );
+
+        if self.consume_from_or_in() {
+            let db_name =
+                self.parser
+                    .parse_object_name()
+                    .with_context(|_| error::UnexpectedSnafu {
+                        sql: self.sql,
+                        expected: "a database name",
+                        actual: self.peek_token_as_string(),
+                    })?;
+            ensure!(
+                db_name.0.len() == 1,
+                InvalidDatabaseNameSnafu {
+                    name: db_name.to_string(),
+                }
+            );
+            // The table name can't be qualified if the database is given.
+            ensure!(
+                table_name.0.len() == 1,
+                InvalidTableNameSnafu {
+                    name: table_name.to_string(),
+                }
+            );
+            table_name.0.splice(0..0, db_name.0);
+        }
+
+        Ok(Statement::ShowColumns(ShowColumns { table_name }))
+    }
+
+    fn consume_from_or_in(&mut self) -> bool {
+        if self.matches_keyword(Keyword::FROM) || self.matches_keyword(Keyword::IN) {
+            self.parser.next_token();
+            true
+        } else {
+            false
+        }
+    }
+
     /// Parse SHOW CREATE TABLE statement
     fn parse_show_create_table(&mut self) -> Result<Statement> {
         let table_name =
@@ -155,9 +241,7 @@ impl<'a> ParserContext<'a> {
                 name: table_name.to_string(),
             }
         );
-        Ok(Statement::ShowCreateTable(ShowCreateTable {
-            table_name: table_name.to_string(),
-        }))
+        Ok(Statement::ShowCreateTable(ShowCreateTable { table_name }))
     }
 
     fn parse_show_tables(&mut self) -> Result<Statement> {
@@ -253,12 +337,7 @@ impl<'a> ParserContext<'a> {
                 name: table_idents.to_string(),
             }
         );
-        let (catalog_name, schema_name, table_name) = table_idents_to_full_name(&table_idents)?;
-        Ok(Statement::DescribeTable(DescribeTable {
-            catalog_name,
-            schema_name,
-            table_name,
-        }))
+        Ok(Statement::DescribeTable(DescribeTable::new(table_idents)))
     }
 
     fn parse_explain(&mut self) -> Result<Statement> {
@@ -296,12 +375,80 @@ impl<'a> ParserContext<'a> {
             }
         );
 
-        let (catalog_name, schema_name, table_name) = table_idents_to_full_name(&table_ident)?;
-        Ok(Statement::DropTable(DropTable {
-            catalog_name,
-            schema_name,
-            table_name,
-        }))
+        Ok(Statement::DropTable(DropTable::new(table_ident)))
+    }
+
+    /// Parses `USE <database>`, the leading `USE` has already been consumed.
+    fn parse_use(&mut self) -> Result<Statement> {
+        let database = self
+            .parser
+            .parse_identifier()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a database name",
+                actual: self.peek_token_as_string(),
+            })?;
+        Ok(Statement::Use(database.value))
+    }
+
+    /// Parses `KILL [QUERY | CONNECTION] <id>`, the leading `KILL` has already been consumed.
+    fn parse_kill(&mut self) -> Result<Statement> {
+        if let Token::Word(w) = self.parser.peek_token() {
+            if w.value.eq_ignore_ascii_case("QUERY") || w.value.eq_ignore_ascii_case("CONNECTION") {
+                self.parser.next_token();
+            }
+        }
+
+        let id = self
+            .parser
+            .parse_literal_uint()
+            .with_context(|_| error::UnexpectedSnafu {
+                sql: self.sql,
+                expected: "a process id",
+                actual: self.peek_token_as_string(),
+            })?;
+        let id = u32::try_from(id).ok().context(error::InvalidSqlSnafu {
+            msg: format!("invalid process id: {}", id),
+        })?;
+        Ok(Statement::Kill(id))
+    }
+
+    /// Parses `ADMIN {FLUSH | COMPACT} TABLE <table>`, the leading `ADMIN` has already been
+    /// consumed.
+    fn parse_admin(&mut self) -> Result<Statement> {
+        let flush = if self.consume_token("FLUSH") {
+            true
+        } else if self.consume_token("COMPACT") {
+            false
+        } else {
+            return self.unsupported(self.peek_token_as_string());
+        };
+        if !self.matches_keyword(Keyword::TABLE) {
+            return self.expected("TABLE", self.parser.peek_token());
+        }
+        self.parser.next_token();
+
+        let table_name =
+            self.parser
+                .parse_object_name()
+                .with_context(|_| error::UnexpectedSnafu {
+                    sql: self.sql,
+                    expected: "a table name",
+                    actual: self.peek_token_as_string(),
+                })?;
+        ensure!(
+            !table_name.0.is_empty(),
+            InvalidTableNameSnafu {
+                name: table_name.to_string()
+            }
+        );
+
+        let admin = if flush {
+            Admin::FlushTable(table_name)
+        } else {
+            Admin::CompactTable(table_name)
+        };
+        Ok(Statement::Admin(admin))
     }
 
     // Report unexpected token
@@ -371,9 +518,8 @@ impl<'a> ParserContext<'a> {
 mod tests {
     use std::assert_matches::assert_matches;
 
-    use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
-    use sqlparser::ast::{Query as SpQuery, Statement as SpStatement};
-    use sqlparser::dialect::GenericDialect;
+    use sqlparser::ast::{Ident, ObjectName, Query as SpQuery, Statement as SpStatement};
+    use sqlparser::dialect::{GenericDialect, MySqlDialect};
 
     use super::*;
 
@@ -567,6 +713,69 @@ mod tests {
         assert_eq!(stmts[0], Statement::Explain(explain))
     }
 
+    #[test]
+    pub fn test_parse_use() {
+        let sql = "USE my_schema";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, stmts.len());
+        assert_eq!(
+            stmts.pop().unwrap(),
+            Statement::Use("my_schema".to_string())
+        );
+
+        let sql = "use `my schema`;";
+        let mut stmts = ParserContext::create_with_dialect(sql, &MySqlDialect {}).unwrap();
+        assert_eq!(
+            Statement::Use("my schema".to_string()),
+            stmts.pop().unwrap()
+        );
+
+        let sql = "USE";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+    }
+
+    #[test]
+    pub fn test_parse_kill() {
+        for sql in ["KILL 42", "kill query 42", "KILL CONNECTION 42;"] {
+            let mut stmts = ParserContext::create_with_dialect(sql, &MySqlDialect {}).unwrap();
+            assert_eq!(1, stmts.len());
+            assert_eq!(Statement::Kill(42), stmts.pop().unwrap());
+        }
+
+        for sql in ["KILL", "KILL QUERY", "KILL a", "KILL 4294967296"] {
+            assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        }
+    }
+
+    #[test]
+    pub fn test_parse_admin() {
+        let sql = "ADMIN FLUSH TABLE foo";
+        let mut stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(
+            Statement::Admin(Admin::FlushTable(ObjectName(vec![Ident::new("foo")]))),
+            stmts.pop().unwrap()
+        );
+
+        let sql = "admin compact table my_schema.foo;";
+        let mut stmts = ParserContext::create_with_dialect(sql, &MySqlDialect {}).unwrap();
+        assert_eq!(
+            Statement::Admin(Admin::CompactTable(ObjectName(vec![
+                Ident::new("my_schema"),
+                Ident::new("foo")
+            ]))),
+            stmts.pop().unwrap()
+        );
+
+        for sql in [
+            "ADMIN",
+            "ADMIN FLUSH",
+            "ADMIN FLUSH foo",
+            "ADMIN DROP TABLE foo",
+        ] {
+            assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+        }
+    }
+
     #[test]
     pub fn test_drop_table() {
         let sql = "DROP TABLE foo";
@@ -574,11 +783,7 @@ mod tests {
         let mut stmts = result.unwrap();
         assert_eq!(
             stmts.pop().unwrap(),
-            Statement::DropTable(DropTable {
-                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
-                schema_name: DEFAULT_SCHEMA_NAME.to_string(),
-                table_name: "foo".to_string()
-            })
+            Statement::DropTable(DropTable::new(ObjectName(vec![Ident::new("foo")])))
         );
 
         let sql = "DROP TABLE my_schema.foo";
@@ -586,11 +791,10 @@ mod tests {
         let mut stmts = result.unwrap();
         assert_eq!(
             stmts.pop().unwrap(),
-            Statement::DropTable(DropTable {
-                catalog_name: DEFAULT_CATALOG_NAME.to_string(),
-                schema_name: "my_schema".to_string(),
-                table_name: "foo".to_string()
-            })
+            Statement::DropTable(DropTable::new(ObjectName(vec![
+                Ident::new("my_schema"),
+                Ident::new("foo")
+            ])))
         );
 
         let sql = "DROP TABLE my_catalog.my_schema.foo";
@@ -598,11 +802,11 @@ mod tests {
         let mut stmts = result.unwrap();
         assert_eq!(
             stmts.pop().unwrap(),
-            Statement::DropTable(DropTable {
-                catalog_name: "my_catalog".to_string(),
-                schema_name: "my_schema".to_string(),
-                table_name: "foo".to_string()
-            })
+            Statement::DropTable(DropTable::new(ObjectName(vec![
+                Ident::new("my_catalog"),
+                Ident::new("my_schema"),
+                Ident::new("foo")
+            ])))
         )
     }
 }
@@ -14,5 +14,6 @@
 
 mod alter_parser;
 pub(crate) mod create_parser;
+pub(crate) mod delete_parser;
 pub(crate) mod insert_parser;
 pub(crate) mod query_parser;
@@ -52,10 +52,14 @@ impl<'a> ParserContext<'a> {
                 )));
             }
         } else {
-            return Err(ParserError::ParserError(format!(
-                "expect keyword ADD or DROP after ALTER TABLE, found {}",
-                parser.peek_token()
-            )));
+            let options = parser.parse_options(Keyword::SET)?;
+            if options.is_empty() {
+                return Err(ParserError::ParserError(format!(
+                    "expect keyword ADD, DROP or SET after ALTER TABLE, found {}",
+                    parser.peek_token()
+                )));
+            }
+            AlterTableOperation::SetOptions { options }
         };
         Ok(AlterTable::new(table_name, alter_operation))
     }
@@ -65,7 +69,7 @@ impl<'a> ParserContext<'a> {
 mod tests {
     use std::assert_matches::assert_matches;
 
-    use sqlparser::ast::{ColumnOption, DataType};
+    use sqlparser::ast::{ColumnOption, DataType, Value};
     use sqlparser::dialect::GenericDialect;
 
     use super::*;
@@ -130,4 +134,44 @@ mod tests {
             _ => unreachable!(),
         }
     }
+
+    #[test]
+    fn test_parse_alter_set_options() {
+        let sql = "ALTER TABLE my_metric_1 SET (ttl = '7d', write_buffer_size = 1024)";
+        let mut result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(1, result.len());
+
+        let statement = result.remove(0);
+        assert_matches!(statement, Statement::Alter { .. });
+        match statement {
+            Statement::Alter(alter_table) => {
+                assert_eq!("my_metric_1", alter_table.table_name().0[0].value);
+
+                let alter_operation = alter_table.alter_operation();
+                assert_matches!(alter_operation, AlterTableOperation::SetOptions { .. });
+                match alter_operation {
+                    AlterTableOperation::SetOptions { options } => {
+                        assert_eq!(2, options.len());
+                        assert_eq!("ttl", options[0].name.value);
+                        assert_eq!(
+                            Value::SingleQuotedString("7d".to_string()),
+                            options[0].value
+                        );
+                        assert_eq!("write_buffer_size", options[1].name.value);
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            _ => unreachable!(),
+        }
+
+        let sql = "ALTER TABLE my_metric_1 SET";
+        assert!(ParserContext::create_with_dialect(sql, &GenericDialect {}).is_err());
+
+        let sql = "ALTER TABLE my_metric_1 RENAME a";
+        let result = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+        assert!(result
+            .to_string()
+            .contains("expect keyword ADD, DROP or SET after ALTER TABLE"));
+    }
 }
@@ -12,8 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod admin;
 pub mod alter;
 pub mod create;
+pub mod delete;
 pub mod describe;
 pub mod drop;
 pub mod explain;
@@ -22,37 +24,41 @@ pub mod query;
 pub mod show;
 pub mod statement;
 
+use std::collections::HashMap;
 use std::str::FromStr;
 
 use api::helper::ColumnDataTypeWrapper;
-use common_catalog::consts::{DEFAULT_CATALOG_NAME, DEFAULT_SCHEMA_NAME};
 use common_time::Timestamp;
-use datatypes::prelude::ConcreteDataType;
+use datatypes::prelude::{ConcreteDataType, DataType};
 use datatypes::schema::{ColumnDefaultConstraint, ColumnSchema};
 use datatypes::types::DateTimeType;
 use datatypes::value::Value;
+use session::context::QueryContext;
 use snafu::{ensure, ResultExt};
 
 use crate::ast::{
-    ColumnDef, ColumnOption, ColumnOptionDef, DataType as SqlDataType, Expr, ObjectName,
-    Value as SqlValue,
+    ColumnDef, ColumnOption, ColumnOptionDef, DataType as SqlDataType, Expr, Ident, ObjectName,
+    SqlOption, Value as SqlValue,
 };
 use crate::error::{
     self, ColumnTypeMismatchSnafu, ConvertToGrpcDataTypeSnafu, ParseSqlValueSnafu, Result,
     SerializeColumnDefaultConstraintSnafu, UnsupportedDefaultValueSnafu,
 };
 
-/// Converts maybe fully-qualified table name (`<catalog>.<schema>.<table>` or `<table>` when
-/// catalog and schema are default) to tuple.
-pub fn table_idents_to_full_name(obj_name: &ObjectName) -> Result<(String, String, String)> {
+/// Converts maybe fully-qualified table name (`<catalog>.<schema>.<table>`, `<schema>.<table>`
+/// or `<table>`) to tuple, missing catalog and schema are taken from the `query_ctx`.
+pub fn table_idents_to_full_name(
+    obj_name: &ObjectName,
+    query_ctx: &QueryContext,
+) -> Result<(String, String, String)> {
     match &obj_name.0[..] {
         [table] => Ok((
-            DEFAULT_CATALOG_NAME.to_string(),
-            DEFAULT_SCHEMA_NAME.to_string(),
+            query_ctx.current_catalog(),
+            query_ctx.current_schema(),
             table.value.clone(),
         )),
         [schema, table] => Ok((
-            DEFAULT_CATALOG_NAME.to_string(),
+            query_ctx.current_catalog(),
             schema.value.clone(),
             table.value.clone(),
         )),
@@ -71,6 +77,21 @@ pub fn table_idents_to_full_name(obj_name: &ObjectName) -> Result<(String, Strin
     }
 }
 
+/// Converts sql options (e.g. options in `WITH`) to a map of table options, keys of the
+/// options are converted to lowercase.
+pub fn sql_options_to_table_options(options: &[SqlOption]) -> HashMap<String, String> {
+    options
+        .iter()
+        .map(|option| {
+            let value = match &option.value {
+                SqlValue::SingleQuotedString(s) | SqlValue::DoubleQuotedString(s) => s.clone(),
+                v => v.to_string(),
+            };
+            (option.name.value.to_lowercase(), value)
+        })
+        .collect()
+}
+
 fn parse_string_to_value(
     column_name: &str,
     s: String,
@@ -126,6 +147,30 @@ fn parse_string_to_value(
     }
 }
 
+/// Parses the hex string literal `X'...'` to a binary value.
+fn parse_hex_string_to_value(
+    column_name: &str,
+    s: &str,
+    data_type: &ConcreteDataType,
+) -> Result<Value> {
+    ensure!(
+        matches!(data_type, ConcreteDataType::Binary(_)),
+        ColumnTypeMismatchSnafu {
+            column_name,
+            expect: data_type.clone(),
+            actual: ConcreteDataType::binary_datatype(),
+        }
+    );
+
+    match hex::decode(s) {
+        Ok(bytes) => Ok(Value::from(bytes)),
+        Err(e) => ParseSqlValueSnafu {
+            msg: format!("Failed to parse hex string {}, {}", s, e),
+        }
+        .fail(),
+    }
+}
+
 macro_rules! parse_number_to_value {
     ($data_type: expr, $n: ident,  $(($Type: ident, $PrimitiveType: ident)), +) => {
         match $data_type {
@@ -199,10 +244,36 @@ pub fn sql_value_to_value(
         SqlValue::DoubleQuotedString(s) | SqlValue::SingleQuotedString(s) => {
             parse_string_to_value(column_name, s.to_owned(), data_type)?
         }
+        SqlValue::HexStringLiteral(s) => parse_hex_string_to_value(column_name, s, data_type)?,
         _ => todo!("Other sql value"),
     })
 }
 
+/// Converts a datatype's value into sql value, the inverse of [sql_value_to_value].
+pub fn value_to_sql_value(val: &Value) -> Result<SqlValue> {
+    Ok(match val {
+        Value::Null => SqlValue::Null,
+        Value::Boolean(b) => SqlValue::Boolean(*b),
+        Value::UInt8(_)
+        | Value::UInt16(_)
+        | Value::UInt32(_)
+        | Value::UInt64(_)
+        | Value::Int8(_)
+        | Value::Int16(_)
+        | Value::Int32(_)
+        | Value::Int64(_)
+        | Value::Float32(_)
+        | Value::Float64(_) => SqlValue::Number(val.to_string(), false),
+        Value::String(s) => SqlValue::SingleQuotedString(s.as_utf8().to_string()),
+        Value::Date(_) | Value::DateTime(_) | Value::Timestamp(_) => {
+            SqlValue::SingleQuotedString(val.to_string())
+        }
+        Value::Binary(_) | Value::List(_) => {
+            return error::ConvertValueSnafu { value: val.clone() }.fail()
+        }
+    })
+}
+
 fn parse_column_default_constraint(
     column_name: &str,
     data_type: &ConcreteDataType,
@@ -321,6 +392,27 @@ pub fn sql_data_type_to_concrete_data_type(data_type: &SqlDataType) -> Result<Co
     }
 }
 
+/// Converts `ConcreteDataType` to the sql data type, the inverse of
+/// [sql_data_type_to_concrete_data_type]. Types that don't have a counterpart in sql are
+/// represented by their names.
+pub fn concrete_data_type_to_sql_data_type(data_type: &ConcreteDataType) -> SqlDataType {
+    match data_type {
+        ConcreteDataType::Int64(_) => SqlDataType::BigInt(None),
+        ConcreteDataType::Int32(_) => SqlDataType::Int(None),
+        ConcreteDataType::Int16(_) => SqlDataType::SmallInt(None),
+        ConcreteDataType::String(_) => SqlDataType::String,
+        ConcreteDataType::Float32(_) => SqlDataType::Float(None),
+        ConcreteDataType::Float64(_) => SqlDataType::Double,
+        ConcreteDataType::Boolean(_) => SqlDataType::Boolean,
+        ConcreteDataType::Date(_) => SqlDataType::Date,
+        ConcreteDataType::Timestamp(_) => SqlDataType::Timestamp,
+        ConcreteDataType::DateTime(_) => {
+            SqlDataType::Custom(ObjectName(vec![Ident::new(DateTimeType::name())]))
+        }
+        _ => SqlDataType::Custom(ObjectName(vec![Ident::new(data_type.name())])),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::assert_matches::assert_matches;
@@ -420,6 +512,68 @@ mod tests {
         assert!(format!("{:?}", v).contains(
             "column_name: \"a\", expect: Float64(Float64), actual: Boolean(BooleanType)"
         ));
+
+        let sql_val = SqlValue::HexStringLiteral("0aff".to_string());
+        assert_eq!(
+            Value::from(vec![0x0a, 0xff]),
+            sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val).unwrap()
+        );
+        let v = sql_value_to_value("a", &ConcreteDataType::string_datatype(), &sql_val);
+        assert!(v.is_err());
+        let sql_val = SqlValue::HexStringLiteral("0g".to_string());
+        let v = sql_value_to_value("a", &ConcreteDataType::binary_datatype(), &sql_val);
+        assert!(v.is_err());
+    }
+
+    #[test]
+    fn test_value_to_sql_value() {
+        let check = |value: Value, data_type: ConcreteDataType| {
+            let sql_val = value_to_sql_value(&value).unwrap();
+            assert_eq!(
+                value,
+                sql_value_to_value("a", &data_type, &sql_val).unwrap()
+            );
+        };
+        check(Value::Null, ConcreteDataType::int32_datatype());
+        check(Value::Boolean(true), ConcreteDataType::boolean_datatype());
+        check(Value::Int32(42), ConcreteDataType::int32_datatype());
+        check(
+            Value::Float64(OrderedFloat(3.5)),
+            ConcreteDataType::float64_datatype(),
+        );
+        check(
+            Value::String("it's".into()),
+            ConcreteDataType::string_datatype(),
+        );
+        check(
+            Value::Timestamp(Timestamp::new(1_000, TimeUnit::Millisecond)),
+            ConcreteDataType::timestamp_millis_datatype(),
+        );
+
+        assert!(value_to_sql_value(&Value::from(b"hello".to_vec())).is_err());
+    }
+
+    #[test]
+    fn test_concrete_data_type_to_sql_data_type() {
+        for data_type in [
+            ConcreteDataType::int64_datatype(),
+            ConcreteDataType::int32_datatype(),
+            ConcreteDataType::int16_datatype(),
+            ConcreteDataType::string_datatype(),
+            ConcreteDataType::float32_datatype(),
+            ConcreteDataType::float64_datatype(),
+            ConcreteDataType::boolean_datatype(),
+            ConcreteDataType::date_datatype(),
+            ConcreteDataType::datetime_datatype(),
+            ConcreteDataType::timestamp_millis_datatype(),
+        ] {
+            check_type(concrete_data_type_to_sql_data_type(&data_type), data_type);
+        }
+
+        assert_eq!(
+            "UInt8",
+            concrete_data_type_to_sql_data_type(&ConcreteDataType::uint8_datatype()).to_string()
+        );
     }
 
     #[test]
@@ -594,4 +748,23 @@ mod tests {
         let grpc_column_def = sql_column_def_to_grpc_column_def(column_def).unwrap();
         assert!(!grpc_column_def.is_nullable);
     }
+
+    #[test]
+    pub fn test_sql_options_to_table_options() {
+        let options = vec![
+            SqlOption {
+                name: Ident::new("TTL"),
+                value: SqlValue::SingleQuotedString("7d".to_string()),
+            },
+            SqlOption {
+                name: Ident::new("write_buffer_size"),
+                value: SqlValue::Number("1024".to_string(), false),
+            },
+        ];
+
+        let table_options = sql_options_to_table_options(&options);
+        assert_eq!(2, table_options.len());
+        assert_eq!("7d", table_options["ttl"]);
+        assert_eq!("1024", table_options["write_buffer_size"]);
+    }
 }
@@ -0,0 +1,33 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use sqlparser::ast::ObjectName;
+
+/// ADMIN statements to maintain a table manually.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Admin {
+    /// `ADMIN FLUSH TABLE <table>`, flushes the memtables of the table.
+    FlushTable(ObjectName),
+    /// `ADMIN COMPACT TABLE <table>`, compacts the SST files of the table.
+    CompactTable(ObjectName),
+}
+
+impl Admin {
+    /// Returns the maybe not fully-qualified name of the table to maintain.
+    pub fn table_name(&self) -> &ObjectName {
+        match self {
+            Admin::FlushTable(table_name) | Admin::CompactTable(table_name) => table_name,
+        }
+    }
+}
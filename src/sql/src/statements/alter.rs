@@ -13,10 +13,13 @@
 // limitations under the License.
 
 use api::v1::{alter_expr, AddColumn, AlterExpr, DropColumn};
-use sqlparser::ast::{ColumnDef, Ident, ObjectName, TableConstraint};
+use session::context::QueryContext;
+use sqlparser::ast::{ColumnDef, Ident, ObjectName, SqlOption, TableConstraint};
 
-use crate::error::UnsupportedAlterTableStatementSnafu;
-use crate::statements::{sql_column_def_to_grpc_column_def, table_idents_to_full_name};
+use crate::error::{Result, UnsupportedAlterTableStatementSnafu};
+use crate::statements::{
+    sql_column_def_to_grpc_column_def, sql_options_to_table_options, table_idents_to_full_name,
+};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct AlterTable {
@@ -49,43 +52,47 @@ pub enum AlterTableOperation {
     AddColumn { column_def: ColumnDef },
     /// `DROP COLUMN <name>`
     DropColumn { name: Ident },
+    /// `SET ( <option> = <value> [, ...] )`
+    SetOptions { options: Vec<SqlOption> },
 }
 
-/// Convert `AlterTable` statement to `AlterExpr` for gRPC
-impl TryFrom<AlterTable> for AlterExpr {
-    type Error = crate::error::Error;
+/// Converts `AlterTable` statement to `AlterExpr` for gRPC, the table name is resolved
+/// against the current catalog and schema in `query_ctx`.
+pub fn alter_to_expr(value: AlterTable, query_ctx: &QueryContext) -> Result<AlterExpr> {
+    let (catalog, schema, table) = table_idents_to_full_name(&value.table_name, query_ctx)?;
 
-    fn try_from(value: AlterTable) -> Result<Self, Self::Error> {
-        let (catalog, schema, table) = table_idents_to_full_name(&value.table_name)?;
-
-        let kind = match value.alter_operation {
-            AlterTableOperation::AddConstraint(_) => {
-                return UnsupportedAlterTableStatementSnafu {
-                    msg: "ADD CONSTRAINT not supported yet.",
-                }
-                .fail();
-            }
-            AlterTableOperation::AddColumn { column_def } => {
-                alter_expr::Kind::AddColumns(api::v1::AddColumns {
-                    add_columns: vec![AddColumn {
-                        column_def: Some(sql_column_def_to_grpc_column_def(column_def)?),
-                        is_key: false,
-                    }],
-                })
+    let kind = match value.alter_operation {
+        AlterTableOperation::AddConstraint(_) => {
+            return UnsupportedAlterTableStatementSnafu {
+                msg: "ADD CONSTRAINT not supported yet.",
             }
-            AlterTableOperation::DropColumn { name } => {
-                alter_expr::Kind::DropColumns(api::v1::DropColumns {
-                    drop_columns: vec![DropColumn { name: name.value }],
-                })
-            }
-        };
-        let expr = AlterExpr {
-            catalog_name: Some(catalog),
-            schema_name: Some(schema),
-            table_name: table,
-            kind: Some(kind),
-        };
+            .fail();
+        }
+        AlterTableOperation::AddColumn { column_def } => {
+            alter_expr::Kind::AddColumns(api::v1::AddColumns {
+                add_columns: vec![AddColumn {
+                    column_def: Some(sql_column_def_to_grpc_column_def(column_def)?),
+                    is_key: false,
+                }],
+            })
+        }
+        AlterTableOperation::DropColumn { name } => {
+            alter_expr::Kind::DropColumns(api::v1::DropColumns {
+                drop_columns: vec![DropColumn { name: name.value }],
+            })
+        }
+        AlterTableOperation::SetOptions { options } => {
+            alter_expr::Kind::SetTableOptions(api::v1::SetTableOptions {
+                table_options: sql_options_to_table_options(&options),
+            })
+        }
+    };
+    let expr = AlterExpr {
+        catalog_name: Some(catalog),
+        schema_name: Some(schema),
+        table_name: table,
+        kind: Some(kind),
+    };
 
-        Ok(expr)
-    }
+    Ok(expr)
 }
@@ -0,0 +1,47 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use session::context::QueryContext;
+use sqlparser::ast::Statement;
+
+use crate::ast::Expr;
+use crate::error::Result;
+use crate::statements::table_idents_to_full_name;
+
+/// DELETE statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Delete {
+    // Can only be sqlparser::ast::Statement::Delete variant
+    pub inner: Statement,
+}
+
+impl Delete {
+    /// Returns the full name of the table, missing catalog and schema are taken from
+    /// `query_ctx`.
+    pub fn full_table_name(&self, query_ctx: &QueryContext) -> Result<(String, String, String)> {
+        match &self.inner {
+            Statement::Delete { table_name, .. } => {
+                table_idents_to_full_name(table_name, query_ctx)
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns the `WHERE` condition of the statement, `None` means deleting all rows.
+    pub fn selection(&self) -> Option<&Expr> {
+        match &self.inner {
+            Statement::Delete { selection, .. } => selection.as_ref(),
+            _ => unreachable!(),
+        }
+    }
+}
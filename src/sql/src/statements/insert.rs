@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use session::context::QueryContext;
 use sqlparser::ast::{SetExpr, Statement, UnaryOperator, Values};
 use sqlparser::parser::ParserError;
 
@@ -26,9 +27,13 @@ pub struct Insert {
 }
 
 impl Insert {
-    pub fn full_table_name(&self) -> Result<(String, String, String)> {
+    /// Returns the full name of the table, missing catalog and schema are taken from
+    /// `query_ctx`.
+    pub fn full_table_name(&self, query_ctx: &QueryContext) -> Result<(String, String, String)> {
         match &self.inner {
-            Statement::Insert { table_name, .. } => table_idents_to_full_name(table_name),
+            Statement::Insert { table_name, .. } => {
+                table_idents_to_full_name(table_name, query_ctx)
+            }
             _ => unreachable!(),
         }
     }
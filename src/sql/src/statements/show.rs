@@ -14,7 +14,7 @@
 
 use std::fmt;
 
-use crate::ast::{Expr, Ident};
+use crate::ast::{Expr, Ident, ObjectName};
 
 /// Show kind for SQL expressions like `SHOW DATABASE` or `SHOW TABLE`
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -57,7 +57,22 @@ pub struct ShowTables {
 /// SQL structure for `SHOW CREATE TABLE`.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ShowCreateTable {
-    pub table_name: String,
+    /// The maybe not fully-qualified name of the table.
+    pub table_name: ObjectName,
+}
+
+/// SQL structure for `SHOW COLUMNS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowColumns {
+    /// The maybe not fully-qualified name of the table.
+    pub table_name: ObjectName,
+}
+
+/// SQL structure for `SHOW [FULL] PROCESSLIST`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ShowProcesslist {
+    /// Shows the full queries instead of their first 100 characters.
+    pub full: bool,
 }
 
 #[cfg(test)]
@@ -124,7 +139,7 @@ mod tests {
         assert_matches!(&stmts[0], Statement::ShowCreateTable { .. });
         match &stmts[0] {
             Statement::ShowCreateTable(show) => {
-                let table_name = show.table_name.as_str();
+                let table_name = show.table_name.to_string();
                 assert_eq!(table_name, "test");
             }
             _ => {
@@ -137,4 +152,53 @@ mod tests {
         let sql = "SHOW CREATE TABLE";
         ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
     }
+
+    #[test]
+    pub fn test_show_columns() {
+        for sql in [
+            "SHOW COLUMNS FROM test_db.test",
+            "SHOW FIELDS IN test FROM test_db",
+        ] {
+            let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+            assert_eq!(1, stmts.len());
+            match &stmts[0] {
+                Statement::ShowColumns(show) => {
+                    assert_eq!("test_db.test", show.table_name.to_string());
+                }
+                _ => unreachable!(),
+            }
+        }
+
+        let sql = "SHOW COLUMNS FROM test";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_matches!(&stmts[0], Statement::ShowColumns { .. });
+
+        for sql in [
+            "SHOW COLUMNS",
+            "SHOW COLUMNS test",
+            "SHOW COLUMNS FROM db.test FROM db",
+        ] {
+            ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+        }
+    }
+
+    #[test]
+    pub fn test_show_processlist() {
+        let sql = "SHOW PROCESSLIST";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(
+            vec![Statement::ShowProcesslist(ShowProcesslist { full: false })],
+            stmts
+        );
+
+        let sql = "show full processlist";
+        let stmts = ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap();
+        assert_eq!(
+            vec![Statement::ShowProcesslist(ShowProcesslist { full: true })],
+            stmts
+        );
+
+        let sql = "SHOW FULL TABLES";
+        ParserContext::create_with_dialect(sql, &GenericDialect {}).unwrap_err();
+    }
 }
@@ -15,14 +15,18 @@
 use sqlparser::ast::Statement as SpStatement;
 use sqlparser::parser::ParserError;
 
+use crate::statements::admin::Admin;
 use crate::statements::alter::AlterTable;
 use crate::statements::create::{CreateDatabase, CreateTable};
+use crate::statements::delete::Delete;
 use crate::statements::describe::DescribeTable;
 use crate::statements::drop::DropTable;
 use crate::statements::explain::Explain;
 use crate::statements::insert::Insert;
 use crate::statements::query::Query;
-use crate::statements::show::{ShowCreateTable, ShowDatabases, ShowTables};
+use crate::statements::show::{
+    ShowColumns, ShowCreateTable, ShowDatabases, ShowProcesslist, ShowTables,
+};
 
 /// Tokens parsed by `DFParser` are converted into these values.
 #[allow(clippy::large_enum_variant)]
@@ -32,6 +36,8 @@ pub enum Statement {
     Query(Box<Query>),
     // Insert
     Insert(Box<Insert>),
+    // Delete
+    Delete(Box<Delete>),
     /// CREATE TABLE
     CreateTable(CreateTable),
     // DROP TABLE
@@ -46,10 +52,20 @@ pub enum Statement {
     ShowTables(ShowTables),
     // SHOW CREATE TABLE
     ShowCreateTable(ShowCreateTable),
+    // SHOW COLUMNS
+    ShowColumns(ShowColumns),
+    // SHOW [FULL] PROCESSLIST
+    ShowProcesslist(ShowProcesslist),
     // DESCRIBE TABLE
     DescribeTable(DescribeTable),
     // EXPLAIN QUERY
     Explain(Explain),
+    // USE
+    Use(String),
+    // KILL [QUERY | CONNECTION], cancels the query running on the connection.
+    Kill(u32),
+    // ADMIN FLUSH TABLE | ADMIN COMPACT TABLE
+    Admin(Admin),
 }
 
 /// Converts Statement to sqlparser statement
@@ -67,14 +83,30 @@ impl TryFrom<Statement> for SpStatement {
             Statement::ShowCreateTable(_) => Err(ParserError::ParserError(
                 "sqlparser does not support SHOW CREATE TABLE query.".to_string(),
             )),
+            Statement::ShowColumns(_) => Err(ParserError::ParserError(
+                "sqlparser does not support SHOW COLUMNS query.".to_string(),
+            )),
+            Statement::ShowProcesslist(_) => Err(ParserError::ParserError(
+                "sqlparser does not support SHOW PROCESSLIST query.".to_string(),
+            )),
             Statement::DescribeTable(_) => Err(ParserError::ParserError(
                 "sqlparser does not support DESCRIBE TABLE query.".to_string(),
             )),
             Statement::DropTable(_) => Err(ParserError::ParserError(
                 "sqlparser does not support DROP TABLE query.".to_string(),
             )),
+            Statement::Use(_) => Err(ParserError::ParserError(
+                "sqlparser does not support USE statement.".to_string(),
+            )),
+            Statement::Kill(_) => Err(ParserError::ParserError(
+                "sqlparser does not support KILL statement.".to_string(),
+            )),
+            Statement::Admin(_) => Err(ParserError::ParserError(
+                "sqlparser does not support ADMIN statement.".to_string(),
+            )),
             Statement::Query(s) => Ok(SpStatement::Query(Box::new(s.inner))),
             Statement::Insert(i) => Ok(i.inner),
+            Statement::Delete(d) => Ok(d.inner),
             Statement::CreateDatabase(_) | Statement::CreateTable(_) | Statement::Alter(_) => {
                 unimplemented!()
             }
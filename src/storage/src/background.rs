@@ -14,14 +14,16 @@
 
 //! Background job management.
 
+use std::fmt;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use async_trait::async_trait;
-use common_runtime::{self, JoinHandle};
+use futures::future::{BoxFuture, Shared};
+use futures::FutureExt;
 use snafu::ResultExt;
 
-use crate::error::{self, Result};
+use crate::error::{self, Error, Result};
 
 /// Background job context.
 #[derive(Clone, Debug, Default)]
@@ -53,17 +55,26 @@ struct ContextInner {
     cancelled: AtomicBool,
 }
 
-/// Handle to the background job.
-#[derive(Debug)]
+/// Result of a background job shared by all handles to the job.
+type SharedResult = std::result::Result<(), Arc<Error>>;
+
+/// Handle to the background job, cloned handles wait for the same job.
+#[derive(Clone)]
 pub struct JobHandle {
     ctx: Context,
-    handle: JoinHandle<Result<()>>,
+    result: Shared<BoxFuture<'static, SharedResult>>,
+}
+
+impl fmt::Debug for JobHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JobHandle").field("ctx", &self.ctx).finish()
+    }
 }
 
 impl JobHandle {
     /// Waits until this background job is finished.
     pub async fn join(self) -> Result<()> {
-        self.handle.await.context(error::JoinTaskSnafu)?
+        self.result.await.context(error::BackgroundJobSnafu)
     }
 
     /// Cancels this background job gracefully and waits until it exits.
@@ -109,8 +120,17 @@ impl JobPool for JobPoolImpl {
         let ctx = Context::new();
         let job_ctx = ctx.clone();
         let handle = common_runtime::spawn_bg(async move { job.run(&job_ctx).await });
-
-        Ok(JobHandle { ctx, handle })
+        let result = async move {
+            match handle.await.context(error::JoinTaskSnafu) {
+                Ok(result) => result.map_err(Arc::new),
+                Err(e) => Err(Arc::new(e)),
+            }
+        };
+
+        Ok(JobHandle {
+            ctx,
+            result: result.boxed().shared(),
+        })
     }
 
     async fn shutdown(&self) -> Result<()> {
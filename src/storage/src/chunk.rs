@@ -15,14 +15,21 @@
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use common_query::cancellation::CancellationToken;
 use common_query::logical_plan::Expr;
+use common_telemetry::debug;
+use common_time::{Timestamp, TimestampRange};
+use metrics::counter;
 use snafu::ResultExt;
 use store_api::storage::{Chunk, ChunkReader, SchemaRef, SequenceNumber};
-use table::predicate::Predicate;
+use table::predicate::{ExactFilter, Predicate, TimeRangePredicateBuilder};
 
 use crate::error::{self, Error, Result};
 use crate::memtable::{IterContext, MemtableRef};
-use crate::read::{BoxedBatchReader, DedupReader, MergeReaderBuilder};
+use crate::metric::{METRIC_SCAN_BYTES, METRIC_SCAN_ROWS};
+use crate::read::{
+    Batch, BatchReader, BoxedBatchReader, DedupReader, FilterReader, MergeReaderBuilder, TtlReader,
+};
 use crate::schema::{ProjectedSchema, ProjectedSchemaRef, RegionSchemaRef};
 use crate::sst::{AccessLayerRef, FileHandle, LevelMetas, ReadOptions, Visitor};
 
@@ -33,6 +40,9 @@ use crate::sst::{AccessLayerRef, FileHandle, LevelMetas, ReadOptions, Visitor};
 pub struct ChunkReaderImpl {
     schema: ProjectedSchemaRef,
     batch_reader: BoxedBatchReader,
+    cancellation: CancellationToken,
+    /// Max number of rows left to return, `None` for no limit.
+    remaining: Option<usize>,
 }
 
 #[async_trait]
@@ -44,24 +54,72 @@ impl ChunkReader for ChunkReaderImpl {
     }
 
     async fn next_chunk(&mut self) -> Result<Option<Chunk>> {
-        let batch = match self.batch_reader.next_batch().await? {
+        let batch = match self.next_batch().await? {
             Some(b) => b,
             None => return Ok(None),
         };
 
         let chunk = self.schema.batch_to_chunk(&batch);
+        let bytes: usize = chunk.columns.iter().map(|v| v.memory_size()).sum();
+        counter!(METRIC_SCAN_ROWS, batch.num_rows() as u64);
+        counter!(METRIC_SCAN_BYTES, bytes as u64);
 
         Ok(Some(chunk))
     }
 }
 
 impl ChunkReaderImpl {
-    pub fn new(schema: ProjectedSchemaRef, batch_reader: BoxedBatchReader) -> ChunkReaderImpl {
+    pub fn new(
+        schema: ProjectedSchemaRef,
+        batch_reader: BoxedBatchReader,
+        cancellation: CancellationToken,
+    ) -> ChunkReaderImpl {
         ChunkReaderImpl {
             schema,
             batch_reader,
+            cancellation,
+            remaining: None,
+        }
+    }
+
+    /// Stops reading once `limit` rows are returned.
+    pub fn with_limit(mut self, limit: Option<usize>) -> ChunkReaderImpl {
+        self.remaining = limit;
+        self
+    }
+
+    pub async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        if self.remaining == Some(0) {
+            // Enough rows are returned, stop reading the remaining sources.
+            return Ok(None);
+        }
+
+        self.cancellation
+            .check()
+            .context(error::ReadCancelledSnafu)?;
+        let batch = match self.batch_reader.next_batch().await? {
+            Some(b) => b,
+            None => return Ok(None),
+        };
+
+        match &mut self.remaining {
+            Some(remaining) if batch.num_rows() > *remaining => {
+                let batch = batch.slice(0, *remaining);
+                *remaining = 0;
+                Ok(Some(batch))
+            }
+            Some(remaining) => {
+                *remaining -= batch.num_rows();
+                Ok(Some(batch))
+            }
+            None => Ok(Some(batch)),
         }
     }
+
+    #[inline]
+    pub fn projected_schema(&self) -> &ProjectedSchemaRef {
+        &self.schema
+    }
 }
 
 /// Builder to create a new [ChunkReaderImpl] from scan request.
@@ -73,6 +131,15 @@ pub struct ChunkReaderBuilder {
     iter_ctx: IterContext,
     memtables: Vec<MemtableRef>,
     files_to_read: Vec<FileHandle>,
+    /// Time range extracted from filters, files out of this range are skipped.
+    time_range: TimestampRange,
+    /// Rows before this timestamp are expired and won't be returned.
+    expire_before: Option<Timestamp>,
+    /// Whether to remove deleted rows from the output.
+    filter_deleted: bool,
+    cancellation: CancellationToken,
+    /// Max number of rows to return.
+    limit: Option<usize>,
 }
 
 impl ChunkReaderBuilder {
@@ -85,6 +152,11 @@ impl ChunkReaderBuilder {
             iter_ctx: IterContext::default(),
             memtables: Vec::new(),
             files_to_read: Vec::new(),
+            time_range: TimestampRange::min_to_max(),
+            expire_before: None,
+            filter_deleted: true,
+            cancellation: CancellationToken::default(),
+            limit: None,
         }
     }
 
@@ -119,13 +191,66 @@ impl ChunkReaderBuilder {
         self
     }
 
+    /// Filters out rows whose timestamp is older than `expire_before`, this should be
+    /// called before [ChunkReaderBuilder::pick_ssts()] so expired files could be skipped.
+    pub fn expire_before(mut self, expire_before: Option<Timestamp>) -> Self {
+        self.expire_before = expire_before;
+        self
+    }
+
+    /// Sets whether to remove deleted rows from the output, default is true.
+    ///
+    /// Tombstones must be kept if the output is written back to the region, otherwise
+    /// deleted rows in other files may become visible again.
+    pub fn filter_deleted(mut self, filter_deleted: bool) -> Self {
+        self.filter_deleted = filter_deleted;
+        self
+    }
+
+    /// Sets the token to stop reading once the query is cancelled.
+    pub fn cancellation(mut self, cancellation: CancellationToken) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    /// Returns rows in descending order of the row key if `descending` is true.
+    pub fn descending(mut self, descending: bool) -> Self {
+        self.iter_ctx.descending = descending;
+        self
+    }
+
+    /// Sets the max number of rows to return, `None` for no limit.
+    pub fn limit(mut self, limit: Option<usize>) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Picks SSTs that may contain data matching the filters, so this should be called
+    /// after [ChunkReaderBuilder::filters()].
     pub fn pick_ssts(mut self, ssts: &LevelMetas) -> Result<Self> {
+        if let Some(ts_col) = self.schema.user_schema().timestamp_column() {
+            self.time_range = TimeRangePredicateBuilder::new(&ts_col.name, &self.filters).build();
+        }
+        if let Some(expire_before) = self.expire_before {
+            self.time_range = self
+                .time_range
+                .and(&TimestampRange::new(Some(expire_before), None));
+        }
+
         ssts.visit_levels(&mut self)?;
 
         Ok(self)
     }
 
+    /// Picks given `files` to read, used to read a subset of SSTs (e.g. the inputs of
+    /// compaction).
+    pub fn pick_files(mut self, files: &[FileHandle]) -> Self {
+        self.files_to_read.extend_from_slice(files);
+        self
+    }
+
     pub async fn build(mut self) -> Result<ChunkReaderImpl> {
+        let exact_filters = self.exact_filters();
         let schema = Arc::new(
             ProjectedSchema::new(self.schema, self.projection)
                 .context(error::InvalidProjectionSnafu)?,
@@ -133,7 +258,8 @@ impl ChunkReaderBuilder {
 
         let num_sources = self.memtables.len() + self.files_to_read.len();
         let mut reader_builder = MergeReaderBuilder::with_capacity(schema.clone(), num_sources)
-            .batch_size(self.iter_ctx.batch_size);
+            .batch_size(self.iter_ctx.batch_size)
+            .descending(self.iter_ctx.descending);
 
         self.iter_ctx.projected_schema = Some(schema.clone());
         for mem in self.memtables {
@@ -145,8 +271,12 @@ impl ChunkReaderBuilder {
             batch_size: self.iter_ctx.batch_size,
             projected_schema: schema.clone(),
             predicate: Predicate::new(self.filters),
+            descending: self.iter_ctx.descending,
         };
         for file in &self.files_to_read {
+            self.cancellation
+                .check()
+                .context(error::ReadCancelledSnafu)?;
             let reader = self
                 .sst_layer
                 .read_sst(file.file_name(), &read_opts)
@@ -156,19 +286,48 @@ impl ChunkReaderBuilder {
         }
 
         let reader = reader_builder.build();
-        let reader = DedupReader::new(schema.clone(), reader);
+        let reader = DedupReader::new(schema.clone(), reader, self.filter_deleted)
+            .with_descending(self.iter_ctx.descending);
+        let reader: BoxedBatchReader = match self.expire_before {
+            Some(expire_before) => Box::new(TtlReader::new(schema.clone(), reader, expire_before)),
+            None => Box::new(reader),
+        };
+        let reader: BoxedBatchReader = if exact_filters.is_empty() {
+            reader
+        } else {
+            Box::new(FilterReader::new(schema.clone(), reader, exact_filters))
+        };
+
+        Ok(ChunkReaderImpl::new(schema, reader, self.cancellation).with_limit(self.limit))
+    }
 
-        Ok(ChunkReaderImpl::new(schema, Box::new(reader)))
+    /// Returns filters that could be evaluated exactly on the row key columns, rows not
+    /// matching them are removed from the output.
+    fn exact_filters(&self) -> Vec<ExactFilter> {
+        let key_columns: Vec<_> = self.schema.row_key_columns().map(|c| c.name()).collect();
+        self.filters
+            .iter()
+            .filter_map(|expr| ExactFilter::try_new(expr, self.schema.user_schema(), &key_columns))
+            .collect()
     }
 }
 
 impl Visitor for ChunkReaderBuilder {
     fn visit(&mut self, _level: usize, files: &[FileHandle]) -> Result<()> {
-        // TODO(yingwen): Filter files by time range.
-
-        // Now we read all files, so just reserve enough space to hold all files.
+        // Now we may read all files, so just reserve enough space to hold all files.
         self.files_to_read.reserve(files.len());
         for file in files {
+            if let Some((start, end)) = file.time_range() {
+                if !self.time_range.intersects(start, end) {
+                    debug!(
+                        "Skip file {} out of time range {:?}",
+                        file.file_name(),
+                        self.time_range
+                    );
+                    continue;
+                }
+            }
+
             // We can't invoke async functions here, so we collects all files first, and
             // create the batch reader later in `ChunkReaderBuilder`.
             self.files_to_read.push(file.clone());
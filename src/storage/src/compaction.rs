@@ -0,0 +1,63 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compaction of SST files.
+//!
+//! Every flush adds new files to level 0, so the number of files in a region grows over
+//! time. A [CompactionPicker] chooses overlapping files after each flush, and a background
+//! [CompactionJob] merges them into new files in a higher level.
+//!
+//! If the region has a ttl, files whose rows are all expired are also picked and removed
+//! from the region by the [CompactionJob]. Each region also checks expired files
+//! periodically, so they are purged even if the region has no write to trigger a flush.
+
+mod picker;
+mod task;
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::background::{Job, JobHandle, JobPoolRef};
+pub use crate::compaction::picker::{
+    CompactionInput, CompactionPicker, CompactionPickerRef, SimplePicker,
+};
+pub use crate::compaction::task::CompactionJob;
+use crate::error::Result;
+
+#[async_trait]
+pub trait CompactionScheduler: Send + Sync + std::fmt::Debug {
+    async fn schedule_compaction(&self, compaction_job: Box<dyn Job>) -> Result<JobHandle>;
+}
+
+pub type CompactionSchedulerRef = Arc<dyn CompactionScheduler>;
+
+#[derive(Debug)]
+pub struct CompactionSchedulerImpl {
+    job_pool: JobPoolRef,
+}
+
+impl CompactionSchedulerImpl {
+    pub fn new(job_pool: JobPoolRef) -> CompactionSchedulerImpl {
+        CompactionSchedulerImpl { job_pool }
+    }
+}
+
+#[async_trait]
+impl CompactionScheduler for CompactionSchedulerImpl {
+    async fn schedule_compaction(&self, compaction_job: Box<dyn Job>) -> Result<JobHandle> {
+        // Compactions are scheduled like flushes, the job pool controls the parallelism.
+        self.job_pool.submit(compaction_job).await
+    }
+}
@@ -0,0 +1,422 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_time::Timestamp;
+
+use crate::sst::{FileHandle, Level, LevelMetas, MAX_LEVEL};
+
+/// Default max number of files in level 0 before triggering compaction.
+const DEFAULT_MAX_FILES_IN_LEVEL0: usize = 8;
+
+/// Files picked by [CompactionPicker].
+#[derive(Debug)]
+pub struct CompactionInput {
+    /// Level of the output files.
+    pub output_level: Level,
+    /// Files to compact, they would be removed from the region after compaction.
+    pub inputs: Vec<FileHandle>,
+    /// Files whose rows are all expired, they would be removed from the region directly.
+    pub expired: Vec<FileHandle>,
+    /// Whether to remove deleted rows and tombstones while compacting, only if no file
+    /// out of the inputs might contain older rows to delete.
+    pub filter_deleted: bool,
+}
+
+impl CompactionInput {
+    /// Marks whether the input files are being compacted, so they won't be picked
+    /// by other compactions.
+    pub fn mark_compacting(&self, compacting: bool) {
+        for file in self.inputs.iter().chain(&self.expired) {
+            file.mark_compacting(compacting);
+        }
+    }
+}
+
+/// Picks files to compact.
+pub trait CompactionPicker: Send + Sync + std::fmt::Debug {
+    /// Picks files to compact from `ssts`, returns `None` if there is nothing to compact.
+    ///
+    /// Rows before `expire_before` are expired, files only containing expired rows should
+    /// be picked as expired files.
+    fn pick(&self, ssts: &LevelMetas, expire_before: Option<Timestamp>) -> Option<CompactionInput>;
+
+    /// Picks files to compact from `ssts` for a manual compaction, which compacts files
+    /// regardless of the compaction threshold.
+    fn pick_manual(
+        &self,
+        ssts: &LevelMetas,
+        expire_before: Option<Timestamp>,
+    ) -> Option<CompactionInput>;
+
+    /// Picks files only containing rows before `expire_before` from `ssts`, returns `None`
+    /// if no file is expired.
+    fn pick_expired(&self, ssts: &LevelMetas, expire_before: Timestamp) -> Option<CompactionInput>;
+}
+
+pub type CompactionPickerRef = Arc<dyn CompactionPicker>;
+
+/// A picker that compacts all level 0 files into level 1.
+///
+/// Files in level 0 may overlap with each other, so once the number of files in level 0
+/// reaches `max_files_in_level0`, the picker picks all of them, together with files in
+/// level 1 that overlap with them. Files in any level whose rows are all expired are
+/// picked as expired files, regardless of the number of files in level 0.
+#[derive(Debug)]
+pub struct SimplePicker {
+    max_files_in_level0: usize,
+}
+
+impl Default for SimplePicker {
+    fn default() -> SimplePicker {
+        SimplePicker::new(DEFAULT_MAX_FILES_IN_LEVEL0)
+    }
+}
+
+impl SimplePicker {
+    pub fn new(max_files_in_level0: usize) -> SimplePicker {
+        SimplePicker {
+            max_files_in_level0,
+        }
+    }
+
+    /// Picks all level 0 files once the number of them reaches `min_files_in_level0`.
+    fn pick_level0(
+        &self,
+        ssts: &LevelMetas,
+        expire_before: Option<Timestamp>,
+        min_files_in_level0: usize,
+    ) -> Option<CompactionInput> {
+        assert!(MAX_LEVEL > 1, "Compaction requires at least 2 levels");
+
+        let expired = expired_files(ssts, expire_before);
+        let is_candidate = |f: &&FileHandle| !f.compacting() && !is_expired(f, expire_before);
+
+        let output_level = 1;
+        let level0_files: Vec<_> = ssts
+            .level(0)
+            .files()
+            .filter(is_candidate)
+            .cloned()
+            .collect();
+        if level0_files.is_empty() || level0_files.len() < min_files_in_level0 {
+            if expired.is_empty() {
+                return None;
+            }
+
+            return Some(CompactionInput {
+                output_level,
+                inputs: Vec::new(),
+                expired,
+                filter_deleted: false,
+            });
+        }
+
+        // Files in level 1 that don't overlap with the time range of level 0 files are
+        // left untouched. Files without time range are always treated as overlapping.
+        let time_range = merged_time_range(&level0_files);
+        let mut inputs = level0_files;
+        inputs.extend(
+            ssts.level(output_level)
+                .files()
+                .filter(is_candidate)
+                .filter(|f| overlaps(time_range.as_ref(), f))
+                .cloned(),
+        );
+
+        // Tombstones are no longer needed if the output is the bottom level and the inputs
+        // contain all files that are not expired.
+        let filter_deleted = usize::from(output_level) == MAX_LEVEL - 1
+            && ssts
+                .levels()
+                .iter()
+                .flat_map(|level| level.files())
+                .all(|f| {
+                    is_expired(f, expire_before)
+                        || inputs
+                            .iter()
+                            .any(|input| input.file_name() == f.file_name())
+                });
+
+        Some(CompactionInput {
+            output_level,
+            inputs,
+            expired,
+            filter_deleted,
+        })
+    }
+}
+
+impl CompactionPicker for SimplePicker {
+    fn pick(&self, ssts: &LevelMetas, expire_before: Option<Timestamp>) -> Option<CompactionInput> {
+        self.pick_level0(ssts, expire_before, self.max_files_in_level0)
+    }
+
+    fn pick_manual(
+        &self,
+        ssts: &LevelMetas,
+        expire_before: Option<Timestamp>,
+    ) -> Option<CompactionInput> {
+        // Any file in level 0 is worth compacting, it might overlap with files in level 1.
+        self.pick_level0(ssts, expire_before, 1)
+    }
+
+    fn pick_expired(&self, ssts: &LevelMetas, expire_before: Timestamp) -> Option<CompactionInput> {
+        let expired = expired_files(ssts, Some(expire_before));
+        if expired.is_empty() {
+            return None;
+        }
+
+        Some(CompactionInput {
+            output_level: 1,
+            inputs: Vec::new(),
+            expired,
+            filter_deleted: false,
+        })
+    }
+}
+
+/// Returns files in all levels that are not compacting and only contain expired rows.
+fn expired_files(ssts: &LevelMetas, expire_before: Option<Timestamp>) -> Vec<FileHandle> {
+    ssts.levels()
+        .iter()
+        .flat_map(|level| level.files())
+        .filter(|f| !f.compacting() && is_expired(f, expire_before))
+        .cloned()
+        .collect()
+}
+
+/// Returns the time range that covers all `files`, or `None` if the time range of any file
+/// is unknown.
+fn merged_time_range(files: &[FileHandle]) -> Option<(Timestamp, Timestamp)> {
+    let mut ranges = files.iter().map(|f| f.time_range().copied());
+    let first = ranges.next()??;
+    ranges.try_fold(first, |(min, max), range| {
+        let (start, end) = range?;
+        Some((min.min(start), max.max(end)))
+    })
+}
+
+/// Returns true if all rows in the `file` are before `expire_before`.
+fn is_expired(file: &FileHandle, expire_before: Option<Timestamp>) -> bool {
+    match (expire_before, file.time_range()) {
+        (Some(expire_before), Some((_, end))) => *end < expire_before,
+        _ => false,
+    }
+}
+
+fn overlaps(time_range: Option<&(Timestamp, Timestamp)>, file: &FileHandle) -> bool {
+    match (time_range, file.time_range()) {
+        (Some((start, end)), Some((file_start, file_end))) => {
+            start <= file_end && file_start <= end
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sst::FileMeta;
+    use crate::test_util::access_layer_util::MockAccessLayer;
+
+    fn new_level_metas(level0: &[&str], level1: &[&str]) -> LevelMetas {
+        let files = level0
+            .iter()
+            .map(|name| (name, 0))
+            .chain(level1.iter().map(|name| (name, 1)))
+            .map(|(name, level)| FileMeta {
+                file_name: name.to_string(),
+                level,
+                ..Default::default()
+            });
+
+        LevelMetas::new(Arc::new(MockAccessLayer)).merge(files, std::iter::empty())
+    }
+
+    fn new_file_meta(name: &str, level: Level, start: i64, end: i64) -> FileMeta {
+        FileMeta {
+            file_name: name.to_string(),
+            level,
+            time_range: Some((Timestamp::from_millis(start), Timestamp::from_millis(end))),
+            ..Default::default()
+        }
+    }
+
+    fn input_file_names(input: &CompactionInput) -> Vec<&str> {
+        let mut names: Vec<_> = input.inputs.iter().map(|f| f.file_name()).collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_simple_picker_not_enough_files() {
+        let picker = SimplePicker::new(3);
+        let ssts = new_level_metas(&["a", "b"], &["c"]);
+
+        assert!(picker.pick(&ssts, None).is_none());
+    }
+
+    #[test]
+    fn test_simple_picker_pick_all() {
+        let picker = SimplePicker::new(3);
+        let ssts = new_level_metas(&["a", "b", "c"], &["d"]);
+
+        let input = picker.pick(&ssts, None).unwrap();
+        assert_eq!(1, input.output_level);
+        assert_eq!(vec!["a", "b", "c", "d"], input_file_names(&input));
+        // All files are compacted into the bottom level.
+        assert!(input.filter_deleted);
+    }
+
+    #[test]
+    fn test_simple_picker_skip_compacting() {
+        let picker = SimplePicker::new(2);
+        let ssts = new_level_metas(&["a", "b", "c"], &["d"]);
+
+        let input = picker.pick(&ssts, None).unwrap();
+        input.mark_compacting(true);
+        // All files are compacting.
+        assert!(picker.pick(&ssts, None).is_none());
+
+        // Add more files to level 0.
+        let ssts = ssts.merge(
+            ["e", "f"].iter().map(|name| FileMeta {
+                file_name: name.to_string(),
+                level: 0,
+                ..Default::default()
+            }),
+            std::iter::empty(),
+        );
+        let input = picker.pick(&ssts, None).unwrap();
+        assert_eq!(vec!["e", "f"], input_file_names(&input));
+
+        input.mark_compacting(false);
+        let input = picker.pick(&ssts, None).unwrap();
+        assert_eq!(vec!["e", "f"], input_file_names(&input));
+    }
+
+    #[test]
+    fn test_simple_picker_overlapping_level1() {
+        let picker = SimplePicker::new(2);
+        let files = vec![
+            new_file_meta("a", 0, 1000, 2000),
+            new_file_meta("b", 0, 1500, 3000),
+            // Overlaps with "a".
+            new_file_meta("c", 1, 0, 1000),
+            // Overlaps with "b".
+            new_file_meta("d", 1, 2500, 4000),
+            // Doesn't overlap with level 0.
+            new_file_meta("e", 1, 3001, 5000),
+            // Unknown time range.
+            FileMeta {
+                file_name: "f".to_string(),
+                level: 1,
+                ..Default::default()
+            },
+        ];
+        let ssts =
+            LevelMetas::new(Arc::new(MockAccessLayer)).merge(files.into_iter(), std::iter::empty());
+
+        let input = picker.pick(&ssts, None).unwrap();
+        assert_eq!(vec!["a", "b", "c", "d", "f"], input_file_names(&input));
+        // "e" might contain rows deleted by tombstones in the inputs.
+        assert!(!input.filter_deleted);
+    }
+
+    #[test]
+    fn test_simple_picker_expired() {
+        let picker = SimplePicker::new(2);
+        let files = vec![
+            new_file_meta("a", 0, 1000, 2000),
+            new_file_meta("b", 0, 1500, 3000),
+            new_file_meta("c", 0, 2500, 4000),
+            new_file_meta("d", 1, 0, 1000),
+            // Unknown time range.
+            FileMeta {
+                file_name: "e".to_string(),
+                level: 1,
+                ..Default::default()
+            },
+        ];
+        let ssts =
+            LevelMetas::new(Arc::new(MockAccessLayer)).merge(files.into_iter(), std::iter::empty());
+
+        // Only "d" is expired, "a" still contains rows not expired.
+        let input = picker
+            .pick(&ssts, Some(Timestamp::from_millis(1500)))
+            .unwrap();
+        assert_eq!(vec!["a", "b", "c", "e"], input_file_names(&input));
+        let expired: Vec<_> = input.expired.iter().map(|f| f.file_name()).collect();
+        assert_eq!(vec!["d"], expired);
+        // Expired files don't need the tombstones.
+        assert!(input.filter_deleted);
+
+        // Not enough files to compact, but expired files are still picked.
+        let input = picker
+            .pick(&ssts, Some(Timestamp::from_millis(3500)))
+            .unwrap();
+        assert!(input.inputs.is_empty());
+        let mut expired: Vec<_> = input.expired.iter().map(|f| f.file_name()).collect();
+        expired.sort_unstable();
+        assert_eq!(vec!["a", "b", "d"], expired);
+    }
+
+    #[test]
+    fn test_simple_picker_pick_expired() {
+        let picker = SimplePicker::new(2);
+        let files = vec![
+            new_file_meta("a", 0, 1000, 2000),
+            new_file_meta("b", 0, 1500, 3000),
+            new_file_meta("c", 1, 0, 1000),
+        ];
+        let ssts =
+            LevelMetas::new(Arc::new(MockAccessLayer)).merge(files.into_iter(), std::iter::empty());
+
+        assert!(picker
+            .pick_expired(&ssts, Timestamp::from_millis(1000))
+            .is_none());
+
+        // Files to compact are never picked.
+        let input = picker
+            .pick_expired(&ssts, Timestamp::from_millis(2500))
+            .unwrap();
+        assert!(input.inputs.is_empty());
+        let mut expired: Vec<_> = input.expired.iter().map(|f| f.file_name()).collect();
+        expired.sort_unstable();
+        assert_eq!(vec!["a", "c"], expired);
+
+        // Expired files being purged are skipped.
+        input.mark_compacting(true);
+        assert!(picker
+            .pick_expired(&ssts, Timestamp::from_millis(2500))
+            .is_none());
+    }
+
+    #[test]
+    fn test_simple_picker_pick_manual() {
+        let picker = SimplePicker::new(3);
+        let ssts = new_level_metas(&["a"], &["b"]);
+        assert!(picker.pick(&ssts, None).is_none());
+
+        let input = picker.pick_manual(&ssts, None).unwrap();
+        assert_eq!(1, input.output_level);
+        assert_eq!(vec!["a", "b"], input_file_names(&input));
+
+        // Nothing to compact without files in level 0.
+        let ssts = new_level_metas(&[], &["b"]);
+        assert!(picker.pick_manual(&ssts, None).is_none());
+    }
+}
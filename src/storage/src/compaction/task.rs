@@ -0,0 +1,179 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use common_telemetry::logging;
+use store_api::logstore::LogStore;
+use store_api::storage::consts::WRITE_ROW_GROUP_SIZE;
+
+use crate::background::{Context, Job};
+use crate::chunk::ChunkReaderBuilder;
+use crate::compaction::picker::CompactionInput;
+use crate::error::{CancelledSnafu, Result};
+use crate::manifest::action::RegionEdit;
+use crate::manifest::region::RegionManifest;
+use crate::region::{RegionWriterRef, SharedDataRef};
+use crate::sst::{self, AccessLayerRef, FileMeta, Source, WriteOptions};
+use crate::wal::Wal;
+
+pub struct CompactionJob<S: LogStore> {
+    /// Files to compact.
+    pub input: CompactionInput,
+    /// Shared data of region to be compacted.
+    pub shared: SharedDataRef,
+    /// Sst access layer of the region.
+    pub sst_layer: AccessLayerRef,
+    /// Region writer, used to persist log entry that points to the latest manifest file.
+    pub writer: RegionWriterRef,
+    /// Region write-ahead logging, used to write data/meta to the log file.
+    pub wal: Wal<S>,
+    /// Region manifest service, used to persist metadata.
+    pub manifest: RegionManifest,
+}
+
+impl<S: LogStore> CompactionJob<S> {
+    /// Merges all input files into a new file in the output level.
+    async fn merge_inputs(&self, ctx: &Context) -> Result<Vec<FileMeta>> {
+        if ctx.is_cancelled() {
+            return CancelledSnafu {}.fail();
+        }
+        if self.input.inputs.is_empty() {
+            // Only need to remove expired files.
+            return Ok(Vec::new());
+        }
+
+        // Files might be written with older schemas, we read them using the current schema
+        // of the region.
+        let schema = self.shared.version_control.current().schema().clone();
+        // The chunk reader merges the input files and removes duplicate and expired rows.
+        // Tombstones are kept unless no older rows to delete remain out of the inputs.
+        let reader = ChunkReaderBuilder::new(schema, self.sst_layer.clone())
+            .batch_size(WRITE_ROW_GROUP_SIZE)
+            .expire_before(self.shared.expire_before())
+            .filter_deleted(self.input.filter_deleted)
+            .pick_files(&self.input.inputs)
+            .build()
+            .await?;
+
+        let file_name = sst::generate_sst_file_name();
+        let sst_info = self
+            .sst_layer
+            .write_sst(&file_name, Source::Reader(reader), &WriteOptions::default())
+            .await?;
+
+        let metas = vec![FileMeta::with_sst_info(
+            file_name,
+            self.input.output_level,
+            sst_info,
+        )];
+
+        logging::info!(
+            "Successfully compact files in region: {}, inputs: {:?}, outputs: {:?}",
+            self.shared.name(),
+            self.input
+                .inputs
+                .iter()
+                .map(|f| f.file_name())
+                .collect::<Vec<_>>(),
+            metas
+        );
+
+        Ok(metas)
+    }
+
+    /// Adds the output files and removes the input and expired files in one edit.
+    async fn write_manifest_and_apply(&self, file_metas: Vec<FileMeta>) -> Result<()> {
+        let version = self.shared.version_control.current();
+        let edit = RegionEdit {
+            region_version: version.metadata().version(),
+            flushed_sequence: version.flushed_sequence(),
+            files_to_add: file_metas,
+            files_to_remove: self
+                .input
+                .inputs
+                .iter()
+                .chain(&self.input.expired)
+                .map(|f| f.meta())
+                .collect(),
+        };
+
+        if !self.input.expired.is_empty() {
+            logging::info!(
+                "Remove expired files in region: {}, files: {:?}",
+                self.shared.name(),
+                self.input
+                    .expired
+                    .iter()
+                    .map(|f| f.file_name())
+                    .collect::<Vec<_>>(),
+            );
+        }
+
+        self.writer
+            .write_edit_and_apply(&self.wal, &self.shared, &self.manifest, edit, None)
+            .await
+    }
+
+    /// Deletes output files that are not added to the region, e.g. the edit fails to
+    /// persist to the manifest.
+    async fn delete_unused_outputs(&self, file_names: &[String]) {
+        let version = self.shared.version_control.current();
+        for file_name in file_names {
+            let in_use = version
+                .ssts()
+                .levels()
+                .iter()
+                .any(|level| level.files().any(|f| f.file_name() == file_name));
+            if in_use {
+                continue;
+            }
+
+            if let Err(e) = self.sst_layer.delete_sst(file_name).await {
+                logging::error!(
+                    e; "Failed to delete compaction output: {}, region: {}",
+                    file_name,
+                    self.shared.name()
+                );
+            }
+        }
+    }
+
+    async fn compact(&self, ctx: &Context) -> Result<()> {
+        let file_metas = self.merge_inputs(ctx).await?;
+        let output_files: Vec<_> = file_metas.iter().map(|m| m.file_name.clone()).collect();
+
+        let result = self.write_manifest_and_apply(file_metas).await;
+        if result.is_err() {
+            self.delete_unused_outputs(&output_files).await;
+        }
+
+        result
+    }
+}
+
+#[async_trait]
+impl<S: LogStore> Job for CompactionJob<S> {
+    async fn run(&mut self, ctx: &Context) -> Result<()> {
+        let result = self.compact(ctx).await;
+        if let Err(e) = &result {
+            logging::error!(e; "Failed to compact region: {}", self.shared.name());
+        }
+
+        // The input files are either removed from the region or still valid in the region
+        // after compaction, so we could always reset the flag.
+        self.input.mark_compacting(false);
+
+        result
+    }
+}
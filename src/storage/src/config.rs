@@ -14,5 +14,51 @@
 
 //! storage engine config
 
-#[derive(Debug, Default, Clone)]
-pub struct EngineConfig {}
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+/// Default interval to check and purge expired files of regions with ttl.
+const DEFAULT_TTL_CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Debug, Clone)]
+pub struct EngineConfig {
+    /// Config of the local read cache for SST files, the cache is disabled if it is `None`.
+    pub read_cache: Option<ReadCacheConfig>,
+    /// Interval to check whether regions with ttl have files to purge.
+    pub ttl_check_interval: Duration,
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            read_cache: None,
+            ttl_check_interval: DEFAULT_TTL_CHECK_INTERVAL,
+        }
+    }
+}
+
+/// Config of the local read cache for SST files.
+///
+/// The cache keeps SST files fetched from the object store in a local directory,
+/// which saves the cost of downloading the same files again when the object store
+/// is a remote service, e.g. S3.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReadCacheConfig {
+    /// Local directory to hold the cached files. Files under this directory would be
+    /// removed on startup.
+    pub dir: String,
+    /// Max total size of cached files in bytes.
+    pub capacity: u64,
+}
+
+impl Default for ReadCacheConfig {
+    fn default() -> Self {
+        Self {
+            dir: "/tmp/greptimedb/read_cache".to_string(),
+            // 1GiB
+            capacity: 1024 * 1024 * 1024,
+        }
+    }
+}
@@ -14,17 +14,21 @@
 
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use async_trait::async_trait;
-use common_telemetry::logging::info;
+use common_telemetry::logging::{self, info};
 use object_store::{util, ObjectStore};
 use snafu::ResultExt;
 use store_api::logstore::LogStore;
 use store_api::storage::{
-    CreateOptions, EngineContext, OpenOptions, RegionDescriptor, StorageEngine,
+    CreateOptions, EngineContext, OpenOptions, Region, RegionDescriptor, StorageEngine,
 };
 
-use crate::background::JobPoolImpl;
+use crate::background::{JobPoolImpl, JobPoolRef};
+use crate::compaction::{
+    CompactionPickerRef, CompactionSchedulerImpl, CompactionSchedulerRef, SimplePicker,
+};
 use crate::config::EngineConfig;
 use crate::error::{self, Error, Result};
 use crate::flush::{FlushSchedulerImpl, FlushSchedulerRef, FlushStrategyRef, SizeBasedStrategy};
@@ -32,7 +36,7 @@ use crate::manifest::region::RegionManifest;
 use crate::memtable::{DefaultMemtableBuilder, MemtableBuilderRef};
 use crate::metadata::RegionMetadata;
 use crate::region::{RegionImpl, StoreConfig};
-use crate::sst::FsAccessLayer;
+use crate::sst::{AccessLayerRef, CachedAccessLayer, FsAccessLayer, SstReadCache, SstReadCacheRef};
 
 /// [StorageEngine] implementation.
 pub struct EngineImpl<S: LogStore> {
@@ -61,8 +65,8 @@ impl<S: LogStore> StorageEngine for EngineImpl<S> {
         self.inner.open_region(name, opts).await
     }
 
-    async fn close_region(&self, _ctx: &EngineContext, _region: Self::Region) -> Result<()> {
-        unimplemented!()
+    async fn close_region(&self, _ctx: &EngineContext, region: Self::Region) -> Result<()> {
+        self.inner.close_region(region).await
     }
 
     async fn create_region(
@@ -74,8 +78,8 @@ impl<S: LogStore> StorageEngine for EngineImpl<S> {
         self.inner.create_region(descriptor, opts).await
     }
 
-    async fn drop_region(&self, _ctx: &EngineContext, _region: Self::Region) -> Result<()> {
-        unimplemented!()
+    async fn drop_region(&self, _ctx: &EngineContext, region: Self::Region) -> Result<()> {
+        self.inner.drop_region(region).await
     }
 
     fn get_region(&self, _ctx: &EngineContext, name: &str) -> Result<Option<Self::Region>> {
@@ -210,12 +214,28 @@ struct EngineInner<S: LogStore> {
     memtable_builder: MemtableBuilderRef,
     flush_scheduler: FlushSchedulerRef,
     flush_strategy: FlushStrategyRef,
+    compaction_picker: CompactionPickerRef,
+    compaction_scheduler: CompactionSchedulerRef,
+    read_cache: Option<SstReadCacheRef>,
+    ttl_check_interval: Duration,
 }
 
 impl<S: LogStore> EngineInner<S> {
-    pub fn new(_config: EngineConfig, log_store: Arc<S>, object_store: ObjectStore) -> Self {
-        let job_pool = Arc::new(JobPoolImpl {});
-        let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool));
+    pub fn new(config: EngineConfig, log_store: Arc<S>, object_store: ObjectStore) -> Self {
+        let job_pool: JobPoolRef = Arc::new(JobPoolImpl {});
+        let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool.clone()));
+        let compaction_scheduler = Arc::new(CompactionSchedulerImpl::new(job_pool));
+        let read_cache = config.read_cache.as_ref().and_then(|cache_config| {
+            match SstReadCache::new(cache_config) {
+                Ok(cache) => Some(Arc::new(cache)),
+                Err(e) => {
+                    // The cache is only an optimization, so we still could serve
+                    // requests without it.
+                    logging::error!(e; "Failed to create SST read cache, read cache is disabled");
+                    None
+                }
+            }
+        });
 
         Self {
             object_store,
@@ -224,6 +244,10 @@ impl<S: LogStore> EngineInner<S> {
             memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
             flush_scheduler,
             flush_strategy: Arc::new(SizeBasedStrategy::default()),
+            compaction_picker: Arc::new(SimplePicker::default()),
+            compaction_scheduler,
+            read_cache,
+            ttl_check_interval: config.ttl_check_interval,
         }
     }
 
@@ -259,7 +283,7 @@ impl<S: LogStore> EngineInner<S> {
 
         let mut guard = SlotGuard::new(name, &self.regions);
 
-        let store_config = self.region_store_config(&opts.parent_dir, name);
+        let store_config = self.region_store_config(&opts.parent_dir, opts.ttl, name);
 
         let region = match RegionImpl::open(name.to_string(), store_config, opts).await? {
             None => return Ok(None),
@@ -289,7 +313,7 @@ impl<S: LogStore> EngineInner<S> {
                 .context(error::InvalidRegionDescSnafu {
                     region: &region_name,
                 })?;
-        let store_config = self.region_store_config(&opts.parent_dir, &region_name);
+        let store_config = self.region_store_config(&opts.parent_dir, opts.ttl, &region_name);
 
         let region = RegionImpl::create(metadata, store_config).await?;
 
@@ -300,18 +324,56 @@ impl<S: LogStore> EngineInner<S> {
         Ok(region)
     }
 
+    async fn close_region(&self, region: RegionImpl<S>) -> Result<()> {
+        self.remove_ready_region(region.name());
+
+        region.close().await?;
+
+        info!("Storage engine close region {}", region.id());
+
+        Ok(())
+    }
+
+    async fn drop_region(&self, region: RegionImpl<S>) -> Result<()> {
+        self.remove_ready_region(region.name());
+
+        region.drop_region().await?;
+
+        info!("Storage engine drop region {}", region.id());
+
+        Ok(())
+    }
+
     fn get_region(&self, name: &str) -> Option<RegionImpl<S>> {
         let slot = self.regions.read().unwrap().get(name).cloned()?;
         slot.get_ready_region()
     }
 
-    fn region_store_config(&self, parent_dir: &str, region_name: &str) -> StoreConfig<S> {
+    /// Removes the region with given `name` from the region map if it is ready, so
+    /// nobody could get the region from the engine anymore.
+    fn remove_ready_region(&self, name: &str) {
+        let mut regions = self.regions.write().unwrap();
+        if let Some(RegionSlot::Ready(_)) = regions.get(name) {
+            regions.remove(name);
+        }
+    }
+
+    fn region_store_config(
+        &self,
+        parent_dir: &str,
+        ttl: Option<Duration>,
+        region_name: &str,
+    ) -> StoreConfig<S> {
         let parent_dir = util::normalize_dir(parent_dir);
 
         let sst_dir = &region_sst_dir(&parent_dir, region_name);
-        let sst_layer = Arc::new(FsAccessLayer::new(sst_dir, self.object_store.clone()));
+        let fs_layer = FsAccessLayer::new(sst_dir, self.object_store.clone());
+        let sst_layer: AccessLayerRef = match &self.read_cache {
+            Some(cache) => Arc::new(CachedAccessLayer::new(fs_layer, cache.clone())),
+            None => Arc::new(fs_layer),
+        };
         let manifest_dir = region_manifest_dir(&parent_dir, region_name);
-        let manifest = RegionManifest::new(&manifest_dir, self.object_store.clone());
+        let manifest = RegionManifest::with_checkpointer(&manifest_dir, self.object_store.clone());
 
         StoreConfig {
             log_store: self.log_store.clone(),
@@ -320,6 +382,10 @@ impl<S: LogStore> EngineInner<S> {
             memtable_builder: self.memtable_builder.clone(),
             flush_scheduler: self.flush_scheduler.clone(),
             flush_strategy: self.flush_strategy.clone(),
+            compaction_picker: self.compaction_picker.clone(),
+            compaction_scheduler: self.compaction_scheduler.clone(),
+            ttl,
+            ttl_check_interval: self.ttl_check_interval,
         }
     }
 }
@@ -329,7 +395,6 @@ mod tests {
     use datatypes::type_id::LogicalTypeId;
     use log_store::test_util::log_store_util;
     use object_store::backend::fs::Builder;
-    use store_api::storage::Region;
     use tempdir::TempDir;
 
     use super::*;
@@ -367,4 +432,40 @@ mod tests {
 
         assert!(engine.get_region(&ctx, "no such region").unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_drop_region() {
+        let (log_store, _tmp) =
+            log_store_util::create_tmp_local_file_log_store("test_engine_wal").await;
+        let dir = TempDir::new("test_drop_region").unwrap();
+        let store_dir = dir.path().to_string_lossy();
+
+        let accessor = Builder::default().root(&store_dir).build().unwrap();
+        let object_store = ObjectStore::new(accessor);
+
+        let config = EngineConfig::default();
+
+        let engine = EngineImpl::new(config, Arc::new(log_store), object_store);
+
+        let region_name = "region-0";
+        let desc = RegionDescBuilder::new(region_name)
+            .push_key_column(("k1", LogicalTypeId::Int32, false))
+            .push_value_column(("v1", LogicalTypeId::Float32, true))
+            .build();
+        let ctx = EngineContext::default();
+        let region = engine
+            .create_region(&ctx, desc, &CreateOptions::default())
+            .await
+            .unwrap();
+
+        engine.drop_region(&ctx, region).await.unwrap();
+        assert!(engine.get_region(&ctx, region_name).unwrap().is_none());
+
+        // The region is removed, so it can't be opened again.
+        let region = engine
+            .open_region(&ctx, region_name, &OpenOptions::default())
+            .await
+            .unwrap();
+        assert!(region.is_none());
+    }
 }
@@ -15,6 +15,7 @@
 use std::any::Any;
 use std::io::Error as IoError;
 use std::str::Utf8Error;
+use std::sync::Arc;
 
 use common_error::prelude::*;
 use datatypes::arrow;
@@ -197,6 +198,34 @@ pub enum Error {
         backtrace: Backtrace,
     },
 
+    #[snafu(display("Region {} is closed, cannot proceed operation", region))]
+    ClosedRegion {
+        region: String,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display(
+        "Failed to delete WAL namespace, region_id: {}, source: {}",
+        region_id,
+        source
+    ))]
+    DeleteWalNamespace {
+        region_id: RegionId,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
+    #[snafu(display(
+        "Failed to mark WAL obsolete, region_id: {}, source: {}",
+        region_id,
+        source
+    ))]
+    MarkWalObsolete {
+        region_id: RegionId,
+        #[snafu(backtrace)]
+        source: BoxedError,
+    },
+
     #[snafu(display("Failed to read WAL, region_id: {}, source: {}", region_id, source))]
     ReadWal {
         region_id: RegionId,
@@ -350,6 +379,42 @@ pub enum Error {
         #[snafu(backtrace)]
         source: datatypes::error::Error,
     },
+
+    #[snafu(display("Failed to init read cache in dir: {}, source: {}", dir, source))]
+    InitReadCache {
+        dir: String,
+        source: object_store::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Failed to clean read cache in dir: {}, source: {}", dir, source))]
+    CleanReadCache {
+        dir: String,
+        source: std::io::Error,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Read is cancelled, source: {}", source))]
+    ReadCancelled {
+        #[snafu(backtrace)]
+        source: common_query::error::Error,
+    },
+
+    #[snafu(display(
+        "Failed to commit write group of region {}, source: {}",
+        region,
+        source
+    ))]
+    GroupCommit { region: String, source: Arc<Error> },
+
+    #[snafu(display("Background job failed, source: {}", source))]
+    BackgroundJob { source: Arc<Error> },
+
+    #[snafu(display("Write to region {} is cancelled", region))]
+    WriteCancelled {
+        region: String,
+        backtrace: Backtrace,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -401,6 +466,11 @@ impl ErrorExt for Error {
             | ReadParquet { .. }
             | ReadParquetIo { .. }
             | InvalidRegionState { .. }
+            | ClosedRegion { .. }
+            | DeleteWalNamespace { .. }
+            | MarkWalObsolete { .. }
+            | InitReadCache { .. }
+            | CleanReadCache { .. }
             | ReadWal { .. } => StatusCode::StorageUnavailable,
 
             InvalidAlterRequest { source, .. }
@@ -409,6 +479,10 @@ impl ErrorExt for Error {
             PushBatch { source, .. } => source.status_code(),
             AddDefault { source, .. } => source.status_code(),
             ConvertChunk { source, .. } => source.status_code(),
+            ReadCancelled { source } => source.status_code(),
+            GroupCommit { source, .. } => source.status_code(),
+            BackgroundJob { source } => source.status_code(),
+            WriteCancelled { .. } => StatusCode::Cancelled,
         }
     }
 
@@ -13,21 +13,24 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Instant;
 
 use async_trait::async_trait;
 use common_telemetry::logging;
+use metrics::{counter, histogram, increment_counter};
 use store_api::logstore::LogStore;
 use store_api::storage::consts::WRITE_ROW_GROUP_SIZE;
 use store_api::storage::SequenceNumber;
-use uuid::Uuid;
 
 use crate::background::{Context, Job, JobHandle, JobPoolRef};
+use crate::compaction::{CompactionJob, CompactionPickerRef, CompactionSchedulerRef};
 use crate::error::{CancelledSnafu, Result};
 use crate::manifest::action::*;
 use crate::manifest::region::RegionManifest;
 use crate::memtable::{IterContext, MemtableId, MemtableRef};
+use crate::metric::{LABEL_REGION, METRIC_FLUSH_BYTES, METRIC_FLUSH_COUNT, METRIC_FLUSH_ELAPSED};
 use crate::region::{RegionWriterRef, SharedDataRef};
-use crate::sst::{AccessLayerRef, FileMeta, WriteOptions};
+use crate::sst::{self, AccessLayerRef, FileMeta, Source, WriteOptions};
 use crate::wal::Wal;
 
 /// Default write buffer size (32M).
@@ -160,6 +163,10 @@ pub struct FlushJob<S: LogStore> {
     pub wal: Wal<S>,
     /// Region manifest service, used to persist metadata.
     pub manifest: RegionManifest,
+    /// Picker to pick files to compact after flush.
+    pub compaction_picker: CompactionPickerRef,
+    /// Scheduler to schedule the compaction job.
+    pub compaction_scheduler: CompactionSchedulerRef,
 }
 
 impl<S: LogStore> FlushJob<S> {
@@ -181,18 +188,16 @@ impl<S: LogStore> FlushJob<S> {
                 continue;
             }
 
-            let file_name = Self::generate_sst_file_name();
+            let file_name = sst::generate_sst_file_name();
             // TODO(hl): Check if random file name already exists in meta.
             let iter = m.iter(&iter_ctx)?;
             futures.push(async move {
-                self.sst_layer
-                    .write_sst(&file_name, iter, &WriteOptions::default())
+                let sst_info = self
+                    .sst_layer
+                    .write_sst(&file_name, Source::Iter(iter), &WriteOptions::default())
                     .await?;
 
-                Ok(FileMeta {
-                    file_name,
-                    level: 0,
-                })
+                Ok(FileMeta::with_sst_info(file_name, 0, sst_info))
             });
         }
 
@@ -221,14 +226,54 @@ impl<S: LogStore> FlushJob<S> {
                 &self.shared,
                 &self.manifest,
                 edit,
-                self.max_memtable_id,
+                Some(self.max_memtable_id),
             )
             .await
     }
 
-    /// Generates random SST file name in format: `^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$`
-    fn generate_sst_file_name() -> String {
-        format!("{}.parquet", Uuid::new_v4().hyphenated())
+    /// Schedules a compaction job if the picker finds files to compact.
+    async fn schedule_compaction(&self) -> Result<()> {
+        let version = self.shared.version_control.current();
+        let input = match self.writer.pick_compaction(|| {
+            self.compaction_picker
+                .pick(version.ssts(), self.shared.expire_before())
+        }) {
+            Some(input) => input,
+            None => return Ok(()),
+        };
+
+        logging::info!(
+            "Schedule compaction for region: {}, output_level: {}, inputs: {:?}, expired: {:?}",
+            self.shared.name(),
+            input.output_level,
+            input
+                .inputs
+                .iter()
+                .map(|f| f.file_name())
+                .collect::<Vec<_>>(),
+            input
+                .expired
+                .iter()
+                .map(|f| f.file_name())
+                .collect::<Vec<_>>(),
+        );
+
+        let compaction_job = CompactionJob {
+            input,
+            shared: self.shared.clone(),
+            sst_layer: self.sst_layer.clone(),
+            writer: self.writer.clone(),
+            wal: self.wal.clone(),
+            manifest: self.manifest.clone(),
+        };
+
+        let handle = self
+            .compaction_scheduler
+            .schedule_compaction(Box::new(compaction_job))
+            .await?;
+        self.writer.set_compaction_handle(handle);
+
+        Ok(())
     }
 }
 
@@ -236,19 +281,34 @@ impl<S: LogStore> FlushJob<S> {
 impl<S: LogStore> Job for FlushJob<S> {
     // TODO(yingwen): [flush] Support in-job parallelism (Flush memtables concurrently)
     async fn run(&mut self, ctx: &Context) -> Result<()> {
+        let start = Instant::now();
         let file_metas = self.write_memtables_to_layer(ctx).await?;
 
         self.write_manifest_and_apply(&file_metas).await?;
 
+        let region = self.shared.id().to_string();
+        let bytes: u64 = file_metas.iter().map(|meta| meta.file_size).sum();
+        increment_counter!(METRIC_FLUSH_COUNT, LABEL_REGION => region.clone());
+        histogram!(METRIC_FLUSH_ELAPSED, start.elapsed(), LABEL_REGION => region.clone());
+        counter!(METRIC_FLUSH_BYTES, bytes, LABEL_REGION => region);
+
+        // Now the flushed sequence is persisted in the manifest, failing to mark the WAL obsolete
+        // only delays purging the log files.
+        if let Err(e) = self.wal.obsolete(self.flush_sequence).await {
+            logging::error!(e; "Failed to mark WAL obsolete, region: {}", self.shared.name());
+        }
+
+        // The flush is already done, failing to schedule compaction won't fail the flush job.
+        if let Err(e) = self.schedule_compaction().await {
+            logging::error!(e; "Failed to schedule compaction, region: {}", self.shared.name());
+        }
+
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use log_store::fs::noop::NoopLogStore;
-    use regex::Regex;
-
     use super::*;
 
     #[test]
@@ -257,15 +317,4 @@ mod tests {
         assert_eq!(8, get_mutable_limitation(10));
         assert_eq!(56, get_mutable_limitation(64));
     }
-
-    #[test]
-    pub fn test_uuid_generate() {
-        let file_name = FlushJob::<NoopLogStore>::generate_sst_file_name();
-        let regex = Regex::new(r"^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$").unwrap();
-        assert!(
-            regex.is_match(&file_name),
-            "illegal sst file name: {}",
-            file_name
-        );
-    }
 }
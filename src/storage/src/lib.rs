@@ -17,6 +17,7 @@
 mod background;
 mod chunk;
 pub mod codec;
+mod compaction;
 pub mod config;
 mod engine;
 pub mod error;
@@ -24,6 +25,7 @@ mod flush;
 pub mod manifest;
 pub mod memtable;
 pub mod metadata;
+mod metric;
 pub mod proto;
 pub mod read;
 pub mod region;
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::io::{BufRead, BufReader};
 
 use serde::{Deserialize, Serialize};
@@ -22,8 +23,8 @@ use store_api::manifest::{ManifestVersion, MetaAction};
 use store_api::storage::{RegionId, SequenceNumber};
 
 use crate::error::{
-    self, DecodeJsonSnafu, DecodeMetaActionListSnafu, ManifestProtocolForbidReadSnafu,
-    ReadlineSnafu, Result,
+    self, DecodeJsonSnafu, DecodeMetaActionListSnafu, EncodeJsonSnafu,
+    ManifestProtocolForbidReadSnafu, ReadlineSnafu, Result,
 };
 use crate::manifest::helper;
 use crate::metadata::{ColumnFamilyMetadata, ColumnMetadata, VersionNumber};
@@ -108,6 +109,99 @@ impl RegionMetaActionList {
     }
 }
 
+/// Snapshot of the region manifest, which is the result of applying actions in the
+/// manifest until a specific version.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct RegionManifestData {
+    /// The metadata change to build the region and its manifest version, which is the
+    /// latest change committed before the flushed sequence. `None` if the region has
+    /// no metadata yet.
+    pub change: Option<(ManifestVersion, RegionChange)>,
+    /// Metadata changes that still need to replay the WAL.
+    pub pending_changes: Vec<(ManifestVersion, RegionChange)>,
+    pub flushed_sequence: SequenceNumber,
+    /// Manifest version of the region version.
+    pub manifest_version: ManifestVersion,
+    /// SST files of the region.
+    pub files: Vec<FileMeta>,
+}
+
+impl RegionManifestData {
+    /// Applies the `action` of manifest `version` to the snapshot.
+    pub fn apply(&mut self, version: ManifestVersion, action: RegionMetaAction) {
+        match action {
+            RegionMetaAction::Change(c) => {
+                if self.change.is_none() {
+                    self.manifest_version = self.manifest_version.max(version);
+                    self.change = Some((version, c));
+                } else {
+                    self.pending_changes.push((version, c));
+                }
+            }
+            RegionMetaAction::Edit(e) => {
+                self.flushed_sequence = self.flushed_sequence.max(e.flushed_sequence);
+                self.manifest_version = self.manifest_version.max(version);
+
+                self.files.extend(e.files_to_add);
+                let files_to_remove: HashSet<_> =
+                    e.files_to_remove.iter().map(|f| &f.file_name).collect();
+                self.files
+                    .retain(|f| !files_to_remove.contains(&f.file_name));
+            }
+            RegionMetaAction::Protocol(_) | RegionMetaAction::Remove(_) => (),
+        }
+    }
+
+    /// Removes metadata changes that are no longer needed to recover the region.
+    ///
+    /// Only the latest change committed before the flushed sequence is required, as
+    /// data written before it has been flushed.
+    pub fn compact(&mut self) {
+        let flushed_sequence = self.flushed_sequence;
+        // Changes are pushed in the order of their committed sequence.
+        let num_flushed = self
+            .pending_changes
+            .partition_point(|(_, c)| c.committed_sequence <= flushed_sequence);
+
+        if let Some((version, change)) = self.pending_changes.drain(..num_flushed).last() {
+            self.manifest_version = self.manifest_version.max(version);
+            self.change = Some((version, change));
+        }
+    }
+}
+
+/// Checkpoint of the region manifest.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct RegionCheckpoint {
+    /// Protocol of the manifest when the checkpoint is made.
+    pub protocol: ProtocolAction,
+    /// Version of the last action compacted into this checkpoint.
+    pub last_version: ManifestVersion,
+    pub data: RegionManifestData,
+}
+
+impl RegionCheckpoint {
+    pub fn encode(&self) -> Result<Vec<u8>> {
+        json::to_vec(self).context(EncodeJsonSnafu)
+    }
+
+    /// Decode the checkpoint from bytes, returns error if the checkpoint is not readable
+    /// by `reader_version`.
+    pub fn decode(bs: &[u8], reader_version: ProtocolVersion) -> Result<Self> {
+        let checkpoint: RegionCheckpoint = json::from_slice(bs).context(DecodeJsonSnafu)?;
+
+        ensure!(
+            checkpoint.protocol.is_readable(reader_version),
+            ManifestProtocolForbidReadSnafu {
+                min_version: checkpoint.protocol.min_reader_version,
+                supported_version: reader_version,
+            }
+        );
+
+        Ok(checkpoint)
+    }
+}
+
 impl MetaAction for RegionMetaActionList {
     type Error = error::Error;
 
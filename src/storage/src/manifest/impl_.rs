@@ -23,10 +23,22 @@ use object_store::ObjectStore;
 use snafu::ensure;
 use store_api::manifest::action::{self, ProtocolAction, ProtocolVersion};
 use store_api::manifest::*;
+use tokio::sync::Mutex;
 
 use crate::error::{Error, ManifestProtocolForbidWriteSnafu, Result};
 use crate::manifest::storage::{ManifestObjectStore, ObjectStoreLogIterator};
 
+/// Compacts actions of a manifest into a checkpoint.
+#[async_trait]
+pub trait Checkpointer<M: MetaAction<Error = Error>>: Send + Sync + std::fmt::Debug {
+    /// Saves a new checkpoint of the `manifest` and deletes the compacted action files,
+    /// returns the version of the last compacted action, or `None` if there is nothing
+    /// to compact.
+    async fn do_checkpoint(&self, manifest: &ManifestImpl<M>) -> Result<Option<ManifestVersion>>;
+}
+
+pub type CheckpointerRef<M> = Arc<dyn Checkpointer<M>>;
+
 #[derive(Clone, Debug)]
 pub struct ManifestImpl<M: MetaAction<Error = Error>> {
     inner: Arc<ManifestImplInner<M>>,
@@ -35,7 +47,31 @@ pub struct ManifestImpl<M: MetaAction<Error = Error>> {
 impl<M: MetaAction<Error = Error>> ManifestImpl<M> {
     pub fn new(manifest_dir: &str, object_store: ObjectStore) -> Self {
         ManifestImpl {
-            inner: Arc::new(ManifestImplInner::new(manifest_dir, object_store)),
+            inner: Arc::new(ManifestImplInner::new(manifest_dir, object_store, None)),
+        }
+    }
+
+    /// Create a manifest that makes a checkpoint by `checkpointer` every
+    /// `checkpoint_margin` actions.
+    pub fn with_checkpointer(
+        manifest_dir: &str,
+        object_store: ObjectStore,
+        checkpointer: CheckpointerRef<M>,
+        checkpoint_margin: u64,
+    ) -> Self {
+        let checkpoint = CheckpointState {
+            checkpointer,
+            margin: checkpoint_margin,
+            last_version: AtomicU64::new(0),
+            lock: Mutex::new(()),
+        };
+
+        ManifestImpl {
+            inner: Arc::new(ManifestImplInner::new(
+                manifest_dir,
+                object_store,
+                Some(checkpoint),
+            )),
         }
     }
 
@@ -43,6 +79,37 @@ impl<M: MetaAction<Error = Error>> ManifestImpl<M> {
     pub fn update_state(&self, version: ManifestVersion, protocol: Option<ProtocolAction>) {
         self.inner.update_state(version, protocol);
     }
+
+    /// Delete all persisted files of this manifest.
+    pub async fn delete_all(&self) -> Result<()> {
+        self.inner.store.delete_all().await
+    }
+
+    #[inline]
+    pub(crate) fn store(&self) -> &ManifestObjectStore {
+        &self.inner.store
+    }
+
+    async fn do_checkpoint(&self) -> Result<Option<ManifestVersion>> {
+        let checkpoint = match &self.inner.checkpoint {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(None),
+        };
+
+        // Avoid making checkpoints concurrently.
+        let _lock = checkpoint.lock.lock().await;
+        let last_version = checkpoint.checkpointer.do_checkpoint(self).await?;
+        if let Some(version) = last_version {
+            checkpoint.last_version.store(version, Ordering::Relaxed);
+            logging::debug!(
+                "Manifest checkpoint saved, path: {}, last_version: {}",
+                self.inner.store.path(),
+                version
+            );
+        }
+
+        Ok(last_version)
+    }
 }
 
 #[async_trait]
@@ -52,7 +119,18 @@ impl<M: 'static + MetaAction<Error = Error>> Manifest for ManifestImpl<M> {
     type MetaActionIterator = MetaActionIteratorImpl<M>;
 
     async fn update(&self, action_list: M) -> Result<ManifestVersion> {
-        self.inner.save(action_list).await
+        let version = self.inner.save(action_list).await?;
+
+        if self.inner.should_checkpoint(version) {
+            // The checkpoint would be retried after following updates, so we don't
+            // fail the update.
+            if let Err(e) = self.do_checkpoint().await {
+                let path = self.inner.store.path();
+                logging::error!(e; "Failed to do checkpoint for manifest in path: {}", path);
+            }
+        }
+
+        Ok(version)
     }
 
     async fn scan(
@@ -63,8 +141,8 @@ impl<M: 'static + MetaAction<Error = Error>> Manifest for ManifestImpl<M> {
         self.inner.scan(start, end).await
     }
 
-    async fn checkpoint(&self) -> Result<ManifestVersion> {
-        unimplemented!();
+    async fn checkpoint(&self) -> Result<Option<ManifestVersion>> {
+        self.do_checkpoint().await
     }
 
     fn last_version(&self) -> ManifestVersion {
@@ -81,9 +159,21 @@ struct ManifestImplInner<M: MetaAction<Error = Error>> {
     /// Current node supported protocols (reader_version, writer_version)
     supported_reader_version: ProtocolVersion,
     supported_writer_version: ProtocolVersion,
+    /// State of checkpoint, `None` if the manifest doesn't make checkpoints.
+    checkpoint: Option<CheckpointState<M>>,
     _phantom: PhantomData<M>,
 }
 
+#[derive(Debug)]
+struct CheckpointState<M: MetaAction<Error = Error>> {
+    checkpointer: CheckpointerRef<M>,
+    /// Number of actions between two checkpoints.
+    margin: u64,
+    /// Version of the last action compacted into the checkpoint.
+    last_version: AtomicU64,
+    lock: Mutex<()>,
+}
+
 pub struct MetaActionIteratorImpl<M: MetaAction<Error = Error>> {
     log_iter: ObjectStoreLogIterator,
     reader_version: ProtocolVersion,
@@ -119,7 +209,11 @@ impl<M: MetaAction<Error = Error>> MetaActionIterator for MetaActionIteratorImpl
 }
 
 impl<M: MetaAction<Error = Error>> ManifestImplInner<M> {
-    fn new(manifest_dir: &str, object_store: ObjectStore) -> Self {
+    fn new(
+        manifest_dir: &str,
+        object_store: ObjectStore,
+        checkpoint: Option<CheckpointState<M>>,
+    ) -> Self {
         let (reader_version, writer_version) = action::supported_protocol_version();
 
         Self {
@@ -128,10 +222,21 @@ impl<M: MetaAction<Error = Error>> ManifestImplInner<M> {
             protocol: ArcSwap::new(Arc::new(ProtocolAction::new())),
             supported_reader_version: reader_version,
             supported_writer_version: writer_version,
+            checkpoint,
             _phantom: PhantomData,
         }
     }
 
+    fn should_checkpoint(&self, version: ManifestVersion) -> bool {
+        match &self.checkpoint {
+            Some(checkpoint) => {
+                version.saturating_sub(checkpoint.last_version.load(Ordering::Relaxed))
+                    >= checkpoint.margin
+            }
+            None => false,
+        }
+    }
+
     #[inline]
     fn inc_version(&self) -> ManifestVersion {
         self.version.fetch_add(1, Ordering::Relaxed)
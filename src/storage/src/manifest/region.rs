@@ -13,11 +13,124 @@
 // limitations under the License.
 
 //! Region manifest impl
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use object_store::ObjectStore;
+use store_api::manifest::action::{self, ProtocolAction};
+use store_api::manifest::{
+    Manifest, ManifestLogStorage, ManifestVersion, MetaActionIterator, MIN_VERSION,
+};
+
+use crate::error::Result;
 use crate::manifest::action::*;
-use crate::manifest::ManifestImpl;
+use crate::manifest::{Checkpointer, ManifestImpl};
+
+/// Default number of actions between two checkpoints of the region manifest.
+const DEFAULT_CHECKPOINT_MARGIN: u64 = 10;
 
 pub type RegionManifest = ManifestImpl<RegionMetaActionList>;
 
+impl RegionManifest {
+    /// Create a region manifest that makes checkpoints periodically.
+    pub fn with_checkpointer(manifest_dir: &str, object_store: ObjectStore) -> Self {
+        Self::with_checkpointer_margin(manifest_dir, object_store, DEFAULT_CHECKPOINT_MARGIN)
+    }
+
+    pub fn with_checkpointer_margin(
+        manifest_dir: &str,
+        object_store: ObjectStore,
+        checkpoint_margin: u64,
+    ) -> Self {
+        ManifestImpl::with_checkpointer(
+            manifest_dir,
+            object_store,
+            Arc::new(RegionManifestCheckpointer),
+            checkpoint_margin,
+        )
+    }
+
+    /// Load the latest checkpoint of the region manifest.
+    pub async fn last_checkpoint(&self) -> Result<Option<RegionCheckpoint>> {
+        match self.store().load_checkpoint().await? {
+            Some((_, bytes)) => {
+                let (reader_version, _) = action::supported_protocol_version();
+                RegionCheckpoint::decode(&bytes, reader_version).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct RegionManifestCheckpointer;
+
+#[async_trait]
+impl Checkpointer<RegionMetaActionList> for RegionManifestCheckpointer {
+    async fn do_checkpoint(&self, manifest: &RegionManifest) -> Result<Option<ManifestVersion>> {
+        let last_checkpoint = manifest.last_checkpoint().await?;
+        let prev_checkpoint_version = last_checkpoint.as_ref().map(|c| c.last_version);
+        let (start, mut protocol, mut data) = match last_checkpoint {
+            Some(c) => (c.last_version + 1, c.protocol, c.data),
+            None => (
+                MIN_VERSION,
+                ProtocolAction::new(),
+                RegionManifestData::default(),
+            ),
+        };
+        let end = manifest.last_version();
+        if start >= end {
+            return Ok(None);
+        }
+
+        let mut iter = manifest.scan(start, end).await?;
+        let mut last_version = None;
+        while let Some((version, action_list)) = iter.next_action().await? {
+            // Stop at the first missing version as it might be still writing.
+            let expect_version = last_version.map(|v| v + 1).unwrap_or(start);
+            if version != expect_version {
+                break;
+            }
+            last_version = Some(version);
+
+            for action in action_list.actions {
+                if let RegionMetaAction::Protocol(p) = &action {
+                    protocol = p.clone();
+                }
+                data.apply(version, action);
+            }
+        }
+
+        let last_version = match last_version {
+            Some(v) => v,
+            None => return Ok(None),
+        };
+        if data.change.is_none() {
+            // The region is not created yet.
+            return Ok(None);
+        }
+        data.compact();
+
+        let checkpoint = RegionCheckpoint {
+            protocol,
+            last_version,
+            data,
+        };
+        let store = manifest.store();
+        store
+            .save_checkpoint(last_version, &checkpoint.encode()?)
+            .await?;
+
+        // Actions and the previous checkpoint are useless after the new checkpoint saved.
+        store.delete(start, last_version + 1).await?;
+        if let Some(version) = prev_checkpoint_version {
+            store.delete_checkpoint(version).await?;
+        }
+
+        Ok(Some(last_version))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -130,4 +243,95 @@ mod tests {
         // Reach end
         assert!(iter.next_action().await.unwrap().is_none());
     }
+
+    #[tokio::test]
+    async fn test_region_manifest_checkpoint() {
+        common_telemetry::init_default_ut_logging();
+        let tmp_dir = TempDir::new("test_region_manifest_checkpoint").unwrap();
+        let object_store = ObjectStore::new(
+            fs::Builder::default()
+                .root(&tmp_dir.path().to_string_lossy())
+                .build()
+                .unwrap(),
+        );
+
+        // Make a checkpoint every 3 actions.
+        let manifest =
+            RegionManifest::with_checkpointer_margin("/manifest/", object_store.clone(), 3);
+
+        let region_meta = Arc::new(build_region_meta());
+        let change = RegionChange {
+            metadata: region_meta.as_ref().into(),
+            committed_sequence: 0,
+        };
+        let mut altered = change.clone();
+        altered.metadata.version = 1;
+        altered.committed_sequence = 5;
+
+        for action in [
+            RegionMetaAction::Change(change.clone()),
+            RegionMetaAction::Edit(build_region_edit(1, &["f1", "f2"], &[])),
+            RegionMetaAction::Change(altered.clone()),
+        ] {
+            manifest
+                .update(RegionMetaActionList::with_action(action))
+                .await
+                .unwrap();
+        }
+        assert!(manifest.last_checkpoint().await.unwrap().is_none());
+
+        // The 4th action triggers a checkpoint.
+        manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Edit(
+                build_region_edit(3, &["f3"], &["f1"]),
+            )))
+            .await
+            .unwrap();
+        let checkpoint = manifest.last_checkpoint().await.unwrap().unwrap();
+        assert_eq!(3, checkpoint.last_version);
+        assert_eq!(ProtocolAction::new(), checkpoint.protocol);
+        let data = checkpoint.data;
+        assert_eq!(Some((0, change)), data.change);
+        assert_eq!(vec![(2, altered.clone())], data.pending_changes);
+        assert_eq!(3, data.flushed_sequence);
+        assert_eq!(3, data.manifest_version);
+        let files: Vec<_> = data.files.iter().map(|f| f.file_name.as_str()).collect();
+        assert_eq!(["f2", "f3"], &files[..]);
+        // Compacted actions are deleted.
+        assert!(manifest
+            .scan(0, MAX_VERSION)
+            .await
+            .unwrap()
+            .next_action()
+            .await
+            .unwrap()
+            .is_none());
+
+        manifest
+            .update(RegionMetaActionList::with_action(RegionMetaAction::Edit(
+                build_region_edit(6, &[], &["f2"]),
+            )))
+            .await
+            .unwrap();
+        assert_eq!(Some(4), manifest.checkpoint().await.unwrap());
+        let checkpoint = manifest.last_checkpoint().await.unwrap().unwrap();
+        assert_eq!(4, checkpoint.last_version);
+        let data = checkpoint.data;
+        // The altered metadata is flushed.
+        assert_eq!(Some((2, altered)), data.change);
+        assert!(data.pending_changes.is_empty());
+        assert_eq!(6, data.flushed_sequence);
+        assert_eq!(4, data.manifest_version);
+        let files: Vec<_> = data.files.iter().map(|f| f.file_name.as_str()).collect();
+        assert_eq!(["f3"], &files[..]);
+        // The previous checkpoint is deleted.
+        assert!(!object_store
+            .object("/manifest/00000000000000000003.checkpoint")
+            .is_exist()
+            .await
+            .unwrap());
+
+        // Nothing to compact.
+        assert_eq!(None, manifest.checkpoint().await.unwrap());
+    }
 }
@@ -105,6 +105,45 @@ impl ManifestObjectStore {
     fn checkpoint_file_path(&self, version: ManifestVersion) -> String {
         format!("{}{}", self.path, checkpoint_file(version))
     }
+
+    #[inline]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Delete all files under the manifest directory, including deltas and checkpoints.
+    pub async fn delete_all(&self) -> Result<()> {
+        let dir = self.object_store.object(&self.path);
+        let dir_exists = dir
+            .is_exist()
+            .await
+            .context(ReadObjectSnafu { path: &self.path })?;
+        if !dir_exists {
+            return Ok(());
+        }
+
+        let objects: Vec<Object> = dir
+            .list()
+            .await
+            .context(ListObjectsSnafu { path: &self.path })?
+            .try_collect()
+            .await
+            .context(ListObjectsSnafu { path: &self.path })?;
+
+        for object in objects {
+            // Manifest directory should not contain any sub directory.
+            if object.path().ends_with('/') {
+                continue;
+            }
+            object.delete().await.context(DeleteObjectSnafu {
+                path: object.path(),
+            })?;
+        }
+
+        logging::debug!("Deleted all manifest files in path: {}", self.path);
+
+        Ok(())
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -273,6 +312,15 @@ impl ManifestLogStorage for ManifestObjectStore {
             Ok(None)
         }
     }
+
+    async fn delete_checkpoint(&self, version: ManifestVersion) -> Result<()> {
+        let object = self
+            .object_store
+            .object(&self.checkpoint_file_path(version));
+        object.delete().await.context(DeleteObjectSnafu {
+            path: object.path(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -341,5 +389,20 @@ mod tests {
         let (v, checkpoint) = log_store.load_checkpoint().await.unwrap().unwrap();
         assert_eq!(checkpoint, "checkpoint".as_bytes());
         assert_eq!(3, v);
+
+        log_store
+            .save_checkpoint(4, "checkpoint4".as_bytes())
+            .await
+            .unwrap();
+        log_store.delete_checkpoint(3).await.unwrap();
+        let (v, checkpoint) = log_store.load_checkpoint().await.unwrap().unwrap();
+        assert_eq!(checkpoint, "checkpoint4".as_bytes());
+        assert_eq!(4, v);
+
+        // test delete all
+        log_store.delete_all().await.unwrap();
+        let mut it = log_store.scan(0, 11).await.unwrap();
+        assert!(it.next_log().await.unwrap().is_none());
+        assert!(log_store.load_checkpoint().await.unwrap().is_none());
     }
 }
@@ -81,6 +81,12 @@ pub struct IterContext {
     ///
     /// Set to `None` to read all columns.
     pub projected_schema: Option<ProjectedSchemaRef>,
+
+    /// Returns rows in descending order of the key.
+    ///
+    /// Rows with the same row key are not deduplicated, the newest visible row of each row
+    /// key is the last one, so the caller needs to dedup them.
+    pub descending: bool,
 }
 
 impl Default for IterContext {
@@ -91,6 +97,7 @@ impl Default for IterContext {
             visible_sequence: SequenceNumber::MAX,
             for_flush: false,
             projected_schema: None,
+            descending: false,
         }
     }
 }
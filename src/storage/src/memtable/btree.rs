@@ -149,17 +149,30 @@ impl BTreeIterator {
 
     fn next_batch(&mut self) -> Result<Option<Batch>> {
         let map = self.map.read().unwrap();
-        let iter = if let Some(last_key) = &self.last_key {
-            map.range((Bound::Excluded(last_key), Bound::Unbounded))
-        } else {
-            map.range(..)
-        };
-
-        let (keys, sequences, op_types, values) = if self.ctx.for_flush {
+        let (keys, sequences, op_types, values) = if self.ctx.descending {
+            let iter = if let Some(last_key) = &self.last_key {
+                map.range((Bound::Unbounded, Bound::Excluded(last_key)))
+            } else {
+                map.range(..)
+            };
+            let visible_sequence = self.ctx.visible_sequence;
+            let iter = iter
+                .rev()
+                .filter(|(inner_key, _)| inner_key.is_visible(visible_sequence));
             collect_iter(iter, self.ctx.batch_size)
         } else {
-            let iter = MapIterWrapper::new(iter, self.ctx.visible_sequence);
-            collect_iter(iter, self.ctx.batch_size)
+            let iter = if let Some(last_key) = &self.last_key {
+                map.range((Bound::Excluded(last_key), Bound::Unbounded))
+            } else {
+                map.range(..)
+            };
+
+            if self.ctx.for_flush {
+                collect_iter(iter, self.ctx.batch_size)
+            } else {
+                let iter = MapIterWrapper::new(iter, self.ctx.visible_sequence);
+                collect_iter(iter, self.ctx.batch_size)
+            }
         };
 
         if keys.is_empty() {
@@ -167,7 +180,10 @@ impl BTreeIterator {
         }
         self.last_key = keys.last().map(|k| {
             let mut last_key = (*k).clone();
-            last_key.reset_for_seek();
+            // Rows of the same row key are not skipped in descending order.
+            if !self.ctx.descending {
+                last_key.reset_for_seek();
+            }
             last_key
         });
 
@@ -13,7 +13,7 @@
 // limitations under the License.
 
 use datatypes::vectors::VectorRef;
-use snafu::OptionExt;
+use snafu::{ensure, OptionExt};
 use store_api::storage::{ColumnDescriptor, OpType, SequenceNumber};
 
 use super::MemtableRef;
@@ -37,6 +37,26 @@ impl Inserter {
         }
     }
 
+    /// Checks whether the `batch` has all columns of the `memtable`, so inserting it into
+    /// the `memtable` won't fail.
+    pub fn validate(batch: &WriteBatch, memtable: &MemtableRef) -> Result<()> {
+        let schema = memtable.schema();
+        for mutation in batch {
+            let put_data = match mutation {
+                Mutation::Put(put_data) | Mutation::Delete(put_data) => put_data,
+            };
+            for column in schema.row_key_columns().chain(schema.value_columns()) {
+                ensure!(
+                    put_data.column_by_name(&column.desc.name).is_some(),
+                    error::BatchMissingColumnSnafu {
+                        column: &column.desc.name
+                    }
+                );
+            }
+        }
+        Ok(())
+    }
+
     // TODO(yingwen): Can we take the WriteBatch?
     /// Insert write batch into memtable.
     ///
@@ -64,7 +84,10 @@ impl Inserter {
         for mutation in batch {
             match mutation {
                 Mutation::Put(put_data) => {
-                    self.write_one_mutation(put_data, memtable, &mut kvs)?;
+                    self.write_one_mutation(OpType::Put, put_data, memtable, &mut kvs)?;
+                }
+                Mutation::Delete(put_data) => {
+                    self.write_one_mutation(OpType::Delete, put_data, memtable, &mut kvs)?;
                 }
             }
         }
@@ -74,6 +97,7 @@ impl Inserter {
 
     fn write_one_mutation(
         &mut self,
+        op_type: OpType,
         put_data: &PutData,
         memtable: &MemtableRef,
         kvs: &mut KeyValues,
@@ -81,7 +105,7 @@ impl Inserter {
         let schema = memtable.schema();
         let num_rows = put_data.num_rows();
 
-        kvs.reset(OpType::Put, self.index_in_batch);
+        kvs.reset(op_type, self.index_in_batch);
 
         for key_col in schema.row_key_columns() {
             clone_put_data_column_to(put_data, &key_col.desc, &mut kvs.keys)?;
@@ -135,6 +159,7 @@ struct SliceIndex {
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::sync::Arc;
 
     use common_time::timestamp::Timestamp;
@@ -229,6 +254,7 @@ mod tests {
             ],
         );
 
+        Inserter::validate(&batch, &mutable_memtable).unwrap();
         inserter.insert_memtable(&batch, &mutable_memtable).unwrap();
         check_memtable_content(
             &mutable_memtable,
@@ -244,4 +270,60 @@ mod tests {
             ],
         );
     }
+
+    #[test]
+    fn test_inserter_validate() {
+        let memtable_schema = new_region_schema();
+        let mutable_memtable = DefaultMemtableBuilder::default().build(memtable_schema);
+
+        // The batch lacks the value column of the memtable.
+        let mut batch =
+            write_batch_util::new_write_batch(&[("ts", LogicalTypeId::Timestamp, false)], Some(0));
+        let mut put_data = PutData::with_num_columns(1);
+        let ts = TimestampVector::from_values([1, 2]);
+        put_data.add_key_column("ts", Arc::new(ts)).unwrap();
+        batch.put(put_data).unwrap();
+
+        assert!(matches!(
+            Inserter::validate(&batch, &mutable_memtable),
+            Err(error::Error::BatchMissingColumn { .. })
+        ));
+        let mut inserter = Inserter::new(1);
+        assert!(inserter.insert_memtable(&batch, &mutable_memtable).is_err());
+    }
+
+    #[test]
+    fn test_inserter_delete() {
+        let sequence = 11111;
+        let memtable_schema = new_region_schema();
+        let mutable_memtable = DefaultMemtableBuilder::default().build(memtable_schema);
+        let mut inserter = Inserter::new(sequence);
+
+        let mut batch = new_test_write_batch();
+        put_batch(&mut batch, &[(1, Some(1)), (2, Some(2))]);
+        let mut keys = HashMap::with_capacity(1);
+        let ts: VectorRef = Arc::new(TimestampVector::from_values([2, 3]));
+        keys.insert("ts".to_string(), ts);
+        batch.delete(keys).unwrap();
+
+        inserter.insert_memtable(&batch, &mutable_memtable).unwrap();
+
+        let iter = mutable_memtable.iter(&IterContext::default()).unwrap();
+        let mut rows = Vec::new();
+        for batch in iter {
+            let batch = batch.unwrap();
+            for i in 0..batch.num_rows() {
+                rows.push((batch.column(0).get(i), batch.column(3).get(i)));
+            }
+        }
+        // Deleted rows are still in the memtable as tombstones.
+        let expect =
+            [(1, OpType::Put), (2, OpType::Delete), (3, OpType::Delete)].map(|(ts, op_type)| {
+                (
+                    Value::Timestamp(Timestamp::from_millis(ts)),
+                    Value::from(op_type.as_u8()),
+                )
+            });
+        assert_eq!(&expect, &rows[..]);
+    }
 }
@@ -471,6 +471,7 @@ fn test_sequence_visibility() {
                 visible_sequence: 9,
                 for_flush: false,
                 projected_schema: None,
+                descending: false,
             };
 
             let mut iter = ctx.memtable.iter(&iter_ctx).unwrap();
@@ -489,6 +490,7 @@ fn test_sequence_visibility() {
                 visible_sequence: 10,
                 for_flush: false,
                 projected_schema: None,
+                descending: false,
             };
 
             let mut iter = ctx.memtable.iter(&iter_ctx).unwrap();
@@ -507,6 +509,7 @@ fn test_sequence_visibility() {
                 visible_sequence: 11,
                 for_flush: false,
                 projected_schema: None,
+                descending: false,
             };
 
             let mut iter = ctx.memtable.iter(&iter_ctx).unwrap();
@@ -518,6 +521,30 @@ fn test_sequence_visibility() {
                 &[(Some(11), None), (Some(12), None)], // values
             );
         }
+
+        {
+            // All visible rows are returned in descending order.
+            let iter_ctx = IterContext {
+                batch_size: 1,
+                visible_sequence: 11,
+                descending: true,
+                ..Default::default()
+            };
+
+            let mut iter = ctx.memtable.iter(&iter_ctx).unwrap();
+            check_iter_content(
+                &mut *iter,
+                &[(1000, 2), (1000, 2), (1000, 1), (1000, 1)], // keys
+                &[10, 11, 10, 11],                             // sequences
+                &[OpType::Put; 4],                             // op_types
+                &[
+                    (Some(2), None),
+                    (Some(12), None),
+                    (Some(1), None),
+                    (Some(11), None),
+                ], // values
+            );
+        }
     });
 }
 
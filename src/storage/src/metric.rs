@@ -0,0 +1,54 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! storage metrics
+
+/// Counter of reads that hit the SST read cache.
+pub const METRIC_SST_READ_CACHE_HIT: &str = "storage.sst_read_cache.hit";
+/// Counter of reads that miss the SST read cache.
+pub const METRIC_SST_READ_CACHE_MISS: &str = "storage.sst_read_cache.miss";
+/// Counter of files evicted from the SST read cache.
+pub const METRIC_SST_READ_CACHE_EVICT: &str = "storage.sst_read_cache.evict";
+/// Gauge of total bytes of files in the SST read cache.
+pub const METRIC_SST_READ_CACHE_BYTES: &str = "storage.sst_read_cache.bytes";
+
+/// Label of the region id.
+pub const LABEL_REGION: &str = "region";
+/// Label of the SST level.
+pub const LABEL_LEVEL: &str = "level";
+
+/// Histogram of elapsed time to write a batch to the region.
+pub const METRIC_WRITE_ELAPSED: &str = "storage.write.elapsed";
+/// Counter of rows written to the region.
+pub const METRIC_WRITE_ROWS: &str = "storage.write.rows";
+/// Counter of bytes of entries appended to the WAL of the region.
+pub const METRIC_WAL_WRITE_BYTES: &str = "storage.wal.write_bytes";
+/// Gauge of bytes allocated by the mutable memtable of the region.
+pub const METRIC_MEMTABLE_MUTABLE_BYTES: &str = "storage.memtable.mutable_bytes";
+/// Gauge of bytes allocated by the immutable memtables of the region.
+pub const METRIC_MEMTABLE_IMMUTABLE_BYTES: &str = "storage.memtable.immutable_bytes";
+/// Counter of flushes finished.
+pub const METRIC_FLUSH_COUNT: &str = "storage.flush.count";
+/// Histogram of elapsed time to flush memtables.
+pub const METRIC_FLUSH_ELAPSED: &str = "storage.flush.elapsed";
+/// Counter of bytes of SSTs written by flushes.
+pub const METRIC_FLUSH_BYTES: &str = "storage.flush.bytes";
+/// Gauge of number of SSTs in each level of the region.
+pub const METRIC_SST_FILES: &str = "storage.sst.files";
+/// Counter of row groups pruned by the predicate while reading SSTs.
+pub const METRIC_SST_PRUNED_ROW_GROUPS: &str = "storage.sst.pruned_row_groups";
+/// Counter of rows returned by scans.
+pub const METRIC_SCAN_ROWS: &str = "storage.scan.rows";
+/// Counter of bytes of rows returned by scans.
+pub const METRIC_SCAN_BYTES: &str = "storage.scan.bytes";
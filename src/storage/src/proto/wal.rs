@@ -22,6 +22,7 @@ pub fn gen_mutation_types(write_batch: &WriteBatch) -> Vec<i32> {
         .iter()
         .map(|m| match m {
             Mutation::Put(_) => MutationType::Put.into(),
+            Mutation::Delete(_) => MutationType::Delete.into(),
         })
         .collect::<Vec<_>>()
 }
@@ -15,7 +15,9 @@
 //! Common structs and utilities for read.
 
 mod dedup;
+mod filter;
 mod merge;
+mod ttl;
 
 use std::cmp::Ordering;
 
@@ -25,8 +27,10 @@ use datatypes::data_type::DataType;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::vectors::{BooleanVector, MutableVector, VectorRef};
 pub use dedup::DedupReader;
+pub use filter::FilterReader;
 pub use merge::{MergeReader, MergeReaderBuilder};
 use snafu::{ensure, ResultExt};
+pub use ttl::TtlReader;
 
 use crate::error::{self, Result};
 
@@ -81,7 +85,7 @@ impl Batch {
     ///
     /// # Panics
     /// Panics if `offset + length > self.num_rows()`.
-    fn slice(&self, offset: usize, length: usize) -> Batch {
+    pub(crate) fn slice(&self, offset: usize, length: usize) -> Batch {
         let columns = self
             .columns
             .iter()
@@ -129,6 +133,15 @@ pub trait BatchOp {
     /// - `selected.len()` is less than the number of rows.
     fn find_unique(&self, batch: &Batch, selected: &mut BitVec, prev: Option<&Batch>);
 
+    /// Set `i-th` bit of `selected` to `false` if the op type of `i-th` row in `batch`
+    /// is [OpType::Delete](store_api::storage::OpType::Delete).
+    ///
+    /// # Panics
+    /// Panics if
+    /// - `batch` doesn't have a valid op type column.
+    /// - `selected.len()` is less than the number of rows.
+    fn unselect_deleted(&self, batch: &Batch, selected: &mut BitVec);
+
     /// Filters the `batch`, returns elements matching the `filter` (i.e. where the values
     /// are true).
     ///
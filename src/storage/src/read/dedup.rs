@@ -31,18 +31,35 @@ pub struct DedupReader<R> {
     prev_batch: Option<Batch>,
     /// Reused bitmap buffer.
     selected: BitVec,
+    /// Whether to remove rows with `OpType::Delete` from the output.
+    ///
+    /// Readers that write their output back to the storage (e.g. compaction) should
+    /// keep the deleted rows, since the tombstones still need to hide older rows in
+    /// other files.
+    filter_deleted: bool,
+    /// Whether rows from the inner reader are in descending order, so the newest row of
+    /// each row key is the last one.
+    descending: bool,
 }
 
 impl<R> DedupReader<R> {
-    pub fn new(schema: ProjectedSchemaRef, reader: R) -> DedupReader<R> {
+    pub fn new(schema: ProjectedSchemaRef, reader: R, filter_deleted: bool) -> DedupReader<R> {
         DedupReader {
             schema,
             reader,
             prev_batch: None,
             selected: BitVec::default(),
+            filter_deleted,
+            descending: false,
         }
     }
 
+    /// Sets whether rows from the inner reader are in descending order.
+    pub fn with_descending(mut self, descending: bool) -> DedupReader<R> {
+        self.descending = descending;
+        self
+    }
+
     /// Take `batch` and then returns a new batch with no duplicated rows.
     ///
     /// This method may returns empty `Batch`.
@@ -60,24 +77,90 @@ impl<R> DedupReader<R> {
 
         // Store current batch to `prev_batch` so we could compare the next batch
         // with this batch. We store batch before filtering it mainly for correctness, as
-        // once we supports `DELETE`, rows with `OpType::Delete` would be removed from the
-        // batch after filter, then we may store an incorrect `last row` of previous batch.
+        // rows with `OpType::Delete` would be removed from the batch after filter, then we
+        // may store an incorrect `last row` of previous batch.
         self.prev_batch
             .get_or_insert_with(Batch::default)
             .clone_from(&batch); // Use `clone_from` to reuse allocated memory if possible.
 
-        // TODO(yingwen): To support `DELETE`, we could find all rows whose op_types are equal
-        // to `OpType::Delete`, mark their `selected` to false, then filter the batch.
+        if self.filter_deleted {
+            // The newest row of each row key is selected now, if it is a tombstone, then
+            // all rows of this key are deleted.
+            self.schema.unselect_deleted(&batch, &mut self.selected);
+        }
 
         let filter = BooleanVector::from_iterator(self.selected.iter().by_vals());
         // Filter duplicate rows.
         self.schema.filter(&batch, &filter)
     }
+
+    /// Take the previous batch and then returns it with no duplicated rows, keeping the last
+    /// row of each row key.
+    ///
+    /// Whether the last row of the previous batch is the last row of its row key is only known
+    /// after reading the `next` batch, `None` if there is no more batch.
+    fn dedup_prev_batch_desc(&mut self, next: Option<&Batch>) -> Result<Option<Batch>> {
+        let prev = match self.prev_batch.take() {
+            Some(prev) => prev,
+            None => return Ok(None),
+        };
+
+        // Marks the first row of each row key, the row before it is the last row of the
+        // previous row key.
+        self.selected.clear();
+        self.selected.resize(prev.num_rows(), false);
+        self.schema.find_unique(&prev, &mut self.selected, None);
+        self.selected.remove(0);
+        let is_last = match next {
+            Some(next) => {
+                let mut first = BitVec::repeat(false, 1);
+                self.schema
+                    .find_unique(&next.slice(0, 1), &mut first, Some(&prev));
+                first[0]
+            }
+            None => true,
+        };
+        self.selected.push(is_last);
+
+        if self.filter_deleted {
+            self.schema.unselect_deleted(&prev, &mut self.selected);
+        }
+
+        let filter = BooleanVector::from_iterator(self.selected.iter().by_vals());
+        self.schema.filter(&prev, &filter).map(Some)
+    }
+}
+
+impl<R: BatchReader> DedupReader<R> {
+    async fn next_batch_desc(&mut self) -> Result<Option<Batch>> {
+        while let Some(batch) = self.reader.next_batch().await? {
+            // Skip empty batch, so the previous batch is always non empty.
+            if batch.is_empty() {
+                continue;
+            }
+
+            let filtered = self.dedup_prev_batch_desc(Some(&batch))?;
+            self.prev_batch = Some(batch);
+            match filtered {
+                Some(filtered) if !filtered.is_empty() => return Ok(Some(filtered)),
+                _ => (),
+            }
+        }
+
+        match self.dedup_prev_batch_desc(None)? {
+            Some(filtered) if !filtered.is_empty() => Ok(Some(filtered)),
+            _ => Ok(None),
+        }
+    }
 }
 
 #[async_trait]
 impl<R: BatchReader> BatchReader for DedupReader<R> {
     async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        if self.descending {
+            return self.next_batch_desc().await;
+        }
+
         while let Some(batch) = self.reader.next_batch().await? {
             let filtered = self.dedup_batch(batch)?;
             // Skip empty batch.
@@ -101,7 +184,7 @@ mod tests {
     async fn test_dedup_reader_empty() {
         let schema = read_util::new_projected_schema();
         let reader = read_util::build_vec_reader(&[]);
-        let mut reader = DedupReader::new(schema, reader);
+        let mut reader = DedupReader::new(schema, reader, true);
 
         assert!(reader.next_batch().await.unwrap().is_none());
         // Call next_batch() again is allowed.
@@ -126,7 +209,7 @@ mod tests {
             ],
             &[(103, 2, 999, OpType::Put)],
         ]);
-        let mut reader = DedupReader::new(schema, reader);
+        let mut reader = DedupReader::new(schema, reader, true);
 
         let result = read_util::collect_kv_batch(&mut reader).await;
         let expect = [
@@ -151,7 +234,7 @@ mod tests {
             &[],
             &[(101, 2, 999, OpType::Put), (102, 12, 1000, OpType::Put)],
         ]);
-        let mut reader = DedupReader::new(schema, reader);
+        let mut reader = DedupReader::new(schema, reader, true);
 
         let result = read_util::collect_kv_batch(&mut reader).await;
         let expect = [(100, Some(1)), (101, Some(1)), (102, Some(12))];
@@ -172,10 +255,96 @@ mod tests {
             &[(101, 3, 998, OpType::Put), (101, 4, 997, OpType::Put)],
             &[(102, 12, 998, OpType::Put)],
         ]);
-        let mut reader = DedupReader::new(schema, reader);
+        let mut reader = DedupReader::new(schema, reader, true);
 
         let result = read_util::collect_kv_batch(&mut reader).await;
         let expect = [(100, Some(1)), (101, Some(1)), (102, Some(12))];
         assert_eq!(&expect, &result[..]);
     }
+
+    #[tokio::test]
+    async fn test_dedup_with_delete() {
+        let schema = read_util::new_projected_schema();
+        let input: &[&[(i64, i64, u64, OpType)]] = &[
+            // key, value, sequence, op_type
+            &[
+                (100, 0, 1001, OpType::Delete),
+                (100, 1, 1000, OpType::Put),
+                (101, 1, 1000, OpType::Put),
+                (102, 0, 1001, OpType::Delete),
+            ],
+            // Older rows of deleted key in next batch.
+            &[(102, 2, 999, OpType::Put), (103, 3, 1000, OpType::Put)],
+            // Row put after deletion is visible.
+            &[(104, 4, 1002, OpType::Put), (104, 0, 1001, OpType::Delete)],
+        ];
+        let reader = read_util::build_full_vec_reader(input);
+        let mut reader = DedupReader::new(schema.clone(), reader, true);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [(101, Some(1)), (103, Some(3)), (104, Some(4))];
+        assert_eq!(&expect, &result[..]);
+
+        // Keep tombstones if `filter_deleted` is false.
+        let reader = read_util::build_full_vec_reader(input);
+        let mut reader = DedupReader::new(schema, reader, false);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [
+            (100, Some(0)),
+            (101, Some(1)),
+            (102, Some(0)),
+            (103, Some(3)),
+            (104, Some(4)),
+        ];
+        assert_eq!(&expect, &result[..]);
+    }
+
+    #[tokio::test]
+    async fn test_dedup_descending() {
+        let schema = read_util::new_projected_schema();
+        let input: &[&[(i64, i64, u64, OpType)]] = &[
+            // key, value, sequence, op_type
+            &[
+                (103, 3, 999, OpType::Put),
+                (103, 13, 1000, OpType::Put),
+                (102, 12, 1000, OpType::Put),
+            ],
+            // Newer row of the same key in next batch.
+            &[(101, 2, 999, OpType::Put)],
+            &[],
+            &[
+                (101, 1, 1000, OpType::Put),
+                (100, 1, 1000, OpType::Put),
+                (100, 0, 1001, OpType::Delete),
+            ],
+            &[(99, 0, 1001, OpType::Delete), (99, 9, 1002, OpType::Put)],
+        ];
+        let reader = read_util::build_full_vec_reader(input);
+        let mut reader = DedupReader::new(schema.clone(), reader, true).with_descending(true);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [
+            (103, Some(13)),
+            (102, Some(12)),
+            (101, Some(1)),
+            (99, Some(9)),
+        ];
+        assert_eq!(&expect, &result[..]);
+        assert!(reader.next_batch().await.unwrap().is_none());
+
+        // Keep tombstones if `filter_deleted` is false.
+        let reader = read_util::build_full_vec_reader(input);
+        let mut reader = DedupReader::new(schema, reader, false).with_descending(true);
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [
+            (103, Some(13)),
+            (102, Some(12)),
+            (101, Some(1)),
+            (100, Some(0)),
+            (99, Some(9)),
+        ];
+        assert_eq!(&expect, &result[..]);
+    }
 }
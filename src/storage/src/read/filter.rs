@@ -0,0 +1,148 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use datatypes::prelude::ScalarVector;
+use datatypes::vectors::BooleanVector;
+use table::predicate::ExactFilter;
+
+use crate::error::Result;
+use crate::read::{Batch, BatchOp, BatchReader};
+use crate::schema::ProjectedSchemaRef;
+
+/// A reader that filters out rows not matching the exact filters from inner reader.
+///
+/// Exact filters only reference row key columns, which are always read, so the filters
+/// could be evaluated no matter which columns are projected.
+pub struct FilterReader<R> {
+    /// Projected schema to read.
+    schema: ProjectedSchemaRef,
+    /// The inner reader.
+    reader: R,
+    /// Filters the returned rows must match.
+    filters: Vec<ExactFilter>,
+}
+
+impl<R> FilterReader<R> {
+    pub fn new(
+        schema: ProjectedSchemaRef,
+        reader: R,
+        filters: Vec<ExactFilter>,
+    ) -> FilterReader<R> {
+        FilterReader {
+            schema,
+            reader,
+            filters,
+        }
+    }
+
+    /// Take `batch` and returns a new batch with rows matching all filters.
+    ///
+    /// This method may returns empty `Batch`.
+    fn filter_batch(&self, batch: Batch) -> Result<Batch> {
+        let schema = self.schema.schema_to_read().schema();
+        let column = |name: &str| {
+            // Exact filters only reference row key columns, which are always read.
+            let idx = schema.column_index_by_name(name).unwrap();
+            batch.column(idx).clone()
+        };
+
+        let mut selected = vec![true; batch.num_rows()];
+        for filter in &self.filters {
+            let matched = filter.evaluate(batch.num_rows(), &column);
+            for (selected, matched) in selected.iter_mut().zip(matched) {
+                *selected = *selected && matched;
+            }
+        }
+
+        if selected.iter().all(|v| *v) {
+            // All rows match the filters.
+            return Ok(batch);
+        }
+
+        let filter = BooleanVector::from_iterator(selected.into_iter());
+        self.schema.filter(&batch, &filter)
+    }
+}
+
+#[async_trait]
+impl<R: BatchReader> BatchReader for FilterReader<R> {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        while let Some(batch) = self.reader.next_batch().await? {
+            let filtered = self.filter_batch(batch)?;
+            // Skip empty batch.
+            if !filtered.is_empty() {
+                return Ok(Some(filtered));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_query::logical_plan::Expr;
+    use datafusion_common::{Column, ScalarValue};
+    use datafusion_expr::Expr as DfExpr;
+
+    use super::*;
+    use crate::test_util::{self, read_util};
+
+    fn new_ts_filter(expr: fn(DfExpr, DfExpr) -> DfExpr, ts: i64) -> ExactFilter {
+        let schema = read_util::new_projected_schema();
+        let ts_col = DfExpr::Column(Column::from_name(test_util::TIMESTAMP_NAME));
+        let ts_lit = DfExpr::Literal(ScalarValue::TimestampMillisecond(Some(ts), None));
+        let expr = Expr::from(expr(ts_col, ts_lit));
+
+        ExactFilter::try_new(
+            &expr,
+            schema.schema_to_read().schema(),
+            &[test_util::TIMESTAMP_NAME],
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_filter_reader_empty() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_vec_reader(&[]);
+        let filter = new_ts_filter(DfExpr::gt, 1000);
+        let mut reader = FilterReader::new(schema, reader, vec![filter]);
+
+        assert!(reader.next_batch().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_filter_reader_filter_rows() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_vec_reader(&[
+            // No row matches the filters.
+            &[(100, Some(1)), (200, Some(2))],
+            &[(999, Some(3)), (1000, Some(4)), (1001, Some(5))],
+            &[(2000, Some(6)), (3000, Some(7))],
+        ]);
+        let filters = vec![
+            new_ts_filter(DfExpr::gt_eq, 1000),
+            new_ts_filter(DfExpr::lt, 3000),
+        ];
+        let mut reader = FilterReader::new(schema, reader, filters);
+
+        read_util::check_reader_with_kv_batch(
+            &mut reader,
+            &[&[(1000, Some(4)), (1001, Some(5))], &[(2000, Some(6))]],
+        )
+        .await;
+    }
+}
@@ -224,6 +224,8 @@ struct Node {
     ///
     /// `None` means the `source` has reached EOF.
     cursor: Option<BatchCursor>,
+    /// Whether rows are merged in descending order.
+    descending: bool,
 }
 
 impl fmt::Debug for Node {
@@ -236,15 +238,26 @@ impl fmt::Debug for Node {
 }
 
 impl Node {
-    async fn new(schema: ProjectedSchemaRef, mut source: Source) -> Result<Node> {
+    async fn new(schema: ProjectedSchemaRef, mut source: Source, descending: bool) -> Result<Node> {
         let cursor = source.next_non_empty_batch().await?.map(BatchCursor::new);
         Ok(Node {
             schema,
             source,
             cursor,
+            descending,
         })
     }
 
+    /// Compare two rows in the order of the merged output.
+    fn compare_row(&self, left: &RowCursor, right: &RowCursor) -> Ordering {
+        let ordering = left.compare(&self.schema, right);
+        if self.descending {
+            ordering.reverse()
+        } else {
+            ordering
+        }
+    }
+
     /// Returns the reference to the cursor.
     ///
     /// # Panics
@@ -275,7 +288,7 @@ impl Node {
     /// Panics if
     /// - either `self` or `other` is EOF.
     fn compare_first_row(&self, other: &Node) -> Ordering {
-        self.first_row().compare(&self.schema, &other.first_row())
+        self.compare_row(&self.first_row(), &other.first_row())
     }
 
     /// Returns true if no more batch could be fetched from this node.
@@ -294,7 +307,7 @@ impl Node {
         let last = other.last_row();
         // `self` is after `other` if min (first) row of `self` is greater than
         // max (last) row of `other`.
-        first.compare(&self.schema, &last) == Ordering::Greater
+        self.compare_row(&first, &last) == Ordering::Greater
     }
 
     /// Fetch next batch and reset its cursor if `self` isn't EOF and the cursor
@@ -379,7 +392,8 @@ impl Ord for Node {
 
 /// A reader that would sort and merge `Batch` from multiple sources by key.
 ///
-/// `Batch` from each `Source` **must** be sorted.
+/// `Batch` from each `Source` **must** be sorted, in descending order if the reader is
+/// descending.
 pub struct MergeReader {
     /// Whether the reader has been initialized.
     initialized: bool,
@@ -405,6 +419,8 @@ pub struct MergeReader {
     batch_size: usize,
     /// Buffered batch.
     batch_builder: BatchBuilder,
+    /// Whether to merge rows in descending order.
+    descending: bool,
 }
 
 #[async_trait]
@@ -418,6 +434,7 @@ pub struct MergeReaderBuilder {
     schema: ProjectedSchemaRef,
     sources: Vec<Source>,
     batch_size: usize,
+    descending: bool,
 }
 
 impl MergeReaderBuilder {
@@ -430,6 +447,7 @@ impl MergeReaderBuilder {
             schema,
             sources: Vec::with_capacity(capacity),
             batch_size: consts::READ_BATCH_SIZE,
+            descending: false,
         }
     }
 
@@ -448,6 +466,12 @@ impl MergeReaderBuilder {
         self
     }
 
+    /// Merges rows in the reverse order of [BatchOp::compare_row()] if `descending` is true.
+    pub fn descending(mut self, descending: bool) -> Self {
+        self.descending = descending;
+        self
+    }
+
     pub fn build(self) -> MergeReader {
         let num_sources = self.sources.len();
         let column_schemas = self.schema.schema_to_read().schema().column_schemas();
@@ -464,6 +488,7 @@ impl MergeReaderBuilder {
             cold: BinaryHeap::with_capacity(num_sources),
             batch_size: self.batch_size,
             batch_builder,
+            descending: self.descending,
         }
     }
 }
@@ -481,7 +506,7 @@ impl MergeReader {
         }
 
         for source in self.sources.drain(..) {
-            let node = Node::new(self.schema.clone(), source).await?;
+            let node = Node::new(self.schema.clone(), source, self.descending).await?;
 
             if !node.is_eof() {
                 self.cold.push(node);
@@ -625,12 +650,12 @@ mod tests {
     async fn test_node() {
         let schema = read_util::new_projected_schema();
         let left_source = read_util::build_boxed_iter(&[&[(1, None), (3, None), (5, None)]]);
-        let mut left = Node::new(schema.clone(), Source::Iter(left_source))
+        let mut left = Node::new(schema.clone(), Source::Iter(left_source), false)
             .await
             .unwrap();
 
         let right_source = read_util::build_boxed_reader(&[&[(2, None), (3, None), (6, None)]]);
-        let mut right = Node::new(schema.clone(), Source::Reader(right_source))
+        let mut right = Node::new(schema.clone(), Source::Reader(right_source), false)
             .await
             .unwrap();
 
@@ -653,6 +678,35 @@ mod tests {
         assert!(output.contains("pos: 1"));
     }
 
+    #[tokio::test]
+    async fn test_merge_descending() {
+        let schema = read_util::new_projected_schema();
+        let mut reader = MergeReaderBuilder::new(schema)
+            .batch_size(3)
+            .descending(true)
+            .push_batch_iter(read_util::build_boxed_iter(&[
+                &[(9, Some(9)), (5, Some(5))],
+                &[(2, Some(2))],
+            ]))
+            .push_batch_reader(read_util::build_boxed_reader(&[
+                &[(7, Some(7)), (4, None)],
+                &[(3, Some(3)), (1, Some(1))],
+            ]))
+            .build();
+
+        let result = read_util::collect_kv_batch(&mut reader).await;
+        let expect = [
+            (9, Some(9)),
+            (7, Some(7)),
+            (5, Some(5)),
+            (4, None),
+            (3, Some(3)),
+            (2, Some(2)),
+            (1, Some(1)),
+        ];
+        assert_eq!(&expect, &result[..]);
+    }
+
     type Batches<'a> = &'a [&'a [(i64, Option<i64>)]];
 
     fn build_merge_reader(sources: &[Batches], num_iter: usize, batch_size: usize) -> MergeReader {
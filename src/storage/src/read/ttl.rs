@@ -0,0 +1,130 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use async_trait::async_trait;
+use common_time::Timestamp;
+use datatypes::prelude::ScalarVector;
+use datatypes::vectors::{BooleanVector, Int64Vector, TimestampVector};
+
+use crate::error::Result;
+use crate::read::{Batch, BatchOp, BatchReader};
+use crate::schema::ProjectedSchemaRef;
+
+/// A reader that filters out expired rows from inner reader.
+///
+/// Rows whose timestamp is older than `expire_before` are expired, they are still kept in
+/// the region until the files containing them are purged.
+pub struct TtlReader<R> {
+    /// Projected schema to read.
+    schema: ProjectedSchemaRef,
+    /// The inner reader.
+    reader: R,
+    /// Rows before this timestamp (exclusive) are expired.
+    expire_before: Timestamp,
+}
+
+impl<R> TtlReader<R> {
+    pub fn new(schema: ProjectedSchemaRef, reader: R, expire_before: Timestamp) -> TtlReader<R> {
+        TtlReader {
+            schema,
+            reader,
+            expire_before,
+        }
+    }
+
+    /// Take `batch` and returns a new batch without expired rows.
+    ///
+    /// This method may returns empty `Batch`.
+    fn filter_expired(&self, batch: Batch) -> Result<Batch> {
+        let ts_index = match self.schema.schema_to_read().schema().timestamp_index() {
+            Some(idx) => idx,
+            None => return Ok(batch),
+        };
+
+        let column = batch.column(ts_index);
+        let selected: Vec<bool> =
+            if let Some(vector) = column.as_any().downcast_ref::<TimestampVector>() {
+                vector
+                    .iter_data()
+                    .map(|ts| ts.map(|ts| ts >= self.expire_before).unwrap_or(true))
+                    .collect()
+            } else if let Some(vector) = column.as_any().downcast_ref::<Int64Vector>() {
+                vector
+                    .iter_data()
+                    .map(|ts| {
+                        ts.map(|ts| Timestamp::from_millis(ts) >= self.expire_before)
+                            .unwrap_or(true)
+                    })
+                    .collect()
+            } else {
+                return Ok(batch);
+            };
+
+        if selected.iter().all(|v| *v) {
+            // Nothing is expired.
+            return Ok(batch);
+        }
+
+        let filter = BooleanVector::from_iterator(selected.into_iter());
+        self.schema.filter(&batch, &filter)
+    }
+}
+
+#[async_trait]
+impl<R: BatchReader> BatchReader for TtlReader<R> {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        while let Some(batch) = self.reader.next_batch().await? {
+            let filtered = self.filter_expired(batch)?;
+            // Skip empty batch.
+            if !filtered.is_empty() {
+                return Ok(Some(filtered));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::read_util;
+
+    #[tokio::test]
+    async fn test_ttl_reader_empty() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_vec_reader(&[]);
+        let mut reader = TtlReader::new(schema, reader, Timestamp::from_millis(1000));
+
+        assert!(reader.next_batch().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_ttl_reader_filter_expired() {
+        let schema = read_util::new_projected_schema();
+        let reader = read_util::build_vec_reader(&[
+            // All rows are expired.
+            &[(100, Some(1)), (200, Some(2))],
+            &[(999, Some(3)), (1000, Some(4)), (1001, Some(5))],
+            &[(2000, Some(6))],
+        ]);
+        let mut reader = TtlReader::new(schema, reader, Timestamp::from_millis(1000));
+
+        read_util::check_reader_with_kv_batch(
+            &mut reader,
+            &[&[(1000, Some(4)), (1001, Some(5))], &[(2000, Some(6))]],
+        )
+        .await;
+    }
+}
@@ -16,26 +16,31 @@
 mod tests;
 mod writer;
 use std::collections::BTreeMap;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
 use common_telemetry::logging;
+use common_time::{util as time_util, Timestamp};
+use metrics::{counter, histogram};
 use snafu::ResultExt;
 use store_api::logstore::LogStore;
 use store_api::manifest::{self, Manifest, ManifestVersion, MetaActionIterator};
 use store_api::storage::{
-    AlterRequest, OpenOptions, ReadContext, Region, RegionId, RegionMeta, SequenceNumber,
-    WriteContext, WriteResponse,
+    AlterRequest, OpenOptions, ReadContext, Region, RegionId, RegionMeta, RegionStat,
+    SequenceNumber, WriteContext, WriteResponse,
 };
 
+use crate::compaction::{CompactionPickerRef, CompactionSchedulerRef};
 use crate::error::{self, Error, Result};
 use crate::flush::{FlushSchedulerRef, FlushStrategyRef};
 use crate::manifest::action::{
-    RawRegionMetadata, RegionChange, RegionMetaAction, RegionMetaActionList,
+    RawRegionMetadata, RegionChange, RegionManifestData, RegionMetaAction, RegionMetaActionList,
 };
 use crate::manifest::region::RegionManifest;
 use crate::memtable::MemtableBuilderRef;
 use crate::metadata::{RegionMetaImpl, RegionMetadata, RegionMetadataRef};
+use crate::metric::{LABEL_REGION, METRIC_WRITE_ELAPSED, METRIC_WRITE_ROWS};
 pub use crate::region::writer::{AlterContext, RegionWriter, RegionWriterRef, WriterContext};
 use crate::schema::compat::CompatWrite;
 use crate::snapshot::SnapshotImpl;
@@ -80,10 +85,24 @@ impl<S: LogStore> Region for RegionImpl<S> {
     }
 
     async fn write(&self, ctx: &WriteContext, mut request: WriteBatch) -> Result<WriteResponse> {
+        let start = Instant::now();
+        let num_rows = request.num_rows();
         // Compat the schema of the write batch outside of the write lock.
         self.inner.compat_write_batch(&mut request)?;
 
-        self.inner.write(ctx, request).await
+        // Writes in a spawned task, so the group commit led by this write runs to completion
+        // even if the caller is cancelled.
+        let inner = self.inner.clone();
+        let ctx = ctx.clone();
+        let response = common_runtime::spawn_write(async move { inner.write(&ctx, request).await })
+            .await
+            .context(error::JoinTaskSnafu)??;
+
+        let region = self.inner.shared.id.to_string();
+        histogram!(METRIC_WRITE_ELAPSED, start.elapsed(), LABEL_REGION => region.clone());
+        counter!(METRIC_WRITE_ROWS, num_rows as u64, LABEL_REGION => region);
+
+        Ok(response)
     }
 
     fn snapshot(&self, _ctx: &ReadContext) -> Result<SnapshotImpl> {
@@ -97,6 +116,22 @@ impl<S: LogStore> Region for RegionImpl<S> {
     async fn alter(&self, request: AlterRequest) -> Result<()> {
         self.inner.alter(request).await
     }
+
+    fn set_ttl(&self, ttl: Option<Duration>) {
+        self.inner.shared.set_ttl(ttl);
+    }
+
+    fn stat(&self) -> RegionStat {
+        self.inner.stat()
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.inner.flush().await
+    }
+
+    async fn compact(&self) -> Result<()> {
+        self.inner.compact().await
+    }
 }
 
 /// Storage related config for region.
@@ -110,6 +145,12 @@ pub struct StoreConfig<S> {
     pub memtable_builder: MemtableBuilderRef,
     pub flush_scheduler: FlushSchedulerRef,
     pub flush_strategy: FlushStrategyRef,
+    pub compaction_picker: CompactionPickerRef,
+    pub compaction_scheduler: CompactionSchedulerRef,
+    /// Time to live of rows in the region.
+    pub ttl: Option<Duration>,
+    /// Interval to check and purge expired files if the region has a ttl.
+    pub ttl_check_interval: Duration,
 }
 
 pub type RecoverdMetadata = (SequenceNumber, (ManifestVersion, RawRegionMetadata));
@@ -139,7 +180,12 @@ impl<S: LogStore> RegionImpl<S> {
         let mutable_memtable = store_config
             .memtable_builder
             .build(metadata.schema().clone());
-        let version = Version::with_manifest_version(metadata, manifest_version, mutable_memtable);
+        let version = Version::with_manifest_version(
+            metadata,
+            manifest_version,
+            mutable_memtable,
+            store_config.sst_layer.clone(),
+        );
         let region = RegionImpl::new(version, store_config);
 
         Ok(region)
@@ -158,6 +204,7 @@ impl<S: LogStore> RegionImpl<S> {
                 id,
                 name,
                 version_control: Arc::new(version_control),
+                ttl: RwLock::new(store_config.ttl),
             }),
             writer: Arc::new(RegionWriter::new(store_config.memtable_builder)),
             wal,
@@ -165,7 +212,10 @@ impl<S: LogStore> RegionImpl<S> {
             flush_scheduler: store_config.flush_scheduler,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
+            compaction_picker: store_config.compaction_picker,
+            compaction_scheduler: store_config.compaction_scheduler,
         });
+        RegionInner::spawn_ttl_checker(&inner, store_config.ttl_check_interval);
 
         RegionImpl { inner }
     }
@@ -182,6 +232,7 @@ impl<S: LogStore> RegionImpl<S> {
         let (version, mut recovered_metadata) = match Self::recover_from_manifest(
             &store_config.manifest,
             &store_config.memtable_builder,
+            &store_config.sst_layer,
         )
         .await?
         {
@@ -229,6 +280,7 @@ impl<S: LogStore> RegionImpl<S> {
             id: metadata.id(),
             name,
             version_control,
+            ttl: RwLock::new(store_config.ttl),
         });
 
         let writer = Arc::new(RegionWriter::new(store_config.memtable_builder));
@@ -240,11 +292,18 @@ impl<S: LogStore> RegionImpl<S> {
             wal: &wal,
             writer: &writer,
             manifest: &store_config.manifest,
+            compaction_picker: &store_config.compaction_picker,
+            compaction_scheduler: &store_config.compaction_scheduler,
         };
         // Replay all unflushed data.
         writer
             .replay(recovered_metadata_after_flushed, writer_ctx)
             .await?;
+        // Entries before the flushed sequence are no longer needed, but the log store
+        // doesn't persist obsolete ids, so we need to mark them again after reopening.
+        if let Err(e) = wal.obsolete(flushed_sequence).await {
+            logging::error!(e; "Failed to mark WAL obsolete, region: {}", shared.name());
+        }
 
         let inner = Arc::new(RegionInner {
             shared,
@@ -254,7 +313,10 @@ impl<S: LogStore> RegionImpl<S> {
             flush_scheduler: store_config.flush_scheduler,
             sst_layer: store_config.sst_layer,
             manifest: store_config.manifest,
+            compaction_picker: store_config.compaction_picker,
+            compaction_scheduler: store_config.compaction_scheduler,
         });
+        RegionInner::spawn_ttl_checker(&inner, store_config.ttl_check_interval);
 
         Ok(Some(RegionImpl { inner }))
     }
@@ -264,17 +326,47 @@ impl<S: LogStore> RegionImpl<S> {
         self.inner.shared.id()
     }
 
+    /// Close the region, waits for running background jobs and rejects following writes.
+    pub async fn close(&self) -> Result<()> {
+        self.inner.close().await
+    }
+
+    /// Drop the region, removes its WAL namespace, manifest and SST files.
+    ///
+    /// The region is closed before removing its data.
+    pub async fn drop_region(&self) -> Result<()> {
+        self.inner.drop_region().await
+    }
+
     async fn recover_from_manifest(
         manifest: &RegionManifest,
         memtable_builder: &MemtableBuilderRef,
+        sst_layer: &AccessLayerRef,
     ) -> Result<(Option<Version>, RecoveredMetadataMap)> {
-        let (start, end) = Self::manifest_scan_range();
-        let mut iter = manifest.scan(start, end).await?;
-
         let mut version = None;
         let mut actions = Vec::new();
         let mut last_manifest_version = manifest::MIN_VERSION;
         let mut recovered_metadata = BTreeMap::new();
+        let mut checkpoint_protocol = None;
+
+        // Recover from the checkpoint first, then replay actions after it.
+        let start = match manifest.last_checkpoint().await? {
+            Some(checkpoint) => {
+                logging::debug!(
+                    "Region recover from checkpoint, last_version: {}",
+                    checkpoint.last_version
+                );
+
+                (version, recovered_metadata) =
+                    Self::recover_from_checkpoint(checkpoint.data, memtable_builder, sst_layer)?;
+                last_manifest_version = checkpoint.last_version;
+                checkpoint_protocol = Some(checkpoint.protocol);
+
+                checkpoint.last_version + 1
+            }
+            None => manifest::MIN_VERSION,
+        };
+        let mut iter = manifest.scan(start, manifest::MAX_VERSION).await?;
 
         while let Some((manifest_version, action_list)) = iter.next_action().await? {
             last_manifest_version = manifest_version;
@@ -294,6 +386,7 @@ impl<S: LogStore> RegionImpl<S> {
                             Arc::new(region_metadata),
                             last_manifest_version,
                             memtable,
+                            sst_layer.clone(),
                         ));
                         for (manifest_version, action) in actions.drain(..) {
                             version = Self::replay_edit(manifest_version, action, version);
@@ -319,16 +412,50 @@ impl<S: LogStore> RegionImpl<S> {
 
         if version.is_some() {
             // update manifest state after recovering
-            let protocol = iter.last_protocol();
-            manifest.update_state(last_manifest_version + 1, protocol.clone());
+            let protocol = iter.last_protocol().clone().or(checkpoint_protocol);
+            manifest.update_state(last_manifest_version + 1, protocol);
         }
 
         Ok((version, recovered_metadata))
     }
 
-    fn manifest_scan_range() -> (ManifestVersion, ManifestVersion) {
-        // TODO(dennis): use manifest version in WAL
-        (manifest::MIN_VERSION, manifest::MAX_VERSION)
+    fn recover_from_checkpoint(
+        data: RegionManifestData,
+        memtable_builder: &MemtableBuilderRef,
+        sst_layer: &AccessLayerRef,
+    ) -> Result<(Option<Version>, RecoveredMetadataMap)> {
+        let change = match data.change {
+            Some((_, change)) => change,
+            None => return Ok((None, BTreeMap::new())),
+        };
+
+        let region = change.metadata.name.clone();
+        let region_metadata: RegionMetadata = change
+            .metadata
+            .try_into()
+            .context(error::InvalidRawRegionSnafu { region })?;
+        let memtable = memtable_builder.build(region_metadata.schema().clone());
+        let mut version = Version::with_manifest_version(
+            Arc::new(region_metadata),
+            data.manifest_version,
+            memtable,
+            sst_layer.clone(),
+        );
+        version.apply_edit(VersionEdit {
+            files_to_add: data.files,
+            files_to_remove: Vec::new(),
+            flushed_sequence: Some(data.flushed_sequence),
+            manifest_version: data.manifest_version,
+            max_memtable_id: None,
+        });
+
+        let recovered_metadata = data
+            .pending_changes
+            .into_iter()
+            .map(|(manifest_version, c)| (c.committed_sequence, (manifest_version, c.metadata)))
+            .collect();
+
+        Ok((Some(version), recovered_metadata))
     }
 
     fn replay_edit(
@@ -339,6 +466,7 @@ impl<S: LogStore> RegionImpl<S> {
         if let RegionMetaAction::Edit(e) = action {
             let edit = VersionEdit {
                 files_to_add: e.files_to_add,
+                files_to_remove: e.files_to_remove,
                 flushed_sequence: Some(e.flushed_sequence),
                 manifest_version,
                 max_memtable_id: None,
@@ -369,6 +497,10 @@ impl<S: LogStore> RegionImpl<S> {
         self.inner.writer.wait_flush_done().await
     }
 
+    async fn wait_compaction_done(&self) -> Result<()> {
+        self.inner.writer.wait_compaction_done().await
+    }
+
     /// Write to inner, also the `RegionWriter` directly.
     async fn write_inner(&self, ctx: &WriteContext, request: WriteBatch) -> Result<WriteResponse> {
         self.inner.write(ctx, request).await
@@ -377,17 +509,10 @@ impl<S: LogStore> RegionImpl<S> {
     // Replay metadata to inner.
     async fn replay_inner(&self, recovered_metadata: RecoveredMetadataMap) -> Result<()> {
         let inner = &self.inner;
-        let writer_ctx = WriterContext {
-            shared: &inner.shared,
-            flush_strategy: &inner.flush_strategy,
-            flush_scheduler: &inner.flush_scheduler,
-            sst_layer: &inner.sst_layer,
-            wal: &inner.wal,
-            writer: &inner.writer,
-            manifest: &inner.manifest,
-        };
-
-        inner.writer.replay(recovered_metadata, writer_ctx).await
+        inner
+            .writer
+            .replay(recovered_metadata, inner.writer_ctx())
+            .await
     }
 }
 
@@ -400,6 +525,8 @@ pub struct SharedData {
     name: String,
     // TODO(yingwen): Maybe no need to use Arc for version control.
     pub version_control: VersionControlRef,
+    /// Time to live of rows in the region, could be altered at runtime.
+    ttl: RwLock<Option<Duration>>,
 }
 
 impl SharedData {
@@ -412,6 +539,26 @@ impl SharedData {
     pub fn name(&self) -> &str {
         &self.name
     }
+
+    #[inline]
+    pub fn ttl(&self) -> Option<Duration> {
+        *self.ttl.read().unwrap()
+    }
+
+    pub fn set_ttl(&self, ttl: Option<Duration>) {
+        logging::info!("Set ttl of region {} to {:?}", self.name, ttl);
+
+        *self.ttl.write().unwrap() = ttl;
+    }
+
+    /// Returns the timestamp before which rows are expired, or `None` if rows in this
+    /// region never expire.
+    pub fn expire_before(&self) -> Option<Timestamp> {
+        self.ttl().map(|ttl| {
+            let ttl_millis = i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX);
+            Timestamp::from_millis(time_util::current_time_millis().saturating_sub(ttl_millis))
+        })
+    }
 }
 
 pub type SharedDataRef = Arc<SharedData>;
@@ -425,6 +572,8 @@ struct RegionInner<S: LogStore> {
     flush_scheduler: FlushSchedulerRef,
     sst_layer: AccessLayerRef,
     manifest: RegionManifest,
+    compaction_picker: CompactionPickerRef,
+    compaction_scheduler: CompactionSchedulerRef,
 }
 
 impl<S: LogStore> RegionInner<S> {
@@ -439,11 +588,37 @@ impl<S: LogStore> RegionInner<S> {
         RegionMetaImpl::new(metadata)
     }
 
+    fn stat(&self) -> RegionStat {
+        let version = self.version_control().current();
+        let (sst_num, sst_bytes) = version
+            .ssts()
+            .levels()
+            .iter()
+            .flat_map(|level| level.files())
+            .fold((0, 0), |(num, bytes), file| {
+                (num + 1, bytes + file.file_size())
+            });
+
+        RegionStat {
+            region_id: self.shared.id,
+            region_name: self.shared.name.clone(),
+            memtable_bytes: version.memtables().total_bytes_allocated() as u64,
+            sst_num,
+            sst_bytes,
+            flushed_sequence: version.flushed_sequence(),
+        }
+    }
+
     fn create_snapshot(&self) -> SnapshotImpl {
         let version = self.version_control().current();
         let sequence = self.version_control().committed_sequence();
 
-        SnapshotImpl::new(version, sequence, self.sst_layer.clone())
+        SnapshotImpl::new(
+            version,
+            sequence,
+            self.shared.expire_before(),
+            self.sst_layer.clone(),
+        )
     }
 
     fn compat_write_batch(&self, request: &mut WriteBatch) -> Result<()> {
@@ -455,9 +630,8 @@ impl<S: LogStore> RegionInner<S> {
         request.compat_write(schema.user_schema())
     }
 
-    /// Write to writer directly.
-    async fn write(&self, ctx: &WriteContext, request: WriteBatch) -> Result<WriteResponse> {
-        let writer_ctx = WriterContext {
+    fn writer_ctx(&self) -> WriterContext<'_, S> {
+        WriterContext {
             shared: &self.shared,
             flush_strategy: &self.flush_strategy,
             flush_scheduler: &self.flush_scheduler,
@@ -465,10 +639,92 @@ impl<S: LogStore> RegionInner<S> {
             wal: &self.wal,
             writer: &self.writer,
             manifest: &self.manifest,
-        };
+            compaction_picker: &self.compaction_picker,
+            compaction_scheduler: &self.compaction_scheduler,
+        }
+    }
+
+    /// Write to writer directly.
+    async fn write(&self, ctx: &WriteContext, request: WriteBatch) -> Result<WriteResponse> {
         // The writer would also try to compat the schema of write batch if it finds out the
         // schema version of request is less than current schema version.
-        self.writer.write(ctx, request, writer_ctx).await
+        self.writer.write(ctx, request, self.writer_ctx()).await
+    }
+
+    async fn flush(&self) -> Result<()> {
+        self.writer.flush(self.writer_ctx()).await
+    }
+
+    async fn compact(&self) -> Result<()> {
+        self.writer.compact(self.writer_ctx()).await
+    }
+
+    /// Spawns a task that checks and purges expired files of the region every `interval`,
+    /// so expired files are removed even if the region has no write to trigger a flush.
+    ///
+    /// The task exits once the region is closed or dropped.
+    fn spawn_ttl_checker(inner: &Arc<RegionInner<S>>, interval: Duration) {
+        let inner = Arc::downgrade(inner);
+        common_runtime::spawn_bg(async move {
+            let mut ticker = tokio::time::interval(interval);
+            // The first tick completes immediately.
+            ticker.tick().await;
+
+            loop {
+                ticker.tick().await;
+
+                let inner = match inner.upgrade() {
+                    Some(inner) => inner,
+                    None => return,
+                };
+                match inner.writer.purge_expired(inner.writer_ctx()).await {
+                    Ok(()) => (),
+                    Err(Error::ClosedRegion { .. }) => return,
+                    Err(e) => {
+                        logging::error!(
+                            e; "Failed to purge expired files, region: {}",
+                            inner.shared.name()
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.writer.close(&self.shared).await;
+
+        logging::info!(
+            "Region {} closed, name: {}",
+            self.shared.id,
+            self.shared.name
+        );
+
+        Ok(())
+    }
+
+    async fn drop_region(&self) -> Result<()> {
+        self.close().await?;
+
+        self.wal.delete_namespace().await?;
+        // Delete the manifest before SST files, so the region won't be recovered with
+        // missing SST files even if we failed to delete all files.
+        self.manifest.delete_all().await?;
+
+        let version = self.version_control().current();
+        for level in version.ssts().levels() {
+            for file in level.files() {
+                self.sst_layer.delete_sst(file.file_name()).await?;
+            }
+        }
+
+        logging::info!(
+            "Region {} dropped, name: {}",
+            self.shared.id,
+            self.shared.name
+        );
+
+        Ok(())
     }
 
     async fn alter(&self, request: AlterRequest) -> Result<()> {
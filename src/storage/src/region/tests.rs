@@ -16,14 +16,17 @@
 
 mod alter;
 mod basic;
+mod compact;
 mod flush;
 mod projection;
 
+use std::collections::HashMap;
+
 use common_telemetry::logging;
 use common_time::timestamp::Timestamp;
 use datatypes::prelude::ScalarVector;
 use datatypes::type_id::LogicalTypeId;
-use datatypes::vectors::{Int64Vector, TimestampVector};
+use datatypes::vectors::{Int64Vector, TimestampVector, VectorRef};
 use log_store::fs::log::LocalFileLogStore;
 use log_store::fs::noop::NoopLogStore;
 use object_store::backend::fs;
@@ -37,6 +40,7 @@ use super::*;
 use crate::manifest::action::{RegionChange, RegionMetaActionList};
 use crate::manifest::test_utils::*;
 use crate::memtable::DefaultMemtableBuilder;
+use crate::test_util::access_layer_util::MockAccessLayer;
 use crate::test_util::descriptor_util::RegionDescBuilder;
 use crate::test_util::{self, config_util, schema_util, write_batch_util};
 use crate::write_batch::PutData;
@@ -94,19 +98,35 @@ impl<S: LogStore> TesterBase<S> {
             .unwrap()
     }
 
+    /// Delete rows by their timestamps.
+    pub async fn delete(&self, keys: &[i64]) -> WriteResponse {
+        let mut batch = new_write_batch_for_test(false);
+        let timestamps = TimestampVector::from_values(keys.iter().copied());
+        let mut key_columns = HashMap::with_capacity(1);
+        key_columns.insert(
+            test_util::TIMESTAMP_NAME.to_string(),
+            Arc::new(timestamps) as VectorRef,
+        );
+        batch.delete(key_columns).unwrap();
+
+        self.region.write(&self.write_ctx, batch).await.unwrap()
+    }
+
     pub async fn replay_inner(&self, recovered_metadata: RecoveredMetadataMap) {
         self.region.replay_inner(recovered_metadata).await.unwrap()
     }
 
     /// Scan all data.
     pub async fn full_scan(&self) -> Vec<(i64, Option<i64>)> {
-        logging::info!("Full scan with ctx {:?}", self.read_ctx);
+        self.scan(ScanRequest::default()).await
+    }
+
+    /// Scan data with given request.
+    pub async fn scan(&self, req: ScanRequest) -> Vec<(i64, Option<i64>)> {
+        logging::info!("Scan with ctx {:?}", self.read_ctx);
         let snapshot = self.region.snapshot(&self.read_ctx).unwrap();
 
-        let resp = snapshot
-            .scan(&self.read_ctx, ScanRequest::default())
-            .await
-            .unwrap();
+        let resp = snapshot.scan(&self.read_ctx, req).await.unwrap();
         let mut reader = resp.reader;
 
         let metadata = self.region.in_memory_metadata();
@@ -200,7 +220,11 @@ async fn test_new_region() {
         .build(metadata.schema().clone());
 
     let region = RegionImpl::new(
-        Version::new(Arc::new(metadata), placeholder_memtable),
+        Version::new(
+            Arc::new(metadata),
+            placeholder_memtable,
+            store_config.sst_layer.clone(),
+        ),
         store_config,
     );
 
@@ -222,6 +246,7 @@ async fn test_new_region() {
 async fn test_recover_region_manifets() {
     let tmp_dir = TempDir::new("test_new_region").unwrap();
     let memtable_builder = Arc::new(DefaultMemtableBuilder::default()) as _;
+    let sst_layer = Arc::new(MockAccessLayer) as _;
 
     let object_store = ObjectStore::new(
         fs::Builder::default()
@@ -234,13 +259,15 @@ async fn test_recover_region_manifets() {
     let region_meta = Arc::new(build_region_meta());
 
     // Recover from empty
-    assert!(
-        RegionImpl::<NoopLogStore>::recover_from_manifest(&manifest, &memtable_builder)
-            .await
-            .unwrap()
-            .0
-            .is_none()
-    );
+    assert!(RegionImpl::<NoopLogStore>::recover_from_manifest(
+        &manifest,
+        &memtable_builder,
+        &sst_layer
+    )
+    .await
+    .unwrap()
+    .0
+    .is_none());
 
     {
         // save some actions into region_meta
@@ -258,6 +285,7 @@ async fn test_recover_region_manifets() {
             .update(RegionMetaActionList::new(vec![
                 RegionMetaAction::Edit(build_region_edit(1, &["f1"], &[])),
                 RegionMetaAction::Edit(build_region_edit(2, &["f2", "f3"], &[])),
+                RegionMetaAction::Edit(build_region_edit(3, &["f4"], &["f2"])),
             ]))
             .await
             .unwrap();
@@ -275,21 +303,22 @@ async fn test_recover_region_manifets() {
 
     // try to recover
     let (version, recovered_metadata) =
-        RegionImpl::<NoopLogStore>::recover_from_manifest(&manifest, &memtable_builder)
+        RegionImpl::<NoopLogStore>::recover_from_manifest(&manifest, &memtable_builder, &sst_layer)
             .await
             .unwrap();
 
     assert_eq!(42, *recovered_metadata.first_key_value().unwrap().0);
     let version = version.unwrap();
     assert_eq!(*version.metadata(), region_meta);
-    assert_eq!(version.flushed_sequence(), 2);
+    assert_eq!(version.flushed_sequence(), 3);
     assert_eq!(version.manifest_version(), 1);
     let ssts = version.ssts();
-    let files = ssts.levels()[0].files();
-    assert_eq!(3, files.len());
-    for (i, file) in files.iter().enumerate() {
-        assert_eq!(format!("f{}", i + 1), file.file_name());
-    }
+    let mut files: Vec<_> = ssts.levels()[0]
+        .files()
+        .map(|f| f.file_name().to_string())
+        .collect();
+    files.sort_unstable();
+    assert_eq!(vec!["f1", "f3", "f4"], files);
 
     // check manifest state
     assert_eq!(3, manifest.last_version());
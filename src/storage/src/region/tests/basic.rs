@@ -14,8 +14,15 @@
 
 //! Region read/write tests.
 
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_query::cancellation::{CancelReason, CancellationToken};
+use futures::FutureExt;
 use log_store::fs::log::LocalFileLogStore;
-use store_api::storage::{OpenOptions, SequenceNumber, WriteResponse};
+use store_api::storage::{
+    ChunkReader, OpenOptions, ReadContext, Region, ScanRequest, SequenceNumber, Snapshot,
+    WriteResponse,
+};
 use tempdir::TempDir;
 
 use crate::error::Result;
@@ -99,6 +106,10 @@ impl Tester {
         self.base().put(data).await
     }
 
+    async fn delete(&self, keys: &[i64]) -> WriteResponse {
+        self.base().delete(keys).await
+    }
+
     async fn full_scan(&self) -> Vec<(i64, Option<i64>)> {
         self.base().full_scan().await
     }
@@ -128,6 +139,32 @@ async fn test_simple_put_scan() {
     assert_eq!(data, output);
 }
 
+#[tokio::test]
+async fn test_scan_cancelled() {
+    let dir = TempDir::new("scan-cancelled").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = Tester::new(REGION_NAME, store_dir).await;
+    tester.put(&[(1000, Some(100)), (1001, Some(101))]).await;
+    tester.set_batch_size(1);
+
+    let base = tester.base();
+    let read_ctx = ReadContext {
+        cancellation: CancellationToken::new(),
+        ..base.read_ctx.clone()
+    };
+    let snapshot = base.region.snapshot(&read_ctx).unwrap();
+    let resp = snapshot
+        .scan(&read_ctx, ScanRequest::default())
+        .await
+        .unwrap();
+    let mut reader = resp.reader;
+    assert!(reader.next_chunk().await.unwrap().is_some());
+
+    read_ctx.cancellation.cancel(CancelReason::Killed);
+    let err = reader.next_chunk().await.unwrap_err();
+    assert_eq!(StatusCode::Cancelled, err.status_code());
+}
+
 #[tokio::test]
 async fn test_sequence_increase() {
     let dir = TempDir::new("sequence").unwrap();
@@ -202,3 +239,93 @@ async fn test_scan_different_batch() {
         assert_eq!(data, output);
     }
 }
+
+#[tokio::test]
+async fn test_put_delete_scan() {
+    let dir = TempDir::new("put-delete-scan").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = Tester::new(REGION_NAME, store_dir).await;
+
+    let data = vec![
+        (1000, Some(100)),
+        (1001, Some(101)),
+        (1002, None),
+        (1003, Some(103)),
+        (1004, Some(104)),
+    ];
+    tester.put(&data).await;
+
+    // Also delete a key that doesn't exist.
+    tester.delete(&[1001, 1003, 1005]).await;
+
+    let expect = vec![(1000, Some(100)), (1002, None), (1004, Some(104))];
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+
+    // Put again after deletion.
+    tester.put(&[(1001, Some(201))]).await;
+    let expect = vec![
+        (1000, Some(100)),
+        (1001, Some(201)),
+        (1002, None),
+        (1004, Some(104)),
+    ];
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+
+    // Deletion is recovered from the WAL after reopen.
+    tester.reopen().await;
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+}
+
+#[tokio::test]
+async fn test_concurrent_put() {
+    let dir = TempDir::new("concurrent-put").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = Tester::new(REGION_NAME, store_dir).await;
+
+    let committed_sequence = tester.committed_sequence();
+    let data: Vec<_> = (0..100).map(|i| (i, Some(i))).collect();
+    // Concurrent puts are committed in groups, each put still has its own sequence.
+    let puts = data.iter().map(|kv| tester.put(std::slice::from_ref(kv)));
+    futures::future::join_all(puts).await;
+    assert_eq!(committed_sequence + 100, tester.committed_sequence());
+
+    let output = tester.full_scan().await;
+    assert_eq!(data, output);
+
+    tester.reopen().await;
+    let output = tester.full_scan().await;
+    assert_eq!(data, output);
+    assert_eq!(committed_sequence + 100, tester.committed_sequence());
+}
+
+#[tokio::test]
+async fn test_cancelled_put() {
+    let dir = TempDir::new("cancelled-put").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+    let mut tester = Tester::new(REGION_NAME, store_dir).await;
+
+    let committed_sequence = tester.committed_sequence();
+    let data: Vec<_> = (0..10).map(|i| (i, Some(i))).collect();
+    // Puts are dropped right after they start, but still run to completion.
+    for kv in &data {
+        let _ = tester.put(std::slice::from_ref(kv)).now_or_never();
+    }
+    for _ in 0..100 {
+        if tester.committed_sequence() == committed_sequence + 10 {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(committed_sequence + 10, tester.committed_sequence());
+
+    let output = tester.full_scan().await;
+    assert_eq!(data, output);
+
+    tester.reopen().await;
+    let output = tester.full_scan().await;
+    assert_eq!(data, output);
+    assert_eq!(committed_sequence + 10, tester.committed_sequence());
+}
@@ -0,0 +1,331 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Region compaction tests.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_time::util as time_util;
+use log_store::fs::log::LocalFileLogStore;
+use store_api::storage::{OpenOptions, Region, WriteResponse};
+use tempdir::TempDir;
+
+use crate::compaction::SimplePicker;
+use crate::flush::{FlushStrategy, FlushStrategyRef};
+use crate::region::tests::{self, FileTesterBase};
+use crate::region::{RegionImpl, SharedDataRef};
+use crate::sst::Level;
+use crate::test_util::config_util;
+
+const REGION_NAME: &str = "region-compact-0";
+
+/// Create a new region for compaction test, compaction is triggered once there are
+/// `max_files_in_level0` files in level 0.
+async fn create_region_for_compaction(
+    store_dir: &str,
+    flush_strategy: FlushStrategyRef,
+    max_files_in_level0: usize,
+) -> RegionImpl<LocalFileLogStore> {
+    let metadata = tests::new_metadata(REGION_NAME, false);
+
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = flush_strategy;
+    store_config.compaction_picker = Arc::new(SimplePicker::new(max_files_in_level0));
+
+    RegionImpl::create(metadata, store_config).await.unwrap()
+}
+
+/// Tester for region compaction.
+struct CompactionTester {
+    base: Option<FileTesterBase>,
+    store_dir: String,
+    flush_strategy: FlushStrategyRef,
+}
+
+impl CompactionTester {
+    async fn new(
+        store_dir: &str,
+        flush_strategy: FlushStrategyRef,
+        max_files_in_level0: usize,
+    ) -> CompactionTester {
+        let region =
+            create_region_for_compaction(store_dir, flush_strategy.clone(), max_files_in_level0)
+                .await;
+
+        CompactionTester {
+            base: Some(FileTesterBase::with_region(region)),
+            store_dir: store_dir.to_string(),
+            flush_strategy,
+        }
+    }
+
+    async fn reopen(&mut self) {
+        // Close the old region.
+        self.base = None;
+        // Reopen the region.
+        let mut store_config = config_util::new_store_config(REGION_NAME, &self.store_dir).await;
+        store_config.flush_strategy = self.flush_strategy.clone();
+        let opts = OpenOptions::default();
+        let region = RegionImpl::open(REGION_NAME.to_string(), store_config, &opts)
+            .await
+            .unwrap()
+            .unwrap();
+        self.base = Some(FileTesterBase::with_region(region));
+    }
+
+    #[inline]
+    fn base(&self) -> &FileTesterBase {
+        self.base.as_ref().unwrap()
+    }
+
+    async fn put(&self, data: &[(i64, Option<i64>)]) -> WriteResponse {
+        self.base().put(data).await
+    }
+
+    async fn full_scan(&self) -> Vec<(i64, Option<i64>)> {
+        self.base().full_scan().await
+    }
+
+    async fn wait_flush_done(&self) {
+        self.base().region.wait_flush_done().await.unwrap();
+    }
+
+    async fn wait_compaction_done(&self) {
+        self.base().region.wait_compaction_done().await.unwrap();
+    }
+
+    async fn flush(&self) {
+        self.base().region.flush().await.unwrap();
+    }
+
+    async fn compact(&self) {
+        self.base().region.compact().await.unwrap();
+    }
+
+    fn set_ttl(&self, ttl: Option<Duration>) {
+        self.base().region.set_ttl(ttl);
+    }
+
+    fn file_num_in_level(&self, level: Level) -> usize {
+        let version = self.base().region.inner.version_control().current();
+        version.ssts().level(level).file_num()
+    }
+}
+
+#[derive(Debug, Default)]
+struct FlushSwitch {
+    should_flush: AtomicBool,
+}
+
+impl FlushSwitch {
+    fn set_should_flush(&self, should_flush: bool) {
+        self.should_flush.store(should_flush, Ordering::Relaxed);
+    }
+}
+
+impl FlushStrategy for FlushSwitch {
+    fn should_flush(
+        &self,
+        _shared: &SharedDataRef,
+        _bytes_mutable: usize,
+        _bytes_total: usize,
+    ) -> bool {
+        self.should_flush.load(Ordering::Relaxed)
+    }
+}
+
+#[tokio::test]
+async fn test_compact_after_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("compact-after-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = CompactionTester::new(store_dir, flush_switch.clone(), 2).await;
+
+    // Put elements so we have content to flush.
+    tester.put(&[(1000, Some(100))]).await;
+    tester.put(&[(2000, Some(200))]).await;
+
+    // Enable flush and put element to trigger flush (In SST1).
+    flush_switch.set_should_flush(true);
+    tester.put(&[(1000, Some(101))]).await;
+    tester.wait_flush_done().await;
+    // Only one file in level 0, no compaction.
+    tester.wait_compaction_done().await;
+    assert_eq!(1, tester.file_num_in_level(0));
+    assert_eq!(0, tester.file_num_in_level(1));
+
+    // Put element to trigger flush (In SST2), then the compaction is triggered.
+    tester.put(&[(3000, Some(300))]).await;
+    tester.wait_flush_done().await;
+    tester.wait_compaction_done().await;
+    assert_eq!(0, tester.file_num_in_level(0));
+    assert_eq!(1, tester.file_num_in_level(1));
+
+    let expect = vec![(1000, Some(101)), (2000, Some(200)), (3000, Some(300))];
+
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+
+    // Reopen
+    let mut tester = tester;
+    tester.reopen().await;
+    assert_eq!(0, tester.file_num_in_level(0));
+    assert_eq!(1, tester.file_num_in_level(1));
+
+    // Scan after reopen.
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+}
+
+#[tokio::test]
+async fn test_purge_expired_files() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("purge-expired-files").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    // Set a large threshold so only expired files would be picked.
+    let tester = CompactionTester::new(store_dir, flush_switch.clone(), 100).await;
+
+    let now = time_util::current_time_millis();
+    let hour = 3600 * 1000;
+    // Put elements so we have content to flush (In SST1).
+    tester.put(&[(now - 3 * hour, Some(100))]).await;
+    tester.put(&[(now - 2 * hour, Some(200))]).await;
+
+    // Enable flush and put element to trigger flush (In SST2).
+    flush_switch.set_should_flush(true);
+    tester.put(&[(now - hour / 2, Some(300))]).await;
+    tester.wait_flush_done().await;
+    tester.wait_compaction_done().await;
+    // Trigger flush again (In memtable).
+    tester.put(&[(now, Some(400))]).await;
+    tester.wait_flush_done().await;
+    tester.wait_compaction_done().await;
+    assert_eq!(2, tester.file_num_in_level(0));
+
+    // Expired rows are invisible once the ttl is set.
+    tester.set_ttl(Some(Duration::from_secs(3600)));
+    let output = tester.full_scan().await;
+    assert_eq!(vec![(now - hour / 2, Some(300)), (now, Some(400))], output);
+
+    // Trigger flush, then SST1 is removed as all rows in it are expired.
+    tester.put(&[(now + 1, Some(500))]).await;
+    tester.wait_flush_done().await;
+    tester.wait_compaction_done().await;
+    assert_eq!(2, tester.file_num_in_level(0));
+
+    let expect = vec![
+        (now - hour / 2, Some(300)),
+        (now, Some(400)),
+        (now + 1, Some(500)),
+    ];
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+
+    // Reopen without ttl, the removed file is not recovered from the manifest.
+    let mut tester = tester;
+    tester.reopen().await;
+    assert_eq!(2, tester.file_num_in_level(0));
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+}
+
+#[tokio::test]
+async fn test_manual_flush_and_compact() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("manual-flush-compact").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    // Never flush automatically and set a large threshold to avoid compaction after flush.
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = CompactionTester::new(store_dir, flush_switch, 100).await;
+
+    // Nothing to compact.
+    tester.compact().await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    tester.flush().await;
+    assert_eq!(1, tester.file_num_in_level(0));
+
+    tester.put(&[(2000, Some(200))]).await;
+    tester.put(&[(1000, Some(101))]).await;
+    tester.flush().await;
+    assert_eq!(2, tester.file_num_in_level(0));
+
+    tester.compact().await;
+    assert_eq!(0, tester.file_num_in_level(0));
+    assert_eq!(1, tester.file_num_in_level(1));
+
+    let expect = vec![(1000, Some(101)), (2000, Some(200))];
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+
+    let mut tester = tester;
+    tester.reopen().await;
+    assert_eq!(1, tester.file_num_in_level(1));
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+}
+
+#[tokio::test]
+async fn test_purge_expired_files_without_write() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("purge-expired-without-write").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    // Never flush automatically and check expired files frequently.
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let metadata = tests::new_metadata(REGION_NAME, false);
+    let mut store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    store_config.flush_strategy = flush_switch.clone();
+    store_config.compaction_picker = Arc::new(SimplePicker::new(100));
+    store_config.ttl_check_interval = Duration::from_millis(100);
+    let region = RegionImpl::create(metadata, store_config).await.unwrap();
+    let tester = CompactionTester {
+        base: Some(FileTesterBase::with_region(region)),
+        store_dir: store_dir.to_string(),
+        flush_strategy: flush_switch,
+    };
+
+    let now = time_util::current_time_millis();
+    let hour = 3600 * 1000;
+    tester.put(&[(now - 3 * hour, Some(100))]).await;
+    tester.flush().await;
+    tester.put(&[(now, Some(200))]).await;
+    tester.flush().await;
+    assert_eq!(2, tester.file_num_in_level(0));
+
+    // No more writes and flushes after setting the ttl, the expired file is removed by the
+    // ttl checker.
+    tester.set_ttl(Some(Duration::from_secs(3600)));
+    for _ in 0..50 {
+        if tester.file_num_in_level(0) == 1 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    assert_eq!(1, tester.file_num_in_level(0));
+
+    let output = tester.full_scan().await;
+    assert_eq!(vec![(now, Some(200))], output);
+}
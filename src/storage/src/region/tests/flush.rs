@@ -17,15 +17,21 @@
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
+use common_time::Timestamp;
+use datafusion_common::{Column, ScalarValue};
+use datafusion_expr::Expr;
 use log_store::fs::log::LocalFileLogStore;
-use store_api::storage::{OpenOptions, WriteResponse};
+use store_api::manifest::Manifest;
+use store_api::storage::{OpenOptions, Region, ScanRequest, WriteContext, WriteResponse};
 use tempdir::TempDir;
 
 use crate::engine;
+use crate::error::Error;
 use crate::flush::{FlushStrategy, FlushStrategyRef};
 use crate::region::tests::{self, FileTesterBase};
 use crate::region::{RegionImpl, SharedDataRef};
-use crate::test_util::config_util;
+use crate::sst::FileMeta;
+use crate::test_util::{self, config_util};
 
 const REGION_NAME: &str = "region-flush-0";
 
@@ -84,13 +90,29 @@ impl FlushTester {
         self.base().put(data).await
     }
 
+    async fn delete(&self, keys: &[i64]) -> WriteResponse {
+        self.base().delete(keys).await
+    }
+
     async fn full_scan(&self) -> Vec<(i64, Option<i64>)> {
         self.base().full_scan().await
     }
 
+    async fn scan(&self, req: ScanRequest) -> Vec<(i64, Option<i64>)> {
+        self.base().scan(req).await
+    }
+
     async fn wait_flush_done(&self) {
         self.base().region.wait_flush_done().await.unwrap();
     }
+
+    /// Returns metas of files in level 0, sorted by time range.
+    fn level0_file_metas(&self) -> Vec<FileMeta> {
+        let version = self.base().region.inner.version_control().current();
+        let mut metas: Vec<_> = version.ssts().level(0).files().map(|f| f.meta()).collect();
+        metas.sort_unstable_by_key(|m| m.time_range);
+        metas
+    }
 }
 
 #[derive(Debug, Default)]
@@ -115,6 +137,16 @@ impl FlushStrategy for FlushSwitch {
     }
 }
 
+fn has_manifest_file(manifest_dir: &str) -> bool {
+    for entry in std::fs::read_dir(manifest_dir).unwrap() {
+        if !entry.unwrap().path().is_dir() {
+            return true;
+        }
+    }
+
+    false
+}
+
 fn has_parquet_file(sst_dir: &str) -> bool {
     for entry in std::fs::read_dir(sst_dir).unwrap() {
         let entry = entry.unwrap();
@@ -222,6 +254,40 @@ async fn test_read_after_flush() {
     assert_eq!(expect, output);
 }
 
+#[tokio::test]
+async fn test_reopen_after_checkpoint() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("reopen-checkpoint").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let mut tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    flush_switch.set_should_flush(true);
+    // Put element to trigger flush.
+    tester.put(&[(2000, Some(200))]).await;
+    tester.wait_flush_done().await;
+    flush_switch.set_should_flush(false);
+
+    let manifest = &tester.base().region.inner.manifest;
+    let last_version = manifest.checkpoint().await.unwrap().unwrap();
+    assert_eq!(last_version + 1, manifest.last_version());
+    // Put data not flushed.
+    tester.put(&[(3000, Some(300))]).await;
+
+    let file_metas = tester.level0_file_metas();
+    assert!(!file_metas.is_empty());
+    let expect = vec![(1000, Some(100)), (2000, Some(200)), (3000, Some(300))];
+    assert_eq!(expect, tester.full_scan().await);
+
+    tester.reopen().await;
+
+    assert_eq!(file_metas, tester.level0_file_metas());
+    assert_eq!(expect, tester.full_scan().await);
+}
+
 #[tokio::test]
 async fn test_merge_read_after_flush() {
     let dir = TempDir::new("merge-read-flush").unwrap();
@@ -266,3 +332,213 @@ async fn test_merge_read_after_flush() {
     let output = tester.full_scan().await;
     assert_eq!(expect, output);
 }
+
+#[tokio::test]
+async fn test_delete_after_flush() {
+    let dir = TempDir::new("delete-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    // Put elements so we have content to flush (In SST1).
+    tester.put(&[(1000, Some(100)), (2000, Some(200))]).await;
+    tester.put(&[(3000, Some(300))]).await;
+
+    // Now set should flush to true and delete a row to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.delete(&[1000]).await;
+    tester.wait_flush_done().await;
+
+    // Disable flush and delete a row in the SST (In memtable).
+    flush_switch.set_should_flush(false);
+    tester.delete(&[3000]).await;
+
+    let expect = vec![(2000, Some(200))];
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+
+    // Reopen
+    let mut tester = tester;
+    tester.reopen().await;
+
+    // Scan after reopen.
+    let output = tester.full_scan().await;
+    assert_eq!(expect, output);
+}
+
+#[tokio::test]
+async fn test_scan_with_time_range_after_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("scan-time-range").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    // Put elements so we have content to flush (In SST1).
+    tester.put(&[(2000, Some(200))]).await;
+    tester.put(&[(1000, Some(100))]).await;
+
+    // Enable flush and put element to trigger flush (In SST2).
+    flush_switch.set_should_flush(true);
+    tester.put(&[(3000, Some(300))]).await;
+    tester.wait_flush_done().await;
+
+    // Trigger flush again (In memtable).
+    tester.put(&[(5000, Some(500))]).await;
+    tester.wait_flush_done().await;
+
+    let metas = tester.level0_file_metas();
+    assert_eq!(2, metas.len());
+    assert_eq!(
+        Some((Timestamp::from_millis(1000), Timestamp::from_millis(2000))),
+        metas[0].time_range
+    );
+    assert_eq!(2, metas[0].num_rows);
+    assert!(metas[0].file_size > 0);
+    assert_eq!(
+        Some((Timestamp::from_millis(3000), Timestamp::from_millis(3000))),
+        metas[1].time_range
+    );
+    assert_eq!(1, metas[1].num_rows);
+
+    let ts_col = || Expr::Column(Column::from_name(test_util::TIMESTAMP_NAME));
+    let ts_lit = |v| Expr::Literal(ScalarValue::TimestampMillisecond(Some(v), None));
+
+    // Only SST2 and memtable match the filter.
+    let req = ScanRequest {
+        filters: vec![ts_col().gt_eq(ts_lit(2500)).into()],
+        ..Default::default()
+    };
+    let output = tester.scan(req).await;
+    assert_eq!(vec![(3000, Some(300)), (5000, Some(500))], output);
+
+    // Only SST1 matches the filter. Filters on the time index are exact, so rows in
+    // memtable are also filtered out.
+    let req = ScanRequest {
+        filters: vec![ts_col().lt(ts_lit(2500)).into()],
+        ..Default::default()
+    };
+    let output = tester.scan(req).await;
+    assert_eq!(vec![(1000, Some(100)), (2000, Some(200))], output);
+
+    // Stops reading once the limit is reached.
+    let req = ScanRequest {
+        limit: Some(2),
+        ..Default::default()
+    };
+    let output = tester.scan(req).await;
+    assert_eq!(vec![(1000, Some(100)), (2000, Some(200))], output);
+
+    // Overwrites a row in SST1, the newest rows are returned in a descending scan.
+    flush_switch.set_should_flush(false);
+    tester.put(&[(1000, Some(101))]).await;
+    let req = ScanRequest {
+        descending: true,
+        ..Default::default()
+    };
+    let output = tester.scan(req).await;
+    assert_eq!(
+        vec![
+            (5000, Some(500)),
+            (3000, Some(300)),
+            (2000, Some(200)),
+            (1000, Some(101))
+        ],
+        output
+    );
+
+    // Returns the latest rows.
+    let req = ScanRequest {
+        limit: Some(2),
+        descending: true,
+        ..Default::default()
+    };
+    let output = tester.scan(req).await;
+    assert_eq!(vec![(5000, Some(500)), (3000, Some(300))], output);
+
+    // Reopen and the time range is recovered from the manifest.
+    let mut tester = tester;
+    tester.reopen().await;
+    assert_eq!(metas, tester.level0_file_metas());
+}
+
+#[tokio::test]
+async fn test_drop_region_after_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("drop-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    // Now set should flush to true to trigger flush.
+    flush_switch.set_should_flush(true);
+    // Put element to trigger flush.
+    tester.put(&[(2000, Some(200))]).await;
+    tester.wait_flush_done().await;
+
+    let sst_dir = format!("{}/{}", store_dir, engine::region_sst_dir("", REGION_NAME));
+    let manifest_dir = format!(
+        "{}/{}",
+        store_dir,
+        engine::region_manifest_dir("", REGION_NAME)
+    );
+    assert!(has_parquet_file(&sst_dir));
+    assert!(has_manifest_file(&manifest_dir));
+
+    let region = &tester.base().region;
+    region.drop_region().await.unwrap();
+
+    // All sst and manifest files are removed.
+    assert!(!has_parquet_file(&sst_dir));
+    assert!(!has_manifest_file(&manifest_dir));
+
+    // The dropped region rejects writes.
+    let err = region
+        .write(&WriteContext::default(), region.write_request())
+        .await
+        .err()
+        .unwrap();
+    assert!(matches!(err, Error::ClosedRegion { .. }));
+
+    // The dropped region could not be opened again.
+    let store_config = config_util::new_store_config(REGION_NAME, store_dir).await;
+    let opts = OpenOptions::default();
+    let region = RegionImpl::open(REGION_NAME.to_string(), store_config, &opts)
+        .await
+        .unwrap();
+    assert!(region.is_none());
+}
+
+#[tokio::test]
+async fn test_region_stat_after_flush() {
+    common_telemetry::init_default_ut_logging();
+
+    let dir = TempDir::new("region-stat-flush").unwrap();
+    let store_dir = dir.path().to_str().unwrap();
+
+    let flush_switch = Arc::new(FlushSwitch::default());
+    let tester = FlushTester::new(store_dir, flush_switch.clone()).await;
+
+    tester.put(&[(1000, Some(100))]).await;
+    let stat = tester.base().region.stat();
+    assert_eq!(REGION_NAME, stat.region_name);
+    assert!(stat.memtable_bytes > 0);
+    assert_eq!(0, stat.sst_num);
+    assert_eq!(0, stat.sst_bytes);
+
+    // Put element to trigger flush.
+    flush_switch.set_should_flush(true);
+    tester.put(&[(2000, Some(200))]).await;
+    tester.wait_flush_done().await;
+
+    let stat = tester.base().region.stat();
+    assert_eq!(1, stat.sst_num);
+    assert!(stat.sst_bytes > 0);
+    assert!(stat.flushed_sequence > 0);
+}
@@ -12,24 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex as StdMutex};
 
 use common_telemetry::logging;
 use futures::TryStreamExt;
-use snafu::ResultExt;
+use metrics::gauge;
+use snafu::{ensure, IntoError, OptionExt, ResultExt};
 use store_api::logstore::LogStore;
 use store_api::manifest::{Manifest, ManifestVersion, MetaAction};
 use store_api::storage::{AlterRequest, SequenceNumber, WriteContext, WriteResponse};
-use tokio::sync::Mutex;
+use tokio::sync::{oneshot, Mutex};
 
 use crate::background::JobHandle;
-use crate::error::{self, Result};
+use crate::compaction::{
+    CompactionInput, CompactionJob, CompactionPickerRef, CompactionSchedulerRef,
+};
+use crate::error::{self, Error, Result};
 use crate::flush::{FlushJob, FlushSchedulerRef, FlushStrategyRef};
 use crate::manifest::action::{
     RawRegionMetadata, RegionChange, RegionEdit, RegionMetaAction, RegionMetaActionList,
 };
 use crate::memtable::{Inserter, MemtableBuilderRef, MemtableId, MemtableRef};
 use crate::metadata::RegionMetadataRef;
+use crate::metric::{
+    LABEL_LEVEL, LABEL_REGION, METRIC_MEMTABLE_IMMUTABLE_BYTES, METRIC_MEMTABLE_MUTABLE_BYTES,
+    METRIC_SST_FILES,
+};
 use crate::proto::wal::WalHeader;
 use crate::region::{RecoverdMetadata, RecoveredMetadataMap, RegionManifest, SharedDataRef};
 use crate::schema::compat::CompatWrite;
@@ -40,7 +48,7 @@ use crate::write_batch::WriteBatch;
 
 pub type RegionWriterRef = Arc<RegionWriter>;
 
-// TODO(yingwen): Add benches for write and support group commit to improve write throughput.
+// TODO(yingwen): Add benches for write.
 
 /// Region writer manages all write operations to the region.
 #[derive(Debug)]
@@ -49,31 +57,69 @@ pub struct RegionWriter {
     /// Inner writer guarded by write lock, the write lock is used to ensure
     /// all write operations are serialized.
     inner: Mutex<WriterInner>,
+    /// Write requests waiting to be committed.
+    ///
+    /// Concurrent writes are queued here, then the writer holding the write lock commits
+    /// all queued requests as a group (group commit).
+    pending_writes: StdMutex<Vec<PendingWrite>>,
     /// Version lock, protects read-write-update to region `Version`.
     ///
     /// Increasing committed sequence should be guarded by this lock.
     version_mutex: Mutex<()>,
+    /// Handle to the last scheduled compaction job.
+    compaction_handle: StdMutex<Option<JobHandle>>,
+    /// Lock to pick files and mark them compacting atomically, since compactions might be
+    /// scheduled by flush jobs and the ttl checker concurrently.
+    pick_mutex: StdMutex<()>,
 }
 
 impl RegionWriter {
     pub fn new(memtable_builder: MemtableBuilderRef) -> RegionWriter {
         RegionWriter {
             inner: Mutex::new(WriterInner::new(memtable_builder)),
+            pending_writes: StdMutex::new(Vec::new()),
             version_mutex: Mutex::new(()),
+            compaction_handle: StdMutex::new(None),
+            pick_mutex: StdMutex::new(()),
         }
     }
 
     /// Write to region in the write lock.
+    ///
+    /// The request is queued first. Whoever acquires the write lock takes all queued
+    /// requests and commits them together, so the request has been committed once
+    /// the write lock is acquired and released.
+    ///
+    /// The returned future must be polled to completion, cancelling it in the middle of a
+    /// group commit leaves the WAL and the memtable inconsistent, so callers should run it
+    /// in a spawned task.
     pub async fn write<S: LogStore>(
         &self,
-        ctx: &WriteContext,
+        _ctx: &WriteContext,
         request: WriteBatch,
         writer_ctx: WriterContext<'_, S>,
     ) -> Result<WriteResponse> {
-        let mut inner = self.inner.lock().await;
-        inner
-            .write(&self.version_mutex, ctx, request, writer_ctx)
-            .await
+        let (sender, receiver) = oneshot::channel();
+        self.pending_writes
+            .lock()
+            .unwrap()
+            .push(PendingWrite { request, sender });
+
+        {
+            let mut inner = self.inner.lock().await;
+            let writes = std::mem::take(&mut *self.pending_writes.lock().unwrap());
+            if !writes.is_empty() {
+                inner
+                    .write_group(&self.version_mutex, writes, &writer_ctx)
+                    .await;
+            }
+        }
+
+        // The sender is dropped without sending if the writer committing our request is
+        // cancelled.
+        receiver.await.ok().context(error::WriteCancelledSnafu {
+            region: writer_ctx.shared.name(),
+        })?
     }
 
     /// Replay data to memtables.
@@ -95,7 +141,7 @@ impl RegionWriter {
         shared: &SharedDataRef,
         manifest: &RegionManifest,
         edit: RegionEdit,
-        max_memtable_id: MemtableId,
+        max_memtable_id: Option<MemtableId>,
     ) -> Result<()> {
         let _lock = self.version_mutex.lock().await;
         // HACK: We won't acquire the write lock here because write stall would hold
@@ -112,6 +158,7 @@ impl RegionWriter {
         );
 
         let files_to_add = edit.files_to_add.clone();
+        let files_to_remove = edit.files_to_remove.clone();
         let flushed_sequence = edit.flushed_sequence;
 
         // Persist the meta action.
@@ -121,14 +168,16 @@ impl RegionWriter {
 
         let version_edit = VersionEdit {
             files_to_add,
+            files_to_remove,
             flushed_sequence: Some(flushed_sequence),
             manifest_version,
-            max_memtable_id: Some(max_memtable_id),
+            max_memtable_id,
         };
 
         // We could tolerate failure during persisting manifest version to the WAL, since it won't
         // affect how we applying the edit to the version.
         version_control.apply_edit(version_edit);
+        record_sst_files(shared);
         // TODO(yingwen): We should set the flush handle to `None`, but we can't acquire
         // write lock here.
 
@@ -138,6 +187,214 @@ impl RegionWriter {
             .await
     }
 
+    /// Picks files to compact by `pick` and marks them as compacting, so other compactions
+    /// of the region won't pick the same files.
+    pub(crate) fn pick_compaction<F>(&self, pick: F) -> Option<CompactionInput>
+    where
+        F: FnOnce() -> Option<CompactionInput>,
+    {
+        let _lock = self.pick_mutex.lock().unwrap();
+        let input = pick()?;
+        input.mark_compacting(true);
+        Some(input)
+    }
+
+    /// Set the handle to the last scheduled compaction job.
+    pub(crate) fn set_compaction_handle(&self, handle: JobHandle) {
+        let mut compaction_handle = self.compaction_handle.lock().unwrap();
+        *compaction_handle = Some(handle);
+    }
+
+    /// Close the writer, rejects all following write operations and waits until
+    /// running background jobs are finished.
+    pub async fn close(&self, shared: &SharedDataRef) {
+        // Holding the write lock ensures there is no in-flight write and no new flush
+        // job could be scheduled.
+        let mut inner = self.inner.lock().await;
+        inner.closed = true;
+
+        if let Some(handle) = inner.flush_handle.take() {
+            // The region is closing, so we only log the error of the flush job.
+            if let Err(e) = handle.join().await {
+                logging::error!(
+                    e; "Flush job failed while closing region: {}",
+                    shared.name()
+                );
+            }
+        }
+
+        // The flush job might schedule a compaction job, so we wait for the compaction job
+        // after the flush job is finished.
+        let handle = self.compaction_handle.lock().unwrap().take();
+        if let Some(handle) = handle {
+            if let Err(e) = handle.join().await {
+                logging::error!(
+                    e; "Compaction job failed while closing region: {}",
+                    shared.name()
+                );
+            }
+        }
+    }
+
+    /// Flush all memtables of the region and waits until the flush job is finished.
+    ///
+    /// The write lock is only held to schedule the flush job, so writes to the region
+    /// are not blocked while waiting for the job.
+    pub async fn flush<S: LogStore>(&self, writer_ctx: WriterContext<'_, S>) -> Result<()> {
+        let handle = {
+            let mut inner = self.inner.lock().await;
+            ensure!(
+                !inner.closed,
+                error::ClosedRegionSnafu {
+                    region: writer_ctx.shared.name(),
+                }
+            );
+
+            inner.trigger_flush(&writer_ctx).await?;
+            // The handle is left in the writer, so the next flush and closing the region
+            // still wait for the job.
+            inner.flush_handle.clone()
+        };
+
+        if let Some(handle) = handle {
+            handle.join().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Compact the region regardless of the compaction threshold and waits until the
+    /// compaction job is finished.
+    ///
+    /// The write lock is only held to take handles of running jobs and to schedule the
+    /// compaction job, so writes to the region are not blocked while waiting for jobs.
+    pub async fn compact<S: LogStore>(&self, writer_ctx: WriterContext<'_, S>) -> Result<()> {
+        let shared = writer_ctx.shared;
+        let flush_handle = {
+            let inner = self.inner.lock().await;
+            ensure!(
+                !inner.closed,
+                error::ClosedRegionSnafu {
+                    region: shared.name(),
+                }
+            );
+            inner.flush_handle.clone()
+        };
+
+        // Wait for the running flush job and the compaction job it schedules, so files of
+        // the flush job are compacted too.
+        if let Some(handle) = flush_handle {
+            handle.join().await?;
+        }
+        let handle = self.compaction_handle.lock().unwrap().clone();
+        if let Some(handle) = handle {
+            handle.join().await?;
+        }
+
+        // Holding the write lock ensures the region won't be closed while scheduling the job.
+        let inner = self.inner.lock().await;
+        ensure!(
+            !inner.closed,
+            error::ClosedRegionSnafu {
+                region: shared.name(),
+            }
+        );
+        let version = shared.version_control.current();
+        let input = match self.pick_compaction(|| {
+            writer_ctx
+                .compaction_picker
+                .pick_manual(version.ssts(), shared.expire_before())
+        }) {
+            Some(input) => input,
+            None => {
+                logging::info!("No files to compact in region: {}", shared.name());
+                return Ok(());
+            }
+        };
+
+        logging::info!(
+            "Compact region manually: {}, output_level: {}, inputs: {}, expired: {}",
+            shared.name(),
+            input.output_level,
+            input.inputs.len(),
+            input.expired.len(),
+        );
+
+        let compaction_job = CompactionJob {
+            input,
+            shared: shared.clone(),
+            sst_layer: writer_ctx.sst_layer.clone(),
+            writer: writer_ctx.writer.clone(),
+            wal: writer_ctx.wal.clone(),
+            manifest: writer_ctx.manifest.clone(),
+        };
+        let handle = writer_ctx
+            .compaction_scheduler
+            .schedule_compaction(Box::new(compaction_job))
+            .await?;
+        // Closing the region waits for the job by its handle.
+        self.set_compaction_handle(handle.clone());
+        drop(inner);
+
+        handle.join().await
+    }
+
+    /// Schedules a compaction job that only removes files whose rows are all expired,
+    /// without waiting for the job.
+    ///
+    /// Flush jobs also remove expired files, but a region without writes never flushes.
+    pub async fn purge_expired<S: LogStore>(&self, writer_ctx: WriterContext<'_, S>) -> Result<()> {
+        // Holding the write lock ensures the region won't be closed while scheduling the job.
+        let inner = self.inner.lock().await;
+        let shared = writer_ctx.shared;
+        ensure!(
+            !inner.closed,
+            error::ClosedRegionSnafu {
+                region: shared.name(),
+            }
+        );
+
+        let expire_before = match shared.expire_before() {
+            Some(expire_before) => expire_before,
+            None => return Ok(()),
+        };
+        let version = shared.version_control.current();
+        let input = match self.pick_compaction(|| {
+            writer_ctx
+                .compaction_picker
+                .pick_expired(version.ssts(), expire_before)
+        }) {
+            Some(input) => input,
+            None => return Ok(()),
+        };
+
+        logging::info!(
+            "Schedule purging expired files in region: {}, expired: {:?}",
+            shared.name(),
+            input
+                .expired
+                .iter()
+                .map(|f| f.file_name())
+                .collect::<Vec<_>>(),
+        );
+
+        let compaction_job = CompactionJob {
+            input,
+            shared: shared.clone(),
+            sst_layer: writer_ctx.sst_layer.clone(),
+            writer: writer_ctx.writer.clone(),
+            wal: writer_ctx.wal.clone(),
+            manifest: writer_ctx.manifest.clone(),
+        };
+        let handle = writer_ctx
+            .compaction_scheduler
+            .schedule_compaction(Box::new(compaction_job))
+            .await?;
+        self.set_compaction_handle(handle);
+
+        Ok(())
+    }
+
     /// Alter schema of the region.
     pub async fn alter<S: LogStore>(
         &self,
@@ -149,6 +406,12 @@ impl RegionWriter {
         // Another potential benefit is that the write lock also protect against concurrent
         // alter request to the region.
         let inner = self.inner.lock().await;
+        ensure!(
+            !inner.closed,
+            error::ClosedRegionSnafu {
+                region: alter_ctx.shared.name(),
+            }
+        );
 
         let version_control = alter_ctx.version_control();
 
@@ -234,6 +497,15 @@ impl RegionWriter {
 
         Ok(())
     }
+
+    pub async fn wait_compaction_done(&self) -> Result<()> {
+        let handle = self.compaction_handle.lock().unwrap().take();
+        if let Some(handle) = handle {
+            handle.join().await?;
+        }
+
+        Ok(())
+    }
 }
 
 pub struct WriterContext<'a, S: LogStore> {
@@ -244,6 +516,8 @@ pub struct WriterContext<'a, S: LogStore> {
     pub wal: &'a Wal<S>,
     pub writer: &'a RegionWriterRef,
     pub manifest: &'a RegionManifest,
+    pub compaction_picker: &'a CompactionPickerRef,
+    pub compaction_scheduler: &'a CompactionSchedulerRef,
 }
 
 impl<'a, S: LogStore> WriterContext<'a, S> {
@@ -266,10 +540,49 @@ impl<'a, S: LogStore> AlterContext<'a, S> {
     }
 }
 
+/// A write request waiting in the queue of the group commit.
+#[derive(Debug)]
+struct PendingWrite {
+    request: WriteBatch,
+    sender: oneshot::Sender<Result<WriteResponse>>,
+}
+
+impl PendingWrite {
+    fn notify(self, result: Result<WriteResponse>) {
+        // The waiter might be cancelled, so ignore the error.
+        let _ = self.sender.send(result);
+    }
+}
+
+/// Fails all `writes` with the same error `err`.
+fn notify_error(writes: Vec<PendingWrite>, err: Error, region: &str) {
+    let err = Arc::new(err);
+    for write in writes {
+        let result = error::GroupCommitSnafu { region }.into_error(err.clone());
+        write.notify(Err(result));
+    }
+}
+
+/// Records the number of SSTs in each level of the region.
+fn record_sst_files(shared: &SharedDataRef) {
+    let version = shared.version_control.current();
+    let region = shared.id().to_string();
+    for level in version.ssts().levels() {
+        gauge!(
+            METRIC_SST_FILES,
+            level.file_num() as f64,
+            LABEL_REGION => region.clone(),
+            LABEL_LEVEL => level.level().to_string()
+        );
+    }
+}
+
 #[derive(Debug)]
 struct WriterInner {
     memtable_builder: MemtableBuilderRef,
     flush_handle: Option<JobHandle>,
+    /// Whether the region is closed, a closed region rejects all write operations.
+    closed: bool,
 }
 
 impl WriterInner {
@@ -277,56 +590,100 @@ impl WriterInner {
         WriterInner {
             memtable_builder,
             flush_handle: None,
+            closed: false,
         }
     }
 
-    /// Write `WriteBatch` to region, now the schema of batch needs to be validated outside.
+    /// Commit a group of `WriteBatch`es to region, now the schema of batches needs to be
+    /// validated outside. Results are sent to the waiters of the `writes`.
+    ///
+    /// Batches are validated against the mutable memtable first, then the valid batches are
+    /// written to the WAL in one append with contiguous sequences, inserted into the memtable
+    /// and made visible together. Invalid batches fail before the WAL append, so every batch
+    /// in the WAL is committed and its sequence is never reused.
     ///
     /// Mutable reference of writer ensure no other reference of this writer can modify the
     /// version control (write is exclusive).
-    async fn write<S: LogStore>(
+    async fn write_group<S: LogStore>(
         &mut self,
         version_mutex: &Mutex<()>,
-        _ctx: &WriteContext,
-        mut request: WriteBatch,
-        writer_ctx: WriterContext<'_, S>,
-    ) -> Result<WriteResponse> {
-        self.preprocess_write(&writer_ctx).await?;
+        writes: Vec<PendingWrite>,
+        writer_ctx: &WriterContext<'_, S>,
+    ) {
+        let region = writer_ctx.shared.name();
+        if self.closed {
+            for write in writes {
+                let result = error::ClosedRegionSnafu { region }.fail();
+                write.notify(result);
+            }
+            return;
+        }
+
+        if let Err(e) = self.preprocess_write(writer_ctx).await {
+            notify_error(writes, e, region);
+            return;
+        }
         let version_control = writer_ctx.version_control();
 
         let _lock = version_mutex.lock().await;
 
         let metadata = version_control.metadata();
+        let version = version_control.current();
         // We need to check the schema again since it might has been altered. We need
         // to compat request's schema before writing it into the WAL otherwise some
         // default constraint like `current_timestamp()` would yield different value
-        // during replay.
-        request.compat_write(metadata.schema().user_schema())?;
+        // during replay. Batches failing to insert into the memtable are also rejected
+        // here, as they would be replayed from the WAL.
+        let mut valid_writes = Vec::with_capacity(writes.len());
+        for mut write in writes {
+            let validated = write
+                .request
+                .compat_write(metadata.schema().user_schema())
+                .and_then(|()| Inserter::validate(&write.request, version.mutable_memtable()));
+            match validated {
+                Ok(()) => valid_writes.push(write),
+                Err(e) => write.notify(Err(e)),
+            }
+        }
+        if valid_writes.is_empty() {
+            return;
+        }
 
         let committed_sequence = version_control.committed_sequence();
-        // Sequence for current write batch.
-        let next_sequence = committed_sequence + 1;
+        // Sequence for the first write batch of the group.
+        let start_sequence = committed_sequence + 1;
 
-        let version = version_control.current();
         let wal_header = WalHeader::with_last_manifest_version(version.manifest_version());
-        writer_ctx
+        let batches = valid_writes
+            .iter()
+            .map(|write| &write.request)
+            .collect::<Vec<_>>();
+        if let Err(e) = writer_ctx
             .wal
-            .write_to_wal(
-                next_sequence,
-                wal_header,
-                Payload::WriteBatchArrow(&request),
-            )
-            .await?;
+            .write_batches_to_wal(start_sequence, wal_header, &batches)
+            .await
+        {
+            notify_error(valid_writes, e, region);
+            return;
+        }
 
-        // Insert batch into memtable.
-        let mut inserter = Inserter::new(next_sequence);
-        inserter.insert_memtable(&request, version.mutable_memtable())?;
+        // Insert batches into memtable, the batches are validated so inserting them won't
+        // fail.
+        let mut results = Vec::with_capacity(valid_writes.len());
+        for (write, sequence) in valid_writes.iter().zip(start_sequence..) {
+            let mut inserter = Inserter::new(sequence);
+            results.push(inserter.insert_memtable(&write.request, version.mutable_memtable()));
+        }
 
-        // Update committed_sequence to make current batch visible. The `&mut self` of WriterInner
-        // guarantees the writer is exclusive.
-        version_control.set_committed_sequence(next_sequence);
+        // Update committed_sequence to make inserted batches visible. All sequences written
+        // to the WAL are committed, so they won't be reused by the next group. The `&mut self`
+        // of WriterInner guarantees the writer is exclusive.
+        version_control
+            .set_committed_sequence(committed_sequence + valid_writes.len() as SequenceNumber);
 
-        Ok(WriteResponse {})
+        for (write, result) in valid_writes.into_iter().zip(results) {
+            write.notify(result.map(|()| WriteResponse {}));
+        }
     }
 
     async fn replay<S: LogStore>(
@@ -512,6 +869,17 @@ impl WriterInner {
         let memtables = current.memtables();
         let mutable_bytes_allocated = memtables.mutable_bytes_allocated();
         let total_bytes_allocated = memtables.total_bytes_allocated();
+        let region = shared.id().to_string();
+        gauge!(
+            METRIC_MEMTABLE_MUTABLE_BYTES,
+            mutable_bytes_allocated as f64,
+            LABEL_REGION => region.clone()
+        );
+        gauge!(
+            METRIC_MEMTABLE_IMMUTABLE_BYTES,
+            (total_bytes_allocated - mutable_bytes_allocated) as f64,
+            LABEL_REGION => region
+        );
         flush_strategy.should_flush(shared, mutable_bytes_allocated, total_bytes_allocated)
     }
 
@@ -554,6 +922,8 @@ impl WriterInner {
             writer: ctx.writer.clone(),
             wal: ctx.wal.clone(),
             manifest: ctx.manifest.clone(),
+            compaction_picker: ctx.compaction_picker.clone(),
+            compaction_scheduler: ctx.compaction_scheduler.clone(),
         };
 
         let flush_handle = ctx
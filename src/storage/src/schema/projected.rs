@@ -18,9 +18,10 @@ use std::sync::Arc;
 
 use common_base::BitVec;
 use common_error::prelude::*;
+use datatypes::prelude::ScalarVector;
 use datatypes::schema::{SchemaBuilder, SchemaRef};
-use datatypes::vectors::BooleanVector;
-use store_api::storage::{Chunk, ColumnId};
+use datatypes::vectors::{BooleanVector, UInt8Vector};
+use store_api::storage::{Chunk, ColumnId, OpType};
 
 use crate::error;
 use crate::metadata::{self, Result};
@@ -317,6 +318,29 @@ impl BatchOp for ProjectedSchema {
         }
     }
 
+    fn unselect_deleted(&self, batch: &Batch, selected: &mut BitVec) {
+        let op_types = batch.column(self.schema_to_read.op_type_index());
+        // Safety: We expect the batch has the same schema as `self.schema_to_read`. The
+        // read procedure should guarantee this, otherwise this is a critical bug and it
+        // should be fine to panic.
+        let op_types = op_types
+            .as_any()
+            .downcast_ref::<UInt8Vector>()
+            .unwrap_or_else(|| {
+                panic!(
+                    "Expect op_type (UInt8) column at index {}, given {:?}",
+                    self.schema_to_read.op_type_index(),
+                    op_types.data_type()
+                );
+            });
+
+        for (i, op_type) in op_types.iter_data().enumerate() {
+            if op_type == Some(OpType::Delete.as_u8()) {
+                selected.set(i, false);
+            }
+        }
+    }
+
     fn filter(&self, batch: &Batch, filter: &BooleanVector) -> error::Result<Batch> {
         let columns = batch
             .columns()
@@ -517,6 +541,22 @@ mod tests {
         assert!(!selected[2]);
     }
 
+    #[test]
+    fn test_unselect_deleted() {
+        let schema = read_util::new_projected_schema();
+        let batch = read_util::new_full_kv_batch(&[
+            (1000, 1, 1000, OpType::Put),
+            (2000, 2, 1000, OpType::Delete),
+            (3000, 3, 1000, OpType::Put),
+        ]);
+
+        let mut selected = BitVec::repeat(true, 3);
+        schema.unselect_deleted(&batch, &mut selected);
+        assert!(selected[0]);
+        assert!(!selected[1]);
+        assert!(selected[2]);
+    }
+
     #[test]
     fn test_filter_batch() {
         let schema = read_util::new_projected_schema();
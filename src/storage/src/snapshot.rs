@@ -15,6 +15,7 @@
 use std::cmp;
 
 use async_trait::async_trait;
+use common_time::Timestamp;
 use store_api::storage::{
     GetRequest, GetResponse, ReadContext, ScanRequest, ScanResponse, SchemaRef, SequenceNumber,
     Snapshot,
@@ -30,6 +31,8 @@ pub struct SnapshotImpl {
     version: VersionRef,
     /// Max sequence number (inclusive) visible to user.
     visible_sequence: SequenceNumber,
+    /// Rows before this timestamp are expired and invisible to user.
+    expire_before: Option<Timestamp>,
     sst_layer: AccessLayerRef,
 }
 
@@ -58,8 +61,12 @@ impl Snapshot for SnapshotImpl {
                 .reserve_num_memtables(memtable_version.num_memtables())
                 .projection(request.projection)
                 .filters(request.filters)
+                .limit(request.limit)
+                .descending(request.descending)
                 .batch_size(ctx.batch_size)
+                .cancellation(ctx.cancellation.clone())
                 .visible_sequence(visible_sequence)
+                .expire_before(self.expire_before)
                 .pick_memtables(mutables.clone());
 
         for memtable in immutables {
@@ -80,11 +87,13 @@ impl SnapshotImpl {
     pub fn new(
         version: VersionRef,
         visible_sequence: SequenceNumber,
+        expire_before: Option<Timestamp>,
         sst_layer: AccessLayerRef,
     ) -> SnapshotImpl {
         SnapshotImpl {
             version,
             visible_sequence,
+            expire_before,
             sst_layer,
         }
     }
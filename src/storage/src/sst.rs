@@ -12,23 +12,34 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod cache;
 mod parquet;
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use common_telemetry::logging;
+use common_time::Timestamp;
 use object_store::{util, ObjectStore};
 use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
 use table::predicate::Predicate;
+use uuid::Uuid;
 
-use crate::error::Result;
+use crate::chunk::ChunkReaderImpl;
+use crate::error::{self, Result};
 use crate::memtable::BoxedBatchIterator;
-use crate::read::BoxedBatchReader;
+use crate::read::{Batch, BoxedBatchReader};
 use crate::schema::ProjectedSchemaRef;
+pub use crate::sst::cache::{CachedAccessLayer, SstReadCache, SstReadCacheRef};
 use crate::sst::parquet::{ParquetReader, ParquetWriter};
 
 /// Maximum level of SSTs.
-pub const MAX_LEVEL: usize = 1;
+pub const MAX_LEVEL: usize = 2;
+
+pub type Level = u8;
 
 // We only has fixed number of level, so we array to hold elements. This implement
 // detail of LevelMetaVec should not be exposed to the user of [LevelMetas].
@@ -48,29 +59,59 @@ pub trait Visitor {
 #[derive(Debug, Clone)]
 pub struct LevelMetas {
     levels: LevelMetaVec,
+    sst_layer: AccessLayerRef,
 }
 
 impl LevelMetas {
     /// Create a new LevelMetas and initialized each level.
-    pub fn new() -> LevelMetas {
+    pub fn new(sst_layer: AccessLayerRef) -> LevelMetas {
         LevelMetas {
             levels: new_level_meta_vec(),
+            sst_layer,
         }
     }
 
+    /// Returns total level number.
+    #[inline]
+    pub fn level_num(&self) -> usize {
+        self.levels.len()
+    }
+
+    /// Returns the metadata of files in `level`.
+    ///
+    /// # Panics
+    /// Panics if `level` is not less than [MAX_LEVEL].
+    #[inline]
+    pub fn level(&self, level: Level) -> &LevelMeta {
+        &self.levels[level as usize]
+    }
+
     /// Merge `self` with files to add/remove to create a new [LevelMetas].
     ///
+    /// Removed files are marked as deleted, they would be purged from the sst layer once
+    /// all handles to them are dropped.
+    ///
     /// # Panics
-    /// Panics if level of [FileHandle] is greater than [MAX_LEVEL].
-    pub fn merge(&self, files_to_add: impl Iterator<Item = FileHandle>) -> LevelMetas {
+    /// Panics if level of [FileMeta] is greater than [MAX_LEVEL].
+    pub fn merge(
+        &self,
+        files_to_add: impl Iterator<Item = FileMeta>,
+        files_to_remove: impl Iterator<Item = FileMeta>,
+    ) -> LevelMetas {
         let mut merged = self.clone();
         for file in files_to_add {
-            let level = file.level_index();
+            let level = file.level;
+            let handle = FileHandle::new(file, self.sst_layer.clone());
 
-            merged.levels[level].add_file(file);
+            merged.levels[level as usize].add_file(handle);
         }
 
-        // TODO(yingwen): Support file removal.
+        for file in files_to_remove {
+            let level = file.level;
+            if let Some(removed) = merged.levels[level as usize].remove_file(&file.file_name) {
+                removed.mark_deleted();
+            }
+        }
 
         merged
     }
@@ -93,41 +134,51 @@ impl LevelMetas {
     }
 }
 
-impl Default for LevelMetas {
-    fn default() -> LevelMetas {
-        LevelMetas::new()
-    }
-}
-
 /// Metadata of files in same SST level.
 #[derive(Debug, Default, Clone)]
 pub struct LevelMeta {
-    level: u8,
+    level: Level,
     /// Handles to the files in this level.
     // TODO(yingwen): Now for simplicity, files are unordered, maybe sort the files by time range
     // or use another structure to hold them.
-    files: Vec<FileHandle>,
+    files: HashMap<String, FileHandle>,
 }
 
 impl LevelMeta {
+    #[inline]
+    pub fn level(&self) -> Level {
+        self.level
+    }
+
+    /// Returns number of files in this level.
+    #[inline]
+    pub fn file_num(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Returns an iterator over all files in this level, the files are unordered.
+    pub fn files(&self) -> impl Iterator<Item = &FileHandle> {
+        self.files.values()
+    }
+
     fn add_file(&mut self, file: FileHandle) {
-        self.files.push(file);
+        self.files.insert(file.file_name().to_string(), file);
     }
 
-    fn visit_level<V: Visitor>(&self, visitor: &mut V) -> Result<()> {
-        visitor.visit(self.level.into(), &self.files)
+    fn remove_file(&mut self, file_name: &str) -> Option<FileHandle> {
+        self.files.remove(file_name)
     }
 
-    #[cfg(test)]
-    pub fn files(&self) -> &[FileHandle] {
-        &self.files
+    fn visit_level<V: Visitor>(&self, visitor: &mut V) -> Result<()> {
+        let files: Vec<_> = self.files.values().cloned().collect();
+        visitor.visit(self.level.into(), &files)
     }
 }
 
 fn new_level_meta_vec() -> LevelMetaVec {
-    let mut levels = [LevelMeta::default(); MAX_LEVEL];
+    let mut levels: LevelMetaVec = Default::default();
     for (i, level) in levels.iter_mut().enumerate() {
-        level.level = i as u8;
+        level.level = i as Level;
     }
 
     levels
@@ -140,9 +191,9 @@ pub struct FileHandle {
 }
 
 impl FileHandle {
-    pub fn new(meta: FileMeta) -> FileHandle {
+    pub fn new(meta: FileMeta, sst_layer: AccessLayerRef) -> FileHandle {
         FileHandle {
-            inner: Arc::new(FileHandleInner::new(meta)),
+            inner: Arc::new(FileHandleInner::new(meta, sst_layer)),
         }
     }
 
@@ -152,10 +203,56 @@ impl FileHandle {
         self.inner.meta.level.into()
     }
 
+    #[inline]
+    pub fn level(&self) -> Level {
+        self.inner.meta.level
+    }
+
     #[inline]
     pub fn file_name(&self) -> &str {
         &self.inner.meta.file_name
     }
+
+    #[inline]
+    pub fn meta(&self) -> FileMeta {
+        self.inner.meta.clone()
+    }
+
+    /// Returns the size of the file in bytes.
+    #[inline]
+    pub fn file_size(&self) -> u64 {
+        self.inner.meta.file_size
+    }
+
+    /// Returns the inclusive timestamp range of rows in the file, `None` if unknown.
+    #[inline]
+    pub fn time_range(&self) -> Option<&(Timestamp, Timestamp)> {
+        self.inner.meta.time_range.as_ref()
+    }
+
+    /// Returns true if the file is being compacted.
+    #[inline]
+    pub fn compacting(&self) -> bool {
+        self.inner.compacting.load(Ordering::Relaxed)
+    }
+
+    /// Marks whether the file is being compacted.
+    #[inline]
+    pub fn mark_compacting(&self, compacting: bool) {
+        self.inner.compacting.store(compacting, Ordering::Relaxed);
+    }
+
+    /// Returns true if the file has been removed from the region.
+    #[inline]
+    pub fn deleted(&self) -> bool {
+        self.inner.deleted.load(Ordering::Relaxed)
+    }
+
+    /// Marks the file as deleted, the file would be purged once the last handle is dropped.
+    #[inline]
+    pub fn mark_deleted(&self) {
+        self.inner.deleted.store(true, Ordering::Relaxed);
+    }
 }
 
 /// Actually data of [FileHandle].
@@ -164,20 +261,88 @@ impl FileHandle {
 #[derive(Debug)]
 struct FileHandleInner {
     meta: FileMeta,
+    /// Whether the file is an input of a running compaction job.
+    compacting: AtomicBool,
+    /// Whether the file has been removed from the region.
+    deleted: AtomicBool,
+    sst_layer: AccessLayerRef,
 }
 
 impl FileHandleInner {
-    fn new(meta: FileMeta) -> FileHandleInner {
-        FileHandleInner { meta }
+    fn new(meta: FileMeta, sst_layer: AccessLayerRef) -> FileHandleInner {
+        FileHandleInner {
+            meta,
+            compacting: AtomicBool::new(false),
+            deleted: AtomicBool::new(false),
+            sst_layer,
+        }
+    }
+}
+
+impl Drop for FileHandleInner {
+    fn drop(&mut self) {
+        if !self.deleted.load(Ordering::Relaxed) {
+            return;
+        }
+
+        // No version references this file now, so it's safe to purge it.
+        let file_name = self.meta.file_name.clone();
+        let sst_layer = self.sst_layer.clone();
+        common_runtime::spawn_bg(async move {
+            match sst_layer.delete_sst(&file_name).await {
+                Ok(()) => logging::debug!("Purged sst file: {}", file_name),
+                Err(e) => logging::error!(e; "Failed to purge sst file: {}", file_name),
+            }
+        });
     }
 }
 
+/// Generates random SST file name in format: `^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$`
+pub fn generate_sst_file_name() -> String {
+    format!("{}.parquet", Uuid::new_v4().hyphenated())
+}
+
 /// Immutable metadata of a sst file.
-#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct FileMeta {
     pub file_name: String,
     /// SST level of the file.
-    pub level: u8,
+    pub level: Level,
+    /// Inclusive timestamp range of rows in the file.
+    ///
+    /// Files written by older versions don't record the time range, so this might be `None`.
+    #[serde(default)]
+    pub time_range: Option<(Timestamp, Timestamp)>,
+    /// Number of rows in the file.
+    #[serde(default)]
+    pub num_rows: usize,
+    /// Size of the file in bytes.
+    #[serde(default)]
+    pub file_size: u64,
+}
+
+impl FileMeta {
+    /// Creates a [FileMeta] of the file in `level` from the [SstInfo] returned by the writer.
+    pub fn with_sst_info(file_name: String, level: Level, info: SstInfo) -> FileMeta {
+        FileMeta {
+            file_name,
+            level,
+            time_range: info.time_range,
+            num_rows: info.num_rows,
+            file_size: info.file_size,
+        }
+    }
+}
+
+/// Info of a SST file collected while writing it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SstInfo {
+    /// Inclusive timestamp range of rows written, `None` if no row is written.
+    pub time_range: Option<(Timestamp, Timestamp)>,
+    /// Number of rows written.
+    pub num_rows: usize,
+    /// Size of the file in bytes.
+    pub file_size: u64,
 }
 
 #[derive(Debug, Default)]
@@ -193,21 +358,50 @@ pub struct ReadOptions {
     pub projected_schema: ProjectedSchemaRef,
 
     pub predicate: Predicate,
+    /// Returns rows in descending order of the key.
+    pub descending: bool,
+}
+
+/// Data source to write into a SST file.
+pub enum Source {
+    /// Writes rows from memtable to SST.
+    Iter(BoxedBatchIterator),
+    /// Writes rows from [ChunkReaderImpl] (maybe a set of SSTs) to SST.
+    Reader(ChunkReaderImpl),
+}
+
+impl Source {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        match self {
+            Source::Iter(iter) => iter.next().transpose(),
+            Source::Reader(reader) => reader.next_batch().await,
+        }
+    }
+
+    fn projected_schema(&self) -> ProjectedSchemaRef {
+        match self {
+            Source::Iter(iter) => iter.schema(),
+            Source::Reader(reader) => reader.projected_schema().clone(),
+        }
+    }
 }
 
 /// SST access layer.
 #[async_trait]
 pub trait AccessLayer: Send + Sync + std::fmt::Debug {
-    /// Writes SST file with given `file_name`.
+    /// Writes SST file with given `file_name` and returns info of the written file.
     async fn write_sst(
         &self,
         file_name: &str,
-        iter: BoxedBatchIterator,
+        source: Source,
         opts: &WriteOptions,
-    ) -> Result<()>;
+    ) -> Result<SstInfo>;
 
     /// Read SST file with given `file_name` and schema.
     async fn read_sst(&self, file_name: &str, opts: &ReadOptions) -> Result<BoxedBatchReader>;
+
+    /// Deletes SST file with given `file_name`.
+    async fn delete_sst(&self, file_name: &str) -> Result<()>;
 }
 
 pub type AccessLayerRef = Arc<dyn AccessLayer>;
@@ -238,16 +432,15 @@ impl AccessLayer for FsAccessLayer {
     async fn write_sst(
         &self,
         file_name: &str,
-        iter: BoxedBatchIterator,
+        source: Source,
         opts: &WriteOptions,
-    ) -> Result<()> {
+    ) -> Result<SstInfo> {
         // Now we only supports parquet format. We may allow caller to specific SST format in
         // WriteOptions in the future.
         let file_path = self.sst_file_path(file_name);
-        let writer = ParquetWriter::new(&file_path, iter, self.object_store.clone());
+        let writer = ParquetWriter::new(&file_path, source, self.object_store.clone());
 
-        writer.write_sst(opts).await?;
-        Ok(())
+        writer.write_sst(opts).await
     }
 
     async fn read_sst(&self, file_name: &str, opts: &ReadOptions) -> Result<BoxedBatchReader> {
@@ -257,9 +450,84 @@ impl AccessLayer for FsAccessLayer {
             self.object_store.clone(),
             opts.projected_schema.clone(),
             opts.predicate.clone(),
-        );
+        )
+        .descending(opts.descending);
 
         let stream = reader.chunk_stream(opts.batch_size).await?;
         Ok(Box::new(stream))
     }
+
+    async fn delete_sst(&self, file_name: &str) -> Result<()> {
+        let path = self.sst_file_path(file_name);
+        let object = self.object_store.object(&path);
+        object
+            .delete()
+            .await
+            .context(error::DeleteObjectSnafu { path })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use regex::Regex;
+
+    use super::*;
+    use crate::test_util::access_layer_util::MockAccessLayer;
+
+    fn create_file_meta(name: &str, level: Level) -> FileMeta {
+        FileMeta {
+            file_name: name.to_string(),
+            level,
+            ..Default::default()
+        }
+    }
+
+    fn file_names_in_level(metas: &LevelMetas, level: Level) -> Vec<String> {
+        let mut names: Vec<_> = metas
+            .level(level)
+            .files()
+            .map(|f| f.file_name().to_string())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    #[test]
+    fn test_uuid_generate() {
+        let file_name = generate_sst_file_name();
+        let regex = Regex::new(r"^[a-f\d]{8}(-[a-f\d]{4}){3}-[a-f\d]{12}.parquet$").unwrap();
+        assert!(
+            regex.is_match(&file_name),
+            "illegal sst file name: {}",
+            file_name
+        );
+    }
+
+    #[test]
+    fn test_level_metas_merge() {
+        let metas = LevelMetas::new(Arc::new(MockAccessLayer));
+        let first = metas.merge(
+            vec![
+                create_file_meta("a", 0),
+                create_file_meta("b", 0),
+                create_file_meta("c", 1),
+            ]
+            .into_iter(),
+            std::iter::empty(),
+        );
+        assert_eq!(vec!["a", "b"], file_names_in_level(&first, 0));
+        assert_eq!(vec!["c"], file_names_in_level(&first, 1));
+
+        let second = first.merge(
+            vec![create_file_meta("d", 1)].into_iter(),
+            vec![create_file_meta("a", 0), create_file_meta("b", 0)].into_iter(),
+        );
+        assert_eq!(0, second.level(0).file_num());
+        assert_eq!(vec!["c", "d"], file_names_in_level(&second, 1));
+
+        // Files removed are marked as deleted, but the old metas still holds them.
+        assert_eq!(vec!["a", "b"], file_names_in_level(&first, 0));
+        assert!(first.level(0).files().all(|f| f.deleted()));
+        assert!(!first.level(1).files().any(|f| f.deleted()));
+    }
 }
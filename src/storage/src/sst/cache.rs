@@ -0,0 +1,474 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Local read cache for SST files.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use common_telemetry::logging;
+use lru::LruCache;
+use metrics::{gauge, increment_counter};
+use object_store::backend::fs::Builder;
+use object_store::{util, ObjectStore};
+use snafu::ResultExt;
+use tokio::sync::OnceCell;
+
+use crate::config::ReadCacheConfig;
+use crate::error::{self, Result};
+use crate::metric::{
+    METRIC_SST_READ_CACHE_BYTES, METRIC_SST_READ_CACHE_EVICT, METRIC_SST_READ_CACHE_HIT,
+    METRIC_SST_READ_CACHE_MISS,
+};
+use crate::read::{Batch, BatchReader, BoxedBatchReader};
+use crate::sst::parquet::ParquetReader;
+use crate::sst::{
+    generate_sst_file_name, AccessLayer, FsAccessLayer, ReadOptions, Source, SstInfo, WriteOptions,
+};
+
+pub type SstReadCacheRef = Arc<SstReadCache>;
+
+/// A size bounded cache that keeps SST files fetched from the object store in local
+/// directory.
+///
+/// Least recently used files are evicted once total size of cached files exceeds the
+/// capacity of the cache.
+pub struct SstReadCache {
+    /// Directory of the cached files.
+    dir: String,
+    /// Object store to access the cached files.
+    local_store: ObjectStore,
+    /// Max total size of cached files.
+    capacity: u64,
+    inner: Mutex<CacheInner>,
+    /// Files being fetched from the object store, keyed by path of the SST file, so
+    /// concurrent misses of the same file share one fetch.
+    loading: Mutex<HashMap<String, Arc<OnceCell<Option<CachedFileRef>>>>>,
+}
+
+struct CacheInner {
+    /// Cached files, keyed by path of the SST file in the object store.
+    files: LruCache<String, CachedFileRef>,
+    /// Total size of cached files.
+    size: u64,
+}
+
+impl fmt::Debug for SstReadCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SstReadCache")
+            .field("dir", &self.dir)
+            .field("capacity", &self.capacity)
+            .finish()
+    }
+}
+
+impl SstReadCache {
+    pub fn new(config: &ReadCacheConfig) -> Result<SstReadCache> {
+        let dir = util::normalize_dir(&config.dir);
+        // Files cached by previous process are not tracked by this cache, so we
+        // remove them to keep the size of the directory bounded.
+        remove_cached_files(&dir)?;
+
+        let atomic_write_dir = format!("{}.tmp/", dir);
+        let accessor = Builder::default()
+            .root(&dir)
+            .atomic_write_dir(&atomic_write_dir)
+            .build()
+            .context(error::InitReadCacheSnafu { dir: &dir })?;
+
+        logging::info!(
+            "SST read cache is enabled, dir: {}, capacity: {}",
+            dir,
+            config.capacity
+        );
+
+        Ok(SstReadCache {
+            dir,
+            local_store: ObjectStore::new(accessor),
+            capacity: config.capacity,
+            inner: Mutex::new(CacheInner {
+                files: LruCache::unbounded(),
+                size: 0,
+            }),
+            loading: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns total size of cached files.
+    pub fn size(&self) -> u64 {
+        self.inner.lock().unwrap().size
+    }
+
+    /// Returns the cached file of SST in `path`, or `None` if the file is not cached.
+    fn get(&self, path: &str) -> Option<CachedFileRef> {
+        let file = self.inner.lock().unwrap().files.get(path).cloned();
+        if file.is_some() {
+            increment_counter!(METRIC_SST_READ_CACHE_HIT);
+        } else {
+            increment_counter!(METRIC_SST_READ_CACHE_MISS);
+        }
+
+        file
+    }
+
+    /// Returns the cached file of SST in `path`, fetches the file from `object_store` if
+    /// it is not cached.
+    ///
+    /// Returns `None` if the file is too large to cache.
+    async fn get_or_load(
+        &self,
+        path: &str,
+        object_store: &ObjectStore,
+    ) -> Result<Option<CachedFileRef>> {
+        if let Some(file) = self.get(path) {
+            return Ok(Some(file));
+        }
+
+        let cell = self
+            .loading
+            .lock()
+            .unwrap()
+            .entry(path.to_string())
+            .or_default()
+            .clone();
+        let result = cell
+            .get_or_try_init(|| self.load(path, object_store))
+            .await
+            .cloned();
+
+        // The loaded file is already in the cache, so later misses don't need the cell.
+        let mut loading = self.loading.lock().unwrap();
+        if matches!(loading.get(path), Some(c) if Arc::ptr_eq(c, &cell)) {
+            loading.remove(path);
+        }
+
+        result
+    }
+
+    /// Fetches SST in `path` from `object_store` and puts it into the cache.
+    ///
+    /// Returns `None` if the file is too large to cache.
+    async fn load(&self, path: &str, object_store: &ObjectStore) -> Result<Option<CachedFileRef>> {
+        let object = object_store.object(path);
+        let file_size = object
+            .metadata()
+            .await
+            .context(error::ReadObjectSnafu { path })?
+            .content_length();
+        if file_size > self.capacity {
+            return Ok(None);
+        }
+
+        let reader = object
+            .range_reader(..)
+            .await
+            .context(error::ReadObjectSnafu { path })?;
+        // Always writes to a new file so readers of the old file of the same SST
+        // won't be affected. The local store streams the object to a temporary file
+        // and renames it into place, so partially written files are never read.
+        let name = generate_sst_file_name();
+        self.local_store
+            .object(&name)
+            .write_from(file_size, reader)
+            .await
+            .context(error::WriteObjectSnafu { path: &name })?;
+
+        let file = Arc::new(CachedFile {
+            name,
+            size: file_size,
+            local_store: self.local_store.clone(),
+        });
+        self.insert(path, file.clone());
+
+        Ok(Some(file))
+    }
+
+    fn insert(&self, path: &str, file: CachedFileRef) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.size += file.size;
+        if let Some(old) = inner.files.put(path.to_string(), file) {
+            inner.size -= old.size;
+        }
+
+        while inner.size > self.capacity {
+            match inner.files.pop_lru() {
+                Some((_, evicted)) => {
+                    inner.size -= evicted.size;
+                    increment_counter!(METRIC_SST_READ_CACHE_EVICT);
+                }
+                None => break,
+            }
+        }
+
+        gauge!(METRIC_SST_READ_CACHE_BYTES, inner.size as f64);
+    }
+
+    /// Removes SST in `path` from the cache.
+    fn remove(&self, path: &str) {
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(file) = inner.files.pop(path) {
+            inner.size -= file.size;
+            gauge!(METRIC_SST_READ_CACHE_BYTES, inner.size as f64);
+        }
+    }
+}
+
+fn remove_cached_files(dir: &str) -> Result<()> {
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e).context(error::CleanReadCacheSnafu { dir }),
+    };
+
+    for entry in entries {
+        let path = entry.context(error::CleanReadCacheSnafu { dir })?.path();
+        if path.is_file() {
+            std::fs::remove_file(&path).context(error::CleanReadCacheSnafu { dir })?;
+        }
+    }
+
+    Ok(())
+}
+
+type CachedFileRef = Arc<CachedFile>;
+
+/// A file in the cache directory.
+///
+/// The file is removed once it is evicted from the cache and no reader holds it.
+#[derive(Debug)]
+struct CachedFile {
+    name: String,
+    size: u64,
+    local_store: ObjectStore,
+}
+
+impl Drop for CachedFile {
+    fn drop(&mut self) {
+        let name = self.name.clone();
+        let object = self.local_store.object(&name);
+        common_runtime::spawn_bg(async move {
+            let res = object
+                .delete()
+                .await
+                .context(error::DeleteObjectSnafu { path: &name });
+            if let Err(e) = res {
+                logging::error!(e; "Failed to remove cached file: {}", name);
+            }
+        });
+    }
+}
+
+/// Reader of a cached file, holds the file so it won't be removed during reading.
+struct CachedFileReader {
+    reader: BoxedBatchReader,
+    _file: CachedFileRef,
+}
+
+#[async_trait]
+impl BatchReader for CachedFileReader {
+    async fn next_batch(&mut self) -> Result<Option<Batch>> {
+        self.reader.next_batch().await
+    }
+}
+
+/// An [AccessLayer] that reads SST files through the [SstReadCache].
+#[derive(Debug)]
+pub struct CachedAccessLayer {
+    inner: FsAccessLayer,
+    cache: SstReadCacheRef,
+}
+
+impl CachedAccessLayer {
+    pub fn new(inner: FsAccessLayer, cache: SstReadCacheRef) -> CachedAccessLayer {
+        CachedAccessLayer { inner, cache }
+    }
+}
+
+#[async_trait]
+impl AccessLayer for CachedAccessLayer {
+    async fn write_sst(
+        &self,
+        file_name: &str,
+        source: Source,
+        opts: &WriteOptions,
+    ) -> Result<SstInfo> {
+        self.inner.write_sst(file_name, source, opts).await
+    }
+
+    async fn read_sst(&self, file_name: &str, opts: &ReadOptions) -> Result<BoxedBatchReader> {
+        let file_path = self.inner.sst_file_path(file_name);
+        let cached = self
+            .cache
+            .get_or_load(&file_path, &self.inner.object_store)
+            .await?;
+        let file = match cached {
+            Some(file) => file,
+            None => return self.inner.read_sst(file_name, opts).await,
+        };
+
+        let reader = ParquetReader::new(
+            &file.name,
+            self.cache.local_store.clone(),
+            opts.projected_schema.clone(),
+            opts.predicate.clone(),
+        )
+        .descending(opts.descending);
+        let stream = reader.chunk_stream(opts.batch_size).await?;
+
+        Ok(Box::new(CachedFileReader {
+            reader: Box::new(stream),
+            _file: file,
+        }))
+    }
+
+    async fn delete_sst(&self, file_name: &str) -> Result<()> {
+        self.cache.remove(&self.inner.sst_file_path(file_name));
+        self.inner.delete_sst(file_name).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use store_api::storage::OpType;
+    use table::predicate::Predicate;
+    use tempdir::TempDir;
+
+    use super::*;
+    use crate::memtable::{
+        tests as memtable_tests, DefaultMemtableBuilder, IterContext, MemtableBuilder,
+    };
+    use crate::schema::ProjectedSchema;
+
+    async fn write_sst(layer: &FsAccessLayer, file_name: &str) -> SstInfo {
+        let schema = memtable_tests::schema_for_test();
+        let memtable = DefaultMemtableBuilder::default().build(schema);
+        memtable_tests::write_kvs(
+            &*memtable,
+            10, // sequence
+            OpType::Put,
+            &[(1000, 1), (1000, 2), (2002, 1)], // keys
+            &[
+                (Some(1), Some(1234)),
+                (Some(2), Some(1234)),
+                (Some(7), None),
+            ], // values
+        );
+
+        let iter = memtable.iter(&IterContext::default()).unwrap();
+        layer
+            .write_sst(file_name, Source::Iter(iter), &WriteOptions::default())
+            .await
+            .unwrap()
+    }
+
+    async fn read_num_rows(layer: &dyn AccessLayer, file_name: &str) -> usize {
+        let schema = memtable_tests::schema_for_test();
+        let opts = ReadOptions {
+            batch_size: 128,
+            projected_schema: Arc::new(ProjectedSchema::no_projection(schema)),
+            predicate: Predicate::empty(),
+            descending: false,
+        };
+        let mut reader = layer.read_sst(file_name, &opts).await.unwrap();
+        let mut num_rows = 0;
+        while let Some(batch) = reader.next_batch().await.unwrap() {
+            num_rows += batch.num_rows();
+        }
+
+        num_rows
+    }
+
+    fn new_fs_layer(dir: &TempDir) -> FsAccessLayer {
+        let path = dir.path().to_str().unwrap();
+        let backend = Builder::default().root(path).build().unwrap();
+        FsAccessLayer::new("region/", ObjectStore::new(backend))
+    }
+
+    #[tokio::test]
+    async fn test_cached_access_layer() {
+        let store_dir = TempDir::new("test_cached_access_layer").unwrap();
+        let cache_dir = TempDir::new("test_cached_access_layer_cache").unwrap();
+        let info = write_sst(&new_fs_layer(&store_dir), "a.parquet").await;
+        write_sst(&new_fs_layer(&store_dir), "b.parquet").await;
+
+        // Only one file could be held by the cache.
+        let config = ReadCacheConfig {
+            dir: cache_dir.path().to_str().unwrap().to_string(),
+            capacity: info.file_size,
+        };
+        let cache = Arc::new(SstReadCache::new(&config).unwrap());
+        let layer = CachedAccessLayer::new(new_fs_layer(&store_dir), cache.clone());
+
+        assert_eq!(3, read_num_rows(&layer, "a.parquet").await);
+        assert_eq!(info.file_size, cache.size());
+        assert!(cache.get("region/a.parquet").is_some());
+
+        // Reads the cached file.
+        assert_eq!(3, read_num_rows(&layer, "a.parquet").await);
+        assert_eq!(info.file_size, cache.size());
+
+        // Evicts `a.parquet`.
+        assert_eq!(3, read_num_rows(&layer, "b.parquet").await);
+        assert_eq!(info.file_size, cache.size());
+        assert!(cache.get("region/a.parquet").is_none());
+        assert!(cache.get("region/b.parquet").is_some());
+
+        layer.delete_sst("b.parquet").await.unwrap();
+        assert_eq!(0, cache.size());
+        assert!(cache.get("region/b.parquet").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_load() {
+        let store_dir = TempDir::new("test_concurrent_load").unwrap();
+        let cache_dir = TempDir::new("test_concurrent_load_cache").unwrap();
+        let info = write_sst(&new_fs_layer(&store_dir), "a.parquet").await;
+
+        let config = ReadCacheConfig {
+            dir: cache_dir.path().to_str().unwrap().to_string(),
+            capacity: info.file_size,
+        };
+        let cache = SstReadCache::new(&config).unwrap();
+        let layer = new_fs_layer(&store_dir);
+
+        // Both misses get the file fetched once.
+        let (a, b) = futures::join!(
+            cache.get_or_load("region/a.parquet", &layer.object_store),
+            cache.get_or_load("region/a.parquet", &layer.object_store)
+        );
+        let (a, b) = (a.unwrap().unwrap(), b.unwrap().unwrap());
+        assert!(Arc::ptr_eq(&a, &b));
+        assert_eq!(info.file_size, cache.size());
+        assert!(cache.loading.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_file_larger_than_capacity() {
+        let store_dir = TempDir::new("test_read_file_larger_than_capacity").unwrap();
+        let cache_dir = TempDir::new("test_read_file_larger_than_capacity_cache").unwrap();
+        let info = write_sst(&new_fs_layer(&store_dir), "a.parquet").await;
+
+        let config = ReadCacheConfig {
+            dir: cache_dir.path().to_str().unwrap().to_string(),
+            capacity: info.file_size - 1,
+        };
+        let cache = Arc::new(SstReadCache::new(&config).unwrap());
+        let layer = CachedAccessLayer::new(new_fs_layer(&store_dir), cache.clone());
+
+        assert_eq!(3, read_num_rows(&layer, "a.parquet").await);
+        assert_eq!(0, cache.size());
+    }
+}
@@ -21,69 +21,72 @@ use std::sync::Arc;
 use async_stream::try_stream;
 use async_trait::async_trait;
 use common_telemetry::debug;
-use datatypes::arrow::array::Array;
+use common_time::Timestamp;
+use datatypes::arrow::array::{Array, UInt32Array};
 use datatypes::arrow::chunk::Chunk;
+use datatypes::arrow::compute::take::take;
 use datatypes::arrow::datatypes::{DataType, Schema};
+use datatypes::arrow::error::Result as ArrowResult;
 use datatypes::arrow::io::parquet::read::{
     infer_schema, read_columns_many_async, read_metadata_async, RowGroupDeserializer,
 };
 use datatypes::arrow::io::parquet::write::{
     Compression, Encoding, FileSink, Version, WriteOptions,
 };
+use datatypes::prelude::{ScalarVector, VectorRef};
+use datatypes::vectors::{Int64Vector, TimestampVector};
 use futures::io::BufReader;
 use futures::AsyncWriteExt;
 use futures_util::sink::SinkExt;
 use futures_util::{try_join, Stream, TryStreamExt};
+use metrics::counter;
 use object_store::{ObjectStore, SeekableReader};
 use sluice::pipe;
 use snafu::ResultExt;
 use table::predicate::Predicate;
 
 use crate::error::{self, Result};
-use crate::memtable::BoxedBatchIterator;
+use crate::metric::METRIC_SST_PRUNED_ROW_GROUPS;
 use crate::read::{Batch, BatchReader};
 use crate::schema::compat::ReadAdapter;
 use crate::schema::{ProjectedSchemaRef, StoreSchema};
-use crate::sst;
+use crate::sst::{self, Source, SstInfo};
 
 /// Parquet sst writer.
 pub struct ParquetWriter<'a> {
     file_path: &'a str,
-    iter: BoxedBatchIterator,
+    source: Source,
     object_store: ObjectStore,
 }
 
 impl<'a> ParquetWriter<'a> {
-    pub fn new(
-        file_path: &'a str,
-        iter: BoxedBatchIterator,
-        object_store: ObjectStore,
-    ) -> ParquetWriter {
+    pub fn new(file_path: &'a str, source: Source, object_store: ObjectStore) -> ParquetWriter {
         ParquetWriter {
             file_path,
-            iter,
+            source,
             object_store,
         }
     }
 
-    pub async fn write_sst(self, _opts: &sst::WriteOptions) -> Result<()> {
+    pub async fn write_sst(self, _opts: &sst::WriteOptions) -> Result<SstInfo> {
         self.write_rows(None).await
     }
 
-    /// Iterates memtable and writes rows to Parquet file.
+    /// Iterates source and writes rows to Parquet file.
     /// A chunk of records yielded from each iteration with a size given
     /// in config will be written to a single row group.
-    async fn write_rows(self, extra_meta: Option<HashMap<String, String>>) -> Result<()> {
-        let projected_schema = self.iter.schema();
+    async fn write_rows(mut self, extra_meta: Option<HashMap<String, String>>) -> Result<SstInfo> {
+        let projected_schema = self.source.projected_schema();
         let store_schema = projected_schema.schema_to_read();
         let schema = store_schema.arrow_schema();
+        let timestamp_index = store_schema.schema().timestamp_index();
         let object = self.object_store.object(self.file_path);
 
         let (reader, mut writer) = pipe::pipe();
 
         // now all physical types use plain encoding, maybe let caller to choose encoding for each type.
         let encodings = get_encoding_for_schema(schema, |_| Encoding::Plain);
-        try_join!(
+        let (_, (time_range, num_rows)) = try_join!(
             async {
                 // FIXME(hl): writer size is not used in fs backend so just leave it to 0,
                 // but in s3/azblob backend the Content-Length field of HTTP request is set
@@ -107,8 +110,14 @@ impl<'a> ParquetWriter<'a> {
                 )
                 .context(error::WriteParquetSnafu)?;
 
-                for batch in self.iter {
-                    let batch = batch?;
+                let mut time_range = None;
+                let mut num_rows = 0;
+                while let Some(batch) = self.source.next_batch().await? {
+                    if let Some(idx) = timestamp_index {
+                        time_range = merge_time_range(time_range, batch.column(idx));
+                    }
+                    num_rows += batch.num_rows();
+
                     sink.send(store_schema.batch_to_arrow_chunk(&batch))
                         .await
                         .context(error::WriteParquetSnafu)?;
@@ -134,13 +143,50 @@ impl<'a> ParquetWriter<'a> {
                     })
                     .context(error::WriteObjectSnafu {
                         path: self.file_path,
-                    })
+                    })?;
+
+                Ok((time_range, num_rows))
             }
-        )
-        .map(|_| ())
+        )?;
+
+        let file_size = object
+            .metadata()
+            .await
+            .context(error::ReadObjectSnafu {
+                path: self.file_path,
+            })?
+            .content_length();
+
+        Ok(SstInfo {
+            time_range,
+            num_rows,
+            file_size,
+        })
     }
 }
 
+/// Merges the timestamp range of `column` into `time_range`.
+///
+/// The `time_range` is left unchanged if `column` is not a timestamp column.
+fn merge_time_range(
+    time_range: Option<(Timestamp, Timestamp)>,
+    column: &VectorRef,
+) -> Option<(Timestamp, Timestamp)> {
+    let timestamps: Box<dyn Iterator<Item = Timestamp>> =
+        if let Some(vector) = column.as_any().downcast_ref::<TimestampVector>() {
+            Box::new(vector.iter_data().flatten())
+        } else if let Some(vector) = column.as_any().downcast_ref::<Int64Vector>() {
+            Box::new(vector.iter_data().flatten().map(Timestamp::from_millis))
+        } else {
+            return time_range;
+        };
+
+    timestamps.fold(time_range, |range, ts| match range {
+        Some((min, max)) => Some((min.min(ts), max.max(ts))),
+        None => Some((ts, ts)),
+    })
+}
+
 fn get_encoding_for_schema<F: Fn(&DataType) -> Encoding + Clone>(
     schema: &Schema,
     map: F,
@@ -200,6 +246,7 @@ pub struct ParquetReader<'a> {
     object_store: ObjectStore,
     projected_schema: ProjectedSchemaRef,
     predicate: Predicate,
+    descending: bool,
 }
 
 type ReaderFactoryFuture<'a, R> =
@@ -217,9 +264,16 @@ impl<'a> ParquetReader<'a> {
             object_store,
             projected_schema,
             predicate,
+            descending: false,
         }
     }
 
+    /// Returns rows in reverse order if `descending` is true.
+    pub fn descending(mut self, descending: bool) -> Self {
+        self.descending = descending;
+        self
+    }
+
     pub async fn chunk_stream(&self, chunk_size: usize) -> Result<ChunkStream> {
         let file_path = self.file_path.to_string();
         let operator = self.object_store.clone();
@@ -251,11 +305,18 @@ impl<'a> ParquetReader<'a> {
         let pruned_row_groups = self
             .predicate
             .prune_row_groups(store_schema.schema().clone(), &metadata.row_groups);
+        let num_pruned = pruned_row_groups.iter().filter(|valid| !**valid).count();
+        counter!(METRIC_SST_PRUNED_ROW_GROUPS, num_pruned as u64);
 
         let projected_fields = adapter.fields_to_read();
+        let mut row_groups: Vec<_> = (0..metadata.row_groups.len()).collect();
+        if self.descending {
+            row_groups.reverse();
+        }
+        let descending = self.descending;
         let chunk_stream = try_stream!({
-            for (idx, valid) in pruned_row_groups.iter().enumerate() {
-                if !valid {
+            for idx in row_groups {
+                if !pruned_row_groups[idx] {
                     debug!("Pruned {} row groups", idx);
                     continue;
                 }
@@ -271,10 +332,24 @@ impl<'a> ParquetReader<'a> {
                 .context(error::ReadParquetSnafu { file: &file_path })?;
 
                 let chunks = RowGroupDeserializer::new(column_chunks, rg.num_rows() as usize, None);
-                for maybe_chunk in chunks {
-                    let columns_in_chunk =
-                        maybe_chunk.context(error::ReadParquetSnafu { file: &file_path })?;
-                    yield columns_in_chunk;
+                if descending {
+                    // Reads the whole row group, then returns its rows from the last one.
+                    let mut chunks_in_rg = Vec::new();
+                    for maybe_chunk in chunks {
+                        chunks_in_rg.push(
+                            maybe_chunk.context(error::ReadParquetSnafu { file: &file_path })?,
+                        );
+                    }
+                    for chunk in chunks_in_rg.iter().rev() {
+                        yield reverse_chunk(chunk)
+                            .context(error::ReadParquetSnafu { file: &file_path })?;
+                    }
+                } else {
+                    for maybe_chunk in chunks {
+                        let columns_in_chunk =
+                            maybe_chunk.context(error::ReadParquetSnafu { file: &file_path })?;
+                        yield columns_in_chunk;
+                    }
                 }
             }
         });
@@ -283,6 +358,18 @@ impl<'a> ParquetReader<'a> {
     }
 }
 
+/// Returns a new chunk with rows of `chunk` in reverse order.
+fn reverse_chunk(chunk: &Chunk<Arc<dyn Array>>) -> ArrowResult<Chunk<Arc<dyn Array>>> {
+    let indices = UInt32Array::from_vec((0..chunk.len() as u32).rev().collect());
+    let arrays = chunk
+        .arrays()
+        .iter()
+        .map(|array| take(&**array, &indices).map(Arc::from))
+        .collect::<ArrowResult<Vec<_>>>()?;
+
+    Ok(Chunk::new(arrays))
+}
+
 pub type SendableChunkStream = Pin<Box<dyn Stream<Item = Result<Chunk<Arc<dyn Array>>>> + Send>>;
 
 pub struct ChunkStream {
@@ -357,7 +444,7 @@ mod tests {
         let object_store = ObjectStore::new(backend);
         let sst_file_name = "test-flush.parquet";
         let iter = memtable.iter(&IterContext::default()).unwrap();
-        let writer = ParquetWriter::new(sst_file_name, iter, object_store);
+        let writer = ParquetWriter::new(sst_file_name, Source::Iter(iter), object_store);
 
         writer
             .write_sst(&sst::WriteOptions::default())
@@ -19,7 +19,9 @@ use log_store::fs::log::LocalFileLogStore;
 use object_store::backend::fs::Builder;
 use object_store::ObjectStore;
 
-use crate::background::JobPoolImpl;
+use crate::background::{JobPoolImpl, JobPoolRef};
+use crate::compaction::{CompactionSchedulerImpl, SimplePicker};
+use crate::config::EngineConfig;
 use crate::engine;
 use crate::flush::{FlushSchedulerImpl, SizeBasedStrategy};
 use crate::manifest::region::RegionManifest;
@@ -43,9 +45,10 @@ pub async fn new_store_config(
     let accessor = Builder::default().root(store_dir).build().unwrap();
     let object_store = ObjectStore::new(accessor);
     let sst_layer = Arc::new(FsAccessLayer::new(&sst_dir, object_store.clone()));
-    let manifest = RegionManifest::new(&manifest_dir, object_store);
-    let job_pool = Arc::new(JobPoolImpl {});
-    let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool));
+    let manifest = RegionManifest::with_checkpointer(&manifest_dir, object_store);
+    let job_pool: JobPoolRef = Arc::new(JobPoolImpl {});
+    let flush_scheduler = Arc::new(FlushSchedulerImpl::new(job_pool.clone()));
+    let compaction_scheduler = Arc::new(CompactionSchedulerImpl::new(job_pool));
     let log_config = LogConfig {
         log_file_dir: log_store_dir(store_dir),
         ..Default::default()
@@ -59,5 +62,9 @@ pub async fn new_store_config(
         memtable_builder: Arc::new(DefaultMemtableBuilder::default()),
         flush_scheduler,
         flush_strategy: Arc::new(SizeBasedStrategy::default()),
+        compaction_picker: Arc::new(SimplePicker::default()),
+        compaction_scheduler,
+        ttl: None,
+        ttl_check_interval: EngineConfig::default().ttl_check_interval,
     }
 }
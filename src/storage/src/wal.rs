@@ -17,6 +17,7 @@ use std::sync::Arc;
 
 use common_error::prelude::BoxedError;
 use futures::{stream, Stream, TryStreamExt};
+use metrics::counter;
 use prost::Message;
 use snafu::{ensure, ResultExt};
 use store_api::logstore::entry::Entry;
@@ -25,6 +26,7 @@ use store_api::storage::{RegionId, SequenceNumber};
 
 use crate::codec::{Decoder, Encoder};
 use crate::error::{self, Error, Result};
+use crate::metric::{LABEL_REGION, METRIC_WAL_WRITE_BYTES};
 use crate::proto::wal::{self, PayloadType, WalHeader};
 use crate::write_batch::codec::{
     WriteBatchArrowDecoder, WriteBatchArrowEncoder, WriteBatchProtobufDecoder,
@@ -68,6 +70,28 @@ impl<S: LogStore> Wal<S> {
     pub fn region_id(&self) -> RegionId {
         self.region_id
     }
+
+    /// Delete the namespace of this region in the log store.
+    pub async fn delete_namespace(&self) -> Result<()> {
+        self.store
+            .delete_namespace(&self.namespace)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::DeleteWalNamespaceSnafu {
+                region_id: self.region_id,
+            })
+    }
+
+    /// Mark entries with sequence `<= seq` as obsolete, so the log store could purge them.
+    pub async fn obsolete(&self, seq: SequenceNumber) -> Result<()> {
+        self.store
+            .obsolete(self.namespace.clone(), seq)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::MarkWalObsoleteSnafu {
+                region_id: self.region_id,
+            })
+    }
 }
 
 impl<S: LogStore> Wal<S> {
@@ -88,9 +112,49 @@ impl<S: LogStore> Wal<S> {
     pub async fn write_to_wal(
         &self,
         seq: SequenceNumber,
-        mut header: WalHeader,
+        header: WalHeader,
         payload: Payload<'_>,
     ) -> Result<(u64, usize)> {
+        let buf = self.encode_entry(header, payload)?;
+
+        // write bytes to wal
+        self.write(seq, &buf).await
+    }
+
+    /// Writes the `batches` to the WAL in one append, the `i`-th batch uses sequence
+    /// `start_seq + i`. Returns the id of the first entry.
+    pub async fn write_batches_to_wal(
+        &self,
+        start_seq: SequenceNumber,
+        header: WalHeader,
+        batches: &[&WriteBatch],
+    ) -> Result<u64> {
+        let mut entries = Vec::with_capacity(batches.len());
+        let mut bytes = 0;
+        for (i, batch) in batches.iter().enumerate() {
+            let buf = self.encode_entry(header.clone(), Payload::WriteBatchArrow(batch))?;
+            bytes += buf.len();
+            let entry = self
+                .store
+                .entry(&buf, start_seq + i as u64, self.namespace.clone());
+            entries.push(entry);
+        }
+
+        let id = self
+            .store
+            .append_batch(&self.namespace, entries)
+            .await
+            .map_err(BoxedError::new)
+            .context(error::WriteWalSnafu {
+                region_id: self.region_id(),
+            })?;
+        self.record_write_bytes(bytes);
+
+        Ok(id)
+    }
+
+    /// Encodes the `header` and `payload` into the bytes of a WAL entry.
+    fn encode_entry(&self, mut header: WalHeader, payload: Payload<'_>) -> Result<Vec<u8>> {
         header.payload_type = payload.payload_type();
         if let Payload::WriteBatchArrow(batch) = payload {
             header.mutation_types = wal::gen_mutation_types(batch);
@@ -124,8 +188,7 @@ impl<S: LogStore> Wal<S> {
                 })?;
         }
 
-        // write bytes to wal
-        self.write(seq, &buf).await
+        Ok(buf)
     }
 
     pub async fn read_from_wal(&self, start_seq: SequenceNumber) -> Result<WriteBatchStream<'_>> {
@@ -163,10 +226,19 @@ impl<S: LogStore> Wal<S> {
             .context(error::WriteWalSnafu {
                 region_id: self.region_id(),
             })?;
+        self.record_write_bytes(bytes.len());
 
         Ok((res.entry_id(), res.offset()))
     }
 
+    fn record_write_bytes(&self, bytes: usize) {
+        counter!(
+            METRIC_WAL_WRITE_BYTES,
+            bytes as u64,
+            LABEL_REGION => self.region_id.to_string()
+        );
+    }
+
     fn decode_entry<E: Entry>(
         &self,
         entry: E,
@@ -276,9 +348,11 @@ impl Decoder for WalHeaderDecoder {
 
 #[cfg(test)]
 mod tests {
+    use datatypes::type_id::LogicalTypeId;
     use log_store::test_util;
 
     use super::*;
+    use crate::test_util::write_batch_util;
 
     #[tokio::test]
     pub async fn test_write_wal() {
@@ -322,6 +396,31 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    pub async fn test_write_batches_to_wal() -> Result<()> {
+        let (log_store, _tmp) =
+            test_util::log_store_util::create_tmp_local_file_log_store("wal_test").await;
+        let wal = Wal::new(0, Arc::new(log_store));
+        let batch =
+            write_batch_util::new_write_batch(&[("ts", LogicalTypeId::Timestamp, false)], Some(0));
+        let header = WalHeader::with_last_manifest_version(111);
+        let first_id = wal
+            .write_batches_to_wal(5, header, &[&batch, &batch])
+            .await?;
+        assert_eq!(5, first_id);
+
+        let mut stream = wal.read_from_wal(first_id).await?;
+        let mut sequences = vec![];
+        while let Some((seq_num, header, write_batch)) = stream.try_next().await? {
+            assert_eq!(111, header.last_manifest_version);
+            assert!(write_batch.is_some());
+            sequences.push(seq_num);
+        }
+        assert_eq!(vec![5, 6], sequences);
+
+        Ok(())
+    }
+
     #[test]
     pub fn test_wal_header_codec() {
         let wal_header = WalHeader {
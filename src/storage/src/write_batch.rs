@@ -24,7 +24,7 @@ use common_time::timestamp_millis::BucketAligned;
 use common_time::RangeMillis;
 use datatypes::arrow::error::ArrowError;
 use datatypes::data_type::ConcreteDataType;
-use datatypes::prelude::{ScalarVector, Value};
+use datatypes::prelude::{ScalarVector, Value, VectorBuilder};
 use datatypes::schema::{ColumnSchema, SchemaRef};
 use datatypes::vectors::{Int64Vector, TimestampVector, VectorRef};
 use prost::{DecodeError, EncodeError};
@@ -198,6 +198,23 @@ impl WriteRequest for WriteBatch {
         Ok(())
     }
 
+    fn delete(&mut self, keys: HashMap<String, VectorRef>) -> Result<()> {
+        let mut data = PutData::with_num_columns(self.schema.num_columns());
+        for (name, vector) in keys {
+            data.add_column_by_name(&name, vector)?;
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        self.preprocess_delete_data(&mut data)?;
+
+        self.add_num_rows(data.num_rows())?;
+        self.mutations.push(Mutation::Delete(data));
+
+        Ok(())
+    }
+
     /// Aligns timestamps in write batch specified by schema to durations.
     ///
     /// A negative timestamp means "before Unix epoch".
@@ -214,7 +231,7 @@ impl WriteRequest for WriteBatch {
         let mut aligned_timestamps: BTreeSet<i64> = BTreeSet::new();
         for m in &self.mutations {
             match m {
-                Mutation::Put(put_data) => {
+                Mutation::Put(put_data) | Mutation::Delete(put_data) => {
                     let column = put_data
                         .column_by_name(ts_col_name)
                         .unwrap_or_else(|| panic!("Cannot find column by name: {}", ts_col_name));
@@ -304,11 +321,19 @@ impl WriteBatch {
     pub fn is_empty(&self) -> bool {
         self.mutations.is_empty()
     }
+
+    /// Returns number of rows in all mutations.
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
 }
 
 /// Enum to wrap different operations.
 pub enum Mutation {
     Put(PutData),
+    /// Delete rows by row keys, the [PutData] holds all columns of the schema, but
+    /// only row key columns are meaningful and value columns are filled by nulls.
+    Delete(PutData),
 }
 
 #[derive(Default, Debug)]
@@ -367,6 +392,19 @@ impl PutData {
 
         self.add_column_by_name(&column_schema.name, vector)
     }
+
+    /// Add columns filled by nulls, used to pad columns that are meaningless to
+    /// delete operation.
+    fn add_null_by_name(&mut self, column_schema: &ColumnSchema) -> Result<()> {
+        let num_rows = self.num_rows();
+
+        let mut builder = VectorBuilder::with_capacity(column_schema.data_type.clone(), num_rows);
+        for _ in 0..num_rows {
+            builder.push_null();
+        }
+
+        self.add_column_by_name(&column_schema.name, builder.finish())
+    }
 }
 
 impl PutOperation for PutData {
@@ -430,6 +468,19 @@ impl PutData {
 }
 
 fn validate_column(column_schema: &ColumnSchema, col: &VectorRef) -> Result<()> {
+    validate_column_type(column_schema, col)?;
+
+    ensure!(
+        column_schema.is_nullable() || col.null_count() == 0,
+        HasNullSnafu {
+            name: &column_schema.name,
+        }
+    );
+
+    Ok(())
+}
+
+fn validate_column_type(column_schema: &ColumnSchema, col: &VectorRef) -> Result<()> {
     if !col.data_type().is_null() {
         // This allow us to use NullVector for columns that only have null value.
         // TODO(yingwen): Let NullVector supports different logical type so we could
@@ -444,13 +495,6 @@ fn validate_column(column_schema: &ColumnSchema, col: &VectorRef) -> Result<()>
         );
     }
 
-    ensure!(
-        column_schema.is_nullable() || col.null_count() == 0,
-        HasNullSnafu {
-            name: &column_schema.name,
-        }
-    );
-
     Ok(())
 }
 
@@ -469,7 +513,28 @@ impl WriteBatch {
             }
         }
 
-        // Check all columns in data also exists in schema.
+        self.ensure_columns_in_schema(data)
+    }
+
+    /// Validate [PutData] of delete operation and fill missing columns by nulls.
+    fn preprocess_delete_data(&self, data: &mut PutData) -> Result<()> {
+        for column_schema in self.schema.column_schemas() {
+            match data.column_by_name(&column_schema.name) {
+                Some(col) => {
+                    validate_column_type(column_schema, col)?;
+                }
+                None => {
+                    // Value columns are useless to delete, so we just fill them by nulls.
+                    data.add_null_by_name(column_schema)?;
+                }
+            }
+        }
+
+        self.ensure_columns_in_schema(data)
+    }
+
+    /// Check all columns in data also exists in schema.
+    fn ensure_columns_in_schema(&self, data: &PutData) -> Result<()> {
         for name in data.columns.keys() {
             ensure!(
                 self.schema.column_schema_by_name(name).is_some(),
@@ -505,6 +570,7 @@ pub mod codec {
     use std::io::Cursor;
     use std::sync::Arc;
 
+    use datatypes::arrow::array::Array;
     use datatypes::arrow::chunk::Chunk as ArrowChunk;
     use datatypes::arrow::io::ipc::read::{self, StreamReader, StreamState};
     use datatypes::arrow::io::ipc::write::{StreamWriter, WriteOptions};
@@ -549,7 +615,7 @@ pub mod codec {
 
             for mutation in item.iter() {
                 let chunk = match mutation {
-                    Mutation::Put(put) => {
+                    Mutation::Put(put) | Mutation::Delete(put) => {
                         let arrays = item_schema
                             .column_schemas()
                             .iter()
@@ -632,19 +698,12 @@ pub mod codec {
             for (mutation_type, chunk) in self.mutation_types.iter().zip(chunks.into_iter()) {
                 match MutationType::from_i32(*mutation_type) {
                     Some(MutationType::Put) => {
-                        let mut put_data = PutData::with_num_columns(schema.num_columns());
-                        for (column_schema, array) in
-                            schema.column_schemas().iter().zip(chunk.arrays().iter())
-                        {
-                            let vector =
-                                Helper::try_into_vector(array).context(DecodeVectorSnafu)?;
-                            put_data.add_column_by_name(&column_schema.name, vector)?;
-                        }
-
+                        let put_data = arrays_to_put_data(&schema, chunk.arrays())?;
                         write_batch.put(put_data)?;
                     }
                     Some(MutationType::Delete) => {
-                        unimplemented!("delete mutation is not implemented")
+                        let put_data = arrays_to_put_data(&schema, chunk.arrays())?;
+                        write_batch.delete(put_data.columns)?;
                     }
                     _ => {
                         return DataCorruptedSnafu {
@@ -659,6 +718,16 @@ pub mod codec {
         }
     }
 
+    fn arrays_to_put_data(schema: &Schema, arrays: &[Arc<dyn Array>]) -> Result<PutData> {
+        let mut put_data = PutData::with_num_columns(schema.num_columns());
+        for (column_schema, array) in schema.column_schemas().iter().zip(arrays.iter()) {
+            let vector = Helper::try_into_vector(array).context(DecodeVectorSnafu)?;
+            put_data.add_column_by_name(&column_schema.name, vector)?;
+        }
+
+        Ok(put_data)
+    }
+
     pub struct WriteBatchProtobufEncoder {}
 
     impl Encoder for WriteBatchProtobufEncoder {
@@ -670,8 +739,11 @@ pub mod codec {
 
             let mutations = item
                 .iter()
-                .map(|mtn| match mtn {
-                    Mutation::Put(put_data) => item
+                .map(|mtn| {
+                    let put_data = match mtn {
+                        Mutation::Put(put_data) | Mutation::Delete(put_data) => put_data,
+                    };
+                    let columns = item
                         .schema()
                         .column_schemas()
                         .iter()
@@ -681,16 +753,22 @@ pub mod codec {
                                 .context(MissingColumnSnafu { name: &cs.name })?;
                             gen_columns(vector).context(ToProtobufSnafu)
                         })
-                        .collect::<Result<Vec<_>>>(),
-                })
-                .collect::<Result<Vec<_>>>()?
-                .into_iter()
-                .map(|columns| write_batch::Mutation {
-                    mutation: Some(write_batch::mutation::Mutation::Put(write_batch::Put {
-                        columns,
-                    })),
+                        .collect::<Result<Vec<_>>>()?;
+
+                    let mutation = match mtn {
+                        Mutation::Put(_) => {
+                            write_batch::mutation::Mutation::Put(write_batch::Put { columns })
+                        }
+                        Mutation::Delete(_) => {
+                            write_batch::mutation::Mutation::Delete(write_batch::Delete { columns })
+                        }
+                    };
+
+                    Ok(write_batch::Mutation {
+                        mutation: Some(mutation),
+                    })
                 })
-                .collect();
+                .collect::<Result<Vec<_>>>()?;
 
             let write_batch = write_batch::WriteBatch {
                 schema: Some(schema),
@@ -701,6 +779,29 @@ pub mod codec {
         }
     }
 
+    fn columns_to_put_data(
+        schema: &SchemaRef,
+        columns: Vec<write_batch::Column>,
+    ) -> Result<PutData> {
+        let mut put_data = PutData::with_num_columns(columns.len());
+
+        schema
+            .column_schemas()
+            .iter()
+            .map(|column| (column.name.clone(), column.data_type.clone()))
+            .zip(columns.into_iter())
+            .map(|((name, data_type), column)| {
+                gen_put_data_vector(data_type, column)
+                    .map(|vector| (name, vector))
+                    .context(FromProtobufSnafu)
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter()
+            .try_for_each(|(name, vector)| put_data.add_column_by_name(&name, vector))?;
+
+        Ok(put_data)
+    }
+
     pub struct WriteBatchProtobufDecoder {
         mutation_types: Vec<i32>,
     }
@@ -741,26 +842,11 @@ pub mod codec {
                 .into_iter()
                 .map(|mtn| match mtn.mutation {
                     Some(write_batch::mutation::Mutation::Put(put)) => {
-                        let mut put_data = PutData::with_num_columns(put.columns.len());
-
-                        let res = schema
-                            .column_schemas()
-                            .iter()
-                            .map(|column| (column.name.clone(), column.data_type.clone()))
-                            .zip(put.columns.into_iter())
-                            .map(|((name, data_type), column)| {
-                                gen_put_data_vector(data_type, column)
-                                    .map(|vector| (name, vector))
-                                    .context(FromProtobufSnafu)
-                            })
-                            .collect::<Result<Vec<_>>>()?
-                            .into_iter()
-                            .map(|(name, vector)| put_data.add_column_by_name(&name, vector))
-                            .collect::<Result<Vec<_>>>();
-
-                        res.map(|_| Mutation::Put(put_data))
+                        columns_to_put_data(&schema, put.columns).map(Mutation::Put)
+                    }
+                    Some(write_batch::mutation::Mutation::Delete(delete)) => {
+                        columns_to_put_data(&schema, delete.columns).map(Mutation::Delete)
                     }
-                    Some(write_batch::mutation::Mutation::Delete(_)) => todo!(),
                     _ => DataCorruptedSnafu {
                         message: "invalid mutation type",
                     }
@@ -774,6 +860,7 @@ pub mod codec {
                 .into_iter()
                 .try_for_each(|mutation| match mutation {
                     Mutation::Put(put_data) => write_batch.put(put_data),
+                    Mutation::Delete(put_data) => write_batch.delete(put_data.columns),
                 })?;
 
             Ok(write_batch)
@@ -860,10 +947,50 @@ mod tests {
         assert!(!batch.is_empty());
 
         let mut iter = batch.iter();
-        let Mutation::Put(put_data) = iter.next().unwrap();
+        let put_data = match iter.next().unwrap() {
+            Mutation::Put(put_data) => put_data,
+            Mutation::Delete(_) => unreachable!(),
+        };
         assert_eq!(3, put_data.num_rows());
     }
 
+    #[test]
+    fn test_write_batch_delete() {
+        let intv = Arc::new(UInt64Vector::from_slice(&[1, 2, 3]));
+        let tsv = Arc::new(TimestampVector::from_vec(vec![0, 0, 0]));
+
+        let mut keys = HashMap::with_capacity(3);
+        keys.insert("k1".to_string(), intv.clone() as VectorRef);
+        keys.insert(consts::VERSION_COLUMN_NAME.to_string(), intv);
+        keys.insert("ts".to_string(), tsv);
+
+        let mut batch = new_test_batch();
+        batch.delete(keys).unwrap();
+        assert_eq!(3, batch.num_rows);
+
+        let delete_data = match batch.iter().next().unwrap() {
+            Mutation::Delete(delete_data) => delete_data,
+            Mutation::Put(_) => unreachable!(),
+        };
+        assert_eq!(3, delete_data.num_rows());
+        // Value column is filled by nulls.
+        let v1 = delete_data.column_by_name("v1").unwrap();
+        assert_eq!(ConcreteDataType::boolean_datatype(), v1.data_type());
+        assert_eq!(3, v1.null_count());
+    }
+
+    #[test]
+    fn test_write_batch_delete_unknown_column() {
+        let intv = Arc::new(UInt64Vector::from_slice(&[1, 2, 3]));
+
+        let mut keys = HashMap::with_capacity(1);
+        keys.insert("k2".to_string(), intv as VectorRef);
+
+        let mut batch = new_test_batch();
+        let err = batch.delete(keys).err().unwrap();
+        check_err(err, "Unknown column k2");
+    }
+
     fn check_err(err: Error, msg: &str) {
         assert_eq!(StatusCode::InvalidArguments, err.status_code());
         assert!(err.backtrace_opt().is_some());
@@ -1058,6 +1185,14 @@ mod tests {
             put_data.add_key_column("ts", tsv).unwrap();
 
             batch.put(put_data).unwrap();
+
+            let mut keys: HashMap<String, VectorRef> = HashMap::with_capacity(2);
+            keys.insert("k1".to_string(), Arc::new(UInt64Vector::from_slice(&[1])));
+            keys.insert(
+                "ts".to_string(),
+                Arc::new(TimestampVector::from_vec(vec![i])),
+            );
+            batch.delete(keys).unwrap();
         }
 
         let types = proto::wal::gen_mutation_types(&batch);
@@ -1065,6 +1200,13 @@ mod tests {
         (batch, types)
     }
 
+    fn num_deletes(batch: &WriteBatch) -> usize {
+        batch
+            .iter()
+            .filter(|m| matches!(m, Mutation::Delete(_)))
+            .count()
+    }
+
     #[test]
     fn test_codec_arrow() -> Result<()> {
         let (batch, mutation_types) = gen_new_batch_and_types();
@@ -1078,6 +1220,7 @@ mod tests {
         let result = decoder.decode(&dst);
         let batch2 = result?;
         assert_eq!(batch.num_rows, batch2.num_rows);
+        assert_eq!(10, num_deletes(&batch2));
 
         Ok(())
     }
@@ -1095,6 +1238,7 @@ mod tests {
         let result = decoder.decode(&dst);
         let batch2 = result?;
         assert_eq!(batch.num_rows, batch2.num_rows);
+        assert_eq!(10, num_deletes(&batch2));
 
         Ok(())
     }
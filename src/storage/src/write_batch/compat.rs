@@ -53,6 +53,9 @@ impl CompatWrite for WriteBatch {
                 Mutation::Put(put_data) => {
                     put_data.compat_write(dest_schema)?;
                 }
+                Mutation::Delete(put_data) => {
+                    compat_delete(put_data, dest_schema)?;
+                }
             }
         }
 
@@ -83,6 +86,26 @@ impl CompatWrite for PutData {
     }
 }
 
+/// Fills columns not in the delete data by nulls, as only row key columns are
+/// meaningful to delete.
+fn compat_delete(put_data: &mut PutData, dest_schema: &SchemaRef) -> Result<()> {
+    if put_data.is_empty() {
+        return Ok(());
+    }
+
+    for column_schema in dest_schema.column_schemas() {
+        if put_data.column_by_name(&column_schema.name).is_none() {
+            put_data
+                .add_null_by_name(column_schema)
+                .context(error::AddDefaultSnafu {
+                    column: &column_schema.name,
+                })?;
+        }
+    }
+
+    Ok(())
+}
+
 fn column_not_in_schema(schema: &SchemaRef, column_schemas: &[ColumnSchema]) -> Option<String> {
     column_schemas.iter().find_map(|col| {
         if schema.column_schema_by_name(&col.name).is_none() {
@@ -166,7 +189,10 @@ mod tests {
         );
         batch.compat_write(&schema_new).unwrap();
         assert_eq!(schema_new, *batch.schema());
-        let Mutation::Put(put_data) = batch.iter().next().unwrap();
+        let put_data = match batch.iter().next().unwrap() {
+            Mutation::Put(put_data) => put_data,
+            Mutation::Delete(_) => unreachable!(),
+        };
         put_data.column_by_name("v0").unwrap();
     }
 
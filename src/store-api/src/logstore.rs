@@ -35,7 +35,7 @@ pub trait LogStore: Send + Sync + 'static + std::fmt::Debug {
     /// Append an `Entry` to WAL with given namespace
     async fn append(&self, mut e: Self::Entry) -> Result<Self::AppendResponse, Self::Error>;
 
-    /// Append a batch of entries atomically and return the offset of first entry.
+    /// Append a batch of entries atomically and return the id of first entry.
     async fn append_batch(
         &self,
         ns: &Self::Namespace,
@@ -51,14 +51,18 @@ pub trait LogStore: Send + Sync + 'static + std::fmt::Debug {
     ) -> Result<SendableEntryStream<Self::Entry, Self::Error>, Self::Error>;
 
     /// Create a new `Namespace`.
-    async fn create_namespace(&mut self, ns: &Self::Namespace) -> Result<(), Self::Error>;
+    async fn create_namespace(&self, ns: &Self::Namespace) -> Result<(), Self::Error>;
 
     /// Delete an existing `Namespace` with given ref.
-    async fn delete_namespace(&mut self, ns: &Self::Namespace) -> Result<(), Self::Error>;
+    async fn delete_namespace(&self, ns: &Self::Namespace) -> Result<(), Self::Error>;
 
     /// List all existing namespaces.
     async fn list_namespaces(&self) -> Result<Vec<Self::Namespace>, Self::Error>;
 
+    /// Mark all entries with ids `<= id` of given `namespace` as obsolete, so that the
+    /// log store could safely delete log files once all entries inside them are obsolete.
+    async fn obsolete(&self, namespace: Self::Namespace, id: Id) -> Result<(), Self::Error>;
+
     /// Create an entry of the associate Entry type
     fn entry<D: AsRef<[u8]>>(&self, data: D, id: Id, ns: Self::Namespace) -> Self::Entry;
 
@@ -75,7 +75,9 @@ pub trait Manifest: Send + Sync + Clone + 'static {
         end: ManifestVersion,
     ) -> Result<Self::MetaActionIterator, Self::Error>;
 
-    async fn checkpoint(&self) -> Result<ManifestVersion, Self::Error>;
+    /// Compact actions into a checkpoint, returns the version of the last compacted
+    /// action, or `None` if there is nothing to compact.
+    async fn checkpoint(&self) -> Result<Option<ManifestVersion>, Self::Error>;
 
     fn last_version(&self) -> ManifestVersion;
 }
@@ -18,6 +18,8 @@
 //! a [`StorageEngine`] instance manages a bunch of storage unit called [`Region`], which holds
 //! chunks of rows, support operations like PUT/DELETE/SCAN.
 
+use std::time::Duration;
+
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
 
@@ -82,6 +84,8 @@ pub struct EngineContext {}
 pub struct CreateOptions {
     /// Region parent directory
     pub parent_dir: String,
+    /// Time to live of rows in the region, `None` means rows never expire.
+    pub ttl: Option<Duration>,
 }
 
 /// Options to open a region.
@@ -89,4 +93,6 @@ pub struct CreateOptions {
 pub struct OpenOptions {
     /// Region parent directory
     pub parent_dir: String,
+    /// Time to live of rows in the region, `None` means rows never expire.
+    pub ttl: Option<Duration>,
 }
@@ -32,6 +32,8 @@
 //! a row key. Note that the implementation may allow multiple rows have same row
 //! key (like ClickHouse), which is useful in analytic scenario.
 
+use std::time::Duration;
+
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
 
@@ -40,7 +42,7 @@ use crate::storage::metadata::RegionMeta;
 use crate::storage::requests::{AlterRequest, WriteRequest};
 use crate::storage::responses::WriteResponse;
 use crate::storage::snapshot::{ReadContext, Snapshot};
-use crate::storage::RegionId;
+use crate::storage::{RegionId, SequenceNumber};
 
 /// Chunks of rows in storage engine.
 #[async_trait]
@@ -72,6 +74,37 @@ pub trait Region: Send + Sync + Clone + std::fmt::Debug + 'static {
     fn write_request(&self) -> Self::WriteRequest;
 
     async fn alter(&self, request: AlterRequest) -> Result<(), Self::Error>;
+
+    /// Sets the time to live of rows in the region, `None` means rows never expire.
+    ///
+    /// Expired rows are invisible to readers, and files only containing expired rows
+    /// would be removed from the region in background.
+    fn set_ttl(&self, ttl: Option<Duration>);
+
+    /// Returns the statistics of the region.
+    fn stat(&self) -> RegionStat;
+
+    /// Flushes all data in memtables to SST files, returns after the flush is finished.
+    async fn flush(&self) -> Result<(), Self::Error>;
+
+    /// Compacts SST files of the region regardless of the compaction threshold, returns
+    /// after the compaction is finished.
+    async fn compact(&self) -> Result<(), Self::Error>;
+}
+
+/// Statistics of a region.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionStat {
+    pub region_id: RegionId,
+    pub region_name: String,
+    /// Bytes allocated by the mutable and immutable memtables.
+    pub memtable_bytes: u64,
+    /// Number of SST files.
+    pub sst_num: u64,
+    /// Total size of the SST files in bytes.
+    pub sst_bytes: u64,
+    /// Sequence of the last data flushed to SST files.
+    pub flushed_sequence: SequenceNumber,
 }
 
 /// Context for write operations.
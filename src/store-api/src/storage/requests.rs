@@ -12,7 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::time::Duration;
 
 use common_error::ext::ErrorExt;
@@ -33,6 +33,13 @@ pub trait WriteRequest: Send {
     /// Add put operation to the request.
     fn put(&mut self, put: Self::PutOp) -> Result<(), Self::Error>;
 
+    /// Add delete operation to the request.
+    ///
+    /// `keys` maps the names of row key columns (including the timestamp column) to
+    /// the values of rows to delete. A tombstone would be written for each row so rows
+    /// with the same row key written before this request become invisible to readers.
+    fn delete(&mut self, keys: HashMap<String, VectorRef>) -> Result<(), Self::Error>;
+
     /// Returns all possible time ranges that contain the timestamp in this batch.
     ///
     /// Each time range is aligned to given `duration`.
@@ -70,13 +77,18 @@ pub struct ScanRequest {
     pub projection: Option<Vec<usize>>,
     /// Filters pushed down
     pub filters: Vec<Expr>,
+    /// Max number of rows to return, `None` for no limit.
+    pub limit: Option<usize>,
+    /// Returns rows in descending order of the row key (row key columns and the timestamp)
+    /// if true, otherwise in ascending order.
+    pub descending: bool,
 }
 
 #[derive(Debug)]
 pub struct GetRequest {}
 
 /// Operation to add a column.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct AddColumn {
     /// Descriptor of the column to add.
     pub desc: ColumnDescriptor,
@@ -85,7 +97,7 @@ pub struct AddColumn {
 }
 
 /// Operation to alter a region.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum AlterOperation {
     /// Add columns to the region.
     AddColumns {
@@ -14,6 +14,7 @@
 
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
+use common_query::cancellation::CancellationToken;
 use datatypes::schema::SchemaRef;
 
 use crate::storage::chunk::ChunkReader;
@@ -44,12 +45,15 @@ pub trait Snapshot: Send + Sync {
 pub struct ReadContext {
     /// Suggested batch size of chunk.
     pub batch_size: usize,
+    /// Token to stop the read once the query is cancelled.
+    pub cancellation: CancellationToken,
 }
 
 impl Default for ReadContext {
     fn default() -> ReadContext {
         ReadContext {
             batch_size: consts::READ_BATCH_SIZE,
+            cancellation: CancellationToken::default(),
         }
     }
 }
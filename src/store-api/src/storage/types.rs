@@ -23,6 +23,9 @@ pub type SequenceNumber = u64;
 pub enum OpType {
     /// Put operation.
     Put,
+    /// Delete operation, the value written with this op type is a tombstone
+    /// that hides older values of the same row key.
+    Delete,
 }
 
 impl OpType {
@@ -43,6 +46,7 @@ mod tests {
     #[test]
     fn test_op_type() {
         assert_eq!(0, OpType::Put.as_u8());
+        assert_eq!(1, OpType::Delete.as_u8());
         assert_eq!(0, OpType::min_type().as_u8());
     }
 }
@@ -110,6 +110,20 @@ pub enum InnerError {
         column_name: String,
         backtrace: Backtrace,
     },
+
+    #[snafu(display("Invalid ttl option {}, source: {}", value, source))]
+    InvalidTtl {
+        value: String,
+        source: humantime::DurationError,
+        backtrace: Backtrace,
+    },
+
+    #[snafu(display("Operation {} is not supported by table {}", operation, table_name))]
+    Unsupported {
+        operation: String,
+        table_name: String,
+        backtrace: Backtrace,
+    },
 }
 
 impl ErrorExt for InnerError {
@@ -121,11 +135,13 @@ impl ErrorExt for InnerError {
             | InnerError::TableProjection { .. } => StatusCode::EngineExecuteQuery,
             InnerError::MissingColumn { .. }
             | InnerError::RemoveColumnInIndex { .. }
-            | InnerError::BuildColumnDescriptor { .. } => StatusCode::InvalidArguments,
+            | InnerError::BuildColumnDescriptor { .. }
+            | InnerError::InvalidTtl { .. } => StatusCode::InvalidArguments,
             InnerError::TablesRecordBatch { .. } => StatusCode::Unexpected,
             InnerError::ColumnExists { .. } => StatusCode::TableColumnExists,
             InnerError::SchemaBuild { source, .. } => source.status_code(),
             InnerError::ColumnNotExists { .. } => StatusCode::TableColumnNotFound,
+            InnerError::Unsupported { .. } => StatusCode::Unsupported,
         }
     }
 
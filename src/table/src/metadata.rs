@@ -14,6 +14,7 @@
 
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
 use chrono::{DateTime, Utc};
 pub use datatypes::error::{Error as ConvertError, Result as ConvertResult};
@@ -24,7 +25,7 @@ use snafu::{ensure, ResultExt};
 use store_api::storage::{ColumnDescriptor, ColumnDescriptorBuilder, ColumnId};
 
 use crate::error::{self, Result};
-use crate::requests::{AddColumnRequest, AlterKind};
+use crate::requests::{self, AddColumnRequest, AlterKind};
 
 pub type TableId = u32;
 pub type TableVersion = u64;
@@ -120,6 +121,11 @@ impl TableMeta {
             .map(|idx| &columns_schemas[*idx].name)
     }
 
+    /// Returns the time to live of rows in the table, `None` means rows never expire.
+    pub fn ttl(&self) -> Result<Option<Duration>> {
+        requests::parse_ttl(&self.options)
+    }
+
     /// Returns the new [TableMetaBuilder] after applying given `alter_kind`.
     ///
     /// The returned builder would derive the next column id of this meta.
@@ -131,6 +137,7 @@ impl TableMeta {
         match alter_kind {
             AlterKind::AddColumns { columns } => self.add_columns(table_name, columns),
             AlterKind::DropColumns { names } => self.remove_columns(table_name, names),
+            AlterKind::SetOptions { options } => self.set_options(options),
         }
     }
 
@@ -168,6 +175,7 @@ impl TableMeta {
             .engine_options(self.engine_options.clone())
             .options(self.options.clone())
             .created_on(self.created_on)
+            .region_numbers(self.region_numbers.clone())
             .next_column_id(self.next_column_id);
 
         builder
@@ -235,6 +243,23 @@ impl TableMeta {
         Ok(meta_builder)
     }
 
+    fn set_options(&self, options: &HashMap<String, String>) -> Result<TableMetaBuilder> {
+        // Validate the options before applying them.
+        requests::parse_ttl(options)?;
+
+        let mut new_options = self.options.clone();
+        new_options.extend(options.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+        let mut meta_builder = self.new_meta_builder();
+        // The schema is unchanged, so we don't need to bump the schema version.
+        meta_builder
+            .schema(self.schema.clone())
+            .primary_key_indices(self.primary_key_indices.clone())
+            .options(new_options);
+
+        Ok(meta_builder)
+    }
+
     fn remove_columns(
         &self,
         table_name: &str,
@@ -420,7 +445,7 @@ impl TryFrom<RawTableMeta> for TableMeta {
             primary_key_indices: raw.primary_key_indices,
             value_indices: raw.value_indices,
             engine: raw.engine,
-            region_numbers: vec![],
+            region_numbers: raw.region_numbers,
             next_column_id: raw.next_column_id,
             engine_options: raw.engine_options,
             options: raw.options,
@@ -748,4 +773,46 @@ mod tests {
         assert_eq!(4, meta.next_column_id);
         assert_eq!(column_schema.name, desc.name);
     }
+
+    #[test]
+    fn test_set_options() {
+        let schema = Arc::new(new_test_schema());
+        let meta = TableMetaBuilder::default()
+            .schema(schema)
+            .primary_key_indices(vec![0])
+            .engine("engine")
+            .next_column_id(3)
+            .options(HashMap::from([("other".to_string(), "value".to_string())]))
+            .build()
+            .unwrap();
+        assert_eq!(None, meta.ttl().unwrap());
+
+        let alter_kind = AlterKind::SetOptions {
+            options: HashMap::from([(requests::TTL_KEY.to_string(), "7d".to_string())]),
+        };
+        let new_meta = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(meta.schema, new_meta.schema);
+        assert_eq!(meta.primary_key_indices, new_meta.primary_key_indices);
+        assert_eq!(meta.value_indices, new_meta.value_indices);
+        assert_eq!("value", new_meta.options["other"]);
+        assert_eq!(
+            Some(Duration::from_secs(7 * 24 * 3600)),
+            new_meta.ttl().unwrap()
+        );
+
+        // Invalid ttl.
+        let alter_kind = AlterKind::SetOptions {
+            options: HashMap::from([(requests::TTL_KEY.to_string(), "7 apples".to_string())]),
+        };
+        let err = meta
+            .builder_with_alter_kind("my_table", &alter_kind)
+            .err()
+            .unwrap();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
 }
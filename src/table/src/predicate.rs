@@ -12,14 +12,20 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod exact;
 mod stats;
 
 use common_query::logical_plan::Expr;
 use common_telemetry::{error, warn};
+use common_time::timestamp::TimeUnit;
+use common_time::{Timestamp, TimestampRange};
+use datafusion::logical_plan::{Expr as DfExpr, Operator};
 use datafusion::physical_optimizer::pruning::PruningPredicate;
+use datafusion_common::ScalarValue;
 use datatypes::arrow::io::parquet::read::RowGroupMetaData;
 use datatypes::schema::SchemaRef;
 
+pub use crate::predicate::exact::ExactFilter;
 use crate::predicate::stats::RowGroupPruningStatistics;
 
 #[derive(Default, Clone)]
@@ -66,6 +72,118 @@ impl Predicate {
     }
 }
 
+/// Builder to extract the range of the timestamp column from filters.
+///
+/// The filters are combined by `AND`. Expressions that can't be used to narrow the range (e.g.
+/// `OR`, filters on other columns) are treated as matching all timestamps, so the extracted
+/// range always contains all timestamps matched by the filters.
+pub struct TimeRangePredicateBuilder<'a> {
+    ts_col_name: &'a str,
+    filters: &'a [Expr],
+}
+
+impl<'a> TimeRangePredicateBuilder<'a> {
+    pub fn new(ts_col_name: &'a str, filters: &'a [Expr]) -> Self {
+        Self {
+            ts_col_name,
+            filters,
+        }
+    }
+
+    pub fn build(&self) -> TimestampRange {
+        self.filters
+            .iter()
+            .fold(TimestampRange::min_to_max(), |range, expr| {
+                range.and(&self.extract_time_range(expr.df_expr()))
+            })
+    }
+
+    fn extract_time_range(&self, expr: &DfExpr) -> TimestampRange {
+        match expr {
+            DfExpr::BinaryExpr {
+                left,
+                op: Operator::And,
+                right,
+            } => self
+                .extract_time_range(left)
+                .and(&self.extract_time_range(right)),
+            DfExpr::BinaryExpr { left, op, right } => match (left.as_ref(), right.as_ref()) {
+                (DfExpr::Column(c), DfExpr::Literal(v)) if c.name == self.ts_col_name => {
+                    range_of_compare_op(*op, v)
+                }
+                (DfExpr::Literal(v), DfExpr::Column(c)) if c.name == self.ts_col_name => {
+                    reverse_operator(*op)
+                        .map(|op| range_of_compare_op(op, v))
+                        .unwrap_or_default()
+                }
+                _ => TimestampRange::min_to_max(),
+            },
+            DfExpr::Between {
+                expr,
+                negated: false,
+                low,
+                high,
+            } => match (expr.as_ref(), low.as_ref(), high.as_ref()) {
+                (DfExpr::Column(c), DfExpr::Literal(low), DfExpr::Literal(high))
+                    if c.name == self.ts_col_name =>
+                {
+                    TimestampRange::new(scalar_to_timestamp(low), scalar_to_timestamp(high))
+                }
+                _ => TimestampRange::min_to_max(),
+            },
+            _ => TimestampRange::min_to_max(),
+        }
+    }
+}
+
+/// Returns the range of `ts` that matches `ts op value`.
+///
+/// Bounds are always inclusive, so the range of `ts > value` also contains `value`. This is
+/// fine as the range is only used to filter out data that can't match.
+fn range_of_compare_op(op: Operator, value: &ScalarValue) -> TimestampRange {
+    let ts = match scalar_to_timestamp(value) {
+        Some(ts) => ts,
+        None => return TimestampRange::min_to_max(),
+    };
+
+    match op {
+        Operator::Eq => TimestampRange::single(ts),
+        Operator::Gt | Operator::GtEq => TimestampRange::new(Some(ts), None),
+        Operator::Lt | Operator::LtEq => TimestampRange::new(None, Some(ts)),
+        _ => TimestampRange::min_to_max(),
+    }
+}
+
+/// Returns the operator `op'` that `a op b` equals to `b op' a`.
+fn reverse_operator(op: Operator) -> Option<Operator> {
+    match op {
+        Operator::Eq => Some(Operator::Eq),
+        Operator::NotEq => Some(Operator::NotEq),
+        Operator::Gt => Some(Operator::Lt),
+        Operator::GtEq => Some(Operator::LtEq),
+        Operator::Lt => Some(Operator::Gt),
+        Operator::LtEq => Some(Operator::GtEq),
+        _ => None,
+    }
+}
+
+fn scalar_to_timestamp(value: &ScalarValue) -> Option<Timestamp> {
+    match value {
+        ScalarValue::Int64(Some(v)) => Some(Timestamp::from_millis(*v)),
+        ScalarValue::TimestampSecond(Some(v), _) => Some(Timestamp::new(*v, TimeUnit::Second)),
+        ScalarValue::TimestampMillisecond(Some(v), _) => {
+            Some(Timestamp::new(*v, TimeUnit::Millisecond))
+        }
+        ScalarValue::TimestampMicrosecond(Some(v), _) => {
+            Some(Timestamp::new(*v, TimeUnit::Microsecond))
+        }
+        ScalarValue::TimestampNanosecond(Some(v), _) => {
+            Some(Timestamp::new(*v, TimeUnit::Nanosecond))
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -240,4 +358,104 @@ mod tests {
         let p = Predicate::new(vec![e.into()]);
         assert_prune(40, p, vec![true, true, false, true]).await;
     }
+
+    fn ts_lit(v: i64) -> Expr {
+        Expr::Literal(ScalarValue::TimestampMillisecond(Some(v), None))
+    }
+
+    fn ts_col() -> Expr {
+        Expr::Column(Column::from_name("ts"))
+    }
+
+    fn build_time_range(exprs: Vec<Expr>) -> TimestampRange {
+        let filters: Vec<_> = exprs.into_iter().map(|e| e.into()).collect();
+        TimeRangePredicateBuilder::new("ts", &filters).build()
+    }
+
+    fn new_range(start: Option<i64>, end: Option<i64>) -> TimestampRange {
+        TimestampRange::new(
+            start.map(Timestamp::from_millis),
+            end.map(Timestamp::from_millis),
+        )
+    }
+
+    #[test]
+    fn test_time_range_empty_filters() {
+        assert_eq!(TimestampRange::min_to_max(), build_time_range(vec![]));
+    }
+
+    #[test]
+    fn test_time_range_compare_op() {
+        assert_eq!(
+            new_range(Some(1000), None),
+            build_time_range(vec![ts_col().gt(ts_lit(1000))])
+        );
+        assert_eq!(
+            new_range(None, Some(1000)),
+            build_time_range(vec![ts_col().lt_eq(ts_lit(1000))])
+        );
+        assert_eq!(
+            new_range(Some(1000), Some(1000)),
+            build_time_range(vec![ts_col().eq(ts_lit(1000))])
+        );
+        // Literal on the left side.
+        assert_eq!(
+            new_range(None, Some(1000)),
+            build_time_range(vec![ts_lit(1000).gt(ts_col())])
+        );
+        // Int64 literal is treated as milliseconds.
+        assert_eq!(
+            new_range(Some(1000), None),
+            build_time_range(vec![ts_col().gt_eq(1000i64.lit())])
+        );
+        assert_eq!(
+            TimestampRange::min_to_max(),
+            build_time_range(vec![ts_col().not_eq(ts_lit(1000))])
+        );
+    }
+
+    #[test]
+    fn test_time_range_conjunction() {
+        // ts >= 1000 AND ts < 2000
+        assert_eq!(
+            new_range(Some(1000), Some(2000)),
+            build_time_range(vec![ts_col()
+                .gt_eq(ts_lit(1000))
+                .and(ts_col().lt(ts_lit(2000)))])
+        );
+        // Multiple filters are also combined by AND.
+        assert_eq!(
+            new_range(Some(1000), Some(2000)),
+            build_time_range(vec![
+                ts_col().gt_eq(ts_lit(1000)),
+                ts_col().lt(ts_lit(2000)),
+                Expr::Column(Column::from_name("cnt")).gt(30.lit()),
+            ])
+        );
+        assert_eq!(
+            new_range(Some(1000), Some(2000)),
+            build_time_range(vec![Expr::Between {
+                expr: Box::new(ts_col()),
+                negated: false,
+                low: Box::new(ts_lit(1000)),
+                high: Box::new(ts_lit(2000)),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_time_range_unsupported_expr() {
+        // ts < 1000 OR ts > 2000
+        assert_eq!(
+            TimestampRange::min_to_max(),
+            build_time_range(vec![ts_col()
+                .lt(ts_lit(1000))
+                .or(ts_col().gt(ts_lit(2000)))])
+        );
+        // Filters on other columns.
+        assert_eq!(
+            TimestampRange::min_to_max(),
+            build_time_range(vec![Expr::Column(Column::from_name("cnt")).gt(30.lit())])
+        );
+    }
 }
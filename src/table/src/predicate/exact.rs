@@ -0,0 +1,263 @@
+// Copyright 2022 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_query::logical_plan::Expr;
+use datafusion::logical_plan::{Expr as DfExpr, Operator};
+use datafusion_common::ScalarValue;
+use datatypes::schema::Schema;
+use datatypes::value::Value;
+use datatypes::vectors::VectorRef;
+
+use crate::predicate::reverse_operator;
+
+/// A filter on the row key columns (the tag columns and the time index) that storage engines
+/// could evaluate exactly, so the rows returned don't need to be filtered again.
+///
+/// Supported filters are comparisons between a row key column and a literal of the same type,
+/// `BETWEEN` a row key column and literals of the same type, and `AND`/`OR` of them.
+#[derive(Debug, Clone)]
+pub struct ExactFilter {
+    expr: DfExpr,
+}
+
+impl ExactFilter {
+    /// Returns the [ExactFilter] of `expr`, or `None` if `expr` can't be evaluated exactly on
+    /// the `key_columns` of `schema`.
+    pub fn try_new(expr: &Expr, schema: &Schema, key_columns: &[&str]) -> Option<ExactFilter> {
+        let checker = Checker {
+            schema,
+            key_columns,
+        };
+        let expr = expr.df_expr();
+        checker
+            .is_exact(expr)
+            .then(|| ExactFilter { expr: expr.clone() })
+    }
+
+    /// Evaluates the filter on `num_rows` rows, returns whether each row matches the filter.
+    ///
+    /// `column` returns the vector of the key column with given name.
+    pub fn evaluate<F>(&self, num_rows: usize, column: F) -> Vec<bool>
+    where
+        F: Fn(&str) -> VectorRef,
+    {
+        evaluate(&self.expr, num_rows, &column)
+    }
+}
+
+struct Checker<'a> {
+    schema: &'a Schema,
+    key_columns: &'a [&'a str],
+}
+
+impl<'a> Checker<'a> {
+    fn is_exact(&self, expr: &DfExpr) -> bool {
+        match expr {
+            DfExpr::BinaryExpr {
+                left,
+                op: Operator::And | Operator::Or,
+                right,
+            } => self.is_exact(left) && self.is_exact(right),
+            DfExpr::BinaryExpr { left, op, right } => match (left.as_ref(), right.as_ref()) {
+                (DfExpr::Column(c), DfExpr::Literal(v))
+                | (DfExpr::Literal(v), DfExpr::Column(c)) => {
+                    reverse_operator(*op).is_some() && self.is_comparable(&c.name, v)
+                }
+                _ => false,
+            },
+            DfExpr::Between {
+                expr,
+                negated: false,
+                low,
+                high,
+            } => match (expr.as_ref(), low.as_ref(), high.as_ref()) {
+                (DfExpr::Column(c), DfExpr::Literal(low), DfExpr::Literal(high)) => {
+                    self.is_comparable(&c.name, low) && self.is_comparable(&c.name, high)
+                }
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+
+    /// Returns true if `column` is a key column and has the same type as `value`.
+    fn is_comparable(&self, column: &str, value: &ScalarValue) -> bool {
+        if !self.key_columns.contains(&column) {
+            return false;
+        }
+
+        self.schema
+            .column_schema_by_name(column)
+            .map(|column_schema| column_schema.data_type.as_arrow_type() == value.get_datatype())
+            .unwrap_or(false)
+    }
+}
+
+fn evaluate<F>(expr: &DfExpr, num_rows: usize, column: &F) -> Vec<bool>
+where
+    F: Fn(&str) -> VectorRef,
+{
+    match expr {
+        DfExpr::BinaryExpr {
+            left,
+            op: op @ (Operator::And | Operator::Or),
+            right,
+        } => {
+            let left = evaluate(left, num_rows, column);
+            let right = evaluate(right, num_rows, column);
+            left.into_iter()
+                .zip(right)
+                .map(|(l, r)| match op {
+                    Operator::And => l && r,
+                    _ => l || r,
+                })
+                .collect()
+        }
+        DfExpr::BinaryExpr { left, op, right } => match (left.as_ref(), right.as_ref()) {
+            (DfExpr::Column(c), DfExpr::Literal(v)) => compare(&column(&c.name), *op, v),
+            (DfExpr::Literal(v), DfExpr::Column(c)) => {
+                // Checked by `Checker::is_exact()`.
+                let op = reverse_operator(*op).unwrap();
+                compare(&column(&c.name), op, v)
+            }
+            _ => unreachable!("Unsupported exact filter: {:?}", expr),
+        },
+        DfExpr::Between {
+            expr, low, high, ..
+        } => match (expr.as_ref(), low.as_ref(), high.as_ref()) {
+            (DfExpr::Column(c), DfExpr::Literal(low), DfExpr::Literal(high)) => {
+                let vector = column(&c.name);
+                compare(&vector, Operator::GtEq, low)
+                    .into_iter()
+                    .zip(compare(&vector, Operator::LtEq, high))
+                    .map(|(l, r)| l && r)
+                    .collect()
+            }
+            _ => unreachable!("Unsupported exact filter: {:?}", expr),
+        },
+        _ => unreachable!("Unsupported exact filter: {:?}", expr),
+    }
+}
+
+/// Returns whether `value op literal` is true for each value in `vector`, comparing with null
+/// is always false.
+fn compare(vector: &VectorRef, op: Operator, literal: &ScalarValue) -> Vec<bool> {
+    let literal = Value::try_from(literal.clone()).unwrap_or(Value::Null);
+    (0..vector.len())
+        .map(|i| {
+            let value = vector.get(i);
+            if value.is_null() || literal.is_null() {
+                return false;
+            }
+
+            match op {
+                Operator::Eq => value == literal,
+                Operator::NotEq => value != literal,
+                Operator::Lt => value < literal,
+                Operator::LtEq => value <= literal,
+                Operator::Gt => value > literal,
+                Operator::GtEq => value >= literal,
+                _ => unreachable!("Unsupported compare operator: {:?}", op),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datafusion_common::Column;
+    use datafusion_expr::{Expr as DfExpr, Literal};
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::ColumnSchema;
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampVector};
+
+    use super::*;
+
+    fn new_schema() -> Schema {
+        Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("cpu", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new("ts", ConcreteDataType::timestamp_millis_datatype(), false),
+        ])
+    }
+
+    fn col(name: &str) -> DfExpr {
+        DfExpr::Column(Column::from_name(name))
+    }
+
+    fn ts(v: i64) -> DfExpr {
+        DfExpr::Literal(ScalarValue::TimestampMillisecond(Some(v), None))
+    }
+
+    fn between(expr: DfExpr, low: DfExpr, high: DfExpr, negated: bool) -> DfExpr {
+        DfExpr::Between {
+            expr: Box::new(expr),
+            negated,
+            low: Box::new(low),
+            high: Box::new(high),
+        }
+    }
+
+    fn new_filter(expr: DfExpr) -> Option<ExactFilter> {
+        ExactFilter::try_new(&Expr::from(expr), &new_schema(), &["host", "ts"])
+    }
+
+    fn evaluate_filter(filter: &ExactFilter) -> Vec<bool> {
+        let hosts: VectorRef = Arc::new(StringVector::from(vec![Some("a"), Some("b"), None]));
+        let tss: VectorRef = Arc::new(TimestampVector::from_vec(vec![1, 2, 3]));
+        let cpus: VectorRef = Arc::new(Float64Vector::from_vec(vec![1.0, 2.0, 3.0]));
+        filter.evaluate(3, |name| match name {
+            "host" => hosts.clone(),
+            "ts" => tss.clone(),
+            _ => cpus.clone(),
+        })
+    }
+
+    #[test]
+    fn test_unsupported_exact_filter() {
+        // Not a key column.
+        assert!(new_filter(col("cpu").gt(1.0f64.lit())).is_none());
+        // Literal has different type.
+        assert!(new_filter(col("ts").gt(1i64.lit())).is_none());
+        assert!(new_filter(col("host").eq(1i64.lit())).is_none());
+        // Unsupported operator or expression.
+        assert!(new_filter(col("host").like("a%".lit())).is_none());
+        assert!(new_filter(col("host").eq(col("host"))).is_none());
+        assert!(new_filter(between(col("ts"), ts(1), ts(2), true)).is_none());
+        assert!(new_filter(col("host").eq("a".lit()).and(col("cpu").gt(1.0f64.lit()))).is_none());
+    }
+
+    #[test]
+    fn test_evaluate_exact_filter() {
+        let filter = new_filter(col("host").eq("a".lit())).unwrap();
+        assert_eq!(vec![true, false, false], evaluate_filter(&filter));
+
+        let filter = new_filter(col("host").not_eq("a".lit())).unwrap();
+        assert_eq!(vec![false, true, false], evaluate_filter(&filter));
+
+        let filter = new_filter(ts(2).lt_eq(col("ts"))).unwrap();
+        assert_eq!(vec![false, true, true], evaluate_filter(&filter));
+
+        let filter = new_filter(between(col("ts"), ts(2), ts(3), false)).unwrap();
+        assert_eq!(vec![false, true, true], evaluate_filter(&filter));
+
+        let filter = new_filter(col("ts").gt(ts(1)).and(col("host").eq("b".lit()))).unwrap();
+        assert_eq!(vec![false, true, false], evaluate_filter(&filter));
+
+        let filter = new_filter(col("ts").lt(ts(2)).or(col("ts").gt(ts(2)))).unwrap();
+        assert_eq!(vec![true, false, true], evaluate_filter(&filter));
+    }
+}
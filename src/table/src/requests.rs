@@ -14,13 +14,32 @@
 
 //! Table and TableEngine requests
 use std::collections::HashMap;
+use std::time::Duration;
 
 use datatypes::prelude::VectorRef;
 use datatypes::schema::{ColumnSchema, SchemaRef};
+use snafu::ResultExt;
 use store_api::storage::RegionNumber;
 
+use crate::error::{self, Result};
 use crate::metadata::TableId;
 
+/// Key of the table option that controls the time to live of rows, e.g. `ttl = '7d'`.
+pub const TTL_KEY: &str = "ttl";
+
+/// Parses the time to live of rows from table `options`.
+///
+/// Returns `None` if the ttl is absent or zero, which means rows never expire.
+pub fn parse_ttl(options: &HashMap<String, String>) -> Result<Option<Duration>> {
+    let value = match options.get(TTL_KEY) {
+        Some(v) => v,
+        None => return Ok(None),
+    };
+    let ttl = humantime::parse_duration(value).context(error::InvalidTtlSnafu { value })?;
+
+    Ok(Some(ttl).filter(|ttl| !ttl.is_zero()))
+}
+
 /// Insert request
 #[derive(Debug)]
 pub struct InsertRequest {
@@ -28,6 +47,16 @@ pub struct InsertRequest {
     pub schema_name: String,
     pub table_name: String,
     pub columns_values: HashMap<String, VectorRef>,
+    /// The region of the table to insert rows into.
+    pub region_number: RegionNumber,
+}
+
+/// Delete request
+#[derive(Debug)]
+pub struct DeleteRequest {
+    /// Values of row key columns (primary key columns and the timestamp column) of
+    /// rows to delete.
+    pub key_column_values: HashMap<String, VectorRef>,
 }
 
 #[derive(Debug, Clone)]
@@ -78,8 +107,16 @@ pub struct AddColumnRequest {
 
 #[derive(Debug)]
 pub enum AlterKind {
-    AddColumns { columns: Vec<AddColumnRequest> },
-    DropColumns { names: Vec<String> },
+    AddColumns {
+        columns: Vec<AddColumnRequest>,
+    },
+    DropColumns {
+        names: Vec<String>,
+    },
+    /// Sets table options, existing options with the same keys are overwritten.
+    SetOptions {
+        options: HashMap<String, String>,
+    },
 }
 
 /// Drop table request
@@ -89,3 +126,19 @@ pub struct DropTableRequest {
     pub schema_name: String,
     pub table_name: String,
 }
+
+/// Flush table request
+#[derive(Debug)]
+pub struct FlushTableRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+}
+
+/// Compact table request
+#[derive(Debug)]
+pub struct CompactTableRequest {
+    pub catalog_name: String,
+    pub schema_name: String,
+    pub table_name: String,
+}
@@ -23,10 +23,11 @@ use async_trait::async_trait;
 use common_query::logical_plan::Expr;
 use common_query::physical_plan::PhysicalPlanRef;
 use datatypes::schema::SchemaRef;
+use store_api::storage::RegionStat;
 
-use crate::error::Result;
+use crate::error::{Result, UnsupportedSnafu};
 use crate::metadata::{FilterPushDownType, TableId, TableInfoRef, TableType};
-use crate::requests::{AlterTableRequest, InsertRequest};
+use crate::requests::{AlterTableRequest, DeleteRequest, InsertRequest};
 
 /// Table abstraction.
 #[async_trait]
@@ -51,6 +52,15 @@ pub trait Table: Send + Sync {
         unimplemented!();
     }
 
+    /// Delete rows by their row keys, returns the number of rows in the request.
+    async fn delete(&self, _request: DeleteRequest) -> Result<usize> {
+        UnsupportedSnafu {
+            operation: "DELETE",
+            table_name: &self.table_info().name,
+        }
+        .fail()?
+    }
+
     /// Scan the table and returns a SendableRecordBatchStream.
     async fn scan(
         &self,
@@ -63,6 +73,21 @@ pub trait Table: Send + Sync {
         limit: Option<usize>,
     ) -> Result<PhysicalPlanRef>;
 
+    /// Scan the table for the latest rows, e.g. `ORDER BY <time index> DESC LIMIT <limit>`.
+    ///
+    /// The table could return rows in descending order of the time index and stop reading
+    /// once the latest `limit` rows are read. The output may still be unordered, so the caller
+    /// needs to sort it. By default, this scans the whole table.
+    async fn scan_latest(
+        &self,
+        projection: &Option<Vec<usize>>,
+        filters: &[Expr],
+        limit: Option<usize>,
+    ) -> Result<PhysicalPlanRef> {
+        let _ = limit;
+        self.scan(projection, filters, None).await
+    }
+
     /// Tests whether the table provider can make use of a filter expression
     /// to optimise data retrieval.
     fn supports_filter_pushdown(&self, _filter: &Expr) -> Result<FilterPushDownType> {
@@ -73,6 +98,31 @@ pub trait Table: Send + Sync {
         let _ = request;
         unimplemented!()
     }
+
+    /// Returns the statistics of the regions of the table, empty if the table is not
+    /// stored in regions.
+    fn region_stats(&self) -> Vec<RegionStat> {
+        Vec::new()
+    }
+
+    /// Flushes the in memory data of the table to storage, returns after the flush is
+    /// finished.
+    async fn flush(&self) -> Result<()> {
+        UnsupportedSnafu {
+            operation: "FLUSH",
+            table_name: &self.table_info().name,
+        }
+        .fail()?
+    }
+
+    /// Compacts the storage files of the table, returns after the compaction is finished.
+    async fn compact(&self) -> Result<()> {
+        UnsupportedSnafu {
+            operation: "COMPACT",
+            table_name: &self.table_info().name,
+        }
+        .fail()?
+    }
 }
 
 pub type TableRef = Arc<dyn Table>;
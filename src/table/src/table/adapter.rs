@@ -34,11 +34,24 @@ use crate::table::{FilterPushDownType, Table, TableRef, TableType};
 /// Greptime Table ->  datafusion TableProvider
 pub struct DfTableProviderAdapter {
     table: TableRef,
+    /// Whether to scan the latest rows of the table by [Table::scan_latest()].
+    latest_first: bool,
 }
 
 impl DfTableProviderAdapter {
     pub fn new(table: TableRef) -> Self {
-        Self { table }
+        Self {
+            table,
+            latest_first: false,
+        }
+    }
+
+    /// Creates an adapter that scans the latest rows of the `table` first.
+    pub fn with_latest_first(table: TableRef) -> Self {
+        Self {
+            table,
+            latest_first: true,
+        }
     }
 
     pub fn table(&self) -> TableRef {
@@ -71,7 +84,11 @@ impl TableProvider for DfTableProviderAdapter {
         limit: Option<usize>,
     ) -> DfResult<Arc<dyn DfPhysicalPlan>> {
         let filters: Vec<Expr> = filters.iter().map(Clone::clone).map(Into::into).collect();
-        let inner = self.table.scan(projection, &filters, limit).await?;
+        let inner = if self.latest_first {
+            self.table.scan_latest(projection, &filters, limit).await?
+        } else {
+            self.table.scan(projection, &filters, limit).await?
+        };
         Ok(Arc::new(DfPhysicalPlanAdapter(inner)))
     }
 